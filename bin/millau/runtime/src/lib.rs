@@ -49,7 +49,7 @@ use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{Block as BlockT, IdentityLookup, Keccak256, NumberFor, OpaqueKeys},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, FixedPointNumber, Perquintill,
+	ApplyExtrinsicResult, FixedPointNumber, FixedU128, Perquintill,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -79,6 +79,7 @@ pub use pallet_balances::Call as BalancesCall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaCall;
 pub use pallet_bridge_messages::Call as MessagesCall;
 pub use pallet_bridge_parachains::Call as BridgeParachainsCall;
+pub use pallet_bridge_relayers::Call as BridgeRelayersCall;
 pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_xcm::Call as XcmCall;
@@ -314,7 +315,7 @@ impl pallet_timestamp::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: bp_millau::Balance = 500;
+	pub const ExistentialDeposit: bp_millau::Balance = bp_millau::EXISTENTIAL_DEPOSIT;
 }
 
 impl pallet_balances::Config for Runtime {
@@ -375,6 +376,7 @@ parameter_types! {
 	pub const Period: BlockNumber = bp_millau::SESSION_LENGTH;
 	pub const Offset: BlockNumber = 0;
 	pub const RelayerStakeReserveId: [u8; 8] = *b"brdgrlrs";
+	pub const RelayerSlashFraction: Perbill = Perbill::from_percent(10);
 }
 
 impl pallet_session::Config for Runtime {
@@ -403,6 +405,8 @@ impl pallet_bridge_relayers::Config for Runtime {
 		ConstU64<1_000>,
 		ConstU64<8>,
 	>;
+	type SlashFraction = RelayerSlashFraction;
+	type RewardPayoutThreshold = ();
 	type WeightInfo = ();
 }
 
@@ -411,6 +415,9 @@ impl pallet_bridge_grandpa::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = bp_rialto::Rialto;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<100>;
+	type FreeHeadersEpochLength = ConstU32<{ bp_millau::DAYS as u32 }>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = ConstU32<{ bp_rialto::DAYS }>;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
@@ -420,6 +427,9 @@ impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = bp_westend::Westend;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<100>;
+	type FreeHeadersEpochLength = ConstU32<{ bp_millau::DAYS as u32 }>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = ConstU32<{ bp_westend::DAYS }>;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
@@ -453,6 +463,9 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	>;
 
 	type MessageDispatch = crate::rialto_messages::FromRialtoMessageDispatch;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
 }
 
 /// Instance of the messages pallet used to relay messages to/from RialtoParachain chain.
@@ -481,6 +494,9 @@ impl pallet_bridge_messages::Config<WithRialtoParachainMessagesInstance> for Run
 	>;
 
 	type MessageDispatch = crate::rialto_parachain_messages::FromRialtoParachainMessageDispatch;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
 }
 
 parameter_types! {
@@ -525,6 +541,12 @@ impl pallet_utility::Config for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const XcmBridgeHubRouterFeeFactorBase: FixedU128 =
+		pallet_xcm_bridge_hub_router::EXPONENTIAL_FEE_BASE;
+	pub const XcmBridgeHubRouterMaxFeeFactor: FixedU128 = FixedU128::from_rational(1_000, 1);
+}
+
 // this config is totally incorrect - the pallet is not actually used at this runtime. We need
 // it only to be able to run benchmarks and make required traits (and default weights for tests).
 impl pallet_xcm_bridge_hub_router::Config for Runtime {
@@ -540,6 +562,12 @@ impl pallet_xcm_bridge_hub_router::Config for Runtime {
 	type BaseFee = ConstU128<1_000_000_000>;
 	type ByteFee = ConstU128<1_000>;
 	type FeeAsset = xcm_config::TokenAssetId;
+
+	type FeeFactorIncreaseBase = XcmBridgeHubRouterFeeFactorBase;
+	type FeeFactorDecreaseBase = XcmBridgeHubRouterFeeFactorBase;
+	type MaxFeeFactor = XcmBridgeHubRouterMaxFeeFactor;
+
+	type RuntimeEvent = RuntimeEvent;
 }
 
 construct_runtime!(
@@ -582,7 +610,7 @@ construct_runtime!(
 		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config<T>} = 99,
 
 		// Pallets that are not actually used here (yet?), but we need to run benchmarks on it.
-		XcmBridgeHubRouter: pallet_xcm_bridge_hub_router::{Pallet, Storage} = 200,
+		XcmBridgeHubRouter: pallet_xcm_bridge_hub_router::{Pallet, Storage, Event<T>} = 200,
 	}
 );
 
@@ -606,6 +634,7 @@ pub type BridgeRefundRialtoParachainMessages = RefundBridgedParachainMessages<
 	ActualFeeRefund<Runtime>,
 	PriorityBoostPerMessage,
 	StrBridgeRefundRialtoPara2000Lane0Msgs,
+	(),
 >;
 
 /// The address format for describing accounts.
@@ -889,6 +918,19 @@ impl_runtime_apis! {
 		) -> Vec<bp_header_chain::StoredHeaderGrandpaInfo<bp_rialto::Header>> {
 			BridgeRialtoGrandpa::synced_headers_grandpa_info()
 		}
+
+		fn grandpa_verify_ancestry(
+			ancestor: bp_rialto::Hash,
+			descendant: bp_rialto::Hash,
+			ancestry: Vec<bp_rialto::Header>,
+		) -> Option<bp_header_chain::StoredHeaderData<bp_rialto::BlockNumber, bp_rialto::Hash>> {
+			BridgeRialtoGrandpa::verify_ancestry(ancestor, descendant, ancestry).ok()
+		}
+
+		fn grandpa_submission_constraints(
+		) -> bp_header_chain::FinalitySubmissionConstraints<bp_rialto::BlockNumber, bp_rialto::Hash> {
+			BridgeRialtoGrandpa::submission_constraints()
+		}
 	}
 
 	impl bp_westend::WestendFinalityApi<Block> for Runtime {
@@ -900,6 +942,19 @@ impl_runtime_apis! {
 		) -> Vec<bp_header_chain::StoredHeaderGrandpaInfo<bp_westend::Header>> {
 			BridgeWestendGrandpa::synced_headers_grandpa_info()
 		}
+
+		fn grandpa_verify_ancestry(
+			ancestor: bp_westend::Hash,
+			descendant: bp_westend::Hash,
+			ancestry: Vec<bp_westend::Header>,
+		) -> Option<bp_header_chain::StoredHeaderData<bp_westend::BlockNumber, bp_westend::Hash>> {
+			BridgeWestendGrandpa::verify_ancestry(ancestor, descendant, ancestry).ok()
+		}
+
+		fn grandpa_submission_constraints(
+		) -> bp_header_chain::FinalitySubmissionConstraints<bp_westend::BlockNumber, bp_westend::Hash> {
+			BridgeWestendGrandpa::submission_constraints()
+		}
 	}
 
 	impl bp_westend::AssetHubWestendFinalityApi<Block> for Runtime {
@@ -925,7 +980,7 @@ impl_runtime_apis! {
 			lane: bp_messages::LaneId,
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
-		) -> Vec<bp_messages::OutboundMessageDetails> {
+		) -> Vec<bp_messages::OutboundMessageDetailsWithReward> {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithRialtoMessagesInstance,
@@ -950,7 +1005,7 @@ impl_runtime_apis! {
 			lane: bp_messages::LaneId,
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
-		) -> Vec<bp_messages::OutboundMessageDetails> {
+		) -> Vec<bp_messages::OutboundMessageDetailsWithReward> {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithRialtoParachainMessagesInstance,
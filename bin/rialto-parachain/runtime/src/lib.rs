@@ -283,7 +283,7 @@ impl pallet_timestamp::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u128 = MILLIUNIT;
+	pub const ExistentialDeposit: u128 = bp_rialto_parachain::EXISTENTIAL_DEPOSIT;
 	pub const TransferFee: u128 = MILLIUNIT;
 	pub const CreationFee: u128 = MILLIUNIT;
 	pub const TransactionByteFee: u128 = MICROUNIT;
@@ -537,6 +537,8 @@ impl pallet_bridge_relayers::Config for Runtime {
 	type PaymentProcedure =
 		bp_relayers::PayRewardFromAccount<pallet_balances::Pallet<Runtime>, AccountId>;
 	type StakeAndSlash = ();
+	type SlashFraction = ();
+	type RewardPayoutThreshold = ();
 	type WeightInfo = ();
 }
 
@@ -545,6 +547,9 @@ impl pallet_bridge_grandpa::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = bp_millau::Millau;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<100>;
+	type FreeHeadersEpochLength = ConstU32<DAYS>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = ConstU32<{ bp_millau::DAYS as u32 }>;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
@@ -576,6 +581,9 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	>;
 
 	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -739,6 +747,19 @@ impl_runtime_apis! {
 		) -> Vec<bp_header_chain::StoredHeaderGrandpaInfo<bp_millau::Header>> {
 			BridgeMillauGrandpa::synced_headers_grandpa_info()
 		}
+
+		fn grandpa_verify_ancestry(
+			ancestor: bp_millau::Hash,
+			descendant: bp_millau::Hash,
+			ancestry: Vec<bp_millau::Header>,
+		) -> Option<bp_header_chain::StoredHeaderData<bp_millau::BlockNumber, bp_millau::Hash>> {
+			BridgeMillauGrandpa::verify_ancestry(ancestor, descendant, ancestry).ok()
+		}
+
+		fn grandpa_submission_constraints(
+		) -> bp_header_chain::FinalitySubmissionConstraints<bp_millau::BlockNumber, bp_millau::Hash> {
+			BridgeMillauGrandpa::submission_constraints()
+		}
 	}
 
 	impl bp_millau::ToMillauOutboundLaneApi<Block> for Runtime {
@@ -746,7 +767,7 @@ impl_runtime_apis! {
 			lane: bp_messages::LaneId,
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
-		) -> Vec<bp_messages::OutboundMessageDetails> {
+		) -> Vec<bp_messages::OutboundMessageDetailsWithReward> {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithMillauMessagesInstance,
@@ -69,6 +69,7 @@ pub use pallet_balances::Call as BalancesCall;
 pub use pallet_bridge_beefy::Call as BridgeBeefyCall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaCall;
 pub use pallet_bridge_messages::Call as MessagesCall;
+pub use pallet_bridge_relayers::Call as BridgeRelayersCall;
 pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_xcm::Call as XcmCall;
@@ -314,7 +315,7 @@ impl pallet_timestamp::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: bp_rialto::Balance = 500;
+	pub const ExistentialDeposit: bp_rialto::Balance = bp_rialto::EXISTENTIAL_DEPOSIT;
 }
 
 impl pallet_balances::Config for Runtime {
@@ -393,6 +394,8 @@ impl pallet_bridge_relayers::Config for Runtime {
 	type PaymentProcedure =
 		bp_relayers::PayRewardFromAccount<pallet_balances::Pallet<Runtime>, AccountId>;
 	type StakeAndSlash = ();
+	type SlashFraction = ();
+	type RewardPayoutThreshold = ();
 	type WeightInfo = ();
 }
 
@@ -401,6 +404,9 @@ impl pallet_bridge_grandpa::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = bp_millau::Millau;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<100>;
+	type FreeHeadersEpochLength = ConstU32<{ bp_rialto::DAYS }>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = ConstU32<{ bp_millau::DAYS as u32 }>;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
@@ -434,6 +440,9 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	>;
 
 	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
 }
 
 pub type MillauBeefyInstance = ();
@@ -687,6 +696,19 @@ impl_runtime_apis! {
 		) -> Vec<bp_header_chain::StoredHeaderGrandpaInfo<bp_millau::Header>> {
 			BridgeMillauGrandpa::synced_headers_grandpa_info()
 		}
+
+		fn grandpa_verify_ancestry(
+			ancestor: bp_millau::Hash,
+			descendant: bp_millau::Hash,
+			ancestry: Vec<bp_millau::Header>,
+		) -> Option<bp_header_chain::StoredHeaderData<bp_millau::BlockNumber, bp_millau::Hash>> {
+			BridgeMillauGrandpa::verify_ancestry(ancestor, descendant, ancestry).ok()
+		}
+
+		fn grandpa_submission_constraints(
+		) -> bp_header_chain::FinalitySubmissionConstraints<bp_millau::BlockNumber, bp_millau::Hash> {
+			BridgeMillauGrandpa::submission_constraints()
+		}
 	}
 
 	impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -955,7 +977,7 @@ impl_runtime_apis! {
 			lane: bp_messages::LaneId,
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
-		) -> Vec<bp_messages::OutboundMessageDetails> {
+		) -> Vec<bp_messages::OutboundMessageDetailsWithReward> {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithMillauMessagesInstance,
@@ -17,7 +17,8 @@
 //! Helpers for implementing various message-related runtime API mthods.
 
 use bp_messages::{
-	InboundMessageDetails, LaneId, MessageNonce, MessagePayload, OutboundMessageDetails,
+	source_chain::DeliveryConfirmationPayments, InboundMessageDetails, LaneId, MessageNonce,
+	MessagePayload, OutboundMessageDetails, OutboundMessageDetailsWithReward,
 };
 use sp_std::vec::Vec;
 
@@ -26,7 +27,7 @@ pub fn outbound_message_details<Runtime, MessagesPalletInstance>(
 	lane: LaneId,
 	begin: MessageNonce,
 	end: MessageNonce,
-) -> Vec<OutboundMessageDetails>
+) -> Vec<OutboundMessageDetailsWithReward>
 where
 	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
 	MessagesPalletInstance: 'static,
@@ -35,12 +36,15 @@ where
 		.filter_map(|nonce| {
 			let message_data =
 				pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_message_data(lane, nonce)?;
-			Some(OutboundMessageDetails {
-				nonce,
-				// dispatch message weight is always zero at the source chain, since we're paying for
-				// dispatch at the target chain
-				dispatch_weight: frame_support::weights::Weight::zero(),
-				size: message_data.len() as _,
+			Some(OutboundMessageDetailsWithReward {
+				details: OutboundMessageDetails {
+					nonce,
+					// dispatch message weight is always zero at the source chain, since we're
+					// paying for dispatch at the target chain
+					dispatch_weight: frame_support::weights::Weight::zero(),
+					size: message_data.len() as _,
+				},
+				delivery_reward: Runtime::DeliveryConfirmationPayments::delivery_reward(lane, nonce),
 			})
 		})
 		.collect()
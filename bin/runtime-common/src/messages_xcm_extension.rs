@@ -28,13 +28,18 @@ use bp_messages::{
 };
 use bp_runtime::{messages::MessageDispatchResult, Chain};
 use codec::{Decode, Encode};
-use frame_support::{dispatch::Weight, CloneNoBound, EqNoBound, PartialEqNoBound};
+use frame_support::{
+	dispatch::Weight, traits::tokens::ConversionToAssetBalance, CloneNoBound, EqNoBound,
+	PartialEqNoBound,
+};
 use pallet_bridge_messages::WeightInfoExt as MessagesPalletWeights;
 use scale_info::TypeInfo;
 use sp_core::Get;
 use sp_runtime::SaturatedConversion;
-use sp_std::marker::PhantomData;
+use sp_std::{marker::PhantomData, prelude::*};
+use xcm::prelude::*;
 use xcm_builder::{DispatchBlob, DispatchBlobError, HaulBlob, HaulBlobError};
+use xcm_executor::traits::ExportXcm;
 
 /// Plain "XCM" payload, which we transfer through bridge
 pub type XcmAsPlainPayload = sp_std::prelude::Vec<u8>;
@@ -166,3 +171,199 @@ impl<H: XcmBlobHauler> HaulBlob for XcmBlobHaulerAdapter<H> {
 			})
 	}
 }
+
+/// Provides asset that is used to pay message export fee for messages, sent from given location.
+pub trait ExportFeeAsset {
+	/// Returns asset that is used to pay export fee for messages, sent from the
+	/// `universal_source`. If `None` is returned, the fee is paid in the native asset.
+	fn export_fee_asset(universal_source: &InteriorMultiLocation) -> Option<AssetId>;
+}
+
+impl ExportFeeAsset for () {
+	fn export_fee_asset(_universal_source: &InteriorMultiLocation) -> Option<AssetId> {
+		None
+	}
+}
+
+/// [`ExportXcm`] adapter that allows paying message export fee in non-native assets.
+///
+/// The `Exporter` computes message export price in the `NativeAsset`. If the `FeeAsset`
+/// selects some other asset for the message sender, the native part of the price is converted
+/// into that asset using the `Converter` (which may be e.g. an asset rate oracle or an asset
+/// conversion pool adapter). Message is rejected with the `SendError::Fees` if conversion
+/// fails or if the non-zero price is converted into zero amount of the fee asset.
+pub struct ExporterWithFeeInAsset<Exporter, NativeAsset, FeeAsset, Converter>(
+	PhantomData<(Exporter, NativeAsset, FeeAsset, Converter)>,
+);
+
+impl<Exporter, NativeAsset, FeeAsset, Converter> ExportXcm
+	for ExporterWithFeeInAsset<Exporter, NativeAsset, FeeAsset, Converter>
+where
+	Exporter: ExportXcm,
+	NativeAsset: Get<AssetId>,
+	FeeAsset: ExportFeeAsset,
+	Converter: ConversionToAssetBalance<u128, AssetId, u128>,
+{
+	type Ticket = Exporter::Ticket;
+
+	fn validate(
+		network: NetworkId,
+		channel: u32,
+		universal_source: &mut Option<InteriorMultiLocation>,
+		destination: &mut Option<InteriorMultiLocation>,
+		message: &mut Option<Xcm<()>>,
+	) -> SendResult<Self::Ticket> {
+		// `Exporter` may consume the `universal_source`, so let's select fee asset before
+		let fee_asset = universal_source.as_ref().and_then(FeeAsset::export_fee_asset);
+		let (ticket, price) =
+			Exporter::validate(network, channel, universal_source, destination, message)?;
+		let fee_asset = match fee_asset {
+			Some(fee_asset) if fee_asset != NativeAsset::get() => fee_asset,
+			_ => return Ok((ticket, price)),
+		};
+
+		let price = price
+			.into_inner()
+			.into_iter()
+			.map(|asset| match asset {
+				MultiAsset { id, fun: Fungible(amount) } if id == NativeAsset::get() =>
+					Converter::to_asset_balance(amount, fee_asset)
+						.ok()
+						// otherwise the message would be exported for free
+						.filter(|fee_amount| *fee_amount != 0 || amount == 0)
+						.map(|fee_amount| (fee_asset, fee_amount).into())
+						.ok_or_else(|| {
+							log::trace!(
+								target: crate::LOG_TARGET_BRIDGE_DISPATCH,
+								"Failed to convert export fee {} to asset {:?}",
+								amount,
+								fee_asset,
+							);
+							SendError::Fees
+						}),
+				asset => Ok(asset),
+			})
+			.collect::<Result<Vec<MultiAsset>, SendError>>()?;
+
+		Ok((ticket, price.into()))
+	}
+
+	fn deliver(ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+		Exporter::deliver(ticket)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::parameter_types;
+
+	parameter_types! {
+		pub NativeAssetId: AssetId = Concrete(Here.into());
+		pub FeeAssetId: AssetId = Concrete(X1(GeneralIndex(1)).into());
+		pub ExpensiveFeeAssetId: AssetId = Concrete(X1(GeneralIndex(2)).into());
+		pub UnsupportedFeeAssetId: AssetId = Concrete(X1(GeneralIndex(3)).into());
+		pub OtherAssetId: AssetId = Concrete(X1(GeneralIndex(4)).into());
+	}
+
+	const NATIVE_EXPORT_PRICE: u128 = 1_000;
+	const OTHER_EXPORT_PRICE: u128 = 10;
+
+	struct TestExporter;
+
+	impl ExportXcm for TestExporter {
+		type Ticket = ();
+
+		fn validate(
+			_network: NetworkId,
+			_channel: u32,
+			_universal_source: &mut Option<InteriorMultiLocation>,
+			_destination: &mut Option<InteriorMultiLocation>,
+			_message: &mut Option<Xcm<()>>,
+		) -> SendResult<Self::Ticket> {
+			Ok(((), export_price(NativeAssetId::get(), NATIVE_EXPORT_PRICE)))
+		}
+
+		fn deliver(_ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+			Ok([0u8; 32])
+		}
+	}
+
+	struct TestFeeAsset;
+
+	impl ExportFeeAsset for TestFeeAsset {
+		fn export_fee_asset(universal_source: &InteriorMultiLocation) -> Option<AssetId> {
+			match universal_source {
+				X1(Parachain(1000)) => Some(FeeAssetId::get()),
+				X1(Parachain(1001)) => Some(ExpensiveFeeAssetId::get()),
+				X1(Parachain(1002)) => Some(UnsupportedFeeAssetId::get()),
+				X1(Parachain(1003)) => Some(NativeAssetId::get()),
+				_ => None,
+			}
+		}
+	}
+
+	struct TestConverter;
+
+	impl ConversionToAssetBalance<u128, AssetId, u128> for TestConverter {
+		type Error = ();
+
+		fn to_asset_balance(balance: u128, asset_id: AssetId) -> Result<u128, ()> {
+			if asset_id == FeeAssetId::get() {
+				Ok(balance / 100)
+			} else if asset_id == ExpensiveFeeAssetId::get() {
+				Ok(balance / 10_000)
+			} else {
+				Err(())
+			}
+		}
+	}
+
+	type TestExporterWithFeeInAsset =
+		ExporterWithFeeInAsset<TestExporter, NativeAssetId, TestFeeAsset, TestConverter>;
+
+	fn export_price(fee_asset: AssetId, fee_amount: u128) -> MultiAssets {
+		vec![(fee_asset, fee_amount).into(), (OtherAssetId::get(), OTHER_EXPORT_PRICE).into()]
+			.into()
+	}
+
+	fn validate(sender: u32) -> SendResult<()> {
+		TestExporterWithFeeInAsset::validate(
+			NetworkId::Kusama,
+			0,
+			&mut Some(X1(Parachain(sender))),
+			&mut Some(Here),
+			&mut Some(Xcm(vec![ClearOrigin])),
+		)
+	}
+
+	#[test]
+	fn export_fee_is_charged_in_native_asset_by_default() {
+		assert_eq!(
+			validate(999),
+			Ok(((), export_price(NativeAssetId::get(), NATIVE_EXPORT_PRICE)))
+		);
+		assert_eq!(
+			validate(1003),
+			Ok(((), export_price(NativeAssetId::get(), NATIVE_EXPORT_PRICE)))
+		);
+	}
+
+	#[test]
+	fn export_fee_is_charged_in_selected_asset() {
+		assert_eq!(
+			validate(1000),
+			Ok(((), export_price(FeeAssetId::get(), NATIVE_EXPORT_PRICE / 100))),
+		);
+	}
+
+	#[test]
+	fn export_is_rejected_if_converted_fee_is_insufficient() {
+		assert_eq!(validate(1001), Err(SendError::Fees));
+	}
+
+	#[test]
+	fn export_is_rejected_if_fee_asset_is_unsupported() {
+		assert_eq!(validate(1002), Err(SendError::Fees));
+	}
+}
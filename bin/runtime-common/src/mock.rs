@@ -40,7 +40,7 @@ use sp_core::Get;
 use sp_runtime::{
 	testing::H256,
 	traits::{BlakeTwo256, ConstU32, ConstU64, ConstU8, IdentityLookup},
-	FixedPointNumber, Perquintill,
+	FixedPointNumber, Perbill, Perquintill,
 };
 
 /// Account identifier at `ThisChain`.
@@ -103,6 +103,8 @@ frame_support::construct_runtime! {
 	{
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Utility: pallet_utility,
+		Proxy: pallet_proxy,
+		Multisig: pallet_multisig,
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
 		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
@@ -128,6 +130,7 @@ parameter_types! {
 	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000u128);
 	pub MaximumMultiplier: Multiplier = sp_runtime::traits::Bounded::max_value();
 	pub const ReserveId: [u8; 8] = *b"brdgrlrs";
+	pub const SlashFraction: Perbill = Perbill::from_percent(50);
 }
 
 impl frame_system::Config for TestRuntime {
@@ -163,6 +166,31 @@ impl pallet_utility::Config for TestRuntime {
 	type WeightInfo = ();
 }
 
+impl pallet_proxy::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ();
+	type ProxyDepositBase = ConstU64<1>;
+	type ProxyDepositFactor = ConstU64<1>;
+	type MaxProxies = ConstU32<4>;
+	type WeightInfo = ();
+	type MaxPending = ConstU32<4>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = ConstU64<1>;
+	type AnnouncementDepositFactor = ConstU64<1>;
+}
+
+impl pallet_multisig::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type DepositBase = ConstU64<1>;
+	type DepositFactor = ConstU64<1>;
+	type MaxSignatories = ConstU32<4>;
+	type WeightInfo = ();
+}
+
 impl pallet_balances::Config for TestRuntime {
 	type Balance = ThisChainBalance;
 	type RuntimeEvent = RuntimeEvent;
@@ -198,6 +226,9 @@ impl pallet_bridge_grandpa::Config for TestRuntime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = BridgedUnderlyingChain;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<32>;
+	type FreeHeadersEpochLength = ConstU32<8>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = ConstU32<8>;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<TestRuntime>;
 }
@@ -229,6 +260,9 @@ impl pallet_bridge_messages::Config for TestRuntime {
 	>;
 
 	type MessageDispatch = ForbidInboundMessages<Vec<u8>>;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
 	type ThisChain = ThisUnderlyingChain;
 	type BridgedChain = BridgedUnderlyingChain;
 	type BridgedHeaderChain = BridgeGrandpa;
@@ -239,6 +273,8 @@ impl pallet_bridge_relayers::Config for TestRuntime {
 	type Reward = ThisChainBalance;
 	type PaymentProcedure = TestPaymentProcedure;
 	type StakeAndSlash = TestStakeAndSlash;
+	type SlashFraction = SlashFraction;
+	type RewardPayoutThreshold = ();
 	type WeightInfo = ();
 }
 
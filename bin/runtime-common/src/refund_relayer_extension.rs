@@ -46,11 +46,13 @@ use pallet_bridge_parachains::{
 use pallet_bridge_relayers::{
 	Config as RelayersConfig, Pallet as RelayersPallet, WeightInfoExt as _,
 };
+use pallet_multisig::{Call as MultisigCall, Config as MultisigConfig, Pallet as MultisigPallet};
+use pallet_proxy::{Call as ProxyCall, Config as ProxyConfig, Pallet as ProxyPallet};
 use pallet_transaction_payment::{Config as TransactionPaymentConfig, OnChargeTransaction};
 use pallet_utility::{Call as UtilityCall, Config as UtilityConfig, Pallet as UtilityPallet};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{DispatchInfoOf, Get, PostDispatchInfoOf, SignedExtension, Zero},
+	traits::{DispatchInfoOf, Get, PostDispatchInfoOf, SignedExtension, StaticLookup, Zero},
 	transaction_validity::{
 		TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransactionBuilder,
 	},
@@ -151,6 +153,112 @@ where
 	}
 }
 
+/// Unwraps bridge calls, that are dispatched on behalf of other accounts.
+///
+/// The relayer may submit bridge transactions on behalf of other account (e.g. using the
+/// `pallet-proxy`), so it doesn't need to hold the key of the account that is registered as
+/// relayer. Then the refund is registered for (and the slash is applied to) the account, on
+/// behalf of which bridge calls are dispatched.
+pub trait RelayerCallUnwrapper<AccountId, Call> {
+	/// Returns the account, on behalf of which the wrapped call is dispatched, and the wrapped
+	/// call itself.
+	///
+	/// Returns `None` if the call is not wrapped or if the `signer` may not dispatch the wrapped
+	/// call on behalf of other account.
+	fn unwrap_call<'a>(signer: &AccountId, call: &'a Call) -> Option<(AccountId, &'a Call)>;
+}
+
+impl<AccountId, Call> RelayerCallUnwrapper<AccountId, Call> for () {
+	fn unwrap_call<'a>(_signer: &AccountId, _call: &'a Call) -> Option<(AccountId, &'a Call)> {
+		None
+	}
+}
+
+impl<AccountId, Call, A, B> RelayerCallUnwrapper<AccountId, Call> for (A, B)
+where
+	A: RelayerCallUnwrapper<AccountId, Call>,
+	B: RelayerCallUnwrapper<AccountId, Call>,
+{
+	fn unwrap_call<'a>(signer: &AccountId, call: &'a Call) -> Option<(AccountId, &'a Call)> {
+		A::unwrap_call(signer, call).or_else(|| B::unwrap_call(signer, call))
+	}
+}
+
+/// `RelayerCallUnwrapper` implementation for the `pallet_proxy::Call::proxy` call.
+///
+/// The call is only unwrapped if the signer is the proxy (without delay) of the `real`
+/// account. The relayer is the `real` account.
+pub struct ProxyCallUnwrapper<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> RelayerCallUnwrapper<Runtime::AccountId, CallOf<Runtime>>
+	for ProxyCallUnwrapper<Runtime>
+where
+	Runtime: ProxyConfig<RuntimeCall = CallOf<Runtime>>,
+	CallOf<Runtime>: IsSubType<CallableCallFor<ProxyPallet<Runtime>, Runtime>>,
+{
+	fn unwrap_call<'a>(
+		signer: &Runtime::AccountId,
+		call: &'a CallOf<Runtime>,
+	) -> Option<(Runtime::AccountId, &'a CallOf<Runtime>)> {
+		let (real, force_proxy_type, call) = match call.is_sub_type()? {
+			ProxyCall::<Runtime>::proxy { ref real, ref force_proxy_type, ref call } =>
+				(real, force_proxy_type, call),
+			_ => return None,
+		};
+
+		// announced calls are not supported, because they can't be dispatched immediately
+		let real = <Runtime as frame_system::Config>::Lookup::lookup(real.clone()).ok()?;
+		let proxy_def =
+			ProxyPallet::<Runtime>::find_proxy(&real, signer, force_proxy_type.clone()).ok()?;
+		if !proxy_def.delay.is_zero() {
+			return None
+		}
+
+		Some((real, &**call))
+	}
+}
+
+/// `RelayerCallUnwrapper` implementation for the `pallet_multisig::Call::as_multi_threshold_1`
+/// call.
+///
+/// The relayer is the multisig account of the signer and other signatories. Multisig accounts
+/// with larger threshold are not supported, because their calls are dispatched by the last
+/// approval, which may be submitted by any of signatories.
+pub struct MultisigThreshold1CallUnwrapper<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> RelayerCallUnwrapper<Runtime::AccountId, CallOf<Runtime>>
+	for MultisigThreshold1CallUnwrapper<Runtime>
+where
+	Runtime: MultisigConfig<RuntimeCall = CallOf<Runtime>>,
+	CallOf<Runtime>: IsSubType<CallableCallFor<MultisigPallet<Runtime>, Runtime>>,
+{
+	fn unwrap_call<'a>(
+		signer: &Runtime::AccountId,
+		call: &'a CallOf<Runtime>,
+	) -> Option<(Runtime::AccountId, &'a CallOf<Runtime>)> {
+		let (other_signatories, call) = match call.is_sub_type()? {
+			MultisigCall::<Runtime>::as_multi_threshold_1 { ref other_signatories, ref call } =>
+				(other_signatories, call),
+			_ => return None,
+		};
+
+		// the same checks are performed by the `pallet-multisig`, so if they fail, the call
+		// fails too
+		let max_other_signatories = Runtime::MaxSignatories::get().saturating_sub(1) as usize;
+		if other_signatories.is_empty() || other_signatories.len() > max_other_signatories {
+			return None
+		}
+		if !other_signatories.windows(2).all(|pair| pair[0] < pair[1]) {
+			return None
+		}
+		let mut signatories = other_signatories.clone();
+		let signer_position = signatories.binary_search(signer).err()?;
+		signatories.insert(signer_position, signer.clone());
+
+		Some((MultisigPallet::<Runtime>::multi_account_id(&signatories, 1), &**call))
+	}
+}
+
 /// Data that is crafted in `pre_dispatch` method and used at `post_dispatch`.
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct PreDispatchData<AccountId> {
@@ -229,6 +337,9 @@ enum RelayerAccountAction<AccountId, Reward> {
 /// parachain head, or just parachain head. Corresponding headers must be used in messages
 /// proof verification.
 ///
+/// Bridge calls may also be dispatched on behalf of other account, using one of calls that are
+/// recognized by the `RelayerCallUnwrapper`. Then the refund goes to this other account.
+///
 /// Extension does not refund transaction tip due to security reasons.
 #[derive(
 	DefaultNoBound,
@@ -240,8 +351,8 @@ enum RelayerAccountAction<AccountId, Reward> {
 	RuntimeDebugNoBound,
 	TypeInfo,
 )]
-#[scale_info(skip_type_params(Runtime, Para, Msgs, Refund, Priority, Id))]
-pub struct RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority, Id>(
+#[scale_info(skip_type_params(Runtime, Para, Msgs, Refund, Priority, Id, Unwrapper))]
+pub struct RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority, Id, Unwrapper>(
 	PhantomData<(
 		// runtime with `frame-utility`, `pallet-bridge-grandpa`, `pallet-bridge-parachains`,
 		// `pallet-bridge-messages` and `pallet-bridge-relayers` pallets deployed
@@ -260,11 +371,14 @@ pub struct RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority,
 		Priority,
 		// the runtime-unique identifier of this signed extension
 		Id,
+		// implementation of the `RelayerCallUnwrapper` trait, that is used to unwrap bridge
+		// calls, dispatched on behalf of other accounts
+		Unwrapper,
 	)>,
 );
 
-impl<Runtime, Para, Msgs, Refund, Priority, Id>
-	RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority, Id>
+impl<Runtime, Para, Msgs, Refund, Priority, Id, Unwrapper>
+	RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority, Id, Unwrapper>
 where
 	Self: 'static + Send + Sync,
 	Runtime: UtilityConfig<RuntimeCall = CallOf<Runtime>>
@@ -277,12 +391,22 @@ where
 	Refund: RefundCalculator<Balance = Runtime::Reward>,
 	Priority: Get<TransactionPriority>,
 	Id: StaticStrProvider,
+	Unwrapper: RelayerCallUnwrapper<Runtime::AccountId, CallOf<Runtime>>,
 	CallOf<Runtime>: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>
 		+ IsSubType<CallableCallFor<UtilityPallet<Runtime>, Runtime>>
 		+ GrandpaCallSubType<Runtime, Runtime::BridgesGrandpaPalletInstance>
 		+ ParachainsCallSubType<Runtime, Para::Instance>
 		+ MessagesCallSubType<Runtime, Msgs::Instance>,
 {
+	/// Returns the relayer account and the bridge call, which may be dispatched on behalf of
+	/// other account.
+	fn unwrap_call<'a>(
+		who: &Runtime::AccountId,
+		call: &'a CallOf<Runtime>,
+	) -> (Runtime::AccountId, &'a CallOf<Runtime>) {
+		Unwrapper::unwrap_call(who, call).unwrap_or_else(|| (who.clone(), call))
+	}
+
 	fn expand_call<'a>(&self, call: &'a CallOf<Runtime>) -> Vec<&'a CallOf<Runtime>> {
 		match call.is_sub_type() {
 			Some(UtilityCall::<Runtime>::batch_all { ref calls }) if calls.len() <= 3 =>
@@ -509,10 +633,18 @@ where
 
 		Some(bundled_messages)
 	}
+
+	/// Returns tag, provided by message delivery transaction that delivers messages starting
+	/// from given nonce.
+	fn message_delivery_tag(
+		first_bundled_nonce: MessageNonce,
+	) -> (&'static str, LaneId, MessageNonce) {
+		(Self::IDENTIFIER, Msgs::Id::get(), first_bundled_nonce)
+	}
 }
 
-impl<Runtime, Para, Msgs, Refund, Priority, Id> SignedExtension
-	for RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority, Id>
+impl<Runtime, Para, Msgs, Refund, Priority, Id, Unwrapper> SignedExtension
+	for RefundBridgedParachainMessages<Runtime, Para, Msgs, Refund, Priority, Id, Unwrapper>
 where
 	Self: 'static + Send + Sync,
 	Runtime: UtilityConfig<RuntimeCall = CallOf<Runtime>>
@@ -525,6 +657,7 @@ where
 	Refund: RefundCalculator<Balance = Runtime::Reward>,
 	Priority: Get<TransactionPriority>,
 	Id: StaticStrProvider,
+	Unwrapper: RelayerCallUnwrapper<Runtime::AccountId, CallOf<Runtime>>,
 	CallOf<Runtime>: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>
 		+ IsSubType<CallableCallFor<UtilityPallet<Runtime>, Runtime>>
 		+ GrandpaCallSubType<Runtime, Runtime::BridgesGrandpaPalletInstance>
@@ -553,26 +686,41 @@ where
 		// we're not calling `validate` from `pre_dispatch` directly because of performance
 		// reasons, so if you're adding some code that may fail here, please check if it needs
 		// to be added to the `pre_dispatch` as well
+		let (relayer, call) = Self::unwrap_call(who, call);
 		let parsed_call = self.parse_and_check_for_obsolete_call(call)?;
 
-		// the following code just plays with transaction priority and never returns an error
+		// the following code just plays with transaction priority and tags and never returns
+		// an error
+		let mut valid_transaction = ValidTransactionBuilder::default();
+
+		// message delivery transactions, delivering messages starting from the same nonce, are
+		// competing with each other - only one of them may be included into the block, because
+		// other transactions become obsolete after that. So we let them provide the same tag
+		// and the pool only keeps the transaction with the largest priority
+		if let Some(delivery_call) =
+			parsed_call.as_ref().filter(|call| call.is_receive_messages_proof_call())
+		{
+			valid_transaction = valid_transaction.and_provides(Self::message_delivery_tag(
+				*delivery_call.messages_call_info().bundled_messages().start(),
+			));
+		}
 
 		// we only boost priority of presumably correct message delivery transactions
 		let bundled_messages = match Self::bundled_messages_for_priority_boost(parsed_call.as_ref())
 		{
 			Some(bundled_messages) => bundled_messages,
-			None => return Ok(Default::default()),
+			None => return valid_transaction.build(),
 		};
 
 		// we only boost priority if relayer has staked required balance
-		if !RelayersPallet::<Runtime>::is_registration_active(who) {
-			return Ok(Default::default())
+		if !RelayersPallet::<Runtime>::is_registration_active(&relayer) {
+			return valid_transaction.build()
 		}
 
 		// compute priority boost
 		let priority_boost =
 			crate::priority_calculator::compute_priority_boost::<Priority>(bundled_messages);
-		let valid_transaction = ValidTransactionBuilder::default().priority(priority_boost);
+		valid_transaction = valid_transaction.priority(priority_boost);
 
 		log::trace!(
 			target: "runtime::bridge",
@@ -581,7 +729,7 @@ where
 			Self::IDENTIFIER,
 			Para::Id::get(),
 			Msgs::Id::get(),
-			who,
+			relayer,
 			bundled_messages,
 			priority_boost,
 		);
@@ -597,6 +745,7 @@ where
 		_len: usize,
 	) -> Result<Self::Pre, TransactionValidityError> {
 		// this is a relevant piece of `validate` that we need here (in `pre_dispatch`)
+		let (relayer, call) = Self::unwrap_call(who, call);
 		let parsed_call = self.parse_and_check_for_obsolete_call(call)?;
 
 		Ok(parsed_call.map(|call_info| {
@@ -608,7 +757,7 @@ where
 				Msgs::Id::get(),
 				call_info,
 			);
-			PreDispatchData { relayer: who.clone(), call_info }
+			PreDispatchData { relayer, call_info }
 		}))
 	}
 
@@ -641,7 +790,7 @@ where
 				);
 			},
 			RelayerAccountAction::Slash(relayer, slash_account) =>
-				RelayersPallet::<Runtime>::slash_and_deregister(&relayer, slash_account),
+				RelayersPallet::<Runtime>::slash(&relayer, slash_account),
 		}
 
 		Ok(())
@@ -678,7 +827,7 @@ mod tests {
 	use sp_runtime::{
 		traits::{ConstU64, Header as HeaderT},
 		transaction_validity::{InvalidTransaction, ValidTransaction},
-		DispatchError,
+		DispatchError, PerThing,
 	};
 
 	parameter_types! {
@@ -703,6 +852,7 @@ mod tests {
 		ActualFeeRefund<TestRuntime>,
 		ConstU64<1>,
 		StrTestExtension,
+		(ProxyCallUnwrapper<TestRuntime>, MultisigThreshold1CallUnwrapper<TestRuntime>),
 	>;
 
 	fn initial_balance_of_relayer_account_at_this_chain() -> ThisChainBalance {
@@ -1042,6 +1192,13 @@ mod tests {
 		extension.validate(&relayer_account_at_this_chain(), &call, &DispatchInfo::default(), 0)
 	}
 
+	fn message_delivery_validity() -> ValidTransaction {
+		ValidTransaction {
+			provides: vec![TestExtension::message_delivery_tag(101).encode()],
+			..Default::default()
+		}
+	}
+
 	fn run_validate_ignore_priority(call: RuntimeCall) -> TransactionValidity {
 		run_validate(call).map(|mut tx| {
 			tx.priority = 0;
@@ -1114,14 +1271,14 @@ mod tests {
 			Balances::set_balance(&relayer_account_at_this_chain(), ExistentialDeposit::get());
 
 			// message delivery is failing
-			assert_eq!(run_validate(message_delivery_call(200)), Ok(Default::default()),);
+			assert_eq!(run_validate(message_delivery_call(200)), Ok(message_delivery_validity()));
 			assert_eq!(
 				run_validate(parachain_finality_and_delivery_batch_call(200, 200)),
-				Ok(Default::default()),
+				Ok(message_delivery_validity()),
 			);
 			assert_eq!(
 				run_validate(all_finality_and_delivery_batch_call(200, 200, 200)),
-				Ok(Default::default()),
+				Ok(message_delivery_validity()),
 			);
 			// message confirmation validation is passing
 			assert_eq!(
@@ -1173,6 +1330,27 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn validate_provides_same_tag_for_competing_message_delivery_transactions() {
+		run_test(|| {
+			initialize_environment(100, 100, 100);
+
+			// transactions delivering messages, starting from the same nonce, are competing
+			let delivery_of_100_messages = run_validate(message_delivery_call(200)).unwrap();
+			let delivery_of_200_messages =
+				run_validate(all_finality_and_delivery_batch_call(200, 200, 300)).unwrap();
+			assert_eq!(delivery_of_100_messages.provides, delivery_of_200_messages.provides);
+
+			// transactions delivering other messages are not competing
+			initialize_environment(100, 100, 150);
+			let delivery_of_50_messages = run_validate(message_delivery_call(200)).unwrap();
+			assert_ne!(delivery_of_100_messages.provides, delivery_of_50_messages.provides);
+
+			// confirmation transactions don't provide any tags
+			assert!(run_validate(message_confirmation_call(200)).unwrap().provides.is_empty());
+		});
+	}
+
 	#[test]
 	fn validate_does_not_boost_priority_of_message_delivery_transactons_with_too_many_messages() {
 		run_test(|| {
@@ -1208,7 +1386,7 @@ mod tests {
 
 			assert_eq!(
 				run_validate_ignore_priority(message_delivery_call(200)),
-				Ok(ValidTransaction::default()),
+				Ok(message_delivery_validity()),
 			);
 			assert_eq!(
 				run_validate_ignore_priority(message_confirmation_call(200)),
@@ -1217,7 +1395,7 @@ mod tests {
 
 			assert_eq!(
 				run_validate_ignore_priority(parachain_finality_and_delivery_batch_call(200, 200)),
-				Ok(ValidTransaction::default()),
+				Ok(message_delivery_validity()),
 			);
 			assert_eq!(
 				run_validate_ignore_priority(parachain_finality_and_confirmation_batch_call(
@@ -1228,7 +1406,7 @@ mod tests {
 
 			assert_eq!(
 				run_validate_ignore_priority(all_finality_and_delivery_batch_call(200, 200, 200)),
-				Ok(ValidTransaction::default()),
+				Ok(message_delivery_validity()),
 			);
 			assert_eq!(
 				run_validate_ignore_priority(all_finality_and_confirmation_batch_call(
@@ -1395,6 +1573,97 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn pre_dispatch_parses_message_transaction_dispatched_by_proxy() {
+		run_test(|| {
+			initialize_environment(100, 100, 100);
+
+			let real = 42;
+			Balances::mint_into(&real, ExistentialDeposit::get() * 100).unwrap();
+			Proxy::add_proxy(RuntimeOrigin::signed(real), relayer_account_at_this_chain(), (), 0)
+				.unwrap();
+
+			let proxy_call = |call| {
+				RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+					real,
+					force_proxy_type: None,
+					call: Box::new(call),
+				})
+			};
+			let mut expected_pre_dispatch_data = delivery_pre_dispatch_data();
+			expected_pre_dispatch_data.relayer = real;
+			assert_eq!(
+				run_pre_dispatch(proxy_call(message_delivery_call(200))),
+				Ok(Some(expected_pre_dispatch_data)),
+			);
+		});
+	}
+
+	#[test]
+	fn pre_dispatch_ignores_message_transaction_dispatched_by_non_proxy() {
+		run_test(|| {
+			initialize_environment(100, 100, 100);
+
+			let proxy_call = RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+				real: 42,
+				force_proxy_type: None,
+				call: Box::new(message_delivery_call(200)),
+			});
+			assert_eq!(run_pre_dispatch(proxy_call), Ok(None));
+		});
+	}
+
+	#[test]
+	fn pre_dispatch_ignores_message_transaction_dispatched_by_delayed_proxy() {
+		run_test(|| {
+			initialize_environment(100, 100, 100);
+
+			let real = 42;
+			Balances::mint_into(&real, ExistentialDeposit::get() * 100).unwrap();
+			Proxy::add_proxy(RuntimeOrigin::signed(real), relayer_account_at_this_chain(), (), 10)
+				.unwrap();
+
+			let proxy_call = RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+				real,
+				force_proxy_type: None,
+				call: Box::new(message_delivery_call(200)),
+			});
+			assert_eq!(run_pre_dispatch(proxy_call), Ok(None));
+		});
+	}
+
+	#[test]
+	fn pre_dispatch_parses_message_transaction_dispatched_by_multisig_with_threshold_1() {
+		run_test(|| {
+			initialize_environment(100, 100, 100);
+
+			let multisig_call = |other_signatories| {
+				RuntimeCall::Multisig(pallet_multisig::Call::as_multi_threshold_1 {
+					other_signatories,
+					call: Box::new(message_delivery_call(200)),
+				})
+			};
+
+			let mut expected_pre_dispatch_data = delivery_pre_dispatch_data();
+			expected_pre_dispatch_data.relayer =
+				Multisig::multi_account_id(&[relayer_account_at_this_chain(), 1, 2], 1);
+			assert_eq!(
+				run_pre_dispatch(multisig_call(vec![1, 2])),
+				Ok(Some(expected_pre_dispatch_data)),
+			);
+
+			// unsorted signatories
+			assert_eq!(run_pre_dispatch(multisig_call(vec![2, 1])), Ok(None));
+			// signer is in the list of other signatories
+			assert_eq!(
+				run_pre_dispatch(multisig_call(vec![relayer_account_at_this_chain(), 1])),
+				Ok(None),
+			);
+			// too many signatories
+			assert_eq!(run_pre_dispatch(multisig_call(vec![1, 2, 3, 4])), Ok(None));
+		});
+	}
+
 	#[test]
 	fn post_dispatch_ignores_unknown_transaction() {
 		run_test(|| {
@@ -1615,6 +1884,7 @@ mod tests {
 				Balances::free_balance(delivery_rewards_account());
 
 			let test_stake: ThisChainBalance = TestStake::get();
+			let test_slash = SlashFraction::get().mul_floor(test_stake);
 			Balances::set_balance(
 				&relayer_account_at_this_chain(),
 				ExistentialDeposit::get() + test_stake * 10,
@@ -1625,9 +1895,13 @@ mod tests {
 				.unwrap();
 			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
 			run_post_dispatch(Some(delivery_pre_dispatch_data()), Ok(()));
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), 0);
 			assert_eq!(
-				delivery_rewards_account_balance + test_stake,
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake - test_slash
+			);
+			assert!(!BridgeRelayers::is_registration_active(&relayer_account_at_this_chain()));
+			assert_eq!(
+				delivery_rewards_account_balance + test_slash,
 				Balances::free_balance(delivery_rewards_account())
 			);
 
@@ -1635,9 +1909,12 @@ mod tests {
 				.unwrap();
 			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
 			run_post_dispatch(Some(parachain_finality_pre_dispatch_data()), Ok(()));
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), 0);
 			assert_eq!(
-				delivery_rewards_account_balance + test_stake * 2,
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake - test_slash
+			);
+			assert_eq!(
+				delivery_rewards_account_balance + test_slash * 2,
 				Balances::free_balance(delivery_rewards_account())
 			);
 
@@ -1645,9 +1922,12 @@ mod tests {
 				.unwrap();
 			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
 			run_post_dispatch(Some(all_finality_pre_dispatch_data()), Ok(()));
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), 0);
 			assert_eq!(
-				delivery_rewards_account_balance + test_stake * 3,
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake - test_slash
+			);
+			assert_eq!(
+				delivery_rewards_account_balance + test_slash * 3,
 				Balances::free_balance(delivery_rewards_account())
 			);
 
@@ -1656,24 +1936,36 @@ mod tests {
 				Balances::free_balance(confirmation_rewards_account());
 
 			Balances::reserve(&relayer_account_at_this_chain(), test_stake).unwrap();
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
+			assert_eq!(
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake * 2 - test_slash
+			);
 
 			assert_eq!(
 				confirmation_rewards_account_balance,
 				Balances::free_balance(confirmation_rewards_account())
 			);
 			run_post_dispatch(Some(confirmation_pre_dispatch_data()), Ok(()));
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
+			assert_eq!(
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake * 2 - test_slash
+			);
 
 			run_post_dispatch(Some(parachain_finality_confirmation_pre_dispatch_data()), Ok(()));
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
+			assert_eq!(
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake * 2 - test_slash
+			);
 
 			run_post_dispatch(Some(all_finality_confirmation_pre_dispatch_data()), Ok(()));
-			assert_eq!(Balances::reserved_balance(relayer_account_at_this_chain()), test_stake);
+			assert_eq!(
+				Balances::reserved_balance(relayer_account_at_this_chain()),
+				test_stake * 2 - test_slash
+			);
 
 			// check that unreserve has happened, not slashing
 			assert_eq!(
-				delivery_rewards_account_balance + test_stake * 3,
+				delivery_rewards_account_balance + test_slash * 3,
 				Balances::free_balance(delivery_rewards_account())
 			);
 			assert_eq!(
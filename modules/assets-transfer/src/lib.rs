@@ -0,0 +1,586 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Module that allows runtimes to transfer assets to the bridged consensus system.
+//!
+//! The pallet provides single `transfer_over_bridge` call, which:
+//!
+//! 1) reserves transferred assets, which reserve is this chain, at the sovereign account of the
+//!    destination location and withdraws transferred assets, which reserve is the destination, from
+//!    the sender account. Assets with other reserve locations are not supported;
+//!
+//! 2) constructs canonical XCM program (`WithdrawAsset` + `ReserveAssetDeposited` + `ClearOrigin`
+//!    + `BuyExecution` + `DepositAsset`) for the destination. `WithdrawAsset` is only included if
+//!    there are assets, withdrawn from the sender account and `ReserveAssetDeposited` is only
+//!    included if there are reserved assets;
+//!
+//! 3) withdraws the delivery fee, computed by the `XcmSender` (e.g. the
+//!    `pallet-xcm-bridge-hub-router`), from the sender account;
+//!
+//! 4) sends the program to the bridged destination using the `XcmSender`.
+//!
+//! All operations are performed atomically - if any of them fails, the call fails and all
+//! changes are reverted.
+
+#![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::traits::Get;
+use sp_std::{boxed::Box, vec::Vec};
+use xcm::prelude::*;
+use xcm_executor::traits::TransactAsset;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+mod mock;
+
+/// The target that will be used when publishing logs related to this pallet.
+pub const LOG_TARGET: &str = "runtime::bridge-assets-transfer";
+
+/// Reserve location of the transferred asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AssetReserve {
+	/// This chain is the reserve of the asset.
+	Local,
+	/// The transfer destination is the reserve of the asset.
+	Destination,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self, I>>
+			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Benchmarks results from runtime we're plugged into.
+		type WeightInfo: WeightInfo;
+
+		/// Runtime's universal location.
+		type UniversalLocation: Get<InteriorMultiLocation>;
+		/// Bridged network id. Only destinations within this consensus system are supported.
+		#[pallet::constant]
+		type BridgedNetworkId: Get<NetworkId>;
+
+		/// Origin that is allowed to transfer assets. Its `Success` value is the location of
+		/// the sender account.
+		type TransferOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = MultiLocation>;
+		/// Asset transactor, used to reserve transferred assets and to pay delivery fee.
+		type AssetTransactor: TransactAsset;
+		/// XCM router that is used to send messages to the bridged consensus system.
+		type XcmSender: SendXcm;
+
+		/// Maximal number of assets that may be transferred using single call.
+		#[pallet::constant]
+		type MaxAssetsLimit: Get<u8>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Transfer assets to the `beneficiary` at the `destination` within the bridged
+		/// consensus system.
+		///
+		/// Local assets are reserved at the sovereign account of the `destination` and assets,
+		/// which reserve is the `destination`, are withdrawn from the sender account. The
+		/// delivery fee is withdrawn from the sender account. The first of transferred assets
+		/// is used to pay for execution at the `destination`. The `beneficiary` is relative to
+		/// the `destination`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::transfer_over_bridge(Pallet::<T, I>::assets_count(assets)))]
+		pub fn transfer_over_bridge(
+			origin: OriginFor<T>,
+			assets: Box<VersionedMultiAssets>,
+			destination: Box<VersionedMultiLocation>,
+			beneficiary: Box<VersionedMultiLocation>,
+		) -> DispatchResult {
+			let sender = T::TransferOrigin::ensure_origin(origin)?;
+			let assets: MultiAssets =
+				(*assets).try_into().map_err(|_| Error::<T, I>::UnsupportedXcmVersion)?;
+			let destination: MultiLocation =
+				(*destination).try_into().map_err(|_| Error::<T, I>::UnsupportedXcmVersion)?;
+			let beneficiary: MultiLocation =
+				(*beneficiary).try_into().map_err(|_| Error::<T, I>::UnsupportedXcmVersion)?;
+
+			ensure!(!assets.is_none(), Error::<T, I>::NoAssets);
+			ensure!(
+				assets.len() <= T::MaxAssetsLimit::get() as usize,
+				Error::<T, I>::TooManyAssets,
+			);
+			ensure!(
+				Self::is_bridged_destination(&destination),
+				Error::<T, I>::UnsupportedDestination
+			);
+
+			// reserve local assets at the sovereign account of the destination and withdraw
+			// assets, which are reserved at the destination, from the sender account
+			let context = XcmContext { origin: Some(sender), message_id: [0u8; 32], topic: None };
+			let mut reserved_assets = Vec::new();
+			let mut withdrawn_assets = Vec::new();
+			for asset in assets.inner() {
+				match Self::asset_reserve(asset, &destination)? {
+					AssetReserve::Local => {
+						T::AssetTransactor::transfer_asset(asset, &sender, &destination, &context)
+							.map_err(|e| {
+								log::trace!(
+									target: LOG_TARGET,
+									"Failed to reserve asset {:?} of {:?}: {:?}",
+									asset,
+									sender,
+									e,
+								);
+								Error::<T, I>::FailedToReserve
+							})?;
+						reserved_assets.push(asset.clone());
+					},
+					AssetReserve::Destination => {
+						T::AssetTransactor::withdraw_asset(asset, &sender, Some(&context))
+							.map_err(|e| {
+								log::trace!(
+									target: LOG_TARGET,
+									"Failed to withdraw asset {:?} from {:?}: {:?}",
+									asset,
+									sender,
+									e,
+								);
+								Error::<T, I>::FailedToWithdraw
+							})?;
+						withdrawn_assets.push(asset.clone());
+					},
+				}
+			}
+
+			// prepare the program that will be executed at the destination
+			let message = Self::transfer_program(
+				reserved_assets.into(),
+				withdrawn_assets.into(),
+				assets.get(0).ok_or(Error::<T, I>::NoAssets)?.clone(),
+				&destination,
+				beneficiary,
+			)?;
+
+			// pay the delivery fee and send the message
+			let (ticket, fee) =
+				validate_send::<T::XcmSender>(destination, message).map_err(|e| {
+					log::trace!(
+						target: LOG_TARGET,
+						"Failed to validate transfer message to {:?}: {:?}",
+						destination,
+						e,
+					);
+					Error::<T, I>::SendFailed
+				})?;
+			for asset in fee.inner() {
+				T::AssetTransactor::withdraw_asset(asset, &sender, Some(&context)).map_err(
+					|e| {
+						log::trace!(
+							target: LOG_TARGET,
+							"Failed to withdraw delivery fee {:?} from {:?}: {:?}",
+							asset,
+							sender,
+							e,
+						);
+						Error::<T, I>::FailedToPayFee
+					},
+				)?;
+			}
+			let message_id = T::XcmSender::deliver(ticket).map_err(|e| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Failed to deliver transfer message to {:?}: {:?}",
+					destination,
+					e,
+				);
+				Error::<T, I>::SendFailed
+			})?;
+
+			log::trace!(
+				target: LOG_TARGET,
+				"Sent message {:?} with assets {:?} from {:?} to {:?}/{:?}",
+				message_id,
+				assets,
+				sender,
+				destination,
+				beneficiary,
+			);
+			Self::deposit_event(Event::AssetsTransferred {
+				sender,
+				destination,
+				beneficiary,
+				assets,
+				fee,
+				message_id,
+			});
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Returns true if `destination` is within the bridged consensus system.
+		fn is_bridged_destination(destination: &MultiLocation) -> bool {
+			T::UniversalLocation::get()
+				.within_global(*destination)
+				.map(|universal_destination| {
+					universal_destination.global_consensus() == Ok(T::BridgedNetworkId::get())
+				})
+				.unwrap_or(false)
+		}
+
+		/// Returns number of assets, that is used to compute the call weight.
+		fn assets_count(assets: &VersionedMultiAssets) -> u32 {
+			let assets_count = match *assets {
+				VersionedMultiAssets::V2(ref assets) => assets.inner().len(),
+				VersionedMultiAssets::V3(ref assets) => assets.inner().len(),
+			};
+			// the call fails early if there are more assets than allowed
+			sp_std::cmp::min(assets_count, T::MaxAssetsLimit::get() as usize) as u32
+		}
+
+		/// Returns reserve location of the transferred `asset`.
+		///
+		/// Only assets that are reserved at this chain or at the `destination` are supported.
+		fn asset_reserve(
+			asset: &MultiAsset,
+			destination: &MultiLocation,
+		) -> Result<AssetReserve, Error<T, I>> {
+			match asset.id {
+				Concrete(ref location) if location.parents == 0 => Ok(AssetReserve::Local),
+				Concrete(ref location) if location.starts_with(destination) =>
+					Ok(AssetReserve::Destination),
+				_ => Err(Error::<T, I>::UnsupportedAssetReserve),
+			}
+		}
+
+		/// Returns program that deposits reserved and withdrawn assets to the `beneficiary` at
+		/// the `destination`.
+		fn transfer_program(
+			mut reserved_assets: MultiAssets,
+			mut withdrawn_assets: MultiAssets,
+			mut fees: MultiAsset,
+			destination: &MultiLocation,
+			beneficiary: MultiLocation,
+		) -> Result<Xcm<()>, Error<T, I>> {
+			let universal_location = T::UniversalLocation::get();
+			reserved_assets
+				.reanchor(destination, universal_location)
+				.map_err(|_| Error::<T, I>::InvalidAssets)?;
+			withdrawn_assets
+				.reanchor(destination, universal_location)
+				.map_err(|_| Error::<T, I>::InvalidAssets)?;
+			fees.reanchor(destination, universal_location)
+				.map_err(|_| Error::<T, I>::InvalidAssets)?;
+			let assets_count = (reserved_assets.len() + withdrawn_assets.len()) as u32;
+
+			let mut program = Vec::with_capacity(5);
+			if !withdrawn_assets.is_none() {
+				program.push(WithdrawAsset(withdrawn_assets));
+			}
+			if !reserved_assets.is_none() {
+				program.push(ReserveAssetDeposited(reserved_assets));
+			}
+			program.push(ClearOrigin);
+			program.push(BuyExecution { fees, weight_limit: Unlimited });
+			program.push(DepositAsset { assets: Wild(AllCounted(assets_count)), beneficiary });
+
+			Ok(Xcm(program))
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Assets have been transferred to the bridged consensus system.
+		AssetsTransferred {
+			/// Location of the sender.
+			sender: MultiLocation,
+			/// Destination location within the bridged consensus system.
+			destination: MultiLocation,
+			/// Beneficiary location, relative to the `destination`.
+			beneficiary: MultiLocation,
+			/// Transferred assets.
+			assets: MultiAssets,
+			/// Delivery fee, paid by the sender.
+			fee: MultiAssets,
+			/// Identifier of the sent XCM message.
+			message_id: XcmHash,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Unsupported XCM version of call arguments.
+		UnsupportedXcmVersion,
+		/// No assets have been provided.
+		NoAssets,
+		/// Too many assets have been provided.
+		TooManyAssets,
+		/// Assets can't be represented at the destination.
+		InvalidAssets,
+		/// Destination is not within the bridged consensus system.
+		UnsupportedDestination,
+		/// Failed to reserve transferred assets.
+		FailedToReserve,
+		/// Failed to pay the delivery fee.
+		FailedToPayFee,
+		/// Failed to send message to the destination.
+		SendFailed,
+		/// Reserve of some of transferred assets is neither this chain, nor the destination.
+		UnsupportedAssetReserve,
+		/// Failed to withdraw transferred assets, which are reserved at the destination.
+		FailedToWithdraw,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::*;
+
+	use frame_support::{assert_noop, assert_ok};
+	use sp_runtime::DispatchError;
+
+	fn native_assets(amount: Balance) -> Box<VersionedMultiAssets> {
+		Box::new(VersionedMultiAssets::from(MultiAssets::from((Here, amount as u128))))
+	}
+
+	fn bridged_destination() -> Box<VersionedMultiLocation> {
+		Box::new(VersionedMultiLocation::from(bridged_asset_hub_location()))
+	}
+
+	fn beneficiary() -> MultiLocation {
+		X1(AccountId32 { network: None, id: [3u8; 32] }).into()
+	}
+
+	fn transfer(
+		origin: RuntimeOrigin,
+		assets: Box<VersionedMultiAssets>,
+		destination: Box<VersionedMultiLocation>,
+	) -> DispatchResult {
+		AssetsTransfer::transfer_over_bridge(
+			origin,
+			assets,
+			destination,
+			Box::new(VersionedMultiLocation::from(beneficiary())),
+		)
+	}
+
+	#[test]
+	fn transfer_over_bridge_works() {
+		run_test(|| {
+			assert_ok!(transfer(
+				RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+				native_assets(1_000),
+				bridged_destination(),
+			));
+
+			// assets are reserved at the sovereign account of the destination and the delivery
+			// fee is paid by the sender
+			assert_eq!(
+				Balances::free_balance(AccountId::from(SENDER_ACCOUNT)),
+				SENDER_BALANCE - 1_000 - DELIVERY_FEE,
+			);
+			assert_eq!(Balances::free_balance(AccountId::from(BRIDGED_ASSET_HUB_ACCOUNT)), 1_000);
+
+			// canonical program is sent to the destination
+			let expected_assets: MultiAssets = (
+				MultiLocation::new(2, X2(GlobalConsensus(RelayNetwork::get()), Parachain(1000))),
+				1_000_u128,
+			)
+				.into();
+			assert_eq!(
+				TestXcmSender::sent_message(),
+				Some((
+					bridged_asset_hub_location(),
+					Xcm(vec![
+						ReserveAssetDeposited(expected_assets.clone()),
+						ClearOrigin,
+						BuyExecution {
+							fees: expected_assets.get(0).unwrap().clone(),
+							weight_limit: Unlimited,
+						},
+						DepositAsset { assets: Wild(AllCounted(1)), beneficiary: beneficiary() },
+					]),
+				)),
+			);
+
+			// and event is emitted
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(RuntimeEvent::AssetsTransfer(Event::AssetsTransferred {
+					sender: sender_location(SENDER_ACCOUNT),
+					destination: bridged_asset_hub_location(),
+					beneficiary: beneficiary(),
+					assets: (Here, 1_000_u128).into(),
+					fee: (Here, DELIVERY_FEE as u128).into(),
+					message_id: [42u8; 32],
+				})),
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_withdraws_assets_reserved_at_destination() {
+		run_test(|| {
+			let assets: MultiAssets =
+				vec![(Here, 1_000_u128).into(), (bridged_asset_hub_location(), 2_000_u128).into()]
+					.into();
+			assert_ok!(transfer(
+				RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+				Box::new(VersionedMultiAssets::from(assets)),
+				bridged_destination(),
+			));
+
+			// local assets are reserved at the sovereign account of the destination and
+			// bridged assets are withdrawn from the sender account
+			assert_eq!(
+				Balances::free_balance(AccountId::from(SENDER_ACCOUNT)),
+				SENDER_BALANCE - 1_000 - DELIVERY_FEE,
+			);
+			assert_eq!(Balances::free_balance(AccountId::from(BRIDGED_ASSET_HUB_ACCOUNT)), 1_000);
+			assert_eq!(
+				BridgedBalances::free_balance(AccountId::from(SENDER_ACCOUNT)),
+				SENDER_BALANCE - 2_000,
+			);
+			assert_eq!(
+				BridgedBalances::free_balance(AccountId::from(BRIDGED_ASSET_HUB_ACCOUNT)),
+				0,
+			);
+
+			// withdrawn assets are withdrawn at the destination
+			let expected_reserved_assets: MultiAssets = (
+				MultiLocation::new(2, X2(GlobalConsensus(RelayNetwork::get()), Parachain(1000))),
+				1_000_u128,
+			)
+				.into();
+			let expected_withdrawn_assets: MultiAssets = (Here, 2_000_u128).into();
+			assert_eq!(
+				TestXcmSender::sent_message(),
+				Some((
+					bridged_asset_hub_location(),
+					Xcm(vec![
+						WithdrawAsset(expected_withdrawn_assets),
+						ReserveAssetDeposited(expected_reserved_assets.clone()),
+						ClearOrigin,
+						BuyExecution {
+							fees: expected_reserved_assets.get(0).unwrap().clone(),
+							weight_limit: Unlimited,
+						},
+						DepositAsset { assets: Wild(AllCounted(2)), beneficiary: beneficiary() },
+					]),
+				)),
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_rejects_assets_with_unsupported_reserve() {
+		run_test(|| {
+			assert_noop!(
+				transfer(
+					RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+					Box::new(VersionedMultiAssets::from(MultiAssets::from((Parent, 1_000_u128)))),
+					bridged_destination(),
+				),
+				Error::<TestRuntime, ()>::UnsupportedAssetReserve,
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_rejects_unsigned_origin() {
+		run_test(|| {
+			assert_noop!(
+				transfer(RuntimeOrigin::root(), native_assets(1_000), bridged_destination()),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_rejects_destination_outside_of_bridged_network() {
+		run_test(|| {
+			assert_noop!(
+				transfer(
+					RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+					native_assets(1_000),
+					Box::new(VersionedMultiLocation::from(MultiLocation::new(
+						1,
+						X1(Parachain(BRIDGED_ASSET_HUB_ID))
+					))),
+				),
+				Error::<TestRuntime, ()>::UnsupportedDestination,
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_rejects_too_many_assets() {
+		run_test(|| {
+			let assets: MultiAssets = vec![
+				(Here, 1_000_u128).into(),
+				(Parent, 1_000_u128).into(),
+				(MultiLocation::new(1, X1(Parachain(42))), 1_000_u128).into(),
+			]
+			.into();
+			assert_noop!(
+				transfer(
+					RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+					Box::new(VersionedMultiAssets::from(assets)),
+					bridged_destination(),
+				),
+				Error::<TestRuntime, ()>::TooManyAssets,
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_fails_if_sender_has_not_enough_funds() {
+		run_test(|| {
+			assert_noop!(
+				transfer(
+					RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+					native_assets(SENDER_BALANCE + 1),
+					bridged_destination(),
+				),
+				Error::<TestRuntime, ()>::FailedToReserve,
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_over_bridge_fails_if_sender_is_unable_to_pay_delivery_fee() {
+		run_test(|| {
+			assert_noop!(
+				transfer(
+					RuntimeOrigin::signed(SENDER_ACCOUNT.into()),
+					native_assets(SENDER_BALANCE - DELIVERY_FEE / 2),
+					bridged_destination(),
+				),
+				Error::<TestRuntime, ()>::FailedToPayFee,
+			);
+		});
+	}
+}
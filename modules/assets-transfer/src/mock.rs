@@ -0,0 +1,256 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(test)]
+
+use crate as pallet_bridge_assets_transfer;
+
+use frame_support::{
+	parameter_types,
+	traits::{EnsureOrigin, OriginTrait, StorageMapShim},
+};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, ConstU32, IdentityLookup},
+	AccountId32, BuildStorage,
+};
+use xcm::prelude::*;
+use xcm_builder::{AccountId32Aliases, CurrencyAdapter, IsConcrete};
+use xcm_executor::traits::ConvertLocation;
+
+pub type AccountId = AccountId32;
+pub type Balance = u64;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+
+/// Identifier of the parachain at bridged network, where assets are transferred to.
+pub const BRIDGED_ASSET_HUB_ID: u32 = 1001;
+/// Delivery fee, charged by the `TestXcmSender`.
+pub const DELIVERY_FEE: Balance = 100;
+/// Sovereign account of the bridged asset hub.
+pub const BRIDGED_ASSET_HUB_ACCOUNT: [u8; 32] = [1u8; 32];
+/// Sender account.
+pub const SENDER_ACCOUNT: [u8; 32] = [2u8; 32];
+/// Initial balance of the sender account.
+pub const SENDER_BALANCE: Balance = 1_000_000;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime {
+		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Event<T>},
+		BridgedBalances: pallet_balances::<Instance1>::{Pallet, Event<T>},
+		AssetsTransfer: pallet_bridge_assets_transfer::{Pallet, Call, Event<T>},
+	}
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl frame_system::Config for TestRuntime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Nonce = u64;
+	type RuntimeCall = RuntimeCall;
+	type Block = Block;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = frame_support::traits::ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type DustRemoval = ();
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Pallet<TestRuntime>;
+	type WeightInfo = ();
+	type MaxReserves = ConstU32<1>;
+	type ReserveIdentifier = [u8; 8];
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type FreezeIdentifier = ();
+	type MaxHolds = ConstU32<0>;
+	type MaxFreezes = ConstU32<0>;
+}
+
+impl pallet_balances::Config<pallet_balances::Instance1> for TestRuntime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type DustRemoval = ();
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = StorageMapShim<
+		pallet_balances::Account<TestRuntime, pallet_balances::Instance1>,
+		AccountId,
+		pallet_balances::AccountData<Balance>,
+	>;
+	type WeightInfo = ();
+	type MaxReserves = ConstU32<1>;
+	type ReserveIdentifier = [u8; 8];
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type FreezeIdentifier = ();
+	type MaxHolds = ConstU32<0>;
+	type MaxFreezes = ConstU32<0>;
+}
+
+parameter_types! {
+	pub const RelayNetwork: NetworkId = NetworkId::Kusama;
+	pub const BridgedRelayNetwork: NetworkId = NetworkId::Polkadot;
+	pub const AnyNetwork: Option<NetworkId> = None;
+	pub UniversalLocation: InteriorMultiLocation = X2(
+		GlobalConsensus(RelayNetwork::get()),
+		Parachain(1000),
+	);
+	pub NativeLocation: MultiLocation = Here.into();
+	pub BridgedNativeLocation: MultiLocation = bridged_asset_hub_location();
+}
+
+/// Converts bridged asset hub location and local accounts into account identifiers.
+pub type LocationToAccountId =
+	(BridgedAssetHubConvertsToAccount, AccountId32Aliases<AnyNetwork, AccountId>);
+
+/// Asset transactor that uses `Balances` to transact native assets and `BridgedBalances` to
+/// transact native assets of the bridged asset hub.
+pub type AssetTransactor = (
+	CurrencyAdapter<Balances, IsConcrete<NativeLocation>, LocationToAccountId, AccountId, ()>,
+	CurrencyAdapter<
+		BridgedBalances,
+		IsConcrete<BridgedNativeLocation>,
+		LocationToAccountId,
+		AccountId,
+		(),
+	>,
+);
+
+impl pallet_bridge_assets_transfer::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+
+	type UniversalLocation = UniversalLocation;
+	type BridgedNetworkId = BridgedRelayNetwork;
+
+	type TransferOrigin = TestTransferOrigin;
+	type AssetTransactor = AssetTransactor;
+	type XcmSender = TestXcmSender;
+
+	type MaxAssetsLimit = frame_support::traits::ConstU8<2>;
+}
+
+pub struct BridgedAssetHubConvertsToAccount;
+
+impl ConvertLocation<AccountId> for BridgedAssetHubConvertsToAccount {
+	fn convert_location(location: &MultiLocation) -> Option<AccountId> {
+		if *location == bridged_asset_hub_location() {
+			Some(BRIDGED_ASSET_HUB_ACCOUNT.into())
+		} else {
+			None
+		}
+	}
+}
+
+pub struct TestTransferOrigin;
+
+impl EnsureOrigin<RuntimeOrigin> for TestTransferOrigin {
+	type Success = MultiLocation;
+
+	fn try_origin(o: RuntimeOrigin) -> Result<Self::Success, RuntimeOrigin> {
+		match o.clone().into_signer() {
+			Some(account) => Ok(sender_location(account.into())),
+			None => Err(o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		Ok(RuntimeOrigin::signed(SENDER_ACCOUNT.into()))
+	}
+}
+
+pub struct TestXcmSender;
+
+impl TestXcmSender {
+	pub fn sent_message() -> Option<(MultiLocation, Xcm<()>)> {
+		frame_support::storage::unhashed::get(b"TestXcmSender.Sent")
+	}
+}
+
+impl SendXcm for TestXcmSender {
+	type Ticket = (MultiLocation, Xcm<()>);
+
+	fn validate(
+		destination: &mut Option<MultiLocation>,
+		message: &mut Option<Xcm<()>>,
+	) -> SendResult<Self::Ticket> {
+		let destination = destination.take().ok_or(SendError::MissingArgument)?;
+		let message = message.take().ok_or(SendError::MissingArgument)?;
+		Ok(((destination, message), (Here, DELIVERY_FEE as u128).into()))
+	}
+
+	fn deliver(ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+		frame_support::storage::unhashed::put(b"TestXcmSender.Sent", &ticket);
+		Ok([42u8; 32])
+	}
+}
+
+/// Location of the sender account.
+pub fn sender_location(account: [u8; 32]) -> MultiLocation {
+	X1(AccountId32 { network: None, id: account }).into()
+}
+
+/// Location of the bridged asset hub.
+pub fn bridged_asset_hub_location() -> MultiLocation {
+	MultiLocation::new(
+		2,
+		X2(GlobalConsensus(BridgedRelayNetwork::get()), Parachain(BRIDGED_ASSET_HUB_ID)),
+	)
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let mut t = frame_system::GenesisConfig::<TestRuntime>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(SENDER_ACCOUNT.into(), SENDER_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	pallet_balances::GenesisConfig::<TestRuntime, pallet_balances::Instance1> {
+		balances: vec![(SENDER_ACCOUNT.into(), SENDER_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		System::set_block_number(1);
+		System::reset_events();
+
+		test()
+	})
+}
@@ -0,0 +1,59 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_bridge_assets_transfer`.
+//!
+//! The pallet is not benchmarked yet, so weights below are rough estimations, based on the
+//! number of storage accesses that are done by the common asset transactor (e.g.
+//! `xcm_builder::CurrencyAdapter`) and XCM router implementations. Every transferred asset is
+//! reserved or withdrawn separately, so the weight has a per-asset component.
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for `pallet_bridge_assets_transfer`.
+pub trait WeightInfo {
+	/// Weight of the `transfer_over_bridge` call, transferring `n` assets.
+	fn transfer_over_bridge(n: u32) -> Weight;
+}
+
+/// Weights for `pallet_bridge_assets_transfer` that are using the runtime database weights.
+pub struct BridgeWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for BridgeWeight<T> {
+	fn transfer_over_bridge(n: u32) -> Weight {
+		Weight::from_parts(75_000_000, 5_000)
+			.saturating_add(Weight::from_parts(25_000_000, 3_000).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads(2_u64.saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64.saturating_mul(n as u64)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn transfer_over_bridge(n: u32) -> Weight {
+		Weight::from_parts(75_000_000, 5_000)
+			.saturating_add(Weight::from_parts(25_000_000, 3_000).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads(2_u64.saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64.saturating_mul(n as u64)))
+	}
+}
@@ -53,12 +53,6 @@ use sp_consensus_grandpa::AuthorityId;
 use sp_runtime::traits::{One, Zero};
 use sp_std::vec::Vec;
 
-/// The maximum number of vote ancestries to include in a justification.
-///
-/// In practice this would be limited by the session length (number of blocks a single authority set
-/// can produce) of a given chain.
-const MAX_VOTE_ANCESTRIES: u32 = 1000;
-
 // `1..MAX_VOTE_ANCESTRIES` is too large && benchmarks are running for almost 40m (steps=50,
 // repeat=20) on a decent laptop, which is too much. Since we're building linear function here,
 // let's just select some limited subrange for benchmarking.
@@ -35,17 +35,19 @@
 #![warn(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use storage_types::StoredAuthoritySet;
+pub use storage_types::{HaltReason, ImportedHashesQueue, StoredAuthoritySet};
 
 use bp_header_chain::{
-	justification::GrandpaJustification, AuthoritySet, ChainWithGrandpa, GrandpaConsensusLogReader,
-	HeaderChain, InitializationData, StoredHeaderData, StoredHeaderDataBuilder,
+	justification::GrandpaJustification, AuthoritySet, ChainWithGrandpa, ConsensusEngine,
+	FinalitySubmissionConstraints, GrandpaConsensusEngine, GrandpaConsensusLogReader, HeaderChain,
+	HeaderChainError, InitializationData, StoredHeaderData, StoredHeaderDataBuilder,
 	StoredHeaderGrandpaInfo,
 };
 use bp_runtime::{BlockNumberOf, HashOf, HasherOf, HeaderId, HeaderOf, OwnedBridgeModule};
+use codec::Encode;
 use frame_support::{dispatch::PostDispatchInfo, ensure, DefaultNoBound};
 use sp_runtime::{
-	traits::{Header as HeaderT, Zero},
+	traits::{Header as HeaderT, Saturating, Zero},
 	SaturatedConversion,
 };
 use sp_std::{boxed::Box, convert::TryInto, prelude::*};
@@ -69,6 +71,21 @@ pub use weights::WeightInfo;
 /// The target that will be used when publishing logs related to this pallet.
 pub const LOG_TARGET: &str = "runtime::bridge-grandpa";
 
+/// The maximum number of vote ancestries that we expect to see in a justification.
+///
+/// In practice this would be limited by the session length (number of blocks a single authority set
+/// can produce) of a given chain. The pallet halts itself if it sees mandatory header justification
+/// that is larger than justification with that number of maximal size headers in its votes
+/// ancestries. Such justifications of other headers are rejected.
+pub const MAX_VOTE_ANCESTRIES: u32 = 1000;
+
+/// Maximal number of old headers that are pruned when new header is imported.
+///
+/// Normally, a single header is pruned on every import. But if the number of headers to keep
+/// has been reduced, we are pruning an extra header on every import until the number of stored
+/// headers goes down to the configured value.
+pub const MAX_PRUNED_HEADERS_PER_IMPORT: u32 = 2;
+
 /// Bridged chain from the pallet configuration.
 pub type BridgedChain<T, I> = <T as Config<I>>::BridgedChain;
 /// Block number of the bridged chain.
@@ -113,13 +130,39 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxFreeMandatoryHeadersPerBlock: Get<u32>;
 
+		/// Maximal number of free header transactions per epoch.
+		///
+		/// This limit is applied in addition to the `MaxFreeMandatoryHeadersPerBlock` limit. So
+		/// if the bridged chain gets compromised, it can't make relayers import its headers
+		/// for free indefinitely. All calls above this limit are accepted, but are not refunded.
+		#[pallet::constant]
+		type MaxFreeHeadersPerEpoch: Get<u32>;
+
+		/// Length of the free headers epoch (in blocks of this chain).
+		///
+		/// The number of free header transactions is reset to the `MaxFreeHeadersPerEpoch`
+		/// at the beginning of every epoch.
+		#[pallet::constant]
+		type FreeHeadersEpochLength: Get<u32>;
+
+		/// If `Some(interval)`, non-mandatory headers that are at least `interval` headers ahead
+		/// of the best finalized header are also imported for free.
+		///
+		/// It allows relayers to keep the bridge reasonably fresh without paying for that, when
+		/// there are no mandatory headers for a long time. Those imports are subject to the same
+		/// free transaction limits as the mandatory headers import.
+		#[pallet::constant]
+		type FreeHeadersInterval: Get<Option<u32>>;
+
 		/// Maximal number of finalized headers to keep in the storage.
 		///
 		/// The setting is there to prevent growing the on-chain state indefinitely. Note
 		/// the setting does not relate to block numbers - we will simply keep as much items
 		/// in the storage, so it doesn't guarantee any fixed timeframe for finality headers.
 		///
-		/// Incautious change of this constant may lead to orphan entries in the runtime storage.
+		/// The pallet owner may lower the number of kept headers using the `set_headers_to_keep`
+		/// call. When the number is lowered, old headers are pruned gradually - see
+		/// `MAX_PRUNED_HEADERS_PER_IMPORT`.
 		#[pallet::constant]
 		type HeadersToKeep: Get<u32>;
 
@@ -167,9 +210,11 @@ pub mod pallet {
 		///
 		/// - verification is not optimized or invalid;
 		///
+		/// - justification of non-mandatory header is larger than the pallet supports;
+		///
 		/// - header contains forced authorities set change or change with non-zero delay.
 		#[pallet::call_index(0)]
-		#[pallet::weight(<T::WeightInfo as WeightInfo>::submit_finality_proof(
+		#[pallet::weight(submit_finality_proof_weight::<T, I>(
 			justification.commit.precommits.len().saturated_into(),
 			justification.votes_ancestries.len().saturated_into(),
 		))]
@@ -179,7 +224,7 @@ pub mod pallet {
 			justification: GrandpaJustification<BridgedHeader<T, I>>,
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
-			ensure_signed(origin)?;
+			let relayer = ensure_signed(origin)?;
 
 			let (hash, number) = (finality_target.hash(), *finality_target.number());
 			log::trace!(
@@ -189,6 +234,7 @@ pub mod pallet {
 			);
 
 			SubmitFinalityProofHelper::<T, I>::check_obsolete(number)?;
+			let best_finalized_number = <BestFinalized<T, I>>::get().map(|id| id.number());
 
 			let authority_set = <CurrentAuthoritySet<T, I>>::get();
 			let unused_proof_size = authority_set.unused_proof_size();
@@ -196,11 +242,47 @@ pub mod pallet {
 			let authority_set: AuthoritySet = authority_set.into();
 			verify_justification::<T, I>(&justification, hash, number, authority_set)?;
 
+			// the justification is valid, so the bridged chain has actually produced it. But if
+			// the header is not mandatory, the relayer may simply skip it and submit some other
+			// header. Otherwise we can't support the bridged chain anymore, so let's halt the
+			// pallet and let the governance decide what to do next
+			let justification_size: u32 = justification.encoded_size().saturated_into();
+			let max_justification_size = max_justification_size::<T, I>();
+			if justification_size > max_justification_size {
+				let is_mandatory_header =
+					GrandpaConsensusLogReader::<BridgedBlockNumber<T, I>>::find_scheduled_change(
+						finality_target.digest(),
+					)
+					.is_some();
+				ensure!(is_mandatory_header, <Error<T, I>>::JustificationTooLarge);
+
+				return halt::<T, I>(
+					hash,
+					HaltReason::JustificationTooLarge {
+						size: justification_size,
+						max_size: max_justification_size,
+					},
+				)
+			}
+
 			let maybe_new_authority_set =
-				try_enact_authority_change::<T, I>(&finality_target, set_id)?;
-			let may_refund_call_fee = maybe_new_authority_set.is_some() &&
+				match try_enact_authority_change::<T, I>(&finality_target, set_id)? {
+					AuthoritySetChange::None => None,
+					AuthoritySetChange::Enacted(new_authority_set) => Some(new_authority_set),
+					AuthoritySetChange::TooManyAuthorities(authorities) =>
+						return halt::<T, I>(
+							hash,
+							HaltReason::TooManyAuthoritiesInSet { set_id: set_id + 1, authorities },
+						),
+				};
+			let is_mandatory = maybe_new_authority_set.is_some();
+			let is_free_header =
+				is_mandatory || is_free_non_mandatory_header::<T, I>(number, best_finalized_number);
+			let may_refund_call_fee = is_free_header &&
 				// if we have seen too many mandatory headers in this block, we don't want to refund
 				Self::free_mandatory_headers_remaining() > 0 &&
+				// if we have seen too many free headers in this epoch, we don't want to refund
+				Self::free_headers_remaining_in_epoch() > 0 &&
 				// if arguments out of expected bounds, we don't want to refund
 				submit_finality_proof_info_from_args::<T, I>(&finality_target, &justification)
 					.fits_limits();
@@ -208,6 +290,9 @@ pub mod pallet {
 				FreeMandatoryHeadersRemaining::<T, I>::mutate(|count| {
 					*count = count.saturating_sub(1)
 				});
+				let remaining_in_epoch = Self::free_headers_remaining_in_epoch().saturating_sub(1);
+				FreeHeadersEpochQuota::<T, I>::put((Self::current_epoch(), remaining_in_epoch));
+				Self::deposit_event(Event::FreeHeaderImported { hash, remaining_in_epoch });
 			}
 			insert_header::<T, I>(*finality_target, hash);
 			log::info!(
@@ -230,7 +315,7 @@ pub mod pallet {
 			// `MaxBridgedAuthorities` in the `CurrentAuthoritySet` (we use `MaxEncodedLen`
 			// estimation). But if their number is lower, then we may "refund" some `proof_size`,
 			// making proof smaller and leaving block space to other useful transactions
-			let pre_dispatch_weight = T::WeightInfo::submit_finality_proof(
+			let pre_dispatch_weight = submit_finality_proof_weight::<T, I>(
 				justification.commit.precommits.len().saturated_into(),
 				justification.votes_ancestries.len().saturated_into(),
 			);
@@ -240,6 +325,10 @@ pub mod pallet {
 			Self::deposit_event(Event::UpdatedBestFinalizedHeader {
 				number,
 				hash,
+				set_id,
+				is_mandatory,
+				justification_size,
+				relayer,
 				grandpa_info: StoredHeaderGrandpaInfo {
 					finality_proof: justification,
 					new_verification_context: maybe_new_authority_set,
@@ -258,6 +347,9 @@ pub mod pallet {
 		/// This function is only allowed to be called from a trusted origin and writes to storage
 		/// with practically no checks in terms of the validity of the data. It is important that
 		/// you ensure that valid data is being passed in.
+		///
+		/// The already initialized pallet may only be re-initialized if it is halted. This may
+		/// be used to recover the bridge that has missed authorities set change.
 		#[pallet::call_index(1)]
 		#[pallet::weight((T::DbWeight::get().reads_writes(2, 5), DispatchClass::Operational))]
 		pub fn initialize(
@@ -266,7 +358,8 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_owner_or_root(origin)?;
 
-			let init_allowed = !<BestFinalized<T, I>>::exists();
+			let init_allowed = !<BestFinalized<T, I>>::exists() ||
+				<PalletOperatingMode<T, I>>::get() == BasicOperatingMode::Halted;
 			ensure!(init_allowed, <Error<T, I>>::AlreadyInitialized);
 			initialize_bridge::<T, I>(init_data.clone())?;
 
@@ -290,14 +383,86 @@ pub mod pallet {
 
 		/// Halt or resume all pallet operations.
 		///
+		/// Resuming pallet operations also clears the `PalletHaltReason`.
+		///
 		/// May only be called either by root, or by `PalletOwner`.
 		#[pallet::call_index(3)]
-		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
 		pub fn set_operating_mode(
 			origin: OriginFor<T>,
 			operating_mode: BasicOperatingMode,
 		) -> DispatchResult {
-			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)
+			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)?;
+			if operating_mode == BasicOperatingMode::Normal {
+				<PalletHaltReason<T, I>>::kill();
+			}
+			Ok(())
+		}
+
+		/// Change number of finalized headers to keep in the storage.
+		///
+		/// The number can't be zero or larger than the `HeadersToKeep` constant. If `None` is
+		/// passed, the `HeadersToKeep` headers are kept.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(4)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_headers_to_keep(
+			origin: OriginFor<T>,
+			headers_to_keep: Option<u32>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+
+			if let Some(headers_to_keep) = headers_to_keep {
+				ensure!(
+					headers_to_keep != 0 && headers_to_keep <= T::HeadersToKeep::get(),
+					Error::<T, I>::InvalidHeadersToKeep,
+				);
+			}
+			HeadersToKeepOverride::<T, I>::set(headers_to_keep);
+
+			log::info!(
+				target: LOG_TARGET,
+				"Number of headers to keep has been changed to {:?}",
+				headers_to_keep,
+			);
+
+			Ok(())
+		}
+
+		/// Replace the current GRANDPA authority set with given set.
+		///
+		/// Unlike the `initialize` call, this call keeps all imported headers. So it may be used
+		/// to recover the bridge that has missed authorities set change (e.g. because of the
+		/// forced change at the bridged chain), without breaking other pallets that are
+		/// using imported headers.
+		///
+		/// This function is only allowed to be called from a trusted origin and writes to storage
+		/// with no checks. It is important that you ensure that valid data is being passed in.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(5)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn force_set_authorities(
+			origin: OriginFor<T>,
+			authority_list: sp_consensus_grandpa::AuthorityList,
+			set_id: sp_consensus_grandpa::SetId,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(<BestFinalized<T, I>>::exists(), <Error<T, I>>::NotInitialized);
+
+			let authority_set = StoredAuthoritySet::<T, I>::try_new(authority_list, set_id)?;
+			<CurrentAuthoritySet<T, I>>::put(&authority_set);
+
+			log::info!(
+				target: LOG_TARGET,
+				"Authority set has been forcibly changed to {:?}",
+				authority_set,
+			);
+
+			Self::deposit_event(Event::AuthoritySetForced { set_id });
+
+			Ok(())
 		}
 	}
 
@@ -316,6 +481,15 @@ pub mod pallet {
 	pub(super) type FreeMandatoryHeadersRemaining<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, u32, ValueQuery>;
 
+	/// Index of the free headers epoch and the number of free header transactions that we may
+	/// accept during this epoch.
+	///
+	/// If the stored epoch is not the current epoch, we may accept `MaxFreeHeadersPerEpoch`
+	/// free header transactions.
+	#[pallet::storage]
+	pub type FreeHeadersEpochQuota<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, (u32, u32), OptionQuery>;
+
 	/// Hash of the header used to bootstrap the pallet.
 	#[pallet::storage]
 	pub(super) type InitialHash<T: Config<I>, I: 'static = ()> =
@@ -327,7 +501,9 @@ pub mod pallet {
 	pub type BestFinalized<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, BridgedBlockId<T, I>, OptionQuery>;
 
-	/// A ring buffer of imported hashes. Ordered by the insertion time.
+	/// A queue of imported hashes. Ordered by the insertion time.
+	///
+	/// The state of the queue is stored in the `ImportedHashesState`.
 	#[pallet::storage]
 	pub(super) type ImportedHashes<T: Config<I>, I: 'static = ()> = StorageMap<
 		Hasher = Identity,
@@ -338,11 +514,28 @@ pub mod pallet {
 		MaxValues = MaybeHeadersToKeep<T, I>,
 	>;
 
-	/// Current ring buffer position.
+	/// Current position of the legacy ring buffer of imported hashes.
+	///
+	/// It is only used to migrate the legacy ring buffer to the `ImportedHashesState` and is
+	/// removed by the migration.
 	#[pallet::storage]
 	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, u32, ValueQuery>;
 
+	/// State of the `ImportedHashes` queue.
+	///
+	/// If it is `None` and the pallet is initialized, the `ImportedHashes` is in the legacy ring
+	/// buffer format. It is migrated on the next header import.
+	#[pallet::storage]
+	pub(super) type ImportedHashesState<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, ImportedHashesQueue, OptionQuery>;
+
+	/// Number of finalized headers to keep in the storage, set by the `set_headers_to_keep`
+	/// call. If it is `None`, the `HeadersToKeep` headers are kept.
+	#[pallet::storage]
+	pub type HeadersToKeepOverride<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, OptionQuery>;
+
 	/// Relevant fields of imported headers.
 	#[pallet::storage]
 	pub type ImportedHeaders<T: Config<I>, I: 'static = ()> = StorageMap<
@@ -376,6 +569,14 @@ pub mod pallet {
 	pub type PalletOperatingMode<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, BasicOperatingMode, ValueQuery>;
 
+	/// The reason why the pallet has halted itself.
+	///
+	/// The pallet halts itself when it sees a valid header that it is unable to import. It is
+	/// cleared when pallet operations are resumed or when the pallet is re-initialized.
+	#[pallet::storage]
+	pub type PalletHaltReason<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, HaltReason, OptionQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(DefaultNoBound)]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
@@ -411,9 +612,41 @@ pub mod pallet {
 			number: BridgedBlockNumber<T, I>,
 			/// Hash of the new best finalized header.
 			hash: BridgedBlockHash<T, I>,
+			/// Identifier of the authority set that has signed the header justification.
+			set_id: sp_consensus_grandpa::SetId,
+			/// True if the header is a mandatory header, which enacts new authority set.
+			is_mandatory: bool,
+			/// Size of the encoded header justification.
+			justification_size: u32,
+			/// Account of the relayer that has submitted the header.
+			relayer: T::AccountId,
 			/// The Grandpa info associated to the new best finalized header.
 			grandpa_info: StoredHeaderGrandpaInfo<BridgedHeader<T, I>>,
 		},
+		/// Header has been imported for free, using the free headers quota of the current epoch.
+		FreeHeaderImported {
+			/// Hash of the imported header.
+			hash: BridgedBlockHash<T, I>,
+			/// Number of free header transactions that we may still accept in the current epoch.
+			remaining_in_epoch: u32,
+		},
+		/// Current authority set has been replaced using the `force_set_authorities` call.
+		AuthoritySetForced {
+			/// Identifier of the new authority set.
+			set_id: sp_consensus_grandpa::SetId,
+		},
+		/// The pallet has halted itself, because it is unable to import valid header.
+		PalletHalted {
+			/// Hash of the header that the pallet has failed to import.
+			hash: BridgedBlockHash<T, I>,
+			/// The reason why the header can't be imported.
+			reason: HaltReason,
+		},
+		/// Old finalized header has been pruned from the storage.
+		PrunedHeader {
+			/// Hash of the pruned header.
+			hash: BridgedBlockHash<T, I>,
+		},
 	}
 
 	#[pallet::error]
@@ -436,6 +669,57 @@ pub mod pallet {
 		TooManyAuthoritiesInSet,
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
+		/// The number of headers to keep is zero or larger than `HeadersToKeep`.
+		InvalidHeadersToKeep,
+		/// The justification of non-mandatory header is larger than the pallet supports.
+		JustificationTooLarge,
+	}
+
+	/// Returns weight of the `submit_finality_proof` call, including weight of pruning
+	/// extra headers.
+	pub(crate) fn submit_finality_proof_weight<T: Config<I>, I: 'static>(
+		precommits_len: u32,
+		votes_ancestries_len: u32,
+	) -> Weight {
+		// benchmarks are only covering pruning of a single header
+		let extra_pruned_headers = (MAX_PRUNED_HEADERS_PER_IMPORT - 1) as u64;
+		T::WeightInfo::submit_finality_proof(precommits_len, votes_ancestries_len).saturating_add(
+			T::DbWeight::get().reads_writes(extra_pruned_headers, 2 * extra_pruned_headers),
+		)
+	}
+
+	/// Halt the pallet, because it is unable to import the valid header with given hash.
+	///
+	/// The call that has submitted the header succeeds, so that the change is persisted, but
+	/// the submitter pays for the transaction.
+	pub(crate) fn halt<T: Config<I>, I: 'static>(
+		hash: BridgedBlockHash<T, I>,
+		reason: HaltReason,
+	) -> DispatchResultWithPostInfo {
+		log::error!(
+			target: LOG_TARGET,
+			"Unable to import header {:?}: {:?}. Halting the pallet",
+			hash,
+			reason,
+		);
+
+		<PalletOperatingMode<T, I>>::put(BasicOperatingMode::Halted);
+		<PalletHaltReason<T, I>>::put(reason);
+		Pallet::<T, I>::deposit_event(Event::PalletHalted { hash, reason });
+
+		Ok(PostDispatchInfo { actual_weight: None, pays_fee: Pays::Yes })
+	}
+
+	/// Result of the `try_enact_authority_change` call.
+	#[derive(RuntimeDebug)]
+	pub(crate) enum AuthoritySetChange {
+		/// The header doesn't change the authority set.
+		None,
+		/// The new authority set has been enacted.
+		Enacted(AuthoritySet),
+		/// The header schedules the authority set with given number of authorities, which is
+		/// more than the pallet supports. The change has not been enacted.
+		TooManyAuthorities(u32),
 	}
 
 	/// Check the given header for a GRANDPA scheduled authority set change. If a change
@@ -444,11 +728,13 @@ pub mod pallet {
 	/// This function does not support forced changes, or scheduled changes with delays
 	/// since these types of changes are indicative of abnormal behavior from GRANDPA.
 	///
-	/// Returned value will indicate if a change was enacted or not.
+	/// Returned value will indicate if a change was enacted or not. If the new set has more
+	/// authorities than the pallet supports, the change is not enacted and the number of
+	/// authorities in the new set is returned.
 	pub(crate) fn try_enact_authority_change<T: Config<I>, I: 'static>(
 		header: &BridgedHeader<T, I>,
 		current_set_id: sp_consensus_grandpa::SetId,
-	) -> Result<Option<AuthoritySet>, DispatchError> {
+	) -> Result<AuthoritySetChange, DispatchError> {
 		// We don't support forced changes - at that point governance intervention is required.
 		ensure!(
 			GrandpaConsensusLogReader::<BridgedBlockNumber<T, I>>::find_forced_change(
@@ -466,12 +752,11 @@ pub mod pallet {
 			ensure!(change.delay == Zero::zero(), <Error<T, I>>::UnsupportedScheduledChange);
 
 			// TODO [#788]: Stop manually increasing the `set_id` here.
-			let next_authorities = StoredAuthoritySet::<T, I> {
-				authorities: change
-					.next_authorities
-					.try_into()
-					.map_err(|_| Error::<T, I>::TooManyAuthoritiesInSet)?,
-				set_id: current_set_id + 1,
+			let authorities_count: u32 = change.next_authorities.len().saturated_into();
+			let next_authorities = match change.next_authorities.try_into() {
+				Ok(authorities) =>
+					StoredAuthoritySet::<T, I> { authorities, set_id: current_set_id + 1 },
+				Err(_) => return Ok(AuthoritySetChange::TooManyAuthorities(authorities_count)),
 			};
 
 			// Since our header schedules a change and we know the delay is 0, it must also enact
@@ -486,10 +771,33 @@ pub mod pallet {
 				next_authorities,
 			);
 
-			return Ok(Some(next_authorities.into()))
+			return Ok(AuthoritySetChange::Enacted(next_authorities.into()))
 		};
 
-		Ok(None)
+		Ok(AuthoritySetChange::None)
+	}
+
+	/// Returns maximal size of justification that the pallet is able to accept.
+	///
+	/// That's the reasonable size of justification, signed by all authorities of the largest
+	/// supported set, plus `MAX_VOTE_ANCESTRIES` headers of maximal size.
+	pub(crate) fn max_justification_size<T: Config<I>, I: 'static>() -> u32 {
+		GrandpaJustification::<BridgedHeader<T, I>>::max_reasonable_size::<T::BridgedChain>(
+			T::BridgedChain::MAX_AUTHORITIES_COUNT,
+		)
+		.saturating_add(MAX_VOTE_ANCESTRIES.saturating_mul(T::BridgedChain::MAX_HEADER_SIZE))
+	}
+
+	/// Returns true if the non-mandatory header with given number may be imported for free.
+	fn is_free_non_mandatory_header<T: Config<I>, I: 'static>(
+		number: BridgedBlockNumber<T, I>,
+		best_finalized_number: Option<BridgedBlockNumber<T, I>>,
+	) -> bool {
+		match (T::FreeHeadersInterval::get(), best_finalized_number) {
+			(Some(interval), Some(best_finalized_number)) =>
+				number.saturating_sub(best_finalized_number) >= interval.into(),
+			_ => false,
+		}
 	}
 
 	/// Verify a GRANDPA justification (finality proof) for a given header.
@@ -504,9 +812,7 @@ pub mod pallet {
 		number: BridgedBlockNumber<T, I>,
 		authority_set: bp_header_chain::AuthoritySet,
 	) -> Result<(), sp_runtime::DispatchError> {
-		use bp_header_chain::justification::verify_justification;
-
-		Ok(verify_justification::<BridgedHeader<T, I>>(
+		Ok(<GrandpaConsensusEngine as ConsensusEngine<BridgedHeader<T, I>>>::verify_finality_proof(
 			(hash, number),
 			&authority_set.try_into().map_err(|_| <Error<T, I>>::InvalidAuthoritySet)?,
 			justification,
@@ -530,18 +836,51 @@ pub mod pallet {
 		header: BridgedHeader<T, I>,
 		hash: BridgedBlockHash<T, I>,
 	) {
-		let index = <ImportedHashesPointer<T, I>>::get();
-		let pruning = <ImportedHashes<T, I>>::try_get(index);
+		let mut queue = imported_hashes_queue::<T, I>();
 		<BestFinalized<T, I>>::put(HeaderId(*header.number(), hash));
 		<ImportedHeaders<T, I>>::insert(hash, header.build());
-		<ImportedHashes<T, I>>::insert(index, hash);
+		<ImportedHashes<T, I>>::insert(queue.push(), hash);
+
+		// Remove oldest headers, if we have more than we want to keep.
+		let headers_to_keep = Pallet::<T, I>::headers_to_keep();
+		for _ in 0..MAX_PRUNED_HEADERS_PER_IMPORT {
+			if queue.len <= headers_to_keep {
+				break
+			}
+
+			if let Some(hash) = queue.pop().and_then(<ImportedHashes<T, I>>::take) {
+				log::debug!(target: LOG_TARGET, "Pruning old header: {:?}.", hash);
+				<ImportedHeaders<T, I>>::remove(hash);
+				Pallet::<T, I>::deposit_event(Event::PrunedHeader { hash });
+			}
+		}
+		<ImportedHashesState<T, I>>::put(queue);
+	}
+
+	/// Returns state of the imported hashes queue, migrating the legacy ring buffer if required.
+	fn imported_hashes_queue<T: Config<I>, I: 'static>() -> ImportedHashesQueue {
+		if let Some(queue) = <ImportedHashesState<T, I>>::get() {
+			return queue
+		}
 
-		// Update ring buffer pointer and remove old header.
-		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
-		if let Ok(hash) = pruning {
-			log::debug!(target: LOG_TARGET, "Pruning old header: {:?}.", hash);
-			<ImportedHeaders<T, I>>::remove(hash);
+		// the legacy ring buffer had its first entry at key `0`. If it is missing, the pallet
+		// has never been initialized
+		if !<ImportedHashes<T, I>>::contains_key(0) {
+			return ImportedHashesQueue::default()
 		}
+
+		let capacity = T::HeadersToKeep::get();
+		let pointer = <ImportedHashesPointer<T, I>>::take();
+		let is_full = <ImportedHashes<T, I>>::contains_key(pointer);
+		let queue = ImportedHashesQueue::from_legacy_ring_buffer(capacity, pointer, is_full);
+		log::info!(
+			target: LOG_TARGET,
+			"Migrated legacy ring buffer of imported hashes (capacity: {}, pointer: {}) to {:?}",
+			capacity,
+			pointer,
+			queue,
+		);
+		queue
 	}
 
 	/// Since this writes to storage with no real checks this should only be used in functions that
@@ -566,12 +905,12 @@ pub mod pallet {
 		let initial_hash = header.hash();
 
 		<InitialHash<T, I>>::put(initial_hash);
-		<ImportedHashesPointer<T, I>>::put(0);
 		insert_header::<T, I>(*header, initial_hash);
 
 		<CurrentAuthoritySet<T, I>>::put(authority_set);
 
 		<PalletOperatingMode<T, I>>::put(operating_mode);
+		<PalletHaltReason<T, I>>::kill();
 
 		Ok(())
 	}
@@ -600,14 +939,70 @@ pub mod pallet {
 
 		// the most obvious way to cause pruning during next insertion would be to insert
 		// `HeadersToKeep` headers. But it'll make our benchmarks slow. So we will just play with
-		// our pruning ring-buffer.
-		assert_eq!(ImportedHashesPointer::<T, I>::get(), 1);
-		ImportedHashesPointer::<T, I>::put(0);
+		// the state of our pruning queue.
+		let mut queue = ImportedHashesState::<T, I>::get().expect("initialized above; qed");
+		assert_eq!(queue.len, 1);
+		queue.len = Pallet::<T, I>::headers_to_keep();
+		ImportedHashesState::<T, I>::put(queue);
 
 		*start_header
 	}
 }
 
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Returns number of free header transactions that we may still accept in the current epoch.
+	pub fn free_headers_remaining_in_epoch() -> u32 {
+		match FreeHeadersEpochQuota::<T, I>::get() {
+			Some((epoch, remaining)) if epoch == Self::current_epoch() => remaining,
+			_ => T::MaxFreeHeadersPerEpoch::get(),
+		}
+	}
+
+	/// Returns index of the current free headers epoch.
+	fn current_epoch() -> u32 {
+		let block_number: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+		block_number / T::FreeHeadersEpochLength::get().max(1)
+	}
+
+	/// Verify that the header with `ancestor` hash is an ancestor of the imported finalized
+	/// header with `descendant` hash.
+	///
+	/// The `ancestry` must contain all headers from the `descendant` (inclusive) to the `ancestor`
+	/// (inclusive), starting with the `descendant` header. Returns data of the `ancestor` header,
+	/// which may be used as an anchor for storage proofs, just like data of imported headers.
+	pub fn verify_ancestry(
+		ancestor: BridgedBlockHash<T, I>,
+		descendant: BridgedBlockHash<T, I>,
+		ancestry: Vec<BridgedHeader<T, I>>,
+	) -> Result<BridgedStoredHeaderData<T, I>, HeaderChainError> {
+		ensure!(ImportedHeaders::<T, I>::contains_key(descendant), HeaderChainError::UnknownHeader);
+		bp_header_chain::verify_ancestry(ancestor, descendant, ancestry)
+			.map(|header| header.build())
+	}
+
+	/// Returns constraints that are applied to submitted finality proofs.
+	pub fn submission_constraints(
+	) -> FinalitySubmissionConstraints<BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>> {
+		FinalitySubmissionConstraints {
+			max_justification_size: max_justification_size::<T, I>(),
+			max_authorities_count: T::BridgedChain::MAX_AUTHORITIES_COUNT,
+			max_free_mandatory_headers_per_block: T::MaxFreeMandatoryHeadersPerBlock::get(),
+			max_free_headers_per_epoch: T::MaxFreeHeadersPerEpoch::get(),
+			free_headers_remaining_in_epoch: Self::free_headers_remaining_in_epoch(),
+			free_headers_interval: T::FreeHeadersInterval::get(),
+			best_finalized: BestFinalized::<T, I>::get(),
+			current_set_id: CurrentAuthoritySet::<T, I>::get().set_id,
+		}
+	}
+
+	/// Returns number of finalized headers to keep in the storage.
+	pub fn headers_to_keep() -> u32 {
+		HeadersToKeepOverride::<T, I>::get()
+			.unwrap_or_else(T::HeadersToKeep::get)
+			.min(T::HeadersToKeep::get())
+	}
+}
+
 impl<T: Config<I>, I: 'static> Pallet<T, I>
 where
 	<T as frame_system::Config>::RuntimeEvent: TryInto<Event<T, I>>,
@@ -844,6 +1239,31 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn halted_pallet_may_be_reinitialized() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				RuntimeOrigin::root(),
+				BasicOperatingMode::Halted
+			));
+			let header = test_header(5);
+			let init_data = InitializationData {
+				header: Box::new(header.clone()),
+				authority_list: authority_list(),
+				set_id: 2,
+				operating_mode: BasicOperatingMode::Normal,
+			};
+			assert_ok!(Pallet::<TestRuntime>::initialize(RuntimeOrigin::root(), init_data));
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get().unwrap().1, header.hash());
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+			assert_eq!(<PalletOperatingMode<TestRuntime>>::get(), BasicOperatingMode::Normal);
+		})
+	}
+
 	#[test]
 	fn init_fails_if_there_are_too_many_authorities_in_the_set() {
 		run_test(|| {
@@ -930,6 +1350,10 @@ mod tests {
 					event: TestEvent::Grandpa(Event::UpdatedBestFinalizedHeader {
 						number: *header.number(),
 						hash: header.hash(),
+						set_id: 1,
+						is_mandatory: false,
+						justification_size: justification.encoded_size() as u32,
+						relayer: 1,
 						grandpa_info: StoredHeaderGrandpaInfo {
 							finality_proof: justification.clone(),
 							new_verification_context: None,
@@ -1069,20 +1493,34 @@ mod tests {
 			// Here
 			assert_eq!(
 				System::events(),
-				vec![EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::Grandpa(Event::UpdatedBestFinalizedHeader {
-						number: *header.number(),
-						hash: header.hash(),
-						grandpa_info: StoredHeaderGrandpaInfo {
-							finality_proof: justification.clone(),
-							new_verification_context: Some(
-								<CurrentAuthoritySet<TestRuntime>>::get().into()
-							),
-						},
-					}),
-					topics: vec![],
-				}],
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Grandpa(Event::FreeHeaderImported {
+							hash: header.hash(),
+							remaining_in_epoch: mock::MaxFreeHeadersPerEpoch::get() - 1,
+						}),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Grandpa(Event::UpdatedBestFinalizedHeader {
+							number: *header.number(),
+							hash: header.hash(),
+							set_id: 1,
+							is_mandatory: true,
+							justification_size: justification.encoded_size() as u32,
+							relayer: 1,
+							grandpa_info: StoredHeaderGrandpaInfo {
+								finality_proof: justification.clone(),
+								new_verification_context: Some(
+									<CurrentAuthoritySet<TestRuntime>>::get().into()
+								),
+							},
+						}),
+						topics: vec![],
+					}
+				],
 			);
 			assert_eq!(
 				Pallet::<TestRuntime>::synced_headers_grandpa_info(),
@@ -1205,7 +1643,7 @@ mod tests {
 	}
 
 	#[test]
-	fn importing_header_rejects_header_with_too_many_authorities() {
+	fn importing_header_with_too_many_authorities_halts_pallet() {
 		run_test(|| {
 			initialize_substrate_bridge();
 
@@ -1217,15 +1655,107 @@ mod tests {
 			// Create a valid justification for the header
 			let justification = make_default_justification(&header);
 
-			// Should not be allowed to import this header
-			assert_err!(
+			// The header is not imported, but the pallet is halted
+			let result = Pallet::<TestRuntime>::submit_finality_proof(
+				RuntimeOrigin::signed(1),
+				Box::new(header.clone()),
+				justification,
+			);
+			assert_ok!(result);
+			assert_eq!(result.unwrap().pays_fee, frame_support::dispatch::Pays::Yes);
+			assert_eq!(<BestFinalized<TestRuntime>>::get().unwrap().1, test_header(0).hash());
+			assert!(!<ImportedHeaders<TestRuntime>>::contains_key(header.hash()));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 1);
+
+			let reason = HaltReason::TooManyAuthoritiesInSet {
+				set_id: 2,
+				authorities: MAX_BRIDGED_AUTHORITIES + 1,
+			};
+			assert_eq!(PalletOperatingMode::<TestRuntime>::get(), BasicOperatingMode::Halted);
+			assert_eq!(PalletHaltReason::<TestRuntime>::get(), Some(reason));
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Grandpa(Event::PalletHalted { hash: header.hash(), reason }),
+					topics: vec![],
+				}],
+			);
+			assert_noop!(
+				submit_finality_proof(3),
+				Error::<TestRuntime>::BridgeModule(bp_runtime::OwnedBridgeModuleError::Halted)
+			);
+
+			// the halt reason is cleared when pallet is resumed
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				RuntimeOrigin::root(),
+				BasicOperatingMode::Normal
+			));
+			assert_eq!(PalletHaltReason::<TestRuntime>::get(), None);
+		});
+	}
+
+	#[test]
+	fn importing_non_mandatory_header_with_too_large_justification_fails() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(2);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				ancestors: 4 * MAX_VOTE_ANCESTRIES,
+				..Default::default()
+			});
+			assert!(
+				justification.encoded_size() as u32 > max_justification_size::<TestRuntime, ()>()
+			);
+
+			assert_noop!(
 				Pallet::<TestRuntime>::submit_finality_proof(
 					RuntimeOrigin::signed(1),
 					Box::new(header),
-					justification
+					justification,
 				),
-				<Error<TestRuntime>>::TooManyAuthoritiesInSet
+				<Error<TestRuntime>>::JustificationTooLarge
+			);
+			assert_eq!(PalletOperatingMode::<TestRuntime>::get(), BasicOperatingMode::Normal);
+		});
+	}
+
+	#[test]
+	fn importing_mandatory_header_with_too_large_justification_halts_pallet() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let mut header = test_header(2);
+			header.digest = change_log(0);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				ancestors: 4 * MAX_VOTE_ANCESTRIES,
+				..Default::default()
+			});
+			let justification_size = justification.encoded_size() as u32;
+			let max_justification_size = max_justification_size::<TestRuntime, ()>();
+			assert!(justification_size > max_justification_size);
+
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				RuntimeOrigin::signed(1),
+				Box::new(header.clone()),
+				justification,
+			));
+			assert_eq!(<BestFinalized<TestRuntime>>::get().unwrap().1, test_header(0).hash());
+			assert_eq!(PalletOperatingMode::<TestRuntime>::get(), BasicOperatingMode::Halted);
+			assert_eq!(
+				PalletHaltReason::<TestRuntime>::get(),
+				Some(HaltReason::JustificationTooLarge {
+					size: justification_size,
+					max_size: max_justification_size,
+				}),
 			);
+
+			// the halt reason is cleared when pallet is re-initialized
+			assert_ok!(init_with_origin(RuntimeOrigin::root()));
+			assert_eq!(PalletHaltReason::<TestRuntime>::get(), None);
 		});
 	}
 
@@ -1364,6 +1894,62 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn rate_limiter_disallows_free_imports_once_epoch_limit_is_hit() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// 2 free headers per block, 5 free headers per epoch
+			for number in 1..=4 {
+				let result = submit_mandatory_finality_proof(number, number.into());
+				assert_eq!(result.expect("call failed").pays_fee, Pays::No);
+				if number % 2 == 0 {
+					next_block();
+				}
+			}
+			let result = submit_mandatory_finality_proof(5, 5);
+			assert_eq!(result.expect("call failed").pays_fee, Pays::No);
+			assert_eq!(Pallet::<TestRuntime>::free_headers_remaining_in_epoch(), 0);
+
+			next_block();
+			let result = submit_mandatory_finality_proof(6, 6);
+			assert_eq!(result.expect("call failed").pays_fee, Pays::Yes);
+
+			// quota is restored when next epoch starts
+			System::set_block_number(mock::FreeHeadersEpochLength::get().into());
+			next_block();
+			assert_eq!(
+				Pallet::<TestRuntime>::free_headers_remaining_in_epoch(),
+				mock::MaxFreeHeadersPerEpoch::get(),
+			);
+			let result = submit_mandatory_finality_proof(7, 7);
+			assert_eq!(result.expect("call failed").pays_fee, Pays::No);
+			assert_eq!(
+				Pallet::<TestRuntime>::free_headers_remaining_in_epoch(),
+				mock::MaxFreeHeadersPerEpoch::get() - 1,
+			);
+		})
+	}
+
+	#[test]
+	fn non_mandatory_headers_are_free_if_free_headers_interval_is_configured() {
+		run_test(|| {
+			mock::FreeHeadersInterval::set(&Some(3));
+			initialize_substrate_bridge();
+
+			let result = submit_finality_proof(2);
+			assert_eq!(result.expect("call failed").pays_fee, Pays::Yes);
+
+			let result = submit_finality_proof(5);
+			assert_eq!(result.expect("call failed").pays_fee, Pays::No);
+			assert!(System::events().iter().any(|event| event.event ==
+				TestEvent::Grandpa(Event::FreeHeaderImported {
+					hash: test_header(5).hash(),
+					remaining_in_epoch: mock::MaxFreeHeadersPerEpoch::get() - 1,
+				})));
+		})
+	}
+
 	#[test]
 	fn should_prune_headers_over_headers_to_keep_parameter() {
 		run_test(|| {
@@ -1390,6 +1976,258 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn pruned_header_event_is_deposited() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let genesis_hash = Pallet::<TestRuntime>::best_finalized().unwrap().hash();
+			for header in 1..=5 {
+				assert_ok!(submit_finality_proof(header));
+				next_block();
+			}
+
+			assert!(!ImportedHeaders::<TestRuntime, ()>::contains_key(genesis_hash));
+			assert!(System::events().iter().any(|event| event.event ==
+				TestEvent::Grandpa(Event::PrunedHeader { hash: genesis_hash })));
+		})
+	}
+
+	#[test]
+	fn set_headers_to_keep_rejects_invalid_values() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(RuntimeOrigin::root(), Some(0)),
+				Error::<TestRuntime>::InvalidHeadersToKeep,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(
+					RuntimeOrigin::root(),
+					Some(mock::HeadersToKeep::get() + 1),
+				),
+				Error::<TestRuntime>::InvalidHeadersToKeep,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(RuntimeOrigin::signed(1), Some(1)),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(RuntimeOrigin::root(), Some(2)));
+			assert_eq!(Pallet::<TestRuntime>::headers_to_keep(), 2);
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(RuntimeOrigin::root(), None));
+			assert_eq!(Pallet::<TestRuntime>::headers_to_keep(), mock::HeadersToKeep::get());
+		})
+	}
+
+	#[test]
+	fn headers_are_pruned_gradually_when_headers_to_keep_is_lowered() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for header in 1..=4 {
+				assert_ok!(submit_finality_proof(header));
+				next_block();
+			}
+			assert_eq!(ImportedHashesState::<TestRuntime>::get().unwrap().len, 5);
+
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(RuntimeOrigin::root(), Some(2)));
+
+			// two headers are pruned on every import
+			assert_ok!(submit_finality_proof(5));
+			next_block();
+			assert_eq!(ImportedHashesState::<TestRuntime>::get().unwrap().len, 4);
+			assert_ok!(submit_finality_proof(6));
+			next_block();
+			assert_eq!(ImportedHashesState::<TestRuntime>::get().unwrap().len, 3);
+			assert_ok!(submit_finality_proof(7));
+			next_block();
+			assert_eq!(ImportedHashesState::<TestRuntime>::get().unwrap().len, 2);
+			assert_eq!(ImportedHeaders::<TestRuntime>::iter().count(), 2);
+			assert!(ImportedHeaders::<TestRuntime>::contains_key(test_header(6).hash()));
+			assert!(ImportedHeaders::<TestRuntime>::contains_key(test_header(7).hash()));
+
+			// and then a single header is pruned on every import
+			assert_ok!(submit_finality_proof(8));
+			assert_eq!(ImportedHashesState::<TestRuntime>::get().unwrap().len, 2);
+			assert!(!ImportedHeaders::<TestRuntime>::contains_key(test_header(6).hash()));
+		})
+	}
+
+	#[test]
+	fn legacy_ring_buffer_is_migrated_on_import() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for header in 1..=4 {
+				assert_ok!(submit_finality_proof(header));
+				next_block();
+			}
+
+			// convert storage to the legacy ring buffer, where the oldest entry is at key 2
+			ImportedHashesState::<TestRuntime>::kill();
+			ImportedHashesPointer::<TestRuntime>::put(2);
+
+			assert_ok!(submit_finality_proof(5));
+
+			assert!(!ImportedHashesPointer::<TestRuntime>::exists());
+			assert_eq!(
+				ImportedHashesState::<TestRuntime>::get(),
+				Some(ImportedHashesQueue { oldest: 3, next: 6, len: 5, legacy_wrap: Some((5, 2)) }),
+			);
+			assert!(!ImportedHeaders::<TestRuntime>::contains_key(test_header(2).hash()));
+			assert!(ImportedHeaders::<TestRuntime>::contains_key(test_header(0).hash()));
+			assert_eq!(ImportedHashes::<TestRuntime>::get(5), Some(test_header(5).hash()));
+		})
+	}
+
+	#[test]
+	fn force_set_authorities_works() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+			let imported_hash = Pallet::<TestRuntime>::best_finalized().unwrap().hash();
+
+			let next_authorities = vec![(ALICE.into(), 1), (BOB.into(), 1)];
+			assert_ok!(Pallet::<TestRuntime>::force_set_authorities(
+				RuntimeOrigin::root(),
+				next_authorities.clone(),
+				42,
+			));
+
+			assert_eq!(
+				<CurrentAuthoritySet<TestRuntime>>::get(),
+				StoredAuthoritySet::<TestRuntime, ()>::try_new(next_authorities, 42).unwrap(),
+			);
+			assert!(<ImportedHeaders<TestRuntime>>::contains_key(imported_hash));
+			assert_eq!(Pallet::<TestRuntime>::best_finalized().unwrap().hash(), imported_hash);
+			assert!(System::events()
+				.iter()
+				.any(|event| event.event ==
+					TestEvent::Grandpa(Event::AuthoritySetForced { set_id: 42 })));
+		})
+	}
+
+	#[test]
+	fn force_set_authorities_rejects_invalid_calls() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::force_set_authorities(
+					RuntimeOrigin::root(),
+					authority_list(),
+					2,
+				),
+				Error::<TestRuntime>::NotInitialized,
+			);
+
+			initialize_substrate_bridge();
+			assert_noop!(
+				Pallet::<TestRuntime>::force_set_authorities(
+					RuntimeOrigin::signed(1),
+					authority_list(),
+					2,
+				),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::force_set_authorities(
+					RuntimeOrigin::root(),
+					vec![(ALICE.into(), 1); MAX_BRIDGED_AUTHORITIES as usize + 1],
+					2,
+				),
+				Error::<TestRuntime>::TooManyAuthoritiesInSet,
+			);
+		})
+	}
+
+	#[test]
+	fn verify_ancestry_works() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(3));
+
+			let descendant = test_header(3);
+			let ancestor = TestHeader::new(
+				2,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			);
+			assert_eq!(*descendant.parent_hash(), ancestor.hash());
+
+			// valid ancestry proof
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_ancestry(
+					ancestor.hash(),
+					descendant.hash(),
+					vec![descendant.clone(), ancestor.clone()],
+				),
+				Ok(ancestor.build()),
+			);
+			// header is an ancestor of itself
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_ancestry(
+					descendant.hash(),
+					descendant.hash(),
+					vec![descendant.clone()],
+				),
+				Ok(descendant.build()),
+			);
+			// ancestry doesn't end with the ancestor
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_ancestry(
+					test_header(1).hash(),
+					descendant.hash(),
+					vec![descendant.clone(), ancestor.clone()],
+				),
+				Err(HeaderChainError::InvalidAncestryProof),
+			);
+			// ancestry has a gap
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_ancestry(
+					test_header(1).hash(),
+					descendant.hash(),
+					vec![descendant.clone(), test_header(1)],
+				),
+				Err(HeaderChainError::InvalidAncestryProof),
+			);
+			// empty ancestry
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_ancestry(ancestor.hash(), descendant.hash(), vec![]),
+				Err(HeaderChainError::InvalidAncestryProof),
+			);
+			// descendant is not imported
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_ancestry(
+					descendant.hash(),
+					test_header(4).hash(),
+					vec![test_header(4), descendant],
+				),
+				Err(HeaderChainError::UnknownHeader),
+			);
+		})
+	}
+
+	#[test]
+	fn submission_constraints_works() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::submission_constraints(),
+				FinalitySubmissionConstraints {
+					max_justification_size: max_justification_size::<TestRuntime, ()>(),
+					max_authorities_count: MAX_BRIDGED_AUTHORITIES,
+					max_free_mandatory_headers_per_block:
+						mock::MaxFreeMandatoryHeadersPerBlock::get(),
+					max_free_headers_per_epoch: mock::MaxFreeHeadersPerEpoch::get(),
+					free_headers_remaining_in_epoch: mock::MaxFreeHeadersPerEpoch::get(),
+					free_headers_interval: None,
+					best_finalized: Some(HeaderId(1, test_header(1).hash())),
+					current_set_id: 1,
+				},
+			);
+		})
+	}
+
 	#[test]
 	fn storage_keys_computed_properly() {
 		assert_eq!(
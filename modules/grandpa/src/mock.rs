@@ -84,6 +84,9 @@ impl frame_system::Config for TestRuntime {
 
 parameter_types! {
 	pub const MaxFreeMandatoryHeadersPerBlock: u32 = 2;
+	pub const MaxFreeHeadersPerEpoch: u32 = 5;
+	pub const FreeHeadersEpochLength: u32 = 10;
+	pub storage FreeHeadersInterval: Option<u32> = None;
 	pub const HeadersToKeep: u32 = 5;
 	pub const SessionLength: u64 = 5;
 	pub const NumValidators: u32 = 5;
@@ -93,6 +96,9 @@ impl grandpa::Config for TestRuntime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = TestBridgedChain;
 	type MaxFreeMandatoryHeadersPerBlock = MaxFreeMandatoryHeadersPerBlock;
+	type MaxFreeHeadersPerEpoch = MaxFreeHeadersPerEpoch;
+	type FreeHeadersEpochLength = FreeHeadersEpochLength;
+	type FreeHeadersInterval = FreeHeadersInterval;
 	type HeadersToKeep = HeadersToKeep;
 	type WeightInfo = ();
 }
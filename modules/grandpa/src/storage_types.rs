@@ -20,7 +20,7 @@ use crate::{Config, Error};
 
 use bp_header_chain::{AuthoritySet, ChainWithGrandpa};
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{traits::Get, BoundedVec, CloneNoBound, RuntimeDebugNoBound};
+use frame_support::{traits::Get, BoundedVec, CloneNoBound, RuntimeDebug, RuntimeDebugNoBound};
 use scale_info::TypeInfo;
 use sp_consensus_grandpa::{AuthorityId, AuthorityList, AuthorityWeight, SetId};
 use sp_std::marker::PhantomData;
@@ -97,6 +97,92 @@ impl<T: Config<I>, I: 'static> From<StoredAuthoritySet<T, I>> for AuthoritySet {
 	}
 }
 
+/// Reason why the pallet has halted itself.
+#[derive(Clone, Copy, Decode, Encode, Eq, PartialEq, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+pub enum HaltReason {
+	/// The header has enacted authority set with more authorities than the pallet supports.
+	TooManyAuthoritiesInSet {
+		/// Identifier of the unsupported authority set.
+		set_id: SetId,
+		/// Number of authorities in the unsupported set.
+		authorities: u32,
+	},
+	/// The header justification is larger than the pallet supports.
+	JustificationTooLarge {
+		/// Size of the encoded justification.
+		size: u32,
+		/// Maximal supported size of the encoded justification.
+		max_size: u32,
+	},
+}
+
+/// State of the queue of imported header hashes (the `ImportedHashes` map).
+///
+/// Hashes are stored in the insertion order, under consecutive keys. The only exception is the
+/// queue, migrated from the legacy ring buffer, where keys wrap at the ring buffer capacity.
+#[derive(
+	Clone, Copy, Decode, Default, Encode, Eq, PartialEq, TypeInfo, MaxEncodedLen, RuntimeDebug,
+)]
+pub struct ImportedHashesQueue {
+	/// Key of the oldest hash in the queue.
+	pub oldest: u32,
+	/// Key that will be used to store the next imported hash.
+	pub next: u32,
+	/// Number of hashes in the queue.
+	pub len: u32,
+	/// If `Some((capacity, pointer))`, the queue contains hashes from the legacy ring buffer
+	/// with given capacity and given pointer. The hash with key `capacity - 1` is followed by the
+	/// hash with key `0` and the hash with key `pointer - 1` is followed by the hash with key
+	/// `capacity`.
+	pub legacy_wrap: Option<(u32, u32)>,
+}
+
+impl ImportedHashesQueue {
+	/// Create queue from the legacy ring buffer state.
+	///
+	/// The `is_full` must be true if the ring buffer has been filled at least once (i.e. if it
+	/// has an entry with `pointer` key).
+	pub fn from_legacy_ring_buffer(capacity: u32, pointer: u32, is_full: bool) -> Self {
+		if !is_full {
+			return ImportedHashesQueue { oldest: 0, next: pointer, len: pointer, legacy_wrap: None }
+		}
+
+		ImportedHashesQueue {
+			oldest: pointer,
+			next: capacity,
+			len: capacity,
+			legacy_wrap: if pointer == 0 { None } else { Some((capacity, pointer)) },
+		}
+	}
+
+	/// Insert new hash into the queue. Returns key that shall be used to store the hash.
+	pub fn push(&mut self) -> u32 {
+		let key = self.next;
+		self.next = self.next.wrapping_add(1);
+		self.len = self.len.saturating_add(1);
+		key
+	}
+
+	/// Remove the oldest hash from the queue. Returns key of the removed hash.
+	pub fn pop(&mut self) -> Option<u32> {
+		if self.len == 0 {
+			return None
+		}
+
+		let key = self.oldest;
+		self.oldest = match self.legacy_wrap {
+			Some((capacity, _)) if key.wrapping_add(1) == capacity => 0,
+			Some((capacity, pointer)) if key.wrapping_add(1) == pointer => {
+				self.legacy_wrap = None;
+				capacity
+			},
+			_ => key.wrapping_add(1),
+		};
+		self.len -= 1;
+		Some(key)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::mock::{TestRuntime, MAX_BRIDGED_AUTHORITIES};
@@ -133,4 +219,44 @@ mod tests {
 		// and we can't have more than `MaxBridgedAuthorities` authorities in the bounded vec, so
 		// no test for this case
 	}
+
+	#[test]
+	fn imported_hashes_queue_works() {
+		let mut queue = ImportedHashesQueue::default();
+		assert_eq!(queue.pop(), None);
+		assert_eq!(queue.push(), 0);
+		assert_eq!(queue.push(), 1);
+		assert_eq!(queue.pop(), Some(0));
+		assert_eq!(queue.pop(), Some(1));
+		assert_eq!(queue.pop(), None);
+		assert_eq!(queue.push(), 2);
+	}
+
+	#[test]
+	fn imported_hashes_queue_is_migrated_from_partially_filled_ring_buffer() {
+		let mut queue = ImportedHashesQueue::from_legacy_ring_buffer(5, 3, false);
+		assert_eq!(queue.push(), 3);
+		assert_eq!((0..4).map(|_| queue.pop().unwrap()).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+		assert_eq!(queue.pop(), None);
+	}
+
+	#[test]
+	fn imported_hashes_queue_is_migrated_from_full_ring_buffer() {
+		let mut queue = ImportedHashesQueue::from_legacy_ring_buffer(5, 0, true);
+		assert_eq!(queue.push(), 5);
+		assert_eq!(
+			(0..6).map(|_| queue.pop().unwrap()).collect::<Vec<_>>(),
+			vec![0, 1, 2, 3, 4, 5]
+		);
+
+		let mut queue = ImportedHashesQueue::from_legacy_ring_buffer(5, 3, true);
+		assert_eq!(queue.push(), 5);
+		assert_eq!(queue.push(), 6);
+		assert_eq!(
+			(0..7).map(|_| queue.pop().unwrap()).collect::<Vec<_>>(),
+			vec![3, 4, 0, 1, 2, 5, 6],
+		);
+		assert_eq!(queue.legacy_wrap, None);
+		assert_eq!(queue.pop(), None);
+	}
 }
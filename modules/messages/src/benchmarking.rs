@@ -318,7 +318,7 @@ mod benchmarks {
 	// * message requires all heavy checks done by dispatcher.
 	#[benchmark]
 	fn receive_single_n_bytes_message_proof(
-		/// Proof size in KB
+		/// Proof size in bytes
 		n: Linear<1, { 16 * 1024 }>,
 	) {
 		// setup code
@@ -518,7 +518,7 @@ mod benchmarks {
 	// #[benchmark(extra)]
 	#[benchmark]
 	fn receive_single_n_bytes_message_proof_with_dispatch(
-		/// Proof size in KB
+		/// Proof size in bytes
 		n: Linear<1, { 16 * 1024 }>,
 	) {
 		// setup code
@@ -16,8 +16,8 @@
 
 use crate::{
 	BridgedChainOf, Config, InboundLane, InboundLaneStorage, InboundLanes, OutboundLane,
-	OutboundLaneStorage, OutboundLanes, OutboundMessages, StoredInboundLaneData,
-	StoredMessagePayload,
+	OutboundLaneStorage, OutboundLanes, OutboundMessages, OutboundMessagesSentAt,
+	StoredInboundLaneData, StoredMessagePayload,
 };
 
 use bp_messages::{
@@ -257,7 +257,9 @@ impl<T: Config<I>, I: 'static> OutboundLaneStorage for RuntimeOutboundLaneStorag
 	}
 
 	fn remove_message(&mut self, nonce: &MessageNonce) {
-		OutboundMessages::<T, I>::remove(MessageKey { lane_id: self.lane_id, nonce: *nonce });
+		let key = MessageKey { lane_id: self.lane_id, nonce: *nonce };
+		OutboundMessages::<T, I>::remove(&key);
+		OutboundMessagesSentAt::<T, I>::remove(&key);
 	}
 
 	fn purge(self) {
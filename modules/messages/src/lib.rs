@@ -53,21 +53,28 @@ pub use weights_ext::{
 use bp_header_chain::HeaderChain;
 use bp_messages::{
 	source_chain::{
-		DeliveryConfirmationPayments, FromBridgedChainMessagesDeliveryProof, SendMessageArtifacts,
+		DeliveryConfirmationPayments, FromBridgedChainMessagesDeliveryProof, OnLaneCongestion,
+		OnMessagesExpired, SendMessageArtifacts,
 	},
 	target_chain::{
 		DeliveryPayments, DispatchMessage, FromBridgedChainMessagesProof, MessageDispatch,
 		ProvedLaneMessages, ProvedMessages,
 	},
 	ChainWithMessages, DeliveredMessages, InboundLaneData, InboundMessageDetails, LaneId,
-	MessageKey, MessageNonce, MessagePayload, MessagesOperatingMode, OutboundLaneData,
+	LaneState, MessageKey, MessageNonce, MessagePayload, MessagesOperatingMode, OutboundLaneData,
 	OutboundMessageDetails, UnrewardedRelayersState, VerificationError,
 };
 use bp_runtime::{
-	AccountIdOf, BasicOperatingMode, HashOf, OwnedBridgeModule, PreComputedSize, Size,
+	AccountIdOf, BasicOperatingMode, HashOf, OperatingMode, OwnedBridgeModule, PreComputedSize,
+	RangeInclusiveExt, Size,
 };
 use codec::{Decode, Encode};
-use frame_support::{dispatch::PostDispatchInfo, ensure, fail, traits::Get, DefaultNoBound};
+use frame_support::{
+	dispatch::PostDispatchInfo, ensure, fail, traits::Get, weights::Weight, DefaultNoBound,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_core::{hashing::blake2_256, H256};
+use sp_runtime::traits::Saturating;
 use sp_std::{marker::PhantomData, prelude::*};
 
 mod inbound_lane;
@@ -93,7 +100,6 @@ pub const LOG_TARGET: &str = "runtime::bridge-messages";
 pub mod pallet {
 	use super::*;
 	use bp_messages::{ReceivalResult, ReceivedMessages};
-	use bp_runtime::RangeInclusiveExt;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
@@ -127,6 +133,15 @@ pub mod pallet {
 
 		/// Message dispatch handler.
 		type MessageDispatch: MessageDispatch<DispatchPayload = Self::InboundPayload>;
+		/// Handler for outbound messages that have expired before being delivered.
+		type OnMessagesExpired: OnMessagesExpired;
+		/// Handler for outbound lane congestion signals.
+		type OnLaneCongestion: OnLaneCongestion;
+
+		/// Maximal number of relayers that may be allowed to deliver messages over the single
+		/// restricted lane.
+		#[pallet::constant]
+		type MaxAllowedRelayersPerLane: Get<u32>;
 	}
 
 	/// Shortcut to this chain type for Config.
@@ -146,6 +161,17 @@ pub mod pallet {
 		type OperatingModeStorage = PalletOperatingMode<T, I>;
 	}
 
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let used_weight = prune_delivered_messages::<T, I>(remaining_weight);
+			used_weight.saturating_add(expire_outbound_messages::<T, I>(
+				now,
+				remaining_weight.saturating_sub(used_weight),
+			))
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		/// Change `PalletOwner`.
@@ -169,6 +195,279 @@ pub mod pallet {
 			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)
 		}
 
+		/// Open new lane with given id.
+		///
+		/// Both inbound and outbound ends of the lane are created in the `Opened` state. The call
+		/// fails if any end of the lane already exists.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(4)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2), DispatchClass::Operational))]
+		pub fn open_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+
+			let lanes_manager = LanesManager::<T, I>::new();
+			lanes_manager
+				.create_inbound_lane(lane_id)
+				.map_err(Error::<T, I>::LanesManager)?;
+			lanes_manager
+				.create_outbound_lane(lane_id)
+				.map_err(Error::<T, I>::LanesManager)?;
+
+			log::info!(target: LOG_TARGET, "Opened lane {:?}", lane_id);
+			Self::deposit_event(Event::LaneOpened { lane_id });
+
+			Ok(())
+		}
+
+		/// Close the lane with given id.
+		///
+		/// Both ends of the lane are switched to the `Closed` state. New messages can't be sent
+		/// over the closed lane and messages from the bridged chain are no longer accepted.
+		/// However, delivery confirmations for already sent messages and outbound lane state
+		/// updates from the bridged chain are still accepted. Once the last sent message is
+		/// confirmed and the bridged chain has confirmed all messages, received over this lane,
+		/// the lane state is purged from the storage by the `on_idle` hook.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(5)]
+		#[pallet::weight((
+			T::DbWeight::get().reads_writes(3, 2).saturating_add(purge_lane_weight::<T, I>()),
+			DispatchClass::Operational,
+		))]
+		pub fn close_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+
+			let lanes_manager = LanesManager::<T, I>::new();
+			let mut inbound_lane = lanes_manager
+				.active_inbound_lane(lane_id)
+				.map_err(Error::<T, I>::LanesManager)?;
+			let mut outbound_lane = lanes_manager
+				.active_outbound_lane(lane_id)
+				.map_err(Error::<T, I>::LanesManager)?;
+			inbound_lane.set_state(LaneState::Closed);
+			outbound_lane.set_state(LaneState::Closed);
+
+			log::info!(target: LOG_TARGET, "Closed lane {:?}", lane_id);
+			Self::deposit_event(Event::LaneClosed { lane_id });
+
+			if is_closed_lane_drained::<T, I>(lane_id, &outbound_lane) {
+				purge_lane::<T, I>(lane_id, outbound_lane);
+			}
+
+			Ok(())
+		}
+
+		/// Set operating mode of the single lane.
+		///
+		/// The lane operating mode may only restrict the pallet operating mode. E.g. if the
+		/// pallet is halted, all lanes are halted too. If `None` is passed, the lane operating
+		/// mode is reset and the lane follows the pallet operating mode.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(6)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_lane_operating_mode(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			operating_mode: Option<MessagesOperatingMode>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				OutboundLanes::<T, I>::contains_key(lane_id),
+				Error::<T, I>::LanesManager(LanesManagerError::UnknownOutboundLane)
+			);
+
+			LaneOperatingModes::<T, I>::set(lane_id, operating_mode);
+			log::info!(
+				target: LOG_TARGET,
+				"Setting lane {:?} operating mode to {:?}",
+				lane_id,
+				operating_mode,
+			);
+			Self::deposit_event(Event::LaneOperatingModeChanged { lane_id, operating_mode });
+
+			Ok(())
+		}
+
+		/// Set time-to-live of messages, sent over the outbound lane.
+		///
+		/// Messages that are not delivered within `ttl` blocks after they have been sent, are
+		/// pruned and the `OnMessagesExpired` handler is called for every pruned message. Only
+		/// messages that are sent after the TTL is configured may expire. If `None` is passed,
+		/// messages never expire.
+		///
+		/// Removing the TTL doesn't remove blocks at which already queued messages have been
+		/// sent. They are removed together with messages, when delivery is confirmed. So if the
+		/// TTL is configured again, those messages may expire too.
+		///
+		/// Messages only expire after the lane is closed. The bridged chain accepts messages in
+		/// order, so pruning undelivered message of the opened lane would make all following
+		/// messages undeliverable.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(7)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_lane_messages_ttl(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			ttl: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				OutboundLanes::<T, I>::contains_key(lane_id),
+				Error::<T, I>::LanesManager(LanesManagerError::UnknownOutboundLane)
+			);
+
+			LaneMessagesTtl::<T, I>::set(lane_id, ttl);
+			log::info!(target: LOG_TARGET, "Setting lane {:?} messages TTL to {:?}", lane_id, ttl);
+
+			Ok(())
+		}
+
+		/// Set congestion threshold of the lane.
+		///
+		/// When the number of queued messages at the lane reaches the threshold, the lane is
+		/// considered congested and the `OnLaneCongestion` handler is notified. When the number
+		/// of queued messages drops below the threshold, the handler is notified again. If `None`
+		/// is passed, the lane is never considered congested.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(8)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2), DispatchClass::Operational))]
+		pub fn set_lane_congestion_threshold(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			threshold: Option<MessageNonce>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			let lane = any_state_outbound_lane::<T, I>(lane_id)?;
+
+			LaneCongestionThresholds::<T, I>::set(lane_id, threshold);
+			log::info!(
+				target: LOG_TARGET,
+				"Setting lane {:?} congestion threshold to {:?}",
+				lane_id,
+				threshold,
+			);
+			update_lane_congestion::<T, I>(lane_id, lane.queued_messages().saturating_len());
+
+			Ok(())
+		}
+
+		/// Set maximal size of messages that may be sent over the lane.
+		///
+		/// The limit may not exceed the maximal size of messages that are accepted by the bridged
+		/// chain. If `None` is passed, the lane uses that default limit.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(9)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_lane_max_message_size(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			max_message_size: Option<u32>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				OutboundLanes::<T, I>::contains_key(lane_id),
+				Error::<T, I>::LanesManager(LanesManagerError::UnknownOutboundLane)
+			);
+			ensure!(
+				max_message_size.map_or(true, |max_message_size| max_message_size <=
+					T::BridgedChain::maximal_incoming_message_size()),
+				Error::<T, I>::InvalidMaxMessageSize
+			);
+
+			LaneMaxMessageSizes::<T, I>::set(lane_id, max_message_size);
+			log::info!(
+				target: LOG_TARGET,
+				"Setting lane {:?} maximal message size to {:?}",
+				lane_id,
+				max_message_size,
+			);
+
+			Ok(())
+		}
+
+		/// Restrict delivery of messages over given inbound lane to allowed relayers or make it
+		/// permissionless again.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(10)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_lane_relayers_restricted(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			restricted: bool,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				InboundLanes::<T, I>::contains_key(lane_id),
+				Error::<T, I>::LanesManager(LanesManagerError::UnknownInboundLane)
+			);
+
+			if restricted {
+				RestrictedLanes::<T, I>::insert(lane_id, ());
+			} else {
+				RestrictedLanes::<T, I>::remove(lane_id);
+			}
+			log::info!(
+				target: LOG_TARGET,
+				"Setting lane {:?} relayers restricted: {}",
+				lane_id,
+				restricted,
+			);
+
+			Ok(())
+		}
+
+		/// Add relayer to (or remove it from) the list of relayers that are allowed to deliver
+		/// messages over given inbound lane.
+		///
+		/// The list is only used when the lane is restricted using the
+		/// `set_lane_relayers_restricted` call. At most `MaxAllowedRelayersPerLane` relayers may
+		/// be allowed at the same lane. May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(11)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_lane_relayer_allowed(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			relayer: T::AccountId,
+			allowed: bool,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				InboundLanes::<T, I>::contains_key(lane_id),
+				Error::<T, I>::LanesManager(LanesManagerError::UnknownInboundLane)
+			);
+
+			LaneAllowedRelayers::<T, I>::try_mutate_exists(lane_id, |relayers| {
+				let mut updated_relayers = relayers.take().unwrap_or_default();
+				if allowed {
+					if !updated_relayers.contains(&relayer) {
+						updated_relayers
+							.try_push(relayer.clone())
+							.map_err(|_| Error::<T, I>::TooManyAllowedRelayers)?;
+					}
+				} else {
+					updated_relayers.retain(|allowed_relayer| *allowed_relayer != relayer);
+				}
+
+				*relayers = Some(updated_relayers).filter(|relayers| !relayers.is_empty());
+				Ok::<_, Error<T, I>>(())
+			})?;
+			log::info!(
+				target: LOG_TARGET,
+				"Setting relayer {:?} allowed at lane {:?}: {}",
+				relayer,
+				lane_id,
+				allowed,
+			);
+
+			Ok(())
+		}
+
 		/// Receive messages proof from bridged chain.
 		///
 		/// The weight of the call assumes that the transaction always brings outbound lane
@@ -186,10 +485,15 @@ pub mod pallet {
 		/// - the proof verification procedure returns an error - e.g. because header used to craft
 		///   proof is not imported by the associated finality pallet;
 		///
+		/// - the lane is restricted and the relayer is not allowed to deliver messages over it;
+		///
 		/// - the `dispatch_weight` argument is not sufficient to dispatch all bundled messages.
 		///
 		/// The call may succeed, but some messages may not be delivered e.g. if they are not fit
 		/// into the unrewarded relayers vector.
+		///
+		/// The `dispatch_weight` is paid upfront. Any part of it that has not been spent on the
+		/// messages dispatch is refunded to the relayer.
 		#[pallet::call_index(2)]
 		#[pallet::weight(T::WeightInfo::receive_messages_proof_weight(&**proof, *messages_count, *dispatch_weight))]
 		pub fn receive_messages_proof(
@@ -235,12 +539,23 @@ pub mod pallet {
 						Error::<T, I>::InvalidMessagesProof
 					},
 				)?;
+			ensure_lane_not_halted::<T, I>(lane_id)?;
+			ensure!(
+				Self::is_relayer_allowed(lane_id, &relayer_id_at_this_chain),
+				Error::<T, I>::RelayerNotAllowed
+			);
 
 			// dispatch messages and (optionally) update lane(s) state(s)
 			let mut total_messages = 0;
 			let mut valid_messages = 0;
 			let mut dispatch_weight_left = dispatch_weight;
-			let mut lane = active_inbound_lane::<T, I>(lane_id)?;
+			// the closed lane still accepts outbound lane state updates, so the bridged chain
+			// may confirm messages that we have received and the lane may be purged
+			let mut lane = if lane_data.messages.is_empty() {
+				any_state_inbound_lane::<T, I>(lane_id)?
+			} else {
+				active_inbound_lane::<T, I>(lane_id)?
+			};
 
 			// subtract extra storage proof bytes from the actual PoV size - there may be
 			// less unrewarded relayers than the maximal configured value
@@ -260,6 +575,12 @@ pub mod pallet {
 						UnrewardedRelayersState::from(&lane.storage().data()),
 					);
 				}
+			} else {
+				// the declared weight always includes the outbound lane state processing, so
+				// let's refund it if the proof isn't carrying the state
+				actual_weight = actual_weight.saturating_sub(
+					T::WeightInfo::receive_messages_proof_outbound_lane_state_overhead(),
+				);
 			}
 
 			let mut messages_received_status =
@@ -309,9 +630,15 @@ pub mod pallet {
 
 				let unspent_weight = unspent_weight.min(message_dispatch_weight);
 				dispatch_weight_left -= message_dispatch_weight - unspent_weight;
-				actual_weight = actual_weight.saturating_sub(unspent_weight);
 			}
 
+			// the relayer has paid for the whole declared `dispatch_weight` upfront. Now we know
+			// how much weight has actually been spent by the dispatch, so let's refund the rest.
+			// It includes both unspent weight of dispatched messages, weight of messages that
+			// have been rejected by the lane and the surplus declared weight that hasn't been
+			// required by any message
+			actual_weight = actual_weight.saturating_sub(dispatch_weight_left);
+
 			// let's now deal with relayer payments
 			T::DeliveryPayments::pay_reward(
 				relayer_id_at_this_chain,
@@ -335,6 +662,9 @@ pub mod pallet {
 		}
 
 		/// Receive messages delivery proof from bridged chain.
+		///
+		/// If the lane is closed and the proof confirms delivery of the last message, sent over
+		/// this lane, the lane state is purged from the storage by the `on_idle` hook.
 		#[pallet::call_index(3)]
 		#[pallet::weight(T::WeightInfo::receive_messages_delivery_proof_weight(
 			proof,
@@ -359,6 +689,7 @@ pub mod pallet {
 
 					Error::<T, I>::InvalidMessagesDeliveryProof
 				})?;
+			ensure_lane_not_halted::<T, I>(lane_id)?;
 			ensure!(
 				relayers_state.is_valid(&lane_data),
 				Error::<T, I>::InvalidUnrewardedRelayersState
@@ -400,6 +731,9 @@ pub mod pallet {
 					relayers_state.total_messages,
 					received_range.checked_len().unwrap_or(MessageNonce::MAX),
 				);
+
+				// the lane may be uncongested now
+				update_lane_congestion::<T, I>(lane_id, lane.queued_messages().saturating_len());
 			};
 
 			log::trace!(
@@ -430,6 +764,11 @@ pub mod pallet {
 			lane_id: LaneId,
 			/// Nonce of accepted message.
 			nonce: MessageNonce,
+			/// Blake2-256 hash of the encoded message payload.
+			///
+			/// The same hash may be computed at the bridged chain, so it may be used to
+			/// correlate message sending with its dispatch.
+			payload_hash: H256,
 		},
 		/// Messages have been received from the bridged chain.
 		MessagesReceived(
@@ -443,6 +782,54 @@ pub mod pallet {
 			/// Delivered messages.
 			messages: DeliveredMessages,
 		},
+		/// New lane has been opened.
+		LaneOpened {
+			/// Identifier of the opened lane.
+			lane_id: LaneId,
+		},
+		/// The lane has been closed. Its state will be purged once all sent messages are
+		/// delivered and all received messages are confirmed by the bridged chain.
+		LaneClosed {
+			/// Identifier of the closed lane.
+			lane_id: LaneId,
+		},
+		/// The closed lane state has been purged from the storage.
+		LanePurged {
+			/// Identifier of the purged lane.
+			lane_id: LaneId,
+		},
+		/// Undelivered messages in the inclusive range have expired and have been pruned.
+		MessagesExpired {
+			/// Lane of expired messages.
+			lane_id: LaneId,
+			/// Nonce of the first expired message.
+			begin: MessageNonce,
+			/// Nonce of the last expired message.
+			end: MessageNonce,
+		},
+		/// The lane operating mode has been changed.
+		LaneOperatingModeChanged {
+			/// Identifier of the lane.
+			lane_id: LaneId,
+			/// New lane operating mode. `None` means that the lane follows the pallet operating
+			/// mode.
+			operating_mode: Option<MessagesOperatingMode>,
+		},
+		/// The number of queued messages at the lane has reached the lane congestion threshold.
+		LaneCongested {
+			/// Identifier of the congested lane.
+			lane_id: LaneId,
+			/// Number of queued messages at the lane.
+			queued_messages: MessageNonce,
+		},
+		/// The number of queued messages at the congested lane has dropped below the lane
+		/// congestion threshold.
+		LaneUncongested {
+			/// Identifier of the lane.
+			lane_id: LaneId,
+			/// Number of queued messages at the lane.
+			queued_messages: MessageNonce,
+		},
 	}
 
 	#[pallet::error]
@@ -469,6 +856,15 @@ pub mod pallet {
 		ReceivalConfirmation(ReceivalConfirmationError),
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
+		/// The lane is halted.
+		LaneHalted,
+		/// The maximal message size exceeds the size of messages that are accepted by the bridged
+		/// chain.
+		InvalidMaxMessageSize,
+		/// The relayer is not allowed to deliver messages over the restricted lane.
+		RelayerNotAllowed,
+		/// The maximal number of relayers, allowed to deliver messages over the lane, is reached.
+		TooManyAllowedRelayers,
 	}
 
 	/// Optional pallet owner.
@@ -489,6 +885,83 @@ pub mod pallet {
 	pub type PalletOperatingMode<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, MessagesOperatingMode, ValueQuery>;
 
+	/// Map of lane id => lane operating mode.
+	///
+	/// Lanes that are missing from this map are following the pallet operating mode. Otherwise,
+	/// the most restrictive of pallet and lane operating modes is applied.
+	#[pallet::storage]
+	pub type LaneOperatingModes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessagesOperatingMode, OptionQuery>;
+
+	/// Map of lane id => time-to-live (in blocks) of messages, sent over this lane.
+	///
+	/// Messages of lanes that are missing from this map never expire.
+	#[pallet::storage]
+	pub type LaneMessagesTtl<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, BlockNumberFor<T>, OptionQuery>;
+
+	/// Numbers of blocks at which queued outbound messages have been sent.
+	///
+	/// It is only filled for messages, sent over lanes with configured `LaneMessagesTtl`.
+	/// Entries are removed together with messages, even if the lane TTL has been removed.
+	#[pallet::storage]
+	pub type OutboundMessagesSentAt<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, BlockNumberFor<T>, OptionQuery>;
+
+	/// The last lane, which delivered messages have been pruned by the `on_idle` hook.
+	///
+	/// The next `on_idle` call starts with the lane that follows it in the `OutboundLanes` map,
+	/// so every lane gets its turn, even if there's not enough weight to process them all.
+	#[pallet::storage]
+	pub type LastLaneWithPrunedMessages<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, LaneId, OptionQuery>;
+
+	/// The last lane, which expired messages have been pruned by the `on_idle` hook.
+	///
+	/// The next `on_idle` call starts with the lane that follows it in the `LaneMessagesTtl`
+	/// map, so every lane gets its turn, even if there's not enough weight to process them all.
+	#[pallet::storage]
+	pub type LastLaneWithExpiredMessages<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, LaneId, OptionQuery>;
+
+	/// Map of lane id => number of queued messages, at which the lane is considered congested.
+	///
+	/// Lanes that are missing from this map are never considered congested.
+	#[pallet::storage]
+	pub type LaneCongestionThresholds<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessageNonce, OptionQuery>;
+
+	/// Map of lane id => maximal size of messages, sent over this lane.
+	///
+	/// Lanes that are missing from this map accept messages up to the maximal size of messages
+	/// that are accepted by the bridged chain.
+	#[pallet::storage]
+	pub type LaneMaxMessageSizes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, u32, OptionQuery>;
+
+	/// Set of inbound lanes where messages may only be delivered by relayers from the
+	/// `LaneAllowedRelayers` map.
+	///
+	/// Messages of lanes that are missing from this set may be delivered by any relayer.
+	#[pallet::storage]
+	pub type RestrictedLanes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, (), OptionQuery>;
+
+	/// Map of lane id => relayers that are allowed to deliver messages over this lane.
+	#[pallet::storage]
+	pub type LaneAllowedRelayers<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		LaneId,
+		BoundedVec<T::AccountId, T::MaxAllowedRelayersPerLane>,
+		OptionQuery,
+	>;
+
+	/// Set of currently congested lanes.
+	#[pallet::storage]
+	pub type CongestedLanes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, (), OptionQuery>;
+
 	// TODO: https://github.com/paritytech/parity-bridges-common/pull/2213: let's limit number of
 	// possible opened lanes && use it to constraint maps below
 
@@ -540,6 +1013,20 @@ pub mod pallet {
 	}
 
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Return maximal size of messages that may be sent over given lane.
+		pub fn max_message_size(lane: LaneId) -> u32 {
+			LaneMaxMessageSizes::<T, I>::get(lane)
+				.unwrap_or_else(T::BridgedChain::maximal_incoming_message_size)
+		}
+
+		/// Return true if given relayer may deliver messages over given lane.
+		pub fn is_relayer_allowed(lane: LaneId, relayer: &T::AccountId) -> bool {
+			!RestrictedLanes::<T, I>::contains_key(lane) ||
+				LaneAllowedRelayers::<T, I>::get(lane)
+					.map(|relayers| relayers.contains(relayer))
+					.unwrap_or(false)
+		}
+
 		/// Get stored data of the outbound message with given nonce.
 		pub fn outbound_message_data(lane: LaneId, nonce: MessageNonce) -> Option<MessagePayload> {
 			OutboundMessages::<T, I>::get(MessageKey { lane_id: lane, nonce }).map(Into::into)
@@ -602,19 +1089,31 @@ fn send_message<T: Config<I>, I: 'static>(
 	// dispatch weight here. But we assume that the bridged chain will just push the message
 	// to some queue (XCMP, UMP, DMP), so the weight is constant and fits the block.
 
-	// we can't accept any messages if the pallet is halted
+	// we can't accept any messages if the pallet or the lane is halted
 	ensure_normal_operating_mode::<T, I>()?;
+	ensure_lane_normal_operating_mode::<T, I>(lane_id)?;
 
 	// finally, save message in outbound storage and emit event
 	let mut lane = active_outbound_lane::<T, I>(lane_id)?;
 	let encoded_payload = payload.encode();
 	let encoded_payload_len = encoded_payload.len();
+	let payload_hash = H256::from(blake2_256(&encoded_payload));
+	ensure!(
+		encoded_payload_len <= Pallet::<T, I>::max_message_size(lane_id) as usize,
+		Error::<T, I>::MessageRejectedByPallet(VerificationError::MessageTooLarge)
+	);
 
-	// the message size is checked by the `send_message` method, so we don't need to repeat
-	// it here
+	// the message size is also checked by the `send_message` method against the limit of the
+	// bridged chain, so we don't need to repeat it here
 	let nonce = lane
 		.send_message(encoded_payload)
 		.map_err(Error::<T, I>::MessageRejectedByPallet)?;
+	if LaneMessagesTtl::<T, I>::contains_key(lane_id) {
+		OutboundMessagesSentAt::<T, I>::insert(
+			MessageKey { lane_id, nonce },
+			frame_system::Pallet::<T>::block_number(),
+		);
+	}
 
 	log::trace!(
 		target: LOG_TARGET,
@@ -624,7 +1123,10 @@ fn send_message<T: Config<I>, I: 'static>(
 		encoded_payload_len,
 	);
 
-	Pallet::<T, I>::deposit_event(Event::MessageAccepted { lane_id, nonce });
+	Pallet::<T, I>::deposit_event(Event::MessageAccepted { lane_id, nonce, payload_hash });
+
+	// the lane may be congested now
+	update_lane_congestion::<T, I>(lane_id, lane.queued_messages().saturating_len());
 
 	Ok(SendMessageArtifacts { nonce })
 }
@@ -640,6 +1142,24 @@ fn ensure_normal_operating_mode<T: Config<I>, I: 'static>() -> Result<(), Error<
 	Err(Error::<T, I>::NotOperatingNormally)
 }
 
+/// Ensure that the lane is in normal operational mode.
+fn ensure_lane_normal_operating_mode<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+) -> Result<(), Error<T, I>> {
+	match LaneOperatingModes::<T, I>::get(lane_id) {
+		None | Some(MessagesOperatingMode::Basic(BasicOperatingMode::Normal)) => Ok(()),
+		Some(_) => Err(Error::<T, I>::NotOperatingNormally),
+	}
+}
+
+/// Ensure that the lane is not halted.
+fn ensure_lane_not_halted<T: Config<I>, I: 'static>(lane_id: LaneId) -> Result<(), Error<T, I>> {
+	match LaneOperatingModes::<T, I>::get(lane_id) {
+		Some(operating_mode) if operating_mode.is_halted() => Err(Error::<T, I>::LaneHalted),
+		_ => Ok(()),
+	}
+}
+
 /// Creates new inbound lane object, backed by runtime storage. Lane must be active.
 fn active_inbound_lane<T: Config<I>, I: 'static>(
 	lane_id: LaneId,
@@ -658,6 +1178,15 @@ fn active_outbound_lane<T: Config<I>, I: 'static>(
 		.map_err(Error::LanesManager)
 }
 
+/// Creates new inbound lane object, backed by runtime storage.
+fn any_state_inbound_lane<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+) -> Result<InboundLane<RuntimeInboundLaneStorage<T, I>>, Error<T, I>> {
+	LanesManager::<T, I>::new()
+		.any_state_inbound_lane(lane_id)
+		.map_err(Error::LanesManager)
+}
+
 /// Creates new outbound lane object, backed by runtime storage.
 fn any_state_outbound_lane<T: Config<I>, I: 'static>(
 	lane_id: LaneId,
@@ -667,6 +1196,272 @@ fn any_state_outbound_lane<T: Config<I>, I: 'static>(
 		.map_err(Error::LanesManager)
 }
 
+/// Purge both ends of the closed lane from the storage.
+fn purge_lane<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+	outbound_lane: OutboundLane<RuntimeOutboundLaneStorage<T, I>>,
+) {
+	outbound_lane.purge();
+	LaneOperatingModes::<T, I>::remove(lane_id);
+	LaneMessagesTtl::<T, I>::remove(lane_id);
+	LaneCongestionThresholds::<T, I>::remove(lane_id);
+	LaneMaxMessageSizes::<T, I>::remove(lane_id);
+	CongestedLanes::<T, I>::remove(lane_id);
+	RestrictedLanes::<T, I>::remove(lane_id);
+	LaneAllowedRelayers::<T, I>::remove(lane_id);
+	if let Ok(inbound_lane) = LanesManager::<T, I>::new().any_state_inbound_lane(lane_id) {
+		inbound_lane.purge();
+	}
+
+	log::info!(target: LOG_TARGET, "Purged closed lane {:?}", lane_id);
+	Pallet::<T, I>::deposit_event(Event::LanePurged { lane_id });
+}
+
+/// Returns true if the closed lane may be purged from the storage.
+///
+/// The outbound end of the lane must have no messages left in the storage. The inbound end must
+/// have no unrewarded relayers, because the bridged chain needs its state to confirm delivery of
+/// its messages and to reward its relayers.
+fn is_closed_lane_drained<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+	outbound_lane: &OutboundLane<RuntimeOutboundLaneStorage<T, I>>,
+) -> bool {
+	!outbound_lane.state().is_active() &&
+		outbound_lane.stored_messages().is_empty() &&
+		LanesManager::<T, I>::new()
+			.any_state_inbound_lane(lane_id)
+			.map(|inbound_lane| inbound_lane.storage().data().relayers.is_empty())
+			.unwrap_or(true)
+}
+
+/// Weight of the `purge_lane` call.
+fn purge_lane_weight<T: Config<I>, I: 'static>() -> Weight {
+	// read inbound lane and remove both lanes and all lane-specific settings
+	T::DbWeight::get().reads_writes(1, 9)
+}
+
+/// Prune delivered outbound messages that are still in the storage and purge closed lanes,
+/// which have no messages left in the storage, spending at most `remaining_weight`.
+///
+/// Delivered messages are normally pruned by the delivery confirmation transaction, so this only
+/// deals with leftovers. Lanes are processed in round-robin order, starting with the lane that
+/// follows the `LastLaneWithPrunedMessages`. Returns consumed weight.
+fn prune_delivered_messages<T: Config<I>, I: 'static>(remaining_weight: Weight) -> Weight {
+	let db_weight = T::DbWeight::get();
+	let lanes_manager = LanesManager::<T, I>::new();
+	let purge_weight = purge_lane_weight::<T, I>();
+	// read and update the last processed lane
+	let mut used_weight = db_weight.reads_writes(1, 1);
+	let last_lane = LastLaneWithPrunedMessages::<T, I>::get();
+	let lanes = match last_lane {
+		Some(last_lane) =>
+			OutboundLanes::<T, I>::iter_keys_from(OutboundLanes::<T, I>::hashed_key_for(last_lane)),
+		None => OutboundLanes::<T, I>::iter_keys(),
+	}
+	.chain(OutboundLanes::<T, I>::iter_keys());
+	let mut first_lane = None;
+	let mut processed_lane = None;
+	let mut lanes_to_purge = Vec::new();
+	for lane_id in lanes {
+		// stop when all lanes have been visited
+		match first_lane {
+			Some(first_lane) if first_lane == lane_id => break,
+			Some(_) => (),
+			None => first_lane = Some(lane_id),
+		}
+
+		// read lane key and data of both lane ends
+		used_weight.saturating_accrue(db_weight.reads(3));
+		if used_weight.any_gt(remaining_weight) {
+			break
+		}
+
+		let mut lane = match lanes_manager.any_state_outbound_lane(lane_id) {
+			Ok(lane) => lane,
+			Err(_) => continue,
+		};
+		used_weight.saturating_accrue(
+			lane.prune_messages(db_weight, remaining_weight.saturating_sub(used_weight)),
+		);
+
+		// if we've run out of weight, we'll start with the same lane next time
+		if lane.stored_messages().start() != lane.queued_messages().start() {
+			break
+		}
+
+		// we can't modify the map while iterating it, so lanes are purged later
+		if is_closed_lane_drained::<T, I>(lane_id, &lane) {
+			if used_weight.saturating_add(purge_weight).any_gt(remaining_weight) {
+				break
+			}
+
+			used_weight.saturating_accrue(purge_weight);
+			lanes_to_purge.push((lane_id, lane));
+		}
+
+		processed_lane = Some(lane_id);
+	}
+
+	if processed_lane.is_some() && processed_lane != last_lane {
+		LastLaneWithPrunedMessages::<T, I>::set(processed_lane);
+	}
+
+	for (lane_id, lane) in lanes_to_purge {
+		purge_lane::<T, I>(lane_id, lane);
+	}
+
+	used_weight
+}
+
+/// Prune undelivered outbound messages of closed lanes that have expired, spending at most
+/// `remaining_weight`.
+///
+/// Messages are pruned in the order they have been sent. Lanes are processed in round-robin
+/// order, starting with the lane that follows the `LastLaneWithExpiredMessages`. Returns
+/// consumed weight.
+fn expire_outbound_messages<T: Config<I>, I: 'static>(
+	now: BlockNumberFor<T>,
+	remaining_weight: Weight,
+) -> Weight {
+	let db_weight = T::DbWeight::get();
+	let lanes_manager = LanesManager::<T, I>::new();
+	// read the last processed lane
+	let mut used_weight = db_weight.reads(1);
+	let last_lane = LastLaneWithExpiredMessages::<T, I>::get();
+	let lanes = match last_lane {
+		Some(last_lane) =>
+			LaneMessagesTtl::<T, I>::iter_from(LaneMessagesTtl::<T, I>::hashed_key_for(last_lane)),
+		None => LaneMessagesTtl::<T, I>::iter(),
+	}
+	.chain(LaneMessagesTtl::<T, I>::iter());
+	let mut first_lane = None;
+	let mut processed_lane = None;
+	for (lane_id, ttl) in lanes {
+		// stop when all lanes have been visited
+		match first_lane {
+			Some(first_lane) if first_lane == lane_id => break,
+			Some(_) => (),
+			None => first_lane = Some(lane_id),
+		}
+
+		// read lane TTL and lane data
+		used_weight.saturating_accrue(db_weight.reads(2));
+		if used_weight.any_gt(remaining_weight) {
+			break
+		}
+
+		let mut lane = match lanes_manager.any_state_outbound_lane(lane_id) {
+			Ok(lane) => lane,
+			Err(_) => continue,
+		};
+		// messages of opened lane never expire, because the bridged chain only accepts messages
+		// in order and pruned messages can't be proved. Expired messages are removed from the
+		// front of the lane, so we need to prune all delivered messages first
+		if lane.state().is_active() ||
+			lane.stored_messages().start() != lane.queued_messages().start()
+		{
+			processed_lane = Some(lane_id);
+			continue
+		}
+
+		let mut expired_messages = None;
+		let mut is_lane_processed = true;
+		let per_message_weight = db_weight.reads_writes(2, 3);
+		for nonce in lane.queued_messages() {
+			if used_weight.saturating_add(per_message_weight).any_gt(remaining_weight) {
+				is_lane_processed = false;
+				break
+			}
+
+			let key = MessageKey { lane_id, nonce };
+			used_weight.saturating_accrue(db_weight.reads(1));
+			let is_expired = OutboundMessagesSentAt::<T, I>::get(&key)
+				.map(|sent_at| now.saturating_sub(sent_at) >= ttl)
+				.unwrap_or(false);
+			if !is_expired {
+				break
+			}
+
+			let payload = OutboundMessages::<T, I>::get(&key).map(Into::into).unwrap_or_default();
+			lane.remove_oldest_unpruned_message();
+			used_weight.saturating_accrue(db_weight.reads_writes(1, 3));
+			used_weight.saturating_accrue(T::OnMessagesExpired::on_message_expired(
+				lane_id, nonce, payload,
+			));
+			expired_messages =
+				Some((expired_messages.map(|(begin, _)| begin).unwrap_or(nonce), nonce));
+		}
+
+		if let Some((begin, end)) = expired_messages {
+			log::trace!(
+				target: LOG_TARGET,
+				"Pruned expired messages {}..={} at lane {:?}",
+				begin,
+				end,
+				lane_id,
+			);
+			Pallet::<T, I>::deposit_event(Event::MessagesExpired { lane_id, begin, end });
+
+			// the lane may be uncongested now
+			used_weight.saturating_accrue(db_weight.reads_writes(2, 1));
+			update_lane_congestion::<T, I>(lane_id, lane.queued_messages().saturating_len());
+		}
+
+		// if we've run out of weight, we'll start with the same lane next time
+		if !is_lane_processed {
+			break
+		}
+		processed_lane = Some(lane_id);
+	}
+
+	if processed_lane.is_some() && processed_lane != last_lane {
+		used_weight.saturating_accrue(db_weight.writes(1));
+		LastLaneWithExpiredMessages::<T, I>::set(processed_lane);
+	}
+
+	used_weight
+}
+
+/// Update congestion state of the lane, given the number of queued messages at the lane.
+///
+/// The `OnLaneCongestion` handler is only called when the lane congestion state changes.
+fn update_lane_congestion<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+	queued_messages: MessageNonce,
+) {
+	let is_congested = LaneCongestionThresholds::<T, I>::get(lane_id)
+		.map(|threshold| queued_messages >= threshold)
+		.unwrap_or(false);
+	let was_congested = CongestedLanes::<T, I>::contains_key(lane_id);
+	match (was_congested, is_congested) {
+		(false, true) => {
+			log::info!(
+				target: LOG_TARGET,
+				"Lane {:?} is congested. Queued messages: {}",
+				lane_id,
+				queued_messages,
+			);
+
+			CongestedLanes::<T, I>::insert(lane_id, ());
+			T::OnLaneCongestion::on_lane_congested(lane_id);
+			Pallet::<T, I>::deposit_event(Event::LaneCongested { lane_id, queued_messages });
+		},
+		(true, false) => {
+			log::info!(
+				target: LOG_TARGET,
+				"Lane {:?} is no longer congested. Queued messages: {}",
+				lane_id,
+				queued_messages,
+			);
+
+			CongestedLanes::<T, I>::remove(lane_id);
+			T::OnLaneCongestion::on_lane_uncongested(lane_id);
+			Pallet::<T, I>::deposit_event(Event::LaneUncongested { lane_id, queued_messages });
+		},
+		_ => (),
+	}
+}
+
 /// Verify messages proof and return proved messages with decoded payload.
 fn verify_and_decode_messages_proof<T: Config<I>, I: 'static>(
 	proof: FromBridgedChainMessagesProof<HashOf<BridgedChainOf<T, I>>>,
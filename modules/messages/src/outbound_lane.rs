@@ -23,7 +23,11 @@ use bp_messages::{
 	OutboundLaneData, UnrewardedRelayer, VerificationError,
 };
 use codec::{Decode, Encode};
-use frame_support::{traits::Get, BoundedVec, PalletError, RuntimeDebug};
+use frame_support::{
+	traits::Get,
+	weights::{RuntimeDbWeight, Weight},
+	BoundedVec, PalletError, RuntimeDebug,
+};
 use scale_info::TypeInfo;
 use sp_std::{collections::vec_deque::VecDeque, marker::PhantomData, ops::RangeInclusive};
 
@@ -107,8 +111,18 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 		self.storage.set_data(data);
 	}
 
-	/// Return nonces of all currently queued messages.
+	/// Return nonces of all currently queued (undelivered) messages.
 	pub fn queued_messages(&self) -> RangeInclusive<MessageNonce> {
+		let data = self.storage.data();
+		sp_std::cmp::max(
+			data.oldest_unpruned_nonce,
+			data.latest_received_nonce.saturating_add(1),
+		)..=data.latest_generated_nonce
+	}
+
+	/// Return nonces of all messages that are still in the storage. It includes both queued
+	/// messages and delivered messages that are not yet pruned.
+	pub fn stored_messages(&self) -> RangeInclusive<MessageNonce> {
 		let data = self.storage.data();
 		data.oldest_unpruned_nonce..=data.latest_generated_nonce
 	}
@@ -170,13 +184,49 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 			self.storage.remove_message(&nonce);
 		}
 
+		// some undelivered messages may have already been pruned, because they have expired
 		data.latest_received_nonce = confirmed_messages.end;
-		data.oldest_unpruned_nonce = data.latest_received_nonce.saturating_add(1);
+		data.oldest_unpruned_nonce = sp_std::cmp::max(
+			data.oldest_unpruned_nonce,
+			data.latest_received_nonce.saturating_add(1),
+		);
 		self.storage.set_data(data);
 
 		Ok(Some(confirmed_messages))
 	}
 
+	/// Prune delivered messages that are still in the storage, spending at most
+	/// `remaining_weight`.
+	///
+	/// Delivered messages are normally pruned by the `confirm_delivery`, so this only removes
+	/// leftovers. Returns weight, consumed by messages pruning and lane state update.
+	pub fn prune_messages(
+		&mut self,
+		db_weight: RuntimeDbWeight,
+		remaining_weight: Weight,
+	) -> Weight {
+		// every message is removed from two maps: `OutboundMessages` and `OutboundMessagesSentAt`
+		let message_weight = db_weight.writes(2);
+		let lane_weight = db_weight.writes(1);
+		let mut spent_weight = Weight::zero();
+		let mut data = self.storage.data();
+		while data.oldest_unpruned_nonce <= data.latest_received_nonce &&
+			remaining_weight.all_gte(spent_weight + message_weight + lane_weight)
+		{
+			self.storage.remove_message(&data.oldest_unpruned_nonce);
+
+			spent_weight += message_weight;
+			data.oldest_unpruned_nonce += 1;
+		}
+
+		if !spent_weight.is_zero() {
+			spent_weight += lane_weight;
+			self.storage.set_data(data);
+		}
+
+		spent_weight
+	}
+
 	/// Remove message from the storage. Doesn't perform any checks.
 	pub fn remove_oldest_unpruned_message(&mut self) {
 		let mut data = self.storage.data();
@@ -226,7 +276,7 @@ fn ensure_unrewarded_relayers_are_correct<RelayerId>(
 mod tests {
 	use super::*;
 	use crate::{active_outbound_lane, tests::mock::*};
-	use frame_support::assert_ok;
+	use frame_support::{assert_ok, weights::constants::RocksDbWeight};
 	use sp_std::ops::RangeInclusive;
 
 	fn unrewarded_relayers(
@@ -420,4 +470,36 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn prune_messages_works() {
+		run_test(|| {
+			let db_weight = RocksDbWeight::get();
+			let mut lane = active_outbound_lane::<TestRuntime, _>(test_lane_id()).unwrap();
+			assert_ok!(lane.send_message(outbound_message_data(REGULAR_PAYLOAD)));
+			assert_ok!(lane.send_message(outbound_message_data(REGULAR_PAYLOAD)));
+			assert_ok!(lane.send_message(outbound_message_data(REGULAR_PAYLOAD)));
+
+			// nothing is pruned until messages are delivered
+			assert_eq!(lane.prune_messages(db_weight, Weight::MAX), Weight::zero());
+			assert_eq!(lane.stored_messages(), 1..=3);
+
+			// delivered messages are left in the storage (e.g. by the previous runtime version)
+			let mut data = lane.storage.data();
+			data.latest_received_nonce = 3;
+			lane.storage.set_data(data);
+
+			// when there's only enough weight to prune single message
+			assert_eq!(lane.prune_messages(db_weight, db_weight.writes(4)), db_weight.writes(3));
+			assert!(lane.storage.message(&1).is_none());
+			assert!(lane.storage.message(&2).is_some());
+			assert_eq!(lane.stored_messages(), 2..=3);
+
+			// when there's enough weight to prune all delivered messages
+			assert_eq!(lane.prune_messages(db_weight, Weight::MAX), db_weight.writes(5));
+			assert!(lane.storage.message(&2).is_none());
+			assert!(lane.storage.message(&3).is_none());
+			assert!(lane.stored_messages().is_empty());
+		});
+	}
 }
@@ -28,7 +28,10 @@ use crate::{
 use bp_header_chain::{ChainWithGrandpa, StoredHeaderData};
 use bp_messages::{
 	calc_relayers_rewards,
-	source_chain::{DeliveryConfirmationPayments, FromBridgedChainMessagesDeliveryProof},
+	source_chain::{
+		DeliveryConfirmationPayments, FromBridgedChainMessagesDeliveryProof, OnLaneCongestion,
+		OnMessagesExpired,
+	},
 	target_chain::{
 		DeliveryPayments, DispatchMessage, DispatchMessageData, FromBridgedChainMessagesProof,
 		MessageDispatch,
@@ -216,6 +219,9 @@ impl pallet_bridge_grandpa::Config for TestRuntime {
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = BridgedChain;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<32>;
+	type FreeHeadersEpochLength = ConstU32<8>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = ConstU32<8>;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<TestRuntime>;
 }
@@ -244,6 +250,9 @@ impl Config for TestRuntime {
 	type DeliveryConfirmationPayments = TestDeliveryConfirmationPayments;
 
 	type MessageDispatch = TestMessageDispatch;
+	type OnMessagesExpired = TestOnMessagesExpired;
+	type OnLaneCongestion = TestOnLaneCongestion;
+	type MaxAllowedRelayersPerLane = ConstU32<2>;
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -377,6 +386,53 @@ impl DeliveryConfirmationPayments<AccountId> for TestDeliveryConfirmationPayment
 	}
 }
 
+/// Expired messages handler that is used in tests.
+pub struct TestOnMessagesExpired;
+
+impl TestOnMessagesExpired {
+	/// Returns true if the handler has been called for the given message. The flag is cleared
+	/// after the call.
+	pub fn is_message_expired(lane_id: LaneId, nonce: MessageNonce) -> bool {
+		let key = (b":expired-message:", lane_id, nonce).encode();
+		frame_support::storage::unhashed::take::<bool>(&key).is_some()
+	}
+}
+
+impl OnMessagesExpired for TestOnMessagesExpired {
+	fn on_message_expired(
+		lane_id: LaneId,
+		nonce: MessageNonce,
+		_payload: MessagePayload,
+	) -> Weight {
+		let key = (b":expired-message:", lane_id, nonce).encode();
+		frame_support::storage::unhashed::put(&key, &true);
+		Weight::zero()
+	}
+}
+
+/// Lane congestion handler that is used in tests.
+pub struct TestOnLaneCongestion;
+
+impl TestOnLaneCongestion {
+	/// Returns true if the lane is congested, according to the handler.
+	pub fn is_lane_congested(lane_id: LaneId) -> bool {
+		let key = (b":congested-lane:", lane_id).encode();
+		frame_support::storage::unhashed::get_or_default(&key)
+	}
+}
+
+impl OnLaneCongestion for TestOnLaneCongestion {
+	fn on_lane_congested(lane_id: LaneId) {
+		let key = (b":congested-lane:", lane_id).encode();
+		frame_support::storage::unhashed::put(&key, &true);
+	}
+
+	fn on_lane_uncongested(lane_id: LaneId) {
+		let key = (b":congested-lane:", lane_id).encode();
+		frame_support::storage::unhashed::kill(&key);
+	}
+}
+
 /// Source header chain that is used in tests.
 #[derive(Debug)]
 pub struct TestMessageDispatch;
@@ -519,6 +575,39 @@ pub fn prepare_messages_proof(
 	})
 }
 
+/// Prepare valid storage proof of the outbound lane state without any messages and insert
+/// appropriate header to the bridged header chain.
+pub fn prepare_outbound_lane_state_proof(
+	lane: LaneId,
+	outbound_lane_data: OutboundLaneData,
+) -> Box<FromBridgedChainMessagesProof<BridgedHeaderHash>> {
+	let (storage_root, storage) = prepare_messages_storage_proof::<BridgedChain, ThisChain>(
+		lane,
+		1..=0,
+		Some(outbound_lane_data),
+		UnverifiedStorageProofParams::default(),
+		|_| unreachable!("there are no messages in the proof; qed"),
+		encode_all_messages,
+		encode_lane_data,
+		false,
+		false,
+	);
+
+	let bridged_header_hash = Default::default();
+	pallet_bridge_grandpa::ImportedHeaders::<TestRuntime>::insert(
+		bridged_header_hash,
+		StoredHeaderData { number: 0, state_root: storage_root },
+	);
+
+	Box::new(FromBridgedChainMessagesProof::<BridgedHeaderHash> {
+		bridged_header_hash,
+		storage,
+		lane,
+		nonces_start: 1,
+		nonces_end: 0,
+	})
+}
+
 /// Prepare valid storage proof for given messages and insert appropriate header to the
 /// bridged header chain.
 ///
@@ -17,11 +17,12 @@
 //! Pallet-level tests.
 
 use crate::{
-	active_outbound_lane, lanes_manager::RuntimeInboundLaneStorage,
-	outbound_lane::ReceivalConfirmationError, send_message, tests::mock::*,
-	weights_ext::WeightInfoExt, Call, Config, Error, Event, InboundLanes, LanesManagerError,
-	OutboundLanes, OutboundMessages, Pallet, PalletOperatingMode, PalletOwner,
-	StoredInboundLaneData,
+	active_outbound_lane, expire_outbound_messages, lanes_manager::RuntimeInboundLaneStorage,
+	outbound_lane::ReceivalConfirmationError, prune_delivered_messages, send_message,
+	tests::mock::*, weights_ext::WeightInfoExt, Call, Config, Error, Event, InboundLanes,
+	LaneAllowedRelayers, LaneMessagesTtl, LanesManagerError, LastLaneWithExpiredMessages,
+	LastLaneWithPrunedMessages, OutboundLanes, OutboundMessages, OutboundMessagesSentAt, Pallet,
+	PalletOperatingMode, PalletOwner, StoredInboundLaneData,
 };
 
 use bp_messages::{
@@ -38,9 +39,11 @@ use frame_support::{
 	assert_noop, assert_ok,
 	dispatch::Pays,
 	storage::generator::{StorageMap, StorageValue},
+	traits::{Get, Hooks},
 	weights::Weight,
 };
 use frame_system::{EventRecord, Pallet as System, Phase};
+use sp_core::hashing::blake2_256;
 use sp_runtime::DispatchError;
 
 fn get_ready_for_events() {
@@ -66,13 +69,18 @@ fn send_regular_message() {
 			phase: Phase::Initialization,
 			event: TestEvent::Messages(Event::MessageAccepted {
 				lane_id: test_lane_id(),
-				nonce: message_nonce
+				nonce: message_nonce,
+				payload_hash: blake2_256(&REGULAR_PAYLOAD.encode()).into(),
 			}),
 			topics: vec![],
 		}],
 	);
 }
 
+fn outbound_lane_state_overhead() -> Weight {
+	<TestRuntime as Config>::WeightInfo::receive_messages_proof_outbound_lane_state_overhead()
+}
+
 fn receive_messages_delivery_proof() {
 	System::<TestRuntime>::set_block_number(1);
 	System::<TestRuntime>::reset_events();
@@ -237,6 +245,63 @@ fn send_message_rejects_too_large_message() {
 	})
 }
 
+#[test]
+fn send_message_respects_lane_max_message_size() {
+	run_test(|| {
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_max_message_size(
+				RuntimeOrigin::signed(1),
+				test_lane_id(),
+				Some(16),
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_max_message_size(
+				RuntimeOrigin::root(),
+				unknown_lane_id(),
+				Some(16),
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::UnknownOutboundLane),
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_max_message_size(
+				RuntimeOrigin::root(),
+				test_lane_id(),
+				Some(BridgedChain::maximal_incoming_message_size() + 1),
+			),
+			Error::<TestRuntime, ()>::InvalidMaxMessageSize,
+		);
+
+		// by default, the limit of the bridged chain is used
+		assert_eq!(
+			Pallet::<TestRuntime>::max_message_size(test_lane_id()),
+			BridgedChain::maximal_incoming_message_size(),
+		);
+
+		// when the lane limit is set, larger messages are rejected
+		let payload_size = REGULAR_PAYLOAD.encoded_size() as u32;
+		assert_ok!(Pallet::<TestRuntime>::set_lane_max_message_size(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(payload_size - 1),
+		));
+		assert_eq!(Pallet::<TestRuntime>::max_message_size(test_lane_id()), payload_size - 1);
+		assert_noop!(
+			send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD),
+			Error::<TestRuntime, ()>::MessageRejectedByPallet(VerificationError::MessageTooLarge),
+		);
+
+		// messages that fit the lane limit are accepted
+		assert_ok!(Pallet::<TestRuntime>::set_lane_max_message_size(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(payload_size),
+		));
+		assert_ok!(send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD));
+	});
+}
+
 #[test]
 fn receive_messages_proof_works() {
 	run_test(|| {
@@ -260,6 +325,152 @@ fn receive_messages_proof_works() {
 	});
 }
 
+#[test]
+fn number_of_lane_allowed_relayers_is_limited() {
+	run_test(|| {
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			1,
+			true,
+		));
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			2,
+			true,
+		));
+		// allowing already allowed relayer is a no-op
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			2,
+			true,
+		));
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_relayer_allowed(
+				RuntimeOrigin::root(),
+				test_lane_id(),
+				3,
+				true,
+			),
+			Error::<TestRuntime, ()>::TooManyAllowedRelayers,
+		);
+
+		// when relayer is removed from the list, another relayer may be allowed
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			1,
+			false,
+		));
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			3,
+			true,
+		));
+		assert_eq!(
+			LaneAllowedRelayers::<TestRuntime>::get(test_lane_id()).map(|r| r.into_inner()),
+			Some(vec![2, 3]),
+		);
+
+		// when the last relayer is removed, the list is removed from the storage
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			2,
+			false,
+		));
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			3,
+			false,
+		));
+		assert!(!LaneAllowedRelayers::<TestRuntime>::contains_key(test_lane_id()));
+	});
+}
+
+#[test]
+fn receive_messages_proof_respects_lane_allowed_relayers() {
+	run_test(|| {
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_relayers_restricted(
+				RuntimeOrigin::signed(1),
+				test_lane_id(),
+				true,
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_relayers_restricted(
+				RuntimeOrigin::root(),
+				unknown_lane_id(),
+				true,
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::UnknownInboundLane),
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_relayer_allowed(
+				RuntimeOrigin::root(),
+				unknown_lane_id(),
+				1,
+				true,
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::UnknownInboundLane),
+		);
+
+		// when lane is restricted, relayers that are not allowed can't deliver messages
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayers_restricted(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			true,
+		));
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayer_allowed(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			1,
+			true,
+		));
+		assert!(Pallet::<TestRuntime>::is_relayer_allowed(test_lane_id(), &1));
+		assert!(!Pallet::<TestRuntime>::is_relayer_allowed(test_lane_id(), &2));
+		assert_noop!(
+			Pallet::<TestRuntime>::receive_messages_proof(
+				RuntimeOrigin::signed(2),
+				TEST_RELAYER_A,
+				prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			),
+			Error::<TestRuntime, ()>::RelayerNotAllowed,
+		);
+
+		// allowed relayers may deliver messages
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+
+		// when lane is permissionless again, any relayer may deliver messages
+		assert_ok!(Pallet::<TestRuntime>::set_lane_relayers_restricted(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			false,
+		));
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(2),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(2, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+	});
+}
+
 #[test]
 fn receive_messages_proof_updates_confirmed_message_nonce() {
 	run_test(|| {
@@ -400,6 +611,8 @@ fn receive_messages_delivery_proof_works() {
 #[test]
 fn receive_messages_delivery_proof_works_on_closed_outbound_lanes() {
 	run_test(|| {
+		// the second message is not confirmed, so the lane is not purged
+		send_regular_message();
 		send_regular_message();
 		active_outbound_lane::<TestRuntime, ()>(test_lane_id())
 			.unwrap()
@@ -681,12 +894,14 @@ fn ref_time_refund_from_receive_messages_proof_works() {
 			*payload.dispatch_result.unspent_weight.ref_time_mut() = unspent_weight;
 			let proof = prepare_messages_proof(vec![message(nonce, payload)], None);
 			let messages_count = 1;
+			// the proof has no outbound lane state, so its overhead is always refunded
 			let pre_dispatch_weight =
 				<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
 					&*proof,
 					messages_count,
 					REGULAR_PAYLOAD.declared_weight,
-				);
+				)
+				.saturating_sub(outbound_lane_state_overhead());
 			let result = Pallet::<TestRuntime>::receive_messages_proof(
 				RuntimeOrigin::signed(1),
 				TEST_RELAYER_A,
@@ -727,6 +942,75 @@ fn ref_time_refund_from_receive_messages_proof_works() {
 	});
 }
 
+#[test]
+fn surplus_dispatch_weight_is_refunded_from_receive_messages_proof() {
+	run_test(|| {
+		// relayer declares twice more dispatch weight than the message requires
+		let mut payload = REGULAR_PAYLOAD;
+		*payload.dispatch_result.unspent_weight.ref_time_mut() = 1;
+		let declared_dispatch_weight = REGULAR_PAYLOAD.declared_weight * 2;
+		let proof = prepare_messages_proof(vec![message(1, payload)], None);
+		let pre_dispatch_weight =
+			<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
+				&*proof,
+				1,
+				declared_dispatch_weight,
+			)
+			.saturating_sub(outbound_lane_state_overhead());
+		let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			proof,
+			1,
+			declared_dispatch_weight,
+		)
+		.expect("delivery has failed")
+		.actual_weight
+		.expect("receive_messages_proof always returns Some");
+
+		// both surplus and unspent weight are refunded
+		assert_eq!(
+			post_dispatch_weight.ref_time(),
+			pre_dispatch_weight.ref_time() - REGULAR_PAYLOAD.declared_weight.ref_time() - 1,
+		);
+	});
+}
+
+#[test]
+fn outbound_lane_state_overhead_is_refunded_from_receive_messages_proof() {
+	run_test(|| {
+		let receive_messages_proof = |nonce, lane_state| {
+			let proof = prepare_messages_proof(vec![message(nonce, REGULAR_PAYLOAD)], lane_state);
+			let pre_dispatch_weight =
+				<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
+					&*proof,
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				);
+			let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+				RuntimeOrigin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			)
+			.expect("delivery has failed")
+			.actual_weight
+			.expect("receive_messages_proof always returns Some");
+			(pre_dispatch_weight, post_dispatch_weight)
+		};
+
+		// when proof carries the outbound lane state, its overhead is not refunded
+		let (pre, post) = receive_messages_proof(1, Some(OutboundLaneData::default()));
+		assert_eq!(post.ref_time(), pre.ref_time());
+
+		// when proof has no outbound lane state, its overhead is refunded
+		let (pre, post) = receive_messages_proof(2, None);
+		assert_ne!(outbound_lane_state_overhead().ref_time(), 0);
+		assert_eq!(post.ref_time(), pre.ref_time() - outbound_lane_state_overhead().ref_time());
+	});
+}
+
 #[test]
 fn proof_size_refund_from_receive_messages_proof_works() {
 	run_test(|| {
@@ -1070,3 +1354,559 @@ fn receive_messages_delivery_proof_fails_if_outbound_lane_is_unknown() {
 		);
 	});
 }
+
+#[test]
+fn open_lane_works() {
+	run_test(|| {
+		get_ready_for_events();
+
+		let lane_id = LaneId::new(42, 43);
+		assert_noop!(
+			Pallet::<TestRuntime>::open_lane(RuntimeOrigin::signed(1), lane_id),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::open_lane(RuntimeOrigin::root(), test_lane_id()),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::InboundLaneAlreadyExists),
+		);
+
+		assert_ok!(Pallet::<TestRuntime>::open_lane(RuntimeOrigin::root(), lane_id));
+		assert_eq!(InboundLanes::<TestRuntime>::get(lane_id).unwrap().state, LaneState::Opened);
+		assert_eq!(OutboundLanes::<TestRuntime>::get(lane_id).unwrap().state, LaneState::Opened);
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::LaneOpened { lane_id }),
+				topics: vec![],
+			}],
+		);
+
+		assert_ok!(send_message::<TestRuntime, ()>(lane_id, REGULAR_PAYLOAD));
+	});
+}
+
+#[test]
+fn close_lane_purges_drained_lane_immediately() {
+	run_test(|| {
+		get_ready_for_events();
+
+		assert_noop!(
+			Pallet::<TestRuntime>::close_lane(RuntimeOrigin::signed(1), test_lane_id()),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::close_lane(RuntimeOrigin::root(), closed_lane_id()),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::ClosedInboundLane),
+		);
+
+		assert_ok!(Pallet::<TestRuntime>::close_lane(RuntimeOrigin::root(), test_lane_id()));
+		assert!(!InboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert!(!OutboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![
+				EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::LaneClosed { lane_id: test_lane_id() }),
+					topics: vec![],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::LanePurged { lane_id: test_lane_id() }),
+					topics: vec![],
+				},
+			],
+		);
+	});
+}
+
+#[test]
+fn closed_lane_is_not_purged_until_bridged_chain_confirms_received_messages() {
+	run_test(|| {
+		// the message is received, but its delivery is not yet confirmed by the bridged chain
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+
+		// the closed lane is not purged, because the bridged chain still needs the inbound lane
+		// state to confirm delivery and reward the relayer
+		get_ready_for_events();
+		assert_ok!(Pallet::<TestRuntime>::close_lane(RuntimeOrigin::root(), test_lane_id()));
+		Pallet::<TestRuntime>::on_idle(1, Weight::MAX);
+		assert!(InboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert!(OutboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert_eq!(InboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().relayers.len(), 1);
+
+		// new messages are rejected by the closed lane
+		assert_noop!(
+			Pallet::<TestRuntime>::receive_messages_proof(
+				RuntimeOrigin::signed(1),
+				TEST_RELAYER_A,
+				prepare_messages_proof(vec![message(2, REGULAR_PAYLOAD)], None),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::ClosedInboundLane),
+		);
+
+		// but the bridged chain may still confirm delivery of the received message
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_outbound_lane_state_proof(
+				test_lane_id(),
+				OutboundLaneData {
+					state: LaneState::Closed,
+					oldest_unpruned_nonce: 2,
+					latest_received_nonce: 1,
+					latest_generated_nonce: 1,
+				},
+			),
+			0,
+			Weight::zero(),
+		));
+		assert!(InboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().relayers.is_empty());
+
+		// and now the lane is purged by the `on_idle`
+		Pallet::<TestRuntime>::on_idle(1, Weight::MAX);
+		assert!(!InboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert!(!OutboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert_eq!(
+			System::<TestRuntime>::events().last().map(|r| r.event.clone()),
+			Some(TestEvent::Messages(Event::LanePurged { lane_id: test_lane_id() })),
+		);
+	});
+}
+
+#[test]
+fn closed_lane_is_purged_when_last_message_is_confirmed() {
+	run_test(|| {
+		send_regular_message();
+		assert_ok!(Pallet::<TestRuntime>::close_lane(RuntimeOrigin::root(), test_lane_id()));
+
+		// new messages are rejected, but the lane is still there
+		assert_noop!(
+			send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::ClosedOutboundLane),
+		);
+		assert_eq!(
+			InboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().state,
+			LaneState::Closed
+		);
+		assert_eq!(
+			OutboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().state,
+			LaneState::Closed
+		);
+
+		// confirmation of the last message is accepted
+		get_ready_for_events();
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+			RuntimeOrigin::signed(1),
+			prepare_messages_delivery_proof(
+				test_lane_id(),
+				InboundLaneData {
+					state: LaneState::Closed,
+					last_confirmed_nonce: 1,
+					relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into(),
+				},
+			),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				messages_in_oldest_entry: 1,
+				total_messages: 1,
+				last_delivered_nonce: 1,
+			},
+		));
+		assert!(InboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert!(OutboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+
+		// the lane is purged by the `on_idle`
+		Pallet::<TestRuntime>::on_idle(1, Weight::MAX);
+		assert!(!InboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert!(!OutboundLanes::<TestRuntime>::contains_key(test_lane_id()));
+		assert!(!OutboundMessages::<TestRuntime>::contains_key(MessageKey {
+			lane_id: test_lane_id(),
+			nonce: 1
+		}));
+		assert_eq!(
+			System::<TestRuntime>::events().last().map(|r| r.event.clone()),
+			Some(TestEvent::Messages(Event::LanePurged { lane_id: test_lane_id() })),
+		);
+	});
+}
+
+#[test]
+fn delivered_messages_are_pruned_on_idle() {
+	run_test(|| {
+		send_regular_message();
+		send_regular_message();
+		send_regular_message();
+		receive_messages_delivery_proof();
+		let message_key = |nonce| MessageKey { lane_id: test_lane_id(), nonce };
+
+		// confirmed message is pruned by the confirmation transaction
+		assert!(!OutboundMessages::<TestRuntime>::contains_key(message_key(1)));
+
+		// delivered message is left in the storage (e.g. by the previous runtime version)
+		OutboundLanes::<TestRuntime>::mutate(test_lane_id(), |data| {
+			data.as_mut().unwrap().latest_received_nonce = 2;
+		});
+
+		// nothing is pruned if there's not enough weight
+		Pallet::<TestRuntime>::on_idle(1, DbWeight::get().reads(2));
+		assert!(OutboundMessages::<TestRuntime>::contains_key(message_key(2)));
+
+		// delivered message is pruned and the queued message is kept
+		Pallet::<TestRuntime>::on_idle(1, Weight::MAX);
+		assert!(!OutboundMessages::<TestRuntime>::contains_key(message_key(2)));
+		assert!(OutboundMessages::<TestRuntime>::contains_key(message_key(3)));
+		assert_eq!(
+			OutboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().oldest_unpruned_nonce,
+			3
+		);
+	});
+}
+
+#[test]
+fn lanes_with_delivered_messages_are_processed_in_round_robin_order() {
+	run_test(|| {
+		let lanes = [test_lane_id(), LaneId::new(1, 5)];
+		assert_ok!(Pallet::<TestRuntime>::open_lane(RuntimeOrigin::root(), lanes[1]));
+		for lane_id in lanes {
+			assert_ok!(send_message::<TestRuntime, ()>(lane_id, REGULAR_PAYLOAD));
+			OutboundLanes::<TestRuntime>::mutate(lane_id, |data| {
+				data.as_mut().unwrap().latest_received_nonce = 1;
+			});
+		}
+
+		// there's only enough weight to prune messages of the single lane, so lanes are
+		// processed one after another
+		let db_weight = <TestRuntime as frame_system::Config>::DbWeight::get();
+		let weight = db_weight.reads_writes(4, 4);
+		let first_lane =
+			OutboundLanes::<TestRuntime>::iter_keys().next().expect("lanes are opened; qed");
+		let second_lane = OutboundLanes::<TestRuntime>::iter_keys()
+			.find(|lane_id| *lane_id != first_lane)
+			.expect("lanes are opened; qed");
+		let is_pruned = |lane_id| {
+			!OutboundMessages::<TestRuntime>::contains_key(MessageKey { lane_id, nonce: 1 })
+		};
+
+		prune_delivered_messages::<TestRuntime, ()>(weight);
+		assert!(is_pruned(first_lane));
+		assert!(!is_pruned(second_lane));
+		assert_eq!(LastLaneWithPrunedMessages::<TestRuntime>::get(), Some(first_lane));
+
+		prune_delivered_messages::<TestRuntime, ()>(weight);
+		assert!(is_pruned(second_lane));
+		assert_eq!(LastLaneWithPrunedMessages::<TestRuntime>::get(), Some(second_lane));
+	});
+}
+
+#[test]
+fn lane_operating_mode_works() {
+	run_test(|| {
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_operating_mode(
+				RuntimeOrigin::signed(1),
+				test_lane_id(),
+				Some(MessagesOperatingMode::RejectingOutboundMessages),
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_operating_mode(
+				RuntimeOrigin::root(),
+				unknown_lane_id(),
+				Some(MessagesOperatingMode::RejectingOutboundMessages),
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::UnknownOutboundLane),
+		);
+
+		// when lane is rejecting outbound messages, it still accepts confirmations
+		send_regular_message();
+		get_ready_for_events();
+		assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(MessagesOperatingMode::RejectingOutboundMessages),
+		));
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::LaneOperatingModeChanged {
+					lane_id: test_lane_id(),
+					operating_mode: Some(MessagesOperatingMode::RejectingOutboundMessages),
+				}),
+				topics: vec![],
+			}],
+		);
+		assert_noop!(
+			send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD),
+			Error::<TestRuntime, ()>::NotOperatingNormally,
+		);
+		receive_messages_delivery_proof();
+
+		// when lane is halted, it rejects everything
+		assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(MessagesOperatingMode::Basic(BasicOperatingMode::Halted)),
+		));
+		assert_noop!(
+			send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD),
+			Error::<TestRuntime, ()>::NotOperatingNormally,
+		);
+		let proof = prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None);
+		assert_noop!(
+			Pallet::<TestRuntime>::receive_messages_proof(
+				RuntimeOrigin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			),
+			Error::<TestRuntime, ()>::LaneHalted,
+		);
+
+		// when lane operating mode is reset, it follows the pallet operating mode
+		assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			None,
+		));
+		assert_ok!(send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD));
+	});
+}
+
+#[test]
+fn outbound_messages_expire_after_lane_ttl() {
+	run_test(|| {
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_messages_ttl(
+				RuntimeOrigin::signed(1),
+				test_lane_id(),
+				Some(10),
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_messages_ttl(
+				RuntimeOrigin::root(),
+				unknown_lane_id(),
+				Some(10),
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::UnknownOutboundLane),
+		);
+
+		// message that has been sent before TTL is configured, never expires
+		send_regular_message();
+		assert_ok!(Pallet::<TestRuntime>::set_lane_messages_ttl(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(10),
+		));
+
+		// messages 2 and 3 are sent at block 2 and message 4 is sent at block 5
+		System::<TestRuntime>::set_block_number(2);
+		assert_ok!(send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD));
+		assert_ok!(send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD));
+		System::<TestRuntime>::set_block_number(5);
+		assert_ok!(send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD));
+
+		// message 1 is confirmed, so messages 2 and 3 are at the front of the queue
+		receive_messages_delivery_proof();
+
+		// messages of the opened lane never expire
+		System::<TestRuntime>::reset_events();
+		Pallet::<TestRuntime>::on_idle(100, Weight::MAX);
+		assert!(System::<TestRuntime>::events().is_empty());
+		assert_ok!(Pallet::<TestRuntime>::close_lane(RuntimeOrigin::root(), test_lane_id()));
+
+		// nothing expires before TTL
+		System::<TestRuntime>::reset_events();
+		Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+		assert!(System::<TestRuntime>::events().is_empty());
+		assert_eq!(
+			OutboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().oldest_unpruned_nonce,
+			2
+		);
+
+		// messages 2 and 3 expire at block 12
+		Pallet::<TestRuntime>::on_idle(12, Weight::MAX);
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::MessagesExpired {
+					lane_id: test_lane_id(),
+					begin: 2,
+					end: 3,
+				}),
+				topics: vec![],
+			}],
+		);
+		assert!(TestOnMessagesExpired::is_message_expired(test_lane_id(), 2));
+		assert!(TestOnMessagesExpired::is_message_expired(test_lane_id(), 3));
+		assert!(!TestOnMessagesExpired::is_message_expired(test_lane_id(), 4));
+		assert!(!OutboundMessages::<TestRuntime>::contains_key(MessageKey {
+			lane_id: test_lane_id(),
+			nonce: 3
+		}));
+		assert!(OutboundMessages::<TestRuntime>::contains_key(MessageKey {
+			lane_id: test_lane_id(),
+			nonce: 4
+		}));
+		assert_eq!(
+			OutboundLanes::<TestRuntime>::get(test_lane_id()).unwrap().oldest_unpruned_nonce,
+			4
+		);
+
+		// when TTL is removed, messages never expire
+		assert_ok!(Pallet::<TestRuntime>::set_lane_messages_ttl(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			None,
+		));
+		Pallet::<TestRuntime>::on_idle(100, Weight::MAX);
+		assert!(!TestOnMessagesExpired::is_message_expired(test_lane_id(), 4));
+	});
+}
+
+#[test]
+fn message_sent_at_is_removed_with_message_when_lane_ttl_is_removed() {
+	run_test(|| {
+		assert_ok!(Pallet::<TestRuntime>::set_lane_messages_ttl(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(10),
+		));
+		send_regular_message();
+		let key = MessageKey { lane_id: test_lane_id(), nonce: 1 };
+		assert!(OutboundMessagesSentAt::<TestRuntime>::contains_key(&key));
+
+		// when TTL is removed, the block at which message has been sent is kept
+		assert_ok!(Pallet::<TestRuntime>::set_lane_messages_ttl(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			None,
+		));
+		assert!(OutboundMessagesSentAt::<TestRuntime>::contains_key(&key));
+
+		// ... and it is removed when message delivery is confirmed
+		receive_messages_delivery_proof();
+		assert!(!OutboundMessages::<TestRuntime>::contains_key(&key));
+		assert!(!OutboundMessagesSentAt::<TestRuntime>::contains_key(&key));
+	});
+}
+
+#[test]
+fn lanes_with_expired_messages_are_processed_in_round_robin_order() {
+	run_test(|| {
+		let lanes = [test_lane_id(), LaneId::new(1, 5)];
+		assert_ok!(Pallet::<TestRuntime>::open_lane(RuntimeOrigin::root(), lanes[1]));
+		for lane_id in lanes {
+			assert_ok!(Pallet::<TestRuntime>::set_lane_messages_ttl(
+				RuntimeOrigin::root(),
+				lane_id,
+				Some(10),
+			));
+			assert_ok!(send_message::<TestRuntime, ()>(lane_id, REGULAR_PAYLOAD));
+			assert_ok!(Pallet::<TestRuntime>::close_lane(RuntimeOrigin::root(), lane_id));
+		}
+
+		// there's only enough weight to expire messages of the single lane, so lanes are
+		// processed one after another
+		let db_weight = <TestRuntime as frame_system::Config>::DbWeight::get();
+		let weight = db_weight.reads_writes(7, 4);
+		let first_lane = LaneMessagesTtl::<TestRuntime>::iter_keys()
+			.next()
+			.expect("lanes are configured; qed");
+		let second_lane = if first_lane == lanes[0] { lanes[1] } else { lanes[0] };
+
+		expire_outbound_messages::<TestRuntime, ()>(100, weight);
+		assert!(TestOnMessagesExpired::is_message_expired(first_lane, 1));
+		assert!(!TestOnMessagesExpired::is_message_expired(second_lane, 1));
+		assert_eq!(LastLaneWithExpiredMessages::<TestRuntime>::get(), Some(first_lane));
+
+		expire_outbound_messages::<TestRuntime, ()>(100, weight);
+		assert!(TestOnMessagesExpired::is_message_expired(second_lane, 1));
+		assert_eq!(LastLaneWithExpiredMessages::<TestRuntime>::get(), Some(second_lane));
+	});
+}
+
+#[test]
+fn lane_congestion_is_signaled() {
+	run_test(|| {
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_congestion_threshold(
+				RuntimeOrigin::signed(1),
+				test_lane_id(),
+				Some(2),
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::set_lane_congestion_threshold(
+				RuntimeOrigin::root(),
+				unknown_lane_id(),
+				Some(2),
+			),
+			Error::<TestRuntime, ()>::LanesManager(LanesManagerError::UnknownOutboundLane),
+		);
+		assert_ok!(Pallet::<TestRuntime>::set_lane_congestion_threshold(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			Some(2),
+		));
+
+		// lane is not congested until the threshold is reached
+		send_regular_message();
+		assert!(!TestOnLaneCongestion::is_lane_congested(test_lane_id()));
+
+		// lane is congested when the threshold is reached
+		get_ready_for_events();
+		assert_ok!(send_message::<TestRuntime, ()>(test_lane_id(), REGULAR_PAYLOAD));
+		assert!(TestOnLaneCongestion::is_lane_congested(test_lane_id()));
+		assert_eq!(
+			System::<TestRuntime>::events().last().map(|r| r.event.clone()),
+			Some(TestEvent::Messages(Event::LaneCongested {
+				lane_id: test_lane_id(),
+				queued_messages: 2,
+			})),
+		);
+
+		// lane is uncongested when the queue drains
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+			RuntimeOrigin::signed(1),
+			prepare_messages_delivery_proof(
+				test_lane_id(),
+				InboundLaneData {
+					state: LaneState::Opened,
+					last_confirmed_nonce: 1,
+					relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into(),
+				},
+			),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				messages_in_oldest_entry: 1,
+				total_messages: 1,
+				last_delivered_nonce: 1,
+			},
+		));
+		assert!(!TestOnLaneCongestion::is_lane_congested(test_lane_id()));
+		assert_eq!(
+			System::<TestRuntime>::events().last().map(|r| r.event.clone()),
+			Some(TestEvent::Messages(Event::LaneUncongested {
+				lane_id: test_lane_id(),
+				queued_messages: 1,
+			})),
+		);
+	});
+}
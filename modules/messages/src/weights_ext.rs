@@ -65,7 +65,9 @@ pub fn ensure_weights_are_correct<W: WeightInfoExt>() {
 	assert_ne!(receive_messages_proof_weight.ref_time(), 0);
 	assert_ne!(receive_messages_proof_weight.proof_size(), 0);
 	messages_proof_size_does_not_affect_proof_size::<W>();
+	messages_proof_size_affects_ref_time::<W>();
 	messages_count_does_not_affect_proof_size::<W>();
+	messages_count_affects_ref_time::<W>();
 
 	// verify `receive_message_proof` weight
 	let receive_messages_delivery_proof_weight = W::receive_messages_delivery_proof_weight(
@@ -170,6 +172,22 @@ fn messages_proof_size_does_not_affect_proof_size<W: WeightInfoExt>() {
 	);
 }
 
+/// Panics if `ref_time` of message delivery call doesn't grow with the message proof size.
+fn messages_proof_size_affects_ref_time<W: WeightInfoExt>() {
+	let dispatch_weight = Weight::zero();
+	let weight_when_proof_size_is_8k =
+		W::receive_messages_proof_weight(&PreComputedSize(8 * 1024), 1, dispatch_weight);
+	let weight_when_proof_size_is_16k =
+		W::receive_messages_proof_weight(&PreComputedSize(16 * 1024), 1, dispatch_weight);
+
+	assert!(
+		weight_when_proof_size_is_16k.ref_time() > weight_when_proof_size_is_8k.ref_time(),
+		"Larger messages proof requires more time to verify: {} must be larger than {}",
+		weight_when_proof_size_is_16k.ref_time(),
+		weight_when_proof_size_is_8k.ref_time(),
+	);
+}
+
 /// Panics if `proof_size` of message delivery call depends on the messages count.
 ///
 /// In practice, it will depend on the messages count, because most probably every
@@ -192,6 +210,23 @@ fn messages_count_does_not_affect_proof_size<W: WeightInfoExt>() {
 	);
 }
 
+/// Panics if `ref_time` of message delivery call doesn't grow with the messages count.
+fn messages_count_affects_ref_time<W: WeightInfoExt>() {
+	let messages_proof_size = PreComputedSize(8 * 1024);
+	let dispatch_weight = Weight::zero();
+	let weight_of_one_incoming_message =
+		W::receive_messages_proof_weight(&messages_proof_size, 1, dispatch_weight);
+	let weight_of_two_incoming_messages =
+		W::receive_messages_proof_weight(&messages_proof_size, 2, dispatch_weight);
+
+	assert!(
+		weight_of_two_incoming_messages.ref_time() > weight_of_one_incoming_message.ref_time(),
+		"Every incoming message requires some time to process: {} must be larger than {}",
+		weight_of_two_incoming_messages.ref_time(),
+		weight_of_one_incoming_message.ref_time(),
+	);
+}
+
 /// Panics if `proof_size` of delivery confirmation call depends on the delivery proof size.
 fn messages_delivery_proof_size_does_not_affect_proof_size<W: WeightInfoExt>() {
 	let relayers_state = UnrewardedRelayersState {
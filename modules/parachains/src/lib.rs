@@ -28,9 +28,12 @@ pub use weights::WeightInfo;
 pub use weights_ext::WeightInfoExt;
 
 use bp_header_chain::{HeaderChain, HeaderChainError};
-use bp_parachains::{parachain_head_storage_key_at_source, ParaInfo, ParaStoredHeaderData};
+use bp_parachains::{
+	parachain_head_storage_key_at_source, ParaConfig, ParaInfo, ParaStoredHeaderData,
+};
 use bp_polkadot_core::parachains::{ParaHash, ParaHead, ParaHeadsProof, ParaId};
 use bp_runtime::{Chain, HashOf, HeaderId, HeaderIdOf, Parachain};
+use codec::Encode;
 use frame_support::{dispatch::PostDispatchInfo, DefaultNoBound};
 use sp_std::{marker::PhantomData, vec::Vec};
 
@@ -38,8 +41,6 @@ use sp_std::{marker::PhantomData, vec::Vec};
 use bp_parachains::ParaStoredHeaderDataBuilder;
 #[cfg(feature = "runtime-benchmarks")]
 use bp_runtime::HeaderOf;
-#[cfg(feature = "runtime-benchmarks")]
-use codec::Encode;
 
 // Re-export in crate namespace for `construct_runtime!`.
 pub use call_ext::*;
@@ -142,6 +143,29 @@ pub mod pallet {
 			/// Parachain head hash.
 			parachain_head_hash: ParaHash,
 		},
+		/// Parachain is no longer tracked and all its heads have been purged.
+		ParachainUntracked {
+			/// Identifier of the parachain.
+			parachain: ParaId,
+		},
+		/// Parachain is tracked again.
+		ParachainTracked {
+			/// Identifier of the parachain.
+			parachain: ParaId,
+		},
+		/// Parachain configuration has been updated.
+		ParachainConfigUpdated {
+			/// Identifier of the parachain.
+			parachain: ParaId,
+			/// New parachain configuration.
+			config: ParaConfig,
+		},
+		/// The caller has provided head of parachain, tracking of which has been disabled by
+		/// the pallet owner.
+		DisabledParachainRejected {
+			/// Identifier of the parachain with disabled tracking.
+			parachain: ParaId,
+		},
 	}
 
 	#[pallet::error]
@@ -154,6 +178,8 @@ pub mod pallet {
 		HeaderChainStorageProof(HeaderChainError),
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
+		/// Maximal head data size of the parachain exceeds the `MaxParaHeadDataSize`.
+		InvalidParachainConfig,
 	}
 
 	/// Convenience trait for defining `BridgedChain` bounds.
@@ -230,7 +256,8 @@ pub mod pallet {
 		///
 		/// Keep in mind that the size of any tracked parachain header data must not exceed this
 		/// value. So if you're going to track multiple parachains, one of which is using large
-		/// hashes, you shall choose this maximal value.
+		/// hashes, you shall choose this maximal value. Lower per-parachain limits may be
+		/// configured using the `set_parachain_config` call.
 		///
 		/// There's no mandatory headers in this pallet, so it can't stall if there's some header
 		/// that exceeds this bound.
@@ -270,6 +297,34 @@ pub mod pallet {
 		MaxValues = MaybeMaxParachains<T, I>,
 	>;
 
+	/// Parachains configuration.
+	///
+	/// Parachains that are missing from this map are tracked and their head data size is
+	/// limited by the `MaxParaHeadDataSize`.
+	#[pallet::storage]
+	pub type ParasConfig<T: Config<I>, I: 'static = ()> = StorageMap<
+		Hasher = Blake2_128Concat,
+		Key = ParaId,
+		Value = ParaConfig,
+		QueryKind = OptionQuery,
+		OnEmpty = GetDefault,
+		MaxValues = MaybeMaxParachains<T, I>,
+	>;
+
+	/// Parachains that are no longer tracked by the pallet.
+	///
+	/// Heads of these parachains are rejected. Parachains are added to and removed from this
+	/// map by the `untrack_parachain` and `track_parachain` calls.
+	#[pallet::storage]
+	pub type UntrackedParachains<T: Config<I>, I: 'static = ()> = StorageMap<
+		Hasher = Blake2_128Concat,
+		Key = ParaId,
+		Value = (),
+		QueryKind = OptionQuery,
+		OnEmpty = GetDefault,
+		MaxValues = MaybeMaxParachains<T, I>,
+	>;
+
 	/// State roots of parachain heads which have been imported into the pallet.
 	#[pallet::storage]
 	pub type ImportedParaHeads<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
@@ -323,7 +378,10 @@ pub mod pallet {
 		///   GRANDPA pallet.
 		///
 		/// The call may succeed, but some heads may not be updated e.g. because pallet knows
-		/// better head or it isn't tracked by the pallet.
+		/// better head or it isn't tracked by the pallet. Every skipped head is reported with
+		/// a dedicated event (e.g. `UntrackedParachainRejected`, `DisabledParachainRejected`,
+		/// `RejectedObsoleteParachainHead` or `RejectedLargeParachainHead`), so that submitter
+		/// may find out why its submission has no effect.
 		#[pallet::call_index(0)]
 		#[pallet::weight(WeightInfoOf::<T, I>::submit_parachain_heads_weight(
 			T::DbWeight::get(),
@@ -427,6 +485,15 @@ pub mod pallet {
 							continue
 						},
 					};
+				if !Self::is_parachain_tracked(parachain) {
+					log::trace!(
+						target: LOG_TARGET,
+						"The head of parachain {:?} has been provided, but its tracking is disabled",
+						parachain,
+					);
+					Self::deposit_event(Event::DisabledParachainRejected { parachain });
+					continue
+				}
 
 				let update_result: Result<_, ()> =
 					ParasInfo::<T, I>::try_mutate(parachain, |stored_best_head| {
@@ -490,6 +557,84 @@ pub mod pallet {
 		) -> DispatchResult {
 			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)
 		}
+
+		/// Set configuration of given parachain or reset it to the default configuration.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(3)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_parachain_config(
+			origin: OriginFor<T>,
+			parachain: ParaId,
+			config: Option<ParaConfig>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				config.map_or(true, |config| config.max_head_data_size <=
+					T::MaxParaHeadDataSize::get()),
+				Error::<T, I>::InvalidParachainConfig,
+			);
+
+			ParasConfig::<T, I>::set(parachain, config);
+			let config = Self::parachain_config(parachain);
+			log::info!(
+				target: LOG_TARGET,
+				"Setting configuration of parachain {:?} to {:?}",
+				parachain,
+				config,
+			);
+			Self::deposit_event(Event::ParachainConfigUpdated { parachain, config });
+
+			Ok(())
+		}
+
+		/// Stop tracking given parachain and purge all its heads from the storage.
+		///
+		/// After this call, all submitted heads of the parachain are rejected until it is tracked
+		/// again using the `track_parachain` call. May only be called either by root, or by
+		/// `PalletOwner`.
+		#[pallet::call_index(4)]
+		#[pallet::weight((
+			T::DbWeight::get().writes(2u64.saturating_add(
+				2u64.saturating_mul(T::HeadsToKeep::get() as u64),
+			)),
+			DispatchClass::Operational,
+		))]
+		pub fn untrack_parachain(origin: OriginFor<T>, parachain: ParaId) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+
+			UntrackedParachains::<T, I>::insert(parachain, ());
+
+			// there are at most `HeadsToKeep` heads and hashes of the parachain in the storage
+			ParasInfo::<T, I>::remove(parachain);
+			let _ = ImportedParaHeads::<T, I>::clear_prefix(parachain, u32::MAX, None);
+			let _ = ImportedParaHashes::<T, I>::clear_prefix(parachain, u32::MAX, None);
+
+			log::info!(
+				target: LOG_TARGET,
+				"Stopped tracking parachain {:?} and purged its heads",
+				parachain,
+			);
+			Self::deposit_event(Event::ParachainUntracked { parachain });
+
+			Ok(())
+		}
+
+		/// Start tracking given parachain again.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(5)]
+		#[pallet::weight((T::DbWeight::get().writes(1), DispatchClass::Operational))]
+		pub fn track_parachain(origin: OriginFor<T>, parachain: ParaId) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+
+			UntrackedParachains::<T, I>::remove(parachain);
+
+			log::info!(target: LOG_TARGET, "Started tracking parachain {:?}", parachain);
+			Self::deposit_event(Event::ParachainTracked { parachain });
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
@@ -498,6 +643,17 @@ pub mod pallet {
 			ParasInfo::<T, I>::get(parachain)
 		}
 
+		/// Get configuration of the given parachain.
+		pub fn parachain_config(parachain: ParaId) -> ParaConfig {
+			ParasConfig::<T, I>::get(parachain)
+				.unwrap_or_else(|| ParaConfig { max_head_data_size: T::MaxParaHeadDataSize::get() })
+		}
+
+		/// Returns true if heads of the given parachain are accepted by the pallet.
+		pub fn is_parachain_tracked(parachain: ParaId) -> bool {
+			!UntrackedParachains::<T, I>::contains_key(parachain)
+		}
+
 		/// Get best finalized head data of the given parachain.
 		pub fn best_parachain_head(parachain: ParaId) -> Option<ParaStoredHeaderData> {
 			let best_para_head_hash = ParasInfo::<T, I>::get(parachain)?.best_head_hash.head_hash;
@@ -564,24 +720,30 @@ pub mod pallet {
 				return Err(())
 			}
 
-			// verify that the parachain head data size is <= `MaxParaHeadDataSize`
+			// verify that the parachain head data size is <= `MaxParaHeadDataSize` and that it
+			// fits the parachain-specific limit
+			let max_head_data_size = Self::parachain_config(parachain).max_head_data_size as usize;
+			let head_data_size = new_head_data.encoded_size();
 			let updated_head_data =
 				match StoredParaHeadDataOf::<T, I>::try_from_inner(new_head_data) {
-					Ok(updated_head_data) => updated_head_data,
-					Err(e) => {
+					Ok(updated_head_data) if head_data_size <= max_head_data_size =>
+						updated_head_data,
+					result => {
+						let maximal_size =
+							result.err().map_or(max_head_data_size, |e| e.maximal_size);
 						log::trace!(
 							target: LOG_TARGET,
 							"The parachain head can't be updated. The parachain head data size \
 							for {:?} is {}. It exceeds maximal configured size {}.",
 							parachain,
-							e.value_size,
-							e.maximal_size,
+							head_data_size,
+							maximal_size,
 						);
 
 						Self::deposit_event(Event::RejectedLargeParachainHead {
 							parachain,
 							parachain_head_hash: new_head_hash,
-							parachain_head_size: e.value_size as _,
+							parachain_head_size: head_data_size as _,
 						});
 
 						return Err(())
@@ -1060,6 +1222,10 @@ pub(crate) mod tests {
 							pallet_bridge_grandpa::Event::UpdatedBestFinalizedHeader {
 								number: 1,
 								hash: relay_1_hash,
+								set_id: 1,
+								is_mandatory: false,
+								justification_size: justification.encoded_size() as u32,
+								relayer: 1,
 								grandpa_info: StoredHeaderGrandpaInfo {
 									finality_proof: justification,
 									new_verification_context: None,
@@ -1201,6 +1367,10 @@ pub(crate) mod tests {
 							pallet_bridge_grandpa::Event::UpdatedBestFinalizedHeader {
 								number: 1,
 								hash: relay_1_hash,
+								set_id: 1,
+								is_mandatory: false,
+								justification_size: justification.encoded_size() as u32,
+								relayer: 1,
 								grandpa_info: StoredHeaderGrandpaInfo {
 									finality_proof: justification,
 									new_verification_context: None,
@@ -1254,6 +1424,10 @@ pub(crate) mod tests {
 							pallet_bridge_grandpa::Event::UpdatedBestFinalizedHeader {
 								number: 1,
 								hash: relay_1_hash,
+								set_id: 1,
+								is_mandatory: false,
+								justification_size: justification.encoded_size() as u32,
+								relayer: 1,
 								grandpa_info: StoredHeaderGrandpaInfo {
 									finality_proof: justification.clone(),
 									new_verification_context: None,
@@ -1295,6 +1469,10 @@ pub(crate) mod tests {
 							pallet_bridge_grandpa::Event::UpdatedBestFinalizedHeader {
 								number: 1,
 								hash: relay_1_hash,
+								set_id: 1,
+								is_mandatory: false,
+								justification_size: justification.encoded_size() as u32,
+								relayer: 1,
 								grandpa_info: StoredHeaderGrandpaInfo {
 									finality_proof: justification,
 									new_verification_context: None,
@@ -1377,6 +1555,164 @@ pub(crate) mod tests {
 		});
 	}
 
+	#[test]
+	fn respects_parachain_config() {
+		let (state_root, proof, parachains) =
+			prepare_parachain_heads_proof::<RegularParachainHeader>(vec![
+				(1, head_data(1, 5)),
+				(2, head_data(2, 5)),
+			]);
+		run_test(|| {
+			// lower head size limit of parachain#2
+			let head_size = stored_head_data(2, 5).encoded_size() as u32;
+			let limited_config = ParaConfig { max_head_data_size: head_size - 1 };
+			assert_noop!(
+				Pallet::<TestRuntime>::set_parachain_config(
+					RuntimeOrigin::signed(1),
+					ParaId(2),
+					Some(limited_config),
+				),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_parachain_config(
+					RuntimeOrigin::root(),
+					ParaId(2),
+					Some(ParaConfig { max_head_data_size: MAXIMAL_PARACHAIN_HEAD_DATA_SIZE + 1 }),
+				),
+				Error::<TestRuntime>::InvalidParachainConfig,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_parachain_config(
+				RuntimeOrigin::root(),
+				ParaId(2),
+				Some(limited_config),
+			));
+			assert_eq!(Pallet::<TestRuntime>::parachain_config(ParaId(2)), limited_config);
+
+			// head of parachain#1 is accepted and head of parachain#2 is rejected
+			initialize(state_root);
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				RuntimeOrigin::signed(1),
+				(0, test_relay_header(0, state_root).hash()),
+				parachains,
+				proof,
+			));
+			assert!(ParasInfo::<TestRuntime>::contains_key(ParaId(1)));
+			assert_eq!(ParasInfo::<TestRuntime>::get(ParaId(2)), None);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Parachains(Event::UpdatedParachainHead {
+							parachain: ParaId(1),
+							parachain_head_hash: head_data(1, 5).hash(),
+						}),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Parachains(Event::RejectedLargeParachainHead {
+							parachain: ParaId(2),
+							parachain_head_hash: head_data(2, 5).hash(),
+							parachain_head_size: head_size,
+						}),
+						topics: vec![],
+					},
+				],
+			);
+
+			// when configuration is reset, default configuration is used
+			assert_ok!(Pallet::<TestRuntime>::set_parachain_config(
+				RuntimeOrigin::root(),
+				ParaId(2),
+				None,
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_config(ParaId(2)),
+				ParaConfig { max_head_data_size: MAXIMAL_PARACHAIN_HEAD_DATA_SIZE },
+			);
+		});
+	}
+
+	#[test]
+	fn untrack_parachain_purges_its_heads() {
+		let (state_root, proof, parachains) =
+			prepare_parachain_heads_proof::<RegularParachainHeader>(vec![
+				(1, head_data(1, 5)),
+				(2, head_data(2, 5)),
+			]);
+		run_test(|| {
+			initialize(state_root);
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				RuntimeOrigin::signed(1),
+				(0, test_relay_header(0, state_root).hash()),
+				parachains.clone(),
+				proof.clone(),
+			));
+			assert!(ParasInfo::<TestRuntime>::contains_key(ParaId(1)));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::untrack_parachain(RuntimeOrigin::signed(1), ParaId(1)),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::untrack_parachain(RuntimeOrigin::root(), ParaId(1)));
+			assert_eq!(
+				System::<TestRuntime>::events().last().map(|record| record.event.clone()),
+				Some(TestEvent::Parachains(Event::ParachainUntracked { parachain: ParaId(1) })),
+			);
+
+			// heads of untracked parachain are purged
+			assert!(!Pallet::<TestRuntime>::is_parachain_tracked(ParaId(1)));
+			assert_eq!(ParasInfo::<TestRuntime>::get(ParaId(1)), None);
+			assert_eq!(
+				ImportedParaHeads::<TestRuntime>::get(ParaId(1), head_data(1, 5).hash()),
+				None
+			);
+			assert_eq!(ImportedParaHashes::<TestRuntime>::get(ParaId(1), 0), None);
+			// heads of other parachains are kept
+			assert!(ParasInfo::<TestRuntime>::contains_key(ParaId(2)));
+			assert!(ImportedParaHeads::<TestRuntime>::contains_key(
+				ParaId(2),
+				head_data(2, 5).hash()
+			));
+
+			// heads of untracked parachain are rejected
+			System::<TestRuntime>::reset_events();
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				RuntimeOrigin::signed(1),
+				(0, test_relay_header(0, state_root).hash()),
+				parachains,
+				proof,
+			));
+			assert_eq!(ParasInfo::<TestRuntime>::get(ParaId(1)), None);
+			assert_eq!(
+				System::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					TestEvent::Parachains(Event::DisabledParachainRejected {
+						parachain: ParaId(1)
+					}),
+					TestEvent::Parachains(Event::RejectedObsoleteParachainHead {
+						parachain: ParaId(2),
+						parachain_head_hash: head_data(2, 5).hash(),
+					}),
+				],
+			);
+
+			// parachain may be tracked again
+			assert_ok!(Pallet::<TestRuntime>::track_parachain(RuntimeOrigin::root(), ParaId(1)));
+			assert_eq!(
+				System::<TestRuntime>::events().last().map(|record| record.event.clone()),
+				Some(TestEvent::Parachains(Event::ParachainTracked { parachain: ParaId(1) })),
+			);
+			assert!(Pallet::<TestRuntime>::is_parachain_tracked(ParaId(1)));
+			assert!(!UntrackedParachains::<TestRuntime>::contains_key(ParaId(1)));
+		});
+	}
+
 	#[test]
 	fn prunes_old_heads() {
 		run_test(|| {
@@ -213,6 +213,9 @@ impl pallet_bridge_grandpa::Config<pallet_bridge_grandpa::Instance1> for TestRun
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = TestBridgedChain;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<2>;
+	type MaxFreeHeadersPerEpoch = ConstU32<32>;
+	type FreeHeadersEpochLength = ConstU32<8>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = HeadersToKeep;
 	type WeightInfo = ();
 }
@@ -221,6 +224,9 @@ impl pallet_bridge_grandpa::Config<pallet_bridge_grandpa::Instance2> for TestRun
 	type RuntimeEvent = RuntimeEvent;
 	type BridgedChain = TestBridgedChain;
 	type MaxFreeMandatoryHeadersPerBlock = ConstU32<2>;
+	type MaxFreeHeadersPerEpoch = ConstU32<32>;
+	type FreeHeadersEpochLength = ConstU32<8>;
+	type FreeHeadersInterval = ();
 	type HeadersToKeep = HeadersToKeep;
 	type WeightInfo = ();
 }
@@ -65,7 +65,13 @@ pub trait WeightInfoExt: WeightInfo {
 		let pruning_weight =
 			Self::parachain_head_pruning_weight(db_weight).saturating_mul(parachains_count as u64);
 
-		base_weight.saturating_add(proof_size_overhead).saturating_add(pruning_weight)
+		// weight of reading parachains configuration and checking whether they're tracked
+		let config_weight = db_weight.reads(2 * parachains_count as u64);
+
+		base_weight
+			.saturating_add(proof_size_overhead)
+			.saturating_add(pruning_weight)
+			.saturating_add(config_weight)
 	}
 
 	/// Returns weight of single parachain head storage update.
@@ -26,8 +26,8 @@ use bp_relayers::{
 use bp_runtime::StorageDoubleMapKeyProvider;
 use frame_support::fail;
 use sp_arithmetic::traits::{AtLeast32BitUnsigned, Zero};
-use sp_runtime::{traits::CheckedSub, Saturating};
-use sp_std::marker::PhantomData;
+use sp_runtime::{traits::CheckedSub, PerThing, Perbill, Saturating};
+use sp_std::{marker::PhantomData, vec::Vec};
 
 pub use pallet::*;
 pub use payment_adapter::DeliveryConfirmationPaymentsAdapter;
@@ -67,6 +67,14 @@ pub mod pallet {
 		type PaymentProcedure: PaymentProcedure<Self::AccountId, Self::Reward>;
 		/// Stake and slash scheme.
 		type StakeAndSlash: StakeAndSlash<Self::AccountId, BlockNumberFor<Self>, Self::Reward>;
+		/// Portion of the relayer stake that is slashed when the relayer submits an invalid or
+		/// useless bridge transaction.
+		type SlashFraction: Get<Perbill>;
+		/// Accumulated relayer reward that is automatically paid to the relayer in the
+		/// `on_idle` hook, without waiting for the `claim_rewards` call.
+		///
+		/// If `None`, rewards are only paid when claimed by the relayer.
+		type RewardPayoutThreshold: Get<Option<Self::Reward>>;
 		/// Pallet call weights.
 		type WeightInfo: WeightInfoExt;
 	}
@@ -74,6 +82,13 @@ pub mod pallet {
 	#[pallet::pallet]
 	pub struct Pallet<T>(PhantomData<T>);
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::pay_rewards_above_threshold(remaining_weight)
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Claim accumulated rewards.
@@ -207,6 +222,42 @@ pub mod pallet {
 				Ok(())
 			})
 		}
+
+		/// `Deregister` relayer before its registration lease has ended.
+		///
+		/// The whole relayer stake is slashed (burnt) as a penalty for leaving early. Use
+		/// the regular `deregister` call after `valid_till` to get the stake back.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::deregister())]
+		pub fn deregister_early(origin: OriginFor<T>) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			let registration = match RegisteredRelayers::<T>::get(&relayer) {
+				Some(registration) => registration,
+				None => fail!(Error::<T>::NotRegistered),
+			};
+
+			// after `valid_till` the regular `deregister` must be used
+			ensure!(
+				registration.valid_till >= frame_system::Pallet::<T>::block_number(),
+				Error::<T>::RegistrationLeaseEnded,
+			);
+
+			RegisteredRelayers::<T>::remove(&relayer);
+
+			let failed_to_slash = T::StakeAndSlash::slash_reserved(&relayer, registration.stake);
+			log::trace!(
+				target: LOG_TARGET,
+				"Relayer {:?} has left early and has been slashed for {:?}. Failed to slash: {:?}",
+				relayer,
+				registration.stake,
+				failed_to_slash,
+			);
+
+			Self::deposit_event(Event::<T>::SlashedAndDeregistered { relayer, registration });
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -237,6 +288,166 @@ pub mod pallet {
 			true
 		}
 
+		/// Returns all relayers with active registration at current block.
+		///
+		/// This function iterates over all registered relayers, so it should only be used
+		/// from runtime APIs and off-chain code.
+		pub fn active_registered_relayers() -> Vec<T::AccountId> {
+			RegisteredRelayers::<T>::iter_keys()
+				.filter(|relayer| Self::is_registration_active(relayer))
+				.collect()
+		}
+
+		/// Pay accumulated rewards that are above the `RewardPayoutThreshold`.
+		///
+		/// Rewards are iterated starting from the `RewardPayoutCursor`, so every reward is
+		/// eventually checked, even if there's not enough weight to process all rewards in
+		/// a single block. Returns the weight that has been used.
+		fn pay_rewards_above_threshold(remaining_weight: Weight) -> Weight {
+			let threshold = match T::RewardPayoutThreshold::get() {
+				Some(threshold) => threshold,
+				None => return Weight::zero(),
+			};
+
+			// we read and write the cursor + read every visited reward + pay some of them
+			let db_weight = T::DbWeight::get();
+			let mut used_weight = db_weight.reads_writes(1, 1);
+			let visit_weight = db_weight.reads(1);
+			let payout_weight = T::WeightInfo::claim_rewards();
+			// we need to be able to pay every visited reward
+			let required_weight = |used_weight: Weight| {
+				used_weight.saturating_add(visit_weight).saturating_add(payout_weight)
+			};
+			if remaining_weight.any_lt(required_weight(used_weight)) {
+				return Weight::zero()
+			}
+
+			let mut cursor = RewardPayoutCursor::<T>::get();
+			let mut rewards = match cursor.clone() {
+				Some((relayer, rewards_account_params)) => RelayerRewards::<T>::iter_from(
+					RelayerRewards::<T>::hashed_key_for(relayer, rewards_account_params),
+				),
+				None => RelayerRewards::<T>::iter(),
+			};
+			let mut reached_end = false;
+			let mut payouts = Vec::new();
+			loop {
+				if remaining_weight.any_lt(required_weight(used_weight)) {
+					break
+				}
+
+				let (relayer, rewards_account_params, reward) = match rewards.next() {
+					Some(entry) => entry,
+					None => {
+						reached_end = true;
+						break
+					},
+				};
+
+				used_weight.saturating_accrue(visit_weight);
+				if reward >= threshold {
+					used_weight.saturating_accrue(payout_weight);
+					payouts.push((relayer.clone(), rewards_account_params, reward));
+				}
+				cursor = Some((relayer, rewards_account_params));
+			}
+
+			// only start from the beginning if we have really visited all rewards
+			match cursor {
+				Some(cursor) if !reached_end => RewardPayoutCursor::<T>::put(cursor),
+				_ => RewardPayoutCursor::<T>::kill(),
+			}
+
+			for (relayer, rewards_account_params, reward) in payouts {
+				match T::PaymentProcedure::pay_reward(&relayer, rewards_account_params, reward) {
+					Ok(()) => {
+						RelayerRewards::<T>::remove(&relayer, rewards_account_params);
+						Self::deposit_event(Event::<T>::RewardPaid {
+							relayer,
+							rewards_account_params,
+							reward,
+						});
+					},
+					Err(e) => {
+						log::trace!(
+							target: LOG_TARGET,
+							"Failed to pay {:?} rewards to {:?}: {:?}",
+							rewards_account_params,
+							relayer,
+							e,
+						);
+					},
+				}
+			}
+
+			used_weight
+		}
+
+		/// Slash `SlashFraction` of the relayer stake.
+		///
+		/// The registration is kept, but since the remaining stake is lower than required, it
+		/// becomes inactive until the relayer tops it up using the `register` call. If nothing
+		/// is left at stake, the relayer is `deregistered`.
+		///
+		/// It may fail inside, but error is swallowed and we only log it.
+		pub fn slash(relayer: &T::AccountId, slash_destination: RewardsAccountParams) {
+			let mut registration = match RegisteredRelayers::<T>::get(relayer) {
+				Some(registration) => registration,
+				None => {
+					log::trace!(
+						target: crate::LOG_TARGET,
+						"Cannot slash unregistered relayer {:?}",
+						relayer,
+					);
+
+					return
+				},
+			};
+
+			let amount = T::SlashFraction::get().mul_floor(registration.stake);
+			let slashed =
+				match T::StakeAndSlash::repatriate_reserved(relayer, slash_destination, amount) {
+					Ok(failed_to_slash) => amount.saturating_sub(failed_to_slash),
+					Err(e) => {
+						log::debug!(
+							target: crate::LOG_TARGET,
+							"Failed to slash relayer account {:?}: {:?}. Maybe beneficiary account doesn't exist? \
+							Beneficiary: {:?}, amount: {:?}",
+							relayer,
+							e,
+							slash_destination,
+							amount,
+						);
+
+						return
+					},
+				};
+
+			registration.stake = registration.stake.saturating_sub(slashed);
+			if registration.stake.is_zero() {
+				RegisteredRelayers::<T>::remove(relayer);
+				Self::deposit_event(Event::<T>::SlashedAndDeregistered {
+					relayer: relayer.clone(),
+					registration,
+				});
+			} else {
+				RegisteredRelayers::<T>::insert(relayer, registration);
+				Self::deposit_event(Event::<T>::Slashed {
+					relayer: relayer.clone(),
+					slash_destination,
+					amount: slashed,
+				});
+			}
+
+			log::trace!(
+				target: crate::LOG_TARGET,
+				"Relayer account {:?} has been slashed for {:?}. Funds were deposited to {:?}",
+				relayer,
+				slashed,
+				slash_destination,
+			);
+		}
+
 		/// Slash and `deregister` relayer. This function slashes all staked balance.
 		///
 		/// It may fail inside, but error is swallowed and we only log it.
@@ -378,6 +589,18 @@ pub mod pallet {
 			/// Reward amount.
 			reward: T::Reward,
 		},
+		/// Message delivery reward has been split between the delivery and the confirmation
+		/// relayers.
+		RewardSplit {
+			/// Relayers have received reward from this account.
+			rewards_account_params: RewardsAccountParams,
+			/// Relayer that has confirmed delivery.
+			confirmation_relayer: T::AccountId,
+			/// Total reward of relayers that have delivered messages.
+			delivery_reward: T::Reward,
+			/// Reward of the relayer that has confirmed delivery.
+			confirmation_reward: T::Reward,
+		},
 		/// Relayer registration has been added or updated.
 		RegistrationUpdated {
 			/// Relayer account that has been registered.
@@ -390,6 +613,15 @@ pub mod pallet {
 			/// Relayer account that has been `deregistered`.
 			relayer: T::AccountId,
 		},
+		/// Portion of the relayer stake has been slashed.
+		Slashed {
+			/// Relayer account that has been slashed.
+			relayer: T::AccountId,
+			/// Account that has received slashed funds.
+			slash_destination: RewardsAccountParams,
+			/// Slashed amount.
+			amount: T::Reward,
+		},
 		/// Relayer has been slashed and `deregistered`.
 		SlashedAndDeregistered {
 			/// Relayer account that has been `deregistered`.
@@ -418,6 +650,8 @@ pub mod pallet {
 		NotRegistered,
 		/// Failed to `deregister` relayer, because lease is still active.
 		RegistrationIsStillActive,
+		/// Failed to `deregister` relayer early, because lease has already ended.
+		RegistrationLeaseEnded,
 	}
 
 	/// Map of the relayer => accumulated reward.
@@ -433,6 +667,12 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// The last reward, visited by the `on_idle` payout of rewards above the
+	/// `RewardPayoutThreshold`. Next payout continues from the next reward.
+	#[pallet::storage]
+	pub type RewardPayoutCursor<T: Config> =
+		StorageValue<_, (T::AccountId, RewardsAccountParams), OptionQuery>;
+
 	/// Relayers that have reserved some of their balance to get free priority boost
 	/// for their message delivery transactions.
 	///
@@ -459,8 +699,13 @@ mod tests {
 	use bp_messages::LaneId;
 	use bp_relayers::RewardsAccountOwner;
 	use frame_support::{
-		assert_noop, assert_ok,
-		traits::fungible::{Inspect, Mutate},
+		assert_noop, assert_ok, assert_storage_noop,
+		traits::{
+			fungible::{Inspect, Mutate},
+			fungibles::{Inspect as _, Mutate as _},
+			Hooks,
+		},
+		weights::Weight,
 	};
 	use frame_system::{EventRecord, Pallet as System, Phase};
 	use sp_runtime::DispatchError;
@@ -587,6 +832,85 @@ mod tests {
 		});
 	}
 
+	type PayLaneRewardFromAccountInAsset =
+		bp_relayers::PayRewardFromAccountInAsset<Assets, AccountId, TestAssetId>;
+
+	fn create_test_asset() {
+		assert_ok!(Assets::force_create(
+			RuntimeOrigin::root(),
+			TestAssetId::get(),
+			REGISTER_RELAYER,
+			true,
+			1,
+		));
+	}
+
+	#[test]
+	fn pay_reward_from_account_in_asset_actually_pays_reward() {
+		run_test(|| {
+			create_test_asset();
+
+			let in_lane_0 = RewardsAccountParams::new(
+				LaneId::new(1, 2),
+				*b"test",
+				RewardsAccountOwner::ThisChain,
+			);
+			let out_lane_1 = RewardsAccountParams::new(
+				LaneId::new(1, 3),
+				*b"test",
+				RewardsAccountOwner::BridgedChain,
+			);
+
+			let in_lane0_rewards_account =
+				bp_relayers::PayRewardFromAccount::<(), AccountId>::rewards_account(in_lane_0);
+			let out_lane1_rewards_account =
+				bp_relayers::PayRewardFromAccount::<(), AccountId>::rewards_account(out_lane_1);
+
+			Assets::mint_into(TestAssetId::get(), &in_lane0_rewards_account, 100).unwrap();
+			Assets::mint_into(TestAssetId::get(), &out_lane1_rewards_account, 100).unwrap();
+			assert_eq!(Assets::balance(TestAssetId::get(), in_lane0_rewards_account), 100);
+			assert_eq!(Assets::balance(TestAssetId::get(), out_lane1_rewards_account), 100);
+			assert_eq!(Assets::balance(TestAssetId::get(), 1), 0);
+
+			PayLaneRewardFromAccountInAsset::pay_reward(&1, in_lane_0, 100).unwrap();
+			assert_eq!(Assets::balance(TestAssetId::get(), in_lane0_rewards_account), 0);
+			assert_eq!(Assets::balance(TestAssetId::get(), out_lane1_rewards_account), 100);
+			assert_eq!(Assets::balance(TestAssetId::get(), 1), 100);
+
+			PayLaneRewardFromAccountInAsset::pay_reward(&1, out_lane_1, 100).unwrap();
+			assert_eq!(Assets::balance(TestAssetId::get(), in_lane0_rewards_account), 0);
+			assert_eq!(Assets::balance(TestAssetId::get(), out_lane1_rewards_account), 0);
+			assert_eq!(Assets::balance(TestAssetId::get(), 1), 200);
+
+			// native balances are not touched
+			assert_eq!(Balances::balance(&1), 0);
+		});
+	}
+
+	#[test]
+	fn pay_reward_from_account_in_asset_fails_if_rewards_account_has_no_funds() {
+		run_test(|| {
+			create_test_asset();
+
+			let rewards_account =
+				bp_relayers::PayRewardFromAccount::<(), AccountId>::rewards_account(
+					test_reward_account_param(),
+				);
+			Assets::mint_into(TestAssetId::get(), &rewards_account, 50).unwrap();
+			// native balance of the rewards account can't be used to pay rewards in asset
+			Balances::mint_into(&rewards_account, 100).unwrap();
+
+			assert!(PayLaneRewardFromAccountInAsset::pay_reward(
+				&1,
+				test_reward_account_param(),
+				100
+			)
+			.is_err());
+			assert_eq!(Assets::balance(TestAssetId::get(), rewards_account), 50);
+			assert_eq!(Assets::balance(TestAssetId::get(), 1), 0);
+		});
+	}
+
 	#[test]
 	fn register_fails_if_valid_till_is_a_past_block() {
 		run_test(|| {
@@ -834,6 +1158,274 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn rewards_above_threshold_are_paid_on_idle() {
+		run_test(|| {
+			get_ready_for_events();
+
+			let threshold = RewardPayoutThreshold::get().unwrap();
+			RelayerRewards::<TestRuntime>::insert(
+				REGULAR_RELAYER,
+				test_reward_account_param(),
+				threshold,
+			);
+			RelayerRewards::<TestRuntime>::insert(
+				REGISTER_RELAYER,
+				test_reward_account_param(),
+				threshold - 1,
+			);
+			RelayerRewards::<TestRuntime>::insert(
+				FAILING_RELAYER,
+				test_reward_account_param(),
+				threshold + 1,
+			);
+
+			Pallet::<TestRuntime>::on_idle(0, Weight::MAX);
+
+			// reward above threshold is paid
+			assert_eq!(
+				RelayerRewards::<TestRuntime>::get(REGULAR_RELAYER, test_reward_account_param()),
+				None
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(RewardPaid {
+						relayer: REGULAR_RELAYER,
+						rewards_account_params: test_reward_account_param(),
+						reward: threshold,
+					}),
+					topics: vec![],
+				}],
+			);
+			// reward below threshold is kept
+			assert_eq!(
+				RelayerRewards::<TestRuntime>::get(REGISTER_RELAYER, test_reward_account_param()),
+				Some(threshold - 1)
+			);
+			// reward that we have failed to pay is kept
+			assert_eq!(
+				RelayerRewards::<TestRuntime>::get(FAILING_RELAYER, test_reward_account_param()),
+				Some(threshold + 1)
+			);
+			// all rewards are visited, so the cursor is reset
+			assert_eq!(RewardPayoutCursor::<TestRuntime>::get(), None);
+		});
+	}
+
+	#[test]
+	fn rewards_payout_on_idle_continues_from_cursor() {
+		run_test(|| {
+			let threshold = RewardPayoutThreshold::get().unwrap();
+			let relayers = [REGULAR_RELAYER, 3, 4];
+			for relayer in relayers {
+				RelayerRewards::<TestRuntime>::insert(
+					relayer,
+					test_reward_account_param(),
+					threshold,
+				);
+			}
+
+			// weight is enough to pay only a single reward
+			let db_weight = DbWeight::get();
+			let remaining_weight = db_weight
+				.reads_writes(1, 1)
+				.saturating_add(db_weight.reads(1))
+				.saturating_add(<TestRuntime as Config>::WeightInfo::claim_rewards());
+			for paid_rewards in 1..=relayers.len() {
+				assert_eq!(Pallet::<TestRuntime>::on_idle(0, remaining_weight), remaining_weight);
+				assert_eq!(
+					RelayerRewards::<TestRuntime>::iter().count(),
+					relayers.len() - paid_rewards
+				);
+			}
+
+			// all rewards are paid, but we don't know that until the next call
+			assert!(RewardPayoutCursor::<TestRuntime>::get().is_some());
+			Pallet::<TestRuntime>::on_idle(0, remaining_weight);
+			assert_eq!(RewardPayoutCursor::<TestRuntime>::get(), None);
+		});
+	}
+
+	#[test]
+	fn rewards_payout_on_idle_does_nothing_if_reward_may_not_be_paid() {
+		run_test(|| {
+			let threshold = RewardPayoutThreshold::get().unwrap();
+			let relayers = [REGULAR_RELAYER, 3];
+			for relayer in relayers {
+				RelayerRewards::<TestRuntime>::insert(
+					relayer,
+					test_reward_account_param(),
+					threshold,
+				);
+			}
+
+			// pay the first reward to set the cursor
+			let db_weight = DbWeight::get();
+			let enough_weight = db_weight
+				.reads_writes(1, 1)
+				.saturating_add(db_weight.reads(1))
+				.saturating_add(<TestRuntime as Config>::WeightInfo::claim_rewards());
+			Pallet::<TestRuntime>::on_idle(0, enough_weight);
+			let cursor = RewardPayoutCursor::<TestRuntime>::get();
+			assert!(cursor.is_some());
+
+			// weight is enough to visit the reward, but not enough to pay it
+			let not_enough_weight = enough_weight.saturating_sub(Weight::from_parts(1, 0));
+			assert_eq!(Pallet::<TestRuntime>::on_idle(0, not_enough_weight), Weight::zero());
+			assert_eq!(RelayerRewards::<TestRuntime>::iter().count(), 1);
+			assert_eq!(RewardPayoutCursor::<TestRuntime>::get(), cursor);
+		});
+	}
+
+	#[test]
+	fn slash_slashes_portion_of_stake() {
+		run_test(|| {
+			get_ready_for_events();
+
+			let slash_destination = test_reward_account_param();
+			let slash_account = TestPaymentProcedure::rewards_account(slash_destination);
+			Balances::mint_into(&slash_account, ExistentialDeposit::get()).unwrap();
+
+			assert_ok!(Pallet::<TestRuntime>::register(
+				RuntimeOrigin::signed(REGISTER_RELAYER),
+				150
+			));
+			assert!(Pallet::<TestRuntime>::is_registration_active(&REGISTER_RELAYER));
+
+			let expected_slash = SlashFraction::get().mul_floor(Stake::get());
+			Pallet::<TestRuntime>::slash(&REGISTER_RELAYER, slash_destination);
+			assert_eq!(Balances::reserved_balance(REGISTER_RELAYER), Stake::get() - expected_slash);
+			assert_eq!(
+				Balances::free_balance(slash_account),
+				ExistentialDeposit::get() + expected_slash
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::registered_relayer(REGISTER_RELAYER),
+				Some(Registration { valid_till: 150, stake: Stake::get() - expected_slash }),
+			);
+			assert!(!Pallet::<TestRuntime>::is_registration_active(&REGISTER_RELAYER));
+
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::Slashed {
+						relayer: REGISTER_RELAYER,
+						slash_destination,
+						amount: expected_slash,
+					}),
+					topics: vec![],
+				}),
+			);
+
+			// relayer may top up its stake to make registration active again
+			assert_ok!(Pallet::<TestRuntime>::register(
+				RuntimeOrigin::signed(REGISTER_RELAYER),
+				150
+			));
+			assert_eq!(Balances::reserved_balance(REGISTER_RELAYER), Stake::get());
+			assert!(Pallet::<TestRuntime>::is_registration_active(&REGISTER_RELAYER));
+		});
+	}
+
+	#[test]
+	fn slash_ignores_unregistered_relayer() {
+		run_test(|| {
+			assert_storage_noop!(Pallet::<TestRuntime>::slash(
+				&REGISTER_RELAYER,
+				test_reward_account_param()
+			));
+		});
+	}
+
+	#[test]
+	fn deregister_early_fails_if_not_registered() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::deregister_early(RuntimeOrigin::signed(REGISTER_RELAYER)),
+				Error::<TestRuntime>::NotRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn deregister_early_fails_if_registration_lease_has_ended() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::register(
+				RuntimeOrigin::signed(REGISTER_RELAYER),
+				150
+			));
+
+			System::<TestRuntime>::set_block_number(151);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::deregister_early(RuntimeOrigin::signed(REGISTER_RELAYER)),
+				Error::<TestRuntime>::RegistrationLeaseEnded,
+			);
+		});
+	}
+
+	#[test]
+	fn deregister_early_slashes_stake() {
+		run_test(|| {
+			get_ready_for_events();
+
+			assert_ok!(Pallet::<TestRuntime>::register(
+				RuntimeOrigin::signed(REGISTER_RELAYER),
+				150
+			));
+
+			System::<TestRuntime>::set_block_number(100);
+
+			let reserved_balance = Balances::reserved_balance(REGISTER_RELAYER);
+			let free_balance = Balances::free_balance(REGISTER_RELAYER);
+			assert_ok!(Pallet::<TestRuntime>::deregister_early(RuntimeOrigin::signed(
+				REGISTER_RELAYER
+			)));
+			assert_eq!(
+				Balances::reserved_balance(REGISTER_RELAYER),
+				reserved_balance - Stake::get()
+			);
+			assert_eq!(Balances::free_balance(REGISTER_RELAYER), free_balance);
+			assert!(Pallet::<TestRuntime>::registered_relayer(REGISTER_RELAYER).is_none());
+
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::SlashedAndDeregistered {
+						relayer: REGISTER_RELAYER,
+						registration: Registration { valid_till: 150, stake: Stake::get() },
+					}),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn active_registered_relayers_works() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(150 - Lease::get());
+
+			RegisteredRelayers::<TestRuntime>::insert(
+				1,
+				Registration { valid_till: 151, stake: Stake::get() },
+			);
+			RegisteredRelayers::<TestRuntime>::insert(
+				2,
+				Registration { valid_till: 151, stake: Stake::get() - 1 },
+			);
+			RegisteredRelayers::<TestRuntime>::insert(
+				3,
+				Registration { valid_till: 150, stake: Stake::get() },
+			);
+			assert_eq!(Pallet::<TestRuntime>::active_registered_relayers(), vec![1]);
+		});
+	}
+
 	#[test]
 	fn is_registration_active_is_false_for_unregistered_relayer() {
 		run_test(|| {
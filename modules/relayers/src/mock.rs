@@ -22,16 +22,22 @@ use bp_messages::LaneId;
 use bp_relayers::{
 	PayRewardFromAccount, PaymentProcedure, RewardsAccountOwner, RewardsAccountParams,
 };
-use frame_support::{parameter_types, traits::fungible::Mutate, weights::RuntimeDbWeight};
+use frame_support::{
+	parameter_types,
+	traits::{fungible::Mutate, AsEnsureOriginWithArg, ConstU64},
+	weights::RuntimeDbWeight,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
 use sp_core::H256;
 use sp_runtime::{
 	traits::{BlakeTwo256, ConstU32, IdentityLookup},
-	BuildStorage,
+	BuildStorage, Perbill,
 };
 
 pub type AccountId = u64;
 pub type Balance = u64;
 pub type BlockNumber = u64;
+pub type AssetId = u32;
 
 pub type TestStakeAndSlash = pallet_bridge_relayers::StakeAndSlashNamed<
 	AccountId,
@@ -49,6 +55,7 @@ frame_support::construct_runtime! {
 	{
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 		Relayers: pallet_bridge_relayers::{Pallet, Call, Event<T>},
 	}
 }
@@ -59,6 +66,9 @@ parameter_types! {
 	pub const ReserveId: [u8; 8] = *b"brdgrlrs";
 	pub const Stake: Balance = 1_000;
 	pub const Lease: BlockNumber = 8;
+	pub const SlashFraction: Perbill = Perbill::from_percent(10);
+	pub const RewardPayoutThreshold: Option<Balance> = Some(100);
+	pub const TestAssetId: AssetId = 1;
 }
 
 impl frame_system::Config for TestRuntime {
@@ -103,11 +113,36 @@ impl pallet_balances::Config for TestRuntime {
 	type MaxFreezes = ConstU32<0>;
 }
 
+impl pallet_assets::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = ConstU32<5>;
+	type AssetId = AssetId;
+	type AssetIdParameter = AssetId;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU64<1>;
+	type AssetAccountDeposit = ConstU64<1>;
+	type MetadataDepositBase = ConstU64<1>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type ApprovalDeposit = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
 impl pallet_bridge_relayers::Config for TestRuntime {
 	type RuntimeEvent = RuntimeEvent;
 	type Reward = Balance;
 	type PaymentProcedure = TestPaymentProcedure;
 	type StakeAndSlash = TestStakeAndSlash;
+	type SlashFraction = SlashFraction;
+	type RewardPayoutThreshold = RewardPayoutThreshold;
 	type WeightInfo = ();
 }
 
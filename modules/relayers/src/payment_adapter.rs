@@ -16,7 +16,7 @@
 
 //! Code that allows relayers pallet to be used as a payment mechanism for the messages pallet.
 
-use crate::{Config, Pallet};
+use crate::{Config, Event, Pallet};
 
 use bp_messages::{
 	source_chain::{DeliveryConfirmationPayments, RelayersRewards},
@@ -25,21 +25,45 @@ use bp_messages::{
 use bp_relayers::{RewardsAccountOwner, RewardsAccountParams};
 use bp_runtime::Chain;
 use frame_support::{sp_runtime::SaturatedConversion, traits::Get};
-use sp_arithmetic::traits::{Saturating, Zero};
+use sp_arithmetic::{
+	traits::{Saturating, Zero},
+	PerThing, Perbill,
+};
 use sp_std::{collections::vec_deque::VecDeque, marker::PhantomData, ops::RangeInclusive};
 
 /// Adapter that allows relayers pallet to be used as a delivery+dispatch payment mechanism
 /// for the messages pallet.
-pub struct DeliveryConfirmationPaymentsAdapter<T, MI, DeliveryReward>(
-	PhantomData<(T, MI, DeliveryReward)>,
+///
+/// The `DeliveryReward` is paid for every delivered message. The `ConfirmationRewardShare`
+/// of this reward goes to the relayer that has confirmed delivery and the rest goes to the
+/// relayer that has delivered the message. By default, the whole reward goes to the
+/// delivery relayer.
+pub struct DeliveryConfirmationPaymentsAdapter<T, MI, DeliveryReward, ConfirmationRewardShare = ()>(
+	PhantomData<(T, MI, DeliveryReward, ConfirmationRewardShare)>,
 );
 
-impl<T, MI, DeliveryReward> DeliveryConfirmationPayments<T::AccountId>
-	for DeliveryConfirmationPaymentsAdapter<T, MI, DeliveryReward>
+impl<T, MI, DeliveryReward, ConfirmationRewardShare>
+	DeliveryConfirmationPaymentsAdapter<T, MI, DeliveryReward, ConfirmationRewardShare>
+where
+	T: Config,
+	DeliveryReward: Get<T::Reward>,
+	ConfirmationRewardShare: Get<Perbill>,
+{
+	/// Returns per-message rewards of the delivery and the confirmation relayers.
+	fn split_reward() -> (T::Reward, T::Reward) {
+		let reward = DeliveryReward::get();
+		let confirmation_reward = ConfirmationRewardShare::get().mul_floor(reward);
+		(reward.saturating_sub(confirmation_reward), confirmation_reward)
+	}
+}
+
+impl<T, MI, DeliveryReward, ConfirmationRewardShare> DeliveryConfirmationPayments<T::AccountId>
+	for DeliveryConfirmationPaymentsAdapter<T, MI, DeliveryReward, ConfirmationRewardShare>
 where
 	T: Config + pallet_bridge_messages::Config<MI>,
 	MI: 'static,
 	DeliveryReward: Get<T::Reward>,
+	ConfirmationRewardShare: Get<Perbill>,
 {
 	type Error = &'static str;
 
@@ -53,6 +77,7 @@ where
 			bp_messages::calc_relayers_rewards::<T::AccountId>(messages_relayers, received_range);
 		let rewarded_relayers = relayers_rewards.len();
 
+		let (delivery_reward, confirmation_reward) = Self::split_reward();
 		register_relayers_rewards::<T>(
 			confirmation_relayer,
 			relayers_rewards,
@@ -61,11 +86,16 @@ where
 				T::BridgedChain::ID,
 				RewardsAccountOwner::BridgedChain,
 			),
-			DeliveryReward::get(),
+			delivery_reward,
+			confirmation_reward,
 		);
 
 		rewarded_relayers as _
 	}
+
+	fn delivery_reward(_lane_id: LaneId, _nonce: MessageNonce) -> Option<u128> {
+		Some(Self::split_reward().0.saturated_into())
+	}
 }
 
 // Update rewards to given relayers, optionally rewarding confirmation relayer.
@@ -74,13 +104,20 @@ fn register_relayers_rewards<T: Config>(
 	relayers_rewards: RelayersRewards<T::AccountId>,
 	lane_id: RewardsAccountParams,
 	delivery_fee: T::Reward,
+	confirmation_fee: T::Reward,
 ) {
 	// reward every relayer except `confirmation_relayer`
+	let mut delivery_relayers_reward = T::Reward::zero();
+	let mut confirmation_reward = T::Reward::zero();
 	let mut confirmation_relayer_reward = T::Reward::zero();
 	for (relayer, messages) in relayers_rewards {
 		// sane runtime configurations guarantee that the number of messages will be below
 		// `u32::MAX`
-		let relayer_reward = T::Reward::saturated_from(messages).saturating_mul(delivery_fee);
+		let messages = T::Reward::saturated_from(messages);
+		let relayer_reward = messages.saturating_mul(delivery_fee);
+		delivery_relayers_reward = delivery_relayers_reward.saturating_add(relayer_reward);
+		confirmation_reward =
+			confirmation_reward.saturating_add(messages.saturating_mul(confirmation_fee));
 
 		if relayer != *confirmation_relayer {
 			Pallet::<T>::register_relayer_reward(lane_id, &relayer, relayer_reward);
@@ -94,8 +131,18 @@ fn register_relayers_rewards<T: Config>(
 	Pallet::<T>::register_relayer_reward(
 		lane_id,
 		confirmation_relayer,
-		confirmation_relayer_reward,
+		confirmation_relayer_reward.saturating_add(confirmation_reward),
 	);
+
+	// the reward is only split if the confirmation relayer has received its share
+	if !confirmation_reward.is_zero() {
+		Pallet::<T>::deposit_event(Event::<T>::RewardSplit {
+			rewards_account_params: lane_id,
+			confirmation_relayer: confirmation_relayer.clone(),
+			delivery_reward: delivery_relayers_reward,
+			confirmation_reward,
+		});
+	}
 }
 
 #[cfg(test)]
@@ -119,6 +166,7 @@ mod tests {
 				relayers_rewards(),
 				test_reward_account_param(),
 				50,
+				0,
 			);
 
 			assert_eq!(
@@ -135,11 +183,15 @@ mod tests {
 	#[test]
 	fn confirmation_relayer_is_not_rewarded_if_it_has_not_delivered_any_messages() {
 		run_test(|| {
+			System::set_block_number(1);
+			System::reset_events();
+
 			register_relayers_rewards::<TestRuntime>(
 				&RELAYER_3,
 				relayers_rewards(),
 				test_reward_account_param(),
 				50,
+				0,
 			);
 
 			assert_eq!(
@@ -154,6 +206,64 @@ mod tests {
 				RelayerRewards::<TestRuntime>::get(RELAYER_3, test_reward_account_param()),
 				None
 			);
+			assert!(!System::events().iter().any(|record| matches!(
+				record.event,
+				RuntimeEvent::Relayers(Event::RewardSplit { .. })
+			)));
+		});
+	}
+
+	#[test]
+	fn confirmation_relayer_receives_its_share_of_reward() {
+		run_test(|| {
+			System::set_block_number(1);
+			System::reset_events();
+
+			register_relayers_rewards::<TestRuntime>(
+				&RELAYER_3,
+				relayers_rewards(),
+				test_reward_account_param(),
+				40,
+				10,
+			);
+
+			assert_eq!(
+				RelayerRewards::<TestRuntime>::get(RELAYER_1, test_reward_account_param()),
+				Some(80)
+			);
+			assert_eq!(
+				RelayerRewards::<TestRuntime>::get(RELAYER_2, test_reward_account_param()),
+				Some(120)
+			);
+			assert_eq!(
+				RelayerRewards::<TestRuntime>::get(RELAYER_3, test_reward_account_param()),
+				Some(50)
+			);
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(RuntimeEvent::Relayers(Event::RewardSplit {
+					rewards_account_params: test_reward_account_param(),
+					confirmation_relayer: RELAYER_3,
+					delivery_reward: 200,
+					confirmation_reward: 50,
+				})),
+			);
 		});
 	}
+
+	#[test]
+	fn delivery_reward_is_split_using_confirmation_reward_share() {
+		type Adapter = DeliveryConfirmationPaymentsAdapter<
+			TestRuntime,
+			(),
+			frame_support::traits::ConstU64<100>,
+			ConfirmationRewardShare,
+		>;
+
+		frame_support::parameter_types! {
+			const ConfirmationRewardShare: Perbill = Perbill::from_percent(25);
+		}
+
+		assert_eq!(Adapter::split_reward(), (75, 25));
+	}
 }
@@ -68,6 +68,10 @@ where
 			BalanceStatus::Free,
 		)
 	}
+
+	fn slash_reserved(relayer: &AccountId, amount: Currency::Balance) -> Currency::Balance {
+		Currency::slash_reserved_named(&ReserveId::get(), relayer, amount).1
+	}
 }
 
 #[cfg(test)]
@@ -124,6 +128,30 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn slash_reserved_works() {
+		run_test(|| {
+			assert_eq!(TestStakeAndSlash::slash_reserved(&1, test_stake()), test_stake());
+			assert_eq!(Balances::free_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(1), 0);
+
+			Balances::mint_into(&2, test_stake() * 2).unwrap();
+			TestStakeAndSlash::reserve(&2, test_stake() / 3).unwrap();
+			assert_eq!(
+				TestStakeAndSlash::slash_reserved(&2, test_stake()),
+				test_stake() - test_stake() / 3
+			);
+			assert_eq!(Balances::free_balance(2), test_stake() * 2 - test_stake() / 3);
+			assert_eq!(Balances::reserved_balance(2), 0);
+
+			Balances::mint_into(&3, test_stake() * 2).unwrap();
+			TestStakeAndSlash::reserve(&3, test_stake()).unwrap();
+			assert_eq!(TestStakeAndSlash::slash_reserved(&3, test_stake()), 0);
+			assert_eq!(Balances::free_balance(3), test_stake());
+			assert_eq!(Balances::reserved_balance(3), 0);
+		})
+	}
+
 	#[test]
 	fn repatriate_reserved_works() {
 		run_test(|| {
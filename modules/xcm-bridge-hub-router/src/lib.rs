@@ -40,9 +40,12 @@ pub mod weights;
 
 mod mock;
 
-/// The factor that is used to increase current message fee factor when bridge experiencing
-/// some lags.
-const EXPONENTIAL_FEE_BASE: FixedU128 = FixedU128::from_rational(105, 100); // 1.05
+/// The factor that may be used to increase current message fee factor when bridge experiencing
+/// some lags and to decrease it when the bridge is uncongested again.
+///
+/// It may be used as a value for both `Config::FeeFactorIncreaseBase` and
+/// `Config::FeeFactorDecreaseBase`.
+pub const EXPONENTIAL_FEE_BASE: FixedU128 = FixedU128::from_rational(105, 100); // 1.05
 /// The factor that is used to increase current message fee factor for every sent kilobyte.
 const MESSAGE_SIZE_FEE_BASE: FixedU128 = FixedU128::from_rational(1, 1000); // 0.001
 
@@ -90,6 +93,19 @@ pub mod pallet {
 		type ByteFee: Get<u128>;
 		/// Asset that is used to paid bridge fee.
 		type FeeAsset: Get<AssetId>;
+
+		/// The factor that is used to increase current message fee factor when the queue with
+		/// sibling bridge hub is congested. Must be larger than one.
+		type FeeFactorIncreaseBase: Get<FixedU128>;
+		/// The factor that is used to decrease current message fee factor at every block when
+		/// the queue with sibling bridge hub is uncongested. Must be larger than one.
+		type FeeFactorDecreaseBase: Get<FixedU128>;
+		/// Maximal value of the message fee factor.
+		type MaxFeeFactor: Get<FixedU128>;
+
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self, I>>
+			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
 	}
 
 	#[pallet::pallet]
@@ -105,7 +121,7 @@ pub mod pallet {
 
 			DeliveryFeeFactor::<T, I>::mutate(|f| {
 				let previous_factor = *f;
-				*f = InitialFactor::get().max(*f / EXPONENTIAL_FEE_BASE);
+				*f = InitialFactor::get().max(*f / T::FeeFactorDecreaseBase::get());
 				if previous_factor != *f {
 					log::info!(
 						target: LOG_TARGET,
@@ -114,6 +130,8 @@ pub mod pallet {
 						f,
 					);
 
+					Self::deposit_event(Event::DeliveryFeeFactorDecreased { new_value: *f });
+
 					T::WeightInfo::on_initialize_when_non_congested()
 				} else {
 					// we have not actually updated the `DeliveryFeeFactor`, so we may deduct
@@ -123,6 +141,17 @@ pub mod pallet {
 				}
 			})
 		}
+
+		fn integrity_test() {
+			assert!(
+				T::FeeFactorIncreaseBase::get() > FixedU128::one(),
+				"FeeFactorIncreaseBase must be larger than one",
+			);
+			assert!(
+				T::FeeFactorDecreaseBase::get() > FixedU128::one(),
+				"FeeFactorDecreaseBase must be larger than one",
+			);
+		}
 	}
 
 	/// Initialization value for the delivery fee factor.
@@ -177,20 +206,39 @@ pub mod pallet {
 			// ok - we need to increase the fee factor, let's do that
 			let message_size_factor = FixedU128::from_u32(message_size.saturating_div(1024))
 				.saturating_mul(MESSAGE_SIZE_FEE_BASE);
-			let total_factor = EXPONENTIAL_FEE_BASE.saturating_add(message_size_factor);
+			let total_factor = T::FeeFactorIncreaseBase::get().saturating_add(message_size_factor);
 			DeliveryFeeFactor::<T, I>::mutate(|f| {
 				let previous_factor = *f;
-				*f = f.saturating_mul(total_factor);
-				log::info!(
-					target: LOG_TARGET,
-					"Bridge queue is congested. Increased fee factor from {} to {}",
-					previous_factor,
-					f,
-				);
+				*f = f.saturating_mul(total_factor).min(T::MaxFeeFactor::get());
+				if previous_factor != *f {
+					log::info!(
+						target: LOG_TARGET,
+						"Bridge queue is congested. Increased fee factor from {} to {}",
+						previous_factor,
+						f,
+					);
+
+					Self::deposit_event(Event::DeliveryFeeFactorIncreased { new_value: *f });
+				}
 				*f
 			});
 		}
 	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Delivery fee factor has been decreased.
+		DeliveryFeeFactorDecreased {
+			/// New value of the `DeliveryFeeFactor`.
+			new_value: FixedU128,
+		},
+		/// Delivery fee factor has been increased.
+		DeliveryFeeFactorIncreased {
+			/// New value of the `DeliveryFeeFactor`.
+			new_value: FixedU128,
+		},
+	}
 }
 
 /// We'll be using `SovereignPaidRemoteExporter` to send remote messages over the sibling/child
@@ -288,6 +336,11 @@ mod tests {
 
 	use frame_support::traits::Hooks;
 
+	#[test]
+	fn integrity_test_passes_with_mock_configuration() {
+		XcmBridgeHubRouter::integrity_test();
+	}
+
 	#[test]
 	fn initial_fee_factor_is_one() {
 		run_test(|| {
@@ -318,6 +371,13 @@ mod tests {
 				XcmBridgeHubRouter::on_initialize(One::one());
 			}
 
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(RuntimeEvent::XcmBridgeHubRouter(Event::DeliveryFeeFactorDecreased {
+					new_value: FixedU128::one(),
+				})),
+			);
+
 			// verify that it doesn't decreases anymore
 			XcmBridgeHubRouter::on_initialize(One::one());
 			assert_eq!(XcmBridgeHubRouter::delivery_fee_factor(), FixedU128::one());
@@ -424,6 +484,36 @@ mod tests {
 
 			assert!(TestToBridgeHubSender::is_message_sent());
 			assert!(old_delivery_fee_factor < XcmBridgeHubRouter::delivery_fee_factor());
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(RuntimeEvent::XcmBridgeHubRouter(Event::DeliveryFeeFactorIncreased {
+					new_value: XcmBridgeHubRouter::delivery_fee_factor(),
+				})),
+			);
+		});
+	}
+
+	#[test]
+	fn fee_factor_never_exceeds_maximal_value() {
+		run_test(|| {
+			TestWithBridgeHubChannel::make_congested();
+			DeliveryFeeFactor::<TestRuntime, ()>::put(MaxFeeFactor::get());
+
+			assert_eq!(
+				send_xcm::<XcmBridgeHubRouter>(
+					MultiLocation::new(
+						2,
+						X2(GlobalConsensus(BridgedNetworkId::get()), Parachain(1000))
+					),
+					vec![ClearOrigin].into(),
+				)
+				.map(drop),
+				Ok(()),
+			);
+
+			assert!(TestToBridgeHubSender::is_message_sent());
+			assert_eq!(XcmBridgeHubRouter::delivery_fee_factor(), MaxFeeFactor::get());
+			assert_eq!(System::events(), vec![]);
 		});
 	}
 }
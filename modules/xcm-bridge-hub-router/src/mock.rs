@@ -17,13 +17,14 @@
 #![cfg(test)]
 
 use crate as pallet_xcm_bridge_hub_router;
+use crate::EXPONENTIAL_FEE_BASE;
 
 use bp_xcm_bridge_hub_router::LocalXcmChannel;
 use frame_support::{construct_runtime, parameter_types};
 use sp_core::H256;
 use sp_runtime::{
 	traits::{BlakeTwo256, ConstU128, IdentityLookup},
-	BuildStorage,
+	BuildStorage, FixedU128,
 };
 use xcm::prelude::*;
 
@@ -41,7 +42,7 @@ construct_runtime! {
 	pub enum TestRuntime
 	{
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
-		XcmBridgeHubRouter: pallet_xcm_bridge_hub_router::{Pallet, Storage},
+		XcmBridgeHubRouter: pallet_xcm_bridge_hub_router::{Pallet, Storage, Event<T>},
 	}
 }
 
@@ -51,6 +52,8 @@ parameter_types! {
 	pub UniversalLocation: InteriorMultiLocation = X2(GlobalConsensus(ThisNetworkId::get()), Parachain(1000));
 	pub SiblingBridgeHubLocation: MultiLocation = ParentThen(X1(Parachain(1002))).into();
 	pub BridgeFeeAsset: AssetId = MultiLocation::parent().into();
+	pub const FeeFactorBase: FixedU128 = EXPONENTIAL_FEE_BASE;
+	pub const MaxFeeFactor: FixedU128 = FixedU128::from_rational(2, 1);
 }
 
 impl frame_system::Config for TestRuntime {
@@ -92,6 +95,12 @@ impl pallet_xcm_bridge_hub_router::Config<()> for TestRuntime {
 	type BaseFee = ConstU128<BASE_FEE>;
 	type ByteFee = ConstU128<BYTE_FEE>;
 	type FeeAsset = BridgeFeeAsset;
+
+	type FeeFactorIncreaseBase = FeeFactorBase;
+	type FeeFactorDecreaseBase = FeeFactorBase;
+	type MaxFeeFactor = MaxFeeFactor;
+
+	type RuntimeEvent = RuntimeEvent;
 }
 
 pub struct TestToBridgeHubSender;
@@ -140,5 +149,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 
 /// Run pallet test.
 pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
-	new_test_ext().execute_with(test)
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		System::reset_events();
+
+		test()
+	})
 }
@@ -18,7 +18,7 @@
 //! exporter at the sending bridge hub. Internally, it just enqueues outbound blob
 //! in the messages pallet queue.
 
-use crate::{Config, Pallet, XcmAsPlainPayload, LOG_TARGET};
+use crate::{Config, Event, Pallet, XcmAsPlainPayload, LOG_TARGET};
 
 use bp_messages::{source_chain::MessagesBridge, LaneId};
 use frame_support::traits::Get;
@@ -43,7 +43,7 @@ where
 		OutboundPayload = XcmAsPlainPayload,
 	>,
 {
-	type Ticket = (LaneId, XcmAsPlainPayload, XcmHash);
+	type Ticket = (LaneId, XcmAsPlainPayload, XcmHash, MultiAssets);
 
 	fn validate(
 		network: NetworkId,
@@ -89,11 +89,11 @@ where
 		)
 		.map_err(|_| SendError::Unroutable)?;
 
-		Ok(((bridge_locations.lane_id, blob, id), price))
+		Ok(((bridge_locations.lane_id, blob, id, price.clone()), price))
 	}
 
 	fn deliver(
-		(lane_id, blob, id): (LaneId, XcmAsPlainPayload, XcmHash),
+		(lane_id, blob, id, fee): (LaneId, XcmAsPlainPayload, XcmHash, MultiAssets),
 	) -> Result<XcmHash, SendError> {
 		let send_result = MessagesPallet::<T, I>::send_message(lane_id, blob);
 
@@ -106,6 +106,12 @@ where
 					lane_id,
 					artifacts.nonce,
 				);
+				Self::deposit_event(Event::XcmMessageEnqueued {
+					lane_id,
+					nonce: artifacts.nonce,
+					message_id: id,
+					fee,
+				});
 			},
 			Err(error) => {
 				log::debug!(
@@ -163,7 +169,8 @@ mod tests {
 				.is_empty());
 
 			// now let's try to enqueue message using our `ExportXcm` implementation
-			export_xcm::<XcmOverBridge>(
+			System::set_block_number(1);
+			let (message_id, fee) = export_xcm::<XcmOverBridge>(
 				BridgedRelayNetwork::get(),
 				0,
 				locations.bridge_origin_universal_location,
@@ -179,6 +186,18 @@ mod tests {
 				.unwrap()
 				.queued_messages()
 				.is_empty());
+
+			// and that the message identifier and fee have been reported
+			assert_eq!(fee, MessageExportPrice::get());
+			assert_eq!(
+				System::events().last().map(|r| r.event.clone()),
+				Some(RuntimeEvent::XcmOverBridge(Event::XcmMessageEnqueued {
+					lane_id: locations.lane_id,
+					nonce: 1,
+					message_id,
+					fee,
+				})),
+			);
 		});
 	}
 }
@@ -274,9 +274,9 @@ pub mod pallet {
 				.any_state_outbound_lane(locations.lane_id)
 				.map_err(Error::<T, I>::LanesManager)?;
 
-			// now prune queued messages
+			// now prune queued and delivered messages
 			let mut pruned_messages = 0;
-			for _ in outbound_lane.queued_messages() {
+			for _ in outbound_lane.stored_messages() {
 				if pruned_messages == may_prune_messages {
 					break
 				}
@@ -285,8 +285,8 @@ pub mod pallet {
 				pruned_messages += 1;
 			}
 
-			// if there are outbound messages in the queue, just update states and early exit
-			if !outbound_lane.queued_messages().is_empty() {
+			// if there are outbound messages in the storage, just update states and early exit
+			if !outbound_lane.stored_messages().is_empty() {
 				// update lanes state. Under normal circumstances, following calls shall never fail
 				inbound_lane.set_state(LaneState::Closed);
 				outbound_lane.set_state(LaneState::Closed);
@@ -417,6 +417,17 @@ pub mod pallet {
 			/// Number of pruned messages during the close call.
 			pruned_messages: MessageNonce,
 		},
+		/// XCM message has been enqueued at the bridge lane.
+		XcmMessageEnqueued {
+			/// Bridge and its lane identifier.
+			lane_id: LaneId,
+			/// Nonce, assigned to the message by the messages pallet.
+			nonce: MessageNonce,
+			/// Identifier of the XCM message.
+			message_id: XcmHash,
+			/// Message export fee, that has been computed by the exporter.
+			fee: MultiAssets,
+		},
 	}
 
 	#[pallet::error]
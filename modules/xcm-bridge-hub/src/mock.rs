@@ -115,6 +115,9 @@ impl pallet_bridge_messages::Config for TestRuntime {
 	type DeliveryPayments = ();
 	type DeliveryConfirmationPayments = ();
 	type MessageDispatch = ForbidInboundMessages<Vec<u8>>;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
 }
 
 parameter_types! {
@@ -122,6 +125,7 @@ parameter_types! {
 	pub const BridgedRelayNetwork: NetworkId = NetworkId::Polkadot;
 	pub const NonBridgedRelayNetwork: NetworkId = NetworkId::Rococo;
 	pub const BridgeReserve: Balance = 100_000;
+	pub MessageExportPrice: MultiAssets = (Here, 1_000_u128).into();
 	pub UniversalLocation: InteriorMultiLocation = X2(
 		GlobalConsensus(RelayNetwork::get()),
 		Parachain(THIS_BRIDGE_HUB_ID),
@@ -221,7 +225,7 @@ impl pallet_xcm_bridge_hub::Config for TestRuntime {
 	type NativeCurrency = Balances;
 
 	type BlobDispatcher = TestBlobDispatcher;
-	type MessageExportPrice = ();
+	type MessageExportPrice = MessageExportPrice;
 }
 
 pub struct TestBlobDispatcher;
@@ -85,6 +85,11 @@ pub type Address = MultiAddress<AccountId, ()>;
 /// Identifier of BridgeHubKusama in the Kusama relay chain.
 pub const BRIDGE_HUB_KUSAMA_PARACHAIN_ID: u32 = 1002;
 
+/// Existential deposit of the BridgeHubKusama native token.
+///
+/// It is 1/10 of the Kusama existential deposit (1 CENTS = UNITS / 3_000).
+pub const EXISTENTIAL_DEPOSIT: Balance = 33_333_333;
+
 /// Name of the With-BridgeHubKusama messages pallet instance that is deployed at bridged chains.
 pub const WITH_BRIDGE_HUB_KUSAMA_MESSAGES_PALLET_NAME: &str = "BridgeKusamaMessages";
 
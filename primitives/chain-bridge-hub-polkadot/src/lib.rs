@@ -76,6 +76,11 @@ impl ChainWithMessages for BridgeHubPolkadot {
 /// Identifier of BridgeHubPolkadot in the Polkadot relay chain.
 pub const BRIDGE_HUB_POLKADOT_PARACHAIN_ID: u32 = 1002;
 
+/// Existential deposit of the BridgeHubPolkadot native token.
+///
+/// It is 1/10 of the Polkadot existential deposit (100 CENTS = UNITS).
+pub const EXISTENTIAL_DEPOSIT: Balance = 1_000_000_000;
+
 /// Name of the With-BridgeHubPolkadot messages pallet instance that is deployed at bridged chains.
 pub const WITH_BRIDGE_HUB_POLKADOT_MESSAGES_PALLET_NAME: &str = "BridgePolkadotMessages";
 
@@ -86,6 +86,11 @@ pub type Address = MultiAddress<AccountId, ()>;
 /// Identifier of BridgeHubRococo in the Rococo relay chain.
 pub const BRIDGE_HUB_ROCOCO_PARACHAIN_ID: u32 = 1013;
 
+/// Existential deposit of the BridgeHubRococo native token.
+///
+/// It is 1/10 of the Rococo existential deposit (1 CENTS = UNITS / 30_000).
+pub const EXISTENTIAL_DEPOSIT: Balance = 3_333_333;
+
 /// Name of the With-BridgeHubRococo messages pallet instance that is deployed at bridged chains.
 pub const WITH_BRIDGE_HUB_ROCOCO_MESSAGES_PALLET_NAME: &str = "BridgeRococoMessages";
 
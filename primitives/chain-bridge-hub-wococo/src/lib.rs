@@ -76,6 +76,11 @@ impl ChainWithMessages for BridgeHubWococo {
 /// Identifier of BridgeHubWococo in the Wococo relay chain.
 pub const BRIDGE_HUB_WOCOCO_PARACHAIN_ID: u32 = 1014;
 
+/// Existential deposit of the BridgeHubWococo native token.
+///
+/// It is 1/10 of the Wococo existential deposit (1 CENTS = UNITS / 30_000).
+pub const EXISTENTIAL_DEPOSIT: Balance = 3_333_333;
+
 /// Name of the With-BridgeHubWococo messages pallet instance that is deployed at bridged chains.
 pub const WITH_BRIDGE_HUB_WOCOCO_MESSAGES_PALLET_NAME: &str = "BridgeWococoMessages";
 
@@ -25,11 +25,10 @@ use bp_beefy::ChainWithBeefy;
 use bp_header_chain::ChainWithGrandpa;
 use bp_messages::{
 	ChainWithMessages, InboundMessageDetails, LaneId, MessageNonce, MessagePayload,
-	OutboundMessageDetails,
+	OutboundMessageDetails, OutboundMessageDetailsWithReward,
 };
-use bp_runtime::{decl_bridge_finality_runtime_apis, decl_bridge_runtime_apis, Chain, ChainId};
+use bp_runtime::{decl_bridge_finality_runtime_apis, decl_bridge_runtime_apis, decl_bridged_chain};
 use frame_support::{
-	dispatch::DispatchClass,
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, IdentityFee, Weight},
 	RuntimeDebug,
 };
@@ -75,6 +74,9 @@ pub const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce = 128;
 /// Maximal number of unconfirmed messages in Millau confirmation transaction.
 pub const MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX: MessageNonce = 128;
 
+/// Existential deposit of the Millau native token.
+pub const EXISTENTIAL_DEPOSIT: Balance = 500;
+
 /// The target length of a session (how often authorities change) on Millau measured in of number of
 /// blocks.
 ///
@@ -157,34 +159,30 @@ pub type Nonce = u32;
 /// Weight-to-Fee type used by Millau.
 pub type WeightToFee = IdentityFee<Balance>;
 
-/// Millau chain.
-#[derive(RuntimeDebug)]
-pub struct Millau;
-
-impl Chain for Millau {
-	const ID: ChainId = *b"mlau";
-
-	type BlockNumber = BlockNumber;
-	type Hash = Hash;
-	type Hasher = Hasher;
-	type Header = Header;
-
-	type AccountId = AccountId;
-	type Balance = Balance;
-	type Nonce = Nonce;
-	type Signature = Signature;
-
-	const STATE_VERSION: StateVersion = StateVersion::V0;
-
-	fn max_extrinsic_size() -> u32 {
-		*BlockLength::get().max.get(DispatchClass::Normal)
-	}
+frame_support::parameter_types! {
+	/// Size limit of the Millau blocks.
+	pub BlockLength: limits::BlockLength =
+		limits::BlockLength::max_with_normal_ratio(2 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
+	/// Weight limit of the Millau blocks.
+	pub BlockWeights: limits::BlockWeights =
+		limits::BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
+}
 
-	fn max_extrinsic_weight() -> Weight {
-		BlockWeights::get()
-			.get(DispatchClass::Normal)
-			.max_extrinsic
-			.unwrap_or(Weight::MAX)
+decl_bridged_chain! {
+	/// Millau chain.
+	pub struct Millau {
+		id: *b"mlau",
+		block_number: BlockNumber,
+		hash: Hash,
+		hasher: Hasher,
+		header: Header,
+		account_id: AccountId,
+		balance: Balance,
+		nonce: Nonce,
+		signature: Signature,
+		state_version: StateVersion::V0,
+		block_length: BlockLength,
+		block_weights: BlockWeights,
 	}
 }
 
@@ -250,15 +248,6 @@ impl sp_runtime::traits::Hash for BlakeTwoAndKeccak256 {
 	}
 }
 
-frame_support::parameter_types! {
-	/// Size limit of the Millau blocks.
-	pub BlockLength: limits::BlockLength =
-		limits::BlockLength::max_with_normal_ratio(2 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
-	/// Weight limit of the Millau blocks.
-	pub BlockWeights: limits::BlockWeights =
-		limits::BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
-}
-
 /// Name of the With-Millau GRANDPA pallet instance that is deployed at bridged chains.
 pub const WITH_MILLAU_GRANDPA_PALLET_NAME: &str = "BridgeMillauGrandpa";
 /// Name of the With-Millau messages pallet instance that is deployed at bridged chains.
@@ -21,13 +21,12 @@
 
 use bp_messages::{
 	ChainWithMessages, InboundMessageDetails, LaneId, MessageNonce, MessagePayload,
-	OutboundMessageDetails,
+	OutboundMessageDetails, OutboundMessageDetailsWithReward,
 };
-use bp_runtime::{decl_bridge_runtime_apis, Chain, ChainId, Parachain};
+use bp_runtime::{decl_bridge_runtime_apis, decl_bridged_chain, Parachain};
 use frame_support::{
-	dispatch::DispatchClass,
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, IdentityFee, Weight},
-	RuntimeDebug, StateVersion,
+	StateVersion,
 };
 use frame_system::limits;
 use sp_core::Hasher as HasherT;
@@ -70,6 +69,9 @@ pub const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce = 1024;
 /// Maximal number of unconfirmed messages in Rialto confirmation transaction.
 pub const MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX: MessageNonce = 1024;
 
+/// Existential deposit of the RialtoParachain native token.
+pub const EXISTENTIAL_DEPOSIT: Balance = 1_000_000_000;
+
 /// Block number type used in Rialto.
 pub type BlockNumber = u32;
 
@@ -104,34 +106,30 @@ pub type Nonce = u32;
 /// Weight-to-Fee type used by Rialto parachain.
 pub type WeightToFee = IdentityFee<Balance>;
 
-/// Rialto parachain.
-#[derive(RuntimeDebug)]
-pub struct RialtoParachain;
-
-impl Chain for RialtoParachain {
-	const ID: ChainId = *b"rlpa";
-
-	type BlockNumber = BlockNumber;
-	type Hash = Hash;
-	type Hasher = Hasher;
-	type Header = Header;
-
-	type AccountId = AccountId;
-	type Balance = Balance;
-	type Nonce = Nonce;
-	type Signature = Signature;
-
-	const STATE_VERSION: StateVersion = StateVersion::V0;
-
-	fn max_extrinsic_size() -> u32 {
-		*BlockLength::get().max.get(DispatchClass::Normal)
-	}
+frame_support::parameter_types! {
+	/// Size limit of the Rialto parachain blocks.
+	pub BlockLength: limits::BlockLength =
+		limits::BlockLength::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
+	/// Weight limit of the Rialto parachain blocks.
+	pub BlockWeights: limits::BlockWeights =
+		limits::BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
+}
 
-	fn max_extrinsic_weight() -> Weight {
-		BlockWeights::get()
-			.get(DispatchClass::Normal)
-			.max_extrinsic
-			.unwrap_or(Weight::MAX)
+decl_bridged_chain! {
+	/// Rialto parachain.
+	pub struct RialtoParachain {
+		id: *b"rlpa",
+		block_number: BlockNumber,
+		hash: Hash,
+		hasher: Hasher,
+		header: Header,
+		account_id: AccountId,
+		balance: Balance,
+		nonce: Nonce,
+		signature: Signature,
+		state_version: StateVersion::V0,
+		block_length: BlockLength,
+		block_weights: BlockWeights,
 	}
 }
 
@@ -152,15 +150,6 @@ impl ChainWithMessages for RialtoParachain {
 // trying to keep it close to the bridge hubs code (at least in this aspect).
 pub use bp_bridge_hub_cumulus::SignedExtension;
 
-frame_support::parameter_types! {
-	/// Size limit of the Rialto parachain blocks.
-	pub BlockLength: limits::BlockLength =
-		limits::BlockLength::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
-	/// Weight limit of the Rialto parachain blocks.
-	pub BlockWeights: limits::BlockWeights =
-		limits::BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
-}
-
 /// Name of the With-Rialto-Parachain messages pallet instance that is deployed at bridged chains.
 pub const WITH_RIALTO_PARACHAIN_MESSAGES_PALLET_NAME: &str = "BridgeRialtoParachainMessages";
 /// Name of the transaction payment pallet at the Rialto parachain runtime.
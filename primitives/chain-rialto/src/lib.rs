@@ -22,13 +22,12 @@
 use bp_header_chain::ChainWithGrandpa;
 use bp_messages::{
 	ChainWithMessages, InboundMessageDetails, LaneId, MessageNonce, MessagePayload,
-	OutboundMessageDetails,
+	OutboundMessageDetails, OutboundMessageDetailsWithReward,
 };
-use bp_runtime::{decl_bridge_finality_runtime_apis, decl_bridge_runtime_apis, Chain, ChainId};
+use bp_runtime::{decl_bridge_finality_runtime_apis, decl_bridge_runtime_apis, decl_bridged_chain};
 use frame_support::{
-	dispatch::DispatchClass,
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, IdentityFee, Weight},
-	RuntimeDebug, StateVersion,
+	StateVersion,
 };
 use frame_system::limits;
 use sp_core::Hasher as HasherT;
@@ -65,6 +64,9 @@ pub const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce = 1024;
 /// Maximal number of unconfirmed messages in Rialto confirmation transaction.
 pub const MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX: MessageNonce = 1024;
 
+/// Existential deposit of the Rialto native token.
+pub const EXISTENTIAL_DEPOSIT: Balance = 500;
+
 /// The target length of a session (how often authorities change) on Rialto measured in of number of
 /// blocks.
 ///
@@ -163,34 +165,30 @@ pub type Nonce = u32;
 /// Weight-to-Fee type used by Rialto.
 pub type WeightToFee = IdentityFee<Balance>;
 
-/// Rialto chain.
-#[derive(RuntimeDebug)]
-pub struct Rialto;
-
-impl Chain for Rialto {
-	const ID: ChainId = *b"rlto";
-
-	type BlockNumber = BlockNumber;
-	type Hash = Hash;
-	type Hasher = Hasher;
-	type Header = Header;
-
-	type AccountId = AccountId;
-	type Balance = Balance;
-	type Nonce = Nonce;
-	type Signature = Signature;
-
-	const STATE_VERSION: StateVersion = StateVersion::V1;
-
-	fn max_extrinsic_size() -> u32 {
-		*BlockLength::get().max.get(DispatchClass::Normal)
-	}
+frame_support::parameter_types! {
+	/// Size limit of the Rialto blocks.
+	pub BlockLength: limits::BlockLength =
+		limits::BlockLength::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
+	/// Weight limit of the Rialto blocks.
+	pub BlockWeights: limits::BlockWeights =
+		limits::BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
+}
 
-	fn max_extrinsic_weight() -> Weight {
-		BlockWeights::get()
-			.get(DispatchClass::Normal)
-			.max_extrinsic
-			.unwrap_or(Weight::MAX)
+decl_bridged_chain! {
+	/// Rialto chain.
+	pub struct Rialto {
+		id: *b"rlto",
+		block_number: BlockNumber,
+		hash: Hash,
+		hasher: Hasher,
+		header: Header,
+		account_id: AccountId,
+		balance: Balance,
+		nonce: Nonce,
+		signature: Signature,
+		state_version: StateVersion::V1,
+		block_length: BlockLength,
+		block_weights: BlockWeights,
 	}
 }
 
@@ -211,15 +209,6 @@ impl ChainWithMessages for Rialto {
 		MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
 }
 
-frame_support::parameter_types! {
-	/// Size limit of the Rialto blocks.
-	pub BlockLength: limits::BlockLength =
-		limits::BlockLength::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
-	/// Weight limit of the Rialto blocks.
-	pub BlockWeights: limits::BlockWeights =
-		limits::BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
-}
-
 /// Name of the With-Rialto GRANDPA pallet instance that is deployed at bridged chains.
 pub const WITH_RIALTO_GRANDPA_PALLET_NAME: &str = "BridgeRialtoGrandpa";
 /// Name of the With-Rialto messages pallet instance that is deployed at bridged chains.
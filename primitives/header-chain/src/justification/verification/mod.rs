@@ -118,8 +118,13 @@ pub enum Error {
 	InvalidAuthorityList,
 	/// Justification is finalizing unexpected header.
 	InvalidJustificationTarget,
-	/// Error validating a precommit
-	Precommit(PrecommitError),
+	/// Error validating a precommit.
+	Precommit {
+		/// Index of the invalid precommit in the justification commit.
+		index: usize,
+		/// The reason why the precommit is invalid.
+		error: PrecommitError,
+	},
 	/// The cumulative weight of all votes in the justification is not enough to justify commit
 	/// header finalization.
 	TooLowCumulativeWeight,
@@ -222,8 +227,9 @@ trait JustificationVerifier<Header: HeaderT> {
 
 		for (precommit_idx, signed) in justification.commit.precommits.iter().enumerate() {
 			if cumulative_weight >= threshold {
-				let action =
-					self.process_redundant_vote(precommit_idx).map_err(Error::Precommit)?;
+				let action = self
+					.process_redundant_vote(precommit_idx)
+					.map_err(|error| Error::Precommit { index: precommit_idx, error })?;
 				if matches!(action, IterationFlow::Skip) {
 					continue
 				}
@@ -236,7 +242,7 @@ trait JustificationVerifier<Header: HeaderT> {
 					// For example to see if the authority has already voted in the same round.
 					let action = self
 						.process_known_authority_vote(precommit_idx, signed)
-						.map_err(Error::Precommit)?;
+						.map_err(|error| Error::Precommit { index: precommit_idx, error })?;
 					if matches!(action, IterationFlow::Skip) {
 						continue
 					}
@@ -244,7 +250,8 @@ trait JustificationVerifier<Header: HeaderT> {
 					authority_info
 				},
 				None => {
-					self.process_unknown_authority_vote(precommit_idx).map_err(Error::Precommit)?;
+					self.process_unknown_authority_vote(precommit_idx)
+						.map_err(|error| Error::Precommit { index: precommit_idx, error })?;
 					continue
 				},
 			};
@@ -255,7 +262,7 @@ trait JustificationVerifier<Header: HeaderT> {
 			if maybe_route.is_none() {
 				let action = self
 					.process_unrelated_ancestry_vote(precommit_idx)
-					.map_err(Error::Precommit)?;
+					.map_err(|error| Error::Precommit { index: precommit_idx, error })?;
 				if matches!(action, IterationFlow::Skip) {
 					continue
 				}
@@ -270,7 +277,8 @@ trait JustificationVerifier<Header: HeaderT> {
 				context.authority_set_id,
 				&mut signature_buffer,
 			) {
-				self.process_invalid_signature_vote(precommit_idx).map_err(Error::Precommit)?;
+				self.process_invalid_signature_vote(precommit_idx)
+					.map_err(|error| Error::Precommit { index: precommit_idx, error })?;
 				continue
 			}
 
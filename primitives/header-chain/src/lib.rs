@@ -24,7 +24,7 @@ use crate::justification::{
 	GrandpaJustification, JustificationVerificationContext, JustificationVerificationError,
 };
 use bp_runtime::{
-	BasicOperatingMode, Chain, HashOf, HasherOf, HeaderOf, StorageProofError,
+	BasicOperatingMode, Chain, HashOf, HasherOf, HeaderId, HeaderOf, StorageProofError,
 	UnderlyingChainProvider, UnverifiedStorageProof, VerifiedStorageProof,
 };
 use codec::{Codec, Decode, Encode, EncodeLike, MaxEncodedLen};
@@ -33,7 +33,7 @@ use frame_support::PalletError;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_consensus_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
-use sp_runtime::{traits::Header as HeaderT, Digest, RuntimeDebug};
+use sp_runtime::{traits::Header as HeaderT, ConsensusEngineId, Digest, RuntimeDebug};
 use sp_std::{boxed::Box, vec::Vec};
 
 pub mod justification;
@@ -46,6 +46,8 @@ pub enum HeaderChainError {
 	UnknownHeader,
 	/// Error generated by the `storage_proof` module.
 	StorageProof(StorageProofError),
+	/// Ancestry proof is invalid.
+	InvalidAncestryProof,
 }
 
 /// Header data that we're storing on-chain.
@@ -86,6 +88,19 @@ pub trait HeaderChain<C: Chain> {
 		db.verify::<HasherOf<C>>(C::STATE_VERSION, &state_root)
 			.map_err(HeaderChainError::StorageProof)
 	}
+
+	/// Returns state (storage) root of the `ancestor` header, if given `ancestry` proves that it
+	/// is an ancestor of the finalized `descendant` header.
+	///
+	/// See `verify_ancestry` for the `ancestry` format.
+	fn ancestor_state_root(
+		ancestor: HashOf<C>,
+		descendant: HashOf<C>,
+		ancestry: Vec<HeaderOf<C>>,
+	) -> Result<HashOf<C>, HeaderChainError> {
+		Self::finalized_header_state_root(descendant).ok_or(HeaderChainError::UnknownHeader)?;
+		verify_ancestry(ancestor, descendant, ancestry).map(|header| *header.state_root())
+	}
 }
 
 impl<C: Chain> HeaderChain<C> for () {
@@ -101,6 +116,37 @@ impl<C: Chain> HeaderChain<C> for () {
 	}
 }
 
+/// Verify that the header with `ancestor` hash is an ancestor of the header with `descendant`
+/// hash.
+///
+/// The `ancestry` must contain all headers from the `descendant` (inclusive) to the `ancestor`
+/// (inclusive), starting with the `descendant` header. Returns the `ancestor` header.
+pub fn verify_ancestry<H: HeaderT>(
+	ancestor: H::Hash,
+	descendant: H::Hash,
+	ancestry: Vec<H>,
+) -> Result<H, HeaderChainError> {
+	let mut expected_hash = descendant;
+	let mut ancestry = ancestry.into_iter().peekable();
+	while let Some(header) = ancestry.next() {
+		if header.hash() != expected_hash {
+			return Err(HeaderChainError::InvalidAncestryProof)
+		}
+
+		if ancestry.peek().is_none() {
+			return if expected_hash == ancestor {
+				Ok(header)
+			} else {
+				Err(HeaderChainError::InvalidAncestryProof)
+			}
+		}
+
+		expected_hash = *header.parent_hash();
+	}
+
+	Err(HeaderChainError::InvalidAncestryProof)
+}
+
 /// A type that can be used as a parameter in a dispatchable function.
 ///
 /// When using `decl_module` all arguments for call functions must implement this trait.
@@ -193,6 +239,49 @@ impl<Number: Codec> ConsensusLogReader for GrandpaConsensusLogReader<Number> {
 	}
 }
 
+/// Consensus engine that is used to finalize headers of the bridged chain.
+///
+/// Groups the finality proof type, its verification routine and the consensus log reader, so
+/// that the code which is not specific to GRANDPA may work with other finality engines.
+pub trait ConsensusEngine<Header: HeaderT> {
+	/// Unique consensus engine identifier.
+	const ID: ConsensusEngineId;
+	/// A reader that can extract the consensus log from the header digest and interpret it.
+	type ConsensusLogReader: ConsensusLogReader;
+	/// Type of finality proofs, used by consensus engine.
+	type FinalityProof: FinalityProof<Header::Number> + Parameter;
+	/// The context needed for verifying finality proofs.
+	type FinalityVerificationContext;
+	/// Finality proof verification error.
+	type Error: Debug;
+
+	/// Verify that the `finality_proof` is finalizing the `finalized_target` header.
+	fn verify_finality_proof(
+		finalized_target: (Header::Hash, Header::Number),
+		context: &Self::FinalityVerificationContext,
+		finality_proof: &Self::FinalityProof,
+	) -> Result<(), Self::Error>;
+}
+
+/// GRANDPA consensus engine.
+pub struct GrandpaConsensusEngine;
+
+impl<Header: HeaderT> ConsensusEngine<Header> for GrandpaConsensusEngine {
+	const ID: ConsensusEngineId = GRANDPA_ENGINE_ID;
+	type ConsensusLogReader = GrandpaConsensusLogReader<Header::Number>;
+	type FinalityProof = GrandpaJustification<Header>;
+	type FinalityVerificationContext = JustificationVerificationContext;
+	type Error = JustificationVerificationError;
+
+	fn verify_finality_proof(
+		finalized_target: (Header::Hash, Header::Number),
+		context: &Self::FinalityVerificationContext,
+		finality_proof: &Self::FinalityProof,
+	) -> Result<(), Self::Error> {
+		justification::verify_justification::<Header>(finalized_target, context, finality_proof)
+	}
+}
+
 /// The finality-related info associated to a header.
 #[derive(Encode, Decode, Debug, PartialEq, Clone, TypeInfo)]
 pub struct HeaderFinalityInfo<FinalityProof, FinalityVerificationContext> {
@@ -224,6 +313,33 @@ impl<Header: HeaderT> TryFrom<StoredHeaderGrandpaInfo<Header>> for HeaderGrandpa
 	}
 }
 
+/// Constraints that the bridge GRANDPA pallet applies to submitted finality proofs.
+///
+/// Relayers may use it to decide whether and when to submit finality proofs, instead of
+/// relying on hardcoded values.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct FinalitySubmissionConstraints<Number, Hash> {
+	/// Maximal size of the encoded justification. The pallet halts itself if the valid
+	/// justification is larger than that.
+	pub max_justification_size: u32,
+	/// Maximal number of authorities in the bridged chain authority set. The pallet halts
+	/// itself if the new authority set is larger than that.
+	pub max_authorities_count: u32,
+	/// Maximal number of mandatory headers that may be imported for free in a single block.
+	pub max_free_mandatory_headers_per_block: u32,
+	/// Maximal number of headers that may be imported for free in a single epoch.
+	pub max_free_headers_per_epoch: u32,
+	/// Number of headers that may still be imported for free in the current epoch.
+	pub free_headers_remaining_in_epoch: u32,
+	/// If `Some(interval)`, non-mandatory header may be imported for free if its number is at
+	/// least `interval` blocks ahead of the best finalized header.
+	pub free_headers_interval: Option<u32>,
+	/// The best finalized header, known to the pallet.
+	pub best_finalized: Option<HeaderId<Hash, Number>>,
+	/// Identifier of the current authority set.
+	pub current_set_id: SetId,
+}
+
 /// Helper trait for finding equivocations in finality proofs.
 pub trait FindEquivocations<FinalityProof, FinalityVerificationContext, EquivocationProof> {
 	/// The type returned when encountering an error while looking for equivocations.
@@ -255,6 +371,12 @@ pub enum BridgeGrandpaCall<Header: HeaderT> {
 		/// All data, required to initialize the pallet.
 		init_data: InitializationData<Header>,
 	},
+	/// `pallet-bridge-grandpa::Call::set_operating_mode`
+	#[codec(index = 3)]
+	set_operating_mode {
+		/// New pallet operating mode.
+		operating_mode: BasicOperatingMode,
+	},
 }
 
 /// The `BridgeGrandpaCall` used by a chain.
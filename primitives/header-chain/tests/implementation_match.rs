@@ -124,7 +124,10 @@ fn same_result_when_precommit_target_has_lower_number_than_commit_target() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::UnrelatedAncestryVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 0,
+			error: PrecommitError::UnrelatedAncestryVote,
+		}),
 	);
 
 	// original implementation returns `Ok(validation_result)`
@@ -156,7 +159,10 @@ fn same_result_when_precommit_target_is_not_descendant_of_commit_target() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::UnrelatedAncestryVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 0,
+			error: PrecommitError::UnrelatedAncestryVote,
+		}),
 	);
 
 	// original implementation returns `Ok(validation_result)`
@@ -226,7 +232,10 @@ fn different_result_when_justification_contains_duplicate_vote() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::DuplicateAuthorityVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 3,
+			error: PrecommitError::DuplicateAuthorityVote,
+		}),
 	);
 	// original implementation returns `Ok(validation_result)`
 	// with `validation_result.is_valid() == true`.
@@ -266,7 +275,10 @@ fn different_results_when_authority_equivocates_once_in_a_round() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::DuplicateAuthorityVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 3,
+			error: PrecommitError::DuplicateAuthorityVote,
+		}),
 	);
 	// original implementation returns `Ok(validation_result)`
 	// with `validation_result.is_valid() == true`.
@@ -318,7 +330,10 @@ fn different_results_when_authority_equivocates_twice_in_a_round() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::DuplicateAuthorityVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 2,
+			error: PrecommitError::DuplicateAuthorityVote,
+		}),
 	);
 	// original implementation returns `Ok(validation_result)`
 	// with `validation_result.is_valid() == true`.
@@ -356,7 +371,10 @@ fn different_results_when_there_are_more_than_enough_votes() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::RedundantAuthorityVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 4,
+			error: PrecommitError::RedundantAuthorityVote,
+		}),
 	);
 	// original implementation returns `Ok(validation_result)`
 	// with `validation_result.is_valid() == true`.
@@ -396,7 +414,10 @@ fn different_results_when_there_is_a_vote_of_unknown_authority() {
 			&full_verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::UnknownAuthorityVote)),
+		Err(JustificationVerificationError::Precommit {
+			index: 3,
+			error: PrecommitError::UnknownAuthorityVote,
+		}),
 	);
 	// original implementation returns `Ok(validation_result)`
 	// with `validation_result.is_valid() == true`.
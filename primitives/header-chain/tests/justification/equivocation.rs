@@ -122,3 +122,40 @@ fn equivocations_are_detected_in_extra_justification_redundant_votes() {
 		)]
 	);
 }
+
+#[test]
+fn equivocations_are_detected_in_justification_with_double_votes() {
+	let params =
+		JustificationGeneratorParams::<TestHeader> { header: test_header(1), ..Default::default() };
+	let justification = make_justification_with_double_votes(params, 2);
+
+	let verification_context = verification_context(TEST_GRANDPA_SET_ID);
+	let collector = EquivocationsCollector::new(&verification_context, &justification).unwrap();
+
+	let mut equivocators = collector
+		.into_equivocation_proofs()
+		.into_iter()
+		.map(|proof| proof.offender().clone())
+		.collect::<Vec<_>>();
+	equivocators.sort();
+	let mut expected_equivocators = vec![ALICE.into(), BOB.into()];
+	expected_equivocators.sort();
+	assert_eq!(equivocators, expected_equivocators);
+}
+
+#[test]
+fn equivocations_are_detected_in_conflicting_justifications() {
+	let params =
+		JustificationGeneratorParams::<TestHeader> { header: test_header(1), ..Default::default() };
+	let (base_justification, conflicting_justification) = make_conflicting_justifications(params);
+
+	let verification_context = verification_context(TEST_GRANDPA_SET_ID);
+	let mut collector =
+		EquivocationsCollector::new(&verification_context, &base_justification).unwrap();
+	collector.parse_justifications(&[conflicting_justification]);
+
+	assert_eq!(
+		collector.into_equivocation_proofs().len(),
+		base_justification.commit.precommits.len(),
+	);
+}
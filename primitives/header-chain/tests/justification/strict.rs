@@ -144,7 +144,10 @@ fn justification_with_invalid_authority_signature_rejected() {
 			&verification_context(TEST_GRANDPA_SET_ID),
 			&justification,
 		),
-		Err(JustificationVerificationError::Precommit(PrecommitError::InvalidAuthoritySignature)),
+		Err(JustificationVerificationError::Precommit {
+			index: 0,
+			error: PrecommitError::InvalidAuthoritySignature,
+		}),
 	);
 }
 
@@ -163,6 +166,42 @@ fn justification_with_invalid_precommit_ancestry() {
 	);
 }
 
+#[test]
+fn justification_with_double_votes_rejected() {
+	let params =
+		JustificationGeneratorParams::<TestHeader> { header: test_header(1), ..Default::default() };
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			&verification_context(TEST_GRANDPA_SET_ID),
+			&make_justification_with_double_votes(params, 1),
+		),
+		Err(JustificationVerificationError::Precommit {
+			index: 1,
+			error: PrecommitError::DuplicateAuthorityVote,
+		}),
+	);
+}
+
+#[test]
+fn justification_with_forged_ancestry_rejected() {
+	let params =
+		JustificationGeneratorParams::<TestHeader> { header: test_header(1), ..Default::default() };
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			&verification_context(TEST_GRANDPA_SET_ID),
+			&make_justification_with_forged_ancestry(params),
+		),
+		Err(JustificationVerificationError::Precommit {
+			index: 0,
+			error: PrecommitError::UnrelatedAncestryVote,
+		}),
+	);
+}
+
 #[test]
 fn justification_is_invalid_if_we_dont_meet_threshold() {
 	// Need at least three authorities to sign off or else the voter set threshold can't be reached
@@ -24,7 +24,7 @@ use bp_runtime::{
 	messages::MessageDispatchResult, AccountIdOf, BasicOperatingMode, Chain, HashOf, OperatingMode,
 	RangeInclusiveExt, StorageProofError, UnderlyingChainOf, UnderlyingChainProvider,
 };
-use codec::{Decode, Encode, MaxEncodedLen};
+use codec::{Codec, Decode, Encode, EncodeLike, MaxEncodedLen};
 use frame_support::{PalletError, RuntimeDebug};
 // Weight is reexported to avoid additional frame-support dependencies in related crates.
 pub use frame_support::weights::Weight;
@@ -33,7 +33,9 @@ use serde::{Deserialize, Serialize};
 use source_chain::RelayersRewards;
 use sp_core::{TypeId, H256};
 use sp_io::hashing::blake2_256;
-use sp_std::{collections::vec_deque::VecDeque, ops::RangeInclusive, prelude::*};
+use sp_std::{
+	collections::vec_deque::VecDeque, convert::TryFrom, fmt::Debug, ops::RangeInclusive, prelude::*,
+};
 
 pub mod source_chain;
 pub mod storage_keys;
@@ -249,6 +251,86 @@ impl TypeId for LaneId {
 	const TYPE_ID: [u8; 4] = *b"blan";
 }
 
+/// Legacy lane identifier.
+///
+/// Bridges that have been built on older `bp-messages` versions are using 4-byte lane
+/// identifiers instead of hashed [`LaneId`]. Legacy identifier may be converted to the [`LaneId`]
+/// by placing its bytes at the beginning of the zero hash. Only such identifiers may be
+/// converted back to the [`LegacyLaneId`].
+#[derive(
+	Clone,
+	Copy,
+	Decode,
+	Default,
+	Encode,
+	Eq,
+	Ord,
+	PartialOrd,
+	PartialEq,
+	TypeInfo,
+	MaxEncodedLen,
+	Serialize,
+	Deserialize,
+)]
+pub struct LegacyLaneId(pub [u8; 4]);
+
+impl core::fmt::Debug for LegacyLaneId {
+	fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+		self.0.fmt(fmt)
+	}
+}
+
+impl From<LegacyLaneId> for LaneId {
+	fn from(lane_id: LegacyLaneId) -> LaneId {
+		let mut hash = H256::zero();
+		hash.as_bytes_mut()[..4].copy_from_slice(&lane_id.0);
+		LaneId(hash)
+	}
+}
+
+impl TryFrom<LaneId> for LegacyLaneId {
+	type Error = ();
+
+	fn try_from(lane_id: LaneId) -> Result<LegacyLaneId, ()> {
+		let bytes = lane_id.0.as_bytes();
+		if bytes[4..].iter().any(|byte| *byte != 0) {
+			return Err(())
+		}
+
+		let mut legacy_lane_id = [0u8; 4];
+		legacy_lane_id.copy_from_slice(&bytes[..4]);
+		Ok(LegacyLaneId(legacy_lane_id))
+	}
+}
+
+/// Lane identifier type, used by the messages pallet storage, calls and runtime APIs.
+///
+/// Every bridge may use its own lane identifier type (e.g. [`LegacyLaneId`] or [`LaneId`]).
+/// Relayers are using [`LaneId`] to identify lanes of all bridges, so the lane identifier must
+/// be convertible from it.
+pub trait LaneIdType:
+	'static
+	+ Clone
+	+ Copy
+	+ Codec
+	+ EncodeLike
+	+ Debug
+	+ Default
+	+ Eq
+	+ Ord
+	+ Send
+	+ Sync
+	+ TypeInfo
+	+ MaxEncodedLen
+	+ TryFrom<LaneId>
+	+ Into<LaneId>
+{
+}
+
+impl LaneIdType for LaneId {}
+
+impl LaneIdType for LegacyLaneId {}
+
 /// Lane state.
 #[derive(Clone, Copy, Decode, Encode, Eq, PartialEq, TypeInfo, MaxEncodedLen, RuntimeDebug)]
 pub enum LaneState {
@@ -412,6 +494,20 @@ pub struct OutboundMessageDetails {
 	pub size: u32,
 }
 
+/// Outbound message details with the expected delivery reward, returned by runtime APIs.
+///
+/// The encoding of this type starts with the encoding of the `OutboundMessageDetails`, so
+/// clients may fall back to decoding the latter when dealing with older runtimes.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct OutboundMessageDetailsWithReward {
+	/// Outbound message details.
+	pub details: OutboundMessageDetails,
+	/// Reward that the relayer would earn for delivering the message, if known.
+	///
+	/// The reward is paid in the source chain tokens, after delivery is confirmed.
+	pub delivery_reward: Option<u128>,
+}
+
 /// Inbound message details, returned by runtime APIs.
 #[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
 pub struct InboundMessageDetails {
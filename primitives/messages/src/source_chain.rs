@@ -16,11 +16,11 @@
 
 //! Primitives of messages module, that are used on the source chain.
 
-use crate::{LaneId, MessageNonce, UnrewardedRelayer};
+use crate::{LaneId, MessageNonce, MessagePayload, UnrewardedRelayer};
 
 use bp_runtime::{Size, UnverifiedStorageProof};
 use codec::{Decode, Encode};
-use frame_support::RuntimeDebug;
+use frame_support::{weights::Weight, RuntimeDebug};
 use scale_info::TypeInfo;
 use sp_std::{
 	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
@@ -38,17 +38,21 @@ use sp_std::{
 /// - storage proof of the inbound lane state;
 ///
 /// - lane id.
+///
+/// Lane identifier type may be different for different bridges (see [`crate::LaneIdType`]).
 #[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-pub struct FromBridgedChainMessagesDeliveryProof<BridgedHeaderHash> {
+pub struct FromBridgedChainMessagesDeliveryProof<BridgedHeaderHash, Lane = LaneId> {
 	/// Hash of the bridge header the proof is for.
 	pub bridged_header_hash: BridgedHeaderHash,
 	/// Storage trie proof generated for [`Self::bridged_header_hash`].
 	pub storage_proof: UnverifiedStorageProof,
 	/// Lane id of which messages were delivered and the proof is for.
-	pub lane: LaneId,
+	pub lane: Lane,
 }
 
-impl<BridgedHeaderHash> Size for FromBridgedChainMessagesDeliveryProof<BridgedHeaderHash> {
+impl<BridgedHeaderHash, Lane> Size
+	for FromBridgedChainMessagesDeliveryProof<BridgedHeaderHash, Lane>
+{
 	fn size(&self) -> u32 {
 		self.storage_proof.size()
 	}
@@ -75,6 +79,11 @@ pub trait DeliveryConfirmationPayments<AccountId> {
 		confirmation_relayer: &AccountId,
 		received_range: &RangeInclusive<MessageNonce>,
 	) -> MessageNonce;
+
+	/// Returns reward that the relayer would earn for delivering given message, if known.
+	fn delivery_reward(_lane_id: LaneId, _nonce: MessageNonce) -> Option<u128> {
+		None
+	}
 }
 
 impl<AccountId> DeliveryConfirmationPayments<AccountId> for () {
@@ -91,6 +100,45 @@ impl<AccountId> DeliveryConfirmationPayments<AccountId> for () {
 	}
 }
 
+/// Handler for outbound messages that have not been delivered in time and have been pruned.
+///
+/// The sending application may use it e.g. to refund its user.
+pub trait OnMessagesExpired {
+	/// Called when the undelivered outbound message has expired and has been pruned.
+	///
+	/// Returns weight, consumed by the call.
+	fn on_message_expired(lane_id: LaneId, nonce: MessageNonce, payload: MessagePayload) -> Weight;
+}
+
+impl OnMessagesExpired for () {
+	fn on_message_expired(
+		_lane_id: LaneId,
+		_nonce: MessageNonce,
+		_payload: MessagePayload,
+	) -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Handler for outbound lane congestion signals.
+///
+/// The sending application may use it e.g. to raise its fees or to suspend the channel that is
+/// feeding messages to the congested lane.
+pub trait OnLaneCongestion {
+	/// Called when the number of queued messages at the lane has reached the lane congestion
+	/// threshold.
+	fn on_lane_congested(lane_id: LaneId);
+	/// Called when the number of queued messages at the congested lane has dropped below the
+	/// lane congestion threshold.
+	fn on_lane_uncongested(lane_id: LaneId);
+}
+
+impl OnLaneCongestion for () {
+	fn on_lane_congested(_lane_id: LaneId) {}
+
+	fn on_lane_uncongested(_lane_id: LaneId) {}
+}
+
 /// Send message artifacts.
 #[derive(Eq, RuntimeDebug, PartialEq)]
 pub struct SendMessageArtifacts {
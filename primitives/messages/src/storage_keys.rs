@@ -25,7 +25,7 @@ pub const OUTBOUND_LANES_MAP_NAME: &str = "OutboundLanes";
 /// Name of the `InboundLanes` storage map.
 pub const INBOUND_LANES_MAP_NAME: &str = "InboundLanes";
 
-use crate::{LaneId, MessageKey, MessageNonce};
+use crate::MessageNonce;
 
 use codec::Encode;
 use frame_support::Blake2_128Concat;
@@ -43,16 +43,23 @@ pub fn operating_mode_key(pallet_prefix: &str) -> StorageKey {
 }
 
 /// Storage key of the outbound message in the runtime storage.
-pub fn message_key(pallet_prefix: &str, lane: &LaneId, nonce: MessageNonce) -> StorageKey {
+///
+/// The lane may be either [`crate::LaneId`] or any other [`crate::LaneIdType`], used by the
+/// bridge. The encoded `(lane, nonce)` tuple matches the encoding of the `MessageKey`.
+pub fn message_key<Lane: Encode>(
+	pallet_prefix: &str,
+	lane: &Lane,
+	nonce: MessageNonce,
+) -> StorageKey {
 	bp_runtime::storage_map_final_key::<Blake2_128Concat>(
 		pallet_prefix,
 		OUTBOUND_MESSAGES_MAP_NAME,
-		&MessageKey { lane_id: *lane, nonce }.encode(),
+		&(lane, nonce).encode(),
 	)
 }
 
 /// Storage key of the outbound message lane state in the runtime storage.
-pub fn outbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey {
+pub fn outbound_lane_data_key<Lane: Encode>(pallet_prefix: &str, lane: &Lane) -> StorageKey {
 	bp_runtime::storage_map_final_key::<Blake2_128Concat>(
 		pallet_prefix,
 		OUTBOUND_LANES_MAP_NAME,
@@ -61,7 +68,7 @@ pub fn outbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey
 }
 
 /// Storage key of the inbound message lane state in the runtime storage.
-pub fn inbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey {
+pub fn inbound_lane_data_key<Lane: Encode>(pallet_prefix: &str, lane: &Lane) -> StorageKey {
 	bp_runtime::storage_map_final_key::<Blake2_128Concat>(
 		pallet_prefix,
 		INBOUND_LANES_MAP_NAME,
@@ -72,6 +79,7 @@ pub fn inbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{LaneId, LegacyLaneId, MessageKey};
 	use hex_literal::hex;
 
 	#[test]
@@ -125,4 +133,34 @@ mod tests {
 			hex::encode(&storage_key),
 		);
 	}
+
+	#[test]
+	fn message_key_matches_encoded_message_key() {
+		let lane_id = LaneId::new(1, 2);
+		assert_eq!(
+			message_key("BridgeMessages", &lane_id, 42),
+			bp_runtime::storage_map_final_key::<Blake2_128Concat>(
+				"BridgeMessages",
+				OUTBOUND_MESSAGES_MAP_NAME,
+				&MessageKey { lane_id, nonce: 42 }.encode(),
+			),
+		);
+	}
+
+	#[test]
+	fn legacy_lane_data_keys_are_computed_using_legacy_encoding() {
+		let legacy_lane_id = LegacyLaneId([0, 0, 0, 1]);
+		assert_eq!(
+			inbound_lane_data_key("BridgeMessages", &legacy_lane_id),
+			bp_runtime::storage_map_final_key::<Blake2_128Concat>(
+				"BridgeMessages",
+				INBOUND_LANES_MAP_NAME,
+				&[0, 0, 0, 1],
+			),
+		);
+		assert_ne!(
+			inbound_lane_data_key("BridgeMessages", &legacy_lane_id),
+			inbound_lane_data_key("BridgeMessages", &LaneId::from(legacy_lane_id)),
+		);
+	}
 }
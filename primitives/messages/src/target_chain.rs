@@ -36,21 +36,23 @@ use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
 /// - lane id;
 ///
 /// - nonces (inclusive range) of messages which are included in this proof.
+///
+/// Lane identifier type may be different for different bridges (see [`crate::LaneIdType`]).
 #[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
-pub struct FromBridgedChainMessagesProof<BridgedHeaderHash> {
+pub struct FromBridgedChainMessagesProof<BridgedHeaderHash, Lane = LaneId> {
 	/// Hash of the finalized bridged header the proof is for.
 	pub bridged_header_hash: BridgedHeaderHash,
 	/// The proved storage containing the messages being delivered.
 	pub storage: UnverifiedStorageProof,
 	/// Messages in this proof are sent over this lane.
-	pub lane: LaneId,
+	pub lane: Lane,
 	/// Nonce of the first message being delivered.
 	pub nonces_start: MessageNonce,
 	/// Nonce of the last message being delivered.
 	pub nonces_end: MessageNonce,
 }
 
-impl<BridgedHeaderHash> Size for FromBridgedChainMessagesProof<BridgedHeaderHash> {
+impl<BridgedHeaderHash, Lane> Size for FromBridgedChainMessagesProof<BridgedHeaderHash, Lane> {
 	fn size(&self) -> u32 {
 		self.storage.size()
 	}
@@ -61,6 +61,16 @@ pub struct ParaInfo {
 	pub next_imported_hash_position: u32,
 }
 
+/// Configuration of the single parachain, tracked by the bridge parachains pallet.
+#[derive(Clone, Copy, Decode, Encode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct ParaConfig {
+	/// Maximal size (in bytes) of the SCALE-encoded parachain head data
+	/// (`bp_parachains::ParaStoredHeaderData`).
+	///
+	/// It must not exceed the `MaxParaHeadDataSize` of the pallet.
+	pub max_head_data_size: u32,
+}
+
 /// Returns runtime storage key of given parachain head at the source chain.
 ///
 /// The head is stored by the `paras` pallet in the `Heads` map.
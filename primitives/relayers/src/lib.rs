@@ -23,7 +23,10 @@ pub use registration::{Registration, StakeAndSlash};
 
 use bp_messages::LaneId;
 use bp_runtime::{ChainId, StorageDoubleMapKeyProvider};
-use frame_support::{traits::tokens::Preservation, Blake2_128Concat, Identity};
+use frame_support::{
+	traits::{fungibles, tokens::Preservation, Get},
+	Blake2_128Concat, Identity,
+};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	codec::{Codec, Decode, Encode, EncodeLike, MaxEncodedLen},
@@ -137,6 +140,40 @@ where
 	}
 }
 
+/// Reward payment procedure that transfers given asset from the account, derived from given
+/// params.
+///
+/// It may be used to pay rewards in some non-native asset (e.g. in some stable asset or in the
+/// bridged chain token, held by the `pallet-assets`). The rewards account is the same account
+/// that is used by the [`PayRewardFromAccount`], so it is the asset balance of this account that
+/// must be topped up.
+pub struct PayRewardFromAccountInAsset<T, Relayer, AssetId>(PhantomData<(T, Relayer, AssetId)>);
+
+impl<T, Relayer, AssetId> PaymentProcedure<Relayer, T::Balance>
+	for PayRewardFromAccountInAsset<T, Relayer, AssetId>
+where
+	T: fungibles::Mutate<Relayer>,
+	Relayer: Decode + Encode,
+	AssetId: Get<T::AssetId>,
+{
+	type Error = sp_runtime::DispatchError;
+
+	fn pay_reward(
+		relayer: &Relayer,
+		rewards_account_params: RewardsAccountParams,
+		reward: T::Balance,
+	) -> Result<(), Self::Error> {
+		T::transfer(
+			AssetId::get(),
+			&PayRewardFromAccount::<(), Relayer>::rewards_account(rewards_account_params),
+			relayer,
+			reward,
+			Preservation::Expendable,
+		)
+		.map(drop)
+	}
+}
+
 /// Can be use to access the runtime storage key within the `RelayerRewards` map of the relayers
 /// pallet.
 pub struct RelayerRewardsKeyProvider<AccountId, Reward>(PhantomData<(AccountId, Reward)>);
@@ -93,6 +93,10 @@ pub trait StakeAndSlash<AccountId, BlockNumber, Balance> {
 		beneficiary: RewardsAccountParams,
 		amount: Balance,
 	) -> Result<Balance, DispatchError>;
+	/// Slash up to `amount` from reserved balance of account `relayer`. Slashed funds are burnt.
+	///
+	/// Returns amount that we have failed to slash.
+	fn slash_reserved(relayer: &AccountId, amount: Balance) -> Balance;
 }
 
 impl<AccountId, BlockNumber, Balance> StakeAndSlash<AccountId, BlockNumber, Balance> for ()
@@ -118,4 +122,9 @@ where
 	) -> Result<Balance, DispatchError> {
 		Ok(Zero::zero())
 	}
+
+	fn slash_reserved(_relayer: &AccountId, amount: Balance) -> Balance {
+		// nothing is reserved, so nothing is slashed
+		amount
+	}
 }
@@ -284,6 +284,96 @@ pub type AccountPublicOf<C> = <SignatureOf<C> as Verify>::Signer;
 /// Transaction era used by the chain.
 pub type TransactionEraOf<C> = crate::TransactionEra<BlockNumberOf<C>, HashOf<C>>;
 
+/// Convenience macro that declares the chain type and implements the [`Chain`] trait for it.
+/// This includes:
+/// - the chain type itself;
+/// - the [`Chain`] trait implementation, which is using given `BlockLength` and `BlockWeights`
+///   parameters to compute maximal size and weight of the chain extrinsic.
+///
+/// Example:
+///
+/// ```nocompile
+/// frame_support::parameter_types! {
+/// 	pub BlockLength: limits::BlockLength =
+/// 		limits::BlockLength::max_with_normal_ratio(MAX_BLOCK_SIZE, NORMAL_DISPATCH_RATIO);
+/// 	pub BlockWeights: limits::BlockWeights =
+/// 		limits::BlockWeights::with_sensible_defaults(MAX_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
+/// }
+///
+/// bp_runtime::decl_bridged_chain! {
+/// 	/// Rialto chain.
+/// 	pub struct Rialto {
+/// 		id: *b"rlto",
+/// 		block_number: BlockNumber,
+/// 		hash: Hash,
+/// 		hasher: Hasher,
+/// 		header: Header,
+/// 		account_id: AccountId,
+/// 		balance: Balance,
+/// 		nonce: Nonce,
+/// 		signature: Signature,
+/// 		state_version: StateVersion::V1,
+/// 		block_length: BlockLength,
+/// 		block_weights: BlockWeights,
+/// 	}
+/// }
+/// ```
+#[macro_export]
+macro_rules! decl_bridged_chain {
+	(
+		$(#[$attr:meta])*
+		pub struct $chain:ident {
+			id: $id:expr,
+			block_number: $block_number:ty,
+			hash: $hash:ty,
+			hasher: $hasher:ty,
+			header: $header:ty,
+			account_id: $account_id:ty,
+			balance: $balance:ty,
+			nonce: $nonce:ty,
+			signature: $signature:ty,
+			state_version: $state_version:expr,
+			block_length: $block_length:ty,
+			block_weights: $block_weights:ty $(,)?
+		}
+	) => {
+		$(#[$attr])*
+		#[derive($crate::frame_support::RuntimeDebug)]
+		pub struct $chain;
+
+		impl $crate::Chain for $chain {
+			const ID: $crate::ChainId = $id;
+
+			type BlockNumber = $block_number;
+			type Hash = $hash;
+			type Hasher = $hasher;
+			type Header = $header;
+
+			type AccountId = $account_id;
+			type Balance = $balance;
+			type Nonce = $nonce;
+			type Signature = $signature;
+
+			const STATE_VERSION: $crate::frame_support::StateVersion = $state_version;
+
+			fn max_extrinsic_size() -> u32 {
+				use $crate::frame_support::{dispatch::DispatchClass, traits::Get};
+				*<$block_length as Get<$crate::frame_system::limits::BlockLength>>::get()
+					.max
+					.get(DispatchClass::Normal)
+			}
+
+			fn max_extrinsic_weight() -> $crate::frame_support::weights::Weight {
+				use $crate::frame_support::{dispatch::DispatchClass, traits::Get, weights::Weight};
+				<$block_weights as Get<$crate::frame_system::limits::BlockWeights>>::get()
+					.get(DispatchClass::Normal)
+					.max_extrinsic
+					.unwrap_or(Weight::MAX)
+			}
+		}
+	};
+}
+
 /// Convenience macro that declares bridge finality runtime apis and related constants for a chain.
 /// This includes:
 /// - chain-specific bridge runtime APIs:
@@ -308,6 +398,15 @@ macro_rules! decl_bridge_finality_runtime_apis {
 					/// runtime method.
 					pub const [<$chain:upper _SYNCED_HEADERS_ $consensus:upper _INFO_METHOD>]: &str =
 						stringify!([<$chain:camel FinalityApi_synced_headers_ $consensus:lower _info>]);
+
+					/// Name of the `<ThisChain>FinalityApi::<consensus>_verify_ancestry` runtime method.
+					pub const [<$chain:upper _ $consensus:upper _VERIFY_ANCESTRY_METHOD>]: &str =
+						stringify!([<$chain:camel FinalityApi_ $consensus:lower _verify_ancestry>]);
+
+					/// Name of the `<ThisChain>FinalityApi::<consensus>_submission_constraints`
+					/// runtime method.
+					pub const [<$chain:upper _ $consensus:upper _SUBMISSION_CONSTRAINTS_METHOD>]: &str =
+						stringify!([<$chain:camel FinalityApi_ $consensus:lower _submission_constraints>]);
 				)?
 
 				sp_api::decl_runtime_apis! {
@@ -323,6 +422,19 @@ macro_rules! decl_bridge_finality_runtime_apis {
 							/// Returns the justifications accepted in the current block.
 							fn [<synced_headers_ $consensus:lower _info>](
 							) -> Vec<$justification_type>;
+
+							/// Verifies that the `ancestor` header is an ancestor of the imported
+							/// finalized `descendant` header, using headers from the `ancestry`.
+							/// Returns `None` if verification has failed.
+							fn [<$consensus:lower _verify_ancestry>](
+								ancestor: Hash,
+								descendant: Hash,
+								ancestry: Vec<Header>,
+							) -> Option<bp_header_chain::StoredHeaderData<BlockNumber, Hash>>;
+
+							/// Returns constraints that are applied to submitted finality proofs.
+							fn [<$consensus:lower _submission_constraints>](
+							) -> bp_header_chain::FinalitySubmissionConstraints<BlockNumber, Hash>;
 						)?
 					}
 				}
@@ -364,17 +476,30 @@ macro_rules! decl_bridge_messages_runtime_apis {
 					///
 					/// This API is implemented by runtimes that are receiving messages from this chain, not by this
 					/// chain's runtime itself.
+					#[api_version(2)]
 					pub trait [<To $chain:camel OutboundLaneApi>] {
-						/// Returns dispatch weight, encoded payload size and delivery+dispatch fee of all
-						/// messages in given inclusive range.
+						/// Returns dispatch weight and encoded payload size of all messages in given
+						/// inclusive range.
 						///
 						/// If some (or all) messages are missing from the storage, they'll also will
 						/// be missing from the resulting vector. The vector is ordered by the nonce.
+						#[changed_in(2)]
 						fn message_details(
 							lane: LaneId,
 							begin: MessageNonce,
 							end: MessageNonce,
 						) -> Vec<OutboundMessageDetails>;
+
+						/// Returns dispatch weight, encoded payload size and expected delivery reward
+						/// of all messages in given inclusive range.
+						///
+						/// If some (or all) messages are missing from the storage, they'll also will
+						/// be missing from the resulting vector. The vector is ordered by the nonce.
+						fn message_details(
+							lane: LaneId,
+							begin: MessageNonce,
+							end: MessageNonce,
+						) -> Vec<OutboundMessageDetailsWithReward>;
 					}
 
 					/// Inbound message lane API for messages sent by this chain.
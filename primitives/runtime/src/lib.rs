@@ -52,6 +52,11 @@ mod storage_types;
 
 // Re-export macro to aviod include paste dependency everywhere
 pub use sp_runtime::paste;
+// Re-export crates that are used by the `decl_bridged_chain` macro
+#[doc(hidden)]
+pub use frame_support;
+#[doc(hidden)]
+pub use frame_system;
 
 /// Use this when something must be shared among all instances.
 pub const NO_INSTANCE_ID: ChainId = [0, 0, 0, 0];
@@ -227,6 +232,39 @@ pub fn storage_map_final_key<H: StorageHasher>(
 	StorageKey(final_key)
 }
 
+/// This is a copy of the
+/// `frame_support::storage::generator::StorageDoubleMap::storage_double_map_final_key1` for double
+/// maps based on selected hasher.
+///
+/// The returned key is the prefix of all double map entries that share the same first key. So it
+/// may be used to iterate over these entries without access to the runtime.
+pub fn storage_double_map_prefix_key<H1: StorageHasher>(
+	pallet_prefix: &str,
+	map_name: &str,
+	key1: &[u8],
+) -> StorageKey {
+	storage_map_final_key::<H1>(pallet_prefix, map_name, key1)
+}
+
+/// This is a copy of the
+/// `frame_support::storage::generator::StorageDoubleMap::storage_double_map_final_key` for double
+/// maps based on selected hashers.
+///
+/// We're using it because to call `storage_double_map_final_key` directly, we need access to the
+/// runtime and pallet instance, which (sometimes) is impossible.
+pub fn storage_double_map_final_key<H1: StorageHasher, H2: StorageHasher>(
+	pallet_prefix: &str,
+	map_name: &str,
+	key1: &[u8],
+	key2: &[u8],
+) -> StorageKey {
+	let key2_hashed = H2::hash(key2);
+	let mut final_key = storage_double_map_prefix_key::<H1>(pallet_prefix, map_name, key1).0;
+	final_key.extend_from_slice(key2_hashed.as_ref());
+
+	StorageKey(final_key)
+}
+
 /// This is how a storage key of storage parameter (`parameter_types! { storage Param: bool = false;
 /// }`) is computed.
 ///
@@ -297,24 +335,12 @@ pub trait StorageDoubleMapKeyProvider {
 	/// We're using it because to call `storage_double_map_final_key` directly, we need access
 	/// to the runtime and pallet instance, which (sometimes) is impossible.
 	fn final_key(pallet_prefix: &str, key1: &Self::Key1, key2: &Self::Key2) -> StorageKey {
-		let key1_hashed = Self::Hasher1::hash(&key1.encode());
-		let key2_hashed = Self::Hasher2::hash(&key2.encode());
-		let pallet_prefix_hashed = frame_support::Twox128::hash(pallet_prefix.as_bytes());
-		let storage_prefix_hashed = frame_support::Twox128::hash(Self::MAP_NAME.as_bytes());
-
-		let mut final_key = Vec::with_capacity(
-			pallet_prefix_hashed.len() +
-				storage_prefix_hashed.len() +
-				key1_hashed.as_ref().len() +
-				key2_hashed.as_ref().len(),
-		);
-
-		final_key.extend_from_slice(&pallet_prefix_hashed[..]);
-		final_key.extend_from_slice(&storage_prefix_hashed[..]);
-		final_key.extend_from_slice(key1_hashed.as_ref());
-		final_key.extend_from_slice(key2_hashed.as_ref());
-
-		StorageKey(final_key)
+		storage_double_map_final_key::<Self::Hasher1, Self::Hasher2>(
+			pallet_prefix,
+			Self::MAP_NAME,
+			&key1.encode(),
+			&key2.encode(),
+		)
 	}
 }
 
@@ -519,6 +545,41 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn storage_map_final_key_works() {
+		assert_eq!(
+			storage_map_final_key::<frame_support::Twox64Concat>("System", "Account", &[0u8; 4]),
+			StorageKey(
+				hex_literal::hex!(
+					"26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9"
+					"b4def25cfda6ef3a00000000"
+				)
+				.to_vec()
+			),
+		);
+	}
+
+	#[test]
+	fn storage_double_map_final_key_works() {
+		use frame_support::{Blake2_128Concat, Twox64Concat};
+
+		let prefix_key =
+			storage_double_map_prefix_key::<Blake2_128Concat>("Pallet", "DoubleMap", b"key1");
+		let final_key = storage_double_map_final_key::<Blake2_128Concat, Twox64Concat>(
+			"Pallet",
+			"DoubleMap",
+			b"key1",
+			b"key2",
+		);
+
+		assert_eq!(
+			prefix_key,
+			storage_map_final_key::<Blake2_128Concat>("Pallet", "DoubleMap", b"key1"),
+		);
+		assert_eq!(final_key.0[..prefix_key.0.len()], prefix_key.0[..]);
+		assert_eq!(final_key.0[prefix_key.0.len()..], Twox64Concat::hash(b"key2")[..]);
+	}
+
 	#[test]
 	fn generate_static_str_provider_works() {
 		generate_static_str_provider!(Test);
@@ -19,7 +19,7 @@
 use frame_support::{PalletError, StateVersion};
 use sp_core::{storage::TrackedStorageKey, RuntimeDebug};
 use sp_runtime::SaturatedConversion;
-use sp_std::{default::Default, vec, vec::Vec};
+use sp_std::{default::Default, vec::Vec};
 use sp_trie::{
 	generate_trie_proof, verify_trie_proof, LayoutV0, LayoutV1, StorageProof, TrieDBBuilder,
 	TrieHash,
@@ -106,8 +106,11 @@ impl UnverifiedStorageProof {
 		H::Out: codec::Codec,
 	{
 		let keys: Vec<_> = entries.iter().map(|(key, _)| key.clone()).collect();
-		let entries: Vec<_> =
-			entries.iter().cloned().map(|(key, val)| (None, vec![(key, val)])).collect();
+		let entries: Vec<_> = entries
+			.iter()
+			.cloned()
+			.map(|(key, val)| (None, sp_std::vec![(key, val)]))
+			.collect();
 		let backend = sp_state_machine::TrieBackend::<sp_trie::MemoryDB<H>, H>::from((
 			entries,
 			state_version,
@@ -161,7 +164,8 @@ impl UnverifiedStorageProof {
 		state_root: &TrieHash<LayoutV1<H>>,
 	) -> Result<VerifiedStorageProof, StorageProofError> {
 		// First we verify the proof for the `UnverifiedStorageProof`.
-		// Note that `verify_trie_proof()` also checks for duplicate keys and unused nodes.
+		// Note that `verify_trie_proof()` also checks for duplicate keys and unused nodes. So all
+		// nodes of the proof are touched during verification.
 		match state_version {
 			StateVersion::V0 =>
 				verify_trie_proof::<LayoutV0<H>, _, _, _>(state_root, &self.proof, &self.db),
@@ -182,25 +186,43 @@ impl UnverifiedStorageProof {
 			}
 			trusted_db.push((TrackedStorageKey::new(key), val))
 		}
-		Ok(VerifiedStorageProof { db: trusted_db })
+		Ok(VerifiedStorageProof {
+			db: trusted_db,
+			proof_nodes: self.proof.len().saturated_into(),
+			proof_bytes: proof_nodes_size(&self.proof).saturated_into(),
+		})
 	}
 }
 
 impl Size for UnverifiedStorageProof {
 	fn size(&self) -> u32 {
-		let proof_size = self.proof.iter().fold(0usize, |sum, node| sum.saturating_add(node.len()));
-		let entries_size = self.db.iter().fold(0usize, |sum, (key, value)| {
-			sum.saturating_add(key.len())
-				.saturating_add(value.as_ref().unwrap_or(&vec![]).len())
-		});
+		let proof_size = proof_nodes_size(&self.proof);
+		let entries_size = self
+			.db
+			.iter()
+			.fold(0usize, |sum, (key, value)| sum.saturating_add(entry_size(key, value)));
 
 		proof_size.saturating_add(entries_size).saturated_into()
 	}
 }
 
+/// Returns total size of all trie nodes in the proof.
+fn proof_nodes_size(proof: &RawStorageProof) -> usize {
+	proof.iter().fold(0usize, |sum, node| sum.saturating_add(node.len()))
+}
+
+/// Returns size of the key-value entry.
+fn entry_size(key: &[u8], value: &Option<DBValue>) -> usize {
+	key.len().saturating_add(value.as_ref().map(|value| value.len()).unwrap_or(0))
+}
+
 /// Structure representing a key-value database stored as a sorted `Vec` of tuples.
 pub struct VerifiedStorageProof {
 	db: Vec<(TrackedStorageKey, Option<DBValue>)>,
+	/// Number of trie nodes in the verified proof.
+	proof_nodes: u32,
+	/// Total size of trie nodes in the verified proof.
+	proof_bytes: u32,
 }
 
 impl VerifiedStorageProof {
@@ -242,6 +264,23 @@ impl VerifiedStorageProof {
 		}
 	}
 
+	/// Returns the number of trie nodes that have been touched during proof verification.
+	///
+	/// Proofs with unused nodes are rejected by the `UnverifiedStorageProof::verify`, so this is
+	/// the number of all nodes in the proof.
+	pub fn touched_nodes(&self) -> u32 {
+		self.proof_nodes
+	}
+
+	/// Returns the number of proof bytes that have been touched during proof verification.
+	///
+	/// It is the size of all trie nodes of the proof. Entries are stored in these nodes, so
+	/// reading them doesn't touch any additional bytes. It may be used to compute actual weight
+	/// of the call that has been given the proof.
+	pub fn touched_bytes(&self) -> u32 {
+		self.proof_bytes
+	}
+
 	/// Checks if each key was read.
 	pub fn ensure_no_unused_keys(&self) -> Result<(), StorageProofError> {
 		for (key, _) in &self.db {
@@ -369,6 +408,26 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn verify_fails_when_proof_contains_unused_trie_nodes() {
+		let (root, mut db) = UnverifiedStorageProof::try_from_entries::<Hasher>(
+			StateVersion::default(),
+			&[(b"key1".to_vec(), Some(b"val1".to_vec().encode()))],
+		)
+		.expect("UnverifiedStorageProof::try_from_entries() shouldn't fail in tests");
+		let (_, other_db) = UnverifiedStorageProof::try_from_entries::<Hasher>(
+			StateVersion::default(),
+			&[(b"key2".to_vec(), Some(b"val2".to_vec().encode()))],
+		)
+		.expect("UnverifiedStorageProof::try_from_entries() shouldn't fail in tests");
+		db.proof.extend(other_db.proof);
+
+		assert!(matches!(
+			db.verify::<Hasher>(StateVersion::V1, &root),
+			Err(StorageProofError::InvalidProof)
+		));
+	}
+
 	#[test]
 	fn verify_fails_when_db_contains_duplicate_nodes() {
 		let (root, mut db) = UnverifiedStorageProof::try_from_entries::<Hasher>(
@@ -472,4 +531,24 @@ mod tests {
 
 		assert!(matches!(trusted_db.ensure_no_unused_keys(), Err(StorageProofError::UnusedKey)));
 	}
+
+	#[test]
+	fn touched_nodes_and_bytes_are_reported() {
+		let (root, db) = UnverifiedStorageProof::try_from_entries::<Hasher>(
+			StateVersion::default(),
+			&[(b"key1".to_vec(), None), (b"key2".to_vec(), Some(b"val2".to_vec()))],
+		)
+		.expect("UnverifiedStorageProof::try_from_entries() shouldn't fail in tests");
+		let proof_nodes = db.proof.len() as u32;
+		let proof_bytes = proof_nodes_size(&db.proof) as u32;
+		let mut trusted_db = db.verify::<Hasher>(StateVersion::V1, &root).unwrap();
+		assert_eq!(trusted_db.touched_nodes(), proof_nodes);
+		assert_eq!(trusted_db.touched_bytes(), proof_bytes);
+
+		// entries are stored in the proof nodes, so reading them doesn't change anything
+		assert!(trusted_db.get(b"key1").is_ok());
+		assert!(trusted_db.get(b"key2").is_ok());
+		assert_eq!(trusted_db.touched_nodes(), proof_nodes);
+		assert_eq!(trusted_db.touched_bytes(), proof_bytes);
+	}
 }
@@ -96,6 +96,70 @@ pub fn make_default_justification<H: HeaderT>(header: &H) -> GrandpaJustificatio
 /// being given an invalid commit.
 pub fn make_justification_for_header<H: HeaderT>(
 	params: JustificationGeneratorParams<H>,
+) -> GrandpaJustification<H> {
+	let ancestry_base = params.header.clone();
+	make_justification_with_ancestry_base(params, &ancestry_base)
+}
+
+/// Make a justification where first `equivocators` authorities have voted twice in the same
+/// round: once for the header (or its descendant) and once for the conflicting header at the
+/// same height.
+///
+/// The conflicting vote immediately follows the honest vote of the same authority, so
+/// the justification is rejected by the verifier because of duplicate authority vote.
+pub fn make_justification_with_double_votes<H: HeaderT>(
+	params: JustificationGeneratorParams<H>,
+	equivocators: usize,
+) -> GrandpaJustification<H> {
+	let (round, set_id) = (params.round, params.set_id);
+	let authorities = params.authorities.clone();
+	let conflicting_header = test_fork_header(&params.header, u32::MAX);
+	let conflicting_target = (conflicting_header.hash(), *conflicting_header.number());
+	let mut justification = make_justification_for_header(params);
+
+	// precommits are signed by authorities in the same order
+	let honest_precommits = sp_std::mem::take(&mut justification.commit.precommits);
+	for (i, honest_precommit) in honest_precommits.into_iter().enumerate() {
+		justification.commit.precommits.push(honest_precommit);
+		if i < equivocators {
+			justification.commit.precommits.push(signed_precommit::<H>(
+				&authorities[i].0,
+				conflicting_target,
+				round,
+				set_id,
+			));
+		}
+	}
+
+	justification
+}
+
+/// Make two justifications for conflicting headers at the same height. Both justifications are
+/// signed by the same authorities in the same round, so every authority is an equivocator.
+pub fn make_conflicting_justifications<H: HeaderT>(
+	params: JustificationGeneratorParams<H>,
+) -> (GrandpaJustification<H>, GrandpaJustification<H>) {
+	let conflicting_header = test_fork_header(&params.header, u32::MAX);
+	let conflicting_params =
+		JustificationGeneratorParams { header: conflicting_header, ..params.clone() };
+
+	(make_justification_for_header(params), make_justification_for_header(conflicting_params))
+}
+
+/// Make a justification with forged `votes_ancestries`.
+///
+/// The vote ancestries are built on top of the header that conflicts with the justification
+/// target, so all precommits are voting for headers that are not descendants of the target.
+pub fn make_justification_with_forged_ancestry<H: HeaderT>(
+	params: JustificationGeneratorParams<H>,
+) -> GrandpaJustification<H> {
+	let ancestry_base = test_fork_header(&params.header, u32::MAX);
+	make_justification_with_ancestry_base(params, &ancestry_base)
+}
+
+fn make_justification_with_ancestry_base<H: HeaderT>(
+	params: JustificationGeneratorParams<H>,
+	ancestry_base: &H,
 ) -> GrandpaJustification<H> {
 	let JustificationGeneratorParams { header, round, set_id, authorities, mut ancestors, forks } =
 		params;
@@ -122,7 +186,7 @@ pub fn make_justification_for_header<H: HeaderT>(
 		};
 
 		// Note: Adding 1 to account for the target header
-		let chain = generate_chain(i, depth + 1, &header);
+		let chain = generate_chain(i, depth + 1, ancestry_base);
 
 		// We don't include our finality target header in the vote ancestries
 		for child in &chain[1..] {
@@ -170,6 +234,17 @@ fn generate_chain<H: HeaderT>(fork_id: u32, depth: u32, ancestor: &H) -> Vec<H>
 	headers
 }
 
+/// Returns header that conflicts with the given `header`. It has the same number and parent, but
+/// different hash.
+pub fn test_fork_header<H: HeaderT>(header: &H, fork_id: u32) -> H {
+	let mut fork_header = header.clone();
+	fork_header
+		.digest_mut()
+		.logs
+		.push(sp_runtime::DigestItem::Other(fork_id.encode()));
+	fork_header
+}
+
 /// Make valid proof for parachain `heads`
 pub fn prepare_parachain_heads_proof<H: HeaderT>(
 	heads: Vec<(u32, ParaHead)>,
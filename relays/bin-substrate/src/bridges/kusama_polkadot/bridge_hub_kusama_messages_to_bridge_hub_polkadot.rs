@@ -54,6 +54,7 @@ pub struct BridgeHubKusamaMessagesToBridgeHubPolkadotMessageLane;
 impl SubstrateMessageLane for BridgeHubKusamaMessagesToBridgeHubPolkadotMessageLane {
 	type SourceChain = BridgeHubKusama;
 	type TargetChain = BridgeHubPolkadot;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder =
 		BridgeHubKusamaMessagesToBridgeHubPolkadotMessageLaneReceiveMessagesProofCallBuilder;
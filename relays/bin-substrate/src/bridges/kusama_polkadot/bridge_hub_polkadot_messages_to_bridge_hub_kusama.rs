@@ -54,6 +54,7 @@ pub struct BridgeHubPolkadotMessagesToBridgeHubKusamaMessageLane;
 impl SubstrateMessageLane for BridgeHubPolkadotMessagesToBridgeHubKusamaMessageLane {
 	type SourceChain = BridgeHubPolkadot;
 	type TargetChain = BridgeHubKusama;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder =
 		BridgeHubPolkadotMessagesToBridgeHubKusamaMessageLaneReceiveMessagesProofCallBuilder;
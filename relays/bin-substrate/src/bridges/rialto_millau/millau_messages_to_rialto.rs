@@ -30,6 +30,7 @@ pub struct MillauMessagesToRialto;
 impl SubstrateMessageLane for MillauMessagesToRialto {
 	type SourceChain = Millau;
 	type TargetChain = Rialto;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder = DirectReceiveMessagesProofCallBuilder<
 		Self,
@@ -30,6 +30,7 @@ pub struct RialtoMessagesToMillau;
 impl SubstrateMessageLane for RialtoMessagesToMillau {
 	type SourceChain = Rialto;
 	type TargetChain = Millau;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder = DirectReceiveMessagesProofCallBuilder<
 		Self,
@@ -37,6 +37,7 @@ pub struct MillauMessagesToRialtoParachain;
 impl SubstrateMessageLane for MillauMessagesToRialtoParachain {
 	type SourceChain = Millau;
 	type TargetChain = RialtoParachain;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder =
 		MillauMessagesToRialtoParachainReceiveMessagesProofCallBuilder;
@@ -37,6 +37,7 @@ pub struct RialtoParachainMessagesToMillau;
 impl SubstrateMessageLane for RialtoParachainMessagesToMillau {
 	type SourceChain = RialtoParachain;
 	type TargetChain = Millau;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder = DirectReceiveMessagesProofCallBuilder<
 		Self,
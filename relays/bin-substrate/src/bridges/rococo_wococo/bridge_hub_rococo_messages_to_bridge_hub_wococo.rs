@@ -53,6 +53,7 @@ pub struct BridgeHubRococoMessagesToBridgeHubWococoMessageLane;
 impl SubstrateMessageLane for BridgeHubRococoMessagesToBridgeHubWococoMessageLane {
 	type SourceChain = BridgeHubRococo;
 	type TargetChain = BridgeHubWococo;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder =
 		BridgeHubRococoMessagesToBridgeHubWococoMessageLaneReceiveMessagesProofCallBuilder;
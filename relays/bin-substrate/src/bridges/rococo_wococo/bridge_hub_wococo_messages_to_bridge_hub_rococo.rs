@@ -53,6 +53,7 @@ pub struct BridgeHubWococoMessagesToBridgeHubRococoMessageLane;
 impl SubstrateMessageLane for BridgeHubWococoMessagesToBridgeHubRococoMessageLane {
 	type SourceChain = BridgeHubWococo;
 	type TargetChain = BridgeHubRococo;
+	type LaneId = bp_messages::LaneId;
 
 	type ReceiveMessagesProofCallBuilder =
 		BridgeHubWococoMessagesToBridgeHubRococoMessageLaneReceiveMessagesProofCallBuilder;
@@ -115,12 +115,14 @@ macro_rules! declare_chain_connection_params_cli_schema {
 					let chain_runtime_version = self
 						.[<$chain_prefix _runtime_version>]
 						.into_runtime_version(Chain::RUNTIME_VERSION)?;
-					Ok(relay_substrate_client::new(relay_substrate_client::ConnectionParams {
-						host: self.[<$chain_prefix _host>],
-						port: self.[<$chain_prefix _port>],
-						secure: self.[<$chain_prefix _secure>],
-						chain_runtime_version,
-					})
+					Ok($crate::cli::shared_clients::shared_client(
+						relay_substrate_client::ConnectionParams {
+							host: self.[<$chain_prefix _host>],
+							port: self.[<$chain_prefix _port>],
+							secure: self.[<$chain_prefix _secure>],
+							chain_runtime_version,
+						},
+					)
 					.await
 					)
 				}
@@ -160,10 +162,10 @@ macro_rules! declare_chain_signing_params_cli_schema {
 			#[doc = $chain " signing params."]
 			#[derive(StructOpt, Debug, PartialEq, Eq, Clone)]
 			pub struct [<$chain SigningParams>] {
-				#[doc = "The SURI of secret key to use when transactions are submitted to the " $chain " node."]
+				#[doc = "The SURI of secret key to use when transactions are submitted to the " $chain " node. May also be supplied using the uppercased option name environment variable (or the one with `_FILE` suffix, which points to the file with SURI)."]
 				#[structopt(long)]
 				pub [<$chain_prefix _signer>]: Option<String>,
-				#[doc = "The password for the SURI of secret key to use when transactions are submitted to the " $chain " node."]
+				#[doc = "The password for the SURI of secret key to use when transactions are submitted to the " $chain " node. May also be supplied using the uppercased option name environment variable (or the one with `_FILE` suffix, which points to the file with password)."]
 				#[structopt(long)]
 				pub [<$chain_prefix _signer_password>]: Option<String>,
 
@@ -173,10 +175,39 @@ macro_rules! declare_chain_signing_params_cli_schema {
 				#[doc = "Path to the file, that password for the SURI of secret key to use when transactions are submitted to the " $chain " node. Can be overridden with " $chain_prefix "_signer_password option."]
 				#[structopt(long)]
 				pub [<$chain_prefix _signer_password_file>]: Option<std::path::PathBuf>,
+				#[doc = "Path to the polkadot-js compatible encrypted JSON keystore with the key to use when transactions are submitted to the " $chain " node. May also be supplied using the uppercased option name environment variable. Keystore password is read from the " $chain_prefix "_signer_password option, from the SUBSTRATE_RELAY_KEYSTORE_PASSWORD environment variable or from the terminal."]
+				#[structopt(long)]
+				pub [<$chain_prefix _signer_keystore>]: Option<std::path::PathBuf>,
 
 				#[doc = "Transactions mortality period, in blocks. MUST be a power of two in [4; 65536] range. MAY NOT be larger than `BlockHashCount` parameter of the chain system module."]
 				#[structopt(long)]
 				pub [<$chain_prefix _transactions_mortality>]: Option<u32>,
+
+				#[doc = "Maximal amount of tokens (in smallest units) that the relayer account may spend on " $chain " transactions during one hour. Any decrease of the account free balance (including reserves and transfers) counts as spending. Once exceeded, the relay stops submitting transactions to the " $chain " node."]
+				#[structopt(long)]
+				pub [<$chain_prefix _max_spending_per_hour>]: Option<u128>,
+				#[doc = "Maximal amount of tokens (in smallest units) that the relayer account may spend on " $chain " transactions during one day. Any decrease of the account free balance (including reserves and transfers) counts as spending. Once exceeded, the relay stops submitting transactions to the " $chain " node."]
+				#[structopt(long)]
+				pub [<$chain_prefix _max_spending_per_day>]: Option<u128>,
+				#[doc = "Minimal free balance (in smallest units) of the relayer account at " $chain ". If the balance (checked before submitting every transaction) drops below this value, the relay is reported as not ready by the health endpoint."]
+				#[structopt(long)]
+				pub [<$chain_prefix _min_balance>]: Option<u128>,
+				#[doc = "Relay accounts balance (in smallest units) at " $chain ", below which the `balance_below_threshold` metric is set and the low balance alert is fired."]
+				#[structopt(long)]
+				pub [<$chain_prefix _balance_threshold>]: Option<u128>,
+
+				#[doc = "Index of the `pallet-proxy` in the " $chain " runtime. If specified, all transactions are submitted using the `proxy.proxy` call on behalf of the " $chain_prefix "_proxied_address account."]
+				#[structopt(long)]
+				pub [<$chain_prefix _proxy_pallet_index>]: Option<u8>,
+				#[doc = "Hex-encoded SCALE-encoded address (lookup source) of the " $chain " account, on behalf of which transactions are submitted using the `proxy.proxy` call."]
+				#[structopt(long)]
+				pub [<$chain_prefix _proxied_address>]: Option<$crate::cli::HexBytes>,
+				#[doc = "Index of the `pallet-multisig` in the " $chain " runtime. If specified, all transactions are submitted using the `multisig.as_multi_threshold_1` call on behalf of the multisig account of the signer and " $chain_prefix "_multisig_other_signatories accounts."]
+				#[structopt(long)]
+				pub [<$chain_prefix _multisig_pallet_index>]: Option<u8>,
+				#[doc = "Hex-encoded SCALE-encoded account ids of other signatories of the " $chain " multisig account."]
+				#[structopt(long)]
+				pub [<$chain_prefix _multisig_other_signatories>]: Vec<$crate::cli::HexBytes>,
 			}
 
 			impl [<$chain SigningParams>] {
@@ -199,39 +230,94 @@ macro_rules! declare_chain_signing_params_cli_schema {
 						.transpose()
 				}
 
+				/// Return relay accounts balance threshold.
+				#[allow(dead_code)]
+				pub fn balance_threshold(&self) -> Option<u128> {
+					self.[<$chain_prefix _balance_threshold>]
+				}
+
+				/// Return spending budget of the transactions author.
+				#[allow(dead_code)]
+				pub fn spending_budget<Chain: relay_substrate_client::Chain>(
+					&self,
+				) -> anyhow::Result<Option<substrate_relay_helper::spending_budget::SpendingBudget>> {
+					let params = substrate_relay_helper::spending_budget::SpendingBudgetParams {
+						max_spending_per_hour: self.[<$chain_prefix _max_spending_per_hour>],
+						max_spending_per_day: self.[<$chain_prefix _max_spending_per_day>],
+						min_balance: self.[<$chain_prefix _min_balance>],
+					};
+					if params.is_unlimited() {
+						return Ok(None)
+					}
+
+					Ok(Some(substrate_relay_helper::spending_budget::SpendingBudget::new::<Chain>(params)?))
+				}
+
+				/// Return wrapper of all calls, submitted by the transactions author.
+				pub fn call_wrapper(&self) -> anyhow::Result<Option<substrate_relay_helper::CallWrapper>> {
+					match (
+						self.[<$chain_prefix _proxy_pallet_index>],
+						self.[<$chain_prefix _proxied_address>].as_ref(),
+						self.[<$chain_prefix _multisig_pallet_index>],
+					) {
+						(None, None, None) => Ok(None),
+						(Some(pallet_index), Some(real), None) => {
+							Ok(Some(substrate_relay_helper::CallWrapper::Proxy {
+								pallet_index,
+								real: real.0.clone(),
+							}))
+						},
+						(None, None, Some(pallet_index)) => {
+							// `pallet-multisig` requires other signatories to be sorted
+							let mut other_signatories = self.[<$chain_prefix _multisig_other_signatories>]
+								.iter()
+								.map(|other_signatory| other_signatory.0.clone())
+								.collect::<Vec<_>>();
+							other_signatories.sort();
+
+							Ok(Some(substrate_relay_helper::CallWrapper::MultisigThreshold1 {
+								pallet_index,
+								other_signatories,
+							}))
+						},
+						_ => Err(anyhow::format_err!(
+							"Either both '{}' and '{}', or only '{}' option must be specified",
+							stringify!([<$chain_prefix _proxy_pallet_index>]),
+							stringify!([<$chain_prefix _proxied_address>]),
+							stringify!([<$chain_prefix _multisig_pallet_index>]),
+						)),
+					}
+				}
+
 				/// Parse signing params into chain-specific KeyPair.
 				#[allow(dead_code)]
 				pub fn to_keypair<Chain: ChainWithTransactions>(&self) -> anyhow::Result<AccountKeyPairOf<Chain>> {
-					let suri = match (self.[<$chain_prefix _signer>].as_ref(), self.[<$chain_prefix _signer_file>].as_ref()) {
-						(Some(suri), _) => suri.to_owned(),
-						(None, Some(suri_file)) => std::fs::read_to_string(suri_file)
-							.map_err(|err| anyhow::format_err!(
-								"Failed to read SURI from file {:?}: {}",
-								suri_file,
-								err,
-							))?,
+					let suri_password = $crate::cli::secrets::resolve_secret(
+						stringify!([<$chain_prefix _signer_password>]),
+						self.[<$chain_prefix _signer_password>].as_ref(),
+						self.[<$chain_prefix _signer_password_file>].as_ref(),
+					)?;
+					let suri = $crate::cli::secrets::resolve_secret(
+						stringify!([<$chain_prefix _signer>]),
+						self.[<$chain_prefix _signer>].as_ref(),
+						self.[<$chain_prefix _signer_file>].as_ref(),
+					)?;
+					let keystore = $crate::cli::secrets::resolve_path(
+						stringify!([<$chain_prefix _signer_keystore>]),
+						self.[<$chain_prefix _signer_keystore>].as_ref(),
+					);
+
+					let suri = match (suri, keystore) {
+						(Some(suri), _) => suri,
+						(None, Some(keystore)) => return $crate::cli::keystore::read_keypair(&keystore, suri_password),
 						(None, None) => return Err(anyhow::format_err!(
-							"One of options must be specified: '{}' or '{}'",
+							"One of options must be specified: '{}', '{}' or '{}'",
 							stringify!([<$chain_prefix _signer>]),
 							stringify!([<$chain_prefix _signer_file>]),
+							stringify!([<$chain_prefix _signer_keystore>]),
 						)),
 					};
 
-					let suri_password = match (
-						self.[<$chain_prefix _signer_password>].as_ref(),
-						self.[<$chain_prefix _signer_password_file>].as_ref(),
-					) {
-						(Some(suri_password), _) => Some(suri_password.to_owned()),
-						(None, Some(suri_password_file)) => std::fs::read_to_string(suri_password_file)
-							.map(Some)
-							.map_err(|err| anyhow::format_err!(
-								"Failed to read SURI password from file {:?}: {}",
-								suri_password_file,
-								err,
-							))?,
-						_ => None,
-					};
-
 					use sp_core::crypto::Pair;
 
 					AccountKeyPairOf::<Chain>::from_string(
@@ -244,13 +330,21 @@ macro_rules! declare_chain_signing_params_cli_schema {
 			#[allow(dead_code)]
 			impl TransactionParamsProvider for [<$chain SigningParams>] {
 				fn is_defined(&self) -> bool {
-					self.[<$chain_prefix _signer>].is_some() || self.[<$chain_prefix _signer_file>].is_some()
+					$crate::cli::secrets::is_secret_defined(
+						stringify!([<$chain_prefix _signer>]),
+						self.[<$chain_prefix _signer>].is_some() || self.[<$chain_prefix _signer_file>].is_some(),
+					) || $crate::cli::secrets::resolve_path(
+						stringify!([<$chain_prefix _signer_keystore>]),
+						self.[<$chain_prefix _signer_keystore>].as_ref(),
+					).is_some()
 				}
 
 				fn transaction_params<Chain: ChainWithTransactions>(&self) -> anyhow::Result<TransactionParams<AccountKeyPairOf<Chain>>> {
 					Ok(TransactionParams {
 						mortality: self.transactions_mortality()?,
 						signer: self.to_keypair::<Chain>()?,
+						spending_budget: self.spending_budget::<Chain>()?,
+						call_wrapper: self.call_wrapper()?,
 					})
 				}
 			}
@@ -307,8 +401,17 @@ mod tests {
 
 				target_signer_file: None,
 				target_signer_password_file: None,
+				target_signer_keystore: None,
 
 				target_transactions_mortality: None,
+				target_max_spending_per_hour: None,
+				target_max_spending_per_day: None,
+				target_min_balance: None,
+				target_balance_threshold: None,
+				target_proxy_pallet_index: None,
+				target_proxied_address: None,
+				target_multisig_pallet_index: None,
+				target_multisig_other_signatories: vec![],
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -324,8 +427,17 @@ mod tests {
 
 				target_signer_file: Some(suri_file_path.clone()),
 				target_signer_password_file: Some(password_file_path.clone()),
+				target_signer_keystore: None,
 
 				target_transactions_mortality: None,
+				target_max_spending_per_hour: None,
+				target_max_spending_per_day: None,
+				target_min_balance: None,
+				target_balance_threshold: None,
+				target_proxy_pallet_index: None,
+				target_proxied_address: None,
+				target_multisig_pallet_index: None,
+				target_multisig_other_signatories: vec![],
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -341,8 +453,17 @@ mod tests {
 
 				target_signer_file: Some(suri_file_path.clone()),
 				target_signer_password_file: Some(password_file_path.clone()),
+				target_signer_keystore: None,
 
 				target_transactions_mortality: None,
+				target_max_spending_per_hour: None,
+				target_max_spending_per_day: None,
+				target_min_balance: None,
+				target_balance_threshold: None,
+				target_proxy_pallet_index: None,
+				target_proxied_address: None,
+				target_multisig_pallet_index: None,
+				target_multisig_other_signatories: vec![],
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -358,8 +479,17 @@ mod tests {
 
 				target_signer_file: Some(suri_file_path),
 				target_signer_password_file: Some(password_file_path),
+				target_signer_keystore: None,
 
 				target_transactions_mortality: None,
+				target_max_spending_per_hour: None,
+				target_max_spending_per_day: None,
+				target_min_balance: None,
+				target_balance_threshold: None,
+				target_proxy_pallet_index: None,
+				target_proxied_address: None,
+				target_multisig_pallet_index: None,
+				target_multisig_other_signatories: vec![],
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -0,0 +1,305 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::{chain_schema::*, CliChain};
+
+use bp_relayers::RelayerRewardsKeyProvider;
+use bp_runtime::{storage_map_final_key, StorageDoubleMapKeyProvider};
+use codec::{Decode, Encode};
+use frame_support::Blake2_128Concat;
+use num_traits::Zero;
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, Chain, ChainWithTransactions, ChainWithUtilityPallet,
+	Client, HashOf, UnsignedTransaction, UtilityPallet,
+};
+use relay_utils::{TrackedTransactionStatus, TransactionTracker};
+use sp_core::Pair;
+use std::fmt::Debug;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Name of the relayers pallet at all supported chains.
+pub const RELAYERS_PALLET_NAME: &str = "BridgeRelayers";
+
+/// Claim all pending relayer rewards.
+#[derive(StructOpt)]
+pub struct ClaimRewards {
+	/// A chain where rewards are claimed.
+	#[structopt(possible_values = ClaimRewardsChain::VARIANTS, case_insensitive = true)]
+	chain: ClaimRewardsChain,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	/// Claim all rewards using single batch transaction.
+	#[structopt(long)]
+	batch: bool,
+}
+
+/// Chain, where rewards are claimed.
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ClaimRewardsChain {
+	Millau,
+	Rialto,
+	RialtoParachain,
+	BridgeHubRococo,
+	BridgeHubWococo,
+}
+
+/// Chain, where relayers pallet is deployed.
+pub(crate) trait RewardsClaimer: ChainWithTransactions + CliChain
+where
+	AccountIdOf<Self>: From<<AccountKeyPairOf<Self> as Pair>::Public>,
+{
+	/// Rewards account parameters, as they are known to the chain runtime.
+	type RewardsAccountParams: Decode + Debug;
+
+	/// Encode `claim_rewards` call of the relayers pallet.
+	fn encode_claim_rewards(rewards_account_params: Self::RewardsAccountParams) -> Self::Call;
+
+	/// Encode batch call. Returns `None` if the chain doesn't support batching.
+	fn encode_batch(_calls: Vec<Self::Call>) -> Option<Self::Call> {
+		None
+	}
+}
+
+impl RewardsClaimer for relay_millau_client::Millau {
+	type RewardsAccountParams = bp_relayers::RewardsAccountParams;
+
+	fn encode_claim_rewards(rewards_account_params: Self::RewardsAccountParams) -> Self::Call {
+		millau_runtime::BridgeRelayersCall::<millau_runtime::Runtime>::claim_rewards {
+			rewards_account_params,
+		}
+		.into()
+	}
+
+	fn encode_batch(calls: Vec<Self::Call>) -> Option<Self::Call> {
+		Some(<Self as ChainWithUtilityPallet>::UtilityPallet::build_batch_call(calls))
+	}
+}
+
+impl RewardsClaimer for relay_rialto_client::Rialto {
+	type RewardsAccountParams = bp_relayers::RewardsAccountParams;
+
+	fn encode_claim_rewards(rewards_account_params: Self::RewardsAccountParams) -> Self::Call {
+		rialto_runtime::BridgeRelayersCall::<rialto_runtime::Runtime>::claim_rewards {
+			rewards_account_params,
+		}
+		.into()
+	}
+}
+
+impl RewardsClaimer for relay_rialto_parachain_client::RialtoParachain {
+	type RewardsAccountParams =
+		relay_rialto_parachain_client::runtime_types::bp_relayers::RewardsAccountParams;
+
+	fn encode_claim_rewards(rewards_account_params: Self::RewardsAccountParams) -> Self::Call {
+		type RelayersCall =
+			relay_rialto_parachain_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_rialto_parachain_client::RuntimeCall::BridgeRelayers(RelayersCall::claim_rewards {
+			rewards_account_params,
+		})
+	}
+}
+
+impl RewardsClaimer for relay_bridge_hub_rococo_client::BridgeHubRococo {
+	type RewardsAccountParams =
+		relay_bridge_hub_rococo_client::runtime_types::bp_relayers::RewardsAccountParams;
+
+	fn encode_claim_rewards(rewards_account_params: Self::RewardsAccountParams) -> Self::Call {
+		type RelayersCall =
+			relay_bridge_hub_rococo_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_bridge_hub_rococo_client::RuntimeCall::BridgeRelayers(RelayersCall::claim_rewards {
+			rewards_account_params,
+		})
+	}
+
+	fn encode_batch(calls: Vec<Self::Call>) -> Option<Self::Call> {
+		Some(<Self as ChainWithUtilityPallet>::UtilityPallet::build_batch_call(calls))
+	}
+}
+
+impl RewardsClaimer for relay_bridge_hub_wococo_client::BridgeHubWococo {
+	type RewardsAccountParams =
+		relay_bridge_hub_wococo_client::runtime_types::bp_relayers::RewardsAccountParams;
+
+	fn encode_claim_rewards(rewards_account_params: Self::RewardsAccountParams) -> Self::Call {
+		type RelayersCall =
+			relay_bridge_hub_wococo_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_bridge_hub_wococo_client::RuntimeCall::BridgeRelayers(RelayersCall::claim_rewards {
+			rewards_account_params,
+		})
+	}
+
+	fn encode_batch(calls: Vec<Self::Call>) -> Option<Self::Call> {
+		Some(<Self as ChainWithUtilityPallet>::UtilityPallet::build_batch_call(calls))
+	}
+}
+
+macro_rules! select_chain {
+	($chain: expr, $generic: tt) => {
+		match $chain {
+			ClaimRewardsChain::Millau => {
+				type Target = relay_millau_client::Millau;
+
+				$generic
+			},
+			ClaimRewardsChain::Rialto => {
+				type Target = relay_rialto_client::Rialto;
+
+				$generic
+			},
+			ClaimRewardsChain::RialtoParachain => {
+				type Target = relay_rialto_parachain_client::RialtoParachain;
+
+				$generic
+			},
+			ClaimRewardsChain::BridgeHubRococo => {
+				type Target = relay_bridge_hub_rococo_client::BridgeHubRococo;
+
+				$generic
+			},
+			ClaimRewardsChain::BridgeHubWococo => {
+				type Target = relay_bridge_hub_wococo_client::BridgeHubWococo;
+
+				$generic
+			},
+		}
+	};
+}
+
+impl ClaimRewards {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_chain!(self.chain, {
+			let client = self.target.into_client::<Target>().await?;
+			let signer = self.target_sign.to_keypair::<Target>()?;
+			claim_rewards::<Target>(client, signer, self.batch).await
+		})
+	}
+}
+
+/// Returns all pending rewards of the relayer.
+pub(crate) async fn pending_rewards<C: RewardsClaimer>(
+	client: &impl Client<C>,
+	at: HashOf<C>,
+	relayer: &AccountIdOf<C>,
+) -> anyhow::Result<Vec<(C::RewardsAccountParams, BalanceOf<C>)>>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+{
+	// all rewards of the relayer are stored under the same prefix and the rest of the storage key
+	// is the encoded (using `Identity` hasher) rewards account params
+	let prefix = storage_map_final_key::<Blake2_128Concat>(
+		RELAYERS_PALLET_NAME,
+		RelayerRewardsKeyProvider::<AccountIdOf<C>, BalanceOf<C>>::MAP_NAME,
+		&relayer.encode(),
+	);
+	let mut rewards = Vec::new();
+	for key in client.storage_keys(at, prefix.clone()).await? {
+		let rewards_account_params = C::RewardsAccountParams::decode(&mut &key.0[prefix.0.len()..])
+			.map_err(|e| anyhow::format_err!("Failed to decode rewards account params: {:?}", e))?;
+		let reward =
+			client.storage_value::<BalanceOf<C>>(at, key).await?.unwrap_or_else(Zero::zero);
+		rewards.push((rewards_account_params, reward));
+	}
+	Ok(rewards)
+}
+
+/// Claim all pending rewards of the signer account.
+async fn claim_rewards<C: RewardsClaimer>(
+	client: impl Client<C>,
+	signer: AccountKeyPairOf<C>,
+	batch: bool,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+{
+	let relayer: AccountIdOf<C> = signer.public().into();
+	let at = client.best_finalized_header_hash().await?;
+
+	let mut calls = Vec::new();
+	for (rewards_account_params, reward) in pending_rewards(&client, at, &relayer).await? {
+		log::info!(
+			target: "bridge",
+			"Relayer {:?} has pending reward {:?} at {}: {:?}",
+			relayer,
+			reward,
+			C::NAME,
+			rewards_account_params,
+		);
+		calls.push(C::encode_claim_rewards(rewards_account_params));
+	}
+
+	if calls.is_empty() {
+		log::info!(target: "bridge", "Relayer {:?} has no pending rewards at {}", relayer, C::NAME);
+		return Ok(())
+	}
+
+	let calls = if batch {
+		vec![C::encode_batch(calls)
+			.ok_or_else(|| anyhow::format_err!("Batch calls are not supported by {}", C::NAME))?]
+	} else {
+		calls
+	};
+	for call in calls {
+		let tx_status = client
+			.submit_and_watch_signed_extrinsic(&signer, move |_, transaction_nonce| {
+				Ok(UnsignedTransaction::new(call.into(), transaction_nonce))
+			})
+			.await?
+			.wait()
+			.await;
+		match tx_status {
+			TrackedTransactionStatus::Finalized(block) => log::info!(
+				target: "bridge",
+				"Rewards claim transaction has been finalized at {} block {:?}",
+				C::NAME,
+				block,
+			),
+			TrackedTransactionStatus::Lost =>
+				return Err(anyhow::format_err!("Rewards claim transaction at {} is lost", C::NAME)),
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn claim_rewards_params_are_parsed() {
+		let claim_rewards = ClaimRewards::from_iter(vec![
+			"claim-rewards",
+			"bridge-hub-rococo",
+			"--target-port",
+			"1234",
+			"--target-signer",
+			"//Alice",
+			"--batch",
+		]);
+
+		assert!(matches!(claim_rewards.chain, ClaimRewardsChain::BridgeHubRococo));
+		assert_eq!(claim_rewards.target.target_port, 1234);
+		assert!(claim_rewards.batch);
+	}
+}
@@ -0,0 +1,354 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reading relay arguments from the TOML configuration file.
+//!
+//! Every top-level key of the configuration file is the name of the long CLI option (without
+//! the `--` prefix) and its value is the option value. Boolean values are used for flags and
+//! arrays are used for options that may be specified multiple times (e.g. `lane`):
+//!
+//! ```toml
+//! millau-host = "millau-node-alice"
+//! millau-port = 9944
+//! millau-secure = false
+//! millau-signer = "//Charlie"
+//! lane = ["0x00000000", "0x00000001"]
+//! prometheus-port = 9616
+//! ```
+//!
+//! The subcommand and positional arguments (e.g. bridge name) are still specified in the command
+//! line. Options that are explicitly specified in the command line override values from the file.
+//!
+//! Some options may be reloaded without restarting the relay. The complex relay re-reads the
+//! configuration file when it receives the `SIGHUP` signal and starts serving lanes that have
+//! been added to the `lane` option. Only additions are supported: the reload is rejected if some
+//! lane has been removed from the file or if any other option has been changed - removing lanes
+//! and changing other options requires the relay restart.
+
+use anyhow::format_err;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::OnceLock,
+};
+
+/// Name of the CLI option that points to the configuration file.
+const CONFIG_OPTION: &str = "--config";
+
+/// Options and their arguments, read from the configuration file when the relay has started.
+static STARTUP_CONFIG_ARGS: OnceLock<Vec<(String, Vec<String>)>> = OnceLock::new();
+
+/// If `--config <file>` option is specified, read the configuration file and insert options from
+/// it into given command line arguments.
+pub fn merge_config_file_args(args: Vec<String>) -> anyhow::Result<Vec<String>> {
+	let (config_path, mut args) = extract_config_path(args)?;
+	let config_path = match config_path {
+		Some(config_path) => config_path,
+		None => return Ok(args),
+	};
+
+	let config = std::fs::read_to_string(&config_path)
+		.map_err(|e| format_err!("Failed to read configuration file {}: {}", config_path, e))?;
+	let config_args = config_to_args(&config)
+		.map_err(|e| format_err!("Invalid configuration file {}: {}", config_path, e))?;
+	let _ = STARTUP_CONFIG_ARGS.set(config_args.clone());
+	for (option, option_args) in config_args {
+		if !is_overridden(&args, &option) {
+			args.extend(option_args);
+		}
+	}
+
+	Ok(args)
+}
+
+/// Re-read the configuration file, specified in the process command line, and return values of
+/// given option (e.g. `lane`) from it.
+///
+/// Returns `None` if the configuration file is not used or if the option is explicitly specified
+/// in the command line, so the configuration file can't change it. Returns error if any other
+/// option, that is not specified in the command line, has been changed in the configuration file
+/// since the relay has started.
+pub fn reload_option_values(option_name: &str) -> anyhow::Result<Option<Vec<String>>> {
+	reload_option_values_from_args(
+		std::env::args().collect(),
+		STARTUP_CONFIG_ARGS.get().map(Vec::as_slice).unwrap_or_default(),
+		option_name,
+	)
+}
+
+fn reload_option_values_from_args(
+	args: Vec<String>,
+	startup_config_args: &[(String, Vec<String>)],
+	option_name: &str,
+) -> anyhow::Result<Option<Vec<String>>> {
+	let (config_path, args) = extract_config_path(args)?;
+	let config_path = match config_path {
+		Some(config_path) => config_path,
+		None => return Ok(None),
+	};
+	let option = format!("--{option_name}");
+	if is_overridden(&args, &option) {
+		return Ok(None)
+	}
+
+	let config = std::fs::read_to_string(&config_path)
+		.map_err(|e| format_err!("Failed to read configuration file {}: {}", config_path, e))?;
+	let config_args = config_to_args(&config)
+		.map_err(|e| format_err!("Invalid configuration file {}: {}", config_path, e))?;
+
+	let mut startup_config_args = startup_config_args.iter().cloned().collect::<BTreeMap<_, _>>();
+	let mut config_args = config_args.into_iter().collect::<BTreeMap<_, _>>();
+	let values = config_args
+		.remove(&option)
+		.map(|option_args| option_args.into_iter().filter(|arg| *arg != option).collect())
+		.unwrap_or_default();
+	startup_config_args.remove(&option);
+
+	let changed_options = startup_config_args
+		.keys()
+		.chain(config_args.keys())
+		.filter(|config_option| !is_overridden(&args, config_option))
+		.filter(|config_option| {
+			startup_config_args.get(*config_option).filter(|args| !args.is_empty()) !=
+				config_args.get(*config_option).filter(|args| !args.is_empty())
+		})
+		.collect::<BTreeSet<_>>();
+	if !changed_options.is_empty() {
+		return Err(format_err!(
+			"Options {:?} have been changed in the configuration file {}. Only `{}` option may be \
+			reloaded, changing other options requires relay restart",
+			changed_options,
+			config_path,
+			option_name,
+		))
+	}
+
+	Ok(Some(values))
+}
+
+/// Returns true if option is explicitly specified in the command line.
+fn is_overridden(args: &[String], option: &str) -> bool {
+	args.iter().any(|arg| {
+		arg == option || arg.strip_prefix(option).map(|rest| rest.starts_with('=')).unwrap_or(false)
+	})
+}
+
+/// Remove `--config <file>` (or `--config=<file>`) from the command line arguments and return
+/// the path to the configuration file.
+fn extract_config_path(args: Vec<String>) -> anyhow::Result<(Option<String>, Vec<String>)> {
+	let mut config_path = None;
+	let mut other_args = Vec::with_capacity(args.len());
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		let path = if arg == CONFIG_OPTION {
+			Some(args.next().ok_or_else(|| format_err!("Missing value of {}", CONFIG_OPTION))?)
+		} else {
+			arg.strip_prefix(CONFIG_OPTION)
+				.and_then(|rest| rest.strip_prefix('='))
+				.map(ToString::to_string)
+		};
+
+		match path {
+			Some(_) if config_path.is_some() =>
+				return Err(format_err!("{} may only be specified once", CONFIG_OPTION)),
+			Some(path) => config_path = Some(path),
+			None => other_args.push(arg),
+		}
+	}
+
+	Ok((config_path, other_args))
+}
+
+/// Convert configuration file into list of CLI options and their arguments.
+fn config_to_args(config: &str) -> anyhow::Result<Vec<(String, Vec<String>)>> {
+	table_to_args(config.parse()?)
+}
+
+/// Convert TOML table into list of CLI options and their arguments.
+pub fn table_to_args(table: toml::Table) -> anyhow::Result<Vec<(String, Vec<String>)>> {
+	table
+		.into_iter()
+		.map(|(key, value)| {
+			let option = format!("--{key}");
+			let values = match value {
+				toml::Value::Array(values) => values,
+				value => vec![value],
+			};
+
+			let mut option_args = Vec::new();
+			for value in values {
+				match value {
+					toml::Value::Boolean(true) => option_args.push(option.clone()),
+					toml::Value::Boolean(false) => (),
+					toml::Value::String(value) => option_args.extend([option.clone(), value]),
+					toml::Value::Integer(value) =>
+						option_args.extend([option.clone(), value.to_string()]),
+					toml::Value::Float(value) =>
+						option_args.extend([option.clone(), value.to_string()]),
+					_ => return Err(format_err!("Unsupported value of `{}` option", key)),
+				}
+			}
+
+			Ok((option, option_args))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+
+	fn args(args: &[&str]) -> Vec<String> {
+		args.iter().map(ToString::to_string).collect()
+	}
+
+	#[test]
+	fn args_are_not_changed_without_config_option() {
+		let cli_args = args(&["substrate-relay", "relay-messages", "millau-to-rialto"]);
+		assert_eq!(merge_config_file_args(cli_args.clone()).unwrap(), cli_args);
+	}
+
+	#[test]
+	fn config_file_args_are_merged_with_cli_args() {
+		let mut config_file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(
+			config_file,
+			r#"
+				source-host = "millau-node"
+				source-port = 9944
+				source-secure = true
+				target-secure = false
+				lane = ["0x00000000", "0x00000001"]
+			"#
+		)
+		.unwrap();
+		let config_path = config_file.path().to_str().unwrap();
+
+		assert_eq!(
+			merge_config_file_args(args(&[
+				"substrate-relay",
+				"relay-messages",
+				"millau-to-rialto",
+				"--config",
+				config_path,
+				"--source-port=9945",
+			]))
+			.unwrap(),
+			args(&[
+				"substrate-relay",
+				"relay-messages",
+				"millau-to-rialto",
+				"--source-port=9945",
+				"--lane",
+				"0x00000000",
+				"--lane",
+				"0x00000001",
+				"--source-host",
+				"millau-node",
+				"--source-secure",
+			]),
+		);
+	}
+
+	#[test]
+	fn option_values_are_reloaded_from_config_file() {
+		let mut config_file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(config_file, r#"lane = ["0x00000000", "0x00000001"]"#).unwrap();
+		let config_path = config_file.path().to_str().unwrap();
+
+		let startup_config_args = vec![("--lane".to_string(), args(&["--lane", "0x00000000"]))];
+		let cli_args =
+			args(&["substrate-relay", "relay-headers-and-messages", "--config", config_path]);
+		assert_eq!(
+			reload_option_values_from_args(cli_args.clone(), &startup_config_args, "lane").unwrap(),
+			Some(args(&["0x00000000", "0x00000001"])),
+		);
+		assert!(reload_option_values_from_args(cli_args, &startup_config_args, "prometheus-port")
+			.is_err());
+
+		let cli_args = args(&["substrate-relay", "--config", config_path, "--lane=0x00000002"]);
+		assert_eq!(
+			reload_option_values_from_args(cli_args, &startup_config_args, "lane").unwrap(),
+			None
+		);
+		assert_eq!(
+			reload_option_values_from_args(
+				args(&["substrate-relay"]),
+				&startup_config_args,
+				"lane"
+			)
+			.unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn reload_is_rejected_if_other_options_have_been_changed() {
+		let mut config_file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(
+			config_file,
+			r#"
+				lane = ["0x00000000", "0x00000001"]
+				source-port = 9945
+				source-secure = false
+			"#
+		)
+		.unwrap();
+		let config_path = config_file.path().to_str().unwrap();
+		let cli_args = args(&["substrate-relay", "--config", config_path]);
+
+		// only lanes have been changed and flag, that was not set, is still not set
+		let startup_config_args = config_to_args(
+			r#"
+				lane = ["0x00000000"]
+				source-port = 9945
+			"#,
+		)
+		.unwrap();
+		assert_eq!(
+			reload_option_values_from_args(cli_args.clone(), &startup_config_args, "lane").unwrap(),
+			Some(args(&["0x00000000", "0x00000001"])),
+		);
+
+		// port has been changed in the configuration file
+		let startup_config_args = config_to_args(
+			r#"
+				lane = ["0x00000000"]
+				source-port = 9944
+			"#,
+		)
+		.unwrap();
+		assert!(reload_option_values_from_args(cli_args, &startup_config_args, "lane").is_err());
+
+		// port has been changed in the configuration file, but it is overridden in the command
+		// line
+		let cli_args = args(&["substrate-relay", "--config", config_path, "--source-port", "9946"]);
+		assert_eq!(
+			reload_option_values_from_args(cli_args, &startup_config_args, "lane").unwrap(),
+			Some(args(&["0x00000000", "0x00000001"])),
+		);
+	}
+
+	#[test]
+	fn config_option_is_rejected_without_value() {
+		assert!(merge_config_file_args(args(&["substrate-relay", "--config"])).is_err());
+	}
+
+	#[test]
+	fn nested_tables_are_rejected() {
+		assert!(config_to_args("[source]\nhost = \"millau-node\"").is_err());
+	}
+}
@@ -0,0 +1,155 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use async_trait::async_trait;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+use crate::bridges::{
+	kusama_polkadot::{
+		kusama_headers_to_bridge_hub_polkadot::KusamaToBridgeHubPolkadotCliBridge,
+		polkadot_headers_to_bridge_hub_kusama::PolkadotToBridgeHubKusamaCliBridge,
+	},
+	rialto_millau::{
+		millau_headers_to_rialto::MillauToRialtoCliBridge,
+		rialto_headers_to_millau::RialtoToMillauCliBridge,
+	},
+	rialto_parachain_millau::millau_headers_to_rialto_parachain::MillauToRialtoParachainCliBridge,
+	rococo_wococo::{
+		rococo_headers_to_bridge_hub_wococo::RococoToBridgeHubWococoCliBridge,
+		wococo_headers_to_bridge_hub_rococo::WococoToBridgeHubRococoCliBridge,
+	},
+	westend_millau::westend_headers_to_millau::WestendToMillauCliBridge,
+};
+use relay_substrate_client::{BlockNumberOf, Chain};
+use substrate_relay_helper::equivocation::find_equivocations_in_range;
+
+use crate::cli::{bridge::*, chain_schema::*};
+
+/// Scan given range of source chain headers, imported by the target chain, for equivocations.
+#[derive(StructOpt)]
+pub struct DetectEquivocations {
+	/// A bridge instance to look for equivocations in.
+	#[structopt(possible_values = DetectEquivocationsBridge::VARIANTS, case_insensitive = true)]
+	bridge: DetectEquivocationsBridge,
+	/// Number of the first source chain header to check.
+	#[structopt(long)]
+	from_block: u32,
+	/// Number of the last source chain header to check.
+	#[structopt(long)]
+	to_block: u32,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+}
+
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+/// Equivocations detection bridge.
+pub enum DetectEquivocationsBridge {
+	MillauToRialto,
+	RialtoToMillau,
+	WestendToMillau,
+	MillauToRialtoParachain,
+	RococoToBridgeHubWococo,
+	WococoToBridgeHubRococo,
+	KusamaToBridgeHubPolkadot,
+	PolkadotToBridgeHubKusama,
+}
+
+#[async_trait]
+trait EquivocationsDetector: RelayToRelayHeadersCliBridge
+where
+	BlockNumberOf<Self::Source>: From<u32>,
+{
+	/// Scan headers range for equivocations.
+	async fn detect_equivocations(data: DetectEquivocations) -> anyhow::Result<()> {
+		if data.from_block > data.to_block {
+			return Err(anyhow::format_err!(
+				"Invalid headers range: {} > {}",
+				data.from_block,
+				data.to_block,
+			))
+		}
+
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		let target_client = data.target.into_client::<Self::Target>().await?;
+
+		let equivocations = find_equivocations_in_range::<Self::Finality>(
+			&source_client,
+			&target_client,
+			data.from_block.into()..=data.to_block.into(),
+		)
+		.await?;
+
+		log::info!(
+			target: "bridge",
+			"Found {} equivocations in {} headers [{}; {}], imported by {}",
+			equivocations.len(),
+			Self::Source::NAME,
+			data.from_block,
+			data.to_block,
+			Self::Target::NAME,
+		);
+		for equivocation in &equivocations {
+			println!(
+				"{} header {:?} imported at {} block {:?}: {:?}",
+				Self::Source::NAME,
+				equivocation.source_header_number,
+				Self::Target::NAME,
+				equivocation.target_block_number,
+				equivocation.equivocation,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl EquivocationsDetector for MillauToRialtoCliBridge {}
+impl EquivocationsDetector for RialtoToMillauCliBridge {}
+impl EquivocationsDetector for WestendToMillauCliBridge {}
+impl EquivocationsDetector for MillauToRialtoParachainCliBridge {}
+impl EquivocationsDetector for RococoToBridgeHubWococoCliBridge {}
+impl EquivocationsDetector for WococoToBridgeHubRococoCliBridge {}
+impl EquivocationsDetector for KusamaToBridgeHubPolkadotCliBridge {}
+impl EquivocationsDetector for PolkadotToBridgeHubKusamaCliBridge {}
+
+impl DetectEquivocations {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			DetectEquivocationsBridge::MillauToRialto =>
+				MillauToRialtoCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::RialtoToMillau =>
+				RialtoToMillauCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::WestendToMillau =>
+				WestendToMillauCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::MillauToRialtoParachain =>
+				MillauToRialtoParachainCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::RococoToBridgeHubWococo =>
+				RococoToBridgeHubWococoCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::WococoToBridgeHubRococo =>
+				WococoToBridgeHubRococoCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::KusamaToBridgeHubPolkadot =>
+				KusamaToBridgeHubPolkadotCliBridge::detect_equivocations(self),
+			DetectEquivocationsBridge::PolkadotToBridgeHubKusama =>
+				PolkadotToBridgeHubKusamaCliBridge::detect_equivocations(self),
+		}
+		.await
+	}
+}
@@ -0,0 +1,249 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	bridges::{
+		rialto_millau::{
+			millau_headers_to_rialto::MillauToRialtoCliBridge,
+			rialto_headers_to_millau::RialtoToMillauCliBridge,
+		},
+		rialto_parachain_millau::{
+			millau_headers_to_rialto_parachain::MillauToRialtoParachainCliBridge,
+			rialto_parachains_to_millau::RialtoParachainToMillauCliBridge,
+		},
+	},
+	cli::{
+		bridge::{FullBridge, MessagesCliBridge},
+		chain_schema::*,
+		encode_message::{self, CliEncodeMessage},
+		send_message::decode_xcm,
+		HexLaneId,
+	},
+};
+use async_trait::async_trait;
+use bp_messages::{target_chain::FromBridgedChainMessagesProof, LaneId};
+use bp_runtime::{EncodedOrDecodedCall, UnverifiedStorageProof};
+use codec::{Decode, Encode};
+use frame_support::weights::Weight;
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, Chain, ChainWithTransactions, Client, SignParam,
+	UnsignedTransaction,
+};
+use sp_core::Pair;
+use structopt::StructOpt;
+use strum::VariantNames;
+use substrate_relay_helper::messages::{
+	source::expected_delivery_reward, ReceiveMessagesProofCallBuilder, SubstrateMessageLane,
+};
+
+/// Estimate cost of sending message over the bridge.
+#[derive(StructOpt)]
+pub struct EstimateMessageFee {
+	/// A bridge instance to estimate message fee for.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	/// Hex-encoded id of the lane, where message is sent.
+	#[structopt(long, default_value = "00000000")]
+	lane: HexLaneId,
+	/// Ref-time component of the message dispatch weight at the target chain.
+	#[structopt(long, default_value = "0")]
+	dispatch_weight: u64,
+	/// Message type.
+	#[structopt(subcommand)]
+	message: encode_message::Message,
+}
+
+/// Lane identifier type, used by the bridge.
+type LaneIdOf<B> = <<B as MessagesCliBridge>::MessagesLane as SubstrateMessageLane>::LaneId;
+/// Builder of the messages delivery call, used by the bridge.
+type ReceiveMessagesProofCallBuilderOf<B> =
+	<<B as MessagesCliBridge>::MessagesLane as SubstrateMessageLane>::ReceiveMessagesProofCallBuilder;
+
+#[async_trait]
+trait MessageFeeEstimator: MessagesCliBridge
+where
+	Self::Source: ChainWithTransactions + CliEncodeMessage,
+	BalanceOf<Self::Source>: Into<u128>,
+	BalanceOf<Self::Target>: Into<u128>,
+	AccountIdOf<Self::Source>: From<<AccountKeyPairOf<Self::Source> as Pair>::Public>,
+{
+	/// Estimate and print cost of sending the message.
+	async fn estimate_message_fee(data: EstimateMessageFee) -> anyhow::Result<()> {
+		let payload = encode_message::encode_message::<Self::Source, Self::Target>(&data.message)?;
+		let payload_len = payload.len();
+
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		let target_client = data.target.into_client::<Self::Target>().await?;
+
+		// cost of the `send_message` transaction at the source chain
+		let send_message_call = Self::Source::encode_execute_xcm(decode_xcm(payload)?)?;
+		let send_message_tx = dummy_transaction::<Self::Source>(send_message_call)?;
+		let source_best_header_hash = source_client.best_header_hash().await?;
+		let sending_cost: u128 = source_client
+			.estimate_extrinsic_fee(source_best_header_hash, send_message_tx)
+			.await?
+			.into();
+
+		// cost of the single message delivery transaction at the target chain
+		let lane = LaneIdOf::<Self>::try_from(LaneId::from(data.lane.clone())).map_err(|_| {
+			anyhow::format_err!("Lane {:?} is not supported by the bridge", data.lane)
+		})?;
+		let delivery_reward = expected_delivery_reward::<Self::MessagesLane>(
+			&source_client,
+			source_best_header_hash,
+			lane,
+		)
+		.await?;
+		let dispatch_weight = Weight::from_parts(data.dispatch_weight, 0);
+		let delivery_call =
+			ReceiveMessagesProofCallBuilderOf::<Self>::build_receive_messages_proof_call(
+				dummy_keypair::<Self::Source>()?.public().into(),
+				(
+					dispatch_weight,
+					FromBridgedChainMessagesProof {
+						bridged_header_hash: Default::default(),
+						storage: dummy_storage_proof(payload_len)?,
+						lane,
+						nonces_start: 1,
+						nonces_end: 1,
+					},
+				),
+				1,
+				dispatch_weight,
+				false,
+			);
+		let delivery_tx = dummy_transaction::<Self::Target>(delivery_call.into())?;
+		let delivery_cost: u128 = target_client
+			.estimate_extrinsic_fee(target_client.best_header_hash().await?, delivery_tx)
+			.await?
+			.into();
+
+		println!("Message size: {payload_len} bytes");
+		println!("Sending cost at {}: {}", Self::Source::NAME, sending_cost);
+		println!("Delivery cost at {}: {}", Self::Target::NAME, delivery_cost);
+		match delivery_reward {
+			Some(delivery_reward) =>
+				println!("Expected relayer reward at {}: {}", Self::Source::NAME, delivery_reward),
+			None => println!("Expected relayer reward at {}: unknown", Self::Source::NAME),
+		}
+
+		Ok(())
+	}
+}
+
+impl MessageFeeEstimator for MillauToRialtoCliBridge {}
+
+impl MessageFeeEstimator for RialtoToMillauCliBridge {}
+
+impl MessageFeeEstimator for MillauToRialtoParachainCliBridge {}
+
+impl MessageFeeEstimator for RialtoParachainToMillauCliBridge {}
+
+impl EstimateMessageFee {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			FullBridge::MillauToRialto => MillauToRialtoCliBridge::estimate_message_fee(self).await,
+			FullBridge::RialtoToMillau => RialtoToMillauCliBridge::estimate_message_fee(self).await,
+			FullBridge::MillauToRialtoParachain =>
+				MillauToRialtoParachainCliBridge::estimate_message_fee(self).await,
+			FullBridge::RialtoParachainToMillau =>
+				RialtoParachainToMillauCliBridge::estimate_message_fee(self).await,
+			FullBridge::BridgeHubRococoToBridgeHubWococo => anyhow::bail!(
+				"Estimating message fee from BridgeHubRococo to BridgeHubWococo is not supported"
+			),
+			FullBridge::BridgeHubWococoToBridgeHubRococo => anyhow::bail!(
+				"Estimating message fee from BridgeHubWococo to BridgeHubRococo is not supported"
+			),
+			FullBridge::BridgeHubKusamaToBridgeHubPolkadot => anyhow::bail!(
+				"Estimating message fee from BridgeHubKusama to BridgeHubPolkadot is not supported"
+			),
+			FullBridge::BridgeHubPolkadotToBridgeHubKusama => anyhow::bail!(
+				"Estimating message fee from BridgeHubPolkadot to BridgeHubKusama is not supported"
+			),
+		}
+	}
+}
+
+/// Returns key pair that is used to sign dummy transactions.
+fn dummy_keypair<C: ChainWithTransactions>() -> anyhow::Result<AccountKeyPairOf<C>> {
+	AccountKeyPairOf::<C>::from_string("//Alice", None)
+		.map_err(|e| anyhow::format_err!("Failed to create dummy key pair: {:?}", e))
+}
+
+/// Returns transaction, signed by the dummy key pair.
+///
+/// The fee estimation RPC doesn't check transaction signature, nonce and era, so we are free
+/// to use dummy values here.
+fn dummy_transaction<C: ChainWithTransactions>(
+	call: EncodedOrDecodedCall<C::Call>,
+) -> anyhow::Result<C::SignedTransaction> {
+	C::sign_transaction(
+		SignParam {
+			spec_version: 0,
+			transaction_version: 0,
+			genesis_hash: Default::default(),
+			signer: dummy_keypair::<C>()?,
+		},
+		UnsignedTransaction::new(call, Default::default()),
+	)
+	.map_err(Into::into)
+}
+
+/// Returns storage proof of given size.
+fn dummy_storage_proof(size: usize) -> anyhow::Result<UnverifiedStorageProof> {
+	// the proof internals are private, so we're decoding it from encoded (trie nodes, entries)
+	let proof: Vec<Vec<u8>> = vec![vec![0u8; size]];
+	let db: Vec<(Vec<u8>, Option<Vec<u8>>)> = Vec::new();
+	UnverifiedStorageProof::decode(&mut &(proof, db).encode()[..])
+		.map_err(|e| anyhow::format_err!("Failed to build dummy storage proof: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bp_runtime::Size;
+
+	#[test]
+	fn estimate_message_fee_params_are_parsed() {
+		let estimate = EstimateMessageFee::from_iter(vec![
+			"estimate-message-fee",
+			"rialto-to-millau",
+			"--lane",
+			"00000001",
+			"--dispatch-weight",
+			"1000000",
+			"sized",
+			"1024",
+		]);
+
+		assert_eq!(estimate.bridge, FullBridge::RialtoToMillau);
+		assert_eq!(estimate.dispatch_weight, 1_000_000);
+		assert_eq!(
+			estimate.message,
+			encode_message::Message::Sized { size: crate::cli::ExplicitOrMaximal::Explicit(1024) },
+		);
+	}
+
+	#[test]
+	fn dummy_storage_proof_has_requested_size() {
+		assert!(dummy_storage_proof(1024).unwrap().size() >= 1024);
+	}
+}
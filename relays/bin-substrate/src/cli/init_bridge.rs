@@ -34,7 +34,7 @@ use crate::{
 		},
 		westend_millau::westend_headers_to_millau::WestendToMillauCliBridge,
 	},
-	cli::{bridge::CliBridgeBase, chain_schema::*},
+	cli::{bridge::CliBridgeBase, chain_schema::*, BlockHashOrNumber},
 };
 use bp_runtime::Chain as ChainBase;
 use relay_substrate_client::{AccountKeyPairOf, Chain, UnsignedTransaction};
@@ -55,9 +55,10 @@ pub struct InitBridge {
 	target: TargetConnectionParams,
 	#[structopt(flatten)]
 	target_sign: TargetSigningParams,
-	/// Generates all required data, but does not submit extrinsic
+	/// Hash or number of the finalized source chain header to initialize the bridge with. By
+	/// default, the next finalized header is used.
 	#[structopt(long)]
-	dry_run: bool,
+	at_block: Option<BlockHashOrNumber>,
 }
 
 #[derive(Debug, EnumString, EnumVariantNames)]
@@ -91,12 +92,17 @@ where
 		let source_client = data.source.into_client::<Self::Source>().await?;
 		let target_client = data.target.into_client::<Self::Target>().await?;
 		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
-		let dry_run = data.dry_run;
+		let dry_run = relay_substrate_client::is_dry_run();
+		let initial_header = match data.at_block {
+			Some(at_block) => Some(at_block.hash(&source_client).await?),
+			None => None,
+		};
 
 		substrate_relay_helper::finality::initialize::initialize::<Self::Engine, _, _, _>(
 			source_client,
 			target_client.clone(),
 			target_sign,
+			initial_header,
 			move |transaction_nonce, initialization_data| {
 				let call = Self::encode_init_bridge(initialization_data);
 				log::info!(
@@ -0,0 +1,372 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use async_trait::async_trait;
+use bp_header_chain::AuthoritySet;
+use bp_messages::{InboundLaneData, LaneId, MessageNonce, MessagesOperatingMode, OutboundLaneData};
+use bp_relayers::{RelayerRewardsKeyProvider, RewardsAccountOwner, RewardsAccountParams};
+use bp_runtime::BasicOperatingMode;
+use relay_substrate_client::{
+	AccountIdOf, BalanceOf, ChainWithGrandpa, ChainWithMessages, Client, HeaderIdOf,
+};
+use relay_utils::UniqueSaturatedInto;
+use serde::Serialize;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+use crate::bridges::rialto_millau::{
+	millau_headers_to_rialto::MillauToRialtoCliBridge,
+	rialto_headers_to_millau::RialtoToMillauCliBridge,
+};
+
+use crate::cli::{bridge::*, chain_schema::*, HexLaneId};
+
+/// Print state of the bridge between two chains.
+#[derive(StructOpt)]
+pub struct InspectBridge {
+	/// A bridge instance to inspect.
+	#[structopt(possible_values = InspectBridgeBridge::VARIANTS, case_insensitive = true)]
+	bridge: InspectBridgeBridge,
+	/// Hex-encoded ids of lanes to inspect.
+	#[structopt(long, default_value = "00000000")]
+	lane: Vec<HexLaneId>,
+	/// Print bridge state as JSON instead of the human-readable table.
+	#[structopt(long)]
+	json: bool,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+}
+
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+/// Inspected bridge.
+pub enum InspectBridgeBridge {
+	MillauToRialto,
+	RialtoToMillau,
+}
+
+/// State of the bridge between two chains.
+#[derive(Debug, Serialize)]
+struct BridgeState {
+	/// State of the source -> target direction.
+	source_to_target: BridgeDirectionState,
+	/// State of the target -> source direction.
+	target_to_source: BridgeDirectionState,
+}
+
+/// State of one direction of the bridge.
+#[derive(Debug, Serialize)]
+struct BridgeDirectionState {
+	/// Name of the chain, which headers and messages are bridged.
+	source_chain: &'static str,
+	/// Name of the chain, where headers and messages are bridged to.
+	target_chain: &'static str,
+	/// Best source header, imported by the target chain.
+	best_source_header_at_target: Option<HeaderState>,
+	/// Id of the source GRANDPA authority set, known to the target chain.
+	source_authority_set_id_at_target: u64,
+	/// Operating mode of the GRANDPA pallet at the target chain.
+	grandpa_pallet_operating_mode: String,
+	/// Operating mode of the messages pallet at the source chain.
+	source_messages_pallet_operating_mode: String,
+	/// Operating mode of the messages pallet at the target chain.
+	target_messages_pallet_operating_mode: String,
+	/// State of inspected lanes.
+	lanes: Vec<LaneState>,
+}
+
+/// Header number and hash.
+#[derive(Debug, Serialize)]
+struct HeaderState {
+	number: u64,
+	hash: String,
+}
+
+/// State of the lane in one bridge direction.
+#[derive(Debug, Serialize)]
+struct LaneState {
+	/// Lane identifier.
+	lane: String,
+	/// Nonce of the latest message, generated at the source chain.
+	latest_generated_nonce: MessageNonce,
+	/// Nonce of the latest message, delivered to the target chain.
+	latest_delivered_nonce: MessageNonce,
+	/// Nonce of the latest message, which delivery has been confirmed at the source chain.
+	latest_confirmed_nonce: MessageNonce,
+	/// Nonce of the oldest message that is not yet pruned at the source chain.
+	oldest_unpruned_nonce: MessageNonce,
+	/// Relayers that have delivered messages, but have not yet been rewarded.
+	unrewarded_relayers: Vec<UnrewardedRelayerState>,
+	/// Rewards of relayers, registered at the source chain and not yet claimed.
+	pending_rewards: Vec<PendingRewardState>,
+}
+
+/// Unrewarded relayer entry.
+#[derive(Debug, Serialize)]
+struct UnrewardedRelayerState {
+	relayer: String,
+	begin: MessageNonce,
+	end: MessageNonce,
+}
+
+/// Relayer reward that has not yet been claimed.
+#[derive(Debug, Serialize)]
+struct PendingRewardState {
+	relayer: String,
+	reward: u128,
+}
+
+#[async_trait]
+trait BridgeInspector: CliBridgeBase
+where
+	Self::Source: ChainWithGrandpa + ChainWithMessages,
+	Self::Target: ChainWithGrandpa + ChainWithMessages,
+{
+	/// Read and print the bridge state.
+	async fn inspect_bridge(data: InspectBridge) -> anyhow::Result<()> {
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		let target_client = data.target.into_client::<Self::Target>().await?;
+		let lanes = data.lane.into_iter().map(Into::into).collect::<Vec<LaneId>>();
+
+		let state = BridgeState {
+			source_to_target: read_direction_state::<Self::Source, Self::Target>(
+				&source_client,
+				&target_client,
+				&lanes,
+			)
+			.await?,
+			target_to_source: read_direction_state::<Self::Target, Self::Source>(
+				&target_client,
+				&source_client,
+				&lanes,
+			)
+			.await?,
+		};
+
+		if data.json {
+			println!("{}", serde_json::to_string_pretty(&state)?);
+		} else {
+			print_direction_state(&state.source_to_target);
+			println!();
+			print_direction_state(&state.target_to_source);
+		}
+
+		Ok(())
+	}
+}
+
+impl BridgeInspector for MillauToRialtoCliBridge {}
+impl BridgeInspector for RialtoToMillauCliBridge {}
+
+impl InspectBridge {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			InspectBridgeBridge::MillauToRialto => MillauToRialtoCliBridge::inspect_bridge(self),
+			InspectBridgeBridge::RialtoToMillau => RialtoToMillauCliBridge::inspect_bridge(self),
+		}
+		.await
+	}
+}
+
+/// Read state of the `Source` -> `Target` bridge direction.
+async fn read_direction_state<Source, Target>(
+	source_client: &impl Client<Source>,
+	target_client: &impl Client<Target>,
+	lanes: &[LaneId],
+) -> anyhow::Result<BridgeDirectionState>
+where
+	Source: ChainWithGrandpa + ChainWithMessages,
+	Target: ChainWithMessages,
+{
+	let at_source = source_client.best_finalized_header_hash().await?;
+	let at_target = target_client.best_finalized_header_hash().await?;
+
+	let best_source_header_at_target = target_client
+		.storage_value::<HeaderIdOf<Source>>(
+			at_target,
+			bp_header_chain::storage_keys::best_finalized_key(
+				Source::WITH_CHAIN_GRANDPA_PALLET_NAME,
+			),
+		)
+		.await?
+		.map(|id| HeaderState {
+			number: id.0.unique_saturated_into(),
+			hash: format!("{:?}", id.1),
+		});
+	let source_authority_set_id_at_target = target_client
+		.storage_value::<AuthoritySet>(
+			at_target,
+			bp_header_chain::storage_keys::current_authority_set_key(
+				Source::WITH_CHAIN_GRANDPA_PALLET_NAME,
+			),
+		)
+		.await?
+		.map(|authority_set| authority_set.set_id)
+		.unwrap_or_default();
+	let grandpa_pallet_operating_mode = target_client
+		.storage_value::<BasicOperatingMode>(
+			at_target,
+			bp_header_chain::storage_keys::pallet_operating_mode_key(
+				Source::WITH_CHAIN_GRANDPA_PALLET_NAME,
+			),
+		)
+		.await?
+		.unwrap_or_default();
+	let source_messages_pallet_operating_mode = source_client
+		.storage_value::<MessagesOperatingMode>(
+			at_source,
+			bp_messages::storage_keys::operating_mode_key(Target::WITH_CHAIN_MESSAGES_PALLET_NAME),
+		)
+		.await?
+		.unwrap_or_default();
+	let target_messages_pallet_operating_mode = target_client
+		.storage_value::<MessagesOperatingMode>(
+			at_target,
+			bp_messages::storage_keys::operating_mode_key(Source::WITH_CHAIN_MESSAGES_PALLET_NAME),
+		)
+		.await?
+		.unwrap_or_default();
+
+	let mut lanes_state = Vec::with_capacity(lanes.len());
+	for lane in lanes {
+		let outbound_lane_data = source_client
+			.storage_value::<OutboundLaneData>(
+				at_source,
+				bp_messages::storage_keys::outbound_lane_data_key(
+					Target::WITH_CHAIN_MESSAGES_PALLET_NAME,
+					lane,
+				),
+			)
+			.await?
+			.unwrap_or_default();
+		let inbound_lane_data = target_client
+			.storage_value::<InboundLaneData<AccountIdOf<Source>>>(
+				at_target,
+				bp_messages::storage_keys::inbound_lane_data_key(
+					Source::WITH_CHAIN_MESSAGES_PALLET_NAME,
+					lane,
+				),
+			)
+			.await?
+			.unwrap_or_default();
+
+		// rewards for delivering messages to the target chain are paid at the source chain
+		let mut pending_rewards = Vec::new();
+		if let Some(relayers_pallet_name) = Target::WITH_CHAIN_RELAYERS_PALLET_NAME {
+			let rewards_account_params =
+				RewardsAccountParams::new(*lane, Target::ID, RewardsAccountOwner::BridgedChain);
+			let mut seen_relayers = Vec::new();
+			for unrewarded_relayer in &inbound_lane_data.relayers {
+				let relayer = &unrewarded_relayer.relayer;
+				if seen_relayers.contains(&relayer) {
+					continue
+				}
+				seen_relayers.push(relayer);
+
+				let reward = source_client
+					.storage_double_map_value::<RelayerRewardsKeyProvider<AccountIdOf<Source>, BalanceOf<Source>>>(
+						at_source,
+						relayers_pallet_name,
+						relayer,
+						&rewards_account_params,
+					)
+					.await?;
+				if let Some(reward) = reward {
+					pending_rewards.push(PendingRewardState {
+						relayer: format!("{relayer:?}"),
+						reward: reward.unique_saturated_into(),
+					});
+				}
+			}
+		}
+
+		lanes_state.push(LaneState {
+			lane: format!("{:?}", lane.as_ref()),
+			latest_generated_nonce: outbound_lane_data.latest_generated_nonce,
+			latest_delivered_nonce: inbound_lane_data.last_delivered_nonce(),
+			latest_confirmed_nonce: outbound_lane_data.latest_received_nonce,
+			oldest_unpruned_nonce: outbound_lane_data.oldest_unpruned_nonce,
+			unrewarded_relayers: inbound_lane_data
+				.relayers
+				.iter()
+				.map(|entry| UnrewardedRelayerState {
+					relayer: format!("{:?}", entry.relayer),
+					begin: entry.messages.begin,
+					end: entry.messages.end,
+				})
+				.collect(),
+			pending_rewards,
+		});
+	}
+
+	Ok(BridgeDirectionState {
+		source_chain: Source::NAME,
+		target_chain: Target::NAME,
+		best_source_header_at_target,
+		source_authority_set_id_at_target,
+		grandpa_pallet_operating_mode: format!("{grandpa_pallet_operating_mode:?}"),
+		source_messages_pallet_operating_mode: format!("{source_messages_pallet_operating_mode:?}"),
+		target_messages_pallet_operating_mode: format!("{target_messages_pallet_operating_mode:?}"),
+		lanes: lanes_state,
+	})
+}
+
+/// Print state of the bridge direction as a human-readable table.
+fn print_direction_state(state: &BridgeDirectionState) {
+	let row = |name: &str, value: String| println!("  {name:<40} {value}");
+
+	println!("{} -> {}", state.source_chain, state.target_chain);
+	row(
+		&format!("Best {} header at {}", state.source_chain, state.target_chain),
+		state
+			.best_source_header_at_target
+			.as_ref()
+			.map(|header| format!("#{} ({})", header.number, header.hash))
+			.unwrap_or_else(|| "<none>".into()),
+	);
+	row(
+		&format!("{} authority set id", state.source_chain),
+		state.source_authority_set_id_at_target.to_string(),
+	);
+	row("GRANDPA pallet operating mode", state.grandpa_pallet_operating_mode.clone());
+	row(
+		&format!("Messages pallet operating mode at {}", state.source_chain),
+		state.source_messages_pallet_operating_mode.clone(),
+	);
+	row(
+		&format!("Messages pallet operating mode at {}", state.target_chain),
+		state.target_messages_pallet_operating_mode.clone(),
+	);
+	for lane in &state.lanes {
+		println!("  Lane {}", lane.lane);
+		row("  Latest generated nonce", lane.latest_generated_nonce.to_string());
+		row("  Latest delivered nonce", lane.latest_delivered_nonce.to_string());
+		row("  Latest confirmed nonce", lane.latest_confirmed_nonce.to_string());
+		row("  Oldest unpruned nonce", lane.oldest_unpruned_nonce.to_string());
+		for entry in &lane.unrewarded_relayers {
+			row(
+				"  Unrewarded relayer",
+				format!("{} (messages {}..={})", entry.relayer, entry.begin, entry.end),
+			);
+		}
+		for reward in &lane.pending_rewards {
+			row("  Pending reward", format!("{} ({})", reward.relayer, reward.reward));
+		}
+	}
+}
@@ -0,0 +1,221 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Support of encrypted JSON keystore files, exported by polkadot-js apps and extension.
+//!
+//! The `encoded` field of the keystore is the base64-encoded concatenation of scrypt
+//! parameters (salt, N, p, r), the xsalsa20-poly1305 nonce and the encrypted PKCS8 document
+//! with the secret and public keys.
+
+use base64::Engine as _;
+use serde::Deserialize;
+use sp_core::{crypto::ByteArray, Pair};
+use std::path::Path;
+use xsalsa20poly1305::{
+	aead::{Aead, KeyInit},
+	Key, Nonce, XSalsa20Poly1305,
+};
+
+/// Name of the environment variable that may hold keystore password.
+pub const KEYSTORE_PASSWORD_ENV: &str = "SUBSTRATE_RELAY_KEYSTORE_PASSWORD";
+
+/// PKCS8 header, preceding the secret key.
+const PKCS8_HEADER: [u8; 16] = [48, 83, 2, 1, 1, 48, 5, 6, 3, 43, 101, 112, 4, 34, 4, 32];
+/// PKCS8 divider between secret and public keys.
+const PKCS8_DIVIDER: [u8; 5] = [161, 35, 3, 33, 0];
+/// Length of the scrypt salt.
+const SCRYPT_SALT_LENGTH: usize = 32;
+/// Length of encoded scrypt parameters (salt, N, p, r).
+const SCRYPT_PARAMS_LENGTH: usize = SCRYPT_SALT_LENGTH + 3 * 4;
+/// Length of the xsalsa20-poly1305 nonce.
+const NONCE_LENGTH: usize = 24;
+
+/// Encrypted JSON keystore.
+#[derive(Deserialize)]
+struct Keystore {
+	/// Base64-encoded encrypted keys.
+	encoded: String,
+	/// Encoding of the `encoded` field.
+	encoding: KeystoreEncoding,
+}
+
+/// Encoding of the encrypted keystore.
+#[derive(Deserialize)]
+struct KeystoreEncoding {
+	/// Content type and key type, e.g. `["pkcs8", "sr25519"]`.
+	content: Vec<String>,
+	/// Encryption algorithms, e.g. `["scrypt", "xsalsa20-poly1305"]`.
+	#[serde(rename = "type")]
+	encryption: Vec<String>,
+}
+
+/// Read key pair from the encrypted JSON keystore file.
+///
+/// If `password` is not provided, it is read from the `SUBSTRATE_RELAY_KEYSTORE_PASSWORD`
+/// environment variable or, if it is not set, from the terminal.
+pub fn read_keypair<P: Pair>(path: &Path, password: Option<String>) -> anyhow::Result<P> {
+	let keystore = std::fs::read_to_string(path)
+		.map_err(|e| anyhow::format_err!("Failed to read keystore file {:?}: {}", path, e))?;
+	let password = match password.or_else(|| std::env::var(KEYSTORE_PASSWORD_ENV).ok()) {
+		Some(password) => password,
+		None => rpassword::prompt_password(format!("Password for keystore {path:?}: "))
+			.map_err(|e| anyhow::format_err!("Failed to read keystore password: {}", e))?,
+	};
+
+	decode_keypair(&keystore, &password)
+		.map_err(|e| anyhow::format_err!("Failed to decode keystore file {:?}: {}", path, e))
+}
+
+/// Decode key pair from the encrypted JSON keystore.
+fn decode_keypair<P: Pair>(keystore: &str, password: &str) -> anyhow::Result<P> {
+	let keystore: Keystore = serde_json::from_str(keystore)?;
+	if keystore.encoding.encryption != ["scrypt", "xsalsa20-poly1305"] {
+		return Err(anyhow::format_err!(
+			"Unsupported keystore encryption: {:?}",
+			keystore.encoding.encryption,
+		))
+	}
+	let key_type = match keystore.encoding.content.as_slice() {
+		[content, key_type] if content == "pkcs8" => key_type.as_str(),
+		content => return Err(anyhow::format_err!("Unsupported keystore content: {:?}", content)),
+	};
+
+	let encoded = base64::engine::general_purpose::STANDARD.decode(keystore.encoded)?;
+	let (secret_key, public_key) = decrypt_pkcs8(&encoded, password)?;
+	let seed = match key_type {
+		"sr25519" => schnorrkel::SecretKey::from_ed25519_bytes(&secret_key)
+			.map_err(|e| anyhow::format_err!("Invalid sr25519 secret key: {:?}", e))?
+			.to_bytes()
+			.to_vec(),
+		"ed25519" => secret_key.get(..32).map(|seed| seed.to_vec()).unwrap_or_default(),
+		"ecdsa" => secret_key,
+		_ => return Err(anyhow::format_err!("Unsupported keystore key type: {}", key_type)),
+	};
+
+	let pair = P::from_seed_slice(&seed)
+		.map_err(|e| anyhow::format_err!("Invalid {} secret key: {:?}", key_type, e))?;
+	if pair.public().to_raw_vec() != public_key {
+		return Err(anyhow::format_err!(
+			"Public key of {} keystore doesn't match the chain account type",
+			key_type,
+		))
+	}
+
+	Ok(pair)
+}
+
+/// Decrypt PKCS8 document and return secret and public keys from it.
+fn decrypt_pkcs8(encoded: &[u8], password: &str) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+	if encoded.len() < SCRYPT_PARAMS_LENGTH + NONCE_LENGTH {
+		return Err(anyhow::format_err!("Encoded keystore is too short"))
+	}
+
+	let read_u32 = |offset: usize| {
+		u32::from_le_bytes(encoded[offset..offset + 4].try_into().expect("slice has 4 bytes; qed"))
+	};
+	let salt = &encoded[..SCRYPT_SALT_LENGTH];
+	let (n, p, r) = (
+		read_u32(SCRYPT_SALT_LENGTH),
+		read_u32(SCRYPT_SALT_LENGTH + 4),
+		read_u32(SCRYPT_SALT_LENGTH + 8),
+	);
+	if !n.is_power_of_two() {
+		return Err(anyhow::format_err!("Invalid scrypt parameter N: {}", n))
+	}
+	let params = scrypt::Params::new(n.trailing_zeros() as u8, r, p, 32)
+		.map_err(|e| anyhow::format_err!("Invalid scrypt parameters: {:?}", e))?;
+	let mut key = [0u8; 32];
+	scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+		.map_err(|e| anyhow::format_err!("Failed to derive keystore key: {:?}", e))?;
+
+	let nonce = &encoded[SCRYPT_PARAMS_LENGTH..SCRYPT_PARAMS_LENGTH + NONCE_LENGTH];
+	let pkcs8 = XSalsa20Poly1305::new(Key::from_slice(&key))
+		.decrypt(Nonce::from_slice(nonce), &encoded[SCRYPT_PARAMS_LENGTH + NONCE_LENGTH..])
+		.map_err(|_| anyhow::format_err!("Invalid keystore password"))?;
+
+	let body = pkcs8
+		.strip_prefix(&PKCS8_HEADER[..])
+		.ok_or_else(|| anyhow::format_err!("Invalid PKCS8 header"))?;
+	let divider_offset = body
+		.windows(PKCS8_DIVIDER.len())
+		.position(|window| window == PKCS8_DIVIDER)
+		.ok_or_else(|| anyhow::format_err!("Invalid PKCS8 document"))?;
+
+	Ok((body[..divider_offset].to_vec(), body[divider_offset + PKCS8_DIVIDER.len()..].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::sr25519;
+
+	fn encrypt_keystore(secret_key: &[u8], public_key: &[u8], password: &str) -> String {
+		// use low N to keep test fast
+		let (salt, n, p, r) = ([42u8; SCRYPT_SALT_LENGTH], 1024u32, 1u32, 8u32);
+		let mut key = [0u8; 32];
+		let params = scrypt::Params::new(n.trailing_zeros() as u8, r, p, 32).unwrap();
+		scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key).unwrap();
+
+		let nonce = [7u8; NONCE_LENGTH];
+		let pkcs8 = [&PKCS8_HEADER[..], secret_key, &PKCS8_DIVIDER[..], public_key].concat();
+		let encrypted = XSalsa20Poly1305::new(Key::from_slice(&key))
+			.encrypt(Nonce::from_slice(&nonce), &pkcs8[..])
+			.unwrap();
+
+		let encoded = [
+			&salt[..],
+			&n.to_le_bytes()[..],
+			&p.to_le_bytes()[..],
+			&r.to_le_bytes()[..],
+			&nonce[..],
+			&encrypted[..],
+		]
+		.concat();
+		serde_json::json!({
+			"encoded": base64::engine::general_purpose::STANDARD.encode(encoded),
+			"encoding": {
+				"content": ["pkcs8", "sr25519"],
+				"type": ["scrypt", "xsalsa20-poly1305"],
+				"version": "3",
+			},
+			"address": "",
+			"meta": {},
+		})
+		.to_string()
+	}
+
+	fn alice_keystore(password: &str) -> (sr25519::Pair, String) {
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap();
+		let secret_key = schnorrkel::SecretKey::from_bytes(&alice.to_raw_vec())
+			.unwrap()
+			.to_ed25519_bytes();
+		let keystore = encrypt_keystore(&secret_key, &alice.public().to_raw_vec(), password);
+		(alice, keystore)
+	}
+
+	#[test]
+	fn sr25519_keystore_is_decoded() {
+		let (alice, keystore) = alice_keystore("password");
+		let decoded: sr25519::Pair = decode_keypair(&keystore, "password").unwrap();
+		assert_eq!(decoded.public(), alice.public());
+	}
+
+	#[test]
+	fn keystore_with_wrong_password_is_rejected() {
+		let (_, keystore) = alice_keystore("password");
+		assert!(decode_keypair::<sr25519::Pair>(&keystore, "wrong").is_err());
+	}
+}
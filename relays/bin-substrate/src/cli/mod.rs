@@ -16,7 +16,7 @@
 
 //! Deal with CLI args of substrate-to-substrate relay.
 
-use std::convert::TryInto;
+use std::{convert::TryInto, path::PathBuf};
 
 use async_std::prelude::*;
 use codec::{Decode, Encode};
@@ -28,38 +28,220 @@ use sp_core::H256;
 use structopt::{clap::arg_enum, StructOpt};
 use strum::{EnumString, EnumVariantNames};
 
-use bp_messages::LaneId;
+use bp_messages::{LaneId, LegacyLaneId};
 use relay_substrate_client::SimpleRuntimeVersion;
+use relay_utils::{
+	alerts::{AlertSink, AlertsParams},
+	initialize::LogFormat,
+	log_file::LogFileParams,
+	otlp::OtlpParams,
+	reconnect_backoff::LoopReconnectBackoff,
+};
 
 pub(crate) mod bridge;
 pub(crate) mod encode_message;
 pub(crate) mod send_message;
 
 mod chain_schema;
+mod claim_rewards;
+mod config_file;
+mod detect_equivocations;
+mod estimate_message_fee;
 mod init_bridge;
+mod inspect_bridge;
+mod keystore;
+mod prune_bridge_storage;
 mod register_parachain;
+mod register_relayer;
+mod reinit_bridge;
+mod relay_bridges;
 mod relay_headers;
 mod relay_headers_and_messages;
 mod relay_messages;
 mod relay_parachains;
+mod relay_state;
+mod relayer_rewards;
 mod resubmit_transactions;
+mod scale_json;
+mod secrets;
+mod shared_clients;
 
 /// The target that will be used when publishing logs related to this pallet.
 pub const LOG_TARGET: &str = "bridge";
 
+/// Maximal time we wait for relay loops to shutdown gracefully after receiving exit signal.
+const SHUTDOWN_TIMEOUT: std::time::Duration = relay_utils::shutdown::SHUTDOWN_DRAIN_TIMEOUT
+	.saturating_add(std::time::Duration::from_secs(30));
+
 /// Default Substrate client type that we are using. We'll use it all over the glue CLI code
 /// to avoid multiple level generic arguments and constraints. We still allow usage of other
 /// clients in the **core logic code**.
 pub type DefaultClient<C> = relay_substrate_client::RpcWithCachingClient<C>;
 
 /// Parse relay CLI args.
-pub fn parse_args() -> Command {
-	Command::from_args()
+///
+/// If `--config <file>` option is specified, options from the TOML configuration file are used
+/// unless they are also specified in the command line.
+pub fn parse_args() -> Cli {
+	match config_file::merge_config_file_args(std::env::args().collect()) {
+		Ok(args) => Cli::from_iter(args),
+		Err(e) => structopt::clap::Error::with_description(
+			&e.to_string(),
+			structopt::clap::ErrorKind::InvalidValue,
+		)
+		.exit(),
+	}
 }
 
-/// Substrate-to-Substrate bridge utilities.
+/// Substrate-to-Substrate relay.
 #[derive(StructOpt)]
 #[structopt(about = "Substrate-to-Substrate relay")]
+pub struct Cli {
+	/// Format of log records: `text` or `json`.
+	#[structopt(long, global = true, default_value = "text")]
+	log_format: LogFormat,
+	/// Write log records to this file instead of stderr.
+	#[structopt(long, global = true)]
+	log_file: Option<PathBuf>,
+	/// Rotate the log file when its size exceeds this number of megabytes.
+	#[structopt(long, global = true)]
+	log_file_max_size_mb: Option<u64>,
+	/// Rotate the log file when it has been written for this number of hours.
+	#[structopt(long, global = true)]
+	log_file_rotation_hours: Option<u64>,
+	/// Number of rotated log files to keep. Older files are removed.
+	#[structopt(long, global = true, default_value = "5")]
+	log_file_keep: usize,
+	/// Export relay loops, races and RPC spans to the OpenTelemetry collector at this
+	/// OTLP/HTTP endpoint (e.g. `http://localhost:4318`).
+	#[structopt(long, global = true)]
+	otlp_endpoint: Option<String>,
+	/// Value of the `service.name` attribute of exported spans.
+	#[structopt(long, global = true, default_value = "substrate-relay")]
+	otlp_service_name: String,
+	/// Send relay failure alerts as JSON objects to this webhook URL. May be repeated.
+	#[structopt(long, global = true)]
+	alert_webhook: Vec<String>,
+	/// Send relay failure alerts to this Slack incoming webhook URL. May be repeated.
+	#[structopt(long, global = true)]
+	alert_slack_webhook: Vec<String>,
+	/// Send relay failure alerts to the PagerDuty service with this Events API v2 routing key.
+	#[structopt(long, global = true)]
+	alert_pagerduty_routing_key: Option<String>,
+	/// Path to the file, that contains PagerDuty routing key. Can be overridden with
+	/// alert_pagerduty_routing_key option.
+	#[structopt(long, global = true)]
+	alert_pagerduty_routing_key_file: Option<PathBuf>,
+	/// Fire alert when relay loop has not made any progress during this number of minutes.
+	#[structopt(long, global = true, default_value = "10")]
+	alert_max_stall_minutes: u64,
+	/// Fire alert when relay loop fails to submit this number of transactions in a row.
+	#[structopt(long, global = true, default_value = "5")]
+	alert_max_failed_submissions: u32,
+	/// Minimal interval (in minutes) between repeated alerts of the same kind.
+	#[structopt(long, global = true, default_value = "60")]
+	alert_repeat_minutes: u64,
+	/// Policy of delays between relay loop reconnect attempts: `constant:<delay>`,
+	/// `exponential:<initial>:<max>` or `fibonacci:<initial>:<max>` (in seconds). The policy may
+	/// be prefixed with the `<loop-name-prefix>=` to only apply it to matching loops (e.g.
+	/// `Millau_to_Rialto_Sync=exponential:1:300`). May be repeated.
+	#[structopt(long, global = true)]
+	reconnect_backoff: Vec<LoopReconnectBackoff>,
+	/// Run the full relay logic, but only log transactions instead of submitting them.
+	#[structopt(long, global = true)]
+	dry_run: bool,
+	/// Check that the signer free balance covers the transaction fee and the existential deposit
+	/// before submitting every transaction. The check costs two additional RPC calls per
+	/// transaction.
+	#[structopt(long, global = true)]
+	check_signer_balance: bool,
+	/// Path to the relay state database. If specified, relay loops are saving their state there
+	/// and, after restart, they don't resubmit transactions that have been submitted before the
+	/// restart.
+	#[structopt(long, global = true)]
+	state_db: Option<PathBuf>,
+	#[structopt(subcommand)]
+	command: Command,
+}
+
+impl Cli {
+	/// Run the command.
+	pub async fn run(self) {
+		relay_substrate_client::set_dry_run(self.dry_run);
+		relay_substrate_client::set_check_signer_balance(self.check_signer_balance);
+		relay_utils::reconnect_backoff::configure_reconnect_backoffs(
+			self.reconnect_backoff.clone(),
+		);
+		if let Some(ref state_db) = self.state_db {
+			if let Err(e) = relay_utils::state_store::open(state_db) {
+				structopt::clap::Error::with_description(
+					&e.to_string(),
+					structopt::clap::ErrorKind::InvalidValue,
+				)
+				.exit()
+			}
+		}
+		let log_file = self.log_file_params();
+		let alerts = match self.alerts_params() {
+			Ok(alerts) => alerts,
+			Err(e) => structopt::clap::Error::with_description(
+				&e.to_string(),
+				structopt::clap::ErrorKind::InvalidValue,
+			)
+			.exit(),
+		};
+		let otlp = self
+			.otlp_endpoint
+			.map(|endpoint| OtlpParams { endpoint, service_name: self.otlp_service_name });
+		self.command.run(self.log_format, log_file, otlp, alerts).await
+	}
+
+	/// Returns alerts parameters, if at least one alert sink is specified.
+	fn alerts_params(&self) -> anyhow::Result<Option<AlertsParams>> {
+		let pagerduty_routing_key = secrets::resolve_secret(
+			"alert_pagerduty_routing_key",
+			self.alert_pagerduty_routing_key.as_ref(),
+			self.alert_pagerduty_routing_key_file.as_ref(),
+		)?;
+		let sinks = self
+			.alert_webhook
+			.iter()
+			.cloned()
+			.map(AlertSink::Webhook)
+			.chain(self.alert_slack_webhook.iter().cloned().map(AlertSink::Slack))
+			.chain(pagerduty_routing_key.map(AlertSink::PagerDuty))
+			.collect::<Vec<_>>();
+		if sinks.is_empty() {
+			return Ok(None)
+		}
+
+		Ok(Some(AlertsParams {
+			sinks,
+			max_stall: std::time::Duration::from_secs(
+				self.alert_max_stall_minutes.saturating_mul(60),
+			),
+			max_failed_submissions: self.alert_max_failed_submissions,
+			repeat_interval: std::time::Duration::from_secs(
+				self.alert_repeat_minutes.saturating_mul(60),
+			),
+		}))
+	}
+
+	/// Returns log file parameters, if log file is specified.
+	fn log_file_params(&self) -> Option<LogFileParams> {
+		self.log_file.clone().map(|path| LogFileParams {
+			path,
+			max_size: self.log_file_max_size_mb.map(|size| size.saturating_mul(1024 * 1024)),
+			rotation_period: self
+				.log_file_rotation_hours
+				.map(|hours| std::time::Duration::from_secs(hours.saturating_mul(60 * 60))),
+			max_rotated_files: self.log_file_keep,
+		})
+	}
+}
+
+/// Substrate-to-Substrate bridge utilities.
+#[derive(StructOpt)]
 pub enum Command {
 	/// Start headers relay between two chains.
 	///
@@ -71,6 +253,11 @@ pub enum Command {
 	/// Ties up to `Messages` pallets on both chains and starts relaying messages.
 	/// Requires the header relay to be already running.
 	RelayMessages(relay_messages::RelayMessages),
+	/// Deliver given range of messages once and exit.
+	///
+	/// Proves messages at the given source chain header and submits single delivery transaction
+	/// to the target chain. The header must be already proved to the target chain.
+	RelayMessagesRange(relay_messages::RelayMessagesRange),
 	/// Start headers and messages relay between two Substrate chains.
 	///
 	/// This high-level relay internally starts four low-level relays: two `RelayHeaders`
@@ -78,38 +265,96 @@ pub enum Command {
 	/// the message relays - i.e. when there are messages or confirmations that needs to be
 	/// relayed between chains.
 	RelayHeadersAndMessages(Box<relay_headers_and_messages::RelayHeadersAndMessages>),
+	/// Start multiple headers and messages relays in a single process.
+	///
+	/// Bridges are described in the TOML file. All bridges are sharing the same metrics
+	/// endpoint and clients that are connected to the same nodes.
+	RelayBridges(relay_bridges::RelayBridges),
 	/// Initialize on-chain bridge pallet with current header data.
 	///
 	/// Sends initialization transaction to bootstrap the bridge with current finalized block data.
 	InitBridge(init_bridge::InitBridge),
+	/// Re-initialize on-chain bridge pallet that has missed authorities set change.
+	///
+	/// Prepares (and optionally submits) batch transaction that halts the bridge pallet and
+	/// re-initializes it with given finalized header and its authorities set.
+	ReinitBridge(reinit_bridge::ReinitBridge),
 	/// Send custom message over the bridge.
 	///
 	/// Allows interacting with the bridge by sending messages over `Messages` component.
 	/// The message is being sent to the source chain, delivered to the target chain and dispatched
 	/// there.
 	SendMessage(send_message::SendMessage),
+	/// Estimate cost of sending message over the bridge.
+	///
+	/// Prints cost of the message sending transaction at the source chain, cost of the message
+	/// delivery transaction at the target chain and the reward that the relayer will get for
+	/// delivering the message.
+	EstimateMessageFee(estimate_message_fee::EstimateMessageFee),
 	/// Resubmit transactions with increased tip if they are stalled.
 	ResubmitTransactions(resubmit_transactions::ResubmitTransactions),
 	/// Register parachain.
 	RegisterParachain(register_parachain::RegisterParachain),
 	///
 	RelayParachains(relay_parachains::RelayParachains),
+	/// Scan given range of source chain headers, imported by the target chain, for equivocations.
+	///
+	/// Finality proofs of source headers that have been imported by the target chain are compared
+	/// with finality proofs stored at the source chain. Found equivocations are printed.
+	DetectEquivocations(detect_equivocations::DetectEquivocations),
+	/// Print state of the bridge between two chains.
+	///
+	/// Prints best imported headers, GRANDPA authority set ids, operating modes of bridge
+	/// pallets and state of given lanes at both chains.
+	InspectBridge(inspect_bridge::InspectBridge),
+	/// Claim all pending relayer rewards.
+	///
+	/// Finds all pending rewards of the signer account in the relayers pallet and submits
+	/// `claim_rewards` transactions (optionally, as a single batch transaction).
+	ClaimRewards(claim_rewards::ClaimRewards),
+	/// Register relayer in the relayers pallet or deregister it.
+	///
+	/// Registered relayers get priority boost for their message delivery transactions. The
+	/// required stake is reserved from the relayer account.
+	RegisterRelayer(register_relayer::RegisterRelayer),
+	/// Find stale headers in the bridge finality pallet storage and craft calls to remove them.
+	///
+	/// Prints hex-encoded `Sudo::sudo(System::kill_storage(..))` calls. With `--submit`, the
+	/// calls are also submitted to the chain, signed by the sudo key.
+	PruneBridgeStorage(prune_bridge_storage::PruneBridgeStorage),
+	/// Print unclaimed and paid rewards of the relayer.
+	///
+	/// Unclaimed rewards are read from the relayers pallet storage and paid rewards are
+	/// computed from `RewardPaid` events of the latest finalized blocks.
+	RelayerRewards(relayer_rewards::RelayerRewards),
+	/// Export the relay state database (see `--state-db`) to the JSON file.
+	///
+	/// The state includes best submitted headers and in-flight message nonces of all relay loops.
+	/// The relay that is using the database must be stopped first.
+	ExportState(relay_state::ExportState),
+	/// Import the relay state from the JSON file to the relay state database (see `--state-db`).
+	///
+	/// May be used to move the relay to another host without losing track of its in-flight
+	/// transactions. The relay that is using the database must be stopped first.
+	ImportState(relay_state::ImportState),
 }
 
 impl Command {
 	// Initialize logger depending on the command.
-	fn init_logger(&self) {
+	fn init_logger(&self, log_format: LogFormat, log_file: Option<LogFileParams>) {
 		use relay_utils::initialize::{initialize_logger, initialize_relay};
 
 		match self {
 			Self::RelayHeaders(_) |
 			Self::RelayMessages(_) |
 			Self::RelayHeadersAndMessages(_) |
-			Self::InitBridge(_) => {
-				initialize_relay();
+			Self::RelayBridges(_) |
+			Self::InitBridge(_) |
+			Self::ReinitBridge(_) => {
+				initialize_relay(log_format, log_file);
 			},
 			_ => {
-				initialize_logger(false);
+				initialize_logger(false, log_format, log_file);
 			},
 		}
 	}
@@ -119,19 +364,45 @@ impl Command {
 		match self {
 			Self::RelayHeaders(arg) => arg.run().await?,
 			Self::RelayMessages(arg) => arg.run().await?,
+			Self::RelayMessagesRange(arg) => arg.run().await?,
 			Self::RelayHeadersAndMessages(arg) => arg.run().await?,
+			Self::RelayBridges(arg) => arg.run().await?,
 			Self::InitBridge(arg) => arg.run().await?,
+			Self::ReinitBridge(arg) => arg.run().await?,
 			Self::SendMessage(arg) => arg.run().await?,
+			Self::EstimateMessageFee(arg) => arg.run().await?,
 			Self::ResubmitTransactions(arg) => arg.run().await?,
 			Self::RegisterParachain(arg) => arg.run().await?,
 			Self::RelayParachains(arg) => arg.run().await?,
+			Self::DetectEquivocations(arg) => arg.run().await?,
+			Self::InspectBridge(arg) => arg.run().await?,
+			Self::ClaimRewards(arg) => arg.run().await?,
+			Self::RegisterRelayer(arg) => arg.run().await?,
+			Self::PruneBridgeStorage(arg) => arg.run().await?,
+			Self::RelayerRewards(arg) => arg.run().await?,
+			Self::ExportState(arg) => arg.run().await?,
+			Self::ImportState(arg) => arg.run().await?,
 		}
 		Ok(())
 	}
 
 	/// Run the command.
-	pub async fn run(self) {
-		self.init_logger();
+	pub async fn run(
+		self,
+		log_format: LogFormat,
+		log_file: Option<LogFileParams>,
+		otlp: Option<OtlpParams>,
+		alerts: Option<AlertsParams>,
+	) {
+		self.init_logger(log_format, log_file);
+		if let Some(alerts) = alerts {
+			relay_utils::alerts::init_alerts(alerts);
+		}
+		if let Some(otlp) = otlp {
+			if let Err(e) = relay_utils::otlp::init_otlp_exporter(otlp) {
+				log::error!(target: LOG_TARGET, "Failed to start OpenTelemetry exporter: {}", e);
+			}
+		}
 
 		let exit_signals = match Signals::new([SIGINT, SIGTERM]) {
 			Ok(signals) => signals,
@@ -145,7 +416,33 @@ impl Command {
 
 		select! {
 			signal = exit_signals.next().fuse() => {
-				log::info!(target: LOG_TARGET, "Received exit signal {:?}", signal);
+				log::info!(
+					target: LOG_TARGET,
+					"Received exit signal {:?}. Waiting for in-flight transactions. \
+					Send the signal again to exit immediately",
+					signal,
+				);
+			},
+			result = run => {
+				if let Err(e) = result {
+					log::error!(target: LOG_TARGET, "substrate-relay: {}", e);
+				}
+				return
+			},
+		}
+
+		// relay loops are stopping new work and waiting for their in-flight transactions
+		relay_utils::shutdown::request_shutdown();
+		select! {
+			signal = exit_signals.next().fuse() => {
+				log::info!(target: LOG_TARGET, "Received exit signal {:?}. Exiting", signal);
+			},
+			_ = async_std::task::sleep(SHUTDOWN_TIMEOUT).fuse() => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to shutdown gracefully in {}s. Exiting",
+					SHUTDOWN_TIMEOUT.as_secs(),
+				);
 			},
 			result = run => {
 				if let Err(e) = result {
@@ -218,12 +515,62 @@ impl std::str::FromStr for HexLaneId {
 	type Err = rustc_hex::FromHexError;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		// legacy (4-byte) lane identifiers are still accepted - they're converted to the
+		// `LaneId` and then back to the legacy encoding by bridges that are using it
+		let hex = s.strip_prefix("0x").unwrap_or(s);
+		if hex.len() == 8 {
+			let mut legacy_lane_id = [0u8; 4];
+			legacy_lane_id.copy_from_slice(&rustc_hex::FromHex::from_hex::<Vec<u8>>(hex)?);
+			return Ok(HexLaneId(*LaneId::from(LegacyLaneId(legacy_lane_id)).as_ref()))
+		}
+
 		Ok(HexLaneId(H256::from_str(s)?))
 	}
 }
 
+/// Block, identified either by its hash or by its number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockHashOrNumber {
+	/// Block hash.
+	Hash(H256),
+	/// Block number.
+	Number(u32),
+}
+
+impl BlockHashOrNumber {
+	/// Resolve block hash using given client.
+	pub async fn hash<C: relay_substrate_client::Chain>(
+		&self,
+		client: &impl relay_substrate_client::Client<C>,
+	) -> anyhow::Result<relay_substrate_client::HashOf<C>> {
+		match *self {
+			BlockHashOrNumber::Hash(hash) => Decode::decode(&mut hash.as_bytes()).map_err(|e| {
+				anyhow::format_err!("Invalid {} block hash {:?}: {:?}", C::NAME, hash, e)
+			}),
+			BlockHashOrNumber::Number(number) =>
+				Ok(client.header_hash_by_number(number.into()).await?),
+		}
+	}
+}
+
+impl std::str::FromStr for BlockHashOrNumber {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.starts_with("0x") {
+			H256::from_str(s)
+				.map(BlockHashOrNumber::Hash)
+				.map_err(|e| format!("Invalid block hash {s}: {e:?}"))
+		} else {
+			s.parse()
+				.map(BlockHashOrNumber::Number)
+				.map_err(|e| format!("Invalid block number {s}: {e:?}"))
+		}
+	}
+}
+
 /// Nicer formatting for raw bytes vectors.
-#[derive(Default, Encode, Decode, PartialEq, Eq)]
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq)]
 pub struct HexBytes(pub Vec<u8>);
 
 impl std::str::FromStr for HexBytes {
@@ -258,6 +605,58 @@ pub struct PrometheusParams {
 	/// Expose Prometheus endpoint at given port.
 	#[structopt(long, default_value = "9616")]
 	pub prometheus_port: u16,
+	/// Path to the PEM file with TLS certificate chain. If specified, metrics are served over
+	/// HTTPS.
+	#[structopt(long, requires = "prometheus-tls-key")]
+	pub prometheus_tls_cert: Option<PathBuf>,
+	/// Path to the PEM file with TLS private key.
+	#[structopt(long, requires = "prometheus-tls-cert")]
+	pub prometheus_tls_key: Option<PathBuf>,
+	/// Require `Authorization: Bearer <token>` header to access metrics.
+	#[structopt(long, conflicts_with_all = &["prometheus-basic-auth", "prometheus-basic-auth-file"])]
+	pub prometheus_bearer_token: Option<String>,
+	/// Path to the file, that contains bearer token. Can be overridden with
+	/// prometheus_bearer_token option.
+	#[structopt(long, conflicts_with_all = &["prometheus-basic-auth", "prometheus-basic-auth-file"])]
+	pub prometheus_bearer_token_file: Option<PathBuf>,
+	/// Require HTTP basic authentication (`<username>:<password>`) to access metrics.
+	#[structopt(long)]
+	pub prometheus_basic_auth: Option<String>,
+	/// Path to the file, that contains `<username>:<password>` for HTTP basic authentication.
+	/// Can be overridden with prometheus_basic_auth option.
+	#[structopt(long)]
+	pub prometheus_basic_auth_file: Option<PathBuf>,
+	/// Periodically push metrics to the Prometheus Pushgateway at given URL.
+	#[structopt(long)]
+	pub prometheus_push_gateway: Option<String>,
+	/// Value of the `job` label of metrics, pushed to the Pushgateway.
+	#[structopt(long, default_value = "substrate-relay")]
+	pub prometheus_push_job: String,
+	/// Value of the `instance` label of metrics, pushed to the Pushgateway.
+	#[structopt(long)]
+	pub prometheus_push_instance: Option<String>,
+	/// Interval (in seconds) between pushes to the Pushgateway.
+	#[structopt(long, default_value = "15")]
+	pub prometheus_push_interval_secs: u64,
+	/// Attach static `<name>=<value>` label to every exported metric (e.g. `environment=prod`).
+	/// May be repeated.
+	#[structopt(long)]
+	pub prometheus_label: Vec<String>,
+	/// Relay loop, that has not made any progress during this number of minutes, is reported
+	/// as unhealthy by the `/healthz` request to the Prometheus endpoint.
+	#[structopt(long, default_value = "10")]
+	pub health_max_stall_minutes: u64,
+	/// Serve control requests (pause, resume, trigger and tip relay loops, dump their state) at
+	/// the Prometheus endpoint. They require the same authentication as metrics, so if it is not
+	/// configured, the endpoint must only be exposed at the local interface. Otherwise the relay
+	/// refuses to start.
+	#[structopt(long)]
+	pub enable_control: bool,
+	/// Export price of the token as the `<token>_to_base_conversion_rate` metric. The value is
+	/// `<token>=<source>`, where source is one of `coingecko:<token-id>/<currency>`,
+	/// `json:<url>#<json-path>` or `static:<price>`. May be repeated.
+	#[structopt(long)]
+	pub token_price: Vec<String>,
 }
 
 /// Struct to get git commit info and build time.
@@ -283,8 +682,43 @@ impl SubstrateRelayBuildInfo {
 impl PrometheusParams {
 	/// Tries to convert CLI metrics params into metrics params, used by the relay.
 	pub fn into_metrics_params(self) -> anyhow::Result<relay_utils::metrics::MetricsParams> {
+		use relay_utils::metrics::StandaloneMetric;
+
+		let token_prices = self.token_prices()?;
+		let labels = self.labels()?;
+		relay_utils::health::set_max_stall(std::time::Duration::from_secs(
+			self.health_max_stall_minutes * 60,
+		));
+		let auth = self.metrics_auth()?;
+		if self.enable_control {
+			if auth.is_none() && !relay_utils::control::is_loopback_host(&self.prometheus_host) {
+				return Err(anyhow::format_err!(
+					"Refusing to serve control requests at {} without authentication. Use \
+					prometheus_bearer_token or prometheus_basic_auth option, or expose the \
+					endpoint at the local interface",
+					self.prometheus_host,
+				))
+			}
+			relay_utils::control::enable();
+		}
+
+		let push_gateway = self.prometheus_push_gateway.clone().map(|url| {
+			relay_utils::metrics::PushGatewayParams {
+				url,
+				job: self.prometheus_push_job.clone(),
+				instance: self.prometheus_push_instance.clone(),
+				interval: std::time::Duration::from_secs(self.prometheus_push_interval_secs),
+			}
+		});
 		let metrics_address = if !self.no_prometheus {
 			Some(relay_utils::metrics::MetricsAddress {
+				auth,
+				tls: self.prometheus_tls_cert.clone().zip(self.prometheus_tls_key.clone()).map(
+					|(cert_path, key_path)| relay_utils::metrics::MetricsTls {
+						cert_path,
+						key_path,
+					},
+				),
 				host: self.prometheus_host,
 				port: self.prometheus_port,
 			})
@@ -294,12 +728,82 @@ impl PrometheusParams {
 
 		let relay_version = option_env!("CARGO_PKG_VERSION").unwrap_or("unknown");
 		let relay_commit = SubstrateRelayBuildInfo::get_git_commit();
-		relay_utils::metrics::MetricsParams::new(
+		let metrics_params = relay_utils::metrics::MetricsParams::new(
 			metrics_address,
 			relay_version.into(),
 			relay_commit,
+			labels,
 		)
-		.map_err(|e| anyhow::format_err!("{:?}", e))
+		.map_err(|e| anyhow::format_err!("{:?}", e))?;
+
+		for (token, source) in token_prices {
+			relay_utils::metrics::TokenPriceMetric::new(
+				source,
+				format!("{token}_to_base_conversion_rate"),
+				format!("Rate used to convert from {token} to some BASE tokens"),
+			)?
+			.register_and_spawn(&metrics_params.registry)?;
+		}
+
+		Ok(match push_gateway {
+			Some(push_gateway) => metrics_params.with_push_gateway(push_gateway),
+			None => metrics_params,
+		})
+	}
+
+	/// Returns static labels of all metrics.
+	fn labels(&self) -> anyhow::Result<std::collections::HashMap<String, String>> {
+		self.prometheus_label
+			.iter()
+			.map(|label| {
+				label
+					.split_once('=')
+					.map(|(name, value)| (name.to_string(), value.to_string()))
+					.ok_or_else(|| anyhow::format_err!("Expected <name>=<value>, got: {}", label))
+			})
+			.collect()
+	}
+
+	/// Returns tokens along with their price sources.
+	fn token_prices(&self) -> anyhow::Result<Vec<(String, relay_utils::metrics::AnyPriceSource)>> {
+		self.token_price
+			.iter()
+			.map(|token_price| {
+				let (token, source) = token_price.split_once('=').ok_or_else(|| {
+					anyhow::format_err!("Expected <token>=<source>, got: {}", token_price)
+				})?;
+				Ok((token.to_lowercase(), source.parse::<relay_utils::metrics::AnyPriceSource>()?))
+			})
+			.collect()
+	}
+
+	/// Returns authentication, required to access metrics.
+	fn metrics_auth(&self) -> anyhow::Result<Option<relay_utils::metrics::MetricsAuth>> {
+		let bearer_token = secrets::resolve_secret(
+			"prometheus_bearer_token",
+			self.prometheus_bearer_token.as_ref(),
+			self.prometheus_bearer_token_file.as_ref(),
+		)?;
+		if let Some(token) = bearer_token {
+			return Ok(Some(relay_utils::metrics::MetricsAuth::Bearer(token)))
+		}
+
+		let basic_auth = secrets::resolve_secret(
+			"prometheus_basic_auth",
+			self.prometheus_basic_auth.as_ref(),
+			self.prometheus_basic_auth_file.as_ref(),
+		)?;
+		basic_auth
+			.map(|basic_auth| match basic_auth.split_once(':') {
+				Some((username, password)) => Ok(relay_utils::metrics::MetricsAuth::Basic {
+					username: username.into(),
+					password: password.into(),
+				}),
+				None => Err(anyhow::format_err!(
+					"Expected `<username>:<password>` in the prometheus_basic_auth option"
+				)),
+			})
+			.transpose()
 	}
 }
 
@@ -356,4 +860,37 @@ mod tests {
 		// then
 		assert_eq!(hex.0, hex2.0);
 	}
+
+	#[test]
+	fn block_hash_or_number_is_parsed() {
+		assert_eq!("42".parse::<BlockHashOrNumber>(), Ok(BlockHashOrNumber::Number(42)));
+		assert_eq!(
+			format!("{:?}", H256::repeat_byte(1)).parse::<BlockHashOrNumber>(),
+			Ok(BlockHashOrNumber::Hash(H256::repeat_byte(1))),
+		);
+		assert!("0x42".parse::<BlockHashOrNumber>().is_err());
+		assert!("block".parse::<BlockHashOrNumber>().is_err());
+	}
+
+	#[test]
+	fn control_without_auth_is_only_enabled_at_local_interface() {
+		let params = PrometheusParams::from_iter_safe([
+			"relay",
+			"--enable-control",
+			"--prometheus-host",
+			"0.0.0.0",
+		])
+		.unwrap();
+		assert!(params.into_metrics_params().is_err());
+	}
+
+	#[test]
+	fn hex_lane_id_accepts_legacy_lane_id() {
+		let lane_id: LaneId = "0x00000001".parse::<HexLaneId>().unwrap().into();
+		assert_eq!(LegacyLaneId::try_from(lane_id), Ok(LegacyLaneId([0, 0, 0, 1])));
+
+		let lane_id: LaneId =
+			format!("{:?}", H256::repeat_byte(1)).parse::<HexLaneId>().unwrap().into();
+		assert_eq!(lane_id, LaneId::from_inner(H256::repeat_byte(1)));
+	}
 }
@@ -0,0 +1,332 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cleaning up storage of bridge finality pallets.
+//!
+//! GRANDPA and parachains finality pallets are keeping a bounded number of imported headers,
+//! pruning the oldest header when a new header is imported. However, headers that have been
+//! imported before the retention window has been reduced (or before the pallet has been
+//! reinitialized) are never pruned. This command finds such headers and crafts
+//! `System::kill_storage` calls (wrapped into `Sudo::sudo`) that remove them.
+
+use crate::cli::{chain_schema::*, CliChain};
+
+use bp_parachains::ImportedParaHeadsKeyProvider;
+use bp_polkadot_core::parachains::{ParaHash, ParaId};
+use bp_runtime::{storage_map_final_key, StorageDoubleMapKeyProvider};
+use codec::{Decode, Encode};
+use frame_support::Identity;
+use relay_substrate_client::{Chain, ChainWithTransactions, Client, HashOf, UnsignedTransaction};
+use relay_utils::{TrackedTransactionStatus, TransactionTracker};
+use sp_core::storage::StorageKey;
+use std::collections::HashSet;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Name of the ring buffer with hashes of headers, retained by the GRANDPA pallet.
+const IMPORTED_HASHES_MAP_NAME: &str = "ImportedHashes";
+/// Name of the map with headers, imported by the GRANDPA pallet.
+const IMPORTED_HEADERS_MAP_NAME: &str = "ImportedHeaders";
+/// Name of the ring buffer with hashes of parachain heads, retained by the parachains pallet.
+const IMPORTED_PARA_HASHES_MAP_NAME: &str = "ImportedParaHashes";
+
+/// Find stale headers in the bridge finality pallet storage and craft calls to remove them.
+#[derive(StructOpt)]
+pub struct PruneBridgeStorage {
+	/// A bridge, which finality pallet storage is inspected.
+	#[structopt(possible_values = PruneBridgeStorageBridge::VARIANTS, case_insensitive = true)]
+	bridge: PruneBridgeStorageBridge,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	/// Maximal number of storage keys, removed by a single call.
+	#[structopt(long, default_value = "256")]
+	max_keys_per_call: usize,
+	/// Submit crafted calls to the target chain. The signer must be the sudo key of the target
+	/// chain. By default, calls are only printed.
+	#[structopt(long)]
+	submit: bool,
+}
+
+/// Bridge, which finality pallet storage is inspected.
+#[derive(Debug, EnumString, EnumVariantNames, PartialEq, Eq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum PruneBridgeStorageBridge {
+	/// GRANDPA pallet, tracking Millau headers at Rialto.
+	MillauToRialto,
+	/// GRANDPA pallet, tracking Rialto headers at Millau.
+	RialtoToMillau,
+	/// Parachains pallet, tracking RialtoParachain heads at Millau.
+	RialtoParachainToMillau,
+}
+
+/// Chain, where bridge finality pallets are deployed.
+trait StorageCleaner: ChainWithTransactions + CliChain {
+	/// Encode root call that removes given storage keys.
+	fn encode_kill_storage(keys: Vec<Vec<u8>>) -> Self::Call;
+}
+
+impl StorageCleaner for relay_millau_client::Millau {
+	fn encode_kill_storage(keys: Vec<Vec<u8>>) -> Self::Call {
+		millau_runtime::SudoCall::<millau_runtime::Runtime>::sudo {
+			call: Box::new(millau_runtime::SystemCall::kill_storage { keys }.into()),
+		}
+		.into()
+	}
+}
+
+impl StorageCleaner for relay_rialto_client::Rialto {
+	fn encode_kill_storage(keys: Vec<Vec<u8>>) -> Self::Call {
+		rialto_runtime::SudoCall::<rialto_runtime::Runtime>::sudo {
+			call: Box::new(rialto_runtime::SystemCall::kill_storage { keys }.into()),
+		}
+		.into()
+	}
+}
+
+impl PruneBridgeStorage {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			PruneBridgeStorageBridge::MillauToRialto => {
+				type Target = relay_rialto_client::Rialto;
+
+				let client = self.target.into_client::<Target>().await?;
+				let stale_keys = stale_grandpa_headers::<relay_millau_client::Millau, Target>(
+					&client,
+					bp_millau::WITH_MILLAU_GRANDPA_PALLET_NAME,
+				)
+				.await?;
+				prune_keys(
+					client,
+					self.target_sign,
+					self.max_keys_per_call,
+					self.submit,
+					stale_keys,
+				)
+				.await
+			},
+			PruneBridgeStorageBridge::RialtoToMillau => {
+				type Target = relay_millau_client::Millau;
+
+				let client = self.target.into_client::<Target>().await?;
+				let stale_keys = stale_grandpa_headers::<relay_rialto_client::Rialto, Target>(
+					&client,
+					bp_rialto::WITH_RIALTO_GRANDPA_PALLET_NAME,
+				)
+				.await?;
+				prune_keys(
+					client,
+					self.target_sign,
+					self.max_keys_per_call,
+					self.submit,
+					stale_keys,
+				)
+				.await
+			},
+			PruneBridgeStorageBridge::RialtoParachainToMillau => {
+				type Target = relay_millau_client::Millau;
+
+				let client = self.target.into_client::<Target>().await?;
+				let stale_keys = stale_parachain_heads::<Target>(
+					&client,
+					bp_rialto::WITH_RIALTO_BRIDGE_PARAS_PALLET_NAME,
+				)
+				.await?;
+				prune_keys(
+					client,
+					self.target_sign,
+					self.max_keys_per_call,
+					self.submit,
+					stale_keys,
+				)
+				.await
+			},
+		}
+	}
+}
+
+/// Returns storage keys of `Source` headers, that are stored by the GRANDPA pallet at the
+/// `Target` chain, but are not in the `ImportedHashes` ring buffer.
+async fn stale_grandpa_headers<Source: Chain, Target: Chain>(
+	client: &impl Client<Target>,
+	pallet_name: &str,
+) -> anyhow::Result<Vec<StorageKey>> {
+	let at = client.best_finalized_header_hash().await?;
+
+	let mut retained_keys = HashSet::new();
+	for key in client
+		.storage_keys(at, map_prefix(pallet_name, IMPORTED_HASHES_MAP_NAME))
+		.await?
+	{
+		if let Some(hash) = client.storage_value::<HashOf<Source>>(at, key).await? {
+			retained_keys.insert(storage_map_final_key::<Identity>(
+				pallet_name,
+				IMPORTED_HEADERS_MAP_NAME,
+				&hash.encode(),
+			));
+		}
+	}
+
+	let imported_keys = client
+		.storage_keys(at, map_prefix(pallet_name, IMPORTED_HEADERS_MAP_NAME))
+		.await?;
+	log::info!(
+		target: "bridge",
+		"{} pallet at {} is retaining {} headers and stores {} headers",
+		pallet_name,
+		Target::NAME,
+		retained_keys.len(),
+		imported_keys.len(),
+	);
+
+	Ok(select_stale_keys(imported_keys, &retained_keys))
+}
+
+/// Returns storage keys of parachain heads, that are stored by the parachains pallet at the
+/// `Target` chain, but are not in the `ImportedParaHashes` ring buffer.
+async fn stale_parachain_heads<Target: Chain>(
+	client: &impl Client<Target>,
+	pallet_name: &str,
+) -> anyhow::Result<Vec<StorageKey>> {
+	let at = client.best_finalized_header_hash().await?;
+
+	// the key of `ImportedParaHashes` map is `prefix ++ blake2_128(para_id) ++ para_id ++
+	// twox64(index) ++ index`
+	let prefix = map_prefix(pallet_name, IMPORTED_PARA_HASHES_MAP_NAME);
+	let para_id_offset = prefix.0.len() + 16;
+	let mut retained_keys = HashSet::new();
+	for key in client.storage_keys(at, prefix).await? {
+		let para_id = key
+			.0
+			.get(para_id_offset..)
+			.and_then(|mut encoded_para_id| ParaId::decode(&mut encoded_para_id).ok())
+			.ok_or_else(|| anyhow::format_err!("Invalid {} key: {:?}", pallet_name, key))?;
+		if let Some(hash) = client.storage_value::<ParaHash>(at, key).await? {
+			retained_keys.insert(ImportedParaHeadsKeyProvider::final_key(
+				pallet_name,
+				&para_id,
+				&hash,
+			));
+		}
+	}
+
+	let imported_keys = client
+		.storage_keys(at, map_prefix(pallet_name, ImportedParaHeadsKeyProvider::MAP_NAME))
+		.await?;
+	log::info!(
+		target: "bridge",
+		"{} pallet at {} is retaining {} heads and stores {} heads",
+		pallet_name,
+		Target::NAME,
+		retained_keys.len(),
+		imported_keys.len(),
+	);
+
+	Ok(select_stale_keys(imported_keys, &retained_keys))
+}
+
+/// Print (and optionally submit) calls that remove given keys from the storage.
+async fn prune_keys<C: StorageCleaner>(
+	client: impl Client<C>,
+	target_sign: TargetSigningParams,
+	max_keys_per_call: usize,
+	submit: bool,
+	stale_keys: Vec<StorageKey>,
+) -> anyhow::Result<()> {
+	if stale_keys.is_empty() {
+		log::info!(target: "bridge", "There are no stale headers at {}", C::NAME);
+		return Ok(())
+	}
+
+	let calls = build_kill_storage_calls(stale_keys, max_keys_per_call, C::encode_kill_storage);
+	for call in &calls {
+		println!("0x{}", hex::encode(call.encode()));
+	}
+	if !submit {
+		return Ok(())
+	}
+
+	let signer = target_sign.to_keypair::<C>()?;
+	for call in calls {
+		let tx_status = client
+			.submit_and_watch_signed_extrinsic(&signer, move |_, transaction_nonce| {
+				Ok(UnsignedTransaction::new(call.into(), transaction_nonce))
+			})
+			.await?
+			.wait()
+			.await;
+		match tx_status {
+			TrackedTransactionStatus::Finalized(block) => log::info!(
+				target: "bridge",
+				"Storage cleanup transaction has been finalized at {} block {:?}",
+				C::NAME,
+				block,
+			),
+			TrackedTransactionStatus::Lost =>
+				return Err(anyhow::format_err!(
+					"Storage cleanup transaction at {} is lost",
+					C::NAME
+				)),
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns prefix of all keys of the storage map.
+fn map_prefix(pallet_name: &str, map_name: &str) -> StorageKey {
+	StorageKey(
+		bp_runtime::storage_value_final_key(pallet_name.as_bytes(), map_name.as_bytes()).to_vec(),
+	)
+}
+
+/// Returns keys that are not in the retained keys set.
+fn select_stale_keys(
+	imported_keys: Vec<StorageKey>,
+	retained_keys: &HashSet<StorageKey>,
+) -> Vec<StorageKey> {
+	imported_keys.into_iter().filter(|key| !retained_keys.contains(key)).collect()
+}
+
+/// Split keys into chunks of at most `max_keys_per_call` keys and build call for every chunk.
+fn build_kill_storage_calls<Call>(
+	keys: Vec<StorageKey>,
+	max_keys_per_call: usize,
+	encode_kill_storage: impl Fn(Vec<Vec<u8>>) -> Call,
+) -> Vec<Call> {
+	keys.chunks(std::cmp::max(max_keys_per_call, 1))
+		.map(|chunk| encode_kill_storage(chunk.iter().map(|key| key.0.clone()).collect()))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stale_keys_are_selected_and_split_into_calls() {
+		let key = |i: u8| StorageKey(vec![i]);
+		let retained_keys = vec![key(1), key(3)].into_iter().collect();
+		let stale_keys =
+			select_stale_keys(vec![key(0), key(1), key(2), key(3), key(4)], &retained_keys);
+		assert_eq!(stale_keys, vec![key(0), key(2), key(4)]);
+
+		let calls = build_kill_storage_calls(stale_keys, 2, |keys| keys);
+		assert_eq!(calls, vec![vec![vec![0], vec![2]], vec![vec![4]]]);
+	}
+}
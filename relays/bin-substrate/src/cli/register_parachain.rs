@@ -308,7 +308,16 @@ mod tests {
 					relaychain_signer_password: None,
 					relaychain_signer_file: None,
 					relaychain_signer_password_file: None,
+					relaychain_signer_keystore: None,
 					relaychain_transactions_mortality: None,
+					relaychain_max_spending_per_hour: None,
+					relaychain_max_spending_per_day: None,
+					relaychain_min_balance: None,
+					relaychain_balance_threshold: None,
+					relaychain_proxy_pallet_index: None,
+					relaychain_proxied_address: None,
+					relaychain_multisig_pallet_index: None,
+					relaychain_multisig_other_signatories: vec![],
 				},
 				para_connection: ParachainConnectionParams {
 					parachain_host: "127.0.0.1".into(),
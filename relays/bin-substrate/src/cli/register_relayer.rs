@@ -0,0 +1,400 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::{chain_schema::*, claim_rewards::RELAYERS_PALLET_NAME, CliChain};
+
+use bp_relayers::Registration;
+use bp_runtime::{storage_map_final_key, HeaderIdProvider};
+use codec::Encode;
+use frame_support::Blake2_128Concat;
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain, ChainWithBalances,
+	ChainWithTransactions, Client, UnsignedTransaction,
+};
+use relay_utils::{TrackedTransactionStatus, TransactionTracker};
+use sp_core::Pair;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Name of the relayers pallet storage map with relayer registrations.
+const REGISTERED_RELAYERS_MAP_NAME: &str = "RegisteredRelayers";
+
+/// Register relayer in the relayers pallet or deregister it.
+#[derive(StructOpt)]
+pub struct RegisterRelayer {
+	/// A chain where relayer is registered.
+	#[structopt(possible_values = RegisterRelayerChain::VARIANTS, case_insensitive = true)]
+	chain: RegisterRelayerChain,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	/// The last block number, where the registration is active. May be used to register
+	/// relayer or to prolong existing registration.
+	#[structopt(long, required_unless = "deregister")]
+	valid_till: Option<u32>,
+	/// Deregister relayer and return its stake. Registration may only be removed after its
+	/// `valid_till` block.
+	#[structopt(long, conflicts_with = "valid-till")]
+	deregister: bool,
+}
+
+/// Chain, where relayer is registered.
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum RegisterRelayerChain {
+	Millau,
+	Rialto,
+	RialtoParachain,
+	BridgeHubRococo,
+	BridgeHubWococo,
+}
+
+/// Chain, where relayers pallet is deployed.
+trait RelayerRegistrar: ChainWithTransactions + ChainWithBalances + CliChain
+where
+	AccountIdOf<Self>: From<<AccountKeyPairOf<Self> as Pair>::Public>,
+	BalanceOf<Self>: Into<u128>,
+{
+	/// Stake that is reserved from the registered relayer account. It is configured in the
+	/// chain runtime.
+	const REQUIRED_STAKE: u128;
+	/// Required remaining registration lease. It is configured in the chain runtime.
+	const REQUIRED_REGISTRATION_LEASE: u32;
+
+	/// Encode `register` call of the relayers pallet.
+	fn encode_register(valid_till: BlockNumberOf<Self>) -> Self::Call;
+	/// Encode `deregister` call of the relayers pallet.
+	fn encode_deregister() -> Self::Call;
+}
+
+impl RelayerRegistrar for relay_millau_client::Millau {
+	const REQUIRED_STAKE: u128 = 1_000;
+	const REQUIRED_REGISTRATION_LEASE: u32 = 8;
+
+	fn encode_register(valid_till: BlockNumberOf<Self>) -> Self::Call {
+		millau_runtime::BridgeRelayersCall::<millau_runtime::Runtime>::register { valid_till }
+			.into()
+	}
+
+	fn encode_deregister() -> Self::Call {
+		millau_runtime::BridgeRelayersCall::<millau_runtime::Runtime>::deregister {}.into()
+	}
+}
+
+impl RelayerRegistrar for relay_rialto_client::Rialto {
+	const REQUIRED_STAKE: u128 = 0;
+	const REQUIRED_REGISTRATION_LEASE: u32 = 0;
+
+	fn encode_register(valid_till: BlockNumberOf<Self>) -> Self::Call {
+		rialto_runtime::BridgeRelayersCall::<rialto_runtime::Runtime>::register { valid_till }
+			.into()
+	}
+
+	fn encode_deregister() -> Self::Call {
+		rialto_runtime::BridgeRelayersCall::<rialto_runtime::Runtime>::deregister {}.into()
+	}
+}
+
+impl RelayerRegistrar for relay_rialto_parachain_client::RialtoParachain {
+	const REQUIRED_STAKE: u128 = 0;
+	const REQUIRED_REGISTRATION_LEASE: u32 = 0;
+
+	fn encode_register(valid_till: BlockNumberOf<Self>) -> Self::Call {
+		type RelayersCall =
+			relay_rialto_parachain_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_rialto_parachain_client::RuntimeCall::BridgeRelayers(RelayersCall::register {
+			valid_till,
+		})
+	}
+
+	fn encode_deregister() -> Self::Call {
+		type RelayersCall =
+			relay_rialto_parachain_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_rialto_parachain_client::RuntimeCall::BridgeRelayers(RelayersCall::deregister)
+	}
+}
+
+impl RelayerRegistrar for relay_bridge_hub_rococo_client::BridgeHubRococo {
+	const REQUIRED_STAKE: u128 = 1_000_000;
+	const REQUIRED_REGISTRATION_LEASE: u32 = 8;
+
+	fn encode_register(valid_till: BlockNumberOf<Self>) -> Self::Call {
+		type RelayersCall =
+			relay_bridge_hub_rococo_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_bridge_hub_rococo_client::RuntimeCall::BridgeRelayers(RelayersCall::register {
+			valid_till,
+		})
+	}
+
+	fn encode_deregister() -> Self::Call {
+		type RelayersCall =
+			relay_bridge_hub_rococo_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_bridge_hub_rococo_client::RuntimeCall::BridgeRelayers(RelayersCall::deregister)
+	}
+}
+
+impl RelayerRegistrar for relay_bridge_hub_wococo_client::BridgeHubWococo {
+	const REQUIRED_STAKE: u128 = 1_000_000;
+	const REQUIRED_REGISTRATION_LEASE: u32 = 8;
+
+	fn encode_register(valid_till: BlockNumberOf<Self>) -> Self::Call {
+		type RelayersCall =
+			relay_bridge_hub_wococo_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_bridge_hub_wococo_client::RuntimeCall::BridgeRelayers(RelayersCall::register {
+			valid_till,
+		})
+	}
+
+	fn encode_deregister() -> Self::Call {
+		type RelayersCall =
+			relay_bridge_hub_wococo_client::runtime_types::pallet_bridge_relayers::pallet::Call;
+
+		relay_bridge_hub_wococo_client::RuntimeCall::BridgeRelayers(RelayersCall::deregister)
+	}
+}
+
+macro_rules! select_chain {
+	($chain: expr, $generic: tt) => {
+		match $chain {
+			RegisterRelayerChain::Millau => {
+				type Target = relay_millau_client::Millau;
+
+				$generic
+			},
+			RegisterRelayerChain::Rialto => {
+				type Target = relay_rialto_client::Rialto;
+
+				$generic
+			},
+			RegisterRelayerChain::RialtoParachain => {
+				type Target = relay_rialto_parachain_client::RialtoParachain;
+
+				$generic
+			},
+			RegisterRelayerChain::BridgeHubRococo => {
+				type Target = relay_bridge_hub_rococo_client::BridgeHubRococo;
+
+				$generic
+			},
+			RegisterRelayerChain::BridgeHubWococo => {
+				type Target = relay_bridge_hub_wococo_client::BridgeHubWococo;
+
+				$generic
+			},
+		}
+	};
+}
+
+impl RegisterRelayer {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_chain!(self.chain, {
+			let client = self.target.into_client::<Target>().await?;
+			let signer = self.target_sign.to_keypair::<Target>()?;
+			match self.valid_till {
+				Some(valid_till) => register::<Target>(client, signer, valid_till.into()).await,
+				None => deregister::<Target>(client, signer).await,
+			}
+		})
+	}
+}
+
+/// Register relayer or prolong its registration.
+async fn register<C: RelayerRegistrar>(
+	client: impl Client<C>,
+	signer: AccountKeyPairOf<C>,
+	valid_till: BlockNumberOf<C>,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	BalanceOf<C>: Into<u128>,
+{
+	let relayer: AccountIdOf<C> = signer.public().into();
+	let best_block = client.best_header().await?.id();
+	let registration = read_registration(&client, best_block.hash(), &relayer).await?;
+
+	let required_lease: BlockNumberOf<C> = C::REQUIRED_REGISTRATION_LEASE.into();
+	if valid_till <= best_block.number().saturating_add(required_lease) {
+		return Err(anyhow::format_err!(
+			"Registration at {} must be valid for more than {} blocks after current best block {}",
+			C::NAME,
+			required_lease,
+			best_block.number(),
+		))
+	}
+	if let Some(ref registration) = registration {
+		if valid_till < registration.valid_till {
+			return Err(anyhow::format_err!(
+				"Existing registration at {} is valid till block {}. It can't be reduced to {}",
+				C::NAME,
+				registration.valid_till,
+				valid_till,
+			))
+		}
+	}
+
+	// pallet only reserves the difference between required and existing stake
+	let existing_stake = registration.map(|r| r.stake.into()).unwrap_or(0);
+	let to_reserve = C::REQUIRED_STAKE.saturating_sub(existing_stake);
+	let free_balance = client.free_native_balance(best_block.hash(), &relayer).await?;
+	if free_balance < to_reserve {
+		return Err(anyhow::format_err!(
+			"Relayer {:?} at {} has free balance {} which is less than the required stake {}",
+			relayer,
+			C::NAME,
+			free_balance,
+			to_reserve,
+		))
+	}
+
+	log::info!(
+		target: "bridge",
+		"Registering relayer {:?} at {} till block {}. Reserving {} more",
+		relayer,
+		C::NAME,
+		valid_till,
+		to_reserve,
+	);
+	submit_and_wait(&client, &signer, C::encode_register(valid_till), "Registration").await
+}
+
+/// Deregister relayer.
+async fn deregister<C: RelayerRegistrar>(
+	client: impl Client<C>,
+	signer: AccountKeyPairOf<C>,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	BalanceOf<C>: Into<u128>,
+{
+	let relayer: AccountIdOf<C> = signer.public().into();
+	let best_block = client.best_header().await?.id();
+	let registration =
+		read_registration(&client, best_block.hash(), &relayer).await?.ok_or_else(|| {
+			anyhow::format_err!("Relayer {:?} is not registered at {}", relayer, C::NAME)
+		})?;
+	if registration.valid_till >= best_block.number() {
+		return Err(anyhow::format_err!(
+			"Registration at {} is active till block {}. Current best block is {}",
+			C::NAME,
+			registration.valid_till,
+			best_block.number(),
+		))
+	}
+
+	log::info!(
+		target: "bridge",
+		"Deregistering relayer {:?} at {}. Stake {:?} is returned",
+		relayer,
+		C::NAME,
+		registration.stake,
+	);
+	submit_and_wait(&client, &signer, C::encode_deregister(), "Deregistration").await
+}
+
+/// Read relayer registration.
+async fn read_registration<C: Chain>(
+	client: &impl Client<C>,
+	at: C::Hash,
+	relayer: &AccountIdOf<C>,
+) -> anyhow::Result<Option<Registration<BlockNumberOf<C>, BalanceOf<C>>>> {
+	let storage_key = storage_map_final_key::<Blake2_128Concat>(
+		RELAYERS_PALLET_NAME,
+		REGISTERED_RELAYERS_MAP_NAME,
+		&relayer.encode(),
+	);
+	Ok(client.storage_value(at, storage_key).await?)
+}
+
+/// Submit transaction and wait until it is finalized.
+async fn submit_and_wait<C: ChainWithTransactions>(
+	client: &impl Client<C>,
+	signer: &AccountKeyPairOf<C>,
+	call: C::Call,
+	description: &str,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+{
+	let tx_status = client
+		.submit_and_watch_signed_extrinsic(signer, move |_, transaction_nonce| {
+			Ok(UnsignedTransaction::new(call.into(), transaction_nonce))
+		})
+		.await?
+		.wait()
+		.await;
+	match tx_status {
+		TrackedTransactionStatus::Finalized(block) => {
+			log::info!(
+				target: "bridge",
+				"{} transaction has been finalized at {} block {:?}",
+				description,
+				C::NAME,
+				block,
+			);
+			Ok(())
+		},
+		TrackedTransactionStatus::Lost =>
+			Err(anyhow::format_err!("{} transaction at {} is lost", description, C::NAME)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn register_relayer_params_are_parsed() {
+		let register = RegisterRelayer::from_iter(vec![
+			"register-relayer",
+			"millau",
+			"--target-port",
+			"1234",
+			"--target-signer",
+			"//Alice",
+			"--valid-till",
+			"1000",
+		]);
+		assert!(matches!(register.chain, RegisterRelayerChain::Millau));
+		assert_eq!(register.valid_till, Some(1000));
+		assert!(!register.deregister);
+
+		let deregister = RegisterRelayer::from_iter_safe(vec![
+			"register-relayer",
+			"millau",
+			"--target-signer",
+			"//Alice",
+			"--deregister",
+		])
+		.unwrap();
+		assert_eq!(deregister.valid_till, None);
+		assert!(deregister.deregister);
+
+		assert!(RegisterRelayer::from_iter_safe(vec![
+			"register-relayer",
+			"millau",
+			"--target-signer",
+			"//Alice",
+		])
+		.is_err());
+	}
+}
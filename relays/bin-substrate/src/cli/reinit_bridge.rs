@@ -0,0 +1,347 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use async_trait::async_trait;
+use codec::Encode;
+
+use crate::{
+	bridges::{
+		kusama_polkadot::{
+			kusama_headers_to_bridge_hub_polkadot::KusamaToBridgeHubPolkadotCliBridge,
+			polkadot_headers_to_bridge_hub_kusama::PolkadotToBridgeHubKusamaCliBridge,
+		},
+		rialto_millau::rialto_headers_to_millau::RialtoToMillauCliBridge,
+		rococo_wococo::{
+			rococo_headers_to_bridge_hub_wococo::RococoToBridgeHubWococoCliBridge,
+			wococo_headers_to_bridge_hub_rococo::WococoToBridgeHubRococoCliBridge,
+		},
+		westend_millau::westend_headers_to_millau::WestendToMillauCliBridge,
+	},
+	cli::{bridge::CliBridgeBase, chain_schema::*, BlockHashOrNumber},
+};
+use bp_runtime::{BasicOperatingMode, Chain as ChainBase};
+use relay_substrate_client::{
+	AccountKeyPairOf, Chain, ChainWithUtilityPallet, Client, UnsignedTransaction, UtilityPallet,
+};
+use sp_core::Pair;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+use substrate_relay_helper::finality_base::engine::{Engine, Grandpa as GrandpaFinalityEngine};
+
+/// Re-initialize bridge pallet, that has missed GRANDPA authorities set change.
+///
+/// The command prepares batch of two calls: the first call halts the pallet and the second call
+/// re-initializes it with the finalized source header and the authorities set at this header.
+/// The batch must be dispatched by the pallet owner or by the root (governance) origin.
+#[derive(StructOpt)]
+pub struct ReinitBridge {
+	/// A bridge instance to re-initialize.
+	#[structopt(possible_values = ReinitBridgeName::VARIANTS, case_insensitive = true)]
+	bridge: ReinitBridgeName,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	/// Hash or number of the finalized source chain header to re-initialize the bridge with. By
+	/// default, the best finalized header is used.
+	#[structopt(long)]
+	at_block: Option<BlockHashOrNumber>,
+	/// If passed, the re-initialization transaction is signed and submitted to the target chain.
+	/// Otherwise, the encoded call is only printed.
+	#[structopt(long)]
+	submit: bool,
+}
+
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+/// Bridge to re-initialize.
+pub enum ReinitBridgeName {
+	RialtoToMillau,
+	WestendToMillau,
+	RococoToBridgeHubWococo,
+	WococoToBridgeHubRococo,
+	KusamaToBridgeHubPolkadot,
+	PolkadotToBridgeHubKusama,
+}
+
+#[async_trait]
+trait BridgeReinitializer: CliBridgeBase
+where
+	Self::Target: ChainWithUtilityPallet,
+	<Self::Target as ChainBase>::AccountId: From<<AccountKeyPairOf<Self::Target> as Pair>::Public>,
+{
+	type Engine: Engine<Self::Source>;
+
+	/// Get the encoded call to change the bridge pallet operating mode.
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call;
+
+	/// Get the encoded call to init the bridge.
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call;
+
+	/// Wrap the call so that it is dispatched with required origin. By default, the call is
+	/// expected to be signed by the pallet owner.
+	fn wrap_with_origin(call: <Self::Target as Chain>::Call) -> <Self::Target as Chain>::Call {
+		call
+	}
+
+	/// Re-initialize the bridge.
+	async fn reinit_bridge(data: ReinitBridge) -> anyhow::Result<()> {
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		let target_client = data.target.into_client::<Self::Target>().await?;
+
+		let initial_header = match data.at_block {
+			Some(at_block) => at_block.hash(&source_client).await?,
+			None => source_client.best_finalized_header_hash().await?,
+		};
+		let initialization_data =
+			Self::Engine::prepare_initialization_data_at(source_client, initial_header)
+				.await
+				.map_err(|e| anyhow::format_err!("{}", e))?;
+		log::info!(
+			target: "bridge",
+			"Prepared re-initialization data for {}-headers bridge at {}: {:?}",
+			Self::Source::NAME,
+			Self::Target::NAME,
+			initialization_data,
+		);
+
+		let call = Self::wrap_with_origin(
+			<Self::Target as ChainWithUtilityPallet>::UtilityPallet::build_batch_call(vec![
+				Self::encode_set_operating_mode(BasicOperatingMode::Halted),
+				Self::encode_init_bridge(initialization_data),
+			]),
+		);
+		log::info!(
+			target: "bridge",
+			"Re-initialize bridge call encoded as hex string: {:?}",
+			format!("0x{}", hex::encode(call.encode()))
+		);
+		if !data.submit {
+			return Ok(())
+		}
+
+		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
+		let tx_status = target_client
+			.submit_and_watch_signed_extrinsic(&target_sign, move |_, transaction_nonce| {
+				Ok(UnsignedTransaction::new(call.into(), transaction_nonce))
+			})
+			.await?
+			.wait()
+			.await;
+		log::info!(
+			target: "bridge",
+			"{}-headers bridge re-initialization transaction at {} status: {:?}",
+			Self::Source::NAME,
+			Self::Target::NAME,
+			tx_status,
+		);
+
+		Ok(())
+	}
+}
+
+impl BridgeReinitializer for RialtoToMillauCliBridge {
+	type Engine = GrandpaFinalityEngine<Self::Source>;
+
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call {
+		millau_runtime::BridgeGrandpaCall::<
+			millau_runtime::Runtime,
+			millau_runtime::RialtoGrandpaInstance,
+		>::set_operating_mode {
+			operating_mode,
+		}
+		.into()
+	}
+
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		millau_runtime::BridgeGrandpaCall::<
+			millau_runtime::Runtime,
+			millau_runtime::RialtoGrandpaInstance,
+		>::initialize {
+			init_data,
+		}
+		.into()
+	}
+
+	fn wrap_with_origin(call: <Self::Target as Chain>::Call) -> <Self::Target as Chain>::Call {
+		millau_runtime::SudoCall::sudo { call: Box::new(call) }.into()
+	}
+}
+
+impl BridgeReinitializer for WestendToMillauCliBridge {
+	type Engine = GrandpaFinalityEngine<Self::Source>;
+
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call {
+		millau_runtime::BridgeGrandpaCall::<
+			millau_runtime::Runtime,
+			millau_runtime::WestendGrandpaInstance,
+		>::set_operating_mode {
+			operating_mode,
+		}
+		.into()
+	}
+
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		millau_runtime::BridgeGrandpaCall::<
+			millau_runtime::Runtime,
+			millau_runtime::WestendGrandpaInstance,
+		>::initialize {
+			init_data,
+		}
+		.into()
+	}
+
+	fn wrap_with_origin(call: <Self::Target as Chain>::Call) -> <Self::Target as Chain>::Call {
+		millau_runtime::SudoCall::sudo { call: Box::new(call) }.into()
+	}
+}
+
+impl BridgeReinitializer for RococoToBridgeHubWococoCliBridge {
+	type Engine = GrandpaFinalityEngine<Self::Source>;
+
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call {
+		type OperatingMode =
+			relay_bridge_hub_wococo_client::runtime_types::bp_runtime::BasicOperatingMode;
+
+		relay_bridge_hub_wococo_client::RuntimeCall::BridgeRococoGrandpa(
+			relay_bridge_hub_wococo_client::BridgeGrandpaCall::set_operating_mode {
+				operating_mode: match operating_mode {
+					BasicOperatingMode::Normal => OperatingMode::Normal,
+					BasicOperatingMode::Halted => OperatingMode::Halted,
+				},
+			},
+		)
+	}
+
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		relay_bridge_hub_wococo_client::RuntimeCall::BridgeRococoGrandpa(
+			relay_bridge_hub_wococo_client::BridgeGrandpaCall::initialize { init_data },
+		)
+	}
+}
+
+impl BridgeReinitializer for WococoToBridgeHubRococoCliBridge {
+	type Engine = GrandpaFinalityEngine<Self::Source>;
+
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call {
+		type OperatingMode =
+			relay_bridge_hub_rococo_client::runtime_types::bp_runtime::BasicOperatingMode;
+
+		relay_bridge_hub_rococo_client::RuntimeCall::BridgeWococoGrandpa(
+			relay_bridge_hub_rococo_client::BridgeGrandpaCall::set_operating_mode {
+				operating_mode: match operating_mode {
+					BasicOperatingMode::Normal => OperatingMode::Normal,
+					BasicOperatingMode::Halted => OperatingMode::Halted,
+				},
+			},
+		)
+	}
+
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		relay_bridge_hub_rococo_client::RuntimeCall::BridgeWococoGrandpa(
+			relay_bridge_hub_rococo_client::BridgeGrandpaCall::initialize { init_data },
+		)
+	}
+}
+
+impl BridgeReinitializer for KusamaToBridgeHubPolkadotCliBridge {
+	type Engine = GrandpaFinalityEngine<Self::Source>;
+
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call {
+		relay_bridge_hub_polkadot_client::runtime::Call::BridgeKusamaGrandpa(
+			relay_bridge_hub_polkadot_client::runtime::BridgeKusamaGrandpaCall::set_operating_mode {
+				operating_mode,
+			},
+		)
+	}
+
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		relay_bridge_hub_polkadot_client::runtime::Call::BridgeKusamaGrandpa(
+			relay_bridge_hub_polkadot_client::runtime::BridgeKusamaGrandpaCall::initialize {
+				init_data,
+			},
+		)
+	}
+}
+
+impl BridgeReinitializer for PolkadotToBridgeHubKusamaCliBridge {
+	type Engine = GrandpaFinalityEngine<Self::Source>;
+
+	fn encode_set_operating_mode(
+		operating_mode: BasicOperatingMode,
+	) -> <Self::Target as Chain>::Call {
+		relay_bridge_hub_kusama_client::runtime::Call::BridgePolkadotGrandpa(
+			relay_bridge_hub_kusama_client::runtime::BridgePolkadotGrandpaCall::set_operating_mode {
+				operating_mode,
+			},
+		)
+	}
+
+	fn encode_init_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		relay_bridge_hub_kusama_client::runtime::Call::BridgePolkadotGrandpa(
+			relay_bridge_hub_kusama_client::runtime::BridgePolkadotGrandpaCall::initialize {
+				init_data,
+			},
+		)
+	}
+}
+
+impl ReinitBridge {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			ReinitBridgeName::RialtoToMillau => RialtoToMillauCliBridge::reinit_bridge(self),
+			ReinitBridgeName::WestendToMillau => WestendToMillauCliBridge::reinit_bridge(self),
+			ReinitBridgeName::RococoToBridgeHubWococo =>
+				RococoToBridgeHubWococoCliBridge::reinit_bridge(self),
+			ReinitBridgeName::WococoToBridgeHubRococo =>
+				WococoToBridgeHubRococoCliBridge::reinit_bridge(self),
+			ReinitBridgeName::KusamaToBridgeHubPolkadot =>
+				KusamaToBridgeHubPolkadotCliBridge::reinit_bridge(self),
+			ReinitBridgeName::PolkadotToBridgeHubKusama =>
+				PolkadotToBridgeHubKusamaCliBridge::reinit_bridge(self),
+		}
+		.await
+	}
+}
@@ -0,0 +1,216 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Running multiple complex relays in a single process.
+//!
+//! Bridges are described in the TOML file. Every `[[bridge]]` table describes single complex
+//! relay: the `name` key is the name of the bridge (as in `relay-headers-and-messages`
+//! subcommand) and other keys are the options of this subcommand:
+//!
+//! ```toml
+//! [[bridge]]
+//! name = "millau-rialto"
+//! millau-host = "millau-node-alice"
+//! millau-port = 9944
+//! millau-signer = "//Charlie"
+//! rialto-host = "rialto-node-alice"
+//! rialto-port = 9944
+//! rialto-signer = "//Charlie"
+//! lane = ["0x00000000"]
+//!
+//! [[bridge]]
+//! name = "millau-rialto-parachain"
+//! millau-host = "millau-node-alice"
+//! millau-port = 9944
+//! ...
+//! ```
+//!
+//! All bridges are sharing the same metrics endpoint, so Prometheus options must be specified
+//! in the command line. Bridges are also sharing clients that are connected to the same node.
+
+use crate::cli::{
+	config_file, relay_headers_and_messages::RelayHeadersAndMessages, PrometheusParams,
+};
+
+use anyhow::format_err;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Name of the TOML array with bridges.
+const BRIDGES_KEY: &str = "bridge";
+/// Name of the key with the bridge name.
+const BRIDGE_NAME_KEY: &str = "name";
+/// Options that may only be specified in the command line.
+const SHARED_OPTIONS: [&str; 17] = [
+	"no-prometheus",
+	"prometheus-host",
+	"prometheus-port",
+	"prometheus-tls-cert",
+	"prometheus-tls-key",
+	"prometheus-bearer-token",
+	"prometheus-bearer-token-file",
+	"prometheus-basic-auth",
+	"prometheus-basic-auth-file",
+	"prometheus-push-gateway",
+	"prometheus-push-job",
+	"prometheus-push-instance",
+	"prometheus-push-interval-secs",
+	"prometheus-label",
+	"health-max-stall-minutes",
+	"enable-control",
+	"token-price",
+];
+
+/// Start multiple complex relays.
+#[derive(StructOpt)]
+pub struct RelayBridges {
+	/// Path to the TOML file with bridges description.
+	#[structopt(long)]
+	bridges_config: PathBuf,
+	#[structopt(flatten)]
+	prometheus_params: PrometheusParams,
+}
+
+impl RelayBridges {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let bridges = read_bridges_config(&self.bridges_config)?;
+
+		let metrics_params = self.prometheus_params.into_metrics_params()?;
+		relay_utils::relay_metrics(metrics_params.clone())
+			.expose()
+			.await
+			.map_err(|e| format_err!("{}", e))?;
+		// metrics are already exposed, so bridges only need the registry
+		let metrics_params = metrics_params.disable();
+
+		// bridges are isolated - failure of one bridge doesn't stop other bridges
+		let results = futures::future::join_all(bridges.into_iter().map(|(name, bridge)| {
+			let metrics_params = metrics_params.clone();
+			async move {
+				let result = bridge.run_with_shared_metrics(Some(metrics_params)).await;
+				if let Err(ref e) = result {
+					log::error!(target: "bridge", "Bridge {} has failed: {}", name, e);
+				}
+				result
+			}
+		}))
+		.await;
+
+		let failed_bridges = results.iter().filter(|result| result.is_err()).count();
+		if failed_bridges != 0 {
+			return Err(format_err!("{} of {} bridges have failed", failed_bridges, results.len()))
+		}
+
+		Ok(())
+	}
+}
+
+/// Read bridges description from the TOML file.
+fn read_bridges_config(path: &Path) -> anyhow::Result<Vec<(String, RelayHeadersAndMessages)>> {
+	let config = std::fs::read_to_string(path)
+		.map_err(|e| format_err!("Failed to read bridges file {:?}: {}", path, e))?;
+	parse_bridges_config(&config).map_err(|e| format_err!("Invalid bridges file {:?}: {}", path, e))
+}
+
+/// Parse bridges description.
+fn parse_bridges_config(config: &str) -> anyhow::Result<Vec<(String, RelayHeadersAndMessages)>> {
+	let mut config: toml::Table = config.parse()?;
+	let bridges = match config.remove(BRIDGES_KEY) {
+		Some(toml::Value::Array(bridges)) if config.is_empty() => bridges,
+		_ => return Err(format_err!("Expected `[[{}]]` tables only", BRIDGES_KEY)),
+	};
+
+	bridges
+		.into_iter()
+		.enumerate()
+		.map(|(index, bridge)| {
+			let mut bridge = match bridge {
+				toml::Value::Table(bridge) => bridge,
+				_ => return Err(format_err!("Bridge #{} is not a table", index)),
+			};
+			let bridge_name = match bridge.remove(BRIDGE_NAME_KEY) {
+				Some(toml::Value::String(bridge_name)) => bridge_name,
+				_ => return Err(format_err!("Bridge #{} has no `{}`", index, BRIDGE_NAME_KEY)),
+			};
+			if let Some(option) = SHARED_OPTIONS.iter().find(|option| bridge.contains_key(**option))
+			{
+				return Err(format_err!(
+					"Bridge #{} has `{}` option, which may only be specified in the command line",
+					index,
+					option,
+				))
+			}
+
+			let mut args = vec!["relay-headers-and-messages".to_string(), bridge_name.clone()];
+			args.extend(config_file::table_to_args(bridge)?.into_iter().flat_map(|(_, args)| args));
+			// metrics are exposed by the `relay-bridges` command
+			args.push("--no-prometheus".into());
+			let bridge = RelayHeadersAndMessages::from_iter_safe(args)
+				.map_err(|e| format_err!("Invalid bridge #{} options: {}", index, e))?;
+
+			Ok((format!("#{index} {bridge_name}"), bridge))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bridges_config_is_parsed() {
+		let bridges = parse_bridges_config(
+			r#"
+				[[bridge]]
+				name = "millau-rialto"
+				millau-port = 9944
+				millau-signer = "//Alice"
+				rialto-port = 9945
+				rialto-signer = "//Alice"
+				lane = ["0x00000000"]
+
+				[[bridge]]
+				name = "millau-rialto-parachain"
+				millau-port = 9944
+				millau-signer = "//Bob"
+				rialto-parachain-port = 9946
+				rialto-parachain-signer = "//Bob"
+				rialto-port = 9945
+				lane = ["0x00000000", "0x00000001"]
+			"#,
+		)
+		.unwrap();
+
+		assert_eq!(bridges.len(), 2);
+		assert_eq!(bridges[0].0, "#0 millau-rialto");
+		assert!(matches!(bridges[0].1, RelayHeadersAndMessages::MillauRialto(_)));
+		assert_eq!(bridges[1].0, "#1 millau-rialto-parachain");
+		assert!(matches!(bridges[1].1, RelayHeadersAndMessages::MillauRialtoParachain(_)));
+	}
+
+	#[test]
+	fn bridge_with_prometheus_options_is_rejected() {
+		assert!(parse_bridges_config(
+			r#"
+				[[bridge]]
+				name = "millau-rialto"
+				prometheus-port = 9617
+			"#,
+		)
+		.is_err());
+	}
+}
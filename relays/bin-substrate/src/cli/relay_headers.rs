@@ -50,6 +50,12 @@ pub struct RelayHeaders {
 	/// are relayed.
 	#[structopt(long)]
 	only_mandatory_headers: bool,
+	/// If passed, the relay doesn't read all headers that are missing at the target chain.
+	/// Instead, it only submits mandatory headers and the best headers with finality proofs.
+	/// This is much faster when the bridge is far behind, but the source node must be an
+	/// archive node.
+	#[structopt(long)]
+	warp_sync: bool,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
@@ -90,6 +96,8 @@ trait HeadersRelayer: RelayToRelayHeadersCliBridge {
 		let target_transactions_params = substrate_relay_helper::TransactionParams {
 			signer: target_sign,
 			mortality: target_transactions_mortality,
+			spending_budget: data.target_sign.spending_budget::<Self::Target>()?,
+			call_wrapper: data.target_sign.call_wrapper()?,
 		};
 		Self::Finality::start_relay_guards(
 			&target_client,
@@ -102,6 +110,7 @@ trait HeadersRelayer: RelayToRelayHeadersCliBridge {
 			source_client,
 			target_client,
 			data.only_mandatory_headers,
+			data.warp_sync,
 			target_transactions_params,
 			metrics_params,
 		)
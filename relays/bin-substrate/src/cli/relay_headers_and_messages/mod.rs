@@ -31,10 +31,12 @@ mod relay_to_relay;
 mod relay_to_parachain;
 
 use async_trait::async_trait;
-use std::{marker::PhantomData, sync::Arc};
+use signal_hook::consts::SIGHUP;
+use signal_hook_async_std::Signals;
+use std::{marker::PhantomData, str::FromStr, sync::Arc};
 use structopt::StructOpt;
 
-use futures::{FutureExt, TryFutureExt};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
 use relay_to_parachain::*;
 use relay_to_relay::*;
 
@@ -63,6 +65,7 @@ use crate::{
 			RelayToRelayHeadersCliBridge,
 		},
 		chain_schema::*,
+		config_file,
 		relay_headers_and_messages::parachain_to_parachain::ParachainToParachainBridge,
 		CliChain, DefaultClient, HexLaneId, PrometheusParams,
 	},
@@ -74,10 +77,12 @@ use relay_substrate_client::{
 	AccountIdOf, AccountKeyPairOf, Chain, ChainWithBalances, ChainWithMessages,
 	ChainWithTransactions, Parachain,
 };
-use relay_utils::metrics::MetricsParams;
+use relay_utils::{metrics::MetricsParams, supervisor::SupervisorParams};
 use sp_core::Pair;
+use sp_runtime::Perbill;
 use substrate_relay_helper::{
-	messages::MessagesRelayParams, on_demand::OnDemandRelay, TaggedAccount, TransactionParams,
+	messages::MessagesRelayParams, on_demand::OnDemandRelay, spending_budget::SpendingBudget,
+	CallWrapper, TaggedAccount, TransactionParams,
 };
 
 /// Parameters that have the same names across all bridges.
@@ -90,10 +95,43 @@ pub struct HeadersAndMessagesSharedParams {
 	/// are relayed.
 	#[structopt(long)]
 	pub only_mandatory_headers: bool,
+	/// Messages relay that is restarted more than this number of times during
+	/// `crash-loop-window-minutes` is considered crash-looping and the alert is fired.
+	#[structopt(long, default_value = "5")]
+	pub max_relay_restarts: u32,
+	/// Period (in minutes) during which messages relay restarts are counted.
+	#[structopt(long, default_value = "10")]
+	pub crash_loop_window_minutes: u64,
+	/// If passed, the complex relay exits when some of its messages relays is crash-looping.
+	#[structopt(long)]
+	pub exit_on_crash_loop: bool,
+	/// If specified, message delivery is paused while recent target chain blocks are, on
+	/// average, filled at least by this percent. By default, delivery is never paused.
+	#[structopt(long)]
+	pub congested_target_block_fullness_percent: Option<u32>,
 	#[structopt(flatten)]
 	pub prometheus_params: PrometheusParams,
 }
 
+impl HeadersAndMessagesSharedParams {
+	/// Returns parameters of relay loops supervisor.
+	pub fn supervisor_params(&self) -> SupervisorParams {
+		SupervisorParams {
+			max_restarts: self.max_relay_restarts,
+			crash_loop_window: std::time::Duration::from_secs(
+				self.crash_loop_window_minutes.saturating_mul(60),
+			),
+			exit_on_crash_loop: self.exit_on_crash_loop,
+		}
+	}
+
+	/// Returns target block fullness, starting from which the target chain is considered
+	/// congested.
+	pub fn congested_target_block_fullness(&self) -> Option<Perbill> {
+		self.congested_target_block_fullness_percent.map(Perbill::from_percent)
+	}
+}
+
 /// Bridge parameters, shared by all bridge types.
 pub struct Full2WayBridgeCommonParams<
 	Left: ChainWithTransactions + CliChain,
@@ -135,6 +173,12 @@ pub struct BridgeEndCommonParams<Chain: ChainWithTransactions + CliChain> {
 	pub sign: AccountKeyPairOf<Chain>,
 	/// Transactions mortality.
 	pub transactions_mortality: Option<u32>,
+	/// Spending budget of the transactions signer.
+	pub spending_budget: Option<SpendingBudget>,
+	/// Wrapper of all calls, submitted by the transactions signer.
+	pub call_wrapper: Option<CallWrapper>,
+	/// Relay accounts balance (in smallest units), below which the low balance alarm is raised.
+	pub balance_threshold: Option<u128>,
 	/// Accounts, which balances are exposed as metrics by the relay process.
 	pub accounts: Vec<TaggedAccount<AccountIdOf<Chain>>>,
 }
@@ -178,21 +222,28 @@ where
 		source_to_target_headers_relay: Arc<dyn OnDemandRelay<Source, Target>>,
 		target_to_source_headers_relay: Arc<dyn OnDemandRelay<Target, Source>>,
 		lane_id: LaneId,
+		congested_target_block_fullness: Option<Perbill>,
 	) -> MessagesRelayParams<Bridge::MessagesLane, DefaultClient<Source>, DefaultClient<Target>> {
 		MessagesRelayParams {
 			source_client: self.source.client.clone(),
 			source_transaction_params: TransactionParams {
 				signer: self.source.sign.clone(),
 				mortality: self.source.transactions_mortality,
+				spending_budget: self.source.spending_budget.clone(),
+				call_wrapper: self.source.call_wrapper.clone(),
 			},
 			target_client: self.target.client.clone(),
 			target_transaction_params: TransactionParams {
 				signer: self.target.sign.clone(),
 				mortality: self.target.transactions_mortality,
+				spending_budget: self.target.spending_budget.clone(),
+				call_wrapper: self.target.call_wrapper.clone(),
 			},
 			source_to_target_headers_relay: Some(source_to_target_headers_relay),
 			target_to_source_headers_relay: Some(target_to_source_headers_relay),
 			lane_id,
+			since_source_block: None,
+			congested_target_block_fullness,
 			metrics_params: self.metrics_params.clone().disable(),
 		}
 	}
@@ -323,7 +374,10 @@ where
 	}
 
 	/// Start complex relay.
-	async fn run(&mut self) -> anyhow::Result<()> {
+	///
+	/// If `reload_lanes` is true, the relay starts serving lanes that are added to the
+	/// configuration file when `SIGHUP` is received.
+	async fn run(&mut self, reload_lanes: bool) -> anyhow::Result<()> {
 		// Register standalone metrics.
 		{
 			let common = self.mut_base().mut_common();
@@ -358,6 +412,7 @@ where
 				&common.metrics_params,
 				&common.left.accounts,
 				&lanes,
+				common.left.balance_threshold,
 			)
 			.await?;
 			substrate_relay_helper::messages::metrics::add_relay_balances_metrics::<_, Self::Left>(
@@ -365,38 +420,18 @@ where
 				&common.metrics_params,
 				&common.right.accounts,
 				&lanes,
+				common.right.balance_threshold,
 			)
 			.await?;
 		}
 
-		// Need 2x capacity since we consider both directions for each lane
-		let mut message_relays = Vec::with_capacity(lanes.len() * 2);
-		for lane in lanes {
-			let left_to_right_messages = substrate_relay_helper::messages::run::<
-				<Self::L2R as MessagesCliBridge>::MessagesLane,
-				_,
-				_,
-			>(self.left_to_right().messages_relay_params(
+		let mut message_relays = FuturesUnordered::new();
+		for lane in &lanes {
+			message_relays.extend(self.start_lane_relays(
+				*lane,
 				left_to_right_on_demand_headers.clone(),
 				right_to_left_on_demand_headers.clone(),
-				lane,
-			))
-			.map_err(|e| anyhow::format_err!("{}", e))
-			.boxed();
-			message_relays.push(left_to_right_messages);
-
-			let right_to_left_messages = substrate_relay_helper::messages::run::<
-				<Self::R2L as MessagesCliBridge>::MessagesLane,
-				_,
-				_,
-			>(self.right_to_left().messages_relay_params(
-				right_to_left_on_demand_headers.clone(),
-				left_to_right_on_demand_headers.clone(),
-				lane,
-			))
-			.map_err(|e| anyhow::format_err!("{}", e))
-			.boxed();
-			message_relays.push(right_to_left_messages);
+			));
 		}
 
 		relay_utils::relay_metrics(self.base().common().metrics_params.clone())
@@ -404,8 +439,148 @@ where
 			.await
 			.map_err(|e| anyhow::format_err!("{}", e))?;
 
-		futures::future::select_all(message_relays).await.0
+		// lanes may be added to the configuration file without restarting the relay
+		let mut running_lanes = lanes;
+		let mut reload_signals = if reload_lanes {
+			Signals::new([SIGHUP])
+				.map_err(|e| anyhow::format_err!("Could not register reload signal: {}", e))?
+				.boxed()
+		} else {
+			futures::stream::pending().boxed()
+		};
+		loop {
+			futures::select! {
+				// if any lane relay fails, the whole relay is stopped. Otherwise we wait until
+				// relays of all lanes are finished
+				result = message_relays.select_next_some() => {
+					result?;
+					if message_relays.is_empty() {
+						return Ok(())
+					}
+				},
+				_ = reload_signals.next().fuse() => {
+					let new_lanes = match reload_lanes(&running_lanes) {
+						Ok(new_lanes) => new_lanes,
+						Err(e) => {
+							log::error!(target: "bridge", "Failed to reload lanes: {}", e);
+							continue
+						},
+					};
+					for lane in new_lanes {
+						log::info!(target: "bridge", "Starting relay of lane {:?}", lane);
+						message_relays.extend(self.start_lane_relays(
+							lane,
+							left_to_right_on_demand_headers.clone(),
+							right_to_left_on_demand_headers.clone(),
+						));
+						running_lanes.push(lane);
+					}
+				},
+			}
+		}
 	}
+
+	/// Start messages relays in both directions for given lane.
+	fn start_lane_relays(
+		&mut self,
+		lane: LaneId,
+		left_to_right_on_demand_headers: Arc<dyn OnDemandRelay<Self::Left, Self::Right>>,
+		right_to_left_on_demand_headers: Arc<dyn OnDemandRelay<Self::Right, Self::Left>>,
+	) -> [BoxFuture<'static, anyhow::Result<()>>; 2] {
+		let supervisor_params = self.base().common().shared.supervisor_params();
+		let congested_target_block_fullness =
+			self.base().common().shared.congested_target_block_fullness();
+
+		// messages relays are restarted by the supervisor when they fail
+		let left_to_right_params = self.left_to_right().messages_relay_params(
+			left_to_right_on_demand_headers.clone(),
+			right_to_left_on_demand_headers.clone(),
+			lane,
+			congested_target_block_fullness,
+		);
+		let left_to_right_messages = relay_utils::supervisor::supervise(
+			format!(
+				"{}-to-{} messages relay of lane {:?}",
+				Self::Left::NAME,
+				Self::Right::NAME,
+				lane
+			),
+			supervisor_params.clone(),
+			move || {
+				substrate_relay_helper::messages::run::<
+					<Self::L2R as MessagesCliBridge>::MessagesLane,
+					_,
+					_,
+				>(left_to_right_params.clone())
+			},
+		)
+		.boxed();
+
+		let right_to_left_params = self.right_to_left().messages_relay_params(
+			right_to_left_on_demand_headers,
+			left_to_right_on_demand_headers,
+			lane,
+			congested_target_block_fullness,
+		);
+		let right_to_left_messages = relay_utils::supervisor::supervise(
+			format!(
+				"{}-to-{} messages relay of lane {:?}",
+				Self::Right::NAME,
+				Self::Left::NAME,
+				lane
+			),
+			supervisor_params,
+			move || {
+				substrate_relay_helper::messages::run::<
+					<Self::R2L as MessagesCliBridge>::MessagesLane,
+					_,
+					_,
+				>(right_to_left_params.clone())
+			},
+		)
+		.boxed();
+
+		[left_to_right_messages, right_to_left_messages]
+	}
+}
+
+/// Re-read lanes from the configuration file and return lanes that are not yet served by the
+/// relay.
+///
+/// Only additions are supported. If any of running lanes has been removed from the configuration
+/// file or any other option has been changed there, an error is returned and no new lanes are
+/// started - removing lanes and changing other options requires the relay restart.
+fn reload_lanes(running_lanes: &[LaneId]) -> anyhow::Result<Vec<LaneId>> {
+	let lanes = match config_file::reload_option_values("lane")? {
+		Some(lanes) => lanes,
+		None => {
+			log::info!(
+				target: "bridge",
+				"Lanes may only be reloaded if they are specified in the configuration file",
+			);
+			return Ok(Vec::new())
+		},
+	};
+	let lanes = lanes
+		.iter()
+		.map(|lane| {
+			HexLaneId::from_str(lane)
+				.map(Into::into)
+				.map_err(|e| anyhow::format_err!("Invalid lane {}: {:?}", lane, e))
+		})
+		.collect::<anyhow::Result<Vec<LaneId>>>()?;
+
+	let removed_lanes =
+		running_lanes.iter().filter(|lane| !lanes.contains(lane)).collect::<Vec<_>>();
+	if !removed_lanes.is_empty() {
+		return Err(anyhow::format_err!(
+			"Lanes {:?} have been removed from the configuration file. Removing lanes requires \
+			relay restart",
+			removed_lanes,
+		))
+	}
+
+	Ok(lanes.into_iter().filter(|lane| !running_lanes.contains(lane)).collect())
 }
 
 /// Millau <> Rialto complex relay.
@@ -528,25 +703,65 @@ pub enum RelayHeadersAndMessages {
 impl RelayHeadersAndMessages {
 	/// Run the command.
 	pub async fn run(self) -> anyhow::Result<()> {
+		self.run_with_shared_metrics(None).await
+	}
+
+	/// Run the command, optionally as one of multiple bridges, served by the same process.
+	///
+	/// If `shared_metrics_params` are provided, relay metrics are registered there instead of
+	/// using own metrics endpoint. Lanes are not reloaded in this case.
+	pub async fn run_with_shared_metrics(
+		self,
+		shared_metrics_params: Option<MetricsParams>,
+	) -> anyhow::Result<()> {
 		match self {
 			RelayHeadersAndMessages::MillauRialto(params) =>
-				MillauRialtoFull2WayBridge::new(params.into_bridge().await?)?.run().await,
+				run_full_bridge::<MillauRialtoFull2WayBridge>(
+					params.into_bridge().await?,
+					shared_metrics_params,
+				)
+				.await,
 			RelayHeadersAndMessages::MillauRialtoParachain(params) =>
-				MillauRialtoParachainFull2WayBridge::new(params.into_bridge().await?)?
-					.run()
-					.await,
+				run_full_bridge::<MillauRialtoParachainFull2WayBridge>(
+					params.into_bridge().await?,
+					shared_metrics_params,
+				)
+				.await,
 			RelayHeadersAndMessages::BridgeHubRococoBridgeHubWococo(params) =>
-				BridgeHubRococoBridgeHubWococoFull2WayBridge::new(params.into_bridge().await?)?
-					.run()
-					.await,
+				run_full_bridge::<BridgeHubRococoBridgeHubWococoFull2WayBridge>(
+					params.into_bridge().await?,
+					shared_metrics_params,
+				)
+				.await,
 			RelayHeadersAndMessages::BridgeHubKusamaBridgeHubPolkadot(params) =>
-				BridgeHubKusamaBridgeHubPolkadotFull2WayBridge::new(params.into_bridge().await?)?
-					.run()
-					.await,
+				run_full_bridge::<BridgeHubKusamaBridgeHubPolkadotFull2WayBridge>(
+					params.into_bridge().await?,
+					shared_metrics_params,
+				)
+				.await,
 		}
 	}
 }
 
+/// Construct and run bidirectional complex relay.
+async fn run_full_bridge<Bridge: Full2WayBridge>(
+	base: Bridge::Base,
+	shared_metrics_params: Option<MetricsParams>,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<Bridge::Left>: From<<AccountKeyPairOf<Bridge::Left> as Pair>::Public>,
+	AccountIdOf<Bridge::Right>: From<<AccountKeyPairOf<Bridge::Right> as Pair>::Public>,
+	BalanceOf<Bridge::Left>: TryFrom<BalanceOf<Bridge::Right>> + Into<u128>,
+	BalanceOf<Bridge::Right>: TryFrom<BalanceOf<Bridge::Left>> + Into<u128>,
+{
+	let mut bridge = Bridge::new(base)?;
+	let reload_lanes = shared_metrics_params.is_none();
+	if let Some(shared_metrics_params) = shared_metrics_params {
+		bridge.mut_base().mut_common().metrics_params = shared_metrics_params;
+	}
+	bridge.run(reload_lanes).await
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -592,10 +807,28 @@ mod tests {
 						HexLaneId(H256::from([0x11u8; 32]))
 					],
 					only_mandatory_headers: false,
+					max_relay_restarts: 5,
+					crash_loop_window_minutes: 10,
+					exit_on_crash_loop: false,
+					congested_target_block_fullness_percent: None,
 					prometheus_params: PrometheusParams {
 						no_prometheus: false,
 						prometheus_host: "0.0.0.0".into(),
 						prometheus_port: 9616,
+						prometheus_tls_cert: None,
+						prometheus_tls_key: None,
+						prometheus_bearer_token: None,
+						prometheus_bearer_token_file: None,
+						prometheus_basic_auth: None,
+						prometheus_basic_auth_file: None,
+						prometheus_push_gateway: None,
+						prometheus_push_job: "substrate-relay".into(),
+						prometheus_push_instance: None,
+						prometheus_push_interval_secs: 15,
+						prometheus_label: vec![],
+						health_max_stall_minutes: 10,
+						enable_control: false,
+						token_price: vec![],
 					},
 				},
 				left: MillauConnectionParams {
@@ -613,14 +846,32 @@ mod tests {
 					millau_signer_password: None,
 					millau_signer_file: None,
 					millau_signer_password_file: None,
+					millau_signer_keystore: None,
 					millau_transactions_mortality: Some(64),
+					millau_max_spending_per_hour: None,
+					millau_max_spending_per_day: None,
+					millau_min_balance: None,
+					millau_balance_threshold: None,
+					millau_proxy_pallet_index: None,
+					millau_proxied_address: None,
+					millau_multisig_pallet_index: None,
+					millau_multisig_other_signatories: vec![],
 				},
 				left_headers_to_right_sign_override: MillauHeadersToRialtoSigningParams {
 					millau_headers_to_rialto_signer: None,
 					millau_headers_to_rialto_signer_password: None,
 					millau_headers_to_rialto_signer_file: None,
 					millau_headers_to_rialto_signer_password_file: None,
+					millau_headers_to_rialto_signer_keystore: None,
 					millau_headers_to_rialto_transactions_mortality: None,
+					millau_headers_to_rialto_max_spending_per_hour: None,
+					millau_headers_to_rialto_max_spending_per_day: None,
+					millau_headers_to_rialto_min_balance: None,
+					millau_headers_to_rialto_balance_threshold: None,
+					millau_headers_to_rialto_proxy_pallet_index: None,
+					millau_headers_to_rialto_proxied_address: None,
+					millau_headers_to_rialto_multisig_pallet_index: None,
+					millau_headers_to_rialto_multisig_other_signatories: vec![],
 				},
 				right: RialtoConnectionParams {
 					rialto_host: "rialto-node-alice".into(),
@@ -637,14 +888,32 @@ mod tests {
 					rialto_signer_password: None,
 					rialto_signer_file: None,
 					rialto_signer_password_file: None,
+					rialto_signer_keystore: None,
 					rialto_transactions_mortality: Some(64),
+					rialto_max_spending_per_hour: None,
+					rialto_max_spending_per_day: None,
+					rialto_min_balance: None,
+					rialto_balance_threshold: None,
+					rialto_proxy_pallet_index: None,
+					rialto_proxied_address: None,
+					rialto_multisig_pallet_index: None,
+					rialto_multisig_other_signatories: vec![],
 				},
 				right_headers_to_left_sign_override: RialtoHeadersToMillauSigningParams {
 					rialto_headers_to_millau_signer: None,
 					rialto_headers_to_millau_signer_password: None,
 					rialto_headers_to_millau_signer_file: None,
 					rialto_headers_to_millau_signer_password_file: None,
+					rialto_headers_to_millau_signer_keystore: None,
 					rialto_headers_to_millau_transactions_mortality: None,
+					rialto_headers_to_millau_max_spending_per_hour: None,
+					rialto_headers_to_millau_max_spending_per_day: None,
+					rialto_headers_to_millau_min_balance: None,
+					rialto_headers_to_millau_balance_threshold: None,
+					rialto_headers_to_millau_proxy_pallet_index: None,
+					rialto_headers_to_millau_proxied_address: None,
+					rialto_headers_to_millau_multisig_pallet_index: None,
+					rialto_headers_to_millau_multisig_other_signatories: vec![],
 				},
 			}),
 		);
@@ -692,10 +961,28 @@ mod tests {
 					shared: HeadersAndMessagesSharedParams {
 						lane: vec![HexLaneId(H256::from([0x00u8; 32]))],
 						only_mandatory_headers: false,
+						max_relay_restarts: 5,
+						crash_loop_window_minutes: 10,
+						exit_on_crash_loop: false,
+						congested_target_block_fullness_percent: None,
 						prometheus_params: PrometheusParams {
 							no_prometheus: false,
 							prometheus_host: "0.0.0.0".into(),
 							prometheus_port: 9616,
+							prometheus_tls_cert: None,
+							prometheus_tls_key: None,
+							prometheus_bearer_token: None,
+							prometheus_bearer_token_file: None,
+							prometheus_basic_auth: None,
+							prometheus_basic_auth_file: None,
+							prometheus_push_gateway: None,
+							prometheus_push_job: "substrate-relay".into(),
+							prometheus_push_instance: None,
+							prometheus_push_interval_secs: 15,
+							prometheus_label: vec![],
+							health_max_stall_minutes: 10,
+							enable_control: false,
+							token_price: vec![],
 						},
 					},
 					left: MillauConnectionParams {
@@ -713,7 +1000,16 @@ mod tests {
 						millau_signer_password: None,
 						millau_signer_file: None,
 						millau_signer_password_file: None,
+						millau_signer_keystore: None,
 						millau_transactions_mortality: Some(64),
+						millau_max_spending_per_hour: None,
+						millau_max_spending_per_day: None,
+						millau_min_balance: None,
+						millau_balance_threshold: None,
+						millau_proxy_pallet_index: None,
+						millau_proxied_address: None,
+						millau_multisig_pallet_index: None,
+						millau_multisig_other_signatories: vec![],
 					},
 					left_headers_to_right_sign_override:
 						MillauHeadersToRialtoParachainSigningParams {
@@ -721,7 +1017,16 @@ mod tests {
 							millau_headers_to_rialto_parachain_signer_password: None,
 							millau_headers_to_rialto_parachain_signer_file: None,
 							millau_headers_to_rialto_parachain_signer_password_file: None,
+							millau_headers_to_rialto_parachain_signer_keystore: None,
 							millau_headers_to_rialto_parachain_transactions_mortality: None,
+							millau_headers_to_rialto_parachain_max_spending_per_hour: None,
+							millau_headers_to_rialto_parachain_max_spending_per_day: None,
+							millau_headers_to_rialto_parachain_min_balance: None,
+							millau_headers_to_rialto_parachain_balance_threshold: None,
+							millau_headers_to_rialto_parachain_proxy_pallet_index: None,
+							millau_headers_to_rialto_parachain_proxied_address: None,
+							millau_headers_to_rialto_parachain_multisig_pallet_index: None,
+							millau_headers_to_rialto_parachain_multisig_other_signatories: vec![],
 						},
 					right: RialtoParachainConnectionParams {
 						rialto_parachain_host: "rialto-parachain-collator-charlie".into(),
@@ -738,21 +1043,48 @@ mod tests {
 						rialto_parachain_signer_password: None,
 						rialto_parachain_signer_file: None,
 						rialto_parachain_signer_password_file: None,
+						rialto_parachain_signer_keystore: None,
 						rialto_parachain_transactions_mortality: Some(64),
+						rialto_parachain_max_spending_per_hour: None,
+						rialto_parachain_max_spending_per_day: None,
+						rialto_parachain_min_balance: None,
+						rialto_parachain_balance_threshold: None,
+						rialto_parachain_proxy_pallet_index: None,
+						rialto_parachain_proxied_address: None,
+						rialto_parachain_multisig_pallet_index: None,
+						rialto_parachain_multisig_other_signatories: vec![],
 					},
 					right_relay_headers_to_left_sign_override: RialtoHeadersToMillauSigningParams {
 						rialto_headers_to_millau_signer: Some("//Ken".into()),
 						rialto_headers_to_millau_signer_password: None,
 						rialto_headers_to_millau_signer_file: None,
 						rialto_headers_to_millau_signer_password_file: None,
+						rialto_headers_to_millau_signer_keystore: None,
 						rialto_headers_to_millau_transactions_mortality: None,
+						rialto_headers_to_millau_max_spending_per_hour: None,
+						rialto_headers_to_millau_max_spending_per_day: None,
+						rialto_headers_to_millau_min_balance: None,
+						rialto_headers_to_millau_balance_threshold: None,
+						rialto_headers_to_millau_proxy_pallet_index: None,
+						rialto_headers_to_millau_proxied_address: None,
+						rialto_headers_to_millau_multisig_pallet_index: None,
+						rialto_headers_to_millau_multisig_other_signatories: vec![],
 					},
 					right_parachains_to_left_sign_override: RialtoParachainsToMillauSigningParams {
 						rialto_parachains_to_millau_signer: None,
 						rialto_parachains_to_millau_signer_password: None,
 						rialto_parachains_to_millau_signer_file: None,
 						rialto_parachains_to_millau_signer_password_file: None,
+						rialto_parachains_to_millau_signer_keystore: None,
 						rialto_parachains_to_millau_transactions_mortality: None,
+						rialto_parachains_to_millau_max_spending_per_hour: None,
+						rialto_parachains_to_millau_max_spending_per_day: None,
+						rialto_parachains_to_millau_min_balance: None,
+						rialto_parachains_to_millau_balance_threshold: None,
+						rialto_parachains_to_millau_proxy_pallet_index: None,
+						rialto_parachains_to_millau_proxied_address: None,
+						rialto_parachains_to_millau_multisig_pallet_index: None,
+						rialto_parachains_to_millau_multisig_other_signatories: vec![],
 					},
 					right_relay: RialtoConnectionParams {
 						rialto_host: "rialto-node-alice".into(),
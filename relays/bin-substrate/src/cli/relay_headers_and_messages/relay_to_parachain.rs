@@ -124,12 +124,18 @@ macro_rules! declare_relay_to_parachain_bridge_schema {
 								client: self.left.into_client::<Left>().await?,
 								sign: self.left_sign.to_keypair::<Left>()?,
 								transactions_mortality: self.left_sign.transactions_mortality()?,
+								spending_budget: self.left_sign.spending_budget::<Left>()?,
+								call_wrapper: self.left_sign.call_wrapper()?,
+								balance_threshold: self.left_sign.balance_threshold(),
 								accounts: vec![],
 							},
 							BridgeEndCommonParams {
 								client: self.right.into_client::<Right>().await?,
 								sign: self.right_sign.to_keypair::<Right>()?,
 								transactions_mortality: self.right_sign.transactions_mortality()?,
+								spending_budget: self.right_sign.spending_budget::<Right>()?,
+								call_wrapper: self.right_sign.call_wrapper()?,
+								balance_threshold: self.right_sign.balance_threshold(),
 								accounts: vec![],
 							},
 						)?,
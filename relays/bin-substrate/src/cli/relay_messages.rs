@@ -15,7 +15,10 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
+use bp_messages::MessageNonce;
+use bp_runtime::HeaderIdProvider;
 use sp_core::Pair;
+use sp_runtime::traits::UniqueSaturatedFrom;
 use structopt::StructOpt;
 use strum::VariantNames;
 
@@ -37,10 +40,21 @@ use crate::bridges::{
 		bridge_hub_wococo_messages_to_bridge_hub_rococo::BridgeHubWococoToBridgeHubRococoMessagesCliBridge,
 	},
 };
-use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, BalanceOf, ChainWithTransactions};
-use substrate_relay_helper::{messages::MessagesRelayParams, TransactionParams};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, ChainWithTransactions, Client,
+};
+use sp_runtime::Perbill;
+use std::sync::Arc;
+use substrate_relay_helper::{
+	messages::MessagesRelayParams,
+	on_demand::{headers::OnDemandHeadersRelay, OnDemandRelay},
+	TransactionParams,
+};
 
-use crate::cli::{bridge::*, chain_schema::*, CliChain, HexLaneId, PrometheusParams};
+use crate::cli::{
+	bridge::*, chain_schema::*, BlockHashOrNumber, CliChain, DefaultClient, HexLaneId,
+	PrometheusParams,
+};
 
 /// Start messages relayer process.
 #[derive(StructOpt)]
@@ -51,6 +65,16 @@ pub struct RelayMessages {
 	/// Hex-encoded lane id that should be served by the relay.
 	#[structopt(long)]
 	lane: HexLaneId,
+	/// Hash or number of the source chain header. If specified, messages that have been sent
+	/// before this header are delivered as soon as this header (or its descendant) is known to
+	/// the target chain. Use it to deliver the backlog of messages that has accumulated while
+	/// the relay has not been running. The source node must be an archive node.
+	#[structopt(long)]
+	since_block: Option<BlockHashOrNumber>,
+	/// If specified, message delivery is paused while recent target chain blocks are, on
+	/// average, filled at least by this percent. By default, delivery is never paused.
+	#[structopt(long)]
+	congested_target_block_fullness_percent: Option<u32>,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
@@ -63,6 +87,40 @@ pub struct RelayMessages {
 	prometheus_params: PrometheusParams,
 }
 
+/// Deliver given range of messages once and exit.
+#[derive(StructOpt)]
+pub struct RelayMessagesRange {
+	/// A bridge instance to relay messages for.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	/// Number of the source chain header that will be used to prepare messages proof. The header
+	/// must be the best source header, known to the target chain. If the bridge supports
+	/// on-demand headers relay, it may also be a header that is not yet known to the target
+	/// chain. Then the header proof is delivered in the same transaction.
+	#[structopt(long)]
+	at_source_block: u128,
+	/// Hex-encoded id of the lane, where messages have been sent.
+	#[structopt(long)]
+	lane: HexLaneId,
+	/// Nonce (inclusive) of the first message to deliver.
+	#[structopt(long)]
+	messages_start: MessageNonce,
+	/// Nonce (inclusive) of the last message to deliver.
+	#[structopt(long)]
+	messages_end: MessageNonce,
+	/// Whether the outbound lane state proof should be included into transaction.
+	#[structopt(long)]
+	outbound_state_proof_required: bool,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	source_sign: SourceSigningParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+}
+
 #[async_trait]
 trait MessagesRelayer: MessagesCliBridge
 where
@@ -78,31 +136,158 @@ where
 		let target_client = data.target.into_client::<Self::Target>().await?;
 		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
 		let target_transactions_mortality = data.target_sign.transactions_mortality()?;
+		let since_source_block = match data.since_block {
+			Some(since_block) => Some(since_block.hash(&source_client).await?),
+			None => None,
+		};
 
 		substrate_relay_helper::messages::run::<Self::MessagesLane, _, _>(MessagesRelayParams {
 			source_client,
 			source_transaction_params: TransactionParams {
 				signer: source_sign,
 				mortality: source_transactions_mortality,
+				spending_budget: data.source_sign.spending_budget::<Self::Source>()?,
+				call_wrapper: data.source_sign.call_wrapper()?,
 			},
 			target_client,
 			target_transaction_params: TransactionParams {
 				signer: target_sign,
 				mortality: target_transactions_mortality,
+				spending_budget: data.target_sign.spending_budget::<Self::Target>()?,
+				call_wrapper: data.target_sign.call_wrapper()?,
 			},
 			source_to_target_headers_relay: None,
 			target_to_source_headers_relay: None,
 			lane_id: data.lane.into(),
+			since_source_block,
+			congested_target_block_fullness: data
+				.congested_target_block_fullness_percent
+				.map(Perbill::from_percent),
 			metrics_params: data.prometheus_params.into_metrics_params()?,
 		})
 		.await
 		.map_err(|e| anyhow::format_err!("{}", e))
 	}
+
+	/// Returns on-demand headers relay that is able to prove source headers to the target chain.
+	///
+	/// By default, the bridge is not able to bundle source header proof with messages.
+	fn source_to_target_headers_relay(
+		_source_client: DefaultClient<Self::Source>,
+		_target_client: DefaultClient<Self::Target>,
+		_target_transaction_params: TransactionParams<AccountKeyPairOf<Self::Target>>,
+	) -> Option<Arc<dyn OnDemandRelay<Self::Source, Self::Target>>> {
+		None
+	}
+
+	async fn relay_messages_range(data: RelayMessagesRange) -> anyhow::Result<()> {
+		if data.messages_start > data.messages_end {
+			return Err(anyhow::format_err!(
+				"Invalid messages range: {} > {}",
+				data.messages_start,
+				data.messages_end,
+			))
+		}
+
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		let source_sign = data.source_sign.to_keypair::<Self::Source>()?;
+		let source_transactions_mortality = data.source_sign.transactions_mortality()?;
+		let target_client = data.target.into_client::<Self::Target>().await?;
+		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
+		let target_transactions_mortality = data.target_sign.transactions_mortality()?;
+
+		let at_source_block = source_client
+			.header_by_number(BlockNumberOf::<Self::Source>::unique_saturated_from(
+				data.at_source_block,
+			))
+			.await
+			.map_err(|e| {
+				anyhow::format_err!(
+					"Failed to read {} header #{}: {}",
+					Self::Source::NAME,
+					data.at_source_block,
+					e,
+				)
+			})?
+			.id();
+
+		let target_transaction_params = TransactionParams {
+			signer: target_sign,
+			mortality: target_transactions_mortality,
+			spending_budget: data.target_sign.spending_budget::<Self::Target>()?,
+			call_wrapper: data.target_sign.call_wrapper()?,
+		};
+		let source_to_target_headers_relay = Self::source_to_target_headers_relay(
+			source_client.clone(),
+			target_client.clone(),
+			target_transaction_params.clone(),
+		);
+
+		substrate_relay_helper::messages::relay_messages_range::<Self::MessagesLane>(
+			source_client,
+			target_client,
+			TransactionParams {
+				signer: source_sign,
+				mortality: source_transactions_mortality,
+				spending_budget: data.source_sign.spending_budget::<Self::Source>()?,
+				call_wrapper: data.source_sign.call_wrapper()?,
+			},
+			target_transaction_params,
+			source_to_target_headers_relay,
+			at_source_block,
+			data.lane.into(),
+			data.messages_start..=data.messages_end,
+			data.outbound_state_proof_required,
+		)
+		.await
+	}
 }
 
-impl MessagesRelayer for MillauToRialtoCliBridge {}
-impl MessagesRelayer for RialtoToMillauCliBridge {}
-impl MessagesRelayer for MillauToRialtoParachainCliBridge {}
+/// Returns on-demand relay of relay chain headers for given bridge.
+fn relay_headers_relay<Bridge: RelayToRelayHeadersCliBridge>(
+	source_client: DefaultClient<Bridge::Source>,
+	target_client: DefaultClient<Bridge::Target>,
+	target_transaction_params: TransactionParams<AccountKeyPairOf<Bridge::Target>>,
+) -> Option<Arc<dyn OnDemandRelay<Bridge::Source, Bridge::Target>>>
+where
+	AccountIdOf<Bridge::Target>: From<<AccountKeyPairOf<Bridge::Target> as Pair>::Public>,
+{
+	Some(Arc::new(OnDemandHeadersRelay::<Bridge::Finality, _, _>::new(
+		source_client,
+		target_client,
+		target_transaction_params,
+		false,
+		None,
+	)))
+}
+
+impl MessagesRelayer for MillauToRialtoCliBridge {
+	fn source_to_target_headers_relay(
+		source_client: DefaultClient<Self::Source>,
+		target_client: DefaultClient<Self::Target>,
+		target_transaction_params: TransactionParams<AccountKeyPairOf<Self::Target>>,
+	) -> Option<Arc<dyn OnDemandRelay<Self::Source, Self::Target>>> {
+		relay_headers_relay::<Self>(source_client, target_client, target_transaction_params)
+	}
+}
+impl MessagesRelayer for RialtoToMillauCliBridge {
+	fn source_to_target_headers_relay(
+		source_client: DefaultClient<Self::Source>,
+		target_client: DefaultClient<Self::Target>,
+		target_transaction_params: TransactionParams<AccountKeyPairOf<Self::Target>>,
+	) -> Option<Arc<dyn OnDemandRelay<Self::Source, Self::Target>>> {
+		relay_headers_relay::<Self>(source_client, target_client, target_transaction_params)
+	}
+}
+impl MessagesRelayer for MillauToRialtoParachainCliBridge {
+	fn source_to_target_headers_relay(
+		source_client: DefaultClient<Self::Source>,
+		target_client: DefaultClient<Self::Target>,
+		target_transaction_params: TransactionParams<AccountKeyPairOf<Self::Target>>,
+	) -> Option<Arc<dyn OnDemandRelay<Self::Source, Self::Target>>> {
+		relay_headers_relay::<Self>(source_client, target_client, target_transaction_params)
+	}
+}
 impl MessagesRelayer for RialtoParachainToMillauCliBridge {}
 impl MessagesRelayer for BridgeHubRococoToBridgeHubWococoMessagesCliBridge {}
 impl MessagesRelayer for BridgeHubWococoToBridgeHubRococoMessagesCliBridge {}
@@ -131,3 +316,26 @@ impl RelayMessages {
 		.await
 	}
 }
+
+impl RelayMessagesRange {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			FullBridge::MillauToRialto => MillauToRialtoCliBridge::relay_messages_range(self),
+			FullBridge::RialtoToMillau => RialtoToMillauCliBridge::relay_messages_range(self),
+			FullBridge::MillauToRialtoParachain =>
+				MillauToRialtoParachainCliBridge::relay_messages_range(self),
+			FullBridge::RialtoParachainToMillau =>
+				RialtoParachainToMillauCliBridge::relay_messages_range(self),
+			FullBridge::BridgeHubRococoToBridgeHubWococo =>
+				BridgeHubRococoToBridgeHubWococoMessagesCliBridge::relay_messages_range(self),
+			FullBridge::BridgeHubWococoToBridgeHubRococo =>
+				BridgeHubWococoToBridgeHubRococoMessagesCliBridge::relay_messages_range(self),
+			FullBridge::BridgeHubKusamaToBridgeHubPolkadot =>
+				BridgeHubKusamaToBridgeHubPolkadotMessagesCliBridge::relay_messages_range(self),
+			FullBridge::BridgeHubPolkadotToBridgeHubKusama =>
+				BridgeHubPolkadotToBridgeHubKusamaMessagesCliBridge::relay_messages_range(self),
+		}
+		.await
+	}
+}
@@ -30,7 +30,7 @@ use async_std::sync::Mutex;
 use async_trait::async_trait;
 use parachains_relay::parachains_loop::{AvailableHeader, SourceClient, TargetClient};
 use relay_substrate_client::Parachain;
-use relay_utils::metrics::{GlobalMetrics, StandaloneMetric};
+use relay_utils::metrics::{GlobalMetrics, Metric, StandaloneMetric};
 use std::sync::Arc;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
@@ -53,6 +53,16 @@ pub struct RelayParachains {
 	bridge: RelayParachainsBridge,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
+	/// Connect to the second source relay chain node at given host. If specified, parachain heads
+	/// are only submitted if both source nodes agree on them.
+	#[structopt(long)]
+	source_verification_host: Option<String>,
+	/// Connect to the second source relay chain node websocket server at given port.
+	#[structopt(long, default_value = "9944")]
+	source_verification_port: u16,
+	/// Use secure websocket connection to the second source relay chain node.
+	#[structopt(long)]
+	source_verification_secure: bool,
 	#[structopt(flatten)]
 	target: TargetConnectionParams,
 	#[structopt(flatten)]
@@ -83,25 +93,46 @@ where
 	<Self as CliBridgeBase>::Source: Parachain,
 {
 	async fn relay_parachains(data: RelayParachains) -> anyhow::Result<()> {
+		let source_verification_client = match data.source_verification_host {
+			Some(source_host) => Some(
+				SourceConnectionParams {
+					source_host,
+					source_port: data.source_verification_port,
+					source_secure: data.source_verification_secure,
+					..data.source.clone()
+				}
+				.into_client::<Self::SourceRelay>()
+				.await?,
+			),
+			None => None,
+		};
 		let source_client = data.source.into_client::<Self::SourceRelay>().await?;
-		let source_client = ParachainsSource::<Self::ParachainFinality, _>::new(
+		let mut source_client = ParachainsSource::<Self::ParachainFinality, _>::new(
 			source_client,
 			Arc::new(Mutex::new(AvailableHeader::Missing)),
 		);
+		if let Some(source_verification_client) = source_verification_client {
+			source_client = source_client.with_verification_client(source_verification_client);
+		}
 
 		let target_transaction_params = TransactionParams {
 			signer: data.target_sign.to_keypair::<Self::Target>()?,
 			mortality: data.target_sign.target_transactions_mortality,
+			spending_budget: data.target_sign.spending_budget::<Self::Target>()?,
+			call_wrapper: data.target_sign.call_wrapper()?,
 		};
 		let target_client = data.target.into_client::<Self::Target>().await?;
-		let target_client = ParachainsTarget::<Self::ParachainFinality, _>::new(
-			target_client.clone(),
-			target_transaction_params,
-		);
 
 		let metrics_params: relay_utils::metrics::MetricsParams =
 			data.prometheus_params.into_metrics_params()?;
 		GlobalMetrics::new()?.register_and_spawn(&metrics_params.registry)?;
+		target_transaction_params
+			.start_spending_budget(target_client.clone(), &metrics_params.registry)?;
+
+		let target_client = ParachainsTarget::<Self::ParachainFinality, _>::new(
+			target_client.clone(),
+			target_transaction_params,
+		);
 
 		parachains_relay::parachains_loop::run(
 			source_client,
@@ -0,0 +1,77 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export and import of the relay state database, opened with the `--state-db` option.
+//!
+//! The database is locked by the running relay, so the relay must be stopped first.
+
+use crate::cli::LOG_TARGET;
+
+use relay_utils::state_store::{self, StateStore};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Export the relay state database to the JSON file.
+#[derive(StructOpt)]
+pub struct ExportState {
+	/// Path to the JSON file to write the relay state to. The state is printed to stdout if the
+	/// path is not specified.
+	#[structopt(long)]
+	output: Option<PathBuf>,
+}
+
+impl ExportState {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let states = state_store::export(opened_state_store()?)?;
+		let states = serde_json::to_string_pretty(&states)?;
+		match self.output {
+			Some(output) => std::fs::write(&output, states).map_err(|e| {
+				anyhow::format_err!("Failed to write relay state to {:?}: {}", output, e)
+			})?,
+			None => println!("{states}"),
+		}
+		Ok(())
+	}
+}
+
+/// Import the relay state from the JSON file to the relay state database.
+#[derive(StructOpt)]
+pub struct ImportState {
+	/// Path to the JSON file, produced by the `export-state` command.
+	#[structopt(long)]
+	input: PathBuf,
+}
+
+impl ImportState {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let states = std::fs::read(&self.input).map_err(|e| {
+			anyhow::format_err!("Failed to read relay state from {:?}: {}", self.input, e)
+		})?;
+		let states = serde_json::from_slice(&states)?;
+		let imported = state_store::import(opened_state_store()?, states)?;
+		log::info!(target: LOG_TARGET, "Imported {} relay states from {:?}", imported, self.input);
+		Ok(())
+	}
+}
+
+/// Returns relay state database, opened with the `--state-db` option.
+fn opened_state_store() -> anyhow::Result<&'static dyn StateStore> {
+	state_store::state_store().ok_or_else(|| {
+		anyhow::format_err!("The relay state database must be specified with the --state-db option")
+	})
+}
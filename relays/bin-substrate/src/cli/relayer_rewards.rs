@@ -0,0 +1,349 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::{
+	chain_schema::*,
+	claim_rewards::{pending_rewards, RewardsClaimer},
+};
+
+use bp_runtime::storage_value_final_key;
+use codec::Decode;
+use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, BalanceOf, Chain, Client, HashOf};
+use relay_utils::UniqueSaturatedInto;
+use sp_core::{storage::StorageKey, Pair};
+use sp_runtime::AccountId32;
+use std::collections::BTreeMap;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Print unclaimed and paid rewards of the relayer.
+#[derive(StructOpt)]
+pub struct RelayerRewards {
+	/// A chain where rewards are registered.
+	#[structopt(possible_values = RelayerRewardsChain::VARIANTS, case_insensitive = true)]
+	chain: RelayerRewardsChain,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	/// SS58 or hex-encoded relayer account.
+	#[structopt(long)]
+	relayer: AccountId32,
+	/// Number of the latest finalized blocks, which events are scanned to compute totals of
+	/// paid rewards. Events of pruned blocks are not available, so it should not exceed the
+	/// state pruning depth of the node.
+	#[structopt(long, default_value = "256")]
+	history_blocks: u32,
+}
+
+/// Chain, where rewards are registered.
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum RelayerRewardsChain {
+	Millau,
+	Rialto,
+	RialtoParachain,
+	BridgeHubRococo,
+	BridgeHubWococo,
+}
+
+/// Reward, paid to the relayer.
+type PaidReward<C> = (AccountIdOf<C>, <C as RewardsClaimer>::RewardsAccountParams, BalanceOf<C>);
+
+/// Chain, where relayers pallet is deployed.
+trait RewardsInspector: RewardsClaimer
+where
+	AccountIdOf<Self>: From<<AccountKeyPairOf<Self> as Pair>::Public>,
+{
+	/// Decode `RewardPaid` events of the relayers pallet from encoded `System::Events` value.
+	fn decode_paid_rewards(events: &[u8]) -> Result<Vec<PaidReward<Self>>, codec::Error>;
+}
+
+impl RewardsInspector for relay_millau_client::Millau {
+	fn decode_paid_rewards(events: &[u8]) -> Result<Vec<PaidReward<Self>>, codec::Error> {
+		type EventRecord = frame_system::EventRecord<millau_runtime::RuntimeEvent, bp_millau::Hash>;
+
+		Ok(Vec::<EventRecord>::decode(&mut &events[..])?
+			.into_iter()
+			.filter_map(|record| match record.event {
+				millau_runtime::RuntimeEvent::BridgeRelayers(
+					pallet_bridge_relayers::Event::RewardPaid {
+						relayer,
+						rewards_account_params,
+						reward,
+					},
+				) => Some((relayer, rewards_account_params, reward)),
+				_ => None,
+			})
+			.collect())
+	}
+}
+
+impl RewardsInspector for relay_rialto_client::Rialto {
+	fn decode_paid_rewards(events: &[u8]) -> Result<Vec<PaidReward<Self>>, codec::Error> {
+		type EventRecord = frame_system::EventRecord<rialto_runtime::RuntimeEvent, bp_rialto::Hash>;
+
+		Ok(Vec::<EventRecord>::decode(&mut &events[..])?
+			.into_iter()
+			.filter_map(|record| match record.event {
+				rialto_runtime::RuntimeEvent::BridgeRelayers(
+					pallet_bridge_relayers::Event::RewardPaid {
+						relayer,
+						rewards_account_params,
+						reward,
+					},
+				) => Some((relayer, rewards_account_params, reward)),
+				_ => None,
+			})
+			.collect())
+	}
+}
+
+impl RewardsInspector for relay_rialto_parachain_client::RialtoParachain {
+	fn decode_paid_rewards(events: &[u8]) -> Result<Vec<PaidReward<Self>>, codec::Error> {
+		use relay_rialto_parachain_client::runtime_types::{
+			frame_system::EventRecord, pallet_bridge_relayers::pallet::Event,
+			rialto_parachain_runtime::RuntimeEvent,
+		};
+
+		Ok(Vec::<EventRecord<RuntimeEvent, sp_core::H256>>::decode(&mut &events[..])?
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::BridgeRelayers(Event::RewardPaid {
+					relayer,
+					rewards_account_params,
+					reward,
+				}) => Some((relayer, rewards_account_params, reward)),
+				_ => None,
+			})
+			.collect())
+	}
+}
+
+impl RewardsInspector for relay_bridge_hub_rococo_client::BridgeHubRococo {
+	fn decode_paid_rewards(events: &[u8]) -> Result<Vec<PaidReward<Self>>, codec::Error> {
+		use relay_bridge_hub_rococo_client::runtime_types::{
+			bridge_hub_rococo_runtime::RuntimeEvent, frame_system::EventRecord,
+			pallet_bridge_relayers::pallet::Event,
+		};
+
+		Ok(Vec::<EventRecord<RuntimeEvent, sp_core::H256>>::decode(&mut &events[..])?
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::BridgeRelayers(Event::RewardPaid {
+					relayer,
+					rewards_account_params,
+					reward,
+				}) => Some((relayer, rewards_account_params, reward)),
+				_ => None,
+			})
+			.collect())
+	}
+}
+
+impl RewardsInspector for relay_bridge_hub_wococo_client::BridgeHubWococo {
+	fn decode_paid_rewards(events: &[u8]) -> Result<Vec<PaidReward<Self>>, codec::Error> {
+		use relay_bridge_hub_wococo_client::runtime_types::{
+			bridge_hub_rococo_runtime::RuntimeEvent, frame_system::EventRecord,
+			pallet_bridge_relayers::pallet::Event,
+		};
+
+		Ok(Vec::<EventRecord<RuntimeEvent, sp_core::H256>>::decode(&mut &events[..])?
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::BridgeRelayers(Event::RewardPaid {
+					relayer,
+					rewards_account_params,
+					reward,
+				}) => Some((relayer, rewards_account_params, reward)),
+				_ => None,
+			})
+			.collect())
+	}
+}
+
+macro_rules! select_chain {
+	($chain: expr, $generic: tt) => {
+		match $chain {
+			RelayerRewardsChain::Millau => {
+				type Target = relay_millau_client::Millau;
+
+				$generic
+			},
+			RelayerRewardsChain::Rialto => {
+				type Target = relay_rialto_client::Rialto;
+
+				$generic
+			},
+			RelayerRewardsChain::RialtoParachain => {
+				type Target = relay_rialto_parachain_client::RialtoParachain;
+
+				$generic
+			},
+			RelayerRewardsChain::BridgeHubRococo => {
+				type Target = relay_bridge_hub_rococo_client::BridgeHubRococo;
+
+				$generic
+			},
+			RelayerRewardsChain::BridgeHubWococo => {
+				type Target = relay_bridge_hub_wococo_client::BridgeHubWococo;
+
+				$generic
+			},
+		}
+	};
+}
+
+impl RelayerRewards {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_chain!(self.chain, {
+			let client = self.target.into_client::<Target>().await?;
+			print_relayer_rewards::<Target>(client, self.relayer.into(), self.history_blocks).await
+		})
+	}
+}
+
+/// Print unclaimed rewards of the relayer and rewards, paid during the last `history_blocks`
+/// finalized blocks.
+async fn print_relayer_rewards<C: RewardsInspector>(
+	client: impl Client<C>,
+	relayer: AccountIdOf<C>,
+	history_blocks: u32,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	BalanceOf<C>: Into<u128>,
+{
+	let best_finalized_number: u32 =
+		client.best_finalized_header_number().await?.unique_saturated_into();
+	let at = client.header_hash_by_number(best_finalized_number.into()).await?;
+
+	println!("Unclaimed rewards of {:?} at {} block #{}:", relayer, C::NAME, best_finalized_number);
+	let unclaimed_rewards = pending_rewards(&client, at, &relayer).await?;
+	if unclaimed_rewards.is_empty() {
+		println!("  none");
+	}
+	for (rewards_account_params, reward) in unclaimed_rewards {
+		println!("  {:?}: {}", rewards_account_params, reward.into());
+	}
+
+	let first_block = best_finalized_number.saturating_sub(history_blocks.saturating_sub(1));
+	let mut paid_rewards = BTreeMap::new();
+	for number in first_block..=best_finalized_number {
+		let at = client.header_hash_by_number(number.into()).await?;
+		for (rewards_account_params, reward) in read_paid_rewards(&client, at, &relayer).await? {
+			*paid_rewards.entry(format!("{rewards_account_params:?}")).or_insert(0u128) +=
+				reward.into();
+		}
+	}
+
+	println!();
+	println!(
+		"Rewards paid to {:?} at {} blocks #{}..=#{}:",
+		relayer,
+		C::NAME,
+		first_block,
+		best_finalized_number,
+	);
+	if paid_rewards.is_empty() {
+		println!("  none");
+	}
+	for (rewards_account_params, total_reward) in paid_rewards {
+		println!("  {rewards_account_params}: {total_reward}");
+	}
+
+	Ok(())
+}
+
+/// Read rewards, paid to the relayer at given block.
+async fn read_paid_rewards<C: RewardsInspector>(
+	client: &impl Client<C>,
+	at: HashOf<C>,
+	relayer: &AccountIdOf<C>,
+) -> anyhow::Result<Vec<(C::RewardsAccountParams, BalanceOf<C>)>>
+where
+	AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+{
+	let events_key = StorageKey(storage_value_final_key(b"System", b"Events").to_vec());
+	let events = match client.raw_storage_value(at, events_key).await? {
+		Some(events) => events,
+		None => return Ok(Vec::new()),
+	};
+	let paid_rewards = C::decode_paid_rewards(&events.0).map_err(|e| {
+		anyhow::format_err!("Failed to decode {} events at {:?}: {:?}", C::NAME, at, e)
+	})?;
+
+	Ok(paid_rewards
+		.into_iter()
+		.filter(|(paid_relayer, _, _)| paid_relayer == relayer)
+		.map(|(_, rewards_account_params, reward)| (rewards_account_params, reward))
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+
+	#[test]
+	fn paid_rewards_are_decoded_from_events() {
+		let relayer = AccountId32::new([1u8; 32]);
+		let rewards_account_params = bp_relayers::RewardsAccountParams::new(
+			Default::default(),
+			*b"test",
+			bp_relayers::RewardsAccountOwner::ThisChain,
+		);
+		let events = vec![
+			frame_system::EventRecord {
+				phase: frame_system::Phase::Initialization,
+				event: millau_runtime::RuntimeEvent::BridgeRelayers(
+					pallet_bridge_relayers::Event::RewardPaid {
+						relayer: relayer.clone(),
+						rewards_account_params,
+						reward: 100,
+					},
+				),
+				topics: vec![],
+			},
+			frame_system::EventRecord {
+				phase: frame_system::Phase::Initialization,
+				event: millau_runtime::RuntimeEvent::BridgeRelayers(
+					pallet_bridge_relayers::Event::Deregistered { relayer: relayer.clone() },
+				),
+				topics: vec![],
+			},
+		];
+
+		assert_eq!(
+			relay_millau_client::Millau::decode_paid_rewards(&events.encode()).unwrap(),
+			vec![(relayer, rewards_account_params, 100)],
+		);
+	}
+
+	#[test]
+	fn relayer_rewards_params_are_parsed() {
+		let relayer_rewards = RelayerRewards::from_iter(vec![
+			"relayer-rewards",
+			"millau",
+			"--relayer",
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+			"--history-blocks",
+			"10",
+		]);
+
+		assert!(matches!(relayer_rewards.chain, RelayerRewardsChain::Millau));
+		assert_eq!(relayer_rewards.history_blocks, 10);
+	}
+}
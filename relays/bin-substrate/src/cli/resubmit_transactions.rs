@@ -21,10 +21,10 @@ use codec::{Decode, Encode};
 use num_traits::{One, Zero};
 use relay_substrate_client::{
 	AccountKeyPairOf, BlockWithJustification, Chain, ChainWithTransactions, Client,
-	Error as SubstrateError, HeaderIdOf, HeaderOf, SignParam,
+	Error as SubstrateError, HeaderIdOf, HeaderOf, SignParam, TransactionEra, UnsignedTransaction,
 };
 use relay_utils::FailedClient;
-use sp_core::Bytes;
+use sp_core::{Bytes, Pair};
 use sp_runtime::{
 	traits::{Hash, Header as HeaderT},
 	transaction_validity::TransactionPriority,
@@ -53,6 +53,14 @@ pub struct ResubmitTransactions {
 	/// this step.
 	#[structopt(long)]
 	tip_step: Balance,
+	/// How stalled transaction is replaced.
+	#[structopt(
+		long,
+		default_value = "tip-bump",
+		possible_values = ReplacementStrategy::VARIANTS,
+		case_insensitive = true
+	)]
+	replacement: ReplacementStrategy,
 	/// Priority selection strategy.
 	#[structopt(subcommand)]
 	strategy: PrioritySelectionStrategy,
@@ -85,6 +93,49 @@ pub enum PrioritySelectionStrategy {
 	MakeItBetterThanQueuedTransaction,
 }
 
+/// Strategy to use when replacing stalled transaction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ReplacementStrategy {
+	/// Increase tip of the stalled transaction until its priority is better than the priority,
+	/// selected by the priority selection strategy.
+	TipBump,
+	/// Re-sign the stalled transaction with the mortal era, starting at the current best block.
+	/// The tip is increased by a single tip step, so that the transaction pool accepts the
+	/// replacement.
+	///
+	/// Helps when the transaction is stalled because its era is about to expire.
+	EraExtension,
+	/// Replace the stalled transaction with the no-op transfer with the same nonce and better
+	/// priority.
+	///
+	/// Helps when the transaction will never be included (e.g. because it fails to dispatch), but
+	/// it blocks all subsequent transactions of the signer.
+	NonceCancellation,
+	/// Remove the stalled transaction from the pool and submit the same call with the same nonce,
+	/// signed using the current runtime version and the best block.
+	///
+	/// Helps when the transaction has been signed using the obsolete runtime version. Requires
+	/// node to allow unsafe RPC methods.
+	DropAndRebuild,
+}
+
+/// Chain, which transactions may be resubmitted.
+trait ResubmitTransactionsChain: ChainWithTransactions {
+	/// Encode no-op call that is used to cancel transaction of given signer.
+	fn encode_cancellation_call(signer: &AccountKeyPairOf<Self>) -> Self::Call;
+}
+
+impl ResubmitTransactionsChain for relay_millau_client::Millau {
+	fn encode_cancellation_call(signer: &AccountKeyPairOf<Self>) -> Self::Call {
+		millau_runtime::BalancesCall::<millau_runtime::Runtime>::transfer_allow_death {
+			dest: signer.public().into(),
+			value: 0,
+		}
+		.into()
+	}
+}
+
 macro_rules! select_bridge {
 	($bridge: expr, $generic: tt) => {
 		match $bridge {
@@ -106,6 +157,8 @@ impl ResubmitTransactions {
 			let transaction_params = TransactionParams {
 				signer: self.target_sign.to_keypair::<Target>()?,
 				mortality: self.target_sign.target_transactions_mortality,
+				spending_budget: None,
+				call_wrapper: None,
 			};
 
 			relay_utils::relay_loop((), client)
@@ -115,6 +168,7 @@ impl ResubmitTransactions {
 						transaction_params.clone(),
 						Context {
 							strategy: self.strategy,
+							replacement: self.replacement,
 							best_header: HeaderOf::<Target>::new(
 								Default::default(),
 								Default::default(),
@@ -157,6 +211,8 @@ impl PrioritySelectionStrategy {
 struct Context<C: Chain> {
 	/// Priority selection strategy.
 	strategy: PrioritySelectionStrategy,
+	/// Stalled transaction replacement strategy.
+	replacement: ReplacementStrategy,
 	/// Best known block header.
 	best_header: C::Header,
 	/// Hash of the (potentially) stalled transaction.
@@ -201,7 +257,7 @@ impl<C: Chain> Context<C> {
 }
 
 /// Run resubmit transactions loop.
-async fn run_until_connection_lost<C: ChainWithTransactions>(
+async fn run_until_connection_lost<C: ResubmitTransactionsChain>(
 	client: impl Client<C>,
 	transaction_params: TransactionParams<AccountKeyPairOf<C>>,
 	mut context: Context<C>,
@@ -226,7 +282,7 @@ async fn run_until_connection_lost<C: ChainWithTransactions>(
 }
 
 /// Run single loop iteration.
-async fn run_loop_iteration<C: ChainWithTransactions>(
+async fn run_loop_iteration<C: ResubmitTransactionsChain>(
 	client: impl Client<C>,
 	transaction_params: TransactionParams<AccountKeyPairOf<C>>,
 	mut context: Context<C>,
@@ -259,31 +315,18 @@ async fn run_loop_iteration<C: ChainWithTransactions>(
 		return Ok(context)
 	}
 
-	// select priority for updated transaction
-	let target_priority = match context.strategy.select_target_priority(&client, &context).await? {
-		Some(target_priority) => target_priority,
-		None => {
-			log::trace!(target: "bridge", "Failed to select target priority");
-			return Ok(context)
-		},
-	};
-
-	// update transaction tip
-	let (is_updated, updated_transaction) = update_transaction_tip(
+	let updated_transaction = match replace_transaction(
 		&client,
 		&transaction_params,
-		context.best_header.id(),
+		&context,
+		original_transaction_hash,
 		original_transaction,
-		context.tip_step,
-		context.tip_limit,
-		target_priority,
 	)
-	.await?;
-
-	if !is_updated {
-		log::trace!(target: "bridge", "{} transaction tip can not be updated. Reached limit?", C::NAME);
-		return Ok(context)
-	}
+	.await?
+	{
+		Some(updated_transaction) => updated_transaction,
+		None => return Ok(context),
+	};
 
 	let updated_transaction = updated_transaction.encode();
 	let updated_transaction_hash = C::Hasher::hash(&updated_transaction);
@@ -385,24 +428,100 @@ fn select_transaction_from_queue<C: Chain>(
 	)
 }
 
+/// Prepare replacement of the stalled transaction. Returns `None` if transaction can't be
+/// replaced.
+async fn replace_transaction<C: ResubmitTransactionsChain>(
+	client: &impl Client<C>,
+	transaction_params: &TransactionParams<AccountKeyPairOf<C>>,
+	context: &Context<C>,
+	original_transaction_hash: C::Hash,
+	original_transaction: C::SignedTransaction,
+) -> Result<Option<C::SignedTransaction>, SubstrateError> {
+	let at_block = context.best_header.id();
+	let original_priority = client
+		.validate_transaction(at_block.hash(), original_transaction.clone())
+		.await??
+		.priority;
+	let stx = format!("{original_transaction:?}");
+	let original_unsigned_tx = C::parse_transaction(original_transaction).ok_or_else(|| {
+		SubstrateError::Custom(format!("Failed to parse {} transaction {stx}", C::NAME,))
+	})?;
+	let original_tip = original_unsigned_tx.tip;
+	let era = TransactionEra::new(at_block, transaction_params.mortality);
+
+	let (unsigned_tx, target_priority) = match context.replacement {
+		ReplacementStrategy::TipBump => {
+			// select priority for updated transaction
+			let target_priority =
+				match context.strategy.select_target_priority(client, context).await? {
+					Some(target_priority) => target_priority,
+					None => {
+						log::trace!(target: "bridge", "Failed to select target priority");
+						return Ok(None)
+					},
+				};
+			(original_unsigned_tx, target_priority)
+		},
+		ReplacementStrategy::EraExtension =>
+			(original_unsigned_tx, original_priority.saturating_add(1)),
+		ReplacementStrategy::NonceCancellation => (
+			UnsignedTransaction::new(
+				C::encode_cancellation_call(&transaction_params.signer).into(),
+				original_unsigned_tx.nonce,
+			)
+			.tip(original_tip),
+			original_priority.saturating_add(1),
+		),
+		ReplacementStrategy::DropAndRebuild => {
+			client.remove_extrinsics(vec![original_transaction_hash]).await?;
+			return Ok(Some(
+				sign_transaction(client, transaction_params, original_unsigned_tx.era(era)).await?,
+			))
+		},
+	};
+
+	let (unsigned_tx, priority) = increase_transaction_tip(
+		client,
+		transaction_params,
+		at_block,
+		unsigned_tx.era(era),
+		context.tip_step,
+		context.tip_limit,
+		target_priority,
+	)
+	.await?;
+	let is_updated = match context.replacement {
+		ReplacementStrategy::TipBump => unsigned_tx.tip != original_tip,
+		_ => priority > original_priority,
+	};
+	if !is_updated {
+		log::trace!(target: "bridge", "{} transaction can not be replaced. Reached tip limit?", C::NAME);
+		return Ok(None)
+	}
+
+	Ok(Some(sign_transaction(client, transaction_params, unsigned_tx).await?))
+}
+
 /// Try to find appropriate tip for transaction so that its priority is larger than given.
-async fn update_transaction_tip<C: ChainWithTransactions>(
+///
+/// Returns updated transaction and its priority.
+async fn increase_transaction_tip<C: ChainWithTransactions>(
 	client: &impl Client<C>,
 	transaction_params: &TransactionParams<AccountKeyPairOf<C>>,
 	at_block: HeaderIdOf<C>,
-	tx: C::SignedTransaction,
+	mut unsigned_tx: UnsignedTransaction<C>,
 	tip_step: C::Balance,
 	tip_limit: C::Balance,
 	target_priority: TransactionPriority,
-) -> Result<(bool, C::SignedTransaction), SubstrateError> {
-	let stx = format!("{tx:?}");
-	let mut current_priority = client.validate_transaction(at_block.1, tx.clone()).await??.priority;
-	let mut unsigned_tx = C::parse_transaction(tx).ok_or_else(|| {
-		SubstrateError::Custom(format!("Failed to parse {} transaction {stx}", C::NAME,))
-	})?;
+) -> Result<(UnsignedTransaction<C>, TransactionPriority), SubstrateError> {
 	let old_tip = unsigned_tx.tip;
-
-	let runtime_version = client.simple_runtime_version().await?;
+	let mut current_priority = client
+		.validate_transaction(
+			at_block.1,
+			sign_transaction(client, transaction_params, unsigned_tx.clone()).await?,
+		)
+		.await??
+		.priority;
 	while current_priority < target_priority {
 		let next_tip = unsigned_tx.tip + tip_step;
 		if next_tip > tip_limit {
@@ -422,15 +541,7 @@ async fn update_transaction_tip<C: ChainWithTransactions>(
 		current_priority = client
 			.validate_transaction(
 				at_block.1,
-				C::sign_transaction(
-					SignParam {
-						spec_version: runtime_version.spec_version,
-						transaction_version: runtime_version.transaction_version,
-						genesis_hash: client.genesis_hash(),
-						signer: transaction_params.signer.clone(),
-					},
-					unsigned_tx.clone(),
-				)?,
+				sign_transaction(client, transaction_params, unsigned_tx.clone()).await?,
 			)
 			.await??
 			.priority;
@@ -444,21 +555,25 @@ async fn update_transaction_tip<C: ChainWithTransactions>(
 		unsigned_tx.tip,
 	);
 
-	Ok((
-		old_tip != unsigned_tx.tip,
-		C::sign_transaction(
-			SignParam {
-				spec_version: runtime_version.spec_version,
-				transaction_version: runtime_version.transaction_version,
-				genesis_hash: client.genesis_hash(),
-				signer: transaction_params.signer.clone(),
-			},
-			unsigned_tx.era(relay_substrate_client::TransactionEra::new(
-				at_block,
-				transaction_params.mortality,
-			)),
-		)?,
-	))
+	Ok((unsigned_tx, current_priority))
+}
+
+/// Sign transaction using current runtime version.
+async fn sign_transaction<C: ChainWithTransactions>(
+	client: &impl Client<C>,
+	transaction_params: &TransactionParams<AccountKeyPairOf<C>>,
+	unsigned_tx: UnsignedTransaction<C>,
+) -> Result<C::SignedTransaction, SubstrateError> {
+	let runtime_version = client.simple_runtime_version().await?;
+	C::sign_transaction(
+		SignParam {
+			spec_version: runtime_version.spec_version,
+			transaction_version: runtime_version.transaction_version,
+			genesis_hash: client.genesis_hash(),
+			signer: transaction_params.signer.clone(),
+		},
+		unsigned_tx,
+	)
 }
 
 #[cfg(test)]
@@ -470,6 +585,7 @@ mod tests {
 	fn context() -> Context<Rialto> {
 		Context {
 			strategy: PrioritySelectionStrategy::MakeItBestTransaction,
+			replacement: ReplacementStrategy::TipBump,
 			best_header: HeaderOf::<Rialto>::new(
 				Default::default(),
 				Default::default(),
@@ -513,6 +629,37 @@ mod tests {
 		assert_eq!(context.stalled_for, 0);
 	}
 
+	#[test]
+	fn replacement_strategy_is_parsed() {
+		let resubmit = ResubmitTransactions::from_iter(vec![
+			"resubmit-transactions",
+			"millau",
+			"--target-signer",
+			"//Alice",
+			"--tip-limit",
+			"1000",
+			"--tip-step",
+			"100",
+			"--replacement",
+			"nonce-cancellation",
+			"make-it-best-transaction",
+		]);
+		assert_eq!(resubmit.replacement, ReplacementStrategy::NonceCancellation);
+
+		let resubmit = ResubmitTransactions::from_iter(vec![
+			"resubmit-transactions",
+			"millau",
+			"--target-signer",
+			"//Alice",
+			"--tip-limit",
+			"1000",
+			"--tip-step",
+			"100",
+			"make-it-best-transaction",
+		]);
+		assert_eq!(resubmit.replacement, ReplacementStrategy::TipBump);
+	}
+
 	#[test]
 	fn select_transaction_from_queue_works_with_empty_queue() {
 		assert_eq!(select_transaction_from_queue(vec![], &context()), None);
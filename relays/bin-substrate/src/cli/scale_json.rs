@@ -0,0 +1,291 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! SCALE encoding of JSON-described values, driven by the type information.
+//!
+//! The JSON format is close to the one used by polkadot-js:
+//!
+//! - structs are objects with field names as keys. Tuple structs with single field are represented
+//!   by the field value and other tuple structs are arrays;
+//!
+//! - enum variants are either strings (for variants without fields), or objects with single key -
+//!   the variant name. Variant names are matched case-insensitively;
+//!
+//! - numbers may be either JSON numbers or strings (for values that don't fit into `u64`);
+//!
+//! - byte sequences and arrays may be either arrays of numbers or `0x`-prefixed hex strings.
+
+use codec::{Compact, Encode};
+use scale_info::{
+	form::PortableForm, Field, MetaType, PortableRegistry, Registry, TypeDef, TypeDefPrimitive,
+	TypeInfo,
+};
+use serde_json::Value;
+
+/// Encode JSON-described value of type `T`.
+pub fn encode_json<T: TypeInfo + 'static>(value: &Value) -> anyhow::Result<Vec<u8>> {
+	let mut registry = Registry::new();
+	let type_id = registry.register_type(&MetaType::new::<T>()).id;
+	let registry: PortableRegistry = registry.into();
+
+	let mut encoded = Vec::new();
+	encode_value(&registry, type_id, value, &mut encoded)?;
+	Ok(encoded)
+}
+
+/// Encode JSON value of given type, appending it to `output`.
+fn encode_value(
+	registry: &PortableRegistry,
+	type_id: u32,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+	let ty = registry
+		.resolve(type_id)
+		.ok_or_else(|| anyhow::format_err!("Unknown type with id {}", type_id))?;
+	let type_name = || ty.path.segments.join("::");
+
+	match &ty.type_def {
+		TypeDef::Composite(composite) => encode_fields(registry, &composite.fields, value, output)
+			.map_err(|e| anyhow::format_err!("{}: {}", type_name(), e)),
+		TypeDef::Variant(variant) => {
+			let (name, fields_value) = match value {
+				Value::String(name) => (name.as_str(), &Value::Null),
+				Value::Null => ("None", &Value::Null),
+				Value::Object(object) if object.len() == 1 => object
+					.iter()
+					.next()
+					.map(|(name, value)| (name.as_str(), value))
+					.expect("object has single entry; qed"),
+				_ => return Err(anyhow::format_err!("{}: expected enum variant", type_name())),
+			};
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| variant.name.eq_ignore_ascii_case(name))
+				.ok_or_else(|| {
+					anyhow::format_err!("{}: unknown enum variant {}", type_name(), name)
+				})?;
+			output.push(variant.index);
+			encode_fields(registry, &variant.fields, fields_value, output)
+				.map_err(|e| anyhow::format_err!("{}::{}: {}", type_name(), variant.name, e))
+		},
+		TypeDef::Sequence(sequence) => {
+			let items = sequence_items(registry, sequence.type_param.id, value)?;
+			Compact(items.len() as u32).encode_to(output);
+			items
+				.iter()
+				.try_for_each(|item| encode_value(registry, sequence.type_param.id, item, output))
+		},
+		TypeDef::Array(array) => {
+			let items = sequence_items(registry, array.type_param.id, value)?;
+			if items.len() != array.len as usize {
+				return Err(anyhow::format_err!(
+					"Expected array of {} items, found {}",
+					array.len,
+					items.len(),
+				))
+			}
+			items
+				.iter()
+				.try_for_each(|item| encode_value(registry, array.type_param.id, item, output))
+		},
+		TypeDef::Tuple(tuple) => {
+			let items: Vec<_> = match (tuple.fields.len(), value) {
+				(0, _) => Vec::new(),
+				(1, value) => vec![value.clone()],
+				(_, Value::Array(items)) if items.len() == tuple.fields.len() => items.clone(),
+				_ => return Err(anyhow::format_err!("Expected tuple of {}", tuple.fields.len())),
+			};
+			tuple
+				.fields
+				.iter()
+				.zip(items.iter())
+				.try_for_each(|(field, item)| encode_value(registry, field.id, item, output))
+		},
+		TypeDef::Primitive(primitive) => encode_primitive(primitive, value, output),
+		TypeDef::Compact(compact) => {
+			// compact encoding only depends on the value, so we may use the widest type here
+			let inner_ty = registry.resolve(compact.type_param.id).ok_or_else(|| {
+				anyhow::format_err!("Unknown type with id {}", compact.type_param.id)
+			})?;
+			match &inner_ty.type_def {
+				TypeDef::Composite(composite) if composite.fields.len() == 1 =>
+					Compact(parse_number(value)?).encode_to(output),
+				TypeDef::Primitive(_) => Compact(parse_number(value)?).encode_to(output),
+				_ => return Err(anyhow::format_err!("Unsupported compact type: {}", type_name())),
+			}
+			Ok(())
+		},
+		TypeDef::BitSequence(_) => Err(anyhow::format_err!("Bit sequences are not supported")),
+	}
+}
+
+/// Encode struct or enum variant fields.
+fn encode_fields(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+	match (fields, value) {
+		([], _) => Ok(()),
+		([field], value) if field.name.is_none() =>
+			encode_value(registry, field.ty.id, value, output),
+		(fields, Value::Object(object)) if fields.iter().all(|field| field.name.is_some()) =>
+			fields.iter().try_for_each(|field| {
+				let name = field.name.as_ref().expect("checked above; qed");
+				let value = object
+					.get(name)
+					.ok_or_else(|| anyhow::format_err!("missing field {}", name))?;
+				encode_value(registry, field.ty.id, value, output)
+			}),
+		(fields, Value::Array(items)) if items.len() == fields.len() => fields
+			.iter()
+			.zip(items.iter())
+			.try_for_each(|(field, item)| encode_value(registry, field.ty.id, item, output)),
+		_ => Err(anyhow::format_err!("expected {} fields", fields.len())),
+	}
+}
+
+/// Returns items of JSON-described sequence or array. Byte sequences may also be described
+/// using hex strings.
+fn sequence_items(
+	registry: &PortableRegistry,
+	item_type_id: u32,
+	value: &Value,
+) -> anyhow::Result<Vec<Value>> {
+	match value {
+		Value::Array(items) => Ok(items.clone()),
+		Value::String(hex) if is_u8(registry, item_type_id) =>
+			hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
+				.map(|bytes| bytes.into_iter().map(Value::from).collect())
+				.map_err(|e| anyhow::format_err!("Invalid hex string {}: {:?}", hex, e)),
+		_ => Err(anyhow::format_err!("Expected array, found {}", value)),
+	}
+}
+
+/// Returns true if given type is `u8`.
+fn is_u8(registry: &PortableRegistry, type_id: u32) -> bool {
+	matches!(
+		registry.resolve(type_id).map(|ty| &ty.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	)
+}
+
+/// Parse unsigned number from JSON number or string.
+fn parse_number(value: &Value) -> anyhow::Result<u128> {
+	match value {
+		Value::Number(number) => number.as_u64().map(Into::into),
+		Value::String(number) => number.parse().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| anyhow::format_err!("Expected unsigned number, found {}", value))
+}
+
+/// Parse signed number from JSON number or string.
+fn parse_signed_number(value: &Value) -> anyhow::Result<i128> {
+	match value {
+		Value::Number(number) => number.as_i64().map(Into::into),
+		Value::String(number) => number.parse().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| anyhow::format_err!("Expected signed number, found {}", value))
+}
+
+/// Encode primitive value.
+fn encode_primitive(
+	primitive: &TypeDefPrimitive,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+	fn narrow<T: TryFrom<V>, V: Copy + std::fmt::Display>(value: V) -> anyhow::Result<T> {
+		T::try_from(value).map_err(|_| anyhow::format_err!("Number {} is out of range", value))
+	}
+
+	match primitive {
+		TypeDefPrimitive::Bool => value
+			.as_bool()
+			.ok_or_else(|| anyhow::format_err!("Expected boolean, found {}", value))?
+			.encode_to(output),
+		TypeDefPrimitive::Str => value
+			.as_str()
+			.ok_or_else(|| anyhow::format_err!("Expected string, found {}", value))?
+			.encode_to(output),
+		TypeDefPrimitive::U8 => narrow::<u8, _>(parse_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::U16 => narrow::<u16, _>(parse_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::U32 => narrow::<u32, _>(parse_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::U64 => narrow::<u64, _>(parse_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::U128 => parse_number(value)?.encode_to(output),
+		TypeDefPrimitive::I8 => narrow::<i8, _>(parse_signed_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::I16 => narrow::<i16, _>(parse_signed_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::I32 => narrow::<i32, _>(parse_signed_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::I64 => narrow::<i64, _>(parse_signed_number(value)?)?.encode_to(output),
+		TypeDefPrimitive::I128 => parse_signed_number(value)?.encode_to(output),
+		TypeDefPrimitive::Char | TypeDefPrimitive::U256 | TypeDefPrimitive::I256 =>
+			return Err(anyhow::format_err!("Unsupported primitive type: {:?}", primitive)),
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use xcm::latest::prelude::*;
+
+	#[test]
+	fn xcm_program_is_encoded() {
+		let json = serde_json::json!({
+			"V3": [
+				{ "ClearOrigin": null },
+				{
+					"ExpectPallet": {
+						"index": 0,
+						"name": "0x2a2a",
+						"module_name": [],
+						"crate_major": 0,
+						"min_crate_minor": "1",
+					}
+				},
+				{
+					"SetTopic": "0x0101010101010101010101010101010101010101010101010101010101010101"
+				},
+			]
+		});
+
+		let expected = xcm::VersionedXcm::<()>::V3(
+			vec![
+				ClearOrigin,
+				ExpectPallet {
+					index: 0,
+					name: vec![42, 42],
+					module_name: vec![],
+					crate_major: 0,
+					min_crate_minor: 1,
+				},
+				SetTopic([1u8; 32]),
+			]
+			.into(),
+		);
+		assert_eq!(encode_json::<xcm::VersionedXcm<()>>(&json).unwrap(), expected.encode());
+	}
+
+	#[test]
+	fn unknown_variant_is_rejected() {
+		let json = serde_json::json!({ "V3": ["Unknown"] });
+		assert!(encode_json::<xcm::VersionedXcm<()>>(&json).is_err());
+	}
+}
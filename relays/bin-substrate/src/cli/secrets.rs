@@ -0,0 +1,131 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Resolution of secret CLI parameters.
+//!
+//! Every secret parameter (e.g. `--source-signer`) may be supplied:
+//!
+//! 1) directly, using the CLI option;
+//!
+//! 2) as a path to file, using the `*-file` CLI option (e.g. `--source-signer-file`);
+//!
+//! 3) using the environment variable with the uppercased option name (e.g. `SOURCE_SIGNER`);
+//!
+//! 4) as a path to file, using the environment variable with the `_FILE` suffix (e.g.
+//! `SOURCE_SIGNER_FILE`).
+//!
+//! Sources are checked in the given order. Trailing newlines are stripped from the file
+//! contents, so that files, produced by `echo` or by Kubernetes secrets, may be used.
+
+use std::path::{Path, PathBuf};
+
+/// Returns name of the environment variable that may hold the secret parameter value.
+fn env_var_name(option_name: &str) -> String {
+	option_name.to_uppercase()
+}
+
+/// Returns name of the environment variable that may hold path to the file with secret
+/// parameter value.
+fn file_env_var_name(option_name: &str) -> String {
+	format!("{}_FILE", env_var_name(option_name))
+}
+
+/// Read secret value from file.
+fn read_secret_file(option_name: &str, path: &Path) -> anyhow::Result<String> {
+	std::fs::read_to_string(path)
+		.map(|secret| secret.trim_end_matches(['\r', '\n']).to_owned())
+		.map_err(|err| {
+			anyhow::format_err!("Failed to read {} from file {:?}: {}", option_name, path, err)
+		})
+}
+
+/// Returns true if secret parameter is supplied using any supported method.
+pub fn is_secret_defined(option_name: &str, is_defined_by_options: bool) -> bool {
+	is_defined_by_options ||
+		std::env::var_os(env_var_name(option_name)).is_some() ||
+		std::env::var_os(file_env_var_name(option_name)).is_some()
+}
+
+/// Resolve secret parameter value.
+///
+/// `option_name` is the name of the CLI option with underscores (e.g. `source_signer`).
+pub fn resolve_secret(
+	option_name: &str,
+	value: Option<&String>,
+	file: Option<&PathBuf>,
+) -> anyhow::Result<Option<String>> {
+	if let Some(value) = value {
+		return Ok(Some(value.clone()))
+	}
+	if let Some(file) = file {
+		return read_secret_file(option_name, file).map(Some)
+	}
+	if let Ok(value) = std::env::var(env_var_name(option_name)) {
+		return Ok(Some(value))
+	}
+	if let Some(file) = std::env::var_os(file_env_var_name(option_name)) {
+		return read_secret_file(option_name, Path::new(&file)).map(Some)
+	}
+
+	Ok(None)
+}
+
+/// Resolve path parameter value, which may also be supplied using environment variable.
+pub fn resolve_path(option_name: &str, value: Option<&PathBuf>) -> Option<PathBuf> {
+	value
+		.cloned()
+		.or_else(|| std::env::var_os(env_var_name(option_name)).map(Into::into))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn secret_is_resolved_in_order() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let option_file = temp_dir.path().join("option");
+		let env_file = temp_dir.path().join("env");
+		std::fs::write(&option_file, "from-option-file\n").unwrap();
+		std::fs::write(&env_file, "from-env-file\r\n").unwrap();
+
+		let option_name = "secrets_test_signer";
+		assert!(!is_secret_defined(option_name, false));
+		assert_eq!(resolve_secret(option_name, None, None).unwrap(), None);
+
+		std::env::set_var("SECRETS_TEST_SIGNER_FILE", &env_file);
+		assert!(is_secret_defined(option_name, false));
+		assert_eq!(resolve_secret(option_name, None, None).unwrap(), Some("from-env-file".into()));
+
+		std::env::set_var("SECRETS_TEST_SIGNER", "from-env");
+		assert_eq!(resolve_secret(option_name, None, None).unwrap(), Some("from-env".into()));
+		assert_eq!(
+			resolve_secret(option_name, None, Some(&option_file)).unwrap(),
+			Some("from-option-file".into()),
+		);
+		assert_eq!(
+			resolve_secret(option_name, Some(&"from-option".into()), Some(&option_file)).unwrap(),
+			Some("from-option".into()),
+		);
+	}
+
+	#[test]
+	fn missing_secret_file_is_an_error() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let missing_file = temp_dir.path().join("missing");
+		assert!(resolve_secret("secrets_test_missing", None, Some(&missing_file)).is_err());
+	}
+}
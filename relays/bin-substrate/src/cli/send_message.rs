@@ -29,7 +29,7 @@ use crate::{
 		bridge::{FullBridge, MessagesCliBridge},
 		chain_schema::*,
 		encode_message::{self, CliEncodeMessage, RawMessage},
-		CliChain,
+		scale_json, CliChain,
 	},
 };
 use async_trait::async_trait;
@@ -40,7 +40,10 @@ use relay_substrate_client::{
 };
 use sp_core::Pair;
 use sp_runtime::AccountId32;
-use std::fmt::Display;
+use std::{
+	fmt::Display,
+	path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 use strum::VariantNames;
 
@@ -54,9 +57,13 @@ pub struct SendMessage {
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
 	source_sign: SourceSigningParams,
+	/// Path to the file with XCM program that is sent over the bridge. The program may either be
+	/// SCALE-encoded `VersionedXcm` (binary or hex-encoded) or its JSON description.
+	#[structopt(long)]
+	payload_file: Option<PathBuf>,
 	/// Message type.
 	#[structopt(subcommand)]
-	message: crate::cli::encode_message::Message,
+	message: Option<crate::cli::encode_message::Message>,
 }
 
 #[async_trait]
@@ -70,7 +77,15 @@ where
 	AccountId32: From<<AccountKeyPairOf<Self::Source> as Pair>::Public>,
 {
 	async fn send_message(data: SendMessage) -> anyhow::Result<()> {
-		let payload = encode_message::encode_message::<Self::Source, Self::Target>(&data.message)?;
+		let payload = match (data.payload_file, data.message) {
+			(Some(payload_file), None) => read_payload_file(&payload_file)?,
+			(None, Some(message)) =>
+				encode_message::encode_message::<Self::Source, Self::Target>(&message)?,
+			_ =>
+				return Err(anyhow::format_err!(
+					"Either message type or --payload-file option must be specified"
+				)),
+		};
 
 		let source_client = data.source.into_client::<Self::Source>().await?;
 		let source_sign = data.source_sign.to_keypair::<Self::Source>()?;
@@ -127,6 +142,32 @@ impl SendMessage {
 	}
 }
 
+/// Read XCM program from file.
+///
+/// If file contents is a JSON document, it is treated as a description of `VersionedXcm`.
+/// If it starts with `0x`, it is treated as hex-encoded program. Otherwise, the file is
+/// expected to contain SCALE-encoded program.
+fn read_payload_file(path: &Path) -> anyhow::Result<RawMessage> {
+	let contents = std::fs::read(path)
+		.map_err(|e| anyhow::format_err!("Failed to read payload file {:?}: {}", path, e))?;
+	let text = std::str::from_utf8(&contents).map(str::trim);
+	let payload = match text {
+		Ok(text) if text.starts_with('{') => {
+			let json = serde_json::from_str(text).map_err(|e| {
+				anyhow::format_err!("Failed to parse payload file {:?}: {}", path, e)
+			})?;
+			scale_json::encode_json::<xcm::VersionedXcm<()>>(&json)?
+		},
+		Ok(text) if text.starts_with("0x") => hex::decode(&text[2..])
+			.map_err(|e| anyhow::format_err!("Invalid hex in payload file {:?}: {}", path, e))?,
+		_ => contents,
+	};
+
+	// ensure that the program is valid before sending it
+	decode_xcm::<()>(payload.clone())?;
+	Ok(payload)
+}
+
 /// Decode SCALE encoded raw XCM message.
 pub(crate) fn decode_xcm<Call>(message: RawMessage) -> anyhow::Result<xcm::VersionedXcm<Call>> {
 	Decode::decode(&mut &message[..])
@@ -158,7 +199,7 @@ mod tests {
 		assert_eq!(send_message.source_sign.source_signer, Some("//Alice".into()));
 		assert_eq!(
 			send_message.message,
-			crate::cli::encode_message::Message::Raw { data: HexBytes(vec![0xDE, 0xAD]) }
+			Some(crate::cli::encode_message::Message::Raw { data: HexBytes(vec![0xDE, 0xAD]) })
 		);
 	}
 
@@ -182,7 +223,45 @@ mod tests {
 		assert_eq!(send_message.source_sign.source_signer, Some("//Alice".into()));
 		assert_eq!(
 			send_message.message,
-			crate::cli::encode_message::Message::Sized { size: ExplicitOrMaximal::Maximal }
+			Some(crate::cli::encode_message::Message::Sized { size: ExplicitOrMaximal::Maximal })
 		);
 	}
+
+	#[test]
+	fn send_message_with_payload_file() {
+		let send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-signer",
+			"//Alice",
+			"--payload-file",
+			"xcm.json",
+		]);
+
+		assert_eq!(send_message.payload_file, Some("xcm.json".into()));
+		assert_eq!(send_message.message, None);
+	}
+
+	#[test]
+	fn payload_file_is_read() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let expected =
+			xcm::VersionedXcm::<()>::V3(vec![xcm::latest::Instruction::ClearOrigin].into())
+				.encode();
+
+		let json_file = temp_dir.path().join("xcm.json");
+		std::fs::write(&json_file, r#"{ "V3": ["ClearOrigin"] }"#).unwrap();
+		assert_eq!(read_payload_file(&json_file).unwrap(), expected);
+
+		let hex_file = temp_dir.path().join("xcm.hex");
+		std::fs::write(&hex_file, format!("0x{}\n", hex::encode(&expected))).unwrap();
+		assert_eq!(read_payload_file(&hex_file).unwrap(), expected);
+
+		let scale_file = temp_dir.path().join("xcm.scale");
+		std::fs::write(&scale_file, &expected).unwrap();
+		assert_eq!(read_payload_file(&scale_file).unwrap(), expected);
+
+		std::fs::write(&scale_file, [0xFF]).unwrap();
+		assert!(read_payload_file(&scale_file).is_err());
+	}
 }
@@ -0,0 +1,72 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clients that are shared by all relays, running in the same process.
+//!
+//! When several bridges are served by the same relay process, some of them may be connected
+//! to the same node. Client to such node is only created once and then it is shared by all
+//! bridges.
+
+use crate::cli::{CliChain, DefaultClient};
+
+use async_std::sync::Mutex;
+use relay_substrate_client::ConnectionParams;
+use std::{any::Any, collections::HashMap, sync::OnceLock};
+
+/// Shared client key: chain name and connection parameters.
+type SharedClientKey = (&'static str, String);
+
+/// All clients, created by this process.
+static SHARED_CLIENTS: OnceLock<Mutex<HashMap<SharedClientKey, Box<dyn Any + Send + Sync>>>> =
+	OnceLock::new();
+
+/// Returns client, connected to the node with given connection parameters. If there's already
+/// such client, it is reused.
+pub async fn shared_client<C: CliChain>(params: ConnectionParams) -> DefaultClient<C> {
+	let key = (
+		C::NAME,
+		format!(
+			"{}:{}:{}:{:?}",
+			params.host, params.port, params.secure, params.chain_runtime_version
+		),
+	);
+	let clients = SHARED_CLIENTS.get_or_init(Default::default);
+	if let Some(client) = find_client::<C>(&*clients.lock().await, &key) {
+		return client
+	}
+
+	// we don't want to hold the lock while connecting, because connection is retried until
+	// it succeeds and it'd block connecting to other nodes
+	let client = relay_substrate_client::new::<C>(params).await;
+
+	let mut clients = clients.lock().await;
+	if let Some(client) = find_client::<C>(&clients, &key) {
+		return client
+	}
+	clients.insert(key, Box::new(client.clone()));
+	client
+}
+
+/// Find existing client.
+fn find_client<C: CliChain>(
+	clients: &HashMap<SharedClientKey, Box<dyn Any + Send + Sync>>,
+	key: &SharedClientKey,
+) -> Option<DefaultClient<C>> {
+	clients
+		.get(key)
+		.and_then(|client| client.downcast_ref::<DefaultClient<C>>())
+		.cloned()
+}
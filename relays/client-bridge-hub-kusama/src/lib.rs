@@ -63,6 +63,8 @@ impl ChainWithTransactions for BridgeHubKusama {
 	type AccountKeyPair = sp_core::sr25519::Pair;
 	type SignedTransaction = runtime::UncheckedExtrinsic;
 
+	const EXISTENTIAL_DEPOSIT: u128 = bp_bridge_hub_kusama::EXISTENTIAL_DEPOSIT;
+
 	fn sign_transaction(
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self>,
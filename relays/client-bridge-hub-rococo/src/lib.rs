@@ -79,6 +79,8 @@ impl ChainWithTransactions for BridgeHubRococo {
 	type AccountKeyPair = sp_core::sr25519::Pair;
 	type SignedTransaction = UncheckedExtrinsic;
 
+	const EXISTENTIAL_DEPOSIT: u128 = bp_bridge_hub_rococo::EXISTENTIAL_DEPOSIT;
+
 	fn sign_transaction(
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self>,
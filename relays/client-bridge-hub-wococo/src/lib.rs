@@ -68,6 +68,8 @@ impl ChainWithTransactions for BridgeHubWococo {
 	type AccountKeyPair = sp_core::sr25519::Pair;
 	type SignedTransaction = UncheckedExtrinsic;
 
+	const EXISTENTIAL_DEPOSIT: u128 = bp_bridge_hub_wococo::EXISTENTIAL_DEPOSIT;
+
 	fn sign_transaction(
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self>,
@@ -78,6 +78,8 @@ impl ChainWithTransactions for Millau {
 	type AccountKeyPair = sp_core::sr25519::Pair;
 	type SignedTransaction = millau_runtime::UncheckedExtrinsic;
 
+	const EXISTENTIAL_DEPOSIT: u128 = bp_millau::EXISTENTIAL_DEPOSIT as u128;
+
 	fn sign_transaction(
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self>,
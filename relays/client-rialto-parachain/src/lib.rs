@@ -76,6 +76,8 @@ impl ChainWithTransactions for RialtoParachain {
 	type SignedTransaction =
 		bp_polkadot_core::UncheckedExtrinsic<Self::Call, bp_rialto_parachain::SignedExtension>;
 
+	const EXISTENTIAL_DEPOSIT: u128 = bp_rialto_parachain::EXISTENTIAL_DEPOSIT;
+
 	fn sign_transaction(
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self>,
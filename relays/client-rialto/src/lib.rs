@@ -84,6 +84,8 @@ impl ChainWithTransactions for Rialto {
 	type AccountKeyPair = sp_core::sr25519::Pair;
 	type SignedTransaction = rialto_runtime::UncheckedExtrinsic;
 
+	const EXISTENTIAL_DEPOSIT: u128 = bp_rialto::EXISTENTIAL_DEPOSIT;
+
 	fn sign_transaction(
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self>,
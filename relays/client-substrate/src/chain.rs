@@ -181,12 +181,15 @@ impl<C: Chain> UnsignedTransaction<C> {
 pub type AccountKeyPairOf<S> = <S as ChainWithTransactions>::AccountKeyPair;
 
 /// Substrate-based chain transactions signing scheme.
-pub trait ChainWithTransactions: Chain {
+pub trait ChainWithTransactions: ChainWithBalances {
 	/// Type of key pairs used to sign transactions.
 	type AccountKeyPair: Pair;
 	/// Signed transaction.
 	type SignedTransaction: Clone + Debug + Codec + Send + 'static;
 
+	/// Existential deposit (in smallest chain units) of the chain native token.
+	const EXISTENTIAL_DEPOSIT: u128;
+
 	/// Create transaction for given runtime call, signed by given account.
 	fn sign_transaction(
 		param: SignParam<Self>,
@@ -236,6 +239,8 @@ impl<Block: BlockT> BlockWithJustification<Block::Header> for SignedBlock<Block>
 /// Trait that provides functionality defined inside `pallet-utility`
 pub trait UtilityPallet<C: Chain> {
 	/// Create batch call from given calls vector.
+	///
+	/// The batch is atomic - if any of calls fails, the whole batch fails.
 	fn build_batch_call(calls: Vec<C::Call>) -> C::Call;
 }
 
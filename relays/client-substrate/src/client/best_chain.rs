@@ -0,0 +1,225 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fork-aware tracking of the best chain.
+
+use crate::{
+	client::Client, error::Result, BlockNumberOf, Chain, HeaderIdOf, HeaderOf, Subscription,
+	ANCIENT_BLOCK_THRESHOLD,
+};
+
+use bp_runtime::HeaderIdProvider;
+use futures::StreamExt;
+use sp_runtime::traits::Header as _;
+use std::collections::VecDeque;
+
+/// Update of the best chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestChainUpdate<C: Chain> {
+	/// New best header.
+	pub best: HeaderIdOf<C>,
+	/// Headers that have been added to the best chain, ordered by number.
+	pub enacted: Vec<HeaderIdOf<C>>,
+	/// Previously seen best chain headers that are no longer in the best chain, ordered by
+	/// number.
+	pub retracted: Vec<HeaderIdOf<C>>,
+}
+
+impl<C: Chain> BestChainUpdate<C> {
+	/// Returns true if some of previously seen headers have been retracted.
+	pub fn is_reorg(&self) -> bool {
+		!self.retracted.is_empty()
+	}
+}
+
+/// Best chain tracker.
+///
+/// Follows best headers of the chain and detects reorganizations. Proofs that are generated at
+/// retracted headers will be rejected by the bridged chain, so consumers that are anchoring
+/// proofs at non-finalized headers may use this to drop them early.
+pub struct BestChainTracker<C: Chain, Clnt> {
+	client: Clnt,
+	subscription: Subscription<HeaderOf<C>>,
+	best_chain: BestChain<C>,
+}
+
+impl<C: Chain, Clnt: Client<C>> BestChainTracker<C, Clnt> {
+	/// Subscribe to best headers of the chain.
+	pub async fn new(client: Clnt) -> Result<Self> {
+		let subscription = client.subscribe_best_headers().await?;
+		Ok(BestChainTracker { client, subscription, best_chain: BestChain::new() })
+	}
+
+	/// Wait for the next update of the best chain.
+	///
+	/// Returns `None` if the underlying subscription has ended. The tracker needs to be
+	/// recreated then.
+	pub async fn next(&mut self) -> Result<Option<BestChainUpdate<C>>> {
+		while let Some(header) = self.subscription.next().await {
+			if let Some(update) = self.import(header).await? {
+				return Ok(Some(update))
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Import new best header.
+	async fn import(&mut self, mut header: HeaderOf<C>) -> Result<Option<BestChainUpdate<C>>> {
+		if self.best_chain.best() == Some(header.id()) {
+			return Ok(None)
+		}
+
+		// walk back until we find the header that we have seen in the best chain
+		let mut branch = Vec::new();
+		let ancestor = loop {
+			let id = header.id();
+			if self.best_chain.contains(&id) {
+				break Some(id)
+			}
+
+			branch.push(id);
+			if !self.best_chain.may_contain_parent(id.number()) {
+				break None
+			}
+			header = self.client.header_by_hash(*header.parent_hash()).await?;
+		};
+		branch.reverse();
+
+		Ok(Some(self.best_chain.update(ancestor, branch)))
+	}
+}
+
+/// Recent headers of the best chain, ordered by number.
+#[derive(Debug)]
+struct BestChain<C: Chain> {
+	headers: VecDeque<HeaderIdOf<C>>,
+}
+
+impl<C: Chain> BestChain<C> {
+	fn new() -> Self {
+		BestChain { headers: VecDeque::new() }
+	}
+
+	/// Returns best known header.
+	fn best(&self) -> Option<HeaderIdOf<C>> {
+		self.headers.back().copied()
+	}
+
+	/// Returns true if the header is in the best chain.
+	fn contains(&self, id: &HeaderIdOf<C>) -> bool {
+		self.headers.contains(id)
+	}
+
+	/// Returns true if parent of the header with given number may be in the best chain.
+	fn may_contain_parent(&self, number: BlockNumberOf<C>) -> bool {
+		self.headers.front().map(|oldest| number > oldest.number()).unwrap_or(false)
+	}
+
+	/// Replace all headers after the `ancestor` with given branch.
+	///
+	/// If `ancestor` is `None`, the branch isn't connected to known headers, so all of them are
+	/// retracted. If `branch` is empty, the `ancestor` becomes the new best header.
+	fn update(
+		&mut self,
+		ancestor: Option<HeaderIdOf<C>>,
+		branch: Vec<HeaderIdOf<C>>,
+	) -> BestChainUpdate<C> {
+		let retained = ancestor
+			.and_then(|ancestor| self.headers.iter().position(|id| *id == ancestor))
+			.map(|position| position + 1)
+			.unwrap_or(0);
+		let retracted = self.headers.drain(retained..).collect();
+
+		self.headers.extend(branch.iter().copied());
+		while self.headers.len() > ANCIENT_BLOCK_THRESHOLD as usize {
+			self.headers.pop_front();
+		}
+
+		BestChainUpdate {
+			best: self.best().expect("either ancestor or branch header is the best; qed"),
+			enacted: branch,
+			retracted,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_chain::TestChain;
+	use bp_runtime::HeaderId;
+	use sp_core::H256;
+
+	fn id(number: u32, fork: u8) -> HeaderIdOf<TestChain> {
+		HeaderId(number, H256::repeat_byte(fork))
+	}
+
+	#[test]
+	fn best_chain_is_extended() {
+		let mut best_chain = BestChain::<TestChain>::new();
+		assert_eq!(
+			best_chain.update(None, vec![id(1, 0)]),
+			BestChainUpdate { best: id(1, 0), enacted: vec![id(1, 0)], retracted: vec![] },
+		);
+		assert_eq!(
+			best_chain.update(Some(id(1, 0)), vec![id(2, 0), id(3, 0)]),
+			BestChainUpdate {
+				best: id(3, 0),
+				enacted: vec![id(2, 0), id(3, 0)],
+				retracted: vec![],
+			},
+		);
+		assert!(best_chain.may_contain_parent(4));
+		assert!(!best_chain.may_contain_parent(1));
+	}
+
+	#[test]
+	fn reorgs_are_detected() {
+		let mut best_chain = BestChain::<TestChain>::new();
+		best_chain.update(None, vec![id(1, 0), id(2, 0), id(3, 0)]);
+
+		// switch to the longer fork
+		let update = best_chain.update(Some(id(1, 0)), vec![id(2, 1), id(3, 1), id(4, 1)]);
+		assert!(update.is_reorg());
+		assert_eq!(update.best, id(4, 1));
+		assert_eq!(update.retracted, vec![id(2, 0), id(3, 0)]);
+
+		// switch back to the ancestor
+		let update = best_chain.update(Some(id(2, 1)), vec![]);
+		assert_eq!(
+			update,
+			BestChainUpdate {
+				best: id(2, 1),
+				enacted: vec![],
+				retracted: vec![id(3, 1), id(4, 1)]
+			},
+		);
+
+		// switch to the fork that isn't connected to known headers
+		let update = best_chain.update(None, vec![id(5, 2)]);
+		assert_eq!(update.retracted, vec![id(1, 0), id(2, 1)]);
+		assert_eq!(best_chain.best(), Some(id(5, 2)));
+	}
+
+	#[test]
+	fn only_recent_headers_are_tracked() {
+		let mut best_chain = BestChain::<TestChain>::new();
+		best_chain.update(None, (1..=ANCIENT_BLOCK_THRESHOLD + 1).map(|n| id(n, 0)).collect());
+		assert!(!best_chain.contains(&id(1, 0)));
+		assert!(best_chain.contains(&id(2, 0)));
+	}
+}
@@ -20,9 +20,10 @@
 use crate::{
 	client::{Client, SubscriptionBroadcaster},
 	error::{Error, Result},
-	AccountIdOf, AccountKeyPairOf, BlockNumberOf, Chain, ChainWithGrandpa, ChainWithTransactions,
-	HashOf, HeaderIdOf, HeaderOf, NonceOf, SignedBlockOf, SimpleRuntimeVersion, Subscription,
-	TransactionTracker, UnsignedTransaction, ANCIENT_BLOCK_THRESHOLD,
+	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain, ChainWithGrandpa,
+	ChainWithTransactions, HashOf, HeaderIdOf, HeaderOf, NonceOf, SignedBlockOf,
+	SimpleRuntimeVersion, Subscription, TransactionTracker, UnsignedTransaction,
+	ANCIENT_BLOCK_THRESHOLD,
 };
 use std::future::Future;
 
@@ -208,6 +209,10 @@ impl<C: Chain, B: Client<C>> Client<C> for CachingClient<C, B> {
 		self.backend.best_header().await
 	}
 
+	async fn subscribe_best_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		self.backend.subscribe_best_headers().await
+	}
+
 	async fn subscribe_grandpa_finality_justifications(&self) -> Result<Subscription<Bytes>>
 	where
 		C: ChainWithGrandpa,
@@ -270,10 +275,18 @@ impl<C: Chain, B: Client<C>> Client<C> for CachingClient<C, B> {
 		.await
 	}
 
+	async fn storage_keys(&self, at: HashOf<C>, prefix: StorageKey) -> Result<Vec<StorageKey>> {
+		self.backend.storage_keys(at, prefix).await
+	}
+
 	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
 		self.backend.pending_extrinsics().await
 	}
 
+	async fn remove_extrinsics(&self, transactions: Vec<HashOf<C>>) -> Result<Vec<HashOf<C>>> {
+		self.backend.remove_extrinsics(transactions).await
+	}
+
 	async fn submit_unsigned_extrinsic(&self, transaction: Bytes) -> Result<HashOf<C>> {
 		self.backend.submit_unsigned_extrinsic(transaction).await
 	}
@@ -325,6 +338,14 @@ impl<C: Chain, B: Client<C>> Client<C> for CachingClient<C, B> {
 		self.backend.estimate_extrinsic_weight(at, transaction).await
 	}
 
+	async fn estimate_extrinsic_fee<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		transaction: SignedTransaction,
+	) -> Result<BalanceOf<C>> {
+		self.backend.estimate_extrinsic_fee(at, transaction).await
+	}
+
 	async fn raw_state_call<Args: Encode + Send>(
 		&self,
 		at: HashOf<C>,
@@ -16,20 +16,25 @@
 
 use crate::{
 	error::{Error, Result},
-	AccountIdOf, AccountKeyPairOf, BlockNumberOf, Chain, ChainWithGrandpa, ChainWithTransactions,
-	HashOf, HeaderIdOf, HeaderOf, NonceOf, SignedBlockOf, SimpleRuntimeVersion, Subscription,
-	TransactionTracker, UnsignedTransaction,
+	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain, ChainWithBalances,
+	ChainWithGrandpa, ChainWithTransactions, HashOf, HeaderIdOf, HeaderOf, NonceOf, SignedBlockOf,
+	SimpleRuntimeVersion, Subscription, TransactionTracker, UnsignedTransaction,
 };
 
 use async_trait::async_trait;
 use bp_runtime::{StorageDoubleMapKeyProvider, StorageMapKeyProvider, UnverifiedStorageProof};
 use codec::{Decode, Encode};
 use frame_support::weights::Weight;
+use frame_system::AccountInfo;
+use pallet_balances::AccountData;
 use sp_core::{
 	storage::{StorageData, StorageKey},
 	Bytes, Pair,
 };
-use sp_runtime::{traits::Header as _, transaction_validity::TransactionValidity};
+use sp_runtime::{
+	traits::{Header as _, UniqueSaturatedInto},
+	transaction_validity::TransactionValidity,
+};
 use sp_version::RuntimeVersion;
 use std::fmt::Debug;
 
@@ -73,6 +78,11 @@ pub trait Client<C: Chain>: 'static + Send + Sync + Clone + Debug {
 	async fn best_header_hash(&self) -> Result<HashOf<C>> {
 		Ok(self.best_header().await?.hash())
 	}
+	/// Subscribe to best headers.
+	///
+	/// Every header of the stream is the new best header of the chain, but it isn't necessarily
+	/// a child of the previous header. Use [`crate::BestChainTracker`] to detect reorganizations.
+	async fn subscribe_best_headers(&self) -> Result<Subscription<HeaderOf<C>>>;
 
 	/// Subscribe to GRANDPA finality justifications.
 	async fn subscribe_grandpa_finality_justifications(&self) -> Result<Subscription<Bytes>>
@@ -125,6 +135,21 @@ pub trait Client<C: Chain>: 'static + Send + Sync + Clone + Debug {
 			})
 			.transpose()
 	}
+	/// Read free balance (in smallest chain units) of given account. Returns zero if the account
+	/// doesn't exist.
+	async fn free_native_balance(&self, at: HashOf<C>, account: &AccountIdOf<C>) -> Result<u128>
+	where
+		C: ChainWithBalances,
+	{
+		Ok(self
+			.storage_value::<AccountInfo<NonceOf<C>, AccountData<BalanceOf<C>>>>(
+				at,
+				C::account_info_storage_key(account),
+			)
+			.await?
+			.map(|account_info| account_info.data.free.unique_saturated_into())
+			.unwrap_or(0))
+	}
 	/// Read and decode value from runtime storage map.
 	///
 	/// `pallet_prefix` is the name of the pallet (used in `construct_runtime`), which
@@ -151,8 +176,17 @@ pub trait Client<C: Chain>: 'static + Send + Sync + Clone + Debug {
 		self.storage_value(at, T::final_key(pallet_prefix, key1, key2)).await
 	}
 
+	/// Returns all runtime storage keys with given prefix.
+	async fn storage_keys(&self, at: HashOf<C>, prefix: StorageKey) -> Result<Vec<StorageKey>>;
+
 	/// Returns pending extrinsics from transaction pool.
 	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>>;
+	/// Remove extrinsics with given hashes from transaction pool. Returns hashes of removed
+	/// extrinsics.
+	///
+	/// Note: this method is considered unsafe, so the node must be started with
+	/// `--rpc-methods unsafe` to allow it.
+	async fn remove_extrinsics(&self, transactions: Vec<HashOf<C>>) -> Result<Vec<HashOf<C>>>;
 	/// Submit unsigned extrinsic for inclusion in a block.
 	///
 	/// Note: The given transaction needs to be SCALE encoded beforehand.
@@ -198,6 +232,12 @@ pub trait Client<C: Chain>: 'static + Send + Sync + Clone + Debug {
 		at: HashOf<C>,
 		transaction: SignedTransaction,
 	) -> Result<Weight>;
+	/// Returns fee that will be paid for the given transaction, excluding tip.
+	async fn estimate_extrinsic_fee<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		transaction: SignedTransaction,
+	) -> Result<BalanceOf<C>>;
 
 	/// Execute runtime call at given block.
 	async fn raw_state_call<Args: Encode + Send>(
@@ -26,12 +26,14 @@ use sp_version::RuntimeVersion;
 pub mod caching;
 pub mod rpc;
 
+mod best_chain;
 // don't want to move all the trait code (200+ lines) here and there's no better name
 #[allow(clippy::module_inception)]
 mod client;
 mod rpc_api;
 mod subscription;
 
+pub use best_chain::{BestChainTracker, BestChainUpdate};
 pub use client::Client;
 pub use subscription::{StreamDescription, Subscription, SubscriptionBroadcaster};
 
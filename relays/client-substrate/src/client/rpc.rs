@@ -28,10 +28,10 @@ use crate::{
 		Client,
 	},
 	error::{Error, Result},
-	transaction_stall_timeout, AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain,
-	ChainRuntimeVersion, ChainWithGrandpa, ChainWithTransactions, ConnectionParams, HashOf,
-	HeaderIdOf, HeaderOf, NonceOf, SignParam, SignedBlockOf, SimpleRuntimeVersion,
-	TransactionTracker, UnsignedTransaction,
+	is_check_signer_balance_enabled, is_dry_run, transaction_stall_timeout, AccountIdOf,
+	AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain, ChainRuntimeVersion, ChainWithGrandpa,
+	ChainWithTransactions, ConnectionParams, HashOf, HeaderIdOf, HeaderOf, NonceOf, SignParam,
+	SignedBlockOf, SimpleRuntimeVersion, TransactionTracker, UnsignedTransaction,
 };
 
 use async_std::sync::{Arc, Mutex, RwLock};
@@ -47,16 +47,24 @@ use jsonrpsee::{
 use num_traits::Zero;
 use pallet_transaction_payment::RuntimeDispatchInfo;
 use relay_utils::{relay_loop::RECONNECT_DELAY, STALL_TIMEOUT};
+use sc_rpc_api::author::hash::ExtrinsicOrHash;
 use sp_core::{
+	hexdisplay::HexDisplay,
 	storage::{StorageData, StorageKey},
 	Bytes, Hasher, Pair,
 };
-use sp_runtime::transaction_validity::{TransactionSource, TransactionValidity};
+use sp_runtime::{
+	traits::UniqueSaturatedInto,
+	transaction_validity::{TransactionSource, TransactionValidity},
+};
 use sp_trie::StorageProof;
 use sp_version::RuntimeVersion;
 use std::{future::Future, marker::PhantomData};
+use tracing::Instrument;
 
 const MAX_SUBSCRIPTION_CAPACITY: usize = 4096;
+/// Max number of storage keys that we are reading using single `state_getKeysPaged` call.
+const STORAGE_KEYS_PAGE_SIZE: u32 = 1024;
 
 const SUB_API_TXPOOL_VALIDATE_TRANSACTION: &str = "TaggedTransactionQueue_validate_transaction";
 const SUB_API_TX_PAYMENT_QUERY_INFO: &str = "TransactionPaymentApi_query_info";
@@ -90,6 +98,19 @@ struct ClientData {
 	client: Arc<WsClient>,
 }
 
+/// Log transaction that would be submitted if relay was not running in the dry-run mode.
+fn log_dry_run_transaction<C: Chain>(tx_hash: HashOf<C>, transaction: &[u8]) {
+	log::info!(
+		target: "bridge",
+		chain = C::NAME,
+		tx_hash = format!("{tx_hash:?}").as_str();
+		"Not submitting transaction {:?} to {} node in dry-run mode: 0x{}",
+		tx_hash,
+		C::NAME,
+		HexDisplay::from(&transaction),
+	);
+}
+
 /// Already encoded value.
 struct PreEncoded(Vec<u8>);
 
@@ -182,9 +203,17 @@ impl<C: Chain> RpcClient<C> {
 		F: Future<Output = Result<T>> + Send + 'static,
 		T: Send + 'static,
 	{
+		// the span is created here, because spawned future loses the current span context
+		let span = tracing::info_span!(
+			target: "bridge",
+			"rpc",
+			chain = C::NAME,
+			// the closure type name includes name of the `RpcClient` method
+			method = std::any::type_name::<MF>(),
+		);
 		let data = self.data.read().await;
 		let client = data.client.clone();
-		data.tokio.spawn(make_jsonrpsee_future(client)).await?
+		data.tokio.spawn(make_jsonrpsee_future(client).instrument(span)).await?
 	}
 
 	/// Prepare parameters used to sign chain transactions.
@@ -201,6 +230,38 @@ impl<C: Chain> RpcClient<C> {
 		})
 	}
 
+	/// Returns error if free balance of the signer doesn't cover the transaction fee and the
+	/// existential deposit.
+	///
+	/// The check costs two additional RPC calls, so it is only performed if it is enabled with
+	/// `set_check_signer_balance`.
+	async fn ensure_can_pay_fee(
+		&self,
+		at: HashOf<C>,
+		signer: AccountIdOf<C>,
+		signed_extrinsic: &[u8],
+	) -> Result<()>
+	where
+		C: ChainWithTransactions,
+	{
+		if !is_check_signer_balance_enabled() {
+			return Ok(())
+		}
+
+		let fee: u128 = self
+			.estimate_extrinsic_fee(at, PreEncoded(signed_extrinsic.to_vec()))
+			.await?
+			.unique_saturated_into();
+		let free_balance = self.free_native_balance(at, &signer).await?;
+
+		let required = fee.saturating_add(C::EXISTENTIAL_DEPOSIT);
+		if free_balance < required {
+			return Err(Error::InsufficientBalance { chain: C::NAME.into(), free_balance, required })
+		}
+
+		Ok(())
+	}
+
 	/// Get the nonce of the given Substrate account.
 	pub async fn next_account_index(&self, account: AccountIdOf<C>) -> Result<NonceOf<C>> {
 		self.jsonrpsee_execute(move |client| async move {
@@ -311,6 +372,20 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 		.map_err(|e| Error::failed_to_read_best_header::<C>(e))
 	}
 
+	async fn subscribe_best_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		let subscription = self
+			.jsonrpsee_execute(move |client| async move {
+				Ok(SubstrateChainClient::<C>::subscribe_new_heads(&*client).await?)
+			})
+			.map_err(|e| Error::failed_to_subscribe_best_headers::<C>(e))
+			.await?;
+
+		Ok(Subscription::new_forwarded(
+			StreamDescription::new("best headers".into(), C::NAME.into()),
+			subscription,
+		))
+	}
+
 	async fn subscribe_grandpa_finality_justifications(&self) -> Result<Subscription<Bytes>>
 	where
 		C: ChainWithGrandpa,
@@ -388,6 +463,33 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 		.map_err(|e| Error::failed_to_read_storage_value::<C>(at, storage_key, e))
 	}
 
+	async fn storage_keys(&self, at: HashOf<C>, prefix: StorageKey) -> Result<Vec<StorageKey>> {
+		let mut keys = Vec::new();
+		loop {
+			let cloned_prefix = prefix.clone();
+			let start_key = keys.last().cloned();
+			let page = self
+				.jsonrpsee_execute(move |client| async move {
+					Ok(SubstrateStateClient::<C>::storage_keys_paged(
+						&*client,
+						Some(cloned_prefix),
+						STORAGE_KEYS_PAGE_SIZE,
+						start_key,
+						Some(at),
+					)
+					.await?)
+				})
+				.await
+				.map_err(|e| Error::failed_to_read_storage_value::<C>(at, prefix.clone(), e))?;
+
+			let is_last_page = page.len() < STORAGE_KEYS_PAGE_SIZE as usize;
+			keys.extend(page);
+			if is_last_page {
+				return Ok(keys)
+			}
+		}
+	}
+
 	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
 		self.jsonrpsee_execute(move |client| async move {
 			Ok(SubstrateAuthorClient::<C>::pending_extrinsics(&*client).await?)
@@ -396,6 +498,25 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 		.map_err(|e| Error::failed_to_get_pending_extrinsics::<C>(e))
 	}
 
+	async fn remove_extrinsics(&self, transactions: Vec<HashOf<C>>) -> Result<Vec<HashOf<C>>> {
+		if is_dry_run() {
+			log::info!(
+				target: "bridge",
+				"Dry run: not removing {} transactions {:?} from the pool",
+				C::NAME,
+				transactions,
+			);
+			return Ok(transactions)
+		}
+
+		self.jsonrpsee_execute(move |client| async move {
+			let bytes_or_hash = transactions.into_iter().map(ExtrinsicOrHash::Hash).collect();
+			Ok(SubstrateAuthorClient::<C>::remove_extrinsic(&*client, bytes_or_hash).await?)
+		})
+		.await
+		.map_err(|e| Error::failed_to_remove_extrinsics::<C>(e))
+	}
+
 	async fn submit_unsigned_extrinsic(&self, transaction: Bytes) -> Result<HashOf<C>> {
 		// one last check that the transaction is valid. Most of checks happen in the relay loop and
 		// it is the "final" check before submission.
@@ -405,6 +526,12 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?
 			.map_err(|e| Error::failed_to_submit_transaction::<C>(Error::TransactionInvalid(e)))?;
 
+		if is_dry_run() {
+			let tx_hash = C::Hasher::hash(&transaction.0);
+			log_dry_run_transaction::<C>(tx_hash, &transaction.0);
+			return Ok(tx_hash)
+		}
+
 		self.jsonrpsee_execute(move |client| async move {
 			let tx_hash = SubstrateAuthorClient::<C>::submit_extrinsic(&*client, transaction)
 				.await
@@ -412,7 +539,14 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 					log::error!(target: "bridge", "Failed to send transaction to {} node: {:?}", C::NAME, e);
 					e
 				})?;
-			log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
+			log::trace!(
+				target: "bridge",
+				chain = C::NAME,
+				tx_hash = format!("{tx_hash:?}").as_str();
+				"Sent transaction to {} node: {:?}",
+				C::NAME,
+				tx_hash,
+			);
 			Ok(tx_hash)
 		})
 		.await
@@ -444,6 +578,9 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 
 		let extrinsic = prepare_extrinsic(best_header_id, transaction_nonce)?;
 		let signed_extrinsic = C::sign_transaction(signing_data, extrinsic)?.encode();
+		self.ensure_can_pay_fee(best_header_id.hash(), signer.public().into(), &signed_extrinsic)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?;
 		self.submit_unsigned_extrinsic(Bytes(signed_extrinsic)).await
 	}
 
@@ -475,11 +612,30 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 
 		// one last check that the transaction is valid. Most of checks happen in the relay loop and
 		// it is the "final" check before submission.
+		self.ensure_can_pay_fee(best_header_id.hash(), signer.public().into(), &signed_extrinsic)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?;
 		self.validate_transaction(best_header_id.hash(), PreEncoded(signed_extrinsic.clone()))
 			.await
 			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?
 			.map_err(|e| Error::failed_to_submit_transaction::<C>(Error::TransactionInvalid(e)))?;
 
+		if is_dry_run() {
+			// the transaction is never submitted, so the tracker will report it as lost after
+			// the stall timeout
+			let tx_hash = C::Hasher::hash(&signed_extrinsic);
+			log_dry_run_transaction::<C>(tx_hash, &signed_extrinsic);
+			return Ok(TransactionTracker::new(
+				self_clone,
+				stall_timeout,
+				tx_hash,
+				Subscription::new_forwarded(
+					StreamDescription::new("transaction events".into(), C::NAME.into()),
+					futures::stream::pending(),
+				),
+			))
+		}
+
 		self.jsonrpsee_execute(move |client| async move {
 			let tx_hash = C::Hasher::hash(&signed_extrinsic);
 			let subscription: jsonrpsee::core::client::Subscription<_> =
@@ -492,7 +648,14 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 					log::error!(target: "bridge", "Failed to send transaction to {} node: {:?}", C::NAME, e);
 					e
 				})?;
-			log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
+			log::trace!(
+				target: "bridge",
+				chain = C::NAME,
+				tx_hash = format!("{tx_hash:?}").as_str();
+				"Sent transaction to {} node: {:?}",
+				C::NAME,
+				tx_hash,
+			);
 			Ok(TransactionTracker::new(
 				self_clone,
 				stall_timeout,
@@ -533,6 +696,19 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 		Ok(dispatch_info.weight)
 	}
 
+	async fn estimate_extrinsic_fee<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		transaction: SignedTransaction,
+	) -> Result<BalanceOf<C>> {
+		let transaction_len = transaction.encoded_size() as u32;
+		let dispatch_info: RuntimeDispatchInfo<BalanceOf<C>> = self
+			.state_call(at, SUB_API_TX_PAYMENT_QUERY_INFO.into(), (transaction, transaction_len))
+			.await?;
+
+		Ok(dispatch_info.partial_fee)
+	}
+
 	async fn raw_state_call<Args: Encode + Send>(
 		&self,
 		at: HashOf<C>,
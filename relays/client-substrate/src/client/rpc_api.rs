@@ -20,7 +20,7 @@ use crate::{Chain, ChainWithGrandpa, TransactionStatusOf};
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use pallet_transaction_payment_rpc_runtime_api::FeeDetails;
-use sc_rpc_api::{state::ReadProof, system::Health};
+use sc_rpc_api::{author::hash::ExtrinsicOrHash, state::ReadProof, system::Health};
 use sp_core::{
 	storage::{StorageData, StorageKey},
 	Bytes,
@@ -54,6 +54,9 @@ pub(crate) trait SubstrateChain<C> {
 	/// Return signed block (with justifications) by its hash.
 	#[method(name = "getBlock")]
 	async fn block(&self, block_hash: Option<C::Hash>) -> RpcResult<C::SignedBlock>;
+	/// Subscribe to best headers.
+	#[subscription(name = "subscribeNewHeads", unsubscribe = "unsubscribeNewHeads", item = C::Header)]
+	async fn subscribe_new_heads(&self);
 }
 
 /// RPC methods of Substrate `author` namespace, that we are using.
@@ -65,6 +68,12 @@ pub(crate) trait SubstrateAuthor<C> {
 	/// Return vector of pending extrinsics from the transaction pool.
 	#[method(name = "pendingExtrinsics")]
 	async fn pending_extrinsics(&self) -> RpcResult<Vec<Bytes>>;
+	/// Remove given extrinsics from the transaction pool.
+	#[method(name = "removeExtrinsic")]
+	async fn remove_extrinsic(
+		&self,
+		bytes_or_hash: Vec<ExtrinsicOrHash<C::Hash>>,
+	) -> RpcResult<Vec<C::Hash>>;
 	/// Submit and watch for extrinsic state.
 	#[subscription(name = "submitAndWatchExtrinsic", unsubscribe = "unwatchExtrinsic", item = TransactionStatusOf<C>)]
 	async fn submit_and_watch_extrinsic(&self, extrinsic: Bytes);
@@ -91,6 +100,15 @@ pub(crate) trait SubstrateState<C> {
 		key: StorageKey,
 		at_block: Option<C::Hash>,
 	) -> RpcResult<Option<StorageData>>;
+	/// Get storage keys with given prefix, starting after given key.
+	#[method(name = "getKeysPaged")]
+	async fn storage_keys_paged(
+		&self,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+		at_block: Option<C::Hash>,
+	) -> RpcResult<Vec<StorageKey>>;
 	/// Get proof of the runtime storage value.
 	#[method(name = "getReadProof")]
 	async fn prove_storage(
@@ -138,6 +138,14 @@ pub enum Error {
 		/// Underlying error.
 		error: Box<Error>,
 	},
+	/// Failed to remove extrinsics from the transaction pool.
+	#[error("Failed to remove {chain} extrinsics from the transaction pool: {error:?}.")]
+	FailedToRemoveExtrinsics {
+		/// Name of the chain where the error has happened.
+		chain: String,
+		/// Underlying error.
+		error: Box<Error>,
+	},
 	/// Failed to submit transaction.
 	#[error("Failed to submit {chain} transaction: {error:?}.")]
 	FailedToSubmitTransaction {
@@ -180,6 +188,14 @@ pub enum Error {
 		/// Underlying error.
 		error: Box<Error>,
 	},
+	/// Failed to subscribe to best headers stream.
+	#[error("Failed to subscribe to {chain} best headers: {error:?}.")]
+	FailedToSubscribeBestHeaders {
+		/// Name of the chain where the error has happened.
+		chain: String,
+		/// Underlying error.
+		error: Box<Error>,
+	},
 	/// The bridge pallet is halted and all transactions will be rejected.
 	#[error("Bridge pallet is halted.")]
 	BridgePalletIsHalted,
@@ -189,6 +205,27 @@ pub enum Error {
 	/// The Substrate transaction is invalid.
 	#[error("Substrate transaction is invalid: {0:?}")]
 	TransactionInvalid(#[from] TransactionValidityError),
+	/// The relayer account has exceeded its spending budget.
+	#[error("Relayer account at {chain} has exceeded its spending budget: {reason}.")]
+	SpendingBudgetExceeded {
+		/// Name of the chain where the error has happened.
+		chain: String,
+		/// Why the budget is considered exceeded.
+		reason: String,
+	},
+	/// The transaction signer can't pay the transaction fee.
+	#[error(
+		"Signer at {chain} has free balance {free_balance}, while {required} is required to pay \
+		the transaction fee and keep the account alive."
+	)]
+	InsufficientBalance {
+		/// Name of the chain where the error has happened.
+		chain: String,
+		/// Free balance of the signer.
+		free_balance: u128,
+		/// Estimated transaction fee plus existential deposit.
+		required: u128,
+	},
 	/// Custom logic error.
 	#[error("{0}")]
 	Custom(String),
@@ -229,11 +266,13 @@ impl Error {
 			Self::FailedToReadStorageValue { ref error, .. } => Some(&**error),
 			Self::FailedToReadRuntimeVersion { ref error, .. } => Some(&**error),
 			Self::FailedToGetPendingExtrinsics { ref error, .. } => Some(&**error),
+			Self::FailedToRemoveExtrinsics { ref error, .. } => Some(&**error),
 			Self::FailedToSubmitTransaction { ref error, .. } => Some(&**error),
 			Self::FailedStateCall { ref error, .. } => Some(&**error),
 			Self::FailedToProveStorage { ref error, .. } => Some(&**error),
 			Self::FailedToGetSystemHealth { ref error, .. } => Some(&**error),
 			Self::FailedToSubscribeJustifications { ref error, .. } => Some(&**error),
+			Self::FailedToSubscribeBestHeaders { ref error, .. } => Some(&**error),
 			_ => None,
 		}
 	}
@@ -302,6 +341,11 @@ impl Error {
 		Error::FailedToGetPendingExtrinsics { chain: C::NAME.into(), error: e.boxed() }
 	}
 
+	/// Constructs `FailedToRemoveExtrinsics` variant.
+	pub fn failed_to_remove_extrinsics<C: Chain>(e: Error) -> Self {
+		Error::FailedToRemoveExtrinsics { chain: C::NAME.into(), error: e.boxed() }
+	}
+
 	/// Constructs `FailedToSubmitTransaction` variant.
 	pub fn failed_to_submit_transaction<C: Chain>(e: Error) -> Self {
 		Error::FailedToSubmitTransaction { chain: C::NAME.into(), error: e.boxed() }
@@ -346,6 +390,11 @@ impl Error {
 	pub fn failed_to_subscribe_justification<C: Chain>(e: Error) -> Self {
 		Error::FailedToSubscribeJustifications { chain: C::NAME.into(), error: e.boxed() }
 	}
+
+	/// Constructs `FailedToSubscribeBestHeaders` variant.
+	pub fn failed_to_subscribe_best_headers<C: Chain>(e: Error) -> Self {
+		Error::FailedToSubscribeBestHeaders { chain: C::NAME.into(), error: e.boxed() }
+	}
 }
 
 impl MaybeConnectionError for Error {
@@ -358,4 +407,31 @@ impl MaybeConnectionError for Error {
 			_ => self.nested().map(|e| e.is_connection_error()).unwrap_or(false),
 		}
 	}
+
+	fn error_code(&self) -> &'static str {
+		if let Some(nested) = self.nested() {
+			return nested.error_code()
+		}
+
+		match *self {
+			Error::Io(_) => "io",
+			Error::RpcError(ref e) => match *e {
+				RpcError::Transport(_) | RpcError::RestartNeeded(_) => "connection",
+				RpcError::RequestTimeout => "timeout",
+				RpcError::Call(_) => "rpc_call",
+				_ => "rpc",
+			},
+			Error::ResponseParseFailed(_) => "decode",
+			Error::ChannelError(_) => "connection",
+			Error::MissingRequiredParachainHead(_, _) => "missing_parachain_head",
+			Error::FinalityProofNotFound(_) => "missing_finality_proof",
+			Error::ClientNotSynced(_) => "node_not_synced",
+			Error::BridgePalletIsHalted => "halted_pallet",
+			Error::BridgePalletIsNotInitialized => "uninitialized_pallet",
+			Error::TransactionInvalid(_) => "invalid_transaction",
+			Error::SpendingBudgetExceeded { .. } => "spending_budget_exceeded",
+			Error::InsufficientBalance { .. } => "insufficient_balance",
+			_ => "other",
+		}
+	}
 }
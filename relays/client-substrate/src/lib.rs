@@ -29,7 +29,10 @@ pub mod guard;
 pub mod metrics;
 pub mod test_chain;
 
-use std::time::Duration;
+use std::{
+	sync::atomic::{AtomicBool, Ordering},
+	time::Duration,
+};
 
 pub use crate::{
 	chain::{
@@ -39,9 +42,9 @@ pub use crate::{
 		SignedBlockOf, TransactionStatusOf, UnsignedTransaction, UtilityPallet,
 	},
 	client::{
-		is_ancient_block, rpc_with_caching as new, ChainRuntimeVersion, Client,
-		OpaqueGrandpaAuthoritiesSet, RpcWithCachingClient, SimpleRuntimeVersion, StreamDescription,
-		Subscription, ANCIENT_BLOCK_THRESHOLD,
+		is_ancient_block, rpc_with_caching as new, BestChainTracker, BestChainUpdate,
+		ChainRuntimeVersion, Client, OpaqueGrandpaAuthoritiesSet, RpcWithCachingClient,
+		SimpleRuntimeVersion, StreamDescription, Subscription, ANCIENT_BLOCK_THRESHOLD,
 	},
 	error::{Error, Result},
 	sync_header::SyncHeader,
@@ -77,6 +80,38 @@ impl Default for ConnectionParams {
 	}
 }
 
+/// Set to `true` when relay is running in the dry-run mode.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable the dry-run mode.
+///
+/// In the dry-run mode, all transactions are prepared and validated as usual, but instead of
+/// being submitted to the node, they are only logged.
+pub fn set_dry_run(dry_run: bool) {
+	DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+/// Returns `true` if relay is running in the dry-run mode.
+pub fn is_dry_run() -> bool {
+	DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Set to `true` when signer balance is checked before submitting transactions.
+static CHECK_SIGNER_BALANCE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable signer balance check.
+///
+/// If enabled, transactions are not submitted if the signer free balance doesn't cover the
+/// transaction fee and the existential deposit of the chain.
+pub fn set_check_signer_balance(check_signer_balance: bool) {
+	CHECK_SIGNER_BALANCE.store(check_signer_balance, Ordering::Relaxed);
+}
+
+/// Returns `true` if signer balance is checked before submitting transactions.
+pub fn is_check_signer_balance_enabled() -> bool {
+	CHECK_SIGNER_BALANCE.load(Ordering::Relaxed)
+}
+
 /// Returns stall timeout for relay loop.
 ///
 /// Relay considers himself stalled if he has submitted transaction to the node, but it has not
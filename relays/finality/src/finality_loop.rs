@@ -28,16 +28,36 @@ use crate::{
 };
 use async_trait::async_trait;
 use backoff::{backoff::Backoff, ExponentialBackoff};
-use futures::{future::Fuse, select, Future, FutureExt};
+use futures::{
+	future::{Fuse, FusedFuture},
+	select, Future, FutureExt,
+};
 use num_traits::Saturating;
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, retry_backoff, FailedClient,
-	HeaderId, MaybeConnectionError, TrackedTransactionStatus, TransactionTracker,
+	clock::{Clock, SystemClock},
+	metrics::MetricsParams,
+	relay_loop::Client as RelayClient,
+	retry_backoff, FailedClient, HeaderId, MaybeConnectionError, TrackedTransactionStatus,
+	TransactionTracker, UniqueSaturatedInto,
 };
+use serde::{Deserialize, Serialize};
 use std::{
 	fmt::Debug,
+	sync::Arc,
 	time::{Duration, Instant},
 };
+use tracing::Instrument;
+
+/// Name of the finality loop state in the relay state database.
+const FINALITY_LOOP_STATE: &str = "finality";
+
+/// Finality loop state, that is saved to the relay state database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct FinalityLoopState {
+	/// Number of the best header that we have submitted to the target node, if the submission
+	/// transaction has not failed.
+	best_submitted_number: Option<u64>,
+}
 
 /// Finality proof synchronization loop parameters.
 #[derive(Debug, Clone)]
@@ -64,6 +84,11 @@ pub struct FinalitySyncParams {
 	pub stall_timeout: Duration,
 	/// If true, only mandatory headers are relayed.
 	pub only_mandatory_headers: bool,
+	/// If true, the loop doesn't read every header that is missing at the target node. Instead,
+	/// it asks the source client for the next mandatory header and submits it directly. If
+	/// there are no mandatory headers, it submits the best header with the finality proof from
+	/// the finality proofs stream.
+	pub warp_sync: bool,
 }
 
 /// Source client used in finality synchronization loop.
@@ -77,6 +102,16 @@ pub trait SourceClient<P: FinalitySyncPipeline>: SourceClientBase<P> {
 		&self,
 		number: P::Number,
 	) -> Result<(P::Header, Option<P::FinalityProof>), Self::Error>;
+
+	/// Get number of the first mandatory header in the `(after; up_to]` range.
+	///
+	/// Returns `None` if there are no mandatory headers in the range. This is only used in the
+	/// warp sync mode.
+	async fn find_mandatory_header_number(
+		&self,
+		after: P::Number,
+		up_to: P::Number,
+	) -> Result<Option<P::Number>, Self::Error>;
 }
 
 /// Target client used in finality synchronization loop.
@@ -193,7 +228,16 @@ impl<Tracker: TransactionTracker, Number: Debug + PartialOrd> Transaction<Tracke
 			P::TARGET_NAME,
 		);
 
-		let tracker = target_client.submit_finality_proof(header, justification).await?;
+		let tracker = target_client
+			.submit_finality_proof(header, justification)
+			.instrument(tracing::info_span!(
+				target: "bridge",
+				"submit_finality_proof",
+				source_chain = P::SOURCE_NAME,
+				target_chain = P::TARGET_NAME,
+				header_number = ?header_number,
+			))
+			.await?;
 		Ok(Transaction { tracker, header_number })
 	}
 
@@ -205,7 +249,18 @@ impl<Tracker: TransactionTracker, Number: Debug + PartialOrd> Transaction<Tracke
 		self,
 		target_client: TC,
 	) -> Result<(), Error<P, SC::Error, TC::Error>> {
-		match self.tracker.wait().await {
+		let status = self
+			.tracker
+			.wait()
+			.instrument(tracing::info_span!(
+				target: "bridge",
+				"wait_finality_proof_finalization",
+				source_chain = P::SOURCE_NAME,
+				target_chain = P::TARGET_NAME,
+				header_number = ?self.header_number,
+			))
+			.await;
+		match status {
 			TrackedTransactionStatus::Finalized(_) => {
 				// The transaction has been finalized, but it may have been finalized in the
 				// "failed" state. So let's check if the block number was actually updated.
@@ -235,12 +290,16 @@ struct FinalityLoop<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClie
 
 	sync_params: FinalitySyncParams,
 	metrics_sync: Option<SyncLoopMetrics>,
+	clock: Arc<dyn Clock>,
 
 	progress: (Instant, Option<P::Number>),
 	retry_backoff: ExponentialBackoff,
 	finality_proofs_stream: FinalityProofsStream<P, SC>,
 	finality_proofs_buf: FinalityProofsBuf<P>,
 	best_submitted_number: Option<P::Number>,
+	/// Number of the header that we have submitted before restart and the time until we are
+	/// waiting for it to be imported by the target node.
+	resumed_submission: Option<(u64, Instant)>,
 }
 
 impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> FinalityLoop<P, SC, TC> {
@@ -249,23 +308,37 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 		target_client: TC,
 		sync_params: FinalitySyncParams,
 		metrics_sync: Option<SyncLoopMetrics>,
+		clock: Arc<dyn Clock>,
 	) -> Self {
 		Self {
 			source_client,
 			target_client,
 			sync_params,
 			metrics_sync,
-			progress: (Instant::now(), None),
+			progress: (clock.now(), None),
+			clock,
 			retry_backoff: retry_backoff(),
 			finality_proofs_stream: FinalityProofsStream::new(),
 			finality_proofs_buf: FinalityProofsBuf::new(vec![]),
 			best_submitted_number: None,
+			resumed_submission: None,
+		}
+	}
+
+	/// Resume from the state, saved before restart.
+	///
+	/// If the header has been submitted before restart, we don't submit anything until it is
+	/// imported by the target node or until the `stall_timeout` expires.
+	fn resume(&mut self, state: Option<FinalityLoopState>) {
+		if let Some(best_submitted_number) = state.and_then(|state| state.best_submitted_number) {
+			self.resumed_submission =
+				Some((best_submitted_number, self.clock.now() + self.sync_params.stall_timeout));
 		}
 	}
 
 	fn update_progress(&mut self, info: &SyncInfo<P>) {
 		let (prev_time, prev_best_number_at_target) = self.progress;
-		let now = Instant::now();
+		let now = self.clock.now();
 
 		let needs_update = now - prev_time > Duration::from_secs(10) ||
 			prev_best_number_at_target
@@ -301,10 +374,19 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 			info.best_number_at_source
 		);
 
+		// in the warp sync mode we don't read all missing headers
+		if self.sync_params.warp_sync {
+			return self.select_header_to_warp(info).await
+		}
+
 		// read missing headers
 		let selector = JustifiedHeaderSelector::new::<SC, TC>(&self.source_client, info).await?;
-		// if we see that the header schedules GRANDPA change, we need to submit it
-		if self.sync_params.only_mandatory_headers {
+		// if we see that the header schedules GRANDPA change, we need to submit it. Other headers
+		// are only submitted if the loop has been triggered using the control endpoint
+		let is_mandatory = matches!(selector, JustifiedHeaderSelector::Mandatory(_));
+		if self.sync_params.only_mandatory_headers &&
+			(is_mandatory || !relay_utils::control::take_trigger())
+		{
 			return Ok(selector.select_mandatory())
 		}
 
@@ -325,6 +407,76 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 		Ok(maybe_justified_header)
 	}
 
+	/// Select header to submit in the warp sync mode.
+	///
+	/// The first mandatory header in the range is selected. If there are no mandatory headers,
+	/// the best header with the finality proof from the stream is selected. If there are no
+	/// such proofs yet, we'll wait for them instead of reading all missing headers.
+	async fn select_header_to_warp(
+		&mut self,
+		info: &SyncInfo<P>,
+	) -> Result<Option<JustifiedHeader<P>>, Error<P, SC::Error, TC::Error>> {
+		let maybe_mandatory_header_number = self
+			.source_client
+			.find_mandatory_header_number(info.best_number_at_target, info.best_number_at_source)
+			.await
+			.map_err(Error::Source)?;
+		if let Some(mandatory_header_number) = maybe_mandatory_header_number {
+			let (header, maybe_proof) = self
+				.source_client
+				.header_and_finality_proof(mandatory_header_number)
+				.await
+				.map_err(Error::Source)?;
+			return match (header.is_mandatory(), maybe_proof) {
+				(true, Some(proof)) => Ok(Some(JustifiedHeader { header, proof })),
+				(true, None) => Err(Error::MissingMandatoryFinalityProof(mandatory_header_number)),
+				(false, _) => {
+					log::warn!(
+						target: "bridge",
+						"Source client has reported that {} header {:?} is mandatory, but it is not",
+						P::SOURCE_NAME,
+						mandatory_header_number,
+					);
+					Ok(None)
+				},
+			}
+		}
+
+		if self.sync_params.only_mandatory_headers && !relay_utils::control::take_trigger() {
+			return Ok(None)
+		}
+
+		// select the best header that we have a finality proof for
+		self.finality_proofs_buf.fill(&mut self.finality_proofs_stream);
+		let maybe_proof = self
+			.finality_proofs_buf
+			.buf()
+			.iter()
+			.rev()
+			.find(|(number, _)| *number <= info.best_number_at_source)
+			.filter(|(number, _)| *number > info.best_number_at_target)
+			.cloned();
+		self.finality_proofs_buf.prune(
+			maybe_proof
+				.as_ref()
+				.map(|(number, _)| *number)
+				.unwrap_or(info.best_number_at_target),
+			self.sync_params.recent_finality_proofs_limit,
+		);
+
+		match maybe_proof {
+			Some((number, proof)) => {
+				let (header, _) = self
+					.source_client
+					.header_and_finality_proof(number)
+					.await
+					.map_err(Error::Source)?;
+				Ok(Some(JustifiedHeader { header, proof }))
+			},
+			None => Ok(None),
+		}
+	}
+
 	pub async fn run_iteration(
 		&mut self,
 	) -> Result<
@@ -335,6 +487,31 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 		let info = SyncInfo::new(&self.source_client, &self.target_client).await?;
 		info.update_metrics(&self.metrics_sync);
 		self.update_progress(&info);
+		let best_number_at_target: u64 = info.best_number_at_target.unique_saturated_into();
+		relay_utils::health::note_race_state(FINALITY_LOOP_STATE, None, best_number_at_target);
+
+		// paused loop doesn't submit anything
+		if relay_utils::control::is_paused() {
+			return Ok(None)
+		}
+
+		// if we have submitted header before restart, then we wait for it until it is stalled
+		if let Some((resumed_number, resumed_until)) = self.resumed_submission {
+			if best_number_at_target < resumed_number && self.clock.now() < resumed_until {
+				return Ok(None)
+			}
+			if best_number_at_target < resumed_number {
+				log::warn!(
+					target: "bridge",
+					"{} header #{} that has been submitted before restart is not imported by {}. \
+					Considering the transaction lost",
+					P::SOURCE_NAME,
+					resumed_number,
+					P::TARGET_NAME,
+				);
+			}
+			self.resumed_submission = None;
+		}
 
 		// if we have already submitted header, then we just need to wait for it
 		// if we're waiting too much, then we believe our transaction has been lost and restart sync
@@ -348,11 +525,30 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 				let transaction =
 					Transaction::submit(&self.target_client, header.header, header.proof)
 						.await
-						.map_err(Error::Target)?;
+						.map_err(|e| {
+							relay_utils::alerts::note_submission_result(false);
+							Error::Target(e)
+						})?;
 				self.best_submitted_number = Some(transaction.header_number);
+				relay_utils::state_store::save(
+					FINALITY_LOOP_STATE,
+					&FinalityLoopState {
+						best_submitted_number: Some(
+							transaction.header_number.unique_saturated_into(),
+						),
+					},
+				);
 				Ok(Some(transaction))
 			},
-			None => Ok(None),
+			None => {
+				// there's nothing to relay, so the loop is not stalled
+				relay_utils::health::note_race_state(
+					FINALITY_LOOP_STATE,
+					Some(best_number_at_target),
+					best_number_at_target,
+				);
+				Ok(None)
+			},
 		}
 	}
 
@@ -391,6 +587,7 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 				},
 				Err(error) => {
 					log::error!(target: "bridge", "Finality sync loop iteration has failed with error: {:?}", error);
+					relay_utils::metrics::note_iteration_failure_code(error.error_code());
 					error.fail_if_connection_error()?;
 					self.retry_backoff
 						.next_backoff()
@@ -400,8 +597,10 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 			self.ensure_finality_proofs_stream().await?;
 
 			// wait till exit signal, or new source block
+			let clock = self.clock.clone();
 			select! {
 				proof_submission_result = proof_submission_tx_tracker => {
+					relay_utils::alerts::note_submission_result(proof_submission_result.is_ok());
 					if let Err(e) = proof_submission_result {
 						log::error!(
 							target: "bridge",
@@ -410,10 +609,24 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 							e,
 						);
 						self.best_submitted_number = None;
+						relay_utils::state_store::save(
+							FINALITY_LOOP_STATE,
+							&FinalityLoopState::default(),
+						);
 						e.fail_if_connection_error()?;
 					}
 				},
-				_ = async_std::task::sleep(next_tick).fuse() => {},
+				_ = clock.sleep(next_tick).fuse() => {},
+				_ = relay_utils::shutdown::shutdown_requested().fuse() => {
+					if !proof_submission_tx_tracker.is_terminated() {
+						relay_utils::shutdown::drain_in_flight_transaction(
+							&format!("{} -> {} finality relay", P::SOURCE_NAME, P::TARGET_NAME),
+							proof_submission_tx_tracker,
+						)
+						.await;
+					}
+					return Ok(())
+				},
 				_ = exit_signal => return Ok(()),
 			}
 		}
@@ -424,9 +637,12 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 		target_client: TC,
 		sync_params: FinalitySyncParams,
 		metrics_sync: Option<SyncLoopMetrics>,
+		clock: Arc<dyn Clock>,
 		exit_signal: impl Future<Output = ()>,
 	) -> Result<(), FailedClient> {
-		let mut finality_loop = Self::new(source_client, target_client, sync_params, metrics_sync);
+		let mut finality_loop =
+			Self::new(source_client, target_client, sync_params, metrics_sync, clock);
+		finality_loop.resume(relay_utils::state_store::load(FINALITY_LOOP_STATE));
 		finality_loop.run_until_connection_lost(exit_signal).await
 	}
 }
@@ -438,9 +654,31 @@ pub async fn run<P: FinalitySyncPipeline>(
 	sync_params: FinalitySyncParams,
 	metrics_params: MetricsParams,
 	exit_signal: impl Future<Output = ()> + 'static + Send,
+) -> Result<(), relay_utils::Error> {
+	run_with_clock(
+		source_client,
+		target_client,
+		sync_params,
+		metrics_params,
+		Arc::new(SystemClock),
+		exit_signal,
+	)
+	.await
+}
+
+/// Run finality proofs synchronization loop, using given clock to measure all delays (ticks,
+/// retry and reconnect delays).
+pub async fn run_with_clock<P: FinalitySyncPipeline>(
+	source_client: impl SourceClient<P>,
+	target_client: impl TargetClient<P>,
+	sync_params: FinalitySyncParams,
+	metrics_params: MetricsParams,
+	clock: Arc<dyn Clock>,
+	exit_signal: impl Future<Output = ()> + 'static + Send,
 ) -> Result<(), relay_utils::Error> {
 	let exit_signal = exit_signal.shared();
 	relay_utils::relay_loop(source_client, target_client)
+		.clock(clock.clone())
 		.with_metrics(metrics_params)
 		.loop_metric(SyncLoopMetrics::new(
 			Some(&metrics_prefix::<P>()),
@@ -455,6 +693,7 @@ pub async fn run<P: FinalitySyncPipeline>(
 				target_client,
 				sync_params.clone(),
 				metrics,
+				clock.clone(),
 				exit_signal.clone(),
 			)
 		})
@@ -468,7 +707,9 @@ mod tests {
 	use crate::mock::*;
 	use futures::{FutureExt, StreamExt};
 	use parking_lot::Mutex;
-	use relay_utils::{FailedClient, HeaderId, TrackedTransactionStatus};
+	use relay_utils::{
+		clock::TestClock, simulation::TestRng, FailedClient, HeaderId, TrackedTransactionStatus,
+	};
 	use std::{collections::HashMap, sync::Arc};
 
 	fn prepare_test_clients(
@@ -483,6 +724,7 @@ mod tests {
 				}
 			});
 		let clients_data = Arc::new(Mutex::new(ClientsData {
+			source_fails: false,
 			source_best_block_number: 10,
 			source_headers,
 			source_proofs: vec![TestFinalityProof(12), TestFinalityProof(14)],
@@ -508,6 +750,7 @@ mod tests {
 			recent_finality_proofs_limit: 1024,
 			stall_timeout: Duration::from_secs(1),
 			only_mandatory_headers: false,
+			warp_sync: false,
 		}
 	}
 
@@ -537,6 +780,7 @@ mod tests {
 			target_client,
 			sync_params,
 			None,
+			Arc::new(SystemClock),
 			exit_receiver.into_future().map(|(_, _)| ()),
 		));
 
@@ -591,6 +835,75 @@ mod tests {
 		);
 	}
 
+	fn resumed_finality_loop(
+		best_submitted_number: u64,
+		clock: TestClock,
+	) -> (
+		FinalityLoop<TestFinalitySyncPipeline, TestSourceClient, TestTargetClient>,
+		Arc<Mutex<ClientsData>>,
+	) {
+		let (exit_sender, _) = futures::channel::mpsc::unbounded();
+		let (source_client, target_client) = prepare_test_clients(
+			exit_sender,
+			|_| false,
+			vec![
+				(5, (TestSourceHeader(false, 5, 5), None)),
+				(6, (TestSourceHeader(false, 6, 6), None)),
+				(7, (TestSourceHeader(false, 7, 7), Some(TestFinalityProof(7)))),
+				(8, (TestSourceHeader(false, 8, 8), None)),
+				(9, (TestSourceHeader(false, 9, 9), None)),
+				(10, (TestSourceHeader(false, 10, 10), None)),
+			]
+			.into_iter()
+			.collect(),
+		);
+		let clients_data = source_client.data.clone();
+		let mut finality_loop = FinalityLoop::new(
+			source_client,
+			target_client,
+			test_sync_params(),
+			None,
+			Arc::new(clock),
+		);
+		finality_loop
+			.resume(Some(FinalityLoopState { best_submitted_number: Some(best_submitted_number) }));
+		(finality_loop, clients_data)
+	}
+
+	#[test]
+	fn header_submitted_before_restart_is_not_submitted_again() {
+		let clock = TestClock::new();
+		let (mut finality_loop, clients_data) = resumed_finality_loop(7, clock.clone());
+
+		async_std::task::block_on(async {
+			// header#7 has been submitted before restart => we're waiting for it
+			assert!(finality_loop.run_iteration().await.unwrap().is_none());
+			assert!(clients_data.lock().target_headers.is_empty());
+
+			// transaction is considered lost after `stall_timeout` => header#7 is submitted again
+			clock.advance(test_sync_params().stall_timeout);
+			assert!(finality_loop.run_iteration().await.unwrap().is_some());
+		});
+		assert_eq!(
+			clients_data.lock().target_headers,
+			vec![(TestSourceHeader(false, 7, 7), TestFinalityProof(7))],
+		);
+	}
+
+	#[test]
+	fn header_is_submitted_if_header_submitted_before_restart_is_imported() {
+		let (mut finality_loop, clients_data) = resumed_finality_loop(5, TestClock::new());
+
+		// header#5 is already imported by the target node => we don't need to wait
+		async_std::task::block_on(async {
+			assert!(finality_loop.run_iteration().await.unwrap().is_some());
+		});
+		assert_eq!(
+			clients_data.lock().target_headers,
+			vec![(TestSourceHeader(false, 7, 7), TestFinalityProof(7))],
+		);
+	}
+
 	fn run_only_mandatory_headers_mode_test(
 		only_mandatory_headers: bool,
 		has_mandatory_headers: bool,
@@ -618,8 +931,10 @@ mod tests {
 					recent_finality_proofs_limit: 0,
 					stall_timeout: Duration::from_secs(0),
 					only_mandatory_headers,
+					warp_sync: false,
 				},
 				None,
+				Arc::new(SystemClock),
 			);
 			let info = SyncInfo {
 				best_number_at_source: 10,
@@ -662,6 +977,74 @@ mod tests {
 		);
 	}
 
+	fn run_warp_sync_mode_test(
+		only_mandatory_headers: bool,
+		has_mandatory_headers: bool,
+	) -> Option<JustifiedHeader<TestFinalitySyncPipeline>> {
+		let (exit_sender, _) = futures::channel::mpsc::unbounded();
+		let (source_client, target_client) = prepare_test_clients(
+			exit_sender,
+			|_| false,
+			vec![
+				(6, (TestSourceHeader(false, 6, 6), None)),
+				(7, (TestSourceHeader(false, 7, 7), Some(TestFinalityProof(7)))),
+				(8, (TestSourceHeader(has_mandatory_headers, 8, 8), Some(TestFinalityProof(8)))),
+				(9, (TestSourceHeader(false, 9, 9), None)),
+				(10, (TestSourceHeader(false, 10, 10), None)),
+			]
+			.into_iter()
+			.collect(),
+		);
+		source_client.data.lock().source_proofs = vec![TestFinalityProof(9), TestFinalityProof(12)];
+		async_std::task::block_on(async {
+			let mut finality_loop = FinalityLoop::new(
+				source_client,
+				target_client,
+				FinalitySyncParams {
+					tick: Duration::from_secs(0),
+					recent_finality_proofs_limit: 0,
+					stall_timeout: Duration::from_secs(0),
+					only_mandatory_headers,
+					warp_sync: true,
+				},
+				None,
+				Arc::new(SystemClock),
+			);
+			finality_loop.ensure_finality_proofs_stream().await.unwrap();
+			let info = SyncInfo {
+				best_number_at_source: 10,
+				best_number_at_target: 5,
+				is_using_same_fork: true,
+			};
+			finality_loop.select_header_to_submit(&info).await.unwrap()
+		})
+	}
+
+	#[test]
+	fn select_header_to_submit_warps_to_mandatory_header() {
+		for only_mandatory_headers in [false, true] {
+			assert_eq!(
+				run_warp_sync_mode_test(only_mandatory_headers, true),
+				Some(JustifiedHeader {
+					header: TestSourceHeader(true, 8, 8),
+					proof: TestFinalityProof(8)
+				}),
+			);
+		}
+	}
+
+	#[test]
+	fn select_header_to_submit_warps_to_best_header_with_streamed_proof() {
+		assert_eq!(run_warp_sync_mode_test(true, false), None);
+		assert_eq!(
+			run_warp_sync_mode_test(false, false),
+			Some(JustifiedHeader {
+				header: TestSourceHeader(false, 9, 9),
+				proof: TestFinalityProof(9)
+			}),
+		);
+	}
+
 	#[test]
 	fn different_forks_at_source_and_at_target_are_detected() {
 		let (exit_sender, _exit_receiver) = futures::channel::mpsc::unbounded();
@@ -687,10 +1070,135 @@ mod tests {
 				target_client,
 				test_sync_params(),
 				Some(metrics_sync.clone()),
+				Arc::new(SystemClock),
 			);
 			finality_loop.run_iteration().await.unwrap()
 		});
 
 		assert!(!metrics_sync.is_using_same_fork());
 	}
+
+	/// Adversarial conditions, applied to clients on every call.
+	#[derive(Clone, Copy, Debug)]
+	enum SyncScenario {
+		/// Source node is randomly failing RPC requests.
+		RpcFlaps,
+		/// Some other relayer is submitting finality proofs concurrently with our relayer.
+		CompetingRelayer,
+		/// Some of our transactions are lost.
+		LostTransactions,
+	}
+
+	/// Returns source header with given number. Every 5th header has persistent finality proof
+	/// and every 20th header is mandatory.
+	fn scripted_source_header(number: TestNumber) -> (TestSourceHeader, Option<TestFinalityProof>) {
+		(
+			TestSourceHeader(number % 20 == 0, number, number),
+			if number % 5 == 0 { Some(TestFinalityProof(number)) } else { None },
+		)
+	}
+
+	/// Run finality loop under given scenario until the source header#100 is synced.
+	///
+	/// The loop is running using the `TestClock`, which is advanced by the loop tick once the
+	/// loop is sleeping.
+	fn run_scripted_sync_loop(scenario: SyncScenario, seed: u64) -> ClientsData {
+		const BEST_SOURCE_NUMBER: TestNumber = 100;
+		const MAX_DURATION: Duration = Duration::from_secs(60 * 60);
+
+		let clock = TestClock::new();
+		let started_at = clock.now();
+		let loop_clock = clock.clone();
+		let rng = Mutex::new(TestRng::new(seed));
+		let (exit_sender, exit_receiver) = futures::channel::mpsc::unbounded();
+		let (source_client, target_client) = prepare_test_clients(
+			exit_sender,
+			move |data| {
+				assert!(
+					loop_clock.now() - started_at < MAX_DURATION,
+					"Scenario {scenario:?} with seed {seed} has not converged in {MAX_DURATION:?}",
+				);
+
+				// new source header is finalized on every call
+				if data.source_best_block_number < BEST_SOURCE_NUMBER {
+					data.source_best_block_number += 1;
+					let number = data.source_best_block_number;
+					data.source_headers.insert(number, scripted_source_header(number));
+				}
+
+				let mut rng = rng.lock();
+				match scenario {
+					SyncScenario::RpcFlaps => data.source_fails = rng.one_in(4),
+					SyncScenario::CompetingRelayer => {
+						let best_with_proof =
+							data.source_best_block_number - data.source_best_block_number % 5;
+						if best_with_proof > data.target_best_block_id.0 && rng.one_in(10) {
+							data.target_best_block_id = HeaderId(best_with_proof, best_with_proof);
+						}
+					},
+					SyncScenario::LostTransactions =>
+						data.target_transaction_tracker = TestTransactionTracker(if rng.one_in(3) {
+							TrackedTransactionStatus::Lost
+						} else {
+							TrackedTransactionStatus::Finalized(Default::default())
+						}),
+				}
+
+				data.target_best_block_id.0 == BEST_SOURCE_NUMBER
+			},
+			(6..=10).map(|number| (number, scripted_source_header(number))).collect(),
+		);
+		let sync_params = FinalitySyncParams { tick: Duration::from_secs(1), ..test_sync_params() };
+
+		let clients_data = source_client.data.clone();
+		let result = async_std::task::block_on(clock.drive(
+			run_with_clock(
+				source_client,
+				target_client,
+				sync_params.clone(),
+				MetricsParams::disabled(),
+				Arc::new(clock.clone()),
+				exit_receiver.into_future().map(|(_, _)| ()),
+			),
+			1,
+			sync_params.tick,
+		));
+		assert!(result.is_ok());
+
+		let clients_data = clients_data.lock().clone();
+		clients_data
+	}
+
+	fn assert_scripted_sync_loop_works(scenario: SyncScenario) {
+		for seed in 0..3 {
+			let data = run_scripted_sync_loop(scenario, seed);
+			assert_eq!(data.target_best_block_id.0, 100);
+			// we never submit headers without finality proofs
+			assert!(data
+				.target_headers
+				.iter()
+				.all(|(header, proof)| header.1 % 5 == 0 && proof.0 == header.1));
+			// if other relayers are not submitting headers, we submit all mandatory headers
+			if !matches!(scenario, SyncScenario::CompetingRelayer) {
+				for mandatory in (20..=100).step_by(20) {
+					assert!(data.target_headers.iter().any(|(header, _)| header.1 == mandatory));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn finality_sync_loop_works_with_rpc_flaps() {
+		assert_scripted_sync_loop_works(SyncScenario::RpcFlaps);
+	}
+
+	#[test]
+	fn finality_sync_loop_works_with_competing_relayer() {
+		assert_scripted_sync_loop_works(SyncScenario::CompetingRelayer);
+	}
+
+	#[test]
+	fn finality_sync_loop_works_with_lost_transactions() {
+		assert_scripted_sync_loop_works(SyncScenario::LostTransactions);
+	}
 }
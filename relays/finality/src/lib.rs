@@ -87,4 +87,14 @@ where
 			_ => Ok(()),
 		}
 	}
+
+	fn error_code(&self) -> &'static str {
+		match *self {
+			Error::Source(ref error) => error.error_code(),
+			Error::Target(ref error) => error.error_code(),
+			Error::MissingMandatoryFinalityProof(_) => "missing_finality_proof",
+			Error::ProofSubmissionTxFailed { .. } => "submission_failed",
+			Error::ProofSubmissionTxLost => "transaction_lost",
+		}
+	}
 }
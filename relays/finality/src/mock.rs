@@ -114,6 +114,7 @@ impl FinalityProof<TestNumber> for TestFinalityProof {
 
 #[derive(Debug, Clone, Default)]
 pub struct ClientsData {
+	pub source_fails: bool,
 	pub source_best_block_number: TestNumber,
 	pub source_headers: HashMap<TestNumber, (TestSourceHeader, Option<TestFinalityProof>)>,
 	pub source_proofs: Vec<TestFinalityProof>,
@@ -154,6 +155,9 @@ impl SourceClient<TestFinalitySyncPipeline> for TestSourceClient {
 	async fn best_finalized_block_number(&self) -> Result<TestNumber, TestError> {
 		let mut data = self.data.lock();
 		(self.on_method_call)(&mut data);
+		if data.source_fails {
+			return Err(TestError::NonConnection)
+		}
 		Ok(data.source_best_block_number)
 	}
 
@@ -163,8 +167,27 @@ impl SourceClient<TestFinalitySyncPipeline> for TestSourceClient {
 	) -> Result<(TestSourceHeader, Option<TestFinalityProof>), TestError> {
 		let mut data = self.data.lock();
 		(self.on_method_call)(&mut data);
+		if data.source_fails {
+			return Err(TestError::NonConnection)
+		}
 		data.source_headers.get(&number).cloned().ok_or(TestError::NonConnection)
 	}
+
+	async fn find_mandatory_header_number(
+		&self,
+		after: TestNumber,
+		up_to: TestNumber,
+	) -> Result<Option<TestNumber>, TestError> {
+		let mut data = self.data.lock();
+		(self.on_method_call)(&mut data);
+		Ok(data
+			.source_headers
+			.values()
+			.filter(|(header, _)| header.is_mandatory())
+			.map(|(header, _)| header.number())
+			.filter(|number| *number > after && *number <= up_to)
+			.min())
+	}
 }
 
 #[derive(Clone)]
@@ -201,7 +224,10 @@ impl TargetClient<TestFinalitySyncPipeline> for TestTargetClient {
 	) -> Result<TestTransactionTracker, TestError> {
 		let mut data = self.data.lock();
 		(self.on_method_call)(&mut data);
-		data.target_best_block_id = HeaderId(header.number(), header.hash());
+		// lost transactions are never included into the target chain
+		if let TrackedTransactionStatus::Finalized(_) = data.target_transaction_tracker.0 {
+			data.target_best_block_id = HeaderId(header.number(), header.hash());
+		}
 		data.target_headers.push((header, proof));
 		(self.on_method_call)(&mut data);
 		Ok(data.target_transaction_tracker.clone())
@@ -0,0 +1,245 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Builder that allows starting bridge relays from other binaries.
+//!
+//! The builder is the supported entry point for projects that want to embed relays into their
+//! own processes instead of running the `substrate-relay` binary:
+//!
+//! ```ignore
+//! let metrics_params = MetricsParams::new(address, version, commit, labels)?;
+//! // custom metrics may be registered in the `metrics_params.registry` here
+//! BridgeRelayBuilder::new()
+//!     .with_metrics_params(metrics_params)
+//!     .with_finality_relay::<MillauFinalityToRialto>(millau, rialto, false, false, tx_params)
+//!     .with_messages_relay(messages_relay_params)
+//!     .with_shutdown_signal(ctrl_c())
+//!     .run()
+//!     .await?;
+//! ```
+
+use crate::{
+	finality::SubstrateFinalitySyncPipeline,
+	messages::{MessagesRelayParams, SubstrateMessageLane},
+	parachains::{ParachainsPipelineAdapter, SubstrateParachainsPipeline},
+	TransactionParams,
+};
+
+use futures::{
+	future::BoxFuture, stream::FuturesUnordered, Future, FutureExt, StreamExt, TryFutureExt,
+};
+use pallet_bridge_parachains::RelayBlockNumber;
+use parachains_relay::parachains_loop::{SourceClient, TargetClient};
+use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, BalanceOf, Chain, Client};
+use relay_utils::metrics::MetricsParams;
+use sp_core::Pair;
+
+/// Builder of the set of bridge relays, running in the same process.
+///
+/// All relays are sharing the same metrics registry and are exposing their metrics at the single
+/// endpoint. Relays are started by the [`BridgeRelayBuilder::run`] call, which returns when any
+/// of relays fails or when all relays have stopped.
+pub struct BridgeRelayBuilder {
+	metrics_params: MetricsParams,
+	relays: Vec<BoxFuture<'static, anyhow::Result<()>>>,
+	shutdown_signal: Option<BoxFuture<'static, ()>>,
+}
+
+impl Default for BridgeRelayBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl BridgeRelayBuilder {
+	/// Create new builder without any relays. Metrics are not exposed by default.
+	pub fn new() -> Self {
+		BridgeRelayBuilder {
+			metrics_params: MetricsParams::disabled(),
+			relays: Vec::new(),
+			shutdown_signal: None,
+		}
+	}
+
+	/// Use given metrics parameters for all relays.
+	///
+	/// Custom metrics may be registered in the `metrics_params.registry` before or after this
+	/// call - they'll be exposed along with relay metrics. Relays, added before this call, will
+	/// be using the previous registry, so this method shall be called first.
+	#[must_use]
+	pub fn with_metrics_params(mut self, metrics_params: MetricsParams) -> Self {
+		self.metrics_params = metrics_params;
+		self
+	}
+
+	/// Returns metrics parameters, that are used by all relays.
+	pub fn metrics_params(&self) -> &MetricsParams {
+		&self.metrics_params
+	}
+
+	/// Request graceful shutdown of all relays when given future resolves.
+	#[must_use]
+	pub fn with_shutdown_signal(
+		mut self,
+		shutdown_signal: impl Future<Output = ()> + Send + 'static,
+	) -> Self {
+		self.shutdown_signal = Some(shutdown_signal.boxed());
+		self
+	}
+
+	/// Add finality relay.
+	#[must_use]
+	pub fn with_finality_relay<P: SubstrateFinalitySyncPipeline>(
+		self,
+		source_client: impl Client<P::SourceChain>,
+		target_client: impl Client<P::TargetChain>,
+		only_mandatory_headers: bool,
+		warp_sync: bool,
+		transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
+	) -> Self {
+		let metrics_params = self.relay_metrics_params();
+		self.with_relay(crate::finality::run::<P>(
+			source_client,
+			target_client,
+			only_mandatory_headers,
+			warp_sync,
+			transaction_params,
+			metrics_params,
+		))
+	}
+
+	/// Add parachains relay.
+	#[must_use]
+	pub fn with_parachains_relay<P: SubstrateParachainsPipeline>(
+		self,
+		source_client: impl SourceClient<ParachainsPipelineAdapter<P>>,
+		target_client: impl TargetClient<ParachainsPipelineAdapter<P>>,
+	) -> Self
+	where
+		P::SourceRelayChain: Chain<BlockNumber = RelayBlockNumber>,
+	{
+		let metrics_params = self.relay_metrics_params();
+		self.with_relay(
+			parachains_relay::parachains_loop::run(
+				source_client,
+				target_client,
+				metrics_params,
+				futures::future::pending(),
+			)
+			.map_err(|e| anyhow::format_err!("{}", e)),
+		)
+	}
+
+	/// Add messages relay.
+	///
+	/// The `metrics_params` of given relay parameters are replaced with the builder parameters.
+	#[must_use]
+	pub fn with_messages_relay<P, SourceClnt, TargetClnt>(
+		self,
+		mut params: MessagesRelayParams<P, SourceClnt, TargetClnt>,
+	) -> Self
+	where
+		P: SubstrateMessageLane,
+		SourceClnt: Client<P::SourceChain>,
+		TargetClnt: Client<P::TargetChain>,
+		AccountIdOf<P::SourceChain>: From<<AccountKeyPairOf<P::SourceChain> as Pair>::Public>,
+		AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
+		BalanceOf<P::SourceChain>: TryFrom<BalanceOf<P::TargetChain>>,
+	{
+		params.metrics_params = self.relay_metrics_params();
+		self.with_relay(crate::messages::run(params))
+	}
+
+	/// Add custom relay (or any other task) that shall run along with other relays.
+	///
+	/// If the task fails, all other relays are stopped.
+	#[must_use]
+	pub fn with_relay(
+		mut self,
+		relay: impl Future<Output = anyhow::Result<()>> + Send + 'static,
+	) -> Self {
+		self.relays.push(relay.boxed());
+		self
+	}
+
+	/// Expose metrics and run all relays.
+	///
+	/// Returns when any of relays fails, or when all relays have stopped (e.g. after graceful
+	/// shutdown has been requested).
+	pub async fn run(self) -> anyhow::Result<()> {
+		relay_utils::relay_metrics(self.metrics_params)
+			.expose()
+			.await
+			.map_err(|e| anyhow::format_err!("{}", e))?;
+
+		if let Some(shutdown_signal) = self.shutdown_signal {
+			async_std::task::spawn(async move {
+				shutdown_signal.await;
+				log::info!(target: "bridge", "Shutdown signal received. Stopping relays");
+				relay_utils::shutdown::request_shutdown();
+			});
+		}
+
+		let mut relays = self.relays.into_iter().collect::<FuturesUnordered<_>>();
+		while let Some(result) = relays.next().await {
+			result?;
+		}
+
+		Ok(())
+	}
+
+	/// Returns metrics parameters for the single relay. Metrics are exposed by the builder, so
+	/// relays only need to register their metrics in the shared registry.
+	fn relay_metrics_params(&self) -> MetricsParams {
+		self.metrics_params.clone().disable()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	};
+
+	#[async_std::test]
+	async fn builder_runs_all_relays() {
+		let started = Arc::new(AtomicUsize::new(0));
+		let relay = |started: Arc<AtomicUsize>| async move {
+			started.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		};
+
+		BridgeRelayBuilder::new()
+			.with_relay(relay(started.clone()))
+			.with_relay(relay(started.clone()))
+			.run()
+			.await
+			.unwrap();
+		assert_eq!(started.load(Ordering::SeqCst), 2);
+	}
+
+	#[async_std::test]
+	async fn builder_stops_when_relay_fails() {
+		let result = BridgeRelayBuilder::new()
+			.with_relay(futures::future::pending())
+			.with_relay(async { Err(anyhow::format_err!("relay has failed")) })
+			.run()
+			.await;
+		assert_eq!(result.unwrap_err().to_string(), "relay has failed");
+	}
+}
@@ -23,14 +23,20 @@ mod target;
 use crate::finality_base::{engine::Engine, SubstrateFinalityPipeline, SubstrateFinalityProof};
 
 use async_trait::async_trait;
+use bp_header_chain::{FinalityProof, FindEquivocations};
 use bp_runtime::{AccountIdOf, BlockNumberOf, HashOf};
+use codec::Decode;
 use equivocation_detector::EquivocationDetectionPipeline;
 use finality_relay::FinalityPipeline;
+use num_traits::{One, Zero};
 use pallet_grandpa::{Call as GrandpaCall, Config as GrandpaConfig};
-use relay_substrate_client::{AccountKeyPairOf, CallOf, Chain, ChainWithTransactions};
+use relay_substrate_client::{
+	AccountKeyPairOf, BlockWithJustification, CallOf, Chain, ChainWithTransactions, Client,
+	Error as SubstrateError,
+};
 use sp_core::Pair;
 use sp_runtime::traits::{Block, Header};
-use std::marker::PhantomData;
+use std::{fmt::Debug, marker::PhantomData, ops::RangeInclusive};
 
 /// Convenience trait that adds bounds to `SubstrateEquivocationDetectionPipeline`.
 pub trait BaseSubstrateEquivocationDetectionPipeline:
@@ -148,3 +154,111 @@ where
 		.into()
 	}
 }
+
+/// Equivocation, found in the finality proof of the source chain header.
+#[derive(Debug)]
+pub struct FoundEquivocation<P: SubstrateFinalityPipeline> {
+	/// Number of the source header, finality proofs of which are conflicting.
+	pub source_header_number: BlockNumberOf<P::SourceChain>,
+	/// Number of the target block where the source header has been imported.
+	pub target_block_number: BlockNumberOf<P::TargetChain>,
+	/// The equivocation proof.
+	pub equivocation: EquivocationProofOf<P>,
+}
+
+/// Scan source headers from the given range, that have been imported by the target chain, for
+/// equivocations.
+///
+/// Finality proofs that have been submitted to the target chain are compared with finality proofs
+/// stored at the source chain. Source headers without stored finality proofs are skipped. The
+/// target chain is scanned backwards, starting from its best finalized block, until we see a
+/// source header that is below the range.
+pub async fn find_equivocations_in_range<P: SubstrateFinalityPipeline>(
+	source_client: &impl Client<P::SourceChain>,
+	target_client: &impl Client<P::TargetChain>,
+	range: RangeInclusive<BlockNumberOf<P::SourceChain>>,
+) -> Result<Vec<FoundEquivocation<P>>, SubstrateError>
+where
+	<EquivocationsFinderOf<P> as FindEquivocations<
+		FinalityProoffOf<P>,
+		FinalityVerificationContextfOf<P>,
+		EquivocationProofOf<P>,
+	>>::Error: Debug,
+{
+	let mut equivocations = Vec::new();
+	let mut target_block_number = target_client.best_finalized_header_number().await?;
+	while !target_block_number.is_zero() {
+		let target_block_hash = target_client.header_hash_by_number(target_block_number).await?;
+		let synced_headers_finality_info =
+			P::FinalityEngine::synced_headers_finality_info(target_client, target_block_hash)
+				.await?;
+
+		let mut is_below_range = false;
+		for synced_header_finality_info in synced_headers_finality_info {
+			let synced_proof = synced_header_finality_info.finality_proof;
+			let source_header_number = synced_proof.target_header_number();
+			if source_header_number < *range.start() {
+				is_below_range = true;
+				continue
+			}
+			if source_header_number > *range.end() {
+				continue
+			}
+
+			let source_proof =
+				match source_finality_proof::<P>(source_client, source_header_number).await? {
+					Some(source_proof) => source_proof,
+					None => continue,
+				};
+
+			// the synced proof has been verified using the context that the target chain had
+			// before importing the header
+			let parent_block_hash =
+				target_client.header_hash_by_number(target_block_number - One::one()).await?;
+			let verification_context =
+				P::FinalityEngine::finality_verification_context(target_client, parent_block_hash)
+					.await?;
+			let found = EquivocationsFinderOf::<P>::find_equivocations(
+				&verification_context,
+				&synced_proof,
+				&[source_proof],
+			)
+			.map_err(|e| {
+				SubstrateError::Custom(format!(
+					"Failed to look for equivocations in {} header {:?} finality proof: {:?}",
+					P::SourceChain::NAME,
+					source_header_number,
+					e,
+				))
+			})?;
+
+			equivocations.extend(found.into_iter().map(|equivocation| FoundEquivocation {
+				source_header_number,
+				target_block_number,
+				equivocation,
+			}));
+		}
+
+		if is_below_range {
+			break
+		}
+
+		target_block_number = target_block_number - One::one();
+	}
+
+	Ok(equivocations)
+}
+
+/// Read finality proof of the given source header, stored at the source chain.
+async fn source_finality_proof<P: SubstrateFinalityPipeline>(
+	source_client: &impl Client<P::SourceChain>,
+	number: BlockNumberOf<P::SourceChain>,
+) -> Result<Option<FinalityProoffOf<P>>, SubstrateError> {
+	let hash = source_client.header_hash_by_number(number).await?;
+	let signed_block = source_client.block_by_hash(hash).await?;
+	signed_block
+		.justification(P::FinalityEngine::ID)
+		.map(|raw_justification| FinalityProoffOf::<P>::decode(&mut raw_justification.as_slice()))
+		.transpose()
+		.map_err(SubstrateError::ResponseParseFailed)
+}
@@ -33,6 +33,7 @@ use relay_substrate_client::{
 	AccountKeyPairOf, Client, Error, TransactionTracker, UnsignedTransaction,
 };
 use relay_utils::relay_loop::Client as RelayClient;
+use sp_runtime::SaturatedConversion;
 
 /// Substrate node as equivocation source.
 pub struct SubstrateEquivocationSource<P: SubstrateEquivocationDetectionPipeline, SourceClnt> {
@@ -92,16 +93,20 @@ impl<P: SubstrateEquivocationDetectionPipeline, SourceClnt: Client<P::SourceChai
 			P::FinalityEngine::generate_source_key_ownership_proof(&self.client, at, &equivocation)
 				.await?;
 
+		self.transaction_params.ensure_within_spending_budget(&self.client).await?;
 		let mortality = self.transaction_params.mortality;
+		let tip = relay_utils::control::tip().saturated_into();
 		let call = P::ReportEquivocationCallBuilder::build_report_equivocation_call(
 			equivocation,
 			key_owner_proof,
 		);
+		let call = self.transaction_params.wrap_call(call);
 		self.client
 			.submit_and_watch_signed_extrinsic(
 				&self.transaction_params.signer,
 				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
+					Ok(UnsignedTransaction::new(call, transaction_nonce)
+						.tip(tip)
 						.era(TransactionEra::new(best_block_id, mortality)))
 				},
 			)
@@ -49,12 +49,21 @@ pub enum Error<Hash: Debug + MaybeDisplay, HeaderNumber: Debug + MaybeDisplay> {
 	/// Failed to retrieve GRANDPA authorities at the given header from the source chain.
 	#[error("Failed to retrive {0} GRANDPA authorities set at header {1}: {2:?}")]
 	RetrieveAuthorities(&'static str, Hash, client::Error),
+	/// Failed to retrieve GRANDPA authorities set id at the given header from the source chain.
+	#[error("Failed to retrive {0} GRANDPA authorities set id at header {1}: {2:?}")]
+	RetrieveAuthoritiesSetId(&'static str, Hash, client::Error),
 	/// Failed to decode GRANDPA authorities at the given header of the source chain.
 	#[error("Failed to decode {0} GRANDPA authorities set at header {1}: {2:?}")]
 	DecodeAuthorities(&'static str, Hash, codec::Error),
 	/// Failed to retrieve header by the hash from the source chain.
 	#[error("Failed to retrieve {0} header with hash {1}: {2:?}")]
 	RetrieveHeader(&'static str, Hash, client::Error),
+	/// Failed to retrieve best finalized header number from the source chain.
+	#[error("Failed to retrieve best finalized {0} header number: {1:?}")]
+	RetrieveBestFinalizedHeader(&'static str, client::Error),
+	/// The header is not finalized or is not on the canonical chain of the source chain.
+	#[error("The {0} header {1}/{2} is not finalized")]
+	NotFinalizedHeader(&'static str, HeaderNumber, Hash),
 	/// Failed to submit signed extrinsic from to the target chain.
 	#[error(
 		"Failed to retrieve `is_initialized` flag of the with-{0} finality pallet at {1}: {2:?}"
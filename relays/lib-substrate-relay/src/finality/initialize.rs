@@ -33,6 +33,9 @@ use relay_utils::{TrackedTransactionStatus, TransactionTracker};
 use sp_runtime::traits::Header as HeaderT;
 
 /// Submit headers-bridge initialization transaction.
+///
+/// If `initial_header` is `None`, the bridge is initialized with the next finalized source header.
+/// Otherwise, given (finalized) header is used.
 pub async fn initialize<
 	E: Engine<SourceChain>,
 	SourceChain: Chain,
@@ -42,6 +45,7 @@ pub async fn initialize<
 	source_client: impl Client<SourceChain>,
 	target_client: impl Client<TargetChain>,
 	target_signer: AccountKeyPairOf<TargetChain>,
+	initial_header: Option<SourceChain::Hash>,
 	prepare_initialize_transaction: F,
 	dry_run: bool,
 ) where
@@ -57,6 +61,7 @@ pub async fn initialize<
 		source_client,
 		target_client,
 		target_signer,
+		initial_header,
 		prepare_initialize_transaction,
 		dry_run,
 	)
@@ -104,6 +109,7 @@ async fn do_initialize<
 	source_client: impl Client<SourceChain>,
 	target_client: impl Client<TargetChain>,
 	target_signer: AccountKeyPairOf<TargetChain>,
+	initial_header: Option<SourceChain::Hash>,
 	prepare_initialize_transaction: F,
 	dry_run: bool,
 ) -> Result<
@@ -134,7 +140,11 @@ where
 		}
 	}
 
-	let initialization_data = E::prepare_initialization_data(source_client).await?;
+	let initialization_data = match initial_header {
+		Some(initial_header) =>
+			E::prepare_initialization_data_at(source_client, initial_header).await?,
+		None => E::prepare_initialization_data(source_client).await?,
+	};
 	log::info!(
 		target: "bridge",
 		"Prepared initialization data for {}-headers bridge at {}: {:?}",
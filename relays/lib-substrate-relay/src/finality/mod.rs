@@ -31,7 +31,7 @@ use relay_substrate_client::{
 	transaction_stall_timeout, AccountIdOf, AccountKeyPairOf, BlockNumberOf, CallOf, Chain,
 	ChainWithTransactions, Client, HashOf, HeaderOf, SyncHeader,
 };
-use relay_utils::metrics::MetricsParams;
+use relay_utils::metrics::{Metric, MetricsParams};
 use sp_core::Pair;
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -186,6 +186,7 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 	source_client: impl Client<P::SourceChain>,
 	target_client: impl Client<P::TargetChain>,
 	only_mandatory_headers: bool,
+	warp_sync: bool,
 	transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
 	metrics_params: MetricsParams,
 ) -> anyhow::Result<()> {
@@ -196,6 +197,8 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 		P::TargetChain::NAME,
 	);
 
+	transaction_params.start_spending_budget(target_client.clone(), &metrics_params.registry)?;
+
 	finality_relay::run(
 		SubstrateFinalitySource::<P, _>::new(source_client, None),
 		SubstrateFinalityTarget::<P, _>::new(target_client, transaction_params.clone()),
@@ -211,6 +214,7 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 				relay_utils::STALL_TIMEOUT,
 			),
 			only_mandatory_headers,
+			warp_sync,
 		},
 		metrics_params,
 		futures::future::pending(),
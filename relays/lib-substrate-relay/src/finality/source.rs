@@ -238,6 +238,44 @@ impl<P: SubstrateFinalitySyncPipeline, SourceClnt: Client<P::SourceChain>>
 	> {
 		header_and_finality_proof::<P>(&self.client, number).await
 	}
+
+	async fn find_mandatory_header_number(
+		&self,
+		after: BlockNumberOf<P::SourceChain>,
+		up_to: BlockNumberOf<P::SourceChain>,
+	) -> Result<Option<BlockNumberOf<P::SourceChain>>, Error> {
+		// every mandatory header changes the authorities set id, so the first header with
+		// different set id is the mandatory header. This requires the source node to keep
+		// the state of all finalized headers (i.e. to be an archive node)
+		if up_to <= after {
+			return Ok(None)
+		}
+		let set_id_after = authorities_set_id_at::<P>(&self.client, after).await?;
+		if authorities_set_id_at::<P>(&self.client, up_to).await? == set_id_after {
+			return Ok(None)
+		}
+
+		// binary search: set id at `low` is the same as at `after` and it is different at `high`
+		let (mut low, mut high) = (after, up_to);
+		while high - low > One::one() {
+			let middle = low + (high - low) / 2u32.into();
+			if authorities_set_id_at::<P>(&self.client, middle).await? == set_id_after {
+				low = middle;
+			} else {
+				high = middle;
+			}
+		}
+
+		Ok(Some(high))
+	}
+}
+
+async fn authorities_set_id_at<P: SubstrateFinalitySyncPipeline>(
+	client: &impl Client<P::SourceChain>,
+	number: BlockNumberOf<P::SourceChain>,
+) -> Result<u64, Error> {
+	let header_hash = client.header_hash_by_number(number).await?;
+	P::FinalityEngine::authorities_set_id(client, header_hash).await
 }
 
 async fn header_and_finality_proof<P: SubstrateFinalitySyncPipeline>(
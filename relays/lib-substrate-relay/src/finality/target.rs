@@ -27,10 +27,11 @@ use crate::{
 use async_trait::async_trait;
 use finality_relay::TargetClient;
 use relay_substrate_client::{
-	AccountKeyPairOf, Client, Error, HeaderIdOf, HeaderOf, SyncHeader, TransactionEra,
+	AccountKeyPairOf, Chain, Client, Error, HeaderIdOf, HeaderOf, SyncHeader, TransactionEra,
 	TransactionTracker, UnsignedTransaction,
 };
 use relay_utils::relay_loop::Client as RelayClient;
+use sp_runtime::SaturatedConversion;
 
 /// Substrate client as Substrate finality target.
 pub struct SubstrateFinalityTarget<P: SubstrateFinalitySyncPipeline, TargetClnt> {
@@ -52,9 +53,20 @@ impl<P: SubstrateFinalitySyncPipeline, TargetClnt: Client<P::TargetChain>>
 	/// Ensure that the bridge pallet at target chain is active.
 	pub async fn ensure_pallet_active(&self) -> Result<(), Error> {
 		let is_halted = P::FinalityEngine::is_halted(&self.client).await?;
+		let alert_subject =
+			format!("{} finality pallet at {}", P::SourceChain::NAME, P::TargetChain::NAME);
 		if is_halted {
+			relay_utils::alerts::fire_alert(
+				relay_utils::alerts::AlertKind::PalletHalted,
+				alert_subject,
+				"pallet is halted".into(),
+			);
 			return Err(Error::BridgePalletIsHalted)
 		}
+		relay_utils::alerts::resolve_alert(
+			relay_utils::alerts::AlertKind::PalletHalted,
+			alert_subject,
+		);
 
 		let is_initialized = P::FinalityEngine::is_initialized(&self.client).await?;
 		if !is_initialized {
@@ -117,14 +129,18 @@ impl<P: SubstrateFinalitySyncPipeline, TargetClnt: Client<P::TargetChain>>
 		P::FinalityEngine::optimize_proof(&self.client, &header, &mut proof).await?;
 
 		// now we may submit optimized finality proof
+		self.transaction_params.ensure_within_spending_budget(&self.client).await?;
 		let mortality = self.transaction_params.mortality;
+		let tip = relay_utils::control::tip().saturated_into();
 		let call =
 			P::SubmitFinalityProofCallBuilder::build_submit_finality_proof_call(header, proof);
+		let call = self.transaction_params.wrap_call(call);
 		self.client
 			.submit_and_watch_signed_extrinsic(
 				&self.transaction_params.signer,
 				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
+					Ok(UnsignedTransaction::new(call, transaction_nonce)
+						.tip(tip)
 						.era(TransactionEra::new(best_block_id, mortality)))
 				},
 			)
@@ -21,13 +21,13 @@ use async_trait::async_trait;
 use bp_header_chain::{
 	justification::{
 		verify_and_optimize_justification, GrandpaEquivocationsFinder, GrandpaJustification,
-		JustificationVerificationContext,
+		JustificationVerificationContext, JustificationVerificationError,
 	},
-	AuthoritySet, ConsensusLogReader, FinalityProof, FindEquivocations, GrandpaConsensusLogReader,
-	HeaderFinalityInfo, HeaderGrandpaInfo, StoredHeaderGrandpaInfo,
+	AuthoritySet, ConsensusEngine, FindEquivocations, GrandpaConsensusEngine,
+	GrandpaConsensusLogReader, HeaderFinalityInfo, HeaderGrandpaInfo, StoredHeaderGrandpaInfo,
 };
 use bp_runtime::{BasicOperatingMode, HeaderIdProvider, OperatingMode};
-use codec::{Decode, Encode};
+use codec::Decode;
 use futures::stream::StreamExt;
 use num_traits::{One, Zero};
 use relay_substrate_client::{
@@ -39,19 +39,18 @@ use sp_core::{storage::StorageKey, Bytes};
 use sp_runtime::{scale_info::TypeInfo, traits::Header, ConsensusEngineId};
 use std::marker::PhantomData;
 
+/// Name of the runtime API method, returning current GRANDPA authorities set id.
+const SUB_API_GRANDPA_CURRENT_SET_ID: &str = "GrandpaApi_current_set_id";
+
 /// Finality engine, used by the Substrate chain.
+///
+/// Finality proof type, its verification and the consensus log reader are defined by the
+/// runtime-level `ConsensusEngine`. This trait adds everything that the relay needs to
+/// read finality data from nodes.
 #[async_trait]
-pub trait Engine<C: Chain>: Send {
-	/// Unique consensus engine identifier.
-	const ID: ConsensusEngineId;
-	/// A reader that can extract the consensus log from the header digest and interpret it.
-	type ConsensusLogReader: ConsensusLogReader;
-	/// Type of finality proofs, used by consensus engine.
-	type FinalityProof: FinalityProof<BlockNumberOf<C>> + Decode + Encode;
-	/// The context needed for verifying finality proofs.
-	type FinalityVerificationContext;
+pub trait Engine<C: Chain>: ConsensusEngine<HeaderOf<C>> + Send {
 	/// The type of the equivocation proof used by the consensus engine.
-	type EquivocationProof: Send + Sync;
+	type EquivocationProof: std::fmt::Debug + Send + Sync;
 	/// The equivocations finder.
 	type EquivocationsFinder: FindEquivocations<
 		Self::FinalityProof,
@@ -104,6 +103,15 @@ pub trait Engine<C: Chain>: Send {
 		source_client: &impl Client<C>,
 	) -> Result<Subscription<Bytes>, SubstrateError>;
 
+	/// Returns id of the authorities set that is active after given source header.
+	///
+	/// The id is changed by every mandatory header, so it may be used to find mandatory headers
+	/// without reading all headers of the source chain.
+	async fn authorities_set_id(
+		source_client: &impl Client<C>,
+		at: HashOf<C>,
+	) -> Result<u64, SubstrateError>;
+
 	/// Optimize finality proof before sending it to the target node.
 	async fn optimize_proof<TargetChain: Chain>(
 		target_client: &impl Client<TargetChain>,
@@ -116,6 +124,13 @@ pub trait Engine<C: Chain>: Send {
 		client: impl Client<C>,
 	) -> Result<Self::InitializationData, Error<HashOf<C>, BlockNumberOf<C>>>;
 
+	/// Prepare initialization data for the finality bridge pallet, using given finalized header
+	/// as the initial header.
+	async fn prepare_initialization_data_at(
+		client: impl Client<C>,
+		header_hash: HashOf<C>,
+	) -> Result<Self::InitializationData, Error<HashOf<C>, BlockNumberOf<C>>>;
+
 	/// Get the context needed for validating a finality proof.
 	async fn finality_verification_context<TargetChain: Chain>(
 		target_client: &impl Client<TargetChain>,
@@ -167,14 +182,41 @@ impl<C: ChainWithGrandpa> Grandpa<C> {
 			.await
 			.map_err(|err| Error::RetrieveAuthorities(C::NAME, header_hash, err))
 	}
+
+	/// Read GRANDPA authorities set id at given header.
+	async fn source_authorities_set_id(
+		source_client: &impl Client<C>,
+		header_hash: C::Hash,
+	) -> Result<sp_consensus_grandpa::SetId, Error<HashOf<C>, BlockNumberOf<C>>> {
+		source_client
+			.state_call(header_hash, SUB_API_GRANDPA_CURRENT_SET_ID.to_string(), ())
+			.await
+			.map_err(|err| Error::RetrieveAuthoritiesSetId(C::NAME, header_hash, err))
+	}
 }
 
-#[async_trait]
-impl<C: ChainWithGrandpa> Engine<C> for Grandpa<C> {
+impl<C: ChainWithGrandpa> ConsensusEngine<HeaderOf<C>> for Grandpa<C> {
 	const ID: ConsensusEngineId = GRANDPA_ENGINE_ID;
 	type ConsensusLogReader = GrandpaConsensusLogReader<<C::Header as Header>::Number>;
 	type FinalityProof = GrandpaJustification<HeaderOf<C>>;
 	type FinalityVerificationContext = JustificationVerificationContext;
+	type Error = JustificationVerificationError;
+
+	fn verify_finality_proof(
+		finalized_target: (<C::Header as Header>::Hash, <C::Header as Header>::Number),
+		context: &Self::FinalityVerificationContext,
+		finality_proof: &Self::FinalityProof,
+	) -> Result<(), Self::Error> {
+		<GrandpaConsensusEngine as ConsensusEngine<HeaderOf<C>>>::verify_finality_proof(
+			finalized_target,
+			context,
+			finality_proof,
+		)
+	}
+}
+
+#[async_trait]
+impl<C: ChainWithGrandpa> Engine<C> for Grandpa<C> {
 	type EquivocationProof = sp_consensus_grandpa::EquivocationProof<HashOf<C>, BlockNumberOf<C>>;
 	type EquivocationsFinder = GrandpaEquivocationsFinder<C>;
 	type KeyOwnerProof = C::KeyOwnerProof;
@@ -195,6 +237,15 @@ impl<C: ChainWithGrandpa> Engine<C> for Grandpa<C> {
 		source_client.subscribe_grandpa_finality_justifications().await
 	}
 
+	async fn authorities_set_id(
+		source_client: &impl Client<C>,
+		at: HashOf<C>,
+	) -> Result<u64, SubstrateError> {
+		source_client
+			.state_call(at, SUB_API_GRANDPA_CURRENT_SET_ID.to_string(), ())
+			.await
+	}
+
 	async fn optimize_proof<TargetChain: Chain>(
 		target_client: &impl Client<TargetChain>,
 		header: &C::Header,
@@ -342,6 +393,54 @@ impl<C: ChainWithGrandpa> Engine<C> for Grandpa<C> {
 		})
 	}
 
+	async fn prepare_initialization_data_at(
+		source_client: impl Client<C>,
+		header_hash: HashOf<C>,
+	) -> Result<Self::InitializationData, Error<HashOf<C>, BlockNumberOf<C>>> {
+		// we can't wait for justification here, because we may be asked to start from an old
+		// header. So we are making sure that the header is finalized and then read the GRANDPA
+		// authorities set and its id directly from the runtime state at this header. If the
+		// header enacts authorities set change, the state already has the new set and its id,
+		// which is exactly what we need for initialization
+		let initial_header = Self::source_header(&source_client, header_hash).await?;
+		let initial_header_number = *initial_header.number();
+		let best_finalized_header_number = source_client
+			.best_finalized_header_number()
+			.await
+			.map_err(|err| Error::RetrieveBestFinalizedHeader(C::NAME, err))?;
+		let canonical_header_hash = source_client
+			.header_hash_by_number(initial_header_number)
+			.await
+			.map_err(|err| Error::RetrieveHeader(C::NAME, header_hash, err))?;
+		if initial_header_number > best_finalized_header_number ||
+			canonical_header_hash != header_hash
+		{
+			return Err(Error::NotFinalizedHeader(C::NAME, initial_header_number, header_hash))
+		}
+		log::trace!(target: "bridge", "Selected {} initial header: {}/{}",
+			C::NAME,
+			initial_header_number,
+			header_hash,
+		);
+
+		let initial_authorities_set =
+			Self::source_authorities_set(&source_client, header_hash).await?;
+		let initial_authorities_set_id =
+			Self::source_authorities_set_id(&source_client, header_hash).await?;
+		log::trace!(target: "bridge", "Selected {} initial authorities set: {:?}, set id: {}",
+			C::NAME,
+			initial_authorities_set,
+			initial_authorities_set_id,
+		);
+
+		Ok(bp_header_chain::InitializationData {
+			header: Box::new(initial_header),
+			authority_list: initial_authorities_set,
+			set_id: initial_authorities_set_id,
+			operating_mode: BasicOperatingMode::Normal,
+		})
+	}
+
 	async fn finality_verification_context<TargetChain: Chain>(
 		target_client: &impl Client<TargetChain>,
 		at: HashOf<TargetChain>,
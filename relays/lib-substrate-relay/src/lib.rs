@@ -18,10 +18,19 @@
 
 #![warn(missing_docs)]
 
-use relay_substrate_client::{Chain, ChainWithUtilityPallet, UtilityPallet};
+use bp_runtime::EncodedOrDecodedCall;
+use codec::{Compact, Decode, DecodeAll, Encode};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, Chain, ChainWithTransactions, ChainWithUtilityPallet, Client,
+	Error as SubstrateError, UtilityPallet,
+};
+use relay_utils::metrics::{Metric, PrometheusError, Registry};
+use sp_core::Pair;
+use sp_runtime::traits::TrailingZeroInput;
 
 use std::marker::PhantomData;
 
+pub mod builder;
 pub mod equivocation;
 pub mod error;
 pub mod finality;
@@ -29,6 +38,7 @@ pub mod finality_base;
 pub mod messages;
 pub mod on_demand;
 pub mod parachains;
+pub mod spending_budget;
 
 /// Transaction creation parameters.
 #[derive(Clone, Debug)]
@@ -37,6 +47,174 @@ pub struct TransactionParams<TS> {
 	pub signer: TS,
 	/// Transactions mortality.
 	pub mortality: Option<u32>,
+	/// Spending budget of the transactions author. If `None`, spendings are not limited.
+	pub spending_budget: Option<spending_budget::SpendingBudget>,
+	/// Wrapper of all calls, submitted by the transactions author. If `None`, calls are
+	/// submitted as is.
+	pub call_wrapper: Option<CallWrapper>,
+}
+
+impl<TS> TransactionParams<TS> {
+	/// Wrap call using configured call wrapper.
+	pub fn wrap_call<Call: Encode>(&self, call: Call) -> EncodedOrDecodedCall<Call> {
+		match self.call_wrapper {
+			Some(ref call_wrapper) => call_wrapper.wrap_call(call),
+			None => call.into(),
+		}
+	}
+}
+
+impl<TS: Pair> TransactionParams<TS> {
+	/// Returns id of the account, on behalf of which calls of the transactions author are
+	/// dispatched. This is the account that is registered as relayer and receives rewards.
+	pub fn dispatch_account_id<AccountId>(&self) -> Result<AccountId, SubstrateError>
+	where
+		AccountId: From<TS::Public> + Decode + Encode,
+	{
+		let signer: AccountId = self.signer.public().into();
+		match self.call_wrapper {
+			Some(ref call_wrapper) => call_wrapper
+				.dispatch_account_id(signer)
+				.map_err(SubstrateError::ResponseParseFailed),
+			None => Ok(signer),
+		}
+	}
+
+	/// Register spending budget metric and start monitoring balance of the transactions author.
+	pub fn start_spending_budget<C: ChainWithTransactions<AccountKeyPair = TS>>(
+		&self,
+		client: impl Client<C>,
+		registry: &Registry,
+	) -> Result<(), PrometheusError>
+	where
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		if let Some(ref spending_budget) = self.spending_budget {
+			spending_budget.register(registry)?;
+			spending_budget.spawn_balance_monitor(client, self.signer.public().into());
+		}
+		Ok(())
+	}
+
+	/// Returns error if the transactions author has exceeded its spending budget.
+	pub async fn ensure_within_spending_budget<C: ChainWithTransactions<AccountKeyPair = TS>>(
+		&self,
+		client: &impl Client<C>,
+	) -> Result<(), SubstrateError>
+	where
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		match self.spending_budget {
+			Some(ref spending_budget) =>
+				spending_budget.ensure_within_budget(client, &self.signer.public().into()).await,
+			None => Ok(()),
+		}
+	}
+}
+
+/// Index of the `pallet_proxy::Call::proxy` call.
+const PROXY_CALL_INDEX: u8 = 0;
+/// Index of the `pallet_multisig::Call::as_multi_threshold_1` call.
+const AS_MULTI_THRESHOLD_1_CALL_INDEX: u8 = 0;
+/// Entropy prefix, used by the `pallet-multisig` to compute multisig account id.
+const MULTISIG_ENTROPY_PREFIX: &[u8; 16] = b"modlpy/utilisuba";
+/// Index of the `MultiAddress::Id` variant.
+const MULTI_ADDRESS_ID_INDEX: u8 = 0;
+
+/// Wrapper of calls, that are dispatched on behalf of some other account.
+///
+/// It allows the relay to hold a key with restricted permissions, while the account that is
+/// registered as relayer (and receives rewards) is kept offline. Calls are wrapped in the
+/// encoded form, so we don't need the full runtime of the chain here.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CallWrapper {
+	/// Wrap calls into the `pallet_proxy::Call::proxy` call.
+	///
+	/// The transactions author must be registered as a proxy of the `real` account.
+	Proxy {
+		/// Index of the `pallet-proxy` in the chain runtime.
+		pallet_index: u8,
+		/// SCALE-encoded address (lookup source) of the proxied account.
+		real: Vec<u8>,
+	},
+	/// Wrap calls into the `pallet_multisig::Call::as_multi_threshold_1` call.
+	///
+	/// The call is dispatched on behalf of the multisig account of the transactions author and
+	/// `other_signatories`.
+	MultisigThreshold1 {
+		/// Index of the `pallet-multisig` in the chain runtime.
+		pallet_index: u8,
+		/// SCALE-encoded account ids of other multisig signatories, sorted by their encoding.
+		other_signatories: Vec<Vec<u8>>,
+	},
+}
+
+impl CallWrapper {
+	/// Wrap given call.
+	pub fn wrap_call<Call: Encode>(&self, call: Call) -> EncodedOrDecodedCall<Call> {
+		let mut encoded_call = Vec::new();
+		match *self {
+			CallWrapper::Proxy { pallet_index, ref real } => {
+				// `proxy(real, force_proxy_type: Option<ProxyType>, call)`
+				encoded_call.push(pallet_index);
+				encoded_call.push(PROXY_CALL_INDEX);
+				encoded_call.extend_from_slice(real);
+				None::<()>.encode_to(&mut encoded_call);
+			},
+			CallWrapper::MultisigThreshold1 { pallet_index, ref other_signatories } => {
+				// `as_multi_threshold_1(other_signatories: Vec<AccountId>, call)`
+				encoded_call.push(pallet_index);
+				encoded_call.push(AS_MULTI_THRESHOLD_1_CALL_INDEX);
+				Compact(other_signatories.len() as u32).encode_to(&mut encoded_call);
+				for other_signatory in other_signatories {
+					encoded_call.extend_from_slice(other_signatory);
+				}
+			},
+		}
+		call.encode_to(&mut encoded_call);
+
+		EncodedOrDecodedCall::Encoded(encoded_call)
+	}
+
+	/// Returns id of the account, on behalf of which wrapped calls of the `signer` are
+	/// dispatched.
+	///
+	/// The proxied account address must either be the account id itself (when the chain is
+	/// using `IdentityLookup`), or the `MultiAddress::Id` variant.
+	pub fn dispatch_account_id<AccountId: Decode + Encode>(
+		&self,
+		signer: AccountId,
+	) -> Result<AccountId, codec::Error> {
+		match *self {
+			CallWrapper::Proxy { ref real, .. } =>
+				AccountId::decode_all(&mut &real[..]).or_else(|e| match real.split_first() {
+					Some((&MULTI_ADDRESS_ID_INDEX, account_id)) =>
+						AccountId::decode_all(&mut &account_id[..]),
+					_ => Err(e),
+				}),
+			CallWrapper::MultisigThreshold1 { ref other_signatories, .. } => {
+				// signatories are sorted by the multisig pallet, so we do the same here
+				let signer = signer.encode();
+				let mut signatories = other_signatories.clone();
+				let signer_position = signatories
+					.binary_search(&signer)
+					.err()
+					.ok_or("Signer is one of other multisig signatories")?;
+				signatories.insert(signer_position, signer);
+
+				// `(b"modlpy/utilisuba", signatories, threshold).encode()`
+				let mut entropy_input = MULTISIG_ENTROPY_PREFIX.to_vec();
+				Compact(signatories.len() as u32).encode_to(&mut entropy_input);
+				for signatory in signatories {
+					entropy_input.extend_from_slice(&signatory);
+				}
+				1u16.encode_to(&mut entropy_input);
+
+				let entropy = sp_core::blake2_256(&entropy_input);
+				AccountId::decode(&mut TrailingZeroInput::new(&entropy))
+			},
+		}
+	}
 }
 
 /// Tagged relay account, which balance may be exposed as metrics by the relay.
@@ -104,6 +282,9 @@ pub trait BatchCallBuilderConstructor<Call>: Clone {
 }
 
 /// Batch call builder based on `pallet-utility`.
+///
+/// Builds atomic `batch_all` calls, so if any of calls (e.g. obsolete header) fails, all other
+/// calls of the batch fail too.
 #[derive(Clone)]
 pub struct UtilityPalletBatchCallBuilder<C: Chain>(PhantomData<C>);
 
@@ -142,3 +323,76 @@ impl<Call> BatchCallBuilder<Call> for () {
 		unreachable!("never called, because ()::new_builder() returns None; qed")
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn proxy_call_wrapper_works() {
+		let call_wrapper = CallWrapper::Proxy { pallet_index: 42, real: vec![1, 2, 3] };
+		assert_eq!(
+			call_wrapper.wrap_call(vec![4u8, 5]),
+			EncodedOrDecodedCall::Encoded(vec![42, 0, 1, 2, 3, 0, 8, 4, 5]),
+		);
+	}
+
+	#[test]
+	fn multisig_call_wrapper_works() {
+		let call_wrapper = CallWrapper::MultisigThreshold1 {
+			pallet_index: 42,
+			other_signatories: vec![vec![1, 2], vec![3, 4]],
+		};
+		assert_eq!(
+			call_wrapper.wrap_call(vec![5u8, 6]),
+			EncodedOrDecodedCall::Encoded(vec![42, 0, 8, 1, 2, 3, 4, 8, 5, 6]),
+		);
+	}
+
+	#[test]
+	fn proxy_call_wrapper_dispatch_account_id_works() {
+		let real = sp_runtime::AccountId32::new([1u8; 32]);
+		let signer = sp_runtime::AccountId32::new([2u8; 32]);
+
+		let call_wrapper = CallWrapper::Proxy { pallet_index: 42, real: real.encode() };
+		assert_eq!(call_wrapper.dispatch_account_id(signer.clone()), Ok(real.clone()));
+
+		let real_address = sp_runtime::MultiAddress::<_, ()>::Id(real.clone());
+		let call_wrapper = CallWrapper::Proxy { pallet_index: 42, real: real_address.encode() };
+		assert_eq!(call_wrapper.dispatch_account_id(signer.clone()), Ok(real));
+
+		let real_address = sp_runtime::MultiAddress::<sp_runtime::AccountId32, ()>::Raw(vec![1]);
+		let call_wrapper = CallWrapper::Proxy { pallet_index: 42, real: real_address.encode() };
+		assert!(call_wrapper.dispatch_account_id(signer).is_err());
+	}
+
+	#[test]
+	fn multisig_call_wrapper_dispatch_account_id_works() {
+		// value is computed using `pallet_multisig::Pallet::multi_account_id`
+		let signatories = vec![
+			sp_runtime::AccountId32::new([1u8; 32]),
+			sp_runtime::AccountId32::new([2u8; 32]),
+			sp_runtime::AccountId32::new([3u8; 32]),
+		];
+		let expected_account_id: sp_runtime::AccountId32 =
+			Decode::decode(&mut TrailingZeroInput::new(
+				&(MULTISIG_ENTROPY_PREFIX, &signatories, 1u16).using_encoded(sp_core::blake2_256),
+			))
+			.unwrap();
+
+		let call_wrapper = CallWrapper::MultisigThreshold1 {
+			pallet_index: 42,
+			other_signatories: vec![signatories[0].encode(), signatories[2].encode()],
+		};
+		assert_eq!(
+			call_wrapper.dispatch_account_id(signatories[1].clone()),
+			Ok(expected_account_id),
+		);
+
+		let call_wrapper = CallWrapper::MultisigThreshold1 {
+			pallet_index: 42,
+			other_signatories: vec![signatories[0].encode(), signatories[1].encode()],
+		};
+		assert!(call_wrapper.dispatch_account_id(signatories[1].clone()).is_err());
+	}
+}
@@ -29,17 +29,21 @@ use relay_substrate_client::{
 	AccountIdOf, BalanceOf, Chain, ChainWithBalances, ChainWithMessages, Client,
 	Error as SubstrateError, NonceOf,
 };
-use relay_utils::metrics::{MetricsParams, StandaloneMetric};
+use relay_utils::metrics::{BalanceThresholdMetric, MetricsParams, StandaloneMetric};
 use sp_core::storage::StorageData;
 use sp_runtime::{FixedPointNumber, FixedU128};
 use std::{convert::TryFrom, fmt::Debug, marker::PhantomData};
 
 /// Add relay accounts balance metrics.
+///
+/// If `balance_threshold` (in smallest units) is specified, the `balance_below_threshold` metric
+/// is also added for every relay account.
 pub async fn add_relay_balances_metrics<C: ChainWithBalances, BC: ChainWithMessages>(
 	client: impl Client<C>,
 	metrics: &MetricsParams,
 	relay_accounts: &Vec<TaggedAccount<AccountIdOf<C>>>,
 	lanes: &[LaneId],
+	balance_threshold: Option<u128>,
 ) -> anyhow::Result<()>
 where
 	BalanceOf<C>: Into<u128> + std::fmt::Debug,
@@ -71,6 +75,10 @@ where
 		)
 	})?;
 
+	let balance_threshold = balance_threshold.map(|balance_threshold| {
+		let balance_threshold = convert_to_token_balance(balance_threshold, token_decimals);
+		balance_threshold.into_inner() as f64 / FixedU128::DIV as f64
+	});
 	for account in relay_accounts {
 		let relay_account_balance_metric = FloatStorageValueMetric::new(
 			AccountBalanceFromAccountInfo::<C> { token_decimals, _phantom: Default::default() },
@@ -79,6 +87,22 @@ where
 			format!("at_{}_relay_{}_balance", C::NAME, account.tag()),
 			format!("Balance of the {} relay account at the {}", account.tag(), C::NAME),
 		)?;
+		if let Some(balance_threshold) = balance_threshold {
+			BalanceThresholdMetric::new(
+				format!("{} relay account at {}", account.tag(), C::NAME),
+				relay_account_balance_metric.shared_value_ref(),
+				balance_threshold,
+				relay_account_balance_metric.update_interval(),
+				format!("at_{}_relay_{}_balance_below_threshold", C::NAME, account.tag()),
+				format!(
+					"Whether the balance of the {} relay account at the {} is below {} (value 1)",
+					account.tag(),
+					C::NAME,
+					balance_threshold,
+				),
+			)?
+			.register_and_spawn(&metrics.registry)?;
+		}
 		relay_account_balance_metric.register_and_spawn(&metrics.registry)?;
 
 		if let Some(relayers_pallet_name) = BC::WITH_CHAIN_RELAYERS_PALLET_NAME {
@@ -27,10 +27,12 @@ use crate::{
 
 use async_std::sync::Arc;
 use bp_messages::{
-	target_chain::FromBridgedChainMessagesProof, ChainWithMessages as _, LaneId, MessageNonce,
+	target_chain::FromBridgedChainMessagesProof, ChainWithMessages as _, LaneId, LaneIdType,
+	MessageNonce,
 };
 use bp_runtime::{
-	AccountIdOf, Chain as _, EncodedOrDecodedCall, HeaderIdOf, TransactionEra, WeightExtraOps,
+	AccountIdOf, Chain as _, EncodedOrDecodedCall, HeaderIdOf, HeaderIdProvider, TransactionEra,
+	WeightExtraOps,
 };
 use codec::Encode;
 use frame_support::{dispatch::GetDispatchInfo, weights::Weight};
@@ -42,12 +44,12 @@ use relay_substrate_client::{
 	UnsignedTransaction,
 };
 use relay_utils::{
-	metrics::{GlobalMetrics, MetricsParams, StandaloneMetric},
+	metrics::{GlobalMetrics, Metric, MetricsParams, StandaloneMetric},
 	STALL_TIMEOUT,
 };
 use sp_core::Pair;
-use sp_runtime::traits::Zero;
-use std::{convert::TryFrom, fmt::Debug, marker::PhantomData};
+use sp_runtime::{traits::Zero, Perbill};
+use std::{convert::TryFrom, fmt::Debug, marker::PhantomData, ops::RangeInclusive};
 
 pub mod metrics;
 pub mod source;
@@ -59,6 +61,11 @@ pub trait SubstrateMessageLane: 'static + Clone + Debug + Send + Sync {
 	type SourceChain: ChainWithMessages + ChainWithTransactions;
 	/// Messages from the `SourceChain` are dispatched on this chain.
 	type TargetChain: ChainWithMessages + ChainWithTransactions;
+	/// Lane identifier type, used by messages pallets of this bridge.
+	///
+	/// The relay is always identifying lanes using `LaneId`. It is converted to this type when
+	/// it is used in storage keys, runtime API calls and proofs.
+	type LaneId: LaneIdType;
 
 	/// How receive messages proof call is built?
 	type ReceiveMessagesProofCallBuilder: ReceiveMessagesProofCallBuilder<Self>;
@@ -81,8 +88,8 @@ impl<P: SubstrateMessageLane> MessageLane for MessageLaneAdapter<P> {
 	const SOURCE_NAME: &'static str = P::SourceChain::NAME;
 	const TARGET_NAME: &'static str = P::TargetChain::NAME;
 
-	type MessagesProof = SubstrateMessagesProof<P::SourceChain>;
-	type MessagesReceivingProof = SubstrateMessagesDeliveryProof<P::TargetChain>;
+	type MessagesProof = SubstrateMessagesProof<P::SourceChain, P::LaneId>;
+	type MessagesReceivingProof = SubstrateMessagesDeliveryProof<P::TargetChain, P::LaneId>;
 
 	type SourceChainBalance = BalanceOf<P::SourceChain>;
 	type SourceHeaderNumber = BlockNumberOf<P::SourceChain>;
@@ -108,12 +115,38 @@ pub struct MessagesRelayParams<P: SubstrateMessageLane, SourceClnt, TargetClnt>
 	/// Optional on-demand target to source headers relay.
 	pub target_to_source_headers_relay:
 		Option<Arc<dyn OnDemandRelay<P::TargetChain, P::SourceChain>>>,
-	/// Identifier of lane that needs to be served.
+	/// Identifier of lane that needs to be served. It is converted to the `P::LaneId` when
+	/// relay is started.
 	pub lane_id: LaneId,
+	/// If specified, messages that have been sent before this source header are delivered
+	/// using proofs, generated at old source headers. The source node must be an archive node.
+	pub since_source_block: Option<HashOf<P::SourceChain>>,
+	/// If specified, message delivery is paused while recent target chain blocks are, on
+	/// average, filled at least by this ratio.
+	pub congested_target_block_fullness: Option<Perbill>,
 	/// Metrics parameters.
 	pub metrics_params: MetricsParams,
 }
 
+impl<P: SubstrateMessageLane, SourceClnt: Clone, TargetClnt: Clone> Clone
+	for MessagesRelayParams<P, SourceClnt, TargetClnt>
+{
+	fn clone(&self) -> Self {
+		MessagesRelayParams {
+			source_client: self.source_client.clone(),
+			source_transaction_params: self.source_transaction_params.clone(),
+			target_client: self.target_client.clone(),
+			target_transaction_params: self.target_transaction_params.clone(),
+			source_to_target_headers_relay: self.source_to_target_headers_relay.clone(),
+			target_to_source_headers_relay: self.target_to_source_headers_relay.clone(),
+			lane_id: self.lane_id,
+			since_source_block: self.since_source_block,
+			congested_target_block_fullness: self.congested_target_block_fullness,
+			metrics_params: self.metrics_params.clone(),
+		}
+	}
+}
+
 /// Batch transaction that brings headers + and messages delivery/receiving confirmations to the
 /// source node.
 #[derive(Clone)]
@@ -198,10 +231,22 @@ where
 	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
 		(max_messages_in_single_batch / 2, max_messages_weight_in_single_batch / 2);
 
+	let lane_id = bridge_lane_id::<P>(params.lane_id)?;
 	let source_client = params.source_client;
 	let target_client = params.target_client;
+	let since_source_block = match params.since_source_block {
+		Some(since_source_block) =>
+			Some(source_client.header_by_hash(since_source_block).await?.id()),
+		None => None,
+	};
+	params
+		.source_transaction_params
+		.start_spending_budget(source_client.clone(), &params.metrics_params.registry)?;
+	params
+		.target_transaction_params
+		.start_spending_budget(target_client.clone(), &params.metrics_params.registry)?;
 	let relayer_id_at_source: AccountIdOf<P::SourceChain> =
-		params.source_transaction_params.signer.public().into();
+		params.source_transaction_params.dispatch_account_id()?;
 
 	log::info!(
 		target: "bridge",
@@ -238,6 +283,18 @@ where
 			source_tick: P::SourceChain::AVERAGE_BLOCK_INTERVAL,
 			target_tick: P::TargetChain::AVERAGE_BLOCK_INTERVAL,
 			reconnect_delay: relay_utils::relay_loop::RECONNECT_DELAY,
+			stall_timeout: std::cmp::max(
+				transaction_stall_timeout(
+					params.source_transaction_params.mortality,
+					P::SourceChain::AVERAGE_BLOCK_INTERVAL,
+					STALL_TIMEOUT,
+				),
+				transaction_stall_timeout(
+					params.target_transaction_params.mortality,
+					P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+					STALL_TIMEOUT,
+				),
+			),
 			delivery_params: messages_relay::message_lane_loop::MessageDeliveryParams {
 				max_unrewarded_relayer_entries_at_target:
 					P::SourceChain::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
@@ -246,19 +303,21 @@ where
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
 				max_messages_size_in_single_batch,
+				congested_target_block_fullness: params.congested_target_block_fullness,
 			},
 		},
+		since_source_block,
 		SubstrateMessagesSource::<P, _, _>::new(
 			source_client.clone(),
 			target_client.clone(),
-			params.lane_id,
+			lane_id,
 			params.source_transaction_params,
 			params.target_to_source_headers_relay,
 		),
 		SubstrateMessagesTarget::<P, _, _>::new(
 			target_client,
 			source_client,
-			params.lane_id,
+			lane_id,
 			relayer_id_at_source,
 			params.target_transaction_params,
 			params.source_to_target_headers_relay,
@@ -273,13 +332,75 @@ where
 	.map_err(Into::into)
 }
 
+/// Deliver given range of Substrate-to-Substrate messages once, using proof generated at given
+/// source chain header. No checks are made to ensure that transaction will succeed.
+///
+/// If the source header is not yet known to the target chain, its proof is bundled with the
+/// messages proof by the `source_to_target_headers_relay`. If it is `None`, the header must be
+/// the best source header, known to the target chain.
+#[allow(clippy::too_many_arguments)]
+pub async fn relay_messages_range<P: SubstrateMessageLane>(
+	source_client: impl Client<P::SourceChain>,
+	target_client: impl Client<P::TargetChain>,
+	source_transaction_params: TransactionParams<AccountKeyPairOf<P::SourceChain>>,
+	target_transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
+	source_to_target_headers_relay: Option<Arc<dyn OnDemandRelay<P::SourceChain, P::TargetChain>>>,
+	at_source_block: HeaderIdOf<P::SourceChain>,
+	lane_id: LaneId,
+	range: RangeInclusive<MessageNonce>,
+	outbound_state_proof_required: bool,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<P::SourceChain>: From<<AccountKeyPairOf<P::SourceChain> as Pair>::Public>,
+	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
+	BalanceOf<P::SourceChain>: TryFrom<BalanceOf<P::TargetChain>>,
+{
+	let bridge_lane_id = bridge_lane_id::<P>(lane_id)?;
+	let relayer_id_at_source: AccountIdOf<P::SourceChain> =
+		source_transaction_params.dispatch_account_id()?;
+	messages_relay::message_lane_loop::relay_messages_range(
+		SubstrateMessagesSource::<P, _, _>::new(
+			source_client.clone(),
+			target_client.clone(),
+			bridge_lane_id,
+			source_transaction_params,
+			None,
+		),
+		SubstrateMessagesTarget::<P, _, _>::new(
+			target_client,
+			source_client,
+			bridge_lane_id,
+			relayer_id_at_source,
+			target_transaction_params,
+			source_to_target_headers_relay,
+		),
+		at_source_block,
+		range,
+		outbound_state_proof_required,
+	)
+	.await
+	.map_err(|_| anyhow::format_err!("Failed to deliver messages range. See logs for details"))
+}
+
+/// Convert relay lane identifier to the identifier, used by the bridge.
+fn bridge_lane_id<P: SubstrateMessageLane>(lane_id: LaneId) -> anyhow::Result<P::LaneId> {
+	P::LaneId::try_from(lane_id).map_err(|_| {
+		anyhow::format_err!(
+			"Lane {:?} can't be used by the {} -> {} bridge: unsupported lane identifier",
+			lane_id,
+			P::SourceChain::NAME,
+			P::TargetChain::NAME,
+		)
+	})
+}
+
 /// Different ways of building `receive_messages_proof` calls.
 pub trait ReceiveMessagesProofCallBuilder<P: SubstrateMessageLane> {
 	/// Given messages proof, build call of `receive_messages_proof` function of bridge
 	/// messages module at the target chain.
 	fn build_receive_messages_proof_call(
 		relayer_id_at_source: AccountIdOf<P::SourceChain>,
-		proof: SubstrateMessagesProof<P::SourceChain>,
+		proof: SubstrateMessagesProof<P::SourceChain, P::LaneId>,
 		messages_count: u32,
 		dispatch_weight: Weight,
 		trace_call: bool,
@@ -294,7 +415,7 @@ pub struct DirectReceiveMessagesProofCallBuilder<P, R, I> {
 
 impl<P, R, I> ReceiveMessagesProofCallBuilder<P> for DirectReceiveMessagesProofCallBuilder<P, R, I>
 where
-	P: SubstrateMessageLane,
+	P: SubstrateMessageLane<LaneId = LaneId>,
 	R: BridgeMessagesConfig<I>,
 	I: 'static,
 	R::BridgedChain:
@@ -303,7 +424,7 @@ where
 {
 	fn build_receive_messages_proof_call(
 		relayer_id_at_source: AccountIdOf<P::SourceChain>,
-		proof: SubstrateMessagesProof<P::SourceChain>,
+		proof: SubstrateMessagesProof<P::SourceChain, P::LaneId>,
 		messages_count: u32,
 		dispatch_weight: Weight,
 		trace_call: bool,
@@ -351,7 +472,8 @@ macro_rules! generate_receive_message_proof_call_builder {
 					<$pipeline as $crate::messages::SubstrateMessageLane>::SourceChain
 				>,
 				proof: $crate::messages::source::SubstrateMessagesProof<
-					<$pipeline as $crate::messages::SubstrateMessageLane>::SourceChain
+					<$pipeline as $crate::messages::SubstrateMessageLane>::SourceChain,
+					<$pipeline as $crate::messages::SubstrateMessageLane>::LaneId
 				>,
 				messages_count: u32,
 				dispatch_weight: bp_messages::Weight,
@@ -377,7 +499,7 @@ pub trait ReceiveMessagesDeliveryProofCallBuilder<P: SubstrateMessageLane> {
 	/// Given messages delivery proof, build call of `receive_messages_delivery_proof` function of
 	/// bridge messages module at the source chain.
 	fn build_receive_messages_delivery_proof_call(
-		proof: SubstrateMessagesDeliveryProof<P::TargetChain>,
+		proof: SubstrateMessagesDeliveryProof<P::TargetChain, P::LaneId>,
 		trace_call: bool,
 	) -> CallOf<P::SourceChain>;
 }
@@ -391,14 +513,14 @@ pub struct DirectReceiveMessagesDeliveryProofCallBuilder<P, R, I> {
 impl<P, R, I> ReceiveMessagesDeliveryProofCallBuilder<P>
 	for DirectReceiveMessagesDeliveryProofCallBuilder<P, R, I>
 where
-	P: SubstrateMessageLane,
+	P: SubstrateMessageLane<LaneId = LaneId>,
 	R: BridgeMessagesConfig<I>,
 	I: 'static,
 	R::BridgedChain: bp_runtime::Chain<Hash = HashOf<P::TargetChain>>,
 	CallOf<P::SourceChain>: From<BridgeMessagesCall<R, I>> + GetDispatchInfo,
 {
 	fn build_receive_messages_delivery_proof_call(
-		proof: SubstrateMessagesDeliveryProof<P::TargetChain>,
+		proof: SubstrateMessagesDeliveryProof<P::TargetChain, P::LaneId>,
 		trace_call: bool,
 	) -> CallOf<P::SourceChain> {
 		let call: CallOf<P::SourceChain> =
@@ -440,7 +562,8 @@ macro_rules! generate_receive_message_delivery_proof_call_builder {
 		{
 			fn build_receive_messages_delivery_proof_call(
 				proof: $crate::messages::target::SubstrateMessagesDeliveryProof<
-					<$pipeline as $crate::messages::SubstrateMessageLane>::TargetChain
+					<$pipeline as $crate::messages::SubstrateMessageLane>::TargetChain,
+					<$pipeline as $crate::messages::SubstrateMessageLane>::LaneId
 				>,
 				_trace_call: bool,
 			) -> relay_substrate_client::CallOf<
@@ -548,7 +671,7 @@ where
 				FromBridgedChainMessagesProof {
 					bridged_header_hash: Default::default(),
 					storage: Default::default(),
-					lane: LaneId::new(1, 2),
+					lane: Default::default(),
 					nonces_start: 1,
 					nonces_end: messages as u64,
 				},
@@ -32,11 +32,12 @@ use async_trait::async_trait;
 use bp_messages::{
 	storage_keys::{operating_mode_key, outbound_lane_data_key},
 	target_chain::FromBridgedChainMessagesProof,
-	ChainWithMessages as _, InboundMessageDetails, LaneId, MessageNonce, MessagePayload,
-	MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails,
+	ChainWithMessages as _, InboundMessageDetails, LaneId, LaneIdType, MessageNonce,
+	MessagePayload, MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails,
+	OutboundMessageDetailsWithReward,
 };
 use bp_runtime::{BasicOperatingMode, HeaderIdProvider, RangeInclusiveExt};
-use codec::Encode;
+use codec::{DecodeAll, Encode};
 use frame_support::weights::Weight;
 use messages_relay::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
@@ -52,20 +53,22 @@ use relay_substrate_client::{
 	UnsignedTransaction,
 };
 use relay_utils::relay_loop::Client as RelayClient;
-use sp_core::Pair;
+use sp_core::{Bytes, Pair};
+use sp_runtime::SaturatedConversion;
 use std::ops::RangeInclusive;
 
 /// Intermediate message proof returned by the source Substrate node. Includes everything
 /// required to submit to the target node: cumulative dispatch weight of bundled messages and
 /// the proof itself.
-pub type SubstrateMessagesProof<C> = (Weight, FromBridgedChainMessagesProof<HashOf<C>>);
+pub type SubstrateMessagesProof<C, L = LaneId> =
+	(Weight, FromBridgedChainMessagesProof<HashOf<C>, L>);
 type MessagesToRefine<'a> = Vec<(MessagePayload, &'a mut OutboundMessageDetails)>;
 
 /// Substrate client as Substrate messages source.
 pub struct SubstrateMessagesSource<P: SubstrateMessageLane, SourceClnt, TargetClnt> {
 	source_client: SourceClnt,
 	target_client: TargetClnt,
-	lane_id: LaneId,
+	lane_id: P::LaneId,
 	transaction_params: TransactionParams<AccountKeyPairOf<P::SourceChain>>,
 	target_to_source_headers_relay: Option<Arc<dyn OnDemandRelay<P::TargetChain, P::SourceChain>>>,
 }
@@ -77,7 +80,7 @@ impl<P: SubstrateMessageLane, SourceClnt: Client<P::SourceChain>, TargetClnt>
 	pub fn new(
 		source_client: SourceClnt,
 		target_client: TargetClnt,
-		lane_id: LaneId,
+		lane_id: P::LaneId,
 		transaction_params: TransactionParams<AccountKeyPairOf<P::SourceChain>>,
 		target_to_source_headers_relay: Option<
 			Arc<dyn OnDemandRelay<P::TargetChain, P::SourceChain>>,
@@ -216,14 +219,28 @@ where
 		id: SourceHeaderIdOf<MessageLaneAdapter<P>>,
 		nonces: RangeInclusive<MessageNonce>,
 	) -> Result<MessageDetailsMap<BalanceOf<P::SourceChain>>, SubstrateError> {
-		let mut out_msgs_details: Vec<_> = self
+		let arguments = (self.lane_id, *nonces.start(), *nonces.end());
+		let encoded_out_msgs_details = self
 			.source_client
-			.state_call::<_, Vec<_>>(
+			.raw_state_call(
 				id.hash(),
 				P::TargetChain::TO_CHAIN_MESSAGE_DETAILS_METHOD.into(),
-				(self.lane_id, *nonces.start(), *nonces.end()),
+				arguments,
 			)
 			.await?;
+		let (mut out_msgs_details, delivery_rewards): (Vec<_>, Vec<_>) =
+			decode_out_msgs_details(&encoded_out_msgs_details.0)
+				.map_err(|e| {
+					SubstrateError::failed_state_call::<P::SourceChain>(
+						id.hash(),
+						P::TargetChain::TO_CHAIN_MESSAGE_DETAILS_METHOD.into(),
+						Bytes(arguments.encode()),
+						e.into(),
+					)
+				})?
+				.into_iter()
+				.map(|details| (details.details, details.delivery_reward))
+				.unzip();
 		validate_out_msgs_details::<P::SourceChain>(&out_msgs_details, nonces)?;
 
 		// prepare arguments of the inbound message details call (if we need it)
@@ -255,7 +272,7 @@ where
 
 		let best_target_header_hash = self.target_client.best_header_hash().await?;
 		for mut msgs_to_refine_batch in
-			split_msgs_to_refine::<P::SourceChain, P::TargetChain>(self.lane_id, msgs_to_refine)?
+			split_msgs_to_refine::<P::SourceChain, P::TargetChain, _>(self.lane_id, msgs_to_refine)?
 		{
 			let in_msgs_details = self
 				.target_client
@@ -265,40 +282,24 @@ where
 					(self.lane_id, &msgs_to_refine_batch),
 				)
 				.await?;
-			if in_msgs_details.len() != msgs_to_refine_batch.len() {
-				return Err(SubstrateError::Custom(format!(
-					"Call of {} at {} has returned {} entries instead of expected {}",
-					P::SourceChain::FROM_CHAIN_MESSAGE_DETAILS_METHOD,
-					P::TargetChain::NAME,
-					in_msgs_details.len(),
-					msgs_to_refine_batch.len(),
-				)))
-			}
-			for ((_, out_msg_details), in_msg_details) in
-				msgs_to_refine_batch.iter_mut().zip(in_msgs_details)
-			{
-				log::trace!(
-					target: "bridge",
-					"Refined weight of {}->{} message {:?}/{}: at-source: {}, at-target: {}",
-					P::SourceChain::NAME,
-					P::TargetChain::NAME,
-					self.lane_id,
-					out_msg_details.nonce,
-					out_msg_details.dispatch_weight,
-					in_msg_details.dispatch_weight,
-				);
-				out_msg_details.dispatch_weight = in_msg_details.dispatch_weight;
-			}
+			refine_msgs_dispatch_weight::<P::SourceChain, P::TargetChain, _>(
+				self.lane_id,
+				&mut msgs_to_refine_batch,
+				in_msgs_details,
+			)?;
 		}
 
 		let mut msgs_details_map = MessageDetailsMap::new();
-		for out_msg_details in out_msgs_details {
+		for (out_msg_details, delivery_reward) in out_msgs_details.into_iter().zip(delivery_rewards)
+		{
 			msgs_details_map.insert(
 				out_msg_details.nonce,
 				MessageDetails {
 					dispatch_weight: out_msg_details.dispatch_weight,
 					size: out_msg_details.size as _,
-					reward: Zero::zero(),
+					reward: delivery_reward
+						.map(SaturatedConversion::saturated_into)
+						.unwrap_or_else(Zero::zero),
 				},
 			);
 		}
@@ -361,13 +362,19 @@ where
 			Some(batch_tx) => batch_tx.append_call_and_build(messages_proof_call),
 			None => messages_proof_call,
 		};
+		let final_call = self.transaction_params.wrap_call(final_call);
 
+		self.transaction_params
+			.ensure_within_spending_budget(&self.source_client)
+			.await?;
 		let transaction_params = self.transaction_params.clone();
+		let tip = relay_utils::control::tip().saturated_into();
 		self.source_client
 			.submit_and_watch_signed_extrinsic(
 				&self.transaction_params.signer,
 				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(final_call.into(), transaction_nonce)
+					Ok(UnsignedTransaction::new(final_call, transaction_nonce)
+						.tip(tip)
 						.era(TransactionEra::new(best_block_id, transaction_params.mortality)))
 				},
 			)
@@ -409,9 +416,20 @@ where
 		.await?;
 	let is_halted =
 		operating_mode == Some(MessagesOperatingMode::Basic(BasicOperatingMode::Halted));
+	let alert_subject =
+		format!("{} pallet at {}", WithChain::WITH_CHAIN_MESSAGES_PALLET_NAME, AtChain::NAME);
 	if is_halted {
+		relay_utils::alerts::fire_alert(
+			relay_utils::alerts::AlertKind::PalletHalted,
+			alert_subject,
+			"pallet is halted".into(),
+		);
 		Err(SubstrateError::BridgePalletIsHalted)
 	} else {
+		relay_utils::alerts::resolve_alert(
+			relay_utils::alerts::AlertKind::PalletHalted,
+			alert_subject,
+		);
 		Ok(())
 	}
 }
@@ -496,6 +514,60 @@ where
 		.await
 }
 
+/// Read the reward that the source chain runtime registers for the relayer that delivers a
+/// message over given lane.
+///
+/// The reward is read for the latest message, sent over the lane. It is `None` if nothing has
+/// been sent over the lane yet or if the runtime doesn't report delivery rewards.
+pub async fn expected_delivery_reward<P: SubstrateMessageLane>(
+	source_client: &impl Client<P::SourceChain>,
+	at: HashOf<P::SourceChain>,
+	lane_id: P::LaneId,
+) -> Result<Option<u128>, SubstrateError> {
+	let latest_generated_nonce = source_client
+		.storage_value::<OutboundLaneData>(
+			at,
+			outbound_lane_data_key(P::TargetChain::WITH_CHAIN_MESSAGES_PALLET_NAME, &lane_id),
+		)
+		.await?
+		.map(|data| data.latest_generated_nonce)
+		.unwrap_or(0);
+	if latest_generated_nonce == 0 {
+		return Ok(None)
+	}
+
+	let arguments = (lane_id, latest_generated_nonce, latest_generated_nonce);
+	let encoded_out_msgs_details = source_client
+		.raw_state_call(at, P::TargetChain::TO_CHAIN_MESSAGE_DETAILS_METHOD.into(), arguments)
+		.await?;
+	let out_msgs_details = decode_out_msgs_details(&encoded_out_msgs_details.0).map_err(|e| {
+		SubstrateError::failed_state_call::<P::SourceChain>(
+			at,
+			P::TargetChain::TO_CHAIN_MESSAGE_DETAILS_METHOD.into(),
+			Bytes(arguments.encode()),
+			e.into(),
+		)
+	})?;
+	Ok(out_msgs_details.into_iter().next().and_then(|details| details.delivery_reward))
+}
+
+/// Decode result of the `To<Chain>OutboundLaneApi::message_details` runtime call.
+///
+/// Runtimes that are implementing the first version of this API are returning message details
+/// without expected delivery reward. In this case the reward is unknown.
+fn decode_out_msgs_details(
+	encoded: &[u8],
+) -> Result<Vec<OutboundMessageDetailsWithReward>, codec::Error> {
+	Vec::<OutboundMessageDetailsWithReward>::decode_all(&mut &encoded[..]).or_else(|_| {
+		Vec::<OutboundMessageDetails>::decode_all(&mut &encoded[..]).map(|out_msgs_details| {
+			out_msgs_details
+				.into_iter()
+				.map(|details| OutboundMessageDetailsWithReward { details, delivery_reward: None })
+				.collect()
+		})
+	})
+}
+
 fn validate_out_msgs_details<C: Chain>(
 	out_msgs_details: &[OutboundMessageDetails],
 	nonces: RangeInclusive<MessageNonce>,
@@ -543,8 +615,8 @@ fn validate_out_msgs_details<C: Chain>(
 	Ok(())
 }
 
-fn split_msgs_to_refine<Source: Chain + ChainWithMessages, Target: Chain>(
-	lane_id: LaneId,
+fn split_msgs_to_refine<Source: Chain + ChainWithMessages, Target: Chain, Lane: LaneIdType>(
+	lane_id: Lane,
 	msgs_to_refine: MessagesToRefine,
 ) -> Result<Vec<MessagesToRefine>, SubstrateError> {
 	let max_batch_size = Target::max_extrinsic_size() as usize;
@@ -576,6 +648,63 @@ fn split_msgs_to_refine<Source: Chain + ChainWithMessages, Target: Chain>(
 	Ok(batches)
 }
 
+/// Replace dispatch weights of outbound messages with the actual dispatch weights, computed by
+/// the target chain runtime.
+///
+/// The weight that has been declared at the source chain may be both larger (then we would be
+/// delivering less messages in a single transaction than we could) or lower (then the delivery
+/// transaction would fail) than the weight, actually required to dispatch the message at the
+/// target chain. So we always use the weight, computed by the target chain runtime.
+fn refine_msgs_dispatch_weight<
+	Source: Chain + ChainWithMessages,
+	Target: Chain,
+	Lane: LaneIdType,
+>(
+	lane_id: Lane,
+	msgs_to_refine: &mut MessagesToRefine,
+	in_msgs_details: Vec<InboundMessageDetails>,
+) -> Result<(), SubstrateError> {
+	if in_msgs_details.len() != msgs_to_refine.len() {
+		return Err(SubstrateError::Custom(format!(
+			"Call of {} at {} has returned {} entries instead of expected {}",
+			Source::FROM_CHAIN_MESSAGE_DETAILS_METHOD,
+			Target::NAME,
+			in_msgs_details.len(),
+			msgs_to_refine.len(),
+		)))
+	}
+
+	for ((_, out_msg_details), in_msg_details) in msgs_to_refine.iter_mut().zip(in_msgs_details) {
+		if in_msg_details.dispatch_weight.any_gt(out_msg_details.dispatch_weight) {
+			log::debug!(
+				target: "bridge",
+				"Weight of {}->{} message {:?}/{} is under-declared at source: at-source: {}, \
+				at-target: {}",
+				Source::NAME,
+				Target::NAME,
+				lane_id,
+				out_msg_details.nonce,
+				out_msg_details.dispatch_weight,
+				in_msg_details.dispatch_weight,
+			);
+		} else {
+			log::trace!(
+				target: "bridge",
+				"Refined weight of {}->{} message {:?}/{}: at-source: {}, at-target: {}",
+				Source::NAME,
+				Target::NAME,
+				lane_id,
+				out_msg_details.nonce,
+				out_msg_details.dispatch_weight,
+				in_msg_details.dispatch_weight,
+			);
+		}
+		out_msg_details.dispatch_weight = in_msg_details.dispatch_weight;
+	}
+
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -597,6 +726,30 @@ mod tests {
 			.collect()
 	}
 
+	#[test]
+	fn decode_out_msgs_details_works_with_delivery_rewards() {
+		let out_msgs_details = message_details_from_rpc(1..=3)
+			.into_iter()
+			.map(|details| OutboundMessageDetailsWithReward {
+				delivery_reward: Some(details.nonce as u128 * 100),
+				details,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(decode_out_msgs_details(&out_msgs_details.encode()).unwrap(), out_msgs_details);
+	}
+
+	#[test]
+	fn decode_out_msgs_details_falls_back_to_details_without_delivery_rewards() {
+		let out_msgs_details = message_details_from_rpc(1..=3);
+		assert_eq!(
+			decode_out_msgs_details(&out_msgs_details.encode()).unwrap(),
+			out_msgs_details
+				.into_iter()
+				.map(|details| OutboundMessageDetailsWithReward { details, delivery_reward: None })
+				.collect::<Vec<_>>(),
+		);
+	}
+
 	#[test]
 	fn validate_out_msgs_details_succeeds_if_no_messages_are_missing() {
 		assert!(
@@ -645,6 +798,51 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn refine_msgs_dispatch_weight_uses_weights_computed_at_target() {
+		let mut out_msgs_details = vec![
+			OutboundMessageDetails {
+				nonce: 1,
+				dispatch_weight: Weight::from_parts(100, 0),
+				size: 0,
+			},
+			OutboundMessageDetails {
+				nonce: 2,
+				dispatch_weight: Weight::from_parts(10, 0),
+				size: 0,
+			},
+		];
+		let mut msgs_to_refine: MessagesToRefine =
+			out_msgs_details.iter_mut().map(|details| (vec![], details)).collect();
+		refine_msgs_dispatch_weight::<Rialto, Rococo, LaneId>(
+			LaneId::new(1, 2),
+			&mut msgs_to_refine,
+			vec![
+				InboundMessageDetails { dispatch_weight: Weight::from_parts(50, 0) },
+				InboundMessageDetails { dispatch_weight: Weight::from_parts(20, 0) },
+			],
+		)
+		.unwrap();
+
+		assert_eq!(out_msgs_details[0].dispatch_weight, Weight::from_parts(50, 0));
+		assert_eq!(out_msgs_details[1].dispatch_weight, Weight::from_parts(20, 0));
+	}
+
+	#[test]
+	fn refine_msgs_dispatch_weight_fails_if_target_returns_wrong_number_of_entries() {
+		let mut out_msgs_details = message_details_from_rpc(1..=2);
+		let mut msgs_to_refine: MessagesToRefine =
+			out_msgs_details.iter_mut().map(|details| (vec![], details)).collect();
+		assert!(matches!(
+			refine_msgs_dispatch_weight::<Rialto, Rococo, LaneId>(
+				LaneId::new(1, 2),
+				&mut msgs_to_refine,
+				vec![InboundMessageDetails { dispatch_weight: Weight::zero() }],
+			),
+			Err(SubstrateError::Custom(_))
+		));
+	}
+
 	fn check_split_msgs_to_refine(
 		payload_sizes: Vec<usize>,
 		expected_batches: Result<Vec<usize>, ()>,
@@ -667,7 +865,7 @@ mod tests {
 		}
 
 		let maybe_batches =
-			split_msgs_to_refine::<Rialto, Rococo>(LaneId::new(1, 2), msgs_to_refine);
+			split_msgs_to_refine::<Rialto, Rococo, LaneId>(LaneId::new(1, 2), msgs_to_refine);
 		match expected_batches {
 			Ok(expected_batches) => {
 				let batches = maybe_batches.unwrap();
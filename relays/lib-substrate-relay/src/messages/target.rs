@@ -37,27 +37,31 @@ use bp_messages::{
 	source_chain::FromBridgedChainMessagesDeliveryProof, storage_keys::inbound_lane_data_key,
 	ChainWithMessages as _, InboundLaneData, LaneId, MessageNonce, UnrewardedRelayersState,
 };
+use bp_runtime::storage_value_key;
+use frame_support::{dispatch::DispatchClass, weights::Weight};
+use frame_system::ConsumedWeight;
 use messages_relay::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
 	message_lane_loop::{NoncesSubmitArtifacts, TargetClient, TargetClientState},
 };
 use relay_substrate_client::{
-	AccountIdOf, AccountKeyPairOf, BalanceOf, CallOf, Client, Error as SubstrateError, HashOf,
-	TransactionEra, TransactionTracker, UnsignedTransaction,
+	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockWithJustification, CallOf, Client,
+	Error as SubstrateError, HashOf, TransactionEra, TransactionTracker, UnsignedTransaction,
 };
 use relay_utils::relay_loop::Client as RelayClient;
 use sp_core::Pair;
+use sp_runtime::{Perbill, SaturatedConversion};
 use std::{convert::TryFrom, ops::RangeInclusive};
 
 /// Message receiving proof returned by the target Substrate node.
-pub type SubstrateMessagesDeliveryProof<C> =
-	(UnrewardedRelayersState, FromBridgedChainMessagesDeliveryProof<HashOf<C>>);
+pub type SubstrateMessagesDeliveryProof<C, L = LaneId> =
+	(UnrewardedRelayersState, FromBridgedChainMessagesDeliveryProof<HashOf<C>, L>);
 
 /// Substrate client as Substrate messages target.
 pub struct SubstrateMessagesTarget<P: SubstrateMessageLane, SourceClnt, TargetClnt> {
 	target_client: TargetClnt,
 	source_client: SourceClnt,
-	lane_id: LaneId,
+	lane_id: P::LaneId,
 	relayer_id_at_source: AccountIdOf<P::SourceChain>,
 	transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
 	source_to_target_headers_relay: Option<Arc<dyn OnDemandRelay<P::SourceChain, P::TargetChain>>>,
@@ -72,7 +76,7 @@ where
 	pub fn new(
 		target_client: TargetClnt,
 		source_client: SourceClnt,
-		lane_id: LaneId,
+		lane_id: P::LaneId,
 		relayer_id_at_source: AccountIdOf<P::SourceChain>,
 		transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
 		source_to_target_headers_relay: Option<
@@ -220,6 +224,38 @@ where
 		Ok((id, (&inbound_lane_data).into()))
 	}
 
+	async fn block_fullness(
+		&self,
+		id: TargetHeaderIdOf<MessageLaneAdapter<P>>,
+	) -> Result<(TargetHeaderIdOf<MessageLaneAdapter<P>>, Perbill), SubstrateError> {
+		let block_weight: Option<ConsumedWeight> = self
+			.target_client
+			.storage_value(id.hash(), storage_value_key("System", "BlockWeight"))
+			.await?;
+		let normal_weight =
+			block_weight.map(|block_weight| *block_weight.get(DispatchClass::Normal));
+		// `System::AllExtrinsicsLen` is killed when block is finalized, so we need to read the
+		// block body to compute its length
+		let block_length = self
+			.target_client
+			.block_by_hash(id.hash())
+			.await?
+			.extrinsics()
+			.iter()
+			.map(|extrinsic| extrinsic.len() as u64)
+			.sum::<u64>();
+		Ok((
+			id,
+			std::cmp::max(
+				weight_fullness(
+					normal_weight.unwrap_or_default(),
+					P::TargetChain::max_extrinsic_weight(),
+				),
+				length_fullness(block_length, P::TargetChain::max_extrinsic_size()),
+			),
+		))
+	}
+
 	async fn prove_messages_receiving(
 		&self,
 		id: TargetHeaderIdOf<MessageLaneAdapter<P>>,
@@ -263,14 +299,20 @@ where
 			Some(batch_tx) => batch_tx.append_call_and_build(messages_proof_call),
 			None => messages_proof_call,
 		};
+		let final_call = self.transaction_params.wrap_call(final_call);
 
+		self.transaction_params
+			.ensure_within_spending_budget(&self.target_client)
+			.await?;
 		let transaction_params = self.transaction_params.clone();
+		let tip = relay_utils::control::tip().saturated_into();
 		let tx_tracker = self
 			.target_client
 			.submit_and_watch_signed_extrinsic(
 				&self.transaction_params.signer,
 				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(final_call.into(), transaction_nonce)
+					Ok(UnsignedTransaction::new(final_call, transaction_nonce)
+						.tip(tip)
 						.era(TransactionEra::new(best_block_id, transaction_params.mortality)))
 				},
 			)
@@ -296,11 +338,36 @@ where
 	}
 }
 
+/// Returns the ratio of `weight` to the `max_weight`. Weight components that are not limited
+/// (have zero maximal value) are ignored.
+fn weight_fullness(weight: Weight, max_weight: Weight) -> Perbill {
+	let component_fullness = |value: u64, max_value: u64| {
+		if max_value == 0 {
+			Perbill::zero()
+		} else {
+			Perbill::from_rational(value, max_value)
+		}
+	};
+	std::cmp::max(
+		component_fullness(weight.ref_time(), max_weight.ref_time()),
+		component_fullness(weight.proof_size(), max_weight.proof_size()),
+	)
+}
+
+/// Returns the ratio of `length` to the `max_length`.
+fn length_fullness(length: u64, max_length: u32) -> Perbill {
+	if max_length == 0 {
+		Perbill::zero()
+	} else {
+		Perbill::from_rational(std::cmp::min(length, max_length as u64), max_length as u64)
+	}
+}
+
 /// Make messages delivery call from given proof.
 fn make_messages_delivery_call<P: SubstrateMessageLane>(
 	relayer_id_at_source: AccountIdOf<P::SourceChain>,
 	nonces: RangeInclusive<MessageNonce>,
-	proof: SubstrateMessagesProof<P::SourceChain>,
+	proof: SubstrateMessagesProof<P::SourceChain, P::LaneId>,
 	trace_call: bool,
 ) -> CallOf<P::TargetChain> {
 	let messages_count = nonces.end() - nonces.start() + 1;
@@ -339,6 +339,7 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 						recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
 						stall_timeout,
 						only_mandatory_headers,
+						warp_sync: false,
 					},
 					metrics_params.clone().unwrap_or_else(MetricsParams::disabled),
 					futures::future::pending(),
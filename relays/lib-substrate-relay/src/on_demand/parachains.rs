@@ -198,7 +198,7 @@ where
 		let mut proved_parachain_block = selected_parachain_block;
 		if proved_relay_block != selected_relay_block {
 			proved_parachain_block = parachains_source
-				.on_chain_para_head_id(proved_relay_block)
+				.on_chain_para_head_id(proved_relay_block, para_id)
 				.await?
 				// this could happen e.g. if parachain has been offboarded?
 				.ok_or_else(|| {
@@ -224,8 +224,14 @@ where
 		}
 
 		// and finally - prove parachain head
-		let (para_proof, para_hash) =
-			parachains_source.prove_parachain_head(proved_relay_block).await?;
+		let (para_proof, para_hashes) =
+			parachains_source.prove_parachain_heads(proved_relay_block, &[para_id]).await?;
+		let para_hash = para_hashes.into_iter().next().flatten().ok_or_else(|| {
+			SubstrateError::MissingRequiredParachainHead(
+				para_id,
+				proved_relay_block.number().unique_saturated_into(),
+			)
+		})?;
 		calls.push(P::SubmitParachainHeadsCallBuilder::build_submit_parachain_heads_call(
 			proved_relay_block,
 			vec![(para_id, para_hash)],
@@ -508,7 +514,10 @@ where
 		source.client().best_finalized_header().await.map_err(map_source_err)?;
 	let best_finalized_relay_block_id = best_finalized_relay_header.id();
 	let para_header_at_source = source
-		.on_chain_para_head_id(best_finalized_relay_block_id)
+		.on_chain_para_head_id(
+			best_finalized_relay_block_id,
+			ParaId(P::SourceParachain::PARACHAIN_ID),
+		)
 		.await
 		.map_err(map_source_err)?;
 
@@ -531,7 +540,10 @@ where
 	let para_header_at_relay_header_at_target =
 		if let Some(available_relay_header_at_target) = available_relay_header_at_target {
 			source
-				.on_chain_para_head_id(available_relay_header_at_target)
+				.on_chain_para_head_id(
+					available_relay_header_at_target,
+					ParaId(P::SourceParachain::PARACHAIN_ID),
+				)
 				.await
 				.map_err(map_source_err)?
 		} else {
@@ -658,7 +670,8 @@ impl<'a, P: SubstrateParachainsPipeline, SourceRelayClnt, TargetClnt>
 	for (
 		&'a OnDemandParachainsRelay<P, SourceRelayClnt, TargetClnt>,
 		&'a ParachainsSource<P, SourceRelayClnt>,
-	) where
+	)
+where
 	SourceRelayClnt: Client<P::SourceRelayChain>,
 	TargetClnt: Client<P::TargetChain>,
 {
@@ -687,7 +700,7 @@ impl<'a, P: SubstrateParachainsPipeline, SourceRelayClnt, TargetClnt>
 		&self,
 		at_relay_block: HeaderIdOf<P::SourceRelayChain>,
 	) -> Result<Option<HeaderIdOf<P::SourceParachain>>, SubstrateError> {
-		self.1.on_chain_para_head_id(at_relay_block).await
+		self.1.on_chain_para_head_id(at_relay_block, self.parachain_id()).await
 	}
 }
 
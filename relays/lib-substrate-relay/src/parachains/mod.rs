@@ -18,14 +18,17 @@
 //! parachain finality proofs synchronization pipelines.
 
 use async_trait::async_trait;
-use bp_polkadot_core::parachains::{ParaHash, ParaHeadsProof, ParaId};
+use bp_polkadot_core::parachains::{ParaHash, ParaHead, ParaHeadsProof, ParaId};
+use bp_runtime::HeaderIdProvider;
+use codec::Decode;
 use pallet_bridge_parachains::{
 	Call as BridgeParachainsCall, Config as BridgeParachainsConfig, RelayBlockHash,
 	RelayBlockHasher, RelayBlockNumber,
 };
 use parachains_relay::ParachainsPipeline;
 use relay_substrate_client::{
-	CallOf, Chain, ChainWithTransactions, HeaderIdOf, Parachain, RelayChain,
+	CallOf, Chain, ChainWithTransactions, Error as SubstrateError, HeaderIdOf, HeaderOf, Parachain,
+	ParachainBase, RelayChain,
 };
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -34,8 +37,9 @@ pub mod target;
 
 /// Substrate -> Substrate parachain finality proofs synchronization pipeline.
 ///
-/// This is currently restricted to the single parachain, because it is how it
-/// will be used (at least) initially.
+/// Heads of several parachains may be synchronized by the same pipeline (see
+/// [`SubstrateParachainsPipeline::parachains`]), but they all must be using the same header
+/// type as the `Self::SourceParachain`.
 #[async_trait]
 pub trait SubstrateParachainsPipeline: 'static + Clone + Debug + Send + Sync {
 	/// Headers of this parachain are submitted to the `Self::TargetChain`.
@@ -47,6 +51,28 @@ pub trait SubstrateParachainsPipeline: 'static + Clone + Debug + Send + Sync {
 
 	/// How submit parachains heads call is built?
 	type SubmitParachainHeadsCallBuilder: SubmitParachainHeadsCallBuilder<Self>;
+
+	/// Returns identifiers of all parachains which heads are synchronized by this pipeline.
+	///
+	/// Updated heads of all these parachains are submitted in a single `submit_parachain_heads`
+	/// call. By default, only heads of the `Self::SourceParachain` are synchronized.
+	fn parachains() -> Vec<ParaId> {
+		vec![ParaId(Self::SourceParachain::PARACHAIN_ID)]
+	}
+
+	/// Decode and validate head of given parachain, read from the source relay chain.
+	///
+	/// By default, the head data is decoded as the `Self::SourceParachain` header. Parachains
+	/// that are using non-standard head data (e.g. with extra commitments, embedded into the
+	/// head) must override this to extract header id from it. If error is returned, the head
+	/// is never submitted to the target chain and is never used as a proof anchor.
+	fn decode_parachain_head(
+		_para_id: ParaId,
+		para_head: &ParaHead,
+	) -> Result<HeaderIdOf<Self::SourceParachain>, SubstrateError> {
+		let para_head: HeaderOf<Self::SourceParachain> = Decode::decode(&mut &para_head.0[..])?;
+		Ok(para_head.id())
+	}
 }
 
 /// Adapter that allows all `SubstrateParachainsPipeline` to act as `ParachainsPipeline`.
@@ -59,6 +85,10 @@ impl<P: SubstrateParachainsPipeline> ParachainsPipeline for ParachainsPipelineAd
 	type SourceParachain = P::SourceParachain;
 	type SourceRelayChain = P::SourceRelayChain;
 	type TargetChain = P::TargetChain;
+
+	fn parachains() -> Vec<ParaId> {
+		P::parachains()
+	}
 }
 
 /// Different ways of building `submit_parachain_heads` calls.
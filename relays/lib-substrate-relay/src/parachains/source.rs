@@ -22,12 +22,9 @@ use async_std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use bp_parachains::parachain_head_storage_key_at_source;
 use bp_polkadot_core::parachains::{ParaHash, ParaHead, ParaHeadsProof, ParaId};
-use bp_runtime::HeaderIdProvider;
-use codec::Decode;
 use parachains_relay::parachains_loop::{AvailableHeader, SourceClient};
 use relay_substrate_client::{
-	is_ancient_block, Chain, Client, Error as SubstrateError, HeaderIdOf, HeaderOf, ParachainBase,
-	RelayChain,
+	is_ancient_block, Chain, Client, Error as SubstrateError, HeaderIdOf, ParachainBase, RelayChain,
 };
 use relay_utils::relay_loop::Client as RelayClient;
 
@@ -39,6 +36,7 @@ pub type RequiredHeaderIdRef<C> = Arc<Mutex<AvailableHeader<HeaderIdOf<C>>>>;
 #[derive(Clone)]
 pub struct ParachainsSource<P: SubstrateParachainsPipeline, SourceRelayClnt> {
 	client: SourceRelayClnt,
+	verification_client: Option<SourceRelayClnt>,
 	max_head_id: RequiredHeaderIdRef<P::SourceParachain>,
 }
 
@@ -50,7 +48,17 @@ impl<P: SubstrateParachainsPipeline, SourceRelayClnt: Client<P::SourceRelayChain
 		client: SourceRelayClnt,
 		max_head_id: RequiredHeaderIdRef<P::SourceParachain>,
 	) -> Self {
-		ParachainsSource { client, max_head_id }
+		ParachainsSource { client, verification_client: None, max_head_id }
+	}
+
+	/// Use second client (connected to other source relay chain node) to cross-check parachain
+	/// heads before submitting them.
+	///
+	/// Heads are only submitted if both nodes agree on the heads storage value at the same relay
+	/// chain block.
+	pub fn with_verification_client(mut self, verification_client: SourceRelayClnt) -> Self {
+		self.verification_client = Some(verification_client);
+		self
 	}
 
 	/// Returns reference to the underlying RPC client.
@@ -58,12 +66,18 @@ impl<P: SubstrateParachainsPipeline, SourceRelayClnt: Client<P::SourceRelayChain
 		&self.client
 	}
 
-	/// Return decoded head of given parachain.
+	/// Return decoded head of given parachain (see
+	/// [`SubstrateParachainsPipeline::decode_parachain_head`]).
+	///
+	/// The `Paras::Heads` map is only updated when the candidate is included (i.e. when it is
+	/// available), so it never contains heads from the unincluded segment, even if the relay
+	/// chain supports async backing. Since we are only reading it at finalized relay chain
+	/// headers, the returned head can't be dropped later.
 	pub async fn on_chain_para_head_id(
 		&self,
 		at_block: HeaderIdOf<P::SourceRelayChain>,
+		para_id: ParaId,
 	) -> Result<Option<HeaderIdOf<P::SourceParachain>>, SubstrateError> {
-		let para_id = ParaId(P::SourceParachain::PARACHAIN_ID);
 		let storage_key =
 			parachain_head_storage_key_at_source(P::SourceRelayChain::PARAS_PALLET_NAME, para_id);
 		let para_head: Option<ParaHead> =
@@ -72,8 +86,7 @@ impl<P: SubstrateParachainsPipeline, SourceRelayClnt: Client<P::SourceRelayChain
 			Some(para_head) => para_head,
 			None => return Ok(None),
 		};
-		let para_head: HeaderOf<P::SourceParachain> = Decode::decode(&mut &para_head.0[..])?;
-		Ok(Some(para_head.id()))
+		P::decode_parachain_head(para_id, &para_head).map(Some)
 	}
 }
 
@@ -84,7 +97,11 @@ impl<P: SubstrateParachainsPipeline, SourceRelayClnt: Client<P::SourceRelayChain
 	type Error = SubstrateError;
 
 	async fn reconnect(&mut self) -> Result<(), SubstrateError> {
-		self.client.reconnect().await
+		self.client.reconnect().await?;
+		if let Some(ref mut verification_client) = self.verification_client {
+			verification_client.reconnect().await?;
+		}
+		Ok(())
 	}
 }
 
@@ -105,6 +122,7 @@ where
 	async fn parachain_head(
 		&self,
 		at_block: HeaderIdOf<P::SourceRelayChain>,
+		para_id: ParaId,
 	) -> Result<AvailableHeader<HeaderIdOf<P::SourceParachain>>, Self::Error> {
 		// if requested relay header is ancient, then we don't even want to try to read the
 		// parachain head - we simply return `Unavailable`
@@ -115,7 +133,7 @@ where
 
 		// else - try to read head from the source client
 		let mut para_head_id = AvailableHeader::Missing;
-		if let Some(on_chain_para_head_id) = self.on_chain_para_head_id(at_block).await? {
+		if let Some(on_chain_para_head_id) = self.on_chain_para_head_id(at_block, para_id).await? {
 			// Never return head that is larger than requested. This way we'll never sync
 			// headers past `max_header_id`.
 			para_head_id = match *self.max_head_id.lock().await {
@@ -124,45 +142,83 @@ where
 					// `max_header_id` is not set. There is no limit.
 					AvailableHeader::Available(on_chain_para_head_id)
 				},
-				AvailableHeader::Available(max_head_id) => {
+				AvailableHeader::Available(max_head_id)
+					if para_id == ParaId(P::SourceParachain::PARACHAIN_ID) =>
+				{
 					// We report at most `max_header_id`.
 					AvailableHeader::Available(std::cmp::min(on_chain_para_head_id, max_head_id))
 				},
+				AvailableHeader::Available(_) => {
+					// `max_header_id` only limits heads of the `P::SourceParachain`.
+					AvailableHeader::Available(on_chain_para_head_id)
+				},
 			}
 		}
 
 		Ok(para_head_id)
 	}
 
-	async fn prove_parachain_head(
+	async fn prove_parachain_heads(
 		&self,
 		at_block: HeaderIdOf<P::SourceRelayChain>,
-	) -> Result<(ParaHeadsProof, ParaHash), Self::Error> {
-		let parachain = ParaId(P::SourceParachain::PARACHAIN_ID);
-		let storage_key =
-			parachain_head_storage_key_at_source(P::SourceRelayChain::PARAS_PALLET_NAME, parachain);
+		parachains: &[ParaId],
+	) -> Result<(ParaHeadsProof, Vec<Option<ParaHash>>), Self::Error> {
+		let storage_keys = parachains
+			.iter()
+			.map(|parachain| {
+				parachain_head_storage_key_at_source(
+					P::SourceRelayChain::PARAS_PALLET_NAME,
+					*parachain,
+				)
+			})
+			.collect::<Vec<_>>();
 
 		let storage_proof =
-			self.client.prove_storage(at_block.hash(), vec![storage_key.clone()]).await?;
+			self.client.prove_storage(at_block.hash(), storage_keys.clone()).await?;
 
-		// why we're reading parachain head here once again (it has already been read at the
+		// why we're reading parachain heads here once again (they have already been read at the
 		// `parachain_head`)? that's because `parachain_head` sometimes returns obsolete parachain
 		// head and loop sometimes asks to prove this obsolete head and gets other (actual) head
 		// instead
 		//
 		// => since we want to provide proper hashes in our `submit_parachain_heads` call, we're
-		// rereading actual value here
-		let parachain_head = self
-			.client
-			.storage_value::<ParaHead>(at_block.hash(), storage_key)
-			.await?
-			.ok_or_else(|| {
-				SubstrateError::Custom(format!(
-					"Failed to read expected parachain {parachain:?} head at {at_block:?}"
-				))
-			})?;
-		let parachain_head_hash = parachain_head.hash();
-
-		Ok((ParaHeadsProof { storage_proof }, parachain_head_hash))
+		// rereading actual values here
+		let mut parachain_head_hashes = Vec::with_capacity(parachains.len());
+		for (parachain, storage_key) in parachains.iter().zip(storage_keys) {
+			let parachain_head = match self
+				.client
+				.storage_value::<ParaHead>(at_block.hash(), storage_key.clone())
+				.await?
+			{
+				Some(parachain_head) => parachain_head,
+				None => {
+					// the proof of other heads is still valid, so let's not fail the whole batch
+					log::warn!(
+						target: "bridge",
+						"Failed to read expected parachain {:?} head at {:?}",
+						parachain,
+						at_block,
+					);
+					parachain_head_hashes.push(None);
+					continue
+				},
+			};
+			if let Some(ref verification_client) = self.verification_client {
+				let verified_parachain_head = verification_client
+					.storage_value::<ParaHead>(at_block.hash(), storage_key)
+					.await?;
+				if verified_parachain_head.as_ref() != Some(&parachain_head) {
+					return Err(SubstrateError::Custom(format!(
+						"Source nodes disagree on parachain {parachain:?} head at {at_block:?}: \
+						{parachain_head:?} vs {verified_parachain_head:?}"
+					)))
+				}
+			}
+			// never submit heads that the pipeline is unable to interpret
+			P::decode_parachain_head(*parachain, &parachain_head)?;
+			parachain_head_hashes.push(Some(parachain_head.hash()));
+		}
+
+		Ok((ParaHeadsProof { storage_proof }, parachain_head_hashes))
 	}
 }
@@ -24,15 +24,19 @@ use crate::{
 };
 
 use async_trait::async_trait;
+use bp_parachains::{
+	ImportedParaHeadsKeyProvider, ParaInfo, ParaStoredHeaderData, ParasInfoKeyProvider,
+};
 use bp_polkadot_core::parachains::{ParaHash, ParaHeadsProof, ParaId};
-use bp_runtime::HeaderIdProvider;
+use bp_runtime::{HeaderId, HeaderIdProvider, StorageDoubleMapKeyProvider, StorageMapKeyProvider};
 use parachains_relay::parachains_loop::TargetClient;
 use relay_substrate_client::{
 	AccountIdOf, AccountKeyPairOf, Chain, Client, Error as SubstrateError, HeaderIdOf,
-	ParachainBase, TransactionEra, TransactionTracker, UnsignedTransaction,
+	ParachainBase, RelayChain, TransactionEra, TransactionTracker, UnsignedTransaction,
 };
 use relay_utils::relay_loop::Client as RelayClient;
 use sp_core::Pair;
+use sp_runtime::SaturatedConversion;
 
 /// Substrate client as parachain heads source.
 pub struct ParachainsTarget<P: SubstrateParachainsPipeline, TargetClnt> {
@@ -55,6 +59,45 @@ impl<P: SubstrateParachainsPipeline, TargetClnt> ParachainsTarget<P, TargetClnt>
 	}
 }
 
+impl<P: SubstrateParachainsPipeline, TargetClnt: Client<P::TargetChain>>
+	ParachainsTarget<P, TargetClnt>
+where
+	P::SourceParachain: Chain<Hash = ParaHash>,
+{
+	/// Read head of given parachain directly from the bridge parachains pallet storage.
+	///
+	/// The runtime only exposes best head of the `P::SourceParachain` through its API, so heads
+	/// of other parachains are read from the storage.
+	async fn parachain_head_from_storage(
+		&self,
+		at_block: HeaderIdOf<P::TargetChain>,
+		para_id: ParaId,
+	) -> Result<Option<HeaderIdOf<P::SourceParachain>>, SubstrateError> {
+		let pallet_name = P::SourceRelayChain::PARACHAINS_FINALITY_PALLET_NAME;
+		let para_info: Option<ParaInfo> = self
+			.client
+			.storage_value(at_block.hash(), ParasInfoKeyProvider::final_key(pallet_name, &para_id))
+			.await?;
+		let head_hash = match para_info {
+			Some(para_info) => para_info.best_head_hash.head_hash,
+			None => return Ok(None),
+		};
+		let head_data: Option<ParaStoredHeaderData> = self
+			.client
+			.storage_value(
+				at_block.hash(),
+				ImportedParaHeadsKeyProvider::final_key(pallet_name, &para_id, &head_hash),
+			)
+			.await?;
+		let head_data = match head_data {
+			Some(head_data) => head_data.decode_parachain_head_data::<P::SourceParachain>()?,
+			None => return Ok(None),
+		};
+
+		Ok(Some(HeaderId(head_data.number, head_hash)))
+	}
+}
+
 impl<P: SubstrateParachainsPipeline, TargetClnt: Clone> Clone for ParachainsTarget<P, TargetClnt> {
 	fn clone(&self) -> Self {
 		ParachainsTarget {
@@ -79,6 +122,7 @@ impl<P: SubstrateParachainsPipeline, TargetClnt: Client<P::TargetChain>> RelayCl
 impl<P, TargetClnt> TargetClient<ParachainsPipelineAdapter<P>> for ParachainsTarget<P, TargetClnt>
 where
 	P: SubstrateParachainsPipeline,
+	P::SourceParachain: Chain<Hash = ParaHash>,
 	TargetClnt: Client<P::TargetChain>,
 	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
 {
@@ -109,7 +153,12 @@ where
 	async fn parachain_head(
 		&self,
 		at_block: HeaderIdOf<P::TargetChain>,
+		para_id: ParaId,
 	) -> Result<Option<HeaderIdOf<P::SourceParachain>>, Self::Error> {
+		if para_id != ParaId(P::SourceParachain::PARACHAIN_ID) {
+			return self.parachain_head_from_storage(at_block, para_id).await
+		}
+
 		self.client
 			.state_call(
 				at_block.hash(),
@@ -119,23 +168,27 @@ where
 			.await
 	}
 
-	async fn submit_parachain_head_proof(
+	async fn submit_parachain_heads_proof(
 		&self,
 		at_relay_block: HeaderIdOf<P::SourceRelayChain>,
-		updated_head_hash: ParaHash,
+		updated_heads: Vec<(ParaId, ParaHash)>,
 		proof: ParaHeadsProof,
 	) -> Result<Self::TransactionTracker, Self::Error> {
+		self.transaction_params.ensure_within_spending_budget(&self.client).await?;
 		let transaction_params = self.transaction_params.clone();
 		let call = P::SubmitParachainHeadsCallBuilder::build_submit_parachain_heads_call(
 			at_relay_block,
-			vec![(ParaId(P::SourceParachain::PARACHAIN_ID), updated_head_hash)],
+			updated_heads,
 			proof,
 		);
+		let call = transaction_params.wrap_call(call);
+		let tip = relay_utils::control::tip().saturated_into();
 		self.client
 			.submit_and_watch_signed_extrinsic(
 				&transaction_params.signer,
 				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
+					Ok(UnsignedTransaction::new(call, transaction_nonce)
+						.tip(tip)
 						.era(TransactionEra::new(best_block_id, transaction_params.mortality)))
 				},
 			)
@@ -0,0 +1,292 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Spending budget of the relayer account.
+//!
+//! The budget limits amount of tokens that the relayer account may spend during the last hour
+//! and/or during the last day. Spendings are computed as decreases of the account free balance,
+//! which is read right before submitting every fee-paying transaction. Once the budget is
+//! exceeded, the relay refuses to submit new transactions, so relay loops keep running in the
+//! monitoring-only mode until enough time passes.
+//!
+//! Any decrease of the free balance counts as spending, not only transaction fees. So tokens
+//! that are reserved (e.g. by relayer registration), transferred or slashed from the relayer
+//! account are also charged to the budget. Balance increases (e.g. rewards) don't restore it.
+//!
+//! The balance is also read periodically, so spendings and low balance are noticed even if the
+//! relay has nothing to submit.
+
+use async_std::sync::{Arc, Mutex};
+use relay_substrate_client::{
+	AccountIdOf, Chain, ChainWithBalances, Client, Error as SubstrateError,
+};
+use relay_utils::metrics::{register, IntGauge, Metric, PrometheusError, Registry};
+use std::{
+	collections::VecDeque,
+	time::{Duration, Instant},
+};
+
+/// Length of the hourly budget window.
+const HOUR: Duration = Duration::from_secs(60 * 60);
+/// Length of the daily budget window.
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// Interval between periodic reads of the relayer account balance.
+const BALANCE_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spending budget parameters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SpendingBudgetParams {
+	/// Maximal amount of tokens (in smallest chain units) that the relayer account may spend
+	/// during the last hour. Any decrease of the account free balance counts as spending.
+	pub max_spending_per_hour: Option<u128>,
+	/// Maximal amount of tokens (in smallest chain units) that the relayer account may spend
+	/// during the last day. Any decrease of the account free balance counts as spending.
+	pub max_spending_per_day: Option<u128>,
+	/// Minimal free balance (in smallest chain units) of the relayer account. If the balance
+	/// drops below this value, the relay is reported as not ready by the health endpoint.
+	pub min_balance: Option<u128>,
+}
+
+impl SpendingBudgetParams {
+	/// Returns true if budget is not limited and the relayer account balance is not monitored.
+	pub fn is_unlimited(&self) -> bool {
+		self.max_spending_per_hour.is_none() &&
+			self.max_spending_per_day.is_none() &&
+			self.min_balance.is_none()
+	}
+}
+
+/// Spending budget of the relayer account.
+///
+/// Cloning only clones references, so all clones are sharing the same spending history.
+#[derive(Clone, Debug)]
+pub struct SpendingBudget {
+	/// Name of the chain where the relayer account is spending tokens.
+	chain: &'static str,
+	/// Budget parameters.
+	params: SpendingBudgetParams,
+	/// Spending history.
+	state: Arc<Mutex<SpendingBudgetState>>,
+	/// Metric that has `1` value when the budget is exceeded and `0` otherwise.
+	is_exceeded_metric: IntGauge,
+}
+
+/// Spending history of the relayer account.
+#[derive(Debug, Default)]
+struct SpendingBudgetState {
+	/// Last seen free balance of the relayer account.
+	last_seen_balance: Option<u128>,
+	/// Spendings that have been observed during the last day.
+	spendings: VecDeque<(Instant, u128)>,
+	/// Whether the budget is currently exceeded.
+	is_exceeded: bool,
+}
+
+impl SpendingBudget {
+	/// Create new spending budget for the relayer account at given chain.
+	pub fn new<C: Chain>(params: SpendingBudgetParams) -> Result<Self, PrometheusError> {
+		Ok(SpendingBudget {
+			chain: C::NAME,
+			params,
+			state: Arc::new(Mutex::new(SpendingBudgetState::default())),
+			is_exceeded_metric: IntGauge::new(
+				format!("at_{}_relay_spending_budget_exceeded", C::NAME),
+				format!(
+					"Whether the relay account at {} has exceeded its spending budget (value 1)",
+					C::NAME
+				),
+			)?,
+		})
+	}
+
+	/// Read the current balance of the relayer account and return error if spending budget is
+	/// exceeded. If the balance is below configured minimum, the relay is reported as not ready.
+	pub async fn ensure_within_budget<C: ChainWithBalances>(
+		&self,
+		client: &impl Client<C>,
+		account: &AccountIdOf<C>,
+	) -> Result<(), SubstrateError> {
+		let at = client.best_header_hash().await?;
+		let free_balance = client.free_native_balance(at, account).await?;
+
+		match self.note_balance(Instant::now(), free_balance).await {
+			Some(reason) =>
+				Err(SubstrateError::SpendingBudgetExceeded { chain: self.chain.into(), reason }),
+			None => Ok(()),
+		}
+	}
+
+	/// Spawn the task that periodically reads the balance of the relayer account.
+	pub fn spawn_balance_monitor<C: ChainWithBalances>(
+		&self,
+		client: impl Client<C>,
+		account: AccountIdOf<C>,
+	) {
+		let budget = self.clone();
+		async_std::task::spawn(async move {
+			loop {
+				if let Err(e) = budget.ensure_within_budget(&client, &account).await {
+					log::trace!(
+						target: "bridge",
+						"Failed to check {} relay account balance: {:?}",
+						budget.chain,
+						e,
+					);
+				}
+				async_std::task::sleep(BALANCE_MONITOR_INTERVAL).await;
+			}
+		});
+	}
+
+	/// Note the free balance of the relayer account, observed at given moment. Returns the
+	/// reason if the budget is exceeded.
+	async fn note_balance(&self, now: Instant, balance: u128) -> Option<String> {
+		let mut state = self.state.lock().await;
+		if let Some(last_seen_balance) = state.last_seen_balance {
+			if balance < last_seen_balance {
+				state.spendings.push_back((now, last_seen_balance - balance));
+			}
+		}
+		state.last_seen_balance = Some(balance);
+
+		if let Some(min_balance) = self.params.min_balance {
+			let check = format!("{} relay account balance", self.chain);
+			let failure = if balance < min_balance {
+				Some(format!("free balance {balance} is below {min_balance}"))
+			} else {
+				None
+			};
+			match failure {
+				Some(ref failure) => relay_utils::alerts::fire_alert(
+					relay_utils::alerts::AlertKind::LowBalance,
+					check.clone(),
+					failure.clone(),
+				),
+				None => relay_utils::alerts::resolve_alert(
+					relay_utils::alerts::AlertKind::LowBalance,
+					check.clone(),
+				),
+			}
+			relay_utils::health::set_readiness_check(check, failure);
+		}
+
+		while state
+			.spendings
+			.front()
+			.map(|(spent_at, _)| now.saturating_duration_since(*spent_at) > DAY)
+			.unwrap_or(false)
+		{
+			state.spendings.pop_front();
+		}
+
+		let spent_during = |window: Duration| {
+			state
+				.spendings
+				.iter()
+				.filter(|(spent_at, _)| now.saturating_duration_since(*spent_at) <= window)
+				.fold(0u128, |total, (_, spent)| total.saturating_add(*spent))
+		};
+		let spent_during_hour = spent_during(HOUR);
+		let spent_during_day = spent_during(DAY);
+		let reason = match (self.params.max_spending_per_hour, self.params.max_spending_per_day) {
+			(Some(max_per_hour), _) if spent_during_hour > max_per_hour => Some(format!(
+				"spent {spent_during_hour} during the last hour, when only {max_per_hour} is allowed"
+			)),
+			(_, Some(max_per_day)) if spent_during_day > max_per_day => Some(format!(
+				"spent {spent_during_day} during the last day, when only {max_per_day} is allowed"
+			)),
+			_ => None,
+		};
+
+		let is_exceeded = reason.is_some();
+		if is_exceeded != state.is_exceeded {
+			match reason {
+				Some(ref reason) => log::error!(
+					target: "bridge",
+					"Relay account at {} has exceeded its spending budget: {}. Stopping \
+					transactions submission",
+					self.chain,
+					reason,
+				),
+				None => log::info!(
+					target: "bridge",
+					"Relay account at {} is back within its spending budget. Resuming \
+					transactions submission",
+					self.chain,
+				),
+			}
+			state.is_exceeded = is_exceeded;
+			self.is_exceeded_metric.set(is_exceeded.into());
+		}
+
+		reason
+	}
+}
+
+impl Metric for SpendingBudget {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		match register(self.is_exceeded_metric.clone(), registry) {
+			Ok(_) | Err(PrometheusError::AlreadyReg) => Ok(()),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use relay_rialto_client::Rialto;
+
+	fn budget(
+		max_spending_per_hour: Option<u128>,
+		max_spending_per_day: Option<u128>,
+	) -> SpendingBudget {
+		SpendingBudget::new::<Rialto>(SpendingBudgetParams {
+			max_spending_per_hour,
+			max_spending_per_day,
+			min_balance: None,
+		})
+		.unwrap()
+	}
+
+	#[async_std::test]
+	async fn hourly_budget_is_enforced() {
+		let budget = budget(Some(100), None);
+		let start = Instant::now();
+
+		assert_eq!(budget.note_balance(start, 1000).await, None);
+		assert_eq!(budget.note_balance(start + Duration::from_secs(60), 950).await, None);
+		// balance increase (e.g. reward) is not a spending
+		assert_eq!(budget.note_balance(start + Duration::from_secs(120), 1000).await, None);
+		assert!(budget.note_balance(start + Duration::from_secs(180), 940).await.is_some());
+		assert_eq!(budget.is_exceeded_metric.get(), 1);
+
+		// an hour later, the budget is restored
+		assert_eq!(budget.note_balance(start + HOUR + Duration::from_secs(181), 940).await, None);
+		assert_eq!(budget.is_exceeded_metric.get(), 0);
+	}
+
+	#[async_std::test]
+	async fn daily_budget_is_enforced() {
+		let budget = budget(Some(100), Some(150));
+		let start = Instant::now();
+
+		assert_eq!(budget.note_balance(start, 1000).await, None);
+		assert_eq!(budget.note_balance(start + Duration::from_secs(60), 920).await, None);
+		assert!(budget.note_balance(start + 2 * HOUR, 840).await.is_some());
+		assert_eq!(budget.note_balance(start + DAY + Duration::from_secs(61), 840).await, None);
+	}
+}
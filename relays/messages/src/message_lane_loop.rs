@@ -24,16 +24,28 @@
 //! finalized header. I.e. when talking about headers in lane context, we
 //! only care about finalized headers.
 
-use std::{collections::BTreeMap, fmt::Debug, future::Future, ops::RangeInclusive, time::Duration};
+use std::{
+	collections::BTreeMap, fmt::Debug, future::Future, ops::RangeInclusive, sync::Arc,
+	time::Duration,
+};
 
 use async_trait::async_trait;
-use futures::{channel::mpsc::unbounded, future::FutureExt, stream::StreamExt};
+use futures::{
+	channel::mpsc::unbounded,
+	future::{FusedFuture, FutureExt},
+	stream::StreamExt,
+};
 
 use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState, Weight};
 use relay_utils::{
-	interval, metrics::MetricsParams, process_future_result, relay_loop::Client as RelayClient,
-	retry_backoff, FailedClient, TransactionTracker,
+	clock::{Clock, SystemClock},
+	interval_with_clock,
+	metrics::MetricsParams,
+	process_future_result,
+	relay_loop::Client as RelayClient,
+	retry_backoff, FailedClient, TrackedTransactionStatus, TransactionTracker,
 };
+use sp_arithmetic::Perbill;
 
 use crate::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
@@ -53,6 +65,8 @@ pub struct Params {
 	pub target_tick: Duration,
 	/// Delay between moments when connection error happens and our reconnect attempt.
 	pub reconnect_delay: Duration,
+	/// Timeout after which the transaction, submitted before restart, is considered lost.
+	pub stall_timeout: Duration,
 	/// Message delivery race parameters.
 	pub delivery_params: MessageDeliveryParams,
 }
@@ -75,6 +89,10 @@ pub struct MessageDeliveryParams {
 	pub max_messages_weight_in_single_batch: Weight,
 	/// Maximal cumulative size of relayed messages in single delivery transaction.
 	pub max_messages_size_in_single_batch: u32,
+	/// If recent target chain blocks are, on average, filled at least by this ratio, the target
+	/// chain is considered congested and message delivery race stops submitting transactions
+	/// until congestion clears. If `None`, the delivery is never throttled.
+	pub congested_target_block_fullness: Option<Perbill>,
 }
 
 /// Message details.
@@ -211,6 +229,12 @@ pub trait TargetClient<P: MessageLane>: RelayClient {
 		id: TargetHeaderIdOf<P>,
 	) -> Result<(TargetHeaderIdOf<P>, UnrewardedRelayersState), Self::Error>;
 
+	/// Get fullness of the given target chain block.
+	async fn block_fullness(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, Perbill), Self::Error>;
+
 	/// Prove messages receiving at given block.
 	async fn prove_messages_receiving(
 		&self,
@@ -280,39 +304,222 @@ pub fn metrics_prefix<P: MessageLane>(lane: &LaneId) -> String {
 }
 
 /// Run message lane service loop.
+///
+/// If `since_source_block` is specified, the delivery race first reads messages at this source
+/// header. Messages, that have been sent before this header, may then be delivered as soon as
+/// the header (or its descendant) is known to the target node, even if the target node is far
+/// behind the best finalized source header. Message proofs are generated at old source headers
+/// in this case, so the source node must be an archive node.
 pub async fn run<P: MessageLane>(
 	params: Params,
+	since_source_block: Option<SourceHeaderIdOf<P>>,
 	source_client: impl SourceClient<P>,
 	target_client: impl TargetClient<P>,
 	metrics_params: MetricsParams,
 	exit_signal: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), relay_utils::Error> {
+	run_with_clock(
+		params,
+		since_source_block,
+		source_client,
+		target_client,
+		metrics_params,
+		Arc::new(SystemClock),
+		exit_signal,
+	)
+	.await
+}
+
+/// Run message lane service loop, using given clock to measure all delays (ticks, retry and
+/// reconnect delays).
+pub async fn run_with_clock<P: MessageLane>(
+	params: Params,
+	since_source_block: Option<SourceHeaderIdOf<P>>,
+	source_client: impl SourceClient<P>,
+	target_client: impl TargetClient<P>,
+	metrics_params: MetricsParams,
+	clock: Arc<dyn Clock>,
+	exit_signal: impl Future<Output = ()> + Send + 'static,
 ) -> Result<(), relay_utils::Error> {
 	let exit_signal = exit_signal.shared();
 	relay_utils::relay_loop(source_client, target_client)
 		.reconnect_delay(params.reconnect_delay)
+		.clock(clock.clone())
 		.with_metrics(metrics_params)
-		.loop_metric(MessageLaneLoopMetrics::new(Some(&metrics_prefix::<P>(&params.lane)))?)?
+		.loop_metric(MessageLaneLoopMetrics::new::<P>(
+			Some(&metrics_prefix::<P>(&params.lane)),
+			&params.lane,
+		)?)?
 		.expose()
 		.await?
 		.run(metrics_prefix::<P>(&params.lane), move |source_client, target_client, metrics| {
 			run_until_connection_lost(
 				params.clone(),
+				since_source_block.clone(),
 				source_client,
 				target_client,
 				metrics,
+				clock.clone(),
 				exit_signal.clone(),
 			)
 		})
 		.await
 }
 
+/// Deliver given range of messages, proved at given source header, and wait until delivery
+/// transaction is finalized. No checks are made to ensure that the transaction will succeed.
+///
+/// The source header must either be the best finalized source header, known to the target, or
+/// be a descendant of this header. In the latter case, the client may bundle the header proof
+/// with the messages proof (see `TargetClient::require_source_header_on_target`). Then the
+/// messages are proved at the header that is actually bundled, which may be a descendant of
+/// the given header.
+pub async fn relay_messages_range<P: MessageLane>(
+	source_client: impl SourceClient<P>,
+	target_client: impl TargetClient<P>,
+	at: SourceHeaderIdOf<P>,
+	range: RangeInclusive<MessageNonce>,
+	outbound_state_proof_required: bool,
+) -> Result<(), ()> {
+	if range.is_empty() {
+		log::error!(target: "bridge", "Invalid {} messages range: {:?}", P::SOURCE_NAME, range);
+		return Err(())
+	}
+
+	// if the header is not yet known to the target, we need to bundle its proof. We can't check
+	// whether older headers are still stored at the target, so the header must match the best
+	// finalized header, known to the target, or be its descendant
+	let target_state = target_client.state().await.map_err(|e| {
+		log::error!(target: "bridge", "Failed to read state of {}: {:?}", P::TARGET_NAME, e);
+	})?;
+	let best_finalized_peer_at_best_self = target_state.best_finalized_peer_at_best_self;
+	let is_header_known_to_target = best_finalized_peer_at_best_self.as_ref() == Some(&at);
+	if !is_header_known_to_target {
+		if let Some(ref best_finalized_peer_at_best_self) = best_finalized_peer_at_best_self {
+			if best_finalized_peer_at_best_self.0 >= at.0 {
+				log::error!(
+					target: "bridge",
+					"{} header {:?} can't be used to prove messages: the best {} header known to {} \
+					is {:?}",
+					P::SOURCE_NAME,
+					at,
+					P::SOURCE_NAME,
+					P::TARGET_NAME,
+					best_finalized_peer_at_best_self,
+				);
+				return Err(())
+			}
+		}
+	}
+	let maybe_batch_tx = if is_header_known_to_target {
+		None
+	} else {
+		match target_client.require_source_header_on_target(at.clone()).await {
+			Ok(Some(batch_tx)) => Some(batch_tx),
+			Ok(None) => {
+				log::error!(
+					target: "bridge",
+					"{} header {:?} is not known to {} and its proof can't be bundled with messages",
+					P::SOURCE_NAME,
+					at,
+					P::TARGET_NAME,
+				);
+				return Err(())
+			},
+			Err(e) => {
+				log::error!(
+					target: "bridge",
+					"Failed to prepare proof of {} header {:?}: {:?}",
+					P::SOURCE_NAME,
+					at,
+					e,
+				);
+				return Err(())
+			},
+		}
+	};
+
+	// the header that is bundled may be a descendant of the requested header
+	let at = match maybe_batch_tx {
+		Some(ref batch_tx) => batch_tx.required_header_id(),
+		None => at,
+	};
+
+	// compute cumulative dispatch weight of all messages in given range
+	let dispatch_weight = source_client
+		.generated_message_details(at.clone(), range.clone())
+		.await
+		.map_err(|e| {
+			log::error!(
+				target: "bridge",
+				"Failed to read details of {} messages {:?} at {:?}: {:?}",
+				P::SOURCE_NAME,
+				range,
+				at,
+				e,
+			);
+		})?
+		.values()
+		.fold(Weight::zero(), |total, details| total.saturating_add(details.dispatch_weight));
+
+	// prepare messages proof
+	let (at, range, proof) = source_client
+		.prove_messages(
+			at.clone(),
+			range.clone(),
+			MessageProofParameters { outbound_state_proof_required, dispatch_weight },
+		)
+		.await
+		.map_err(|e| {
+			log::error!(
+				target: "bridge",
+				"Failed to generate proof of {} messages {:?} at {:?}: {:?}",
+				P::SOURCE_NAME,
+				range,
+				at,
+				e,
+			);
+		})?;
+
+	// submit messages proof to the target node
+	let tx_tracker = target_client
+		.submit_messages_proof(maybe_batch_tx, at, range.clone(), proof)
+		.await
+		.map_err(|e| {
+			log::error!(
+				target: "bridge",
+				"Failed to submit {} messages {:?} proof to {}: {:?}",
+				P::SOURCE_NAME,
+				range,
+				P::TARGET_NAME,
+				e,
+			);
+		})?
+		.tx_tracker;
+
+	match tx_tracker.wait().await {
+		TrackedTransactionStatus::Finalized(_) => Ok(()),
+		TrackedTransactionStatus::Lost => {
+			log::error!(
+				target: "bridge",
+				"Transaction with {} messages {:?} has been lost",
+				P::SOURCE_NAME,
+				range,
+			);
+			Err(())
+		},
+	}
+}
+
 /// Run one-way message delivery loop until connection with target or source node is lost, or exit
 /// signal is received.
 async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: TargetClient<P>>(
 	params: Params,
+	since_source_block: Option<SourceHeaderIdOf<P>>,
 	source_client: SC,
 	target_client: TC,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	clock: Arc<dyn Clock>,
 	exit_signal: impl Future<Output = ()>,
 ) -> Result<(), FailedClient> {
 	let mut source_retry_backoff = retry_backoff();
@@ -320,19 +527,29 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 	let mut source_state_required = true;
 	let source_state = source_client.state().fuse();
 	let source_go_offline_future = futures::future::Fuse::terminated();
-	let source_tick_stream = interval(params.source_tick).fuse();
+	let source_tick_stream = interval_with_clock(clock.clone(), params.source_tick).fuse();
 
 	let mut target_retry_backoff = retry_backoff();
 	let mut target_client_is_online = false;
 	let mut target_state_required = true;
 	let target_state = target_client.state().fuse();
 	let target_go_offline_future = futures::future::Fuse::terminated();
-	let target_tick_stream = interval(params.target_tick).fuse();
+	let target_tick_stream = interval_with_clock(clock.clone(), params.target_tick).fuse();
 
 	let (
 		(delivery_source_state_sender, delivery_source_state_receiver),
 		(delivery_target_state_sender, delivery_target_state_receiver),
 	) = (unbounded(), unbounded());
+	// the delivery race will read nonces at the historical source header before reading them at
+	// the actual best finalized header
+	if let Some(since_source_block) = since_source_block {
+		let _ = delivery_source_state_sender.unbounded_send(ClientState {
+			best_self: since_source_block.clone(),
+			best_finalized_self: since_source_block,
+			best_finalized_peer_at_best_self: None,
+			actual_best_finalized_peer_at_best_self: None,
+		});
+	}
 	let delivery_race_loop = run_message_delivery_race(
 		source_client.clone(),
 		delivery_source_state_receiver,
@@ -340,6 +557,8 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 		delivery_target_state_receiver,
 		metrics_msg.clone(),
 		params.delivery_params,
+		params.stall_timeout,
+		clock.clone(),
 	)
 	.fuse();
 
@@ -353,6 +572,8 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 		target_client.clone(),
 		receiving_target_state_receiver,
 		metrics_msg.clone(),
+		params.stall_timeout,
+		clock.clone(),
 	)
 	.fuse();
 
@@ -393,7 +614,7 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 						}
 					},
 					&mut source_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error retrieving state from {} node", P::SOURCE_NAME),
 				).fail_if_connection_error(FailedClient::Source)?;
 			},
@@ -424,7 +645,7 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 						}
 					},
 					&mut target_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error retrieving state from {} node", P::TARGET_NAME),
 				).fail_if_connection_error(FailedClient::Target)?;
 			},
@@ -435,16 +656,18 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 				target_state_required = true;
 			},
 
-			delivery_error = delivery_race_loop => {
-				match delivery_error {
-					Ok(_) => unreachable!("only ends with error; qed"),
-					Err(err) => return Err(err),
+			delivery_result = delivery_race_loop => {
+				// race only ends with `Ok(())` when graceful shutdown is requested
+				delivery_result?;
+				if receiving_race_loop.is_terminated() {
+					return Ok(());
 				}
 			},
-			receiving_error = receiving_race_loop => {
-				match receiving_error {
-					Ok(_) => unreachable!("only ends with error; qed"),
-					Err(err) => return Err(err),
+			receiving_result = receiving_race_loop => {
+				// race only ends with `Ok(())` when graceful shutdown is requested
+				receiving_result?;
+				if delivery_race_loop.is_terminated() {
+					return Ok(());
 				}
 			},
 
@@ -474,7 +697,10 @@ pub(crate) mod tests {
 	use futures::stream::StreamExt;
 	use parking_lot::Mutex;
 
-	use relay_utils::{HeaderId, MaybeConnectionError, TrackedTransactionStatus};
+	use relay_utils::{
+		clock::TestClock, simulation::TestRng, HeaderId, MaybeConnectionError,
+		TrackedTransactionStatus,
+	};
 
 	use super::*;
 
@@ -579,6 +805,7 @@ pub(crate) mod tests {
 		target_latest_received_nonce: MessageNonce,
 		target_latest_confirmed_received_nonce: MessageNonce,
 		target_tracked_transaction_status: TrackedTransactionStatus<TestTargetHeaderId>,
+		target_block_fullness: Perbill,
 		submitted_messages_proofs: Vec<TestMessagesProof>,
 		target_to_source_batch_transaction: Option<TestConfirmationBatchTransaction>,
 		target_to_source_header_required: Option<TestTargetHeaderId>,
@@ -610,6 +837,7 @@ pub(crate) mod tests {
 					0,
 					Default::default(),
 				)),
+				target_block_fullness: Perbill::zero(),
 				submitted_messages_proofs: Vec::new(),
 				target_to_source_batch_transaction: None,
 				target_to_source_header_required: None,
@@ -879,6 +1107,13 @@ pub(crate) mod tests {
 			))
 		}
 
+		async fn block_fullness(
+			&self,
+			id: TargetHeaderIdOf<TestMessageLane>,
+		) -> Result<(TargetHeaderIdOf<TestMessageLane>, Perbill), TestError> {
+			Ok((id, self.data.lock().target_block_fullness))
+		}
+
 		async fn latest_confirmed_received_nonce(
 			&self,
 			id: TargetHeaderIdOf<TestMessageLane>,
@@ -936,6 +1171,24 @@ pub(crate) mod tests {
 		}
 	}
 
+	fn test_loop_params() -> Params {
+		Params {
+			lane: LaneId::new(1, 2),
+			source_tick: Duration::from_millis(100),
+			target_tick: Duration::from_millis(100),
+			reconnect_delay: Duration::from_millis(0),
+			stall_timeout: Duration::from_secs(60),
+			delivery_params: MessageDeliveryParams {
+				max_unrewarded_relayer_entries_at_target: 4,
+				max_unconfirmed_nonces_at_target: 4,
+				max_messages_in_single_batch: 4,
+				max_messages_weight_in_single_batch: Weight::from_parts(4, 0),
+				max_messages_size_in_single_batch: 4,
+				congested_target_block_fullness: None,
+			},
+		}
+	}
+
 	fn run_loop_test(
 		data: Arc<Mutex<TestClientData>>,
 		source_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
@@ -943,6 +1196,7 @@ pub(crate) mod tests {
 		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		target_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		exit_signal: impl Future<Output = ()> + 'static + Send,
+		since_source_block: Option<SourceHeaderIdOf<TestMessageLane>>,
 	) -> TestClientData {
 		async_std::task::block_on(async {
 			let source_client = TestSourceClient {
@@ -956,19 +1210,8 @@ pub(crate) mod tests {
 				post_tick: target_post_tick,
 			};
 			let _ = run(
-				Params {
-					lane: LaneId::new(1, 2),
-					source_tick: Duration::from_millis(100),
-					target_tick: Duration::from_millis(100),
-					reconnect_delay: Duration::from_millis(0),
-					delivery_params: MessageDeliveryParams {
-						max_unrewarded_relayer_entries_at_target: 4,
-						max_unconfirmed_nonces_at_target: 4,
-						max_messages_in_single_batch: 4,
-						max_messages_weight_in_single_batch: Weight::from_parts(4, 0),
-						max_messages_size_in_single_batch: 4,
-					},
-				},
+				test_loop_params(),
+				since_source_block,
 				source_client,
 				target_client,
 				MetricsParams::disabled(),
@@ -980,6 +1223,110 @@ pub(crate) mod tests {
 		})
 	}
 
+	fn relay_messages_range_test(data: TestClientData) -> (Result<(), ()>, TestClientData) {
+		let data = Arc::new(Mutex::new(data));
+		let source_client = TestSourceClient {
+			data: data.clone(),
+			tick: Arc::new(|_| {}),
+			post_tick: Arc::new(|_| {}),
+		};
+		let target_client = TestTargetClient {
+			data: data.clone(),
+			tick: Arc::new(|_| {}),
+			post_tick: Arc::new(|_| {}),
+		};
+		let result = async_std::task::block_on(relay_messages_range(
+			source_client,
+			target_client,
+			HeaderId(10, 10),
+			5..=7,
+			true,
+		));
+		let data = data.lock().clone();
+		(result, data)
+	}
+
+	#[test]
+	fn relay_messages_range_delivers_messages_if_source_header_is_known_to_target() {
+		let (result, data) = relay_messages_range_test(TestClientData {
+			source_latest_confirmed_received_nonce: 4,
+			target_state: ClientState {
+				best_finalized_peer_at_best_self: Some(HeaderId(10, 10)),
+				..Default::default()
+			},
+			..Default::default()
+		});
+
+		assert_eq!(result, Ok(()));
+		assert_eq!(data.submitted_messages_proofs, vec![(5..=7, Some(4))]);
+		assert!(data.source_to_target_header_requirements.is_empty());
+	}
+
+	#[test]
+	fn relay_messages_range_bundles_source_header_proof() {
+		let (result, data) = relay_messages_range_test(TestClientData {
+			source_latest_confirmed_received_nonce: 4,
+			target_state: ClientState {
+				best_finalized_peer_at_best_self: Some(HeaderId(5, 5)),
+				..Default::default()
+			},
+			source_to_target_batch_transaction: Some(TestMessagesBatchTransaction {
+				required_header_id: HeaderId(0, 0),
+			}),
+			..Default::default()
+		});
+
+		assert_eq!(result, Ok(()));
+		assert_eq!(data.submitted_messages_proofs, vec![(5..=7, Some(4))]);
+		assert_eq!(data.target_state.best_finalized_peer_at_best_self, Some(HeaderId(10, 10)));
+	}
+
+	#[test]
+	fn relay_messages_range_fails_if_source_header_is_unknown_to_target() {
+		let (result, data) = relay_messages_range_test(TestClientData {
+			target_state: ClientState {
+				best_finalized_peer_at_best_self: Some(HeaderId(5, 5)),
+				..Default::default()
+			},
+			..Default::default()
+		});
+
+		assert_eq!(result, Err(()));
+		assert!(data.submitted_messages_proofs.is_empty());
+	}
+
+	#[test]
+	fn relay_messages_range_fails_if_target_knows_other_source_header_with_same_number() {
+		let (result, data) = relay_messages_range_test(TestClientData {
+			target_state: ClientState {
+				best_finalized_peer_at_best_self: Some(HeaderId(10, 11)),
+				..Default::default()
+			},
+			source_to_target_batch_transaction: Some(TestMessagesBatchTransaction {
+				required_header_id: HeaderId(0, 0),
+			}),
+			..Default::default()
+		});
+
+		assert_eq!(result, Err(()));
+		assert!(data.submitted_messages_proofs.is_empty());
+		assert!(data.source_to_target_header_requirements.is_empty());
+	}
+
+	#[test]
+	fn relay_messages_range_fails_if_target_knows_better_source_header() {
+		let (result, data) = relay_messages_range_test(TestClientData {
+			target_state: ClientState {
+				best_finalized_peer_at_best_self: Some(HeaderId(15, 15)),
+				..Default::default()
+			},
+			..Default::default()
+		});
+
+		assert_eq!(result, Err(()));
+		assert!(data.submitted_messages_proofs.is_empty());
+	}
+
 	#[test]
 	fn message_lane_loop_is_able_to_recover_from_connection_errors() {
 		// with this configuration, source client will return Err, making source client
@@ -1028,6 +1375,7 @@ pub(crate) mod tests {
 			}),
 			Arc::new(|_| {}),
 			exit_receiver.into_future().map(|(_, _)| ()),
+			None,
 		);
 
 		assert_eq!(result.submitted_messages_proofs, vec![(1..=1, None)],);
@@ -1108,6 +1456,7 @@ pub(crate) mod tests {
 				}
 			}),
 			exit_receiver.into_future().map(|(_, _)| ()),
+			None,
 		);
 
 		assert_eq!(result.submitted_messages_proofs.len(), 2);
@@ -1188,6 +1537,7 @@ pub(crate) mod tests {
 			}),
 			Arc::new(|_| {}),
 			exit_receiver.into_future().map(|(_, _)| ()),
+			None,
 		);
 
 		// there are no strict restrictions on when reward confirmation should come
@@ -1204,6 +1554,47 @@ pub(crate) mod tests {
 		assert!(!result.source_to_target_header_requirements.is_empty());
 	}
 
+	#[test]
+	fn message_lane_loop_delivers_messages_since_historical_source_block() {
+		// the target node only knows source header #10, while messages have been sent before
+		// source header #5. Without reading nonces at #5, the loop would ask for header #100
+		let (exit_sender, exit_receiver) = unbounded();
+		let result = run_loop_test(
+			Arc::new(Mutex::new(TestClientData {
+				source_state: ClientState {
+					best_self: HeaderId(100, 100),
+					best_finalized_self: HeaderId(100, 100),
+					best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+					actual_best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+				},
+				source_latest_generated_nonce: 1,
+				target_state: ClientState {
+					best_self: HeaderId(0, 0),
+					best_finalized_self: HeaderId(0, 0),
+					best_finalized_peer_at_best_self: Some(HeaderId(10, 10)),
+					actual_best_finalized_peer_at_best_self: Some(HeaderId(10, 10)),
+				},
+				target_latest_received_nonce: 0,
+				..Default::default()
+			})),
+			Arc::new(|_| {}),
+			Arc::new(|_| {}),
+			Arc::new(|_| {}),
+			Arc::new(move |data: &mut TestClientData| {
+				if !data.submitted_messages_proofs.is_empty() ||
+					!data.source_to_target_header_requirements.is_empty()
+				{
+					exit_sender.unbounded_send(()).unwrap();
+				}
+			}),
+			exit_receiver.into_future().map(|(_, _)| ()),
+			Some(HeaderId(5, 5)),
+		);
+
+		assert!(!result.submitted_messages_proofs.is_empty());
+		assert!(result.source_to_target_header_requirements.is_empty());
+	}
+
 	#[test]
 	fn message_lane_loop_works_with_batch_transactions() {
 		let (exit_sender, exit_receiver) = unbounded();
@@ -1259,6 +1650,7 @@ pub(crate) mod tests {
 				}
 			}),
 			exit_receiver.into_future().map(|(_, _)| ()),
+			None,
 		);
 
 		// there are no strict restrictions on when reward confirmation should come
@@ -1275,11 +1667,184 @@ pub(crate) mod tests {
 		assert!(!result.source_to_target_header_requirements.is_empty());
 	}
 
+	/// Adversarial conditions, applied to clients on every tick.
+	#[derive(Clone, Copy, Debug)]
+	enum LoopScenario {
+		/// Both nodes are randomly failing RPC requests.
+		RpcFlaps,
+		/// Some other relayer is delivering messages concurrently with our relayer.
+		CompetingRelayer,
+		/// Best (not yet finalized) target headers are randomly replaced by their forks.
+		TargetReorgs,
+		/// Target blocks are randomly full, so transaction fees are spiking and the delivery
+		/// is paused until the target chain is not congested.
+		FeeSpikes,
+	}
+
+	/// Run messages loop under given scenario until all 10 messages are delivered and confirmed.
+	///
+	/// The loop is running using the `TestClock`, which is advanced by the loop tick once both
+	/// client tick streams are sleeping.
+	fn run_scripted_loop_test(scenario: LoopScenario, seed: u64) -> TestClientData {
+		const MAX_TICKS: u64 = 10_000;
+		const MAX_DURATION: Duration = Duration::from_secs(60 * 60);
+
+		let (exit_sender, exit_receiver) = unbounded();
+		let rng = Arc::new(Mutex::new(TestRng::new(seed)));
+		let source_rng = rng.clone();
+		let target_rng = rng;
+		let ticks = Arc::new(Mutex::new(0u64));
+		let clock = TestClock::new();
+		let started_at = clock.now();
+		let target_clock = clock.clone();
+
+		let mut params = test_loop_params();
+		if let LoopScenario::FeeSpikes = scenario {
+			params.delivery_params.congested_target_block_fullness =
+				Some(Perbill::from_percent(50));
+		}
+
+		let data = Arc::new(Mutex::new(TestClientData {
+			source_state: ClientState {
+				best_self: HeaderId(10, 10),
+				best_finalized_self: HeaderId(10, 10),
+				best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+				actual_best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+			},
+			source_latest_generated_nonce: 10,
+			target_state: ClientState {
+				best_self: HeaderId(0, 0),
+				best_finalized_self: HeaderId(0, 0),
+				best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+				actual_best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+			},
+			..Default::default()
+		}));
+		let source_client = TestSourceClient {
+			data: data.clone(),
+			tick: Arc::new(move |data: &mut TestClientData| {
+				let mut rng = source_rng.lock();
+				if let LoopScenario::RpcFlaps = scenario {
+					data.is_source_fails = rng.one_in(8);
+				}
+				// blocks are produced on every tick
+				data.source_state.best_self =
+					HeaderId(data.source_state.best_self.0 + 1, data.source_state.best_self.1 + 1);
+				data.source_state.best_finalized_self = data.source_state.best_self;
+				// syncing target headers -> source chain
+				if let Some(last_requirement) = data.target_to_source_header_requirements.last() {
+					data.source_state.best_finalized_peer_at_best_self = Some(*last_requirement);
+				}
+			}),
+			post_tick: Arc::new(|_| {}),
+		};
+		let target_client = TestTargetClient {
+			data: data.clone(),
+			tick: Arc::new(move |data: &mut TestClientData| {
+				let mut ticks = ticks.lock();
+				*ticks += 1;
+				assert!(
+					*ticks < MAX_TICKS,
+					"Scenario {scenario:?} with seed {seed} has not converged in {MAX_TICKS} ticks",
+				);
+				assert!(
+					target_clock.now() - started_at < MAX_DURATION,
+					"Scenario {scenario:?} with seed {seed} has not converged in {MAX_DURATION:?}",
+				);
+
+				let mut rng = target_rng.lock();
+				match scenario {
+					LoopScenario::RpcFlaps => data.is_target_fails = rng.one_in(8),
+					LoopScenario::CompetingRelayer =>
+						if data.target_latest_received_nonce < data.source_latest_generated_nonce &&
+							rng.one_in(3)
+						{
+							data.target_latest_received_nonce += 1;
+						},
+					LoopScenario::TargetReorgs => (),
+					LoopScenario::FeeSpikes =>
+						data.target_block_fullness =
+							if rng.one_in(2) { Perbill::one() } else { Perbill::zero() },
+				}
+				// blocks are produced on every tick, but only canonical blocks are finalized
+				let best_number = data.target_state.best_self.0 + 1;
+				data.target_state.best_self = HeaderId(best_number, best_number);
+				data.target_state.best_finalized_self = data.target_state.best_self;
+				if let LoopScenario::TargetReorgs = scenario {
+					if rng.one_in(3) {
+						data.target_state.best_finalized_self =
+							HeaderId(best_number - 1, best_number - 1);
+						data.target_state.best_self.1 += 1_000_000;
+					}
+				}
+				// syncing source headers -> target chain
+				if let Some(last_requirement) = data.source_to_target_header_requirements.last() {
+					data.target_state.best_finalized_peer_at_best_self = Some(*last_requirement);
+				}
+				// if source has received all messages receiving confirmations => stop
+				if data.source_latest_confirmed_received_nonce == 10 {
+					let _ = exit_sender.unbounded_send(());
+				}
+			}),
+			post_tick: Arc::new(|_| {}),
+		};
+
+		let _ = async_std::task::block_on(clock.drive(
+			run_with_clock(
+				params.clone(),
+				None,
+				source_client,
+				target_client,
+				MetricsParams::disabled(),
+				Arc::new(clock.clone()),
+				exit_receiver.into_future().map(|(_, _)| ()),
+			),
+			2,
+			params.target_tick,
+		));
+		let result = data.lock().clone();
+		result
+	}
+
+	fn assert_scripted_loop_test_works(scenario: LoopScenario) {
+		for seed in 0..3 {
+			let result = run_scripted_loop_test(scenario, seed);
+			assert_eq!(result.target_latest_received_nonce, 10);
+			assert_eq!(result.source_latest_confirmed_received_nonce, 10);
+			assert!(result
+				.submitted_messages_proofs
+				.iter()
+				.all(|(nonces, _)| *nonces.start() >= 1 && *nonces.end() <= 10));
+		}
+	}
+
+	#[test]
+	fn message_lane_loop_works_with_rpc_flaps() {
+		assert_scripted_loop_test_works(LoopScenario::RpcFlaps);
+	}
+
+	#[test]
+	fn message_lane_loop_works_with_competing_relayer() {
+		assert_scripted_loop_test_works(LoopScenario::CompetingRelayer);
+	}
+
+	#[test]
+	fn message_lane_loop_works_with_target_reorgs() {
+		assert_scripted_loop_test_works(LoopScenario::TargetReorgs);
+	}
+
+	#[test]
+	fn message_lane_loop_works_with_fee_spikes() {
+		assert_scripted_loop_test_works(LoopScenario::FeeSpikes);
+	}
+
 	#[test]
 	fn metrics_prefix_is_valid() {
-		assert!(MessageLaneLoopMetrics::new(Some(&metrics_prefix::<TestMessageLane>(
-			&LaneId::new(1, 2)
-		)))
+		let lane = LaneId::new(1, 2);
+		assert!(MessageLaneLoopMetrics::new::<TestMessageLane>(
+			Some(&metrics_prefix::<TestMessageLane>(&lane)),
+			&lane,
+		)
 		.is_ok());
 	}
 }
@@ -13,13 +13,16 @@
 
 //! Message delivery race delivers proof-of-messages from "lane.source" to "lane.target".
 
-use std::{collections::VecDeque, marker::PhantomData, ops::RangeInclusive};
+use std::{
+	collections::VecDeque, marker::PhantomData, ops::RangeInclusive, sync::Arc, time::Duration,
+};
 
 use async_trait::async_trait;
 use futures::stream::FusedStream;
 
 use bp_messages::{MessageNonce, UnrewardedRelayersState, Weight};
-use relay_utils::FailedClient;
+use relay_utils::{clock::Clock, FailedClient};
+use sp_arithmetic::Perbill;
 
 use crate::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
@@ -37,6 +40,9 @@ use crate::{
 	metrics::MessageLaneLoopMetrics,
 };
 
+/// Number of recent target chain blocks that are used to detect target chain congestion.
+const CONGESTION_DETECTION_BLOCKS: usize = 4;
+
 /// Run message delivery race.
 pub async fn run<P: MessageLane>(
 	source_client: impl MessageLaneSourceClient<P>,
@@ -45,6 +51,8 @@ pub async fn run<P: MessageLane>(
 	target_state_updates: impl FusedStream<Item = TargetClientState<P>>,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
 	params: MessageDeliveryParams,
+	stall_timeout: Duration,
+	clock: Arc<dyn Clock>,
 ) -> Result<(), FailedClient> {
 	crate::message_race_loop::run(
 		MessageDeliveryRaceSource {
@@ -56,6 +64,7 @@ pub async fn run<P: MessageLane>(
 		MessageDeliveryRaceTarget {
 			client: target_client.clone(),
 			metrics_msg: metrics_msg.clone(),
+			detect_congestion: params.congested_target_block_fullness.is_some(),
 			_phantom: Default::default(),
 		},
 		target_state_updates,
@@ -68,11 +77,16 @@ pub async fn run<P: MessageLane>(
 			max_messages_in_single_batch: params.max_messages_in_single_batch,
 			max_messages_weight_in_single_batch: params.max_messages_weight_in_single_batch,
 			max_messages_size_in_single_batch: params.max_messages_size_in_single_batch,
+			congested_target_block_fullness: params.congested_target_block_fullness,
+			recent_target_block_fullness: VecDeque::new(),
+			last_target_block_with_fullness: None,
 			latest_confirmed_nonces_at_source: VecDeque::new(),
 			target_nonces: None,
 			strategy: BasicStrategy::new(),
 			metrics_msg,
 		},
+		stall_timeout,
+		clock,
 	)
 	.await
 }
@@ -139,6 +153,10 @@ where
 			MessageDetailsMap::new()
 		};
 
+		if let Some(metrics_msg) = self.metrics_msg.as_ref() {
+			metrics_msg.note_generated_messages::<P>(&new_nonces);
+		}
+
 		Ok((
 			at_block,
 			SourceClientNonces { new_nonces, confirmed_nonce: Some(latest_confirmed_nonce) },
@@ -160,6 +178,8 @@ where
 struct MessageDeliveryRaceTarget<P: MessageLane, C> {
 	client: C,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	/// If `false`, fullness of target blocks is not read, because nobody uses it.
+	detect_congestion: bool,
 	_phantom: PhantomData<P>,
 }
 
@@ -170,7 +190,7 @@ where
 	C: MessageLaneTargetClient<P>,
 {
 	type Error = C::Error;
-	type TargetNoncesData = DeliveryRaceTargetNoncesData;
+	type TargetNoncesData = DeliveryRaceTargetNoncesData<TargetHeaderIdOf<P>>;
 	type BatchTransaction = C::BatchTransaction;
 	type TransactionTracker = C::TransactionTracker;
 
@@ -185,13 +205,24 @@ where
 		&self,
 		at_block: TargetHeaderIdOf<P>,
 		update_metrics: bool,
-	) -> Result<(TargetHeaderIdOf<P>, TargetClientNonces<DeliveryRaceTargetNoncesData>), Self::Error>
-	{
+	) -> Result<
+		(
+			TargetHeaderIdOf<P>,
+			TargetClientNonces<DeliveryRaceTargetNoncesData<TargetHeaderIdOf<P>>>,
+		),
+		Self::Error,
+	> {
 		let (at_block, latest_received_nonce) = self.client.latest_received_nonce(at_block).await?;
 		let (at_block, latest_confirmed_nonce) =
 			self.client.latest_confirmed_received_nonce(at_block).await?;
 		let (at_block, unrewarded_relayers) =
 			self.client.unrewarded_relayers_state(at_block).await?;
+		let (at_block, block_fullness) = if self.detect_congestion {
+			let (at_block, block_fullness) = self.client.block_fullness(at_block).await?;
+			(at_block.clone(), Some((at_block, block_fullness)))
+		} else {
+			(at_block, None)
+		};
 
 		if update_metrics {
 			if let Some(metrics_msg) = self.metrics_msg.as_ref() {
@@ -207,6 +238,7 @@ where
 				nonces_data: DeliveryRaceTargetNoncesData {
 					confirmed_nonce: latest_confirmed_nonce,
 					unrewarded_relayers,
+					block_fullness,
 				},
 			},
 		))
@@ -219,21 +251,28 @@ where
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::MessagesProof,
 	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error> {
-		self.client
+		let artifacts = self
+			.client
 			.submit_messages_proof(maybe_batch_tx, generated_at_block, nonces, proof)
-			.await
+			.await?;
+		if let Some(metrics_msg) = self.metrics_msg.as_ref() {
+			metrics_msg.note_delivery_transaction_submitted();
+		}
+		Ok(artifacts)
 	}
 }
 
 /// Additional nonces data from the target client used by message delivery race.
 #[derive(Debug, Clone)]
-struct DeliveryRaceTargetNoncesData {
+struct DeliveryRaceTargetNoncesData<TargetHeaderId> {
 	/// The latest nonce that we know: (1) has been delivered to us (2) has been confirmed
 	/// back to the source node (by confirmations race) and (3) relayer has received
 	/// reward for (and this has been confirmed by the message delivery race).
 	confirmed_nonce: MessageNonce,
 	/// State of the unrewarded relayers set at the target node.
 	unrewarded_relayers: UnrewardedRelayersState,
+	/// Fullness of the target block, if congestion detection is enabled.
+	block_fullness: Option<(TargetHeaderId, Perbill)>,
 }
 
 /// Messages delivery strategy.
@@ -252,11 +291,18 @@ struct MessageDeliveryStrategy<P: MessageLane, SC, TC> {
 	max_messages_weight_in_single_batch: Weight,
 	/// Maximal messages size in the single delivery transaction.
 	max_messages_size_in_single_batch: u32,
+	/// Average fullness of recent target blocks, starting from which the target chain is
+	/// considered congested.
+	congested_target_block_fullness: Option<Perbill>,
+	/// Fullness of recent best target blocks.
+	recent_target_block_fullness: VecDeque<Perbill>,
+	/// The last target block, which fullness has been added to `recent_target_block_fullness`.
+	last_target_block_with_fullness: Option<TargetHeaderIdOf<P>>,
 	/// Latest confirmed nonces at the source client + the header id where we have first met this
 	/// nonce.
 	latest_confirmed_nonces_at_source: VecDeque<(SourceHeaderIdOf<P>, MessageNonce)>,
 	/// Target nonces available at the **best** block of the target chain.
-	target_nonces: Option<TargetClientNonces<DeliveryRaceTargetNoncesData>>,
+	target_nonces: Option<TargetClientNonces<DeliveryRaceTargetNoncesData<TargetHeaderIdOf<P>>>>,
 	/// Basic delivery strategy.
 	strategy: MessageDeliveryStrategyBase<P>,
 	/// Message lane metrics.
@@ -283,6 +329,9 @@ impl<P: MessageLane, SC, TC> std::fmt::Debug for MessageDeliveryStrategy<P, SC,
 			.field("max_messages_in_single_batch", &self.max_messages_in_single_batch)
 			.field("max_messages_weight_in_single_batch", &self.max_messages_weight_in_single_batch)
 			.field("max_messages_size_in_single_batch", &self.max_messages_size_in_single_batch)
+			.field("congested_target_block_fullness", &self.congested_target_block_fullness)
+			.field("recent_target_block_fullness", &self.recent_target_block_fullness)
+			.field("last_target_block_with_fullness", &self.last_target_block_with_fullness)
 			.field("latest_confirmed_nonces_at_source", &self.latest_confirmed_nonces_at_source)
 			.field("target_nonces", &self.target_nonces)
 			.field("strategy", &self.strategy)
@@ -332,6 +381,12 @@ where
 			return None
 		}
 
+		// if target chain is congested, our transaction would likely lose priority races, so
+		// let's wait until congestion clears
+		if self.is_target_congested() {
+			return None
+		}
+
 		let best_target_nonce = self.strategy.best_at_target()?;
 		let best_finalized_source_header_id_at_best_target =
 			race_state.best_finalized_source_header_id_at_best_target()?;
@@ -446,6 +501,67 @@ where
 		))
 	}
 
+	/// Returns true if recent target blocks are, on average, filled above the congestion
+	/// threshold.
+	fn is_target_congested(&self) -> bool {
+		let congested_target_block_fullness = match self.congested_target_block_fullness {
+			Some(congested_target_block_fullness) => congested_target_block_fullness,
+			None => return false,
+		};
+		if self.recent_target_block_fullness.is_empty() {
+			return false
+		}
+
+		let total_fullness = self
+			.recent_target_block_fullness
+			.iter()
+			.map(|fullness| fullness.deconstruct() as u64)
+			.sum::<u64>();
+		let average_fullness = Perbill::from_parts(
+			(total_fullness / self.recent_target_block_fullness.len() as u64) as u32,
+		);
+		average_fullness >= congested_target_block_fullness
+	}
+
+	/// Remember fullness of the new best target block.
+	///
+	/// Target nonces are read more often than new blocks are produced, so the same block may
+	/// be seen several times. Every block is only taken into account once.
+	fn note_target_block_fullness(
+		&mut self,
+		at_block: TargetHeaderIdOf<P>,
+		block_fullness: Perbill,
+	) {
+		if self.last_target_block_with_fullness.as_ref() == Some(&at_block) {
+			return
+		}
+		self.last_target_block_with_fullness = Some(at_block);
+
+		let was_congested = self.is_target_congested();
+		self.recent_target_block_fullness.push_back(block_fullness);
+		while self.recent_target_block_fullness.len() > CONGESTION_DETECTION_BLOCKS {
+			self.recent_target_block_fullness.pop_front();
+		}
+
+		let is_congested = self.is_target_congested();
+		if is_congested != was_congested {
+			if is_congested {
+				log::info!(
+					target: "bridge",
+					"{} chain is congested (recent blocks fullness: {:?}). Pausing message delivery",
+					P::TARGET_NAME,
+					self.recent_target_block_fullness,
+				);
+			} else {
+				log::info!(
+					target: "bridge",
+					"{} chain is no longer congested. Resuming message delivery",
+					P::TARGET_NAME,
+				);
+			}
+		}
+	}
+
 	/// Returns lastest confirmed message at source chain, given source block.
 	fn latest_confirmed_nonce_at_source(&self, at: &SourceHeaderIdOf<P>) -> Option<MessageNonce> {
 		self.latest_confirmed_nonces_at_source
@@ -480,7 +596,7 @@ where
 {
 	type SourceNoncesRange = MessageDetailsMap<P::SourceChainBalance>;
 	type ProofParameters = MessageProofParameters;
-	type TargetNoncesData = DeliveryRaceTargetNoncesData;
+	type TargetNoncesData = DeliveryRaceTargetNoncesData<TargetHeaderIdOf<P>>;
 
 	fn is_empty(&self) -> bool {
 		self.strategy.is_empty()
@@ -568,11 +684,21 @@ where
 
 	fn best_target_nonces_updated<RS: RaceState<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>>>(
 		&mut self,
-		nonces: TargetClientNonces<DeliveryRaceTargetNoncesData>,
+		nonces: TargetClientNonces<DeliveryRaceTargetNoncesData<TargetHeaderIdOf<P>>>,
 		race_state: &mut RS,
 	) {
 		// best target nonces must always be ge than finalized target nonces
 		let latest_nonce = nonces.latest_nonce;
+		if let Some((at_block, block_fullness)) = nonces.nonces_data.block_fullness.clone() {
+			self.note_target_block_fullness(at_block, block_fullness);
+		}
+		if let Some(metrics_msg) = self.metrics_msg.as_ref() {
+			metrics_msg.update_target_unrewarded_relayers_state(
+				&nonces.nonces_data.unrewarded_relayers,
+				self.max_unrewarded_relayer_entries_at_target,
+				self.max_unconfirmed_nonces_at_target,
+			);
+		}
 		self.target_nonces = Some(nonces);
 
 		self.strategy.best_target_nonces_updated(
@@ -583,7 +709,7 @@ where
 
 	fn finalized_target_nonces_updated<RS: RaceState<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>>>(
 		&mut self,
-		nonces: TargetClientNonces<DeliveryRaceTargetNoncesData>,
+		nonces: TargetClientNonces<DeliveryRaceTargetNoncesData<TargetHeaderIdOf<P>>>,
 		race_state: &mut RS,
 	) {
 		if let Some(ref best_finalized_source_header_id_at_best_target) =
@@ -706,6 +832,9 @@ mod tests {
 			max_messages_in_single_batch: 4,
 			max_messages_weight_in_single_batch: Weight::from_parts(4, 0),
 			max_messages_size_in_single_batch: 4,
+			congested_target_block_fullness: Some(Perbill::from_percent(90)),
+			recent_target_block_fullness: VecDeque::new(),
+			last_target_block_with_fullness: None,
 			latest_confirmed_nonces_at_source: vec![(header_id(1), 19)].into_iter().collect(),
 			lane_source_client: TestSourceClient::default(),
 			lane_target_client: TestTargetClient::default(),
@@ -720,6 +849,7 @@ mod tests {
 						total_messages: 0,
 						last_delivered_nonce: 0,
 					},
+					block_fullness: None,
 				},
 			}),
 			strategy: BasicStrategy::new(),
@@ -822,6 +952,41 @@ mod tests {
 		assert_eq!(strategy.select_nonces_to_deliver(state).await, None);
 	}
 
+	#[async_std::test]
+	async fn message_delivery_strategy_selects_nothing_if_target_chain_is_congested() {
+		let (mut state, mut strategy) = prepare_strategy();
+
+		// recent target blocks are full => target is congested
+		strategy.congested_target_block_fullness = Some(Perbill::from_percent(70));
+		let mut target_nonces = strategy.target_nonces.clone().unwrap();
+		for i in 0..CONGESTION_DETECTION_BLOCKS {
+			target_nonces.nonces_data.block_fullness =
+				Some((header_id(10 + i as u64), Perbill::one()));
+			strategy.best_target_nonces_updated(target_nonces.clone(), &mut state);
+		}
+		assert!(strategy.is_target_congested());
+		assert_eq!(strategy.select_nonces_to_deliver(state.clone()).await, None);
+
+		// a single empty block doesn't immediately clear congestion
+		target_nonces.nonces_data.block_fullness = Some((header_id(20), Perbill::zero()));
+		strategy.best_target_nonces_updated(target_nonces.clone(), &mut state);
+		assert_eq!(strategy.select_nonces_to_deliver(state.clone()).await, None);
+
+		// reading nonces at the same block again doesn't change anything
+		strategy.best_target_nonces_updated(target_nonces.clone(), &mut state);
+		assert_eq!(strategy.recent_target_block_fullness.len(), CONGESTION_DETECTION_BLOCKS);
+		assert!(strategy.is_target_congested());
+
+		// but once recent blocks are less occupied, delivery is resumed
+		target_nonces.nonces_data.block_fullness = Some((header_id(21), Perbill::zero()));
+		strategy.best_target_nonces_updated(target_nonces, &mut state);
+		assert!(!strategy.is_target_congested());
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(false, 4)))
+		);
+	}
+
 	#[async_std::test]
 	async fn message_delivery_strategy_selects_nothing_if_proved_rewards_is_not_enough_to_remove_oldest_unrewarded_entry(
 	) {
@@ -1016,6 +1181,7 @@ mod tests {
 						total_messages: 4,
 						last_delivered_nonce: 23,
 					},
+					block_fullness: None,
 				},
 			},
 			&mut state,
@@ -1103,6 +1269,9 @@ mod tests {
 			max_messages_in_single_batch: 4,
 			max_messages_weight_in_single_batch: Weight::from_parts(4, 0),
 			max_messages_size_in_single_batch: 4,
+			congested_target_block_fullness: Some(Perbill::from_percent(90)),
+			recent_target_block_fullness: VecDeque::new(),
+			last_target_block_with_fullness: None,
 			latest_confirmed_nonces_at_source: VecDeque::new(),
 			lane_source_client: TestSourceClient::default(),
 			lane_target_client: TestTargetClient::default(),
@@ -1174,6 +1343,7 @@ mod tests {
 		let target_nonces_data = DeliveryRaceTargetNoncesData {
 			confirmed_nonce: 19,
 			unrewarded_relayers: UnrewardedRelayersState::default(),
+			block_fullness: None,
 		};
 		let target_header_2 = header_id(2);
 		state.best_target_header_id = Some(target_header_2);
@@ -1231,6 +1401,7 @@ mod tests {
 						total_messages: occupied_message_slots,
 						..Default::default()
 					},
+					block_fullness: None,
 				},
 			};
 
@@ -1388,6 +1559,7 @@ mod tests {
 					total_messages: 2,
 					..Default::default()
 				},
+				block_fullness: None,
 			},
 		};
 		state.best_target_header_id = Some(header_id(2));
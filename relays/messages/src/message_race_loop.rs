@@ -25,18 +25,21 @@ use crate::message_lane_loop::{BatchTransaction, ClientState, NoncesSubmitArtifa
 use async_trait::async_trait;
 use bp_messages::MessageNonce;
 use futures::{
-	future::{FutureExt, TryFutureExt},
+	future::{FusedFuture, FutureExt, TryFutureExt},
 	stream::{FusedStream, StreamExt},
 };
 use relay_utils::{
-	process_future_result, retry_backoff, FailedClient, MaybeConnectionError,
+	clock::Clock, process_future_result, retry_backoff, FailedClient, MaybeConnectionError,
 	TrackedTransactionStatus, TransactionTracker,
 };
+use serde::{Deserialize, Serialize};
 use std::{
 	fmt::Debug,
 	ops::RangeInclusive,
+	sync::Arc,
 	time::{Duration, Instant},
 };
+use tracing::Instrument;
 
 /// One of races within lane.
 pub trait MessageRace {
@@ -247,6 +250,14 @@ pub trait RaceState<SourceHeaderId, TargetHeaderId>: Clone + Send + Sync {
 	fn reset_nonces_submitted(&mut self);
 }
 
+/// Race state, that is saved to the relay state database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct RaceLoopState {
+	/// Range of nonces that we have submitted to the target node, if the submission transaction
+	/// has not failed.
+	nonces_submitted: Option<RangeInclusive<MessageNonce>>,
+}
+
 /// State of the race and prepared batch transaction (if available).
 #[derive(Debug, Clone)]
 pub(crate) struct RaceStateImpl<SourceHeaderId, TargetHeaderId, Proof, BatchTx> {
@@ -284,6 +295,20 @@ impl<SourceHeaderId, TargetHeaderId, Proof, BatchTx> Default
 	}
 }
 
+impl<SourceHeaderId, TargetHeaderId, Proof, BatchTx>
+	RaceStateImpl<SourceHeaderId, TargetHeaderId, Proof, BatchTx>
+{
+	/// Resume from the state, saved before restart. Returns range of nonces that have been
+	/// submitted before restart.
+	///
+	/// Those nonces are treated as submitted, so the race strategy won't select them again
+	/// until they're delivered or until the submission is considered lost.
+	fn resume(&mut self, state: Option<RaceLoopState>) -> Option<RangeInclusive<MessageNonce>> {
+		self.nonces_submitted = state.and_then(|state| state.nonces_submitted);
+		self.nonces_submitted.clone()
+	}
+}
+
 impl<SourceHeaderId, TargetHeaderId, Proof, BatchTx> RaceState<SourceHeaderId, TargetHeaderId>
 	for RaceStateImpl<SourceHeaderId, TargetHeaderId, Proof, BatchTx>
 where
@@ -331,6 +356,15 @@ where
 }
 
 /// Run race loop until connection with target or source node is lost.
+///
+/// All delays (e.g. before retrying failed requests) are measured using given `clock`.
+///
+/// If the relay state database is opened, the range of submitted nonces is saved there. After
+/// restart, those nonces are not submitted again until they're delivered or until the
+/// `stall_timeout` expires.
+///
+/// If graceful shutdown is requested, the loop stops starting new work and returns `Ok(())`
+/// once its in-flight transaction is tracked to finalization (or lost).
 pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 	race_source: SC,
 	race_source_updated: impl FusedStream<Item = SourceClientState<P>>,
@@ -344,9 +378,15 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 		ProofParameters = SC::ProofParameters,
 		TargetNoncesData = TC::TargetNoncesData,
 	>,
+	stall_timeout: Duration,
+	clock: Arc<dyn Clock>,
 ) -> Result<(), FailedClient> {
 	let mut progress_context = Instant::now();
 	let mut race_state = RaceStateImpl::default();
+	let race_state_name = format!("{} -> {}", P::source_name(), P::target_name());
+	let resumed_nonces_submitted =
+		race_state.resume(relay_utils::state_store::load(&race_state_name));
+	let resumed_submission_timeout = futures::future::Fuse::terminated();
 
 	let mut source_retry_backoff = retry_backoff();
 	let mut source_client_is_online = true;
@@ -368,6 +408,14 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 	let target_tx_tracker = futures::future::Fuse::terminated();
 	let target_go_offline_future = futures::future::Fuse::terminated();
 
+	// span that covers delivery of the single nonces range: from asking for the proof and
+	// until the delivery transaction is finalized or lost
+	let mut journey_span = tracing::Span::none();
+
+	let mut is_shutting_down = false;
+	let shutdown_requested = relay_utils::shutdown::shutdown_requested().fuse();
+	let shutdown_timeout = futures::future::Fuse::terminated();
+
 	futures::pin_mut!(
 		race_source_updated,
 		source_nonces,
@@ -380,8 +428,15 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 		target_submit_proof,
 		target_tx_tracker,
 		target_go_offline_future,
+		shutdown_requested,
+		shutdown_timeout,
+		resumed_submission_timeout,
 	);
 
+	if resumed_nonces_submitted.is_some() {
+		resumed_submission_timeout.set(clock.sleep(stall_timeout).fuse());
+	}
+
 	loop {
 		futures::select! {
 			// when headers ids are updated
@@ -433,9 +488,10 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 						);
 
 						strategy.source_nonces_updated(at_block, nonces);
+						note_race_state::<P, _>(&race_state_name, &strategy);
 					},
 					&mut source_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error retrieving nonces from {}", P::source_name()),
 				).fail_if_connection_error(FailedClient::Source)?;
 
@@ -459,9 +515,10 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 						);
 
 						strategy.best_target_nonces_updated(nonces, &mut race_state);
+						note_race_state::<P, _>(&race_state_name, &strategy);
 					},
 					&mut target_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error retrieving best nonces from {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
 			},
@@ -482,7 +539,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 						strategy.finalized_target_nonces_updated(nonces, &mut race_state);
 					},
 					&mut target_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error retrieving finalized nonces from {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
 			},
@@ -509,7 +566,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 						target_batch_transaction = maybe_batch_transaction;
 					},
 					&mut target_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error asking for source headers at {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
 			},
@@ -529,27 +586,47 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 						race_state.nonces_to_submit_batch = batch_transaction;
 					},
 					&mut source_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error generating proof at {}", P::source_name()),
 				).fail_if_error(FailedClient::Source).map(|_| true)?;
 			},
 			proof_submit_result = target_submit_proof => {
+				if proof_submit_result.is_err() {
+					relay_utils::alerts::note_submission_result(false);
+				}
 				target_client_is_online = process_future_result(
 					proof_submit_result,
 					&mut target_retry_backoff,
 					|artifacts: NoncesSubmitArtifacts<TC::TransactionTracker>| {
 						log::debug!(
 							target: "bridge",
+							chain = P::target_name().as_str(),
+							nonces_begin = *artifacts.nonces.start(),
+							nonces_end = *artifacts.nonces.end();
 							"Successfully submitted proof of nonces {:?} to {}",
 							artifacts.nonces,
 							P::target_name(),
 						);
 
-						race_state.nonces_submitted = Some(artifacts.nonces);
-						target_tx_tracker.set(artifacts.tx_tracker.wait().fuse());
+						race_state.nonces_submitted = Some(artifacts.nonces.clone());
+						relay_utils::state_store::save(
+							&race_state_name,
+							&RaceLoopState { nonces_submitted: Some(artifacts.nonces) },
+						);
+						target_tx_tracker.set(
+							artifacts
+								.tx_tracker
+								.wait()
+								.instrument(tracing::info_span!(
+									target: "bridge",
+									parent: &journey_span,
+									"wait_finalization",
+								))
+								.fuse(),
+						);
 					},
 					&mut target_go_offline_future,
-					async_std::task::sleep,
+					|delay| clock.sleep(delay),
 					|| format!("Error submitting proof {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
 
@@ -581,7 +658,13 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 									Ok(())
 								}
 							})
+							.map(|_| {
+								journey_span.record("status", "finalized");
+								relay_utils::alerts::note_submission_result(true);
+							})
 							.map_err(|e| {
+								journey_span.record("status", "failed");
+								relay_utils::alerts::note_submission_result(false);
 								log::error!(
 									target: "bridge",
 									"{} -> {} race transaction failed: {}",
@@ -591,7 +674,12 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 								);
 
 								race_state.reset_nonces_submitted();
+								relay_utils::state_store::save(
+									&race_state_name,
+									&RaceLoopState::default(),
+								);
 							});
+						journey_span = tracing::Span::none();
 					},
 					(TrackedTransactionStatus::Lost, _) => {
 						log::warn!(
@@ -603,11 +691,35 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 							strategy,
 						);
 
+						journey_span.record("status", "lost");
+						relay_utils::alerts::note_submission_result(false);
+						journey_span = tracing::Span::none();
 						race_state.reset_nonces_submitted();
+						relay_utils::state_store::save(&race_state_name, &RaceLoopState::default());
 					},
 					_ => (),
 				}
 			},
+			_ = resumed_submission_timeout => {
+				// if nonces have been submitted by this relay instance, then we have the
+				// transaction tracker to tell us its status
+				let is_resumed_submission_pending = target_tx_tracker.is_terminated() &&
+					race_state.nonces_submitted.is_some() &&
+					race_state.nonces_submitted == resumed_nonces_submitted;
+				if is_resumed_submission_pending {
+					log::warn!(
+						target: "bridge",
+						"{} -> {} race transaction with nonces {:?}, submitted before restart, has not \
+						been delivered in {}s. Considering it lost",
+						P::source_name(),
+						P::target_name(),
+						resumed_nonces_submitted,
+						stall_timeout.as_secs(),
+					);
+					race_state.reset_nonces_submitted();
+					relay_utils::state_store::save(&race_state_name, &RaceLoopState::default());
+				}
+			},
 
 			// when we're ready to retry request
 			_ = source_go_offline_future => {
@@ -616,11 +728,44 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 			_ = target_go_offline_future => {
 				target_client_is_online = true;
 			},
+
+			// when we're asked to stop
+			_ = shutdown_requested => {
+				is_shutting_down = true;
+				shutdown_timeout
+					.set(clock.sleep(relay_utils::shutdown::SHUTDOWN_DRAIN_TIMEOUT).fuse());
+			},
+			_ = shutdown_timeout => {
+				log::warn!(
+					target: "bridge",
+					"{} -> {} race is shutting down. Failed to track in-flight transaction status in {}s",
+					P::source_name(),
+					P::target_name(),
+					relay_utils::shutdown::SHUTDOWN_DRAIN_TIMEOUT.as_secs(),
+				);
+				return Ok(())
+			},
 		}
 
 		progress_context = print_race_progress::<P, _>(progress_context, &strategy);
 
-		if source_client_is_online {
+		// when shutting down, we only wait for the in-flight transaction
+		if is_shutting_down {
+			if target_submit_proof.is_terminated() && target_tx_tracker.is_terminated() {
+				log::info!(
+					target: "bridge",
+					"{} -> {} race has no in-flight transactions. Shutting down",
+					P::source_name(),
+					P::target_name(),
+				);
+				return Ok(())
+			}
+
+			continue
+		}
+
+		// paused loop doesn't submit anything
+		if source_client_is_online && !relay_utils::control::is_paused() {
 			source_client_is_online = false;
 
 			// if we've started to submit batch transaction, let's prioritize it
@@ -656,12 +801,27 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 					at_block,
 				);
 
+				journey_span = tracing::info_span!(
+					target: "bridge",
+					parent: None,
+					"messages_race",
+					source_chain = %P::source_name(),
+					target_chain = %P::target_name(),
+					nonces_begin = *nonces_range.start(),
+					nonces_end = *nonces_range.end(),
+					status = tracing::field::Empty,
+				);
 				source_generate_proof.set(
 					race_source
 						.generate_proof(at_block, nonces_range, proof_parameters)
 						.and_then(|(at_source_block, nonces, proof)| async {
 							Ok((at_source_block, nonces, proof, target_batch_transaction))
 						})
+						.instrument(tracing::info_span!(
+							target: "bridge",
+							parent: &journey_span,
+							"generate_proof",
+						))
 						.fuse(),
 				);
 			} else if let (true, Some(best_at_source)) = (source_nonces_required, best_at_source) {
@@ -686,6 +846,9 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 			if let Some((at_block, nonces_range, proof)) = race_state.nonces_to_submit.as_ref() {
 				log::debug!(
 					target: "bridge",
+					chain = P::target_name().as_str(),
+					nonces_begin = *nonces_range.start(),
+					nonces_end = *nonces_range.end();
 					"Going to submit proof of messages in range {:?} to {} node{}",
 					nonces_range,
 					P::target_name(),
@@ -703,6 +866,11 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 							nonces_range.clone(),
 							proof.clone(),
 						)
+						.instrument(tracing::info_span!(
+							target: "bridge",
+							parent: &journey_span,
+							"submit_proof",
+						))
 						.fuse(),
 				);
 			} else if let Some(source_required_header) = source_required_header.clone() {
@@ -758,6 +926,10 @@ where
 	let now_best_nonce_at_target = strategy.best_at_target();
 	log::info!(
 		target: "bridge",
+		source_chain = P::source_name().as_str(),
+		target_chain = P::target_name().as_str(),
+		best_nonce_at_source = now_best_nonce_at_source,
+		best_nonce_at_target = now_best_nonce_at_target;
 		"Synced {:?} of {:?} nonces in {} -> {} race",
 		now_best_nonce_at_target,
 		now_best_nonce_at_source,
@@ -767,6 +939,17 @@ where
 	now_time
 }
 
+/// Note race state in the health state of the relay loop.
+fn note_race_state<P, S>(race_name: &str, strategy: &S)
+where
+	P: MessageRace,
+	S: RaceStrategy<P::SourceHeaderId, P::TargetHeaderId, P::Proof>,
+{
+	if let Some(best_at_target) = strategy.best_at_target() {
+		relay_utils::health::note_race_state(race_name, strategy.best_at_source(), best_at_target);
+	}
+}
+
 async fn select_nonces_to_deliver<SourceHeaderId, TargetHeaderId, Proof, Strategy>(
 	race_state: impl RaceState<SourceHeaderId, TargetHeaderId>,
 	strategy: &Strategy,
@@ -832,4 +1015,42 @@ mod tests {
 			Some((HeaderId(BEST_AT_TARGET, BEST_AT_TARGET), 6..=10, (),))
 		);
 	}
+
+	#[async_std::test]
+	async fn nonces_submitted_before_restart_are_not_selected_until_delivered() {
+		let mut race_state = RaceStateImpl::<_, _, (), ()> {
+			best_finalized_source_header_id_at_source: Some(HeaderId(10, 10)),
+			best_finalized_source_header_id_at_best_target: Some(HeaderId(10, 10)),
+			best_target_header_id: Some(HeaderId(0, 0)),
+			best_finalized_target_header_id: Some(HeaderId(0, 0)),
+			..Default::default()
+		};
+		assert_eq!(
+			race_state.resume(Some(RaceLoopState { nonces_submitted: Some(6..=8) })),
+			Some(6..=8),
+		);
+
+		let mut strategy = BasicStrategy::<_, _, _, _, _, ()>::new();
+		strategy.source_nonces_updated(
+			HeaderId(10, 10),
+			SourceClientNonces { new_nonces: 0..=10, confirmed_nonce: None },
+		);
+		strategy.best_target_nonces_updated(
+			TargetClientNonces { latest_nonce: 5u64, nonces_data: () },
+			&mut race_state,
+		);
+
+		// nonces 6..=8 have been submitted before restart => nothing is selected
+		assert_eq!(select_nonces_to_deliver(race_state.clone(), &strategy).await, None);
+
+		// once nonces are delivered, the race continues from the next nonce
+		strategy.best_target_nonces_updated(
+			TargetClientNonces { latest_nonce: 8u64, nonces_data: () },
+			&mut race_state,
+		);
+		assert_eq!(
+			select_nonces_to_deliver(race_state, &strategy).await,
+			Some((HeaderId(10, 10), 9..=10, (),))
+		);
+	}
 }
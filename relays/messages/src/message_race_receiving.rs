@@ -30,8 +30,8 @@ use crate::{
 use async_trait::async_trait;
 use bp_messages::MessageNonce;
 use futures::stream::FusedStream;
-use relay_utils::FailedClient;
-use std::{marker::PhantomData, ops::RangeInclusive};
+use relay_utils::{clock::Clock, FailedClient};
+use std::{marker::PhantomData, ops::RangeInclusive, sync::Arc, time::Duration};
 
 /// Message receiving confirmations delivery strategy.
 type ReceivingConfirmationsBasicStrategy<P> = BasicStrategy<
@@ -50,6 +50,8 @@ pub async fn run<P: MessageLane>(
 	target_client: impl MessageLaneTargetClient<P>,
 	target_state_updates: impl FusedStream<Item = TargetClientState<P>>,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	stall_timeout: Duration,
+	clock: Arc<dyn Clock>,
 ) -> Result<(), FailedClient> {
 	crate::message_race_loop::run(
 		ReceivingConfirmationsRaceSource {
@@ -65,6 +67,8 @@ pub async fn run<P: MessageLane>(
 		},
 		source_state_updates,
 		ReceivingConfirmationsBasicStrategy::<P>::new(),
+		stall_timeout,
+		clock,
 	)
 	.await
 }
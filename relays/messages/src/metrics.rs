@@ -18,14 +18,38 @@
 
 use crate::{
 	message_lane::MessageLane,
-	message_lane_loop::{SourceClientState, TargetClientState},
+	message_lane_loop::{MessageDetailsMap, SourceClientState, TargetClientState},
 };
 
-use bp_messages::MessageNonce;
+use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState};
 use finality_relay::SyncLoopMetrics;
-use relay_utils::metrics::{
-	metric_name, register, GaugeVec, Metric, Opts, PrometheusError, Registry, U64,
+use parking_lot::Mutex;
+use relay_utils::{
+	metrics::{
+		metric_name, register, Counter, GaugeVec, Histogram, HistogramOpts, Metric, Opts,
+		PrometheusError, Registry, U64,
+	},
+	UniqueSaturatedInto,
 };
+use std::{collections::BTreeMap, sync::Arc, time::Instant};
+
+/// Buckets (in seconds) of the message delivery latency histogram.
+const DELIVERY_LATENCY_BUCKETS: [f64; 10] =
+	[6.0, 12.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 3600.0, 7200.0];
+
+/// Number of target chain blocks, during which the latest received nonce may stay the same while
+/// there are undelivered messages at the source, before the delivery is considered stalled.
+const DELIVERY_STALL_ALERT_BLOCKS: u64 = 64;
+
+/// Alert reason: the latest received nonce at the target hasn't advanced for at least
+/// `DELIVERY_STALL_ALERT_BLOCKS` target blocks while there are undelivered messages.
+const ALERT_DELIVERY_STALLED: &str = "delivery_stalled";
+/// Alert reason: the unrewarded relayers vector at the target is full, so new messages will be
+/// rejected until rewards are confirmed.
+const ALERT_UNREWARDED_RELAYERS_FULL: &str = "unrewarded_relayers_full";
+/// Alert reason: there are too many unconfirmed messages at the target, so new messages will be
+/// rejected until delivery is confirmed.
+const ALERT_UNCONFIRMED_MESSAGES_FULL: &str = "unconfirmed_messages_full";
 
 /// Message lane relay metrics.
 ///
@@ -39,11 +63,67 @@ pub struct MessageLaneLoopMetrics {
 	/// Lane state nonces: "source_latest_generated", "source_latest_confirmed",
 	/// "target_latest_received", "target_latest_confirmed".
 	lane_state_nonces: GaugeVec<U64>,
+	/// Number of message delivery transactions submitted to the target node.
+	delivery_transactions: Counter<U64>,
+	/// Cumulative reward of messages, delivered to the target node.
+	delivered_messages_reward: Counter<U64>,
+	/// Time between the moment when the relay has first seen the message at the source node and
+	/// the moment when it has seen the message received at the target node.
+	delivery_latency: Histogram,
+	/// Messages that are generated at the source, but not yet seen at the target, mapped to
+	/// their reward and the moment when the relay has first seen them.
+	undelivered_messages: Arc<Mutex<BTreeMap<MessageNonce, (Instant, u64)>>>,
+	/// Lane alerts, labeled with the alert reason. Alert is raised if the value is `1`.
+	lane_alerts: GaugeVec<U64>,
+	/// State, used to detect stalled delivery.
+	delivery_stall_detector: Arc<Mutex<DeliveryStallDetector>>,
+}
+
+/// State, used to detect stalled delivery.
+#[derive(Debug, Default)]
+struct DeliveryStallDetector {
+	/// Latest generated nonce at the source.
+	source_latest_generated_nonce: MessageNonce,
+	/// Latest received nonce at the target.
+	target_latest_received_nonce: MessageNonce,
+	/// Best known target block number.
+	best_target_block_number: u64,
+	/// Best target block number at the moment when the latest received nonce has been updated
+	/// last time or when new messages have been generated at the (idle) source.
+	stalled_since_target_block_number: Option<u64>,
+}
+
+impl DeliveryStallDetector {
+	/// Returns true if the delivery is stalled.
+	fn is_stalled(&mut self) -> bool {
+		if self.source_latest_generated_nonce <= self.target_latest_received_nonce {
+			self.stalled_since_target_block_number = None;
+			return false
+		}
+
+		let stalled_since = *self
+			.stalled_since_target_block_number
+			.get_or_insert(self.best_target_block_number);
+		self.best_target_block_number.saturating_sub(stalled_since) >= DELIVERY_STALL_ALERT_BLOCKS
+	}
 }
 
 impl MessageLaneLoopMetrics {
 	/// Create and register messages loop metrics.
-	pub fn new(prefix: Option<&str>) -> Result<Self, PrometheusError> {
+	///
+	/// All lane-specific metrics are labeled with the lane id and the lane direction.
+	pub fn new<P: MessageLane>(
+		prefix: Option<&str>,
+		lane: &LaneId,
+	) -> Result<Self, PrometheusError> {
+		let lane_label = format!("{lane:?}");
+		let direction_label = format!("{}_to_{}", P::SOURCE_NAME, P::TARGET_NAME);
+		let lane_opts = |name: &str, help: &str| {
+			Opts::new(metric_name(prefix, name), help)
+				.const_label("lane", &lane_label)
+				.const_label("direction", &direction_label)
+		};
+
 		Ok(MessageLaneLoopMetrics {
 			source_to_target_finality_metrics: SyncLoopMetrics::new(
 				prefix,
@@ -56,9 +136,30 @@ impl MessageLaneLoopMetrics {
 				"target_at_source",
 			)?,
 			lane_state_nonces: GaugeVec::new(
-				Opts::new(metric_name(prefix, "lane_state_nonces"), "Nonces of the lane state"),
+				lane_opts("lane_state_nonces", "Nonces of the lane state"),
 				&["type"],
 			)?,
+			delivery_transactions: Counter::with_opts(lane_opts(
+				"lane_delivery_transactions",
+				"Number of submitted message delivery transactions",
+			))?,
+			delivered_messages_reward: Counter::with_opts(lane_opts(
+				"lane_delivered_messages_reward",
+				"Cumulative reward of delivered messages",
+			))?,
+			delivery_latency: Histogram::with_opts(
+				HistogramOpts::from(lane_opts(
+					"lane_delivery_latency",
+					"Time (in seconds) between message generation and its dispatch at the target",
+				))
+				.buckets(DELIVERY_LATENCY_BUCKETS.to_vec()),
+			)?,
+			undelivered_messages: Arc::new(Mutex::new(BTreeMap::new())),
+			lane_alerts: GaugeVec::new(
+				lane_opts("lane_alerts", "Lane alerts. Alert is raised if the value is 1"),
+				&["reason"],
+			)?,
+			delivery_stall_detector: Arc::new(Mutex::new(DeliveryStallDetector::default())),
 		})
 	}
 
@@ -83,6 +184,10 @@ impl MessageLaneLoopMetrics {
 
 	/// Update target client state metrics.
 	pub fn update_target_state<P: MessageLane>(&self, target_client_state: TargetClientState<P>) {
+		self.update_delivery_stall_alert(|detector| {
+			detector.best_target_block_number =
+				target_client_state.best_self.0.unique_saturated_into()
+		});
 		self.target_to_source_finality_metrics
 			.update_best_block_at_source(target_client_state.best_self.0);
 		if let Some(best_finalized_peer_at_best_self) =
@@ -108,6 +213,9 @@ impl MessageLaneLoopMetrics {
 		self.lane_state_nonces
 			.with_label_values(&["source_latest_generated"])
 			.set(source_latest_generated_nonce);
+		self.update_delivery_stall_alert(|detector| {
+			detector.source_latest_generated_nonce = source_latest_generated_nonce
+		});
 	}
 
 	/// Update the latest confirmed nonce at source.
@@ -125,6 +233,41 @@ impl MessageLaneLoopMetrics {
 		self.lane_state_nonces
 			.with_label_values(&["target_latest_received"])
 			.set(target_latest_generated_nonce);
+		self.update_delivery_stall_alert(|detector| {
+			if detector.target_latest_received_nonce != target_latest_generated_nonce {
+				detector.target_latest_received_nonce = target_latest_generated_nonce;
+				detector.stalled_since_target_block_number = None;
+			}
+		});
+
+		let mut undelivered_messages = self.undelivered_messages.lock();
+		let still_undelivered =
+			undelivered_messages.split_off(&(target_latest_generated_nonce + 1));
+		let delivered = std::mem::replace(&mut *undelivered_messages, still_undelivered);
+		for (seen_at, reward) in delivered.into_values() {
+			self.delivery_latency.observe(seen_at.elapsed().as_secs_f64());
+			self.delivered_messages_reward.inc_by(reward);
+		}
+	}
+
+	/// Remember messages that have been generated at the source, so that we can compute their
+	/// delivery latency later.
+	pub fn note_generated_messages<P: MessageLane>(
+		&self,
+		messages: &MessageDetailsMap<P::SourceChainBalance>,
+	) {
+		let now = Instant::now();
+		let mut undelivered_messages = self.undelivered_messages.lock();
+		for (nonce, details) in messages {
+			undelivered_messages
+				.entry(*nonce)
+				.or_insert_with(|| (now, details.reward.unique_saturated_into()));
+		}
+	}
+
+	/// Note that the message delivery transaction has been submitted.
+	pub fn note_delivery_transaction_submitted(&self) {
+		self.delivery_transactions.inc();
 	}
 
 	/// Update the latest confirmed nonce at target.
@@ -136,6 +279,42 @@ impl MessageLaneLoopMetrics {
 			.with_label_values(&["target_latest_confirmed"])
 			.set(target_latest_confirmed_nonce);
 	}
+
+	/// Update alerts that are raised when the target rejects new messages because of too many
+	/// unrewarded relayer entries or unconfirmed messages.
+	pub fn update_target_unrewarded_relayers_state(
+		&self,
+		unrewarded_relayers: &UnrewardedRelayersState,
+		max_unrewarded_relayer_entries_at_target: MessageNonce,
+		max_unconfirmed_nonces_at_target: MessageNonce,
+	) {
+		self.set_alert(
+			ALERT_UNREWARDED_RELAYERS_FULL,
+			unrewarded_relayers.unrewarded_relayer_entries >=
+				max_unrewarded_relayer_entries_at_target,
+		);
+		self.set_alert(
+			ALERT_UNCONFIRMED_MESSAGES_FULL,
+			unrewarded_relayers.total_messages >= max_unconfirmed_nonces_at_target,
+		);
+	}
+
+	/// Update delivery stall detector state and the corresponding alert.
+	fn update_delivery_stall_alert(&self, update: impl FnOnce(&mut DeliveryStallDetector)) {
+		let mut detector = self.delivery_stall_detector.lock();
+		update(&mut detector);
+		let is_stalled = detector.is_stalled();
+		self.set_alert(ALERT_DELIVERY_STALLED, is_stalled);
+	}
+
+	/// Raise or clear given alert.
+	fn set_alert(&self, reason: &str, is_raised: bool) {
+		let alert = self.lane_alerts.with_label_values(&[reason]);
+		if is_raised && alert.get() == 0 {
+			log::warn!(target: "bridge", "Lane alert is raised: {}", reason);
+		}
+		alert.set(is_raised.into());
+	}
 }
 
 impl Metric for MessageLaneLoopMetrics {
@@ -143,6 +322,90 @@ impl Metric for MessageLaneLoopMetrics {
 		self.source_to_target_finality_metrics.register(registry)?;
 		self.target_to_source_finality_metrics.register(registry)?;
 		register(self.lane_state_nonces.clone(), registry)?;
+		register(self.delivery_transactions.clone(), registry)?;
+		register(self.delivered_messages_reward.clone(), registry)?;
+		register(self.delivery_latency.clone(), registry)?;
+		register(self.lane_alerts.clone(), registry)?;
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::message_lane_loop::{tests::TestMessageLane, MessageDetails};
+	use bp_messages::Weight;
+
+	#[test]
+	fn delivered_messages_are_removed_from_undelivered_set() {
+		let metrics =
+			MessageLaneLoopMetrics::new::<TestMessageLane>(None, &LaneId::new(1, 2)).unwrap();
+		metrics.note_generated_messages::<TestMessageLane>(
+			&(1..=5)
+				.map(|nonce| {
+					(nonce, MessageDetails { dispatch_weight: Weight::zero(), size: 0, reward: 10 })
+				})
+				.collect(),
+		);
+
+		metrics.update_target_latest_received_nonce(3);
+		assert_eq!(
+			metrics.undelivered_messages.lock().keys().cloned().collect::<Vec<_>>(),
+			vec![4, 5]
+		);
+		assert_eq!(metrics.delivered_messages_reward.get(), 30);
+		assert_eq!(metrics.delivery_latency.get_sample_count(), 3);
+	}
+
+	#[test]
+	fn delivery_stall_alert_is_raised_and_cleared() {
+		let metrics =
+			MessageLaneLoopMetrics::new::<TestMessageLane>(None, &LaneId::new(1, 2)).unwrap();
+		let is_stalled =
+			|| metrics.lane_alerts.with_label_values(&[ALERT_DELIVERY_STALLED]).get() == 1;
+		let set_best_target_block = |number: u64| {
+			metrics
+				.update_delivery_stall_alert(|detector| detector.best_target_block_number = number)
+		};
+
+		// no undelivered messages => no alert
+		set_best_target_block(100);
+		metrics.update_target_latest_received_nonce(5);
+		metrics.update_source_latest_generated_nonce(5);
+		set_best_target_block(100 + DELIVERY_STALL_ALERT_BLOCKS);
+		assert!(!is_stalled());
+
+		// new messages are generated, but not delivered for a long time => alert
+		metrics.update_source_latest_generated_nonce(10);
+		set_best_target_block(100 + 2 * DELIVERY_STALL_ALERT_BLOCKS - 1);
+		assert!(!is_stalled());
+		set_best_target_block(100 + 2 * DELIVERY_STALL_ALERT_BLOCKS);
+		assert!(is_stalled());
+
+		// some messages are delivered => alert is cleared
+		metrics.update_target_latest_received_nonce(7);
+		assert!(!is_stalled());
+	}
+
+	#[test]
+	fn unrewarded_relayers_alerts_are_raised_and_cleared() {
+		let metrics =
+			MessageLaneLoopMetrics::new::<TestMessageLane>(None, &LaneId::new(1, 2)).unwrap();
+		let is_raised = |reason| metrics.lane_alerts.with_label_values(&[reason]).get() == 1;
+
+		let mut state = UnrewardedRelayersState {
+			unrewarded_relayer_entries: 4,
+			total_messages: 2,
+			..Default::default()
+		};
+		metrics.update_target_unrewarded_relayers_state(&state, 4, 4);
+		assert!(is_raised(ALERT_UNREWARDED_RELAYERS_FULL));
+		assert!(!is_raised(ALERT_UNCONFIRMED_MESSAGES_FULL));
+
+		state.unrewarded_relayer_entries = 1;
+		state.total_messages = 4;
+		metrics.update_target_unrewarded_relayers_state(&state, 4, 4);
+		assert!(!is_raised(ALERT_UNREWARDED_RELAYERS_FULL));
+		assert!(is_raised(ALERT_UNCONFIRMED_MESSAGES_FULL));
+	}
+}
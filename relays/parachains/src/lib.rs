@@ -16,7 +16,8 @@
 
 use std::fmt::Debug;
 
-use relay_substrate_client::{Chain, Parachain};
+use bp_polkadot_core::parachains::ParaId;
+use relay_substrate_client::{Chain, Parachain, ParachainBase};
 
 pub mod parachains_loop;
 pub mod parachains_loop_metrics;
@@ -29,4 +30,13 @@ pub trait ParachainsPipeline: 'static + Clone + Debug + Send + Sync {
 	type SourceParachain: Parachain;
 	/// Target chain (either relay or para) which wants to know about new parachain heads.
 	type TargetChain: Chain;
+
+	/// Returns identifiers of all parachains which heads are synchronized by this pipeline.
+	///
+	/// Updated heads of all these parachains are submitted in a single transaction, so they
+	/// all must be using the same header type as the `Self::SourceParachain`. By default, only
+	/// heads of the `Self::SourceParachain` are synchronized.
+	fn parachains() -> Vec<ParaId> {
+		vec![ParaId(Self::SourceParachain::PARACHAIN_ID)]
+	}
 }
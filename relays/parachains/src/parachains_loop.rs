@@ -28,9 +28,9 @@ use futures::{
 use relay_substrate_client::{Chain, HeaderIdOf, ParachainBase};
 use relay_utils::{
 	metrics::MetricsParams, relay_loop::Client as RelayClient, FailedClient,
-	TrackedTransactionStatus, TransactionTracker,
+	TrackedTransactionStatus, TransactionTracker, UniqueSaturatedInto,
 };
-use std::{future::Future, pin::Pin, task::Poll};
+use std::{collections::BTreeMap, future::Future, pin::Pin, task::Poll};
 
 /// Parachain header availability at a certain chain.
 #[derive(Clone, Copy, Debug)]
@@ -74,17 +74,22 @@ pub trait SourceClient<P: ParachainsPipeline>: RelayClient {
 	/// Returns `Ok(true)` if client is in synced state.
 	async fn ensure_synced(&self) -> Result<bool, Self::Error>;
 
-	/// Get parachain head id at given block.
+	/// Get head id of given parachain at given block.
 	async fn parachain_head(
 		&self,
 		at_block: HeaderIdOf<P::SourceRelayChain>,
+		para_id: ParaId,
 	) -> Result<AvailableHeader<HeaderIdOf<P::SourceParachain>>, Self::Error>;
 
-	/// Get parachain head proof at given block.
-	async fn prove_parachain_head(
+	/// Get proof of heads of given parachains at given block.
+	///
+	/// Returned hashes are hashes of proved heads, in the same order as `parachains`. The hash
+	/// is `None` if head of the parachain is missing at given block.
+	async fn prove_parachain_heads(
 		&self,
 		at_block: HeaderIdOf<P::SourceRelayChain>,
-	) -> Result<(ParaHeadsProof, ParaHash), Self::Error>;
+		parachains: &[ParaId],
+	) -> Result<(ParaHeadsProof, Vec<Option<ParaHash>>), Self::Error>;
 }
 
 /// Target client used in parachain heads synchronization loop.
@@ -102,17 +107,18 @@ pub trait TargetClient<P: ParachainsPipeline>: RelayClient {
 		at_block: &HeaderIdOf<P::TargetChain>,
 	) -> Result<HeaderIdOf<P::SourceRelayChain>, Self::Error>;
 
-	/// Get parachain head id at given block.
+	/// Get head id of given parachain at given block.
 	async fn parachain_head(
 		&self,
 		at_block: HeaderIdOf<P::TargetChain>,
+		para_id: ParaId,
 	) -> Result<Option<HeaderIdOf<P::SourceParachain>>, Self::Error>;
 
-	/// Submit parachain heads proof.
-	async fn submit_parachain_head_proof(
+	/// Submit proof of heads of given parachains.
+	async fn submit_parachain_heads_proof(
 		&self,
 		at_source_block: HeaderIdOf<P::SourceRelayChain>,
-		para_head_hash: ParaHash,
+		updated_heads: Vec<(ParaId, ParaHash)>,
 		proof: ParaHeadsProof,
 	) -> Result<Self::TransactionTracker, Self::Error>;
 }
@@ -166,6 +172,7 @@ where
 		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
 	);
 
+	let parachains = P::parachains();
 	let mut submitted_heads_tracker: Option<SubmittedHeadsTracker<P>> = None;
 
 	futures::pin_mut!(exit_signal);
@@ -180,6 +187,20 @@ where
 		// it doesn't make sense to perform one more loop iteration.
 		select_biased! {
 			_ = exit_signal => return Ok(()),
+			_ = relay_utils::shutdown::shutdown_requested().fuse() => {
+				if let Some(tracker) = submitted_heads_tracker.take() {
+					relay_utils::shutdown::drain_in_flight_transaction(
+						&format!(
+							"{} -> {} parachains relay",
+							P::SourceRelayChain::NAME,
+							P::TargetChain::NAME,
+						),
+						tracker.transaction_tracker,
+					)
+					.await;
+				}
+				return Ok(())
+			},
 			_ = async_std::task::sleep(min_block_interval).fuse() => {},
 		}
 
@@ -211,12 +232,16 @@ where
 			log::warn!(target: "bridge", "Failed to read best {} block: {:?}", P::SourceRelayChain::NAME, e);
 			FailedClient::Target
 		})?;
-		let head_at_target =
-			read_head_at_target(&target_client, metrics.as_ref(), &best_target_block).await?;
+		let heads_at_target =
+			read_heads_at_target(&target_client, metrics.as_ref(), &best_target_block, &parachains)
+				.await?;
+		for (para_id, head_at_target) in &heads_at_target {
+			note_parachain_state::<P>(*para_id, head_at_target, false);
+		}
 
 		// check if our transaction has been mined
 		if let Some(tracker) = submitted_heads_tracker.take() {
-			match tracker.update(&best_target_block, &head_at_target).await {
+			match tracker.update(&best_target_block, &heads_at_target).await {
 				SubmittedHeadStatus::Waiting(tracker) => {
 					// no news about our transaction and we shall keep waiting
 					submitted_heads_tracker = Some(tracker);
@@ -238,6 +263,11 @@ where
 			}
 		}
 
+		// paused loop doesn't submit anything
+		if relay_utils::control::is_paused() {
+			continue
+		}
+
 		// we have no active transaction and may need to update heads, but do we have something for
 		// update?
 		let best_finalized_relay_block = target_client
@@ -253,55 +283,121 @@ where
 				);
 				FailedClient::Target
 			})?;
-		let head_at_source =
-			read_head_at_source(&source_client, metrics.as_ref(), &best_finalized_relay_block)
-				.await?;
-		let is_update_required = is_update_required::<P>(head_at_source, head_at_target);
+		let heads_at_source = read_heads_at_source(
+			&source_client,
+			metrics.as_ref(),
+			&best_finalized_relay_block,
+			&parachains,
+		)
+		.await?;
+		let updated_heads = parachains
+			.iter()
+			.filter(|para_id| {
+				is_update_required::<P>(
+					**para_id,
+					heads_at_source[*para_id],
+					heads_at_target[*para_id],
+				)
+			})
+			.map(|para_id| (*para_id, heads_at_source[para_id]))
+			.collect::<Vec<_>>();
+		for (para_id, head_at_target) in &heads_at_target {
+			if updated_heads.iter().all(|(updated_para_id, _)| updated_para_id != para_id) {
+				note_parachain_state::<P>(*para_id, head_at_target, true);
+			}
+		}
 
-		if is_update_required {
-			let (head_proof, head_hash) = source_client
-				.prove_parachain_head(best_finalized_relay_block)
+		if !updated_heads.is_empty() {
+			let updated_parachains =
+				updated_heads.iter().map(|(para_id, _)| *para_id).collect::<Vec<_>>();
+			let (heads_proof, head_hashes) = source_client
+				.prove_parachain_heads(best_finalized_relay_block, &updated_parachains)
 				.await
 				.map_err(|e| {
 					log::warn!(
 						target: "bridge",
-						"Failed to prove {} parachain ParaId({}) heads: {:?}",
+						"Failed to prove {} parachains {:?} heads: {:?}",
 						P::SourceRelayChain::NAME,
-						P::SourceParachain::PARACHAIN_ID,
+						updated_parachains,
 						e,
 					);
 					FailedClient::Source
 				})?;
+
+			// heads that have disappeared since we have read them are not submitted
+			let (updated_heads, submitted_hashes): (Vec<_>, Vec<_>) = updated_heads
+				.into_iter()
+				.zip(head_hashes)
+				.filter_map(|((para_id, head), head_hash)| match head_hash {
+					Some(head_hash) => Some(((para_id, head), (para_id, head_hash))),
+					None => {
+						log::warn!(
+							target: "bridge",
+							"Head of {} parachain {:?} is missing at {:?}. Skipping it",
+							P::SourceRelayChain::NAME,
+							para_id,
+							best_finalized_relay_block,
+						);
+						None
+					},
+				})
+				.unzip();
+			if updated_heads.is_empty() {
+				continue
+			}
+			let updated_parachains =
+				updated_heads.iter().map(|(para_id, _)| *para_id).collect::<Vec<_>>();
+
 			log::info!(
 				target: "bridge",
-				"Submitting {} parachain ParaId({}) head update transaction to {}",
+				"Submitting {} parachains {:?} head update transaction to {}",
 				P::SourceRelayChain::NAME,
-				P::SourceParachain::PARACHAIN_ID,
+				updated_parachains,
 				P::TargetChain::NAME,
 			);
 
 			let transaction_tracker = target_client
-				.submit_parachain_head_proof(best_finalized_relay_block, head_hash, head_proof)
+				.submit_parachain_heads_proof(
+					best_finalized_relay_block,
+					submitted_hashes,
+					heads_proof,
+				)
 				.await
 				.map_err(|e| {
 					log::warn!(
 						target: "bridge",
-						"Failed to submit {} parachain ParaId({}) heads proof to {}: {:?}",
+						"Failed to submit {} parachains {:?} heads proof to {}: {:?}",
 						P::SourceRelayChain::NAME,
-						P::SourceParachain::PARACHAIN_ID,
+						updated_parachains,
 						P::TargetChain::NAME,
 						e,
 					);
 					FailedClient::Target
 				})?;
 			submitted_heads_tracker =
-				Some(SubmittedHeadsTracker::<P>::new(head_at_source, transaction_tracker));
+				Some(SubmittedHeadsTracker::<P>::new(updated_heads, transaction_tracker));
 		}
 	}
 }
 
+/// Note state of the given parachain heads relay in the health state of the loop.
+fn note_parachain_state<P: ParachainsPipeline>(
+	para_id: ParaId,
+	head_at_target: &Option<HeaderIdOf<P::SourceParachain>>,
+	is_up_to_date: bool,
+) {
+	let best_at_target =
+		head_at_target.map(|head| head.number().unique_saturated_into()).unwrap_or(0);
+	relay_utils::health::note_race_state(
+		&format!("parachain {para_id:?}"),
+		is_up_to_date.then_some(best_at_target),
+		best_at_target,
+	);
+}
+
 /// Returns `true` if we need to submit parachain-head-update transaction.
 fn is_update_required<P: ParachainsPipeline>(
+	para_id: ParaId,
 	head_at_source: AvailableHeader<HeaderIdOf<P::SourceParachain>>,
 	head_at_target: Option<HeaderIdOf<P::SourceParachain>>,
 ) -> bool
@@ -310,11 +406,11 @@ where
 {
 	log::trace!(
 		target: "bridge",
-		"Checking if {} parachain ParaId({}) needs update at {}:\n\t\
+		"Checking if {} parachain {:?} needs update at {}:\n\t\
 			At {}: {:?}\n\t\
 			At {}: {:?}",
 		P::SourceRelayChain::NAME,
-		P::SourceParachain::PARACHAIN_ID,
+		para_id,
 		P::TargetChain::NAME,
 		P::SourceRelayChain::NAME,
 		head_at_source,
@@ -359,9 +455,9 @@ where
 	if needs_update {
 		log::trace!(
 			target: "bridge",
-			"{} parachain ParaId({}) needs update at {}: {:?} vs {:?}",
+			"{} parachain {:?} needs update at {}: {:?} vs {:?}",
 			P::SourceRelayChain::NAME,
-			P::SourceParachain::PARACHAIN_ID,
+			para_id,
 			P::TargetChain::NAME,
 			head_at_source,
 			head_at_target,
@@ -371,67 +467,79 @@ where
 	needs_update
 }
 
-/// Reads parachain head from the source client.
-async fn read_head_at_source<P: ParachainsPipeline>(
+/// Reads heads of given parachains from the source client.
+async fn read_heads_at_source<P: ParachainsPipeline>(
 	source_client: &impl SourceClient<P>,
 	metrics: Option<&ParachainsLoopMetrics>,
 	at_relay_block: &HeaderIdOf<P::SourceRelayChain>,
-) -> Result<AvailableHeader<HeaderIdOf<P::SourceParachain>>, FailedClient> {
-	let para_head = source_client.parachain_head(*at_relay_block).await;
-	match para_head {
-		Ok(AvailableHeader::Available(para_head)) => {
-			if let Some(metrics) = metrics {
-				metrics.update_best_parachain_block_at_source(
-					ParaId(P::SourceParachain::PARACHAIN_ID),
-					para_head.number(),
+	parachains: &[ParaId],
+) -> Result<BTreeMap<ParaId, AvailableHeader<HeaderIdOf<P::SourceParachain>>>, FailedClient> {
+	let mut para_head_ids = BTreeMap::new();
+	for para_id in parachains {
+		let para_head = source_client.parachain_head(*at_relay_block, *para_id).await;
+		let para_head = match para_head {
+			Ok(AvailableHeader::Available(para_head)) => {
+				if let Some(metrics) = metrics {
+					metrics.update_best_parachain_block_at_source(
+						*para_id,
+						at_relay_block.number(),
+						para_head.number(),
+					);
+				}
+				AvailableHeader::Available(para_head)
+			},
+			Ok(r) => r,
+			Err(e) => {
+				log::warn!(
+					target: "bridge",
+					"Failed to read head of {} parachain {:?}: {:?}",
+					P::SourceRelayChain::NAME,
+					para_id,
+					e,
 				);
-			}
-			Ok(AvailableHeader::Available(para_head))
-		},
-		Ok(r) => Ok(r),
-		Err(e) => {
-			log::warn!(
-				target: "bridge",
-				"Failed to read head of {} parachain ParaId({:?}): {:?}",
-				P::SourceRelayChain::NAME,
-				P::SourceParachain::PARACHAIN_ID,
-				e,
-			);
-			Err(FailedClient::Source)
-		},
+				return Err(FailedClient::Source)
+			},
+		};
+		para_head_ids.insert(*para_id, para_head);
 	}
+
+	Ok(para_head_ids)
 }
 
-/// Reads parachain head from the target client.
-async fn read_head_at_target<P: ParachainsPipeline>(
+/// Reads heads of given parachains from the target client.
+async fn read_heads_at_target<P: ParachainsPipeline>(
 	target_client: &impl TargetClient<P>,
 	metrics: Option<&ParachainsLoopMetrics>,
 	at_block: &HeaderIdOf<P::TargetChain>,
-) -> Result<Option<HeaderIdOf<P::SourceParachain>>, FailedClient> {
-	let para_head_id = target_client.parachain_head(*at_block).await;
-	match para_head_id {
-		Ok(Some(para_head_id)) => {
-			if let Some(metrics) = metrics {
-				metrics.update_best_parachain_block_at_target(
-					ParaId(P::SourceParachain::PARACHAIN_ID),
-					para_head_id.number(),
+	parachains: &[ParaId],
+) -> Result<BTreeMap<ParaId, Option<HeaderIdOf<P::SourceParachain>>>, FailedClient> {
+	let mut para_head_ids = BTreeMap::new();
+	for para_id in parachains {
+		let para_head_id = target_client.parachain_head(*at_block, *para_id).await;
+		let para_head_id = match para_head_id {
+			Ok(Some(para_head_id)) => {
+				if let Some(metrics) = metrics {
+					metrics.update_best_parachain_block_at_target(*para_id, para_head_id.number());
+				}
+				Some(para_head_id)
+			},
+			Ok(None) => None,
+			Err(e) => {
+				log::warn!(
+					target: "bridge",
+					"Failed to read head of {} parachain {:?} at {}: {:?}",
+					P::SourceRelayChain::NAME,
+					para_id,
+					P::TargetChain::NAME,
+					e,
 				);
-			}
-			Ok(Some(para_head_id))
-		},
-		Ok(None) => Ok(None),
-		Err(e) => {
-			log::warn!(
-				target: "bridge",
-				"Failed to read head of {} parachain ParaId({}) at {}: {:?}",
-				P::SourceRelayChain::NAME,
-				P::SourceParachain::PARACHAIN_ID,
-				P::TargetChain::NAME,
-				e,
-			);
-			Err(FailedClient::Target)
-		},
+				return Err(FailedClient::Target)
+			},
+		};
+		para_head_ids.insert(*para_id, para_head_id);
 	}
+
+	Ok(para_head_ids)
 }
 
 /// Submitted heads status.
@@ -459,8 +567,8 @@ type SharedTransactionTracker<P> = Shared<
 
 /// Submitted parachain heads transaction.
 struct SubmittedHeadsTracker<P: ParachainsPipeline> {
-	/// Parachain header id that we have submitted.
-	submitted_head: AvailableHeader<HeaderIdOf<P::SourceParachain>>,
+	/// Parachain header ids that we have submitted.
+	submitted_heads: Vec<(ParaId, AvailableHeader<HeaderIdOf<P::SourceParachain>>)>,
 	/// Future that waits for submitted transaction finality or loss.
 	///
 	/// It needs to be shared because of `poll` macro and our consuming `update` method.
@@ -470,11 +578,11 @@ struct SubmittedHeadsTracker<P: ParachainsPipeline> {
 impl<P: ParachainsPipeline> SubmittedHeadsTracker<P> {
 	/// Creates new parachain heads transaction tracker.
 	pub fn new(
-		submitted_head: AvailableHeader<HeaderIdOf<P::SourceParachain>>,
+		submitted_heads: Vec<(ParaId, AvailableHeader<HeaderIdOf<P::SourceParachain>>)>,
 		transaction_tracker: impl TransactionTracker<HeaderId = HeaderIdOf<P::TargetChain>> + 'static,
 	) -> Self {
 		SubmittedHeadsTracker {
-			submitted_head,
+			submitted_heads,
 			transaction_tracker: transaction_tracker.wait().fuse().boxed().shared(),
 		}
 	}
@@ -483,23 +591,26 @@ impl<P: ParachainsPipeline> SubmittedHeadsTracker<P> {
 	pub async fn update(
 		self,
 		at_target_block: &HeaderIdOf<P::TargetChain>,
-		head_at_target: &Option<HeaderIdOf<P::SourceParachain>>,
+		heads_at_target: &BTreeMap<ParaId, Option<HeaderIdOf<P::SourceParachain>>>,
 	) -> SubmittedHeadStatus<P> {
-		// check if our head has been updated
-		let is_head_updated = match (self.submitted_head, head_at_target) {
-			(AvailableHeader::Available(submitted_head), Some(head_at_target))
-				if head_at_target.number() >= submitted_head.number() =>
-				true,
-			(AvailableHeader::Missing, None) => true,
-			_ => false,
-		};
-		if is_head_updated {
+		// check if all our heads have been updated
+		let are_heads_updated = self.submitted_heads.iter().all(|(para_id, submitted_head)| {
+			let head_at_target = heads_at_target.get(para_id).cloned().flatten();
+			match (submitted_head, head_at_target) {
+				(AvailableHeader::Available(submitted_head), Some(head_at_target))
+					if head_at_target.number() >= submitted_head.number() =>
+					true,
+				(AvailableHeader::Missing, None) => true,
+				_ => false,
+			}
+		});
+		if are_heads_updated {
 			log::trace!(
 				target: "bridge",
-				"Head of parachain ParaId({}) has been updated at {}: {:?}",
-				P::SourceParachain::PARACHAIN_ID,
+				"Heads of parachains {:?} have been updated at {}: {:?}",
+				self.submitted_heads.iter().map(|(para_id, _)| *para_id).collect::<Vec<_>>(),
 				P::TargetChain::NAME,
-				head_at_target,
+				heads_at_target,
 			);
 
 			return SubmittedHeadStatus::Final(TrackedTransactionStatus::Finalized(*at_target_block))
@@ -553,6 +664,10 @@ mod tests {
 		type SourceRelayChain = TestChain;
 		type SourceParachain = TestParachain;
 		type TargetChain = TestChain;
+
+		fn parachains() -> Vec<ParaId> {
+			vec![ParaId(1000), ParaId(2000)]
+		}
 	}
 
 	#[derive(Clone, Debug)]
@@ -580,11 +695,13 @@ mod tests {
 		source_sync_status: Result<bool, TestError>,
 		source_head: Result<AvailableHeader<HeaderIdOf<TestParachain>>, TestError>,
 		source_proof: Result<(), TestError>,
+		source_missing_heads: Vec<ParaId>,
 
 		target_best_block: Result<HeaderIdOf<TestChain>, TestError>,
 		target_best_finalized_source_block: Result<HeaderIdOf<TestChain>, TestError>,
 		target_head: Result<Option<HeaderIdOf<TestParachain>>, TestError>,
 		target_submit_result: Result<(), TestError>,
+		target_submitted_heads: Vec<Vec<(ParaId, ParaHash)>>,
 
 		exit_signal_sender: Option<Box<futures::channel::mpsc::UnboundedSender<()>>>,
 	}
@@ -595,11 +712,13 @@ mod tests {
 				source_sync_status: Ok(true),
 				source_head: Ok(AvailableHeader::Available(HeaderId(0, PARA_20_HASH))),
 				source_proof: Ok(()),
+				source_missing_heads: Vec::new(),
 
 				target_best_block: Ok(HeaderId(0, Default::default())),
 				target_best_finalized_source_block: Ok(HeaderId(0, Default::default())),
 				target_head: Ok(None),
 				target_submit_result: Ok(()),
+				target_submitted_heads: Vec::new(),
 
 				exit_signal_sender: None,
 			}
@@ -638,17 +757,28 @@ mod tests {
 		async fn parachain_head(
 			&self,
 			_at_block: HeaderIdOf<TestChain>,
+			_para_id: ParaId,
 		) -> Result<AvailableHeader<HeaderIdOf<TestParachain>>, TestError> {
 			self.data.lock().await.source_head.clone()
 		}
 
-		async fn prove_parachain_head(
+		async fn prove_parachain_heads(
 			&self,
 			_at_block: HeaderIdOf<TestChain>,
-		) -> Result<(ParaHeadsProof, ParaHash), TestError> {
-			let head = *self.data.lock().await.source_head.clone()?.as_available().unwrap();
-			let proof = (ParaHeadsProof { storage_proof: Default::default() }, head.hash());
-			self.data.lock().await.source_proof.clone().map(|_| proof)
+			parachains: &[ParaId],
+		) -> Result<(ParaHeadsProof, Vec<Option<ParaHash>>), TestError> {
+			let data = self.data.lock().await;
+			let head = *data.source_head.clone()?.as_available().unwrap();
+			let proof = (
+				ParaHeadsProof { storage_proof: Default::default() },
+				parachains
+					.iter()
+					.map(|para_id| {
+						Some(head.hash()).filter(|_| !data.source_missing_heads.contains(para_id))
+					})
+					.collect(),
+			);
+			data.source_proof.clone().map(|_| proof)
 		}
 	}
 
@@ -670,18 +800,20 @@ mod tests {
 		async fn parachain_head(
 			&self,
 			_at_block: HeaderIdOf<TestChain>,
+			_para_id: ParaId,
 		) -> Result<Option<HeaderIdOf<TestParachain>>, TestError> {
 			self.data.lock().await.target_head.clone()
 		}
 
-		async fn submit_parachain_head_proof(
+		async fn submit_parachain_heads_proof(
 			&self,
 			_at_source_block: HeaderIdOf<TestChain>,
-			_updated_parachain_head: ParaHash,
+			updated_heads: Vec<(ParaId, ParaHash)>,
 			_proof: ParaHeadsProof,
 		) -> Result<TestTransactionTracker, Self::Error> {
 			let mut data = self.data.lock().await;
 			data.target_submit_result.clone()?;
+			data.target_submitted_heads.push(updated_heads);
 
 			if let Some(mut exit_signal_sender) = data.exit_signal_sender.take() {
 				exit_signal_sender.send(()).await.unwrap();
@@ -818,13 +950,61 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn heads_of_all_parachains_are_submitted_in_single_transaction() {
+		let (exit_signal_sender, exit_signal) = futures::channel::mpsc::unbounded();
+		let target_client =
+			TestClient::from(TestClientData::with_exit_signal_sender(exit_signal_sender));
+		assert_eq!(
+			async_std::task::block_on(run_until_connection_lost(
+				TestClient::from(TestClientData::minimal()),
+				target_client.clone(),
+				None,
+				exit_signal.into_future().map(|(_, _)| ()),
+			)),
+			Ok(()),
+		);
+		assert_eq!(
+			async_std::task::block_on(target_client.data.lock()).target_submitted_heads,
+			vec![vec![(ParaId(1000), PARA_20_HASH), (ParaId(2000), PARA_20_HASH)]],
+		);
+	}
+
+	#[test]
+	fn missing_parachain_head_is_not_submitted_with_other_heads() {
+		let mut test_source_client = TestClientData::minimal();
+		test_source_client.source_missing_heads = vec![ParaId(1000)];
+		let (exit_signal_sender, exit_signal) = futures::channel::mpsc::unbounded();
+		let target_client =
+			TestClient::from(TestClientData::with_exit_signal_sender(exit_signal_sender));
+		assert_eq!(
+			async_std::task::block_on(run_until_connection_lost(
+				TestClient::from(test_source_client),
+				target_client.clone(),
+				None,
+				exit_signal.into_future().map(|(_, _)| ()),
+			)),
+			Ok(()),
+		);
+		assert_eq!(
+			async_std::task::block_on(target_client.data.lock()).target_submitted_heads,
+			vec![vec![(ParaId(2000), PARA_20_HASH)]],
+		);
+	}
+
 	fn test_tx_tracker() -> SubmittedHeadsTracker<TestParachainsPipeline> {
 		SubmittedHeadsTracker::new(
-			AvailableHeader::Available(HeaderId(20, PARA_20_HASH)),
+			vec![(ParaId(1000), AvailableHeader::Available(HeaderId(20, PARA_20_HASH)))],
 			TestTransactionTracker(None),
 		)
 	}
 
+	fn heads_at_target(
+		head_at_target: Option<HeaderIdOf<TestParachain>>,
+	) -> BTreeMap<ParaId, Option<HeaderIdOf<TestParachain>>> {
+		vec![(ParaId(1000), head_at_target)].into_iter().collect()
+	}
+
 	impl From<SubmittedHeadStatus<TestParachainsPipeline>> for Option<()> {
 		fn from(status: SubmittedHeadStatus<TestParachainsPipeline>) -> Option<()> {
 			match status {
@@ -839,7 +1019,10 @@ mod tests {
 		assert_eq!(
 			Some(()),
 			test_tx_tracker()
-				.update(&HeaderId(0, Default::default()), &Some(HeaderId(10, PARA_10_HASH)))
+				.update(
+					&HeaderId(0, Default::default()),
+					&heads_at_target(Some(HeaderId(10, PARA_10_HASH)))
+				)
 				.await
 				.into(),
 		);
@@ -850,7 +1033,10 @@ mod tests {
 		assert_eq!(
 			Some(()),
 			test_tx_tracker()
-				.update(&HeaderId(0, Default::default()), &Some(HeaderId(10, PARA_10_HASH)))
+				.update(
+					&HeaderId(0, Default::default()),
+					&heads_at_target(Some(HeaderId(10, PARA_10_HASH)))
+				)
 				.await
 				.into(),
 		);
@@ -860,7 +1046,10 @@ mod tests {
 	async fn tx_tracker_update_when_head_at_target_has_same_value() {
 		assert!(matches!(
 			test_tx_tracker()
-				.update(&HeaderId(0, Default::default()), &Some(HeaderId(20, PARA_20_HASH)))
+				.update(
+					&HeaderId(0, Default::default()),
+					&heads_at_target(Some(HeaderId(20, PARA_20_HASH)))
+				)
 				.await,
 			SubmittedHeadStatus::Final(TrackedTransactionStatus::Finalized(_)),
 		));
@@ -870,7 +1059,10 @@ mod tests {
 	async fn tx_tracker_update_when_head_at_target_has_better_value() {
 		assert!(matches!(
 			test_tx_tracker()
-				.update(&HeaderId(0, Default::default()), &Some(HeaderId(30, PARA_20_HASH)))
+				.update(
+					&HeaderId(0, Default::default()),
+					&heads_at_target(Some(HeaderId(30, PARA_20_HASH)))
+				)
 				.await,
 			SubmittedHeadStatus::Final(TrackedTransactionStatus::Finalized(_)),
 		));
@@ -883,7 +1075,10 @@ mod tests {
 			futures::future::ready(TrackedTransactionStatus::Lost).boxed().shared();
 		assert!(matches!(
 			tx_tracker
-				.update(&HeaderId(0, Default::default()), &Some(HeaderId(10, PARA_10_HASH)))
+				.update(
+					&HeaderId(0, Default::default()),
+					&heads_at_target(Some(HeaderId(10, PARA_10_HASH)))
+				)
 				.await,
 			SubmittedHeadStatus::Final(TrackedTransactionStatus::Lost),
 		));
@@ -898,7 +1093,10 @@ mod tests {
 				.shared();
 		assert!(matches!(
 			tx_tracker
-				.update(&HeaderId(0, Default::default()), &Some(HeaderId(10, PARA_10_HASH)))
+				.update(
+					&HeaderId(0, Default::default()),
+					&heads_at_target(Some(HeaderId(10, PARA_10_HASH)))
+				)
 				.await,
 			SubmittedHeadStatus::Final(TrackedTransactionStatus::Lost),
 		));
@@ -906,8 +1104,13 @@ mod tests {
 
 	#[test]
 	fn parachain_is_not_updated_if_it_is_unavailable() {
-		assert!(!is_update_required::<TestParachainsPipeline>(AvailableHeader::Unavailable, None));
 		assert!(!is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
+			AvailableHeader::Unavailable,
+			None
+		));
+		assert!(!is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
 			AvailableHeader::Unavailable,
 			Some(HeaderId(10, PARA_10_HASH))
 		));
@@ -915,12 +1118,17 @@ mod tests {
 
 	#[test]
 	fn parachain_is_not_updated_if_it_is_unknown_to_both_clients() {
-		assert!(!is_update_required::<TestParachainsPipeline>(AvailableHeader::Missing, None),);
+		assert!(!is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
+			AvailableHeader::Missing,
+			None
+		),);
 	}
 
 	#[test]
 	fn parachain_is_not_updated_if_target_has_better_head() {
 		assert!(!is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
 			AvailableHeader::Available(HeaderId(10, Default::default())),
 			Some(HeaderId(20, Default::default())),
 		),);
@@ -929,6 +1137,7 @@ mod tests {
 	#[test]
 	fn parachain_is_updated_after_offboarding() {
 		assert!(is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
 			AvailableHeader::Missing,
 			Some(HeaderId(20, Default::default())),
 		),);
@@ -937,6 +1146,7 @@ mod tests {
 	#[test]
 	fn parachain_is_updated_after_onboarding() {
 		assert!(is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
 			AvailableHeader::Available(HeaderId(30, Default::default())),
 			None,
 		),);
@@ -945,6 +1155,7 @@ mod tests {
 	#[test]
 	fn parachain_is_updated_if_newer_head_is_known() {
 		assert!(is_update_required::<TestParachainsPipeline>(
+			ParaId(1000),
 			AvailableHeader::Available(HeaderId(40, Default::default())),
 			Some(HeaderId(30, Default::default())),
 		),);
@@ -15,39 +15,149 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use bp_polkadot_core::parachains::ParaId;
+use parking_lot::Mutex;
 use relay_utils::{
-	metrics::{metric_name, register, Gauge, Metric, PrometheusError, Registry, U64},
+	metrics::{metric_name, register, GaugeVec, Metric, Opts, PrometheusError, Registry, F64, U64},
 	UniqueSaturatedInto,
 };
+use std::{
+	collections::BTreeMap,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Number of source relay chain blocks, during which the head of the parachain at the source
+/// may stay the same before the parachain is considered stalled.
+const PARACHAIN_STALL_ALERT_RELAY_BLOCKS: u64 = 50;
+
+/// Alert reason: the head of the parachain at the source relay chain hasn't changed for at least
+/// `PARACHAIN_STALL_ALERT_RELAY_BLOCKS` relay chain blocks.
+const ALERT_SOURCE_PARACHAIN_STALLED: &str = "source_parachain_stalled";
 
 /// Parachains sync metrics.
+///
+/// Cloning only clones references.
 #[derive(Clone)]
 pub struct ParachainsLoopMetrics {
 	/// Best parachains header numbers at the source.
-	best_source_block_numbers: Gauge<U64>,
+	best_source_block_numbers: GaugeVec<U64>,
 	/// Best parachains header numbers at the target.
-	best_target_block_numbers: Gauge<U64>,
+	best_target_block_numbers: GaugeVec<U64>,
+	/// Age of the best parachain headers at the target, in source relay chain blocks.
+	best_target_block_ages_in_relay_blocks: GaugeVec<U64>,
+	/// Age of the best parachain headers at the target, in seconds.
+	best_target_block_ages_in_seconds: GaugeVec<F64>,
+	/// Parachain alerts, labeled with the parachain and the alert reason. Alert is raised if the
+	/// value is `1`.
+	parachain_alerts: GaugeVec<U64>,
+	/// Freshness of parachain heads, tracked by the relay.
+	heads_freshness: Arc<Mutex<BTreeMap<ParaId, HeadsFreshness>>>,
+}
+
+/// Freshness of the parachain heads at the source and at the target chains.
+#[derive(Debug, Default)]
+struct HeadsFreshness {
+	/// Best source relay chain block number, where the parachain head has been read.
+	best_relay_block_number: u64,
+	/// Numbers of parachain heads that have been seen at the source, mapped to the relay block
+	/// number and the moment when they have been seen first time. We only keep heads that are
+	/// not yet known to the target and the best head, known to the target.
+	source_heads: BTreeMap<u64, (u64, Instant)>,
+	/// Best parachain head number at the target.
+	target_head: Option<u64>,
+}
+
+impl HeadsFreshness {
+	/// Returns age of the best head at the target, in relay chain blocks and in seconds.
+	fn target_head_age(&self, now: Instant) -> Option<(u64, Duration)> {
+		let target_head = self.target_head?;
+		let (_, (seen_at_relay_block_number, seen_at)) =
+			self.source_heads.range(..=target_head).next_back()?;
+		Some((
+			self.best_relay_block_number.saturating_sub(*seen_at_relay_block_number),
+			now.saturating_duration_since(*seen_at),
+		))
+	}
+
+	/// Returns true if the parachain head at the source hasn't changed for too long.
+	fn is_source_stalled(&self) -> bool {
+		self.source_heads
+			.iter()
+			.next_back()
+			.map(|(_, (seen_at_relay_block_number, _))| {
+				self.best_relay_block_number.saturating_sub(*seen_at_relay_block_number) >=
+					PARACHAIN_STALL_ALERT_RELAY_BLOCKS
+			})
+			.unwrap_or(false)
+	}
+
+	/// Forget about heads that are older than the best head at the target.
+	fn prune(&mut self) {
+		if let Some(target_head) = self.target_head {
+			let best_known_head =
+				self.source_heads.range(..=target_head).next_back().map(|(number, _)| *number);
+			if let Some(best_known_head) = best_known_head {
+				self.source_heads = self.source_heads.split_off(&best_known_head);
+			}
+		}
+	}
 }
 
 impl ParachainsLoopMetrics {
 	/// Create and register parachains loop metrics.
 	pub fn new(prefix: Option<&str>) -> Result<Self, PrometheusError> {
 		Ok(ParachainsLoopMetrics {
-			best_source_block_numbers: Gauge::new(
-				metric_name(prefix, "best_parachain_block_number_at_source"),
-				"Best parachain block numbers at the source relay chain".to_string(),
+			best_source_block_numbers: GaugeVec::new(
+				Opts::new(
+					metric_name(prefix, "best_parachain_block_number_at_source"),
+					"Best parachain block numbers at the source relay chain".to_string(),
+				),
+				&["parachain"],
+			)?,
+			best_target_block_numbers: GaugeVec::new(
+				Opts::new(
+					metric_name(prefix, "best_parachain_block_number_at_target"),
+					"Best parachain block numbers at the target chain".to_string(),
+				),
+				&["parachain"],
+			)?,
+			best_target_block_ages_in_relay_blocks: GaugeVec::new(
+				Opts::new(
+					metric_name(prefix, "best_parachain_block_age_at_target_in_relay_blocks"),
+					"Number of source relay chain blocks since the best parachain block at the \
+					target chain has been seen at the source"
+						.to_string(),
+				),
+				&["parachain"],
 			)?,
-			best_target_block_numbers: Gauge::new(
-				metric_name(prefix, "best_parachain_block_number_at_target"),
-				"Best parachain block numbers at the target chain".to_string(),
+			best_target_block_ages_in_seconds: GaugeVec::new(
+				Opts::new(
+					metric_name(prefix, "best_parachain_block_age_at_target_in_seconds"),
+					"Number of seconds since the best parachain block at the target chain has \
+					been seen at the source"
+						.to_string(),
+				),
+				&["parachain"],
 			)?,
+			parachain_alerts: GaugeVec::new(
+				Opts::new(
+					metric_name(prefix, "parachain_alerts"),
+					"Parachain alerts. Alert is raised if the value is 1".to_string(),
+				),
+				&["parachain", "reason"],
+			)?,
+			heads_freshness: Arc::new(Mutex::new(BTreeMap::new())),
 		})
 	}
 
 	/// Update best block number at source.
-	pub fn update_best_parachain_block_at_source<Number: UniqueSaturatedInto<u64>>(
+	pub fn update_best_parachain_block_at_source<
+		RelayNumber: UniqueSaturatedInto<u64>,
+		Number: UniqueSaturatedInto<u64>,
+	>(
 		&self,
 		parachain: ParaId,
+		at_relay_block_number: RelayNumber,
 		block_number: Number,
 	) {
 		let block_number = block_number.unique_saturated_into();
@@ -57,7 +167,20 @@ impl ParachainsLoopMetrics {
 			parachain,
 			block_number,
 		);
-		self.best_source_block_numbers.set(block_number);
+		self.best_source_block_numbers
+			.with_label_values(&[&parachain.0.to_string()])
+			.set(block_number);
+
+		let at_relay_block_number = at_relay_block_number.unique_saturated_into();
+		let now = Instant::now();
+		self.update_heads_freshness(parachain, now, |freshness| {
+			freshness.best_relay_block_number =
+				std::cmp::max(freshness.best_relay_block_number, at_relay_block_number);
+			freshness
+				.source_heads
+				.entry(block_number)
+				.or_insert((at_relay_block_number, now));
+		});
 	}
 
 	/// Update best block number at target.
@@ -73,7 +196,58 @@ impl ParachainsLoopMetrics {
 			parachain,
 			block_number,
 		);
-		self.best_target_block_numbers.set(block_number);
+		self.best_target_block_numbers
+			.with_label_values(&[&parachain.0.to_string()])
+			.set(block_number);
+
+		let now = Instant::now();
+		self.update_heads_freshness(parachain, now, |freshness| {
+			// if relay has been started after the head has been seen at the source, let's assume
+			// it has been seen right now
+			if freshness.source_heads.range(..=block_number).next_back().is_none() {
+				freshness
+					.source_heads
+					.insert(block_number, (freshness.best_relay_block_number, now));
+			}
+			freshness.target_head = Some(block_number);
+		});
+	}
+
+	/// Update heads freshness and the corresponding metrics.
+	fn update_heads_freshness(
+		&self,
+		parachain: ParaId,
+		now: Instant,
+		update: impl FnOnce(&mut HeadsFreshness),
+	) {
+		let mut heads_freshness = self.heads_freshness.lock();
+		let freshness = heads_freshness.entry(parachain).or_default();
+		update(freshness);
+		freshness.prune();
+
+		let parachain_label = parachain.0.to_string();
+		if let Some((age_in_relay_blocks, age)) = freshness.target_head_age(now) {
+			self.best_target_block_ages_in_relay_blocks
+				.with_label_values(&[&parachain_label])
+				.set(age_in_relay_blocks);
+			self.best_target_block_ages_in_seconds
+				.with_label_values(&[&parachain_label])
+				.set(age.as_secs_f64());
+		}
+
+		let is_stalled = freshness.is_source_stalled();
+		let alert = self
+			.parachain_alerts
+			.with_label_values(&[&parachain_label, ALERT_SOURCE_PARACHAIN_STALLED]);
+		if is_stalled && alert.get() == 0 {
+			log::warn!(
+				target: "bridge",
+				"Parachain {:?} alert is raised: {}",
+				parachain,
+				ALERT_SOURCE_PARACHAIN_STALLED,
+			);
+		}
+		alert.set(is_stalled.into());
 	}
 }
 
@@ -81,6 +255,75 @@ impl Metric for ParachainsLoopMetrics {
 	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
 		register(self.best_source_block_numbers.clone(), registry)?;
 		register(self.best_target_block_numbers.clone(), registry)?;
+		register(self.best_target_block_ages_in_relay_blocks.clone(), registry)?;
+		register(self.best_target_block_ages_in_seconds.clone(), registry)?;
+		register(self.parachain_alerts.clone(), registry)?;
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn target_head_age_is_computed() {
+		let metrics = ParachainsLoopMetrics::new(None).unwrap();
+		let parachain = ParaId(1000);
+		let age_in_relay_blocks = || {
+			metrics
+				.best_target_block_ages_in_relay_blocks
+				.with_label_values(&[&parachain.0.to_string()])
+				.get()
+		};
+
+		// head 10 is seen at relay block 100 and is delivered to the target
+		metrics.update_best_parachain_block_at_source(parachain, 100u64, 10u64);
+		metrics.update_best_parachain_block_at_target(parachain, 10u64);
+		assert_eq!(age_in_relay_blocks(), 0);
+
+		// head 12 is seen at relay block 104, but it is not yet at the target
+		metrics.update_best_parachain_block_at_source(parachain, 104u64, 12u64);
+		metrics.update_best_parachain_block_at_target(parachain, 10u64);
+		assert_eq!(age_in_relay_blocks(), 4);
+
+		// head 12 is delivered to the target
+		metrics.update_best_parachain_block_at_source(parachain, 106u64, 12u64);
+		metrics.update_best_parachain_block_at_target(parachain, 12u64);
+		assert_eq!(age_in_relay_blocks(), 2);
+	}
+
+	#[test]
+	fn source_parachain_stall_alert_is_raised_and_cleared() {
+		let metrics = ParachainsLoopMetrics::new(None).unwrap();
+		let parachain = ParaId(1000);
+		let is_stalled = || {
+			metrics
+				.parachain_alerts
+				.with_label_values(&[&parachain.0.to_string(), ALERT_SOURCE_PARACHAIN_STALLED])
+				.get() == 1
+		};
+
+		metrics.update_best_parachain_block_at_source(parachain, 100u64, 10u64);
+		metrics.update_best_parachain_block_at_source(
+			parachain,
+			100 + PARACHAIN_STALL_ALERT_RELAY_BLOCKS - 1,
+			10u64,
+		);
+		assert!(!is_stalled());
+
+		metrics.update_best_parachain_block_at_source(
+			parachain,
+			100 + PARACHAIN_STALL_ALERT_RELAY_BLOCKS,
+			10u64,
+		);
+		assert!(is_stalled());
+
+		metrics.update_best_parachain_block_at_source(
+			parachain,
+			101 + PARACHAIN_STALL_ALERT_RELAY_BLOCKS,
+			11u64,
+		);
+		assert!(!is_stalled());
+	}
+}
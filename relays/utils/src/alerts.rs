@@ -0,0 +1,367 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Alerts on relay failures.
+//!
+//! When some failure condition is detected, the alert is sent to all configured sinks (generic
+//! JSON webhooks, Slack incoming webhooks and PagerDuty). The alert is repeated at most once
+//! per configured interval while the condition holds, and the `resolved` notification is sent
+//! when the condition is gone. Supported conditions are:
+//!
+//! - relay loop has not made any progress during the configured period;
+//!
+//! - relay loop has failed to submit several transactions in a row;
+//!
+//! - relayer account balance is below the configured minimum;
+//!
+//! - bridge pallet at the target chain is halted;
+//!
+//! - supervised relay loop keeps failing right after restart.
+//!
+//! Alerts are not sent until [`init_alerts`] is called.
+
+use crate::initialize::loop_name;
+
+use std::{
+	collections::BTreeMap,
+	sync::{Mutex, OnceLock},
+	time::{Duration, Instant},
+};
+
+/// PagerDuty Events API v2 endpoint.
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+/// Interval between checks of relay loops progress.
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Destination of alerts.
+#[derive(Clone, PartialEq, Eq)]
+pub enum AlertSink {
+	/// Generic webhook that receives alerts as JSON objects.
+	Webhook(String),
+	/// Slack incoming webhook.
+	Slack(String),
+	/// PagerDuty service, identified by the Events API v2 routing key.
+	PagerDuty(String),
+}
+
+impl std::fmt::Debug for AlertSink {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		// webhook URLs and routing keys are secrets
+		match self {
+			AlertSink::Webhook(_) => fmt.write_str("Webhook(***)"),
+			AlertSink::Slack(_) => fmt.write_str("Slack(***)"),
+			AlertSink::PagerDuty(_) => fmt.write_str("PagerDuty(***)"),
+		}
+	}
+}
+
+/// Alerts parameters.
+#[derive(Debug, Clone)]
+pub struct AlertsParams {
+	/// Where to send alerts.
+	pub sinks: Vec<AlertSink>,
+	/// Relay loop, that has not made any progress during this period, is considered stalled.
+	pub max_stall: Duration,
+	/// Alert is fired when relay loop fails to submit this number of transactions in a row.
+	pub max_failed_submissions: u32,
+	/// Minimal interval between repeated alerts of the same kind and subject.
+	pub repeat_interval: Duration,
+}
+
+/// Kind of the alert.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertKind {
+	/// Relay loop has not made any progress.
+	LoopStalled,
+	/// Relay loop has failed to submit several transactions in a row.
+	SubmissionFailures,
+	/// Relayer account balance is below the configured minimum.
+	LowBalance,
+	/// Bridge pallet is halted.
+	PalletHalted,
+	/// Relay loop keeps failing right after restart.
+	CrashLoop,
+}
+
+impl AlertKind {
+	/// Returns name of the alert kind.
+	pub fn name(&self) -> &'static str {
+		match self {
+			AlertKind::LoopStalled => "loop_stalled",
+			AlertKind::SubmissionFailures => "submission_failures",
+			AlertKind::LowBalance => "low_balance",
+			AlertKind::PalletHalted => "pallet_halted",
+			AlertKind::CrashLoop => "crash_loop",
+		}
+	}
+}
+
+/// Global alerts state.
+static ALERTS: OnceLock<Mutex<AlertsState>> = OnceLock::new();
+
+fn with_alerts<R>(f: impl FnOnce(&mut AlertsState) -> R) -> R {
+	// the lock is never held across panics, so poisoning is not expected
+	let mut state = ALERTS
+		.get_or_init(|| Mutex::new(AlertsState::default()))
+		.lock()
+		.unwrap_or_else(|e| e.into_inner());
+	f(&mut state)
+}
+
+/// Alerts state.
+#[derive(Debug, Default)]
+struct AlertsState {
+	/// Alerts parameters. `None` if alerts are disabled.
+	params: Option<AlertsParams>,
+	/// Firing alerts along with the moment when they have been sent last time.
+	firing: BTreeMap<(AlertKind, String), Instant>,
+	/// Number of consecutive failed submissions of every relay loop.
+	failed_submissions: BTreeMap<String, u32>,
+}
+
+impl AlertsState {
+	/// Note that the alert condition holds. Returns sinks if alert needs to be sent.
+	fn note_firing(
+		&mut self,
+		kind: AlertKind,
+		subject: &str,
+		now: Instant,
+	) -> Option<Vec<AlertSink>> {
+		let params = self.params.as_ref()?;
+		let key = (kind, subject.to_string());
+		match self.firing.get(&key) {
+			Some(sent_at) if now.saturating_duration_since(*sent_at) < params.repeat_interval =>
+				None,
+			_ => {
+				self.firing.insert(key, now);
+				Some(params.sinks.clone())
+			},
+		}
+	}
+
+	/// Note that the alert condition is gone. Returns sinks if the `resolved` notification
+	/// needs to be sent.
+	fn note_resolved(&mut self, kind: AlertKind, subject: &str) -> Option<Vec<AlertSink>> {
+		let params = self.params.as_ref()?;
+		self.firing.remove(&(kind, subject.to_string())).map(|_| params.sinks.clone())
+	}
+
+	/// Note result of transaction submission. Returns true if the submission failures alert
+	/// condition holds.
+	fn note_submission_result(&mut self, subject: &str, succeeded: bool) -> bool {
+		let max_failed_submissions = match self.params {
+			Some(ref params) => params.max_failed_submissions,
+			None => return false,
+		};
+		let failed_submissions = self.failed_submissions.entry(subject.into()).or_default();
+		*failed_submissions = if succeeded { 0 } else { failed_submissions.saturating_add(1) };
+		*failed_submissions >= max_failed_submissions
+	}
+}
+
+/// Start sending alerts to configured sinks.
+pub fn init_alerts(params: AlertsParams) {
+	let max_stall = params.max_stall;
+	with_alerts(|state| state.params = Some(params));
+
+	async_std::task::spawn(async move {
+		loop {
+			async_std::task::sleep(STALL_CHECK_INTERVAL).await;
+
+			for (loop_name, stalled_for) in crate::health::loops_stalled_for(Instant::now()) {
+				if stalled_for > max_stall && !crate::control::is_loop_paused(&loop_name) {
+					fire_alert(
+						AlertKind::LoopStalled,
+						loop_name,
+						format!("no progress during last {}s", stalled_for.as_secs()),
+					);
+				} else {
+					resolve_alert(AlertKind::LoopStalled, loop_name);
+				}
+			}
+		}
+	});
+}
+
+/// Fire the alert of given kind and subject (relay loop, chain or account). The alert is not
+/// repeated until the repeat interval passes.
+pub fn fire_alert(kind: AlertKind, subject: String, message: String) {
+	if let Some(sinks) = with_alerts(|state| state.note_firing(kind, &subject, Instant::now())) {
+		log::trace!(target: "bridge", "Firing {} alert for {}: {}", kind.name(), subject, message);
+		send(sinks, kind, subject, message, false);
+	}
+}
+
+/// Resolve the alert of given kind and subject, if it is firing.
+pub fn resolve_alert(kind: AlertKind, subject: String) {
+	if let Some(sinks) = with_alerts(|state| state.note_resolved(kind, &subject)) {
+		log::trace!(target: "bridge", "Resolving {} alert for {}", kind.name(), subject);
+		send(sinks, kind, subject, "resolved".into(), true);
+	}
+}
+
+/// Note result of transaction submission by the current relay loop. The alert is fired when
+/// the loop fails to submit several transactions in a row.
+pub fn note_submission_result(succeeded: bool) {
+	let subject = loop_name().unwrap_or_else(|| "relay".into());
+	let is_firing = with_alerts(|state| state.note_submission_result(&subject, succeeded));
+	if is_firing {
+		fire_alert(
+			AlertKind::SubmissionFailures,
+			subject,
+			"failed to submit several transactions in a row".into(),
+		);
+	} else if succeeded {
+		resolve_alert(AlertKind::SubmissionFailures, subject);
+	}
+}
+
+/// Send alert to all sinks.
+fn send(sinks: Vec<AlertSink>, kind: AlertKind, subject: String, message: String, resolved: bool) {
+	async_std::task::spawn(async move {
+		for sink in sinks {
+			let (url, body) = encode_alert(&sink, kind, &subject, &message, resolved);
+			if let Err(err) = post(&url, body).await {
+				log::error!(
+					target: "bridge",
+					"Failed to send {} alert for {} to {:?}: {:?}",
+					kind.name(),
+					subject,
+					sink,
+					err,
+				);
+			}
+		}
+	});
+}
+
+/// Returns URL and JSON body of the request that delivers alert to the sink.
+fn encode_alert(
+	sink: &AlertSink,
+	kind: AlertKind,
+	subject: &str,
+	message: &str,
+	resolved: bool,
+) -> (String, serde_json::Value) {
+	let status = if resolved { "resolved" } else { "firing" };
+	match sink {
+		AlertSink::Webhook(url) => (
+			url.clone(),
+			serde_json::json!({
+				"alert": kind.name(),
+				"subject": subject,
+				"message": message,
+				"status": status,
+			}),
+		),
+		AlertSink::Slack(url) => (
+			url.clone(),
+			serde_json::json!({
+				"text": format!(
+					"{} [{}] {}: {}",
+					if resolved { ":white_check_mark:" } else { ":rotating_light:" },
+					kind.name(),
+					subject,
+					message,
+				),
+			}),
+		),
+		AlertSink::PagerDuty(routing_key) => (
+			PAGERDUTY_EVENTS_URL.into(),
+			serde_json::json!({
+				"routing_key": routing_key,
+				"event_action": if resolved { "resolve" } else { "trigger" },
+				"dedup_key": format!("substrate-relay/{}/{}", kind.name(), subject),
+				"payload": {
+					"summary": format!("[{}] {}: {}", kind.name(), subject, message),
+					"source": subject,
+					"severity": "critical",
+				},
+			}),
+		),
+	}
+}
+
+/// POST JSON body to given URL.
+async fn post(url: &str, body: serde_json::Value) -> anyhow::Result<()> {
+	use isahc::{HttpClient, Request};
+
+	let request = Request::post(url)
+		.header("Content-Type", "application/json")
+		.body(body.to_string())?;
+	let response = HttpClient::new()?.send_async(request).await?;
+	if !response.status().is_success() {
+		return Err(anyhow::format_err!("Alert sink has responded with {}", response.status()))
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn state() -> AlertsState {
+		AlertsState {
+			params: Some(AlertsParams {
+				sinks: vec![AlertSink::Webhook("http://localhost/alerts".into())],
+				max_stall: Duration::from_secs(600),
+				max_failed_submissions: 2,
+				repeat_interval: Duration::from_secs(60),
+			}),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn alert_is_repeated_after_interval() {
+		let now = Instant::now();
+		let mut state = state();
+
+		assert!(state.note_firing(AlertKind::LowBalance, "Millau", now).is_some());
+		assert!(state.note_firing(AlertKind::LowBalance, "Millau", now).is_none());
+		assert!(state.note_firing(AlertKind::LowBalance, "Rialto", now).is_some());
+		assert!(state
+			.note_firing(AlertKind::LowBalance, "Millau", now + Duration::from_secs(60))
+			.is_some());
+
+		assert!(state.note_resolved(AlertKind::LowBalance, "Millau").is_some());
+		assert!(state.note_resolved(AlertKind::LowBalance, "Millau").is_none());
+
+		state.params = None;
+		assert!(state.note_firing(AlertKind::PalletHalted, "Millau", now).is_none());
+	}
+
+	#[test]
+	fn submission_failures_are_counted() {
+		let mut state = state();
+
+		assert!(!state.note_submission_result("A-to-B", false));
+		assert!(state.note_submission_result("A-to-B", false));
+		assert!(!state.note_submission_result("B-to-A", false));
+		assert!(!state.note_submission_result("A-to-B", true));
+		assert!(!state.note_submission_result("A-to-B", false));
+	}
+
+	#[test]
+	fn pagerduty_alert_is_encoded() {
+		let sink = AlertSink::PagerDuty("key".into());
+		let (url, body) = encode_alert(&sink, AlertKind::PalletHalted, "Millau", "halted", true);
+		assert_eq!(url, PAGERDUTY_EVENTS_URL);
+		assert_eq!(body["event_action"], "resolve");
+		assert_eq!(body["dedup_key"], "substrate-relay/pallet_halted/Millau");
+	}
+}
@@ -0,0 +1,216 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clock abstraction, used by relay loops.
+//!
+//! Relay loops are using the `SystemClock` by default. Tests may use the `TestClock` to
+//! advance time manually, without waiting for real timers.
+
+use async_trait::async_trait;
+use futures::{channel::oneshot, Future, FutureExt};
+use std::{
+	fmt::Debug,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// Source of time for relay loops.
+#[async_trait]
+pub trait Clock: Debug + Send + Sync + 'static {
+	/// Return current time.
+	fn now(&self) -> Instant;
+
+	/// Sleep given amount of time.
+	async fn sleep(&self, duration: Duration);
+}
+
+/// Clock that is using system time and `async_std` timers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+	fn now(&self) -> Instant {
+		Instant::now()
+	}
+
+	async fn sleep(&self, duration: Duration) {
+		async_std::task::sleep(duration).await
+	}
+}
+
+/// Clock that is only advanced manually, using `TestClock::advance`.
+///
+/// Sleeping tasks are woken up when the clock is advanced past their deadline.
+#[derive(Clone, Debug)]
+pub struct TestClock {
+	state: Arc<Mutex<TestClockState>>,
+}
+
+#[derive(Debug)]
+struct TestClockState {
+	now: Instant,
+	sleepers: Vec<(Instant, oneshot::Sender<()>)>,
+}
+
+impl TestClock {
+	/// Create new test clock.
+	pub fn new() -> Self {
+		TestClock {
+			state: Arc::new(Mutex::new(TestClockState {
+				now: Instant::now(),
+				sleepers: Vec::new(),
+			})),
+		}
+	}
+
+	/// Advance clock by given duration, waking up all tasks whose sleep has ended.
+	pub fn advance(&self, duration: Duration) {
+		let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		state.now += duration;
+
+		let now = state.now;
+		let (awake, sleeping) = std::mem::take(&mut state.sleepers)
+			.into_iter()
+			.partition(|(deadline, _)| *deadline <= now);
+		state.sleepers = sleeping;
+		for (_, sender) in awake {
+			let _ = sender.send(());
+		}
+	}
+
+	/// Returns number of tasks that are currently sleeping.
+	pub fn sleepers(&self) -> usize {
+		self.state
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.sleepers
+			.iter()
+			.filter(|(_, sender)| !sender.is_canceled())
+			.count()
+	}
+
+	/// Wait until given number of tasks are sleeping.
+	pub async fn wait_for_sleepers(&self, count: usize) {
+		while self.sleepers() < count {
+			async_std::task::yield_now().await;
+		}
+	}
+
+	/// Run given future to completion, advancing the clock by `step` every time there are at
+	/// least `min_sleepers` sleeping tasks.
+	///
+	/// Before advancing the clock, other tasks are given a chance to process all events that
+	/// are not related to time.
+	pub async fn drive<T>(
+		&self,
+		future: impl Future<Output = T>,
+		min_sleepers: usize,
+		step: Duration,
+	) -> T {
+		const IDLE_YIELDS: usize = 32;
+
+		let future = future.fuse();
+		futures::pin_mut!(future);
+		loop {
+			futures::select! {
+				result = future => return result,
+				_ = self.wait_for_sleepers(min_sleepers).fuse() => {
+					for _ in 0..IDLE_YIELDS {
+						async_std::task::yield_now().await;
+					}
+					self.advance(step);
+				},
+			}
+		}
+	}
+}
+
+impl Default for TestClock {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait]
+impl Clock for TestClock {
+	fn now(&self) -> Instant {
+		self.state.lock().unwrap_or_else(|e| e.into_inner()).now
+	}
+
+	async fn sleep(&self, duration: Duration) {
+		let wake_up = {
+			let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+			let (sender, receiver) = oneshot::channel();
+			let deadline = state.now + duration;
+			if deadline <= state.now {
+				return
+			}
+			state.sleepers.push((deadline, sender));
+			receiver
+		};
+
+		let _ = wake_up.await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::FutureExt;
+
+	#[test]
+	fn test_clock_wakes_sleepers_when_advanced() {
+		let clock = TestClock::new();
+		let started_at = clock.now();
+
+		let sleeper = {
+			let clock = clock.clone();
+			async_std::task::spawn(async move { clock.sleep(Duration::from_secs(10)).await })
+		};
+		async_std::task::block_on(clock.wait_for_sleepers(1));
+
+		clock.advance(Duration::from_secs(9));
+		assert_eq!(clock.sleepers(), 1);
+		assert_eq!(clock.now(), started_at + Duration::from_secs(9));
+
+		clock.advance(Duration::from_secs(1));
+		async_std::task::block_on(sleeper);
+		assert_eq!(clock.sleepers(), 0);
+	}
+
+	#[test]
+	fn test_clock_drives_future_to_completion() {
+		let clock = TestClock::new();
+		let started_at = clock.now();
+
+		let sleeper = {
+			let clock = clock.clone();
+			async_std::task::spawn(async move {
+				clock.sleep(Duration::from_secs(10)).await;
+				clock.sleep(Duration::from_secs(10)).await;
+			})
+		};
+		async_std::task::block_on(clock.drive(sleeper, 1, Duration::from_secs(1)));
+		assert_eq!(clock.now(), started_at + Duration::from_secs(20));
+	}
+
+	#[test]
+	fn test_clock_zero_sleep_returns_immediately() {
+		let clock = TestClock::new();
+		assert_eq!(clock.sleep(Duration::ZERO).now_or_never(), Some(()));
+	}
+}
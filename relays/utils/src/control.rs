@@ -0,0 +1,315 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime control of relay loops, running in this process.
+//!
+//! Control requests are served at the relay (Prometheus) endpoint if the control has been
+//! enabled. They require the same authentication as metrics, so the endpoint must either be
+//! protected or exposed only at the local interface. Following requests are supported:
+//!
+//! - `GET /loops` returns the list of all relay loops, along with their state (`running` or
+//!   `paused`), the time since their last progress and the tip, if it has been set;
+//!
+//! - `POST /loops/<loop-name>/pause` pauses given relay loop. Paused loop keeps reading state of
+//!   both nodes and tracking its in-flight transactions, but it doesn't submit new transactions;
+//!
+//! - `POST /loops/<loop-name>/resume` resumes given relay loop;
+//!
+//! - `POST /loops/<loop-name>/trigger` asks the finality loop, which only relays mandatory headers,
+//!   to submit the best finalized header once, even if it is not mandatory;
+//!
+//! - `POST /loops/<loop-name>/tip/<amount>` sets the tip of all transactions, submitted by given
+//!   relay loop from now on. Zero tip removes it;
+//!
+//! - `GET /state` returns the saved state of all relay loops (see the `state_store` module) in the
+//!   same format as the `export-state` command does.
+
+use crate::{health::loops_stalled_for, initialize::loop_name, state_store};
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex, OnceLock,
+	},
+	time::{Duration, Instant},
+};
+
+/// True if control requests are served.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Global control state of all relay loops.
+static CONTROL: OnceLock<Mutex<ControlState>> = OnceLock::new();
+
+/// Control state of all relay loops.
+#[derive(Debug, Default, PartialEq)]
+struct ControlState {
+	/// Names of all paused relay loops.
+	paused: BTreeSet<String>,
+	/// Names of relay loops, that have been asked to submit the best header.
+	triggered: BTreeSet<String>,
+	/// Tips of relay loops transactions.
+	tips: BTreeMap<String, u128>,
+}
+
+fn with_control<R>(f: impl FnOnce(&mut ControlState) -> R) -> R {
+	// the lock is never held across panics, so poisoning is not expected
+	let mut state = CONTROL
+		.get_or_init(|| Mutex::new(ControlState::default()))
+		.lock()
+		.unwrap_or_else(|e| e.into_inner());
+	f(&mut state)
+}
+
+/// Start serving control requests at the relay endpoint.
+pub fn enable() {
+	ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if control requests are served.
+pub(crate) fn is_enabled() -> bool {
+	ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns true if the endpoint, exposed at given host, is only reachable from this machine.
+/// Control without authentication may only be enabled at such endpoint.
+pub fn is_loopback_host(host: &str) -> bool {
+	host.eq_ignore_ascii_case("localhost") ||
+		host.trim_start_matches('[')
+			.trim_end_matches(']')
+			.parse::<std::net::IpAddr>()
+			.map(|addr| addr.is_loopback())
+			.unwrap_or(false)
+}
+
+/// Returns true if the current relay loop has been paused and must not submit any new
+/// transactions.
+pub fn is_paused() -> bool {
+	loop_name().map(|loop_name| is_loop_paused(&loop_name)).unwrap_or(false)
+}
+
+/// Returns true if relay loop with given name has been paused.
+pub(crate) fn is_loop_paused(loop_name: &str) -> bool {
+	with_control(|state| state.paused.contains(loop_name))
+}
+
+/// Returns true if the current relay loop has been asked to submit the best header. The request
+/// is forgotten after this call.
+pub fn take_trigger() -> bool {
+	loop_name()
+		.map(|loop_name| with_control(|state| state.triggered.remove(&loop_name)))
+		.unwrap_or(false)
+}
+
+/// Returns tip of transactions, submitted by the current relay loop.
+pub fn tip() -> u128 {
+	loop_name()
+		.and_then(|loop_name| with_control(|state| state.tips.get(&loop_name).copied()))
+		.unwrap_or(0)
+}
+
+/// Handle single control request. Returns response status, content type and body.
+pub(crate) fn handle_request(method: &str, path: &str) -> (&'static str, &'static str, String) {
+	if (method, path) == ("GET", "/state") {
+		return match state_store::state_store().map(state_store::export) {
+			Some(Ok(states)) => ("200 OK", "application/json", states.to_string()),
+			Some(Err(e)) => ("500 Internal Server Error", "text/plain", format!("{e}\n")),
+			None => ("404 Not Found", "text/plain", "relay state database is not opened\n".into()),
+		}
+	}
+
+	// the health lock is never taken while the control lock is held
+	let loops = loops_stalled_for(Instant::now());
+	let (status, body) = with_control(|state| handle_loops_request(state, loops, method, path));
+	(status, "text/plain", body)
+}
+
+/// Handle single relay loops request, given control state and time since the last progress of
+/// every relay loop.
+fn handle_loops_request(
+	state: &mut ControlState,
+	loops: Vec<(String, Duration)>,
+	method: &str,
+	path: &str,
+) -> (&'static str, String) {
+	let mut path = path.trim_matches('/').split('/');
+	let (loop_name, action, argument) = match (method, path.next(), path.next()) {
+		("GET", Some("loops"), None) => {
+			let body = loops
+				.into_iter()
+				.map(|(loop_name, stalled_for)| {
+					let run_state =
+						if state.paused.contains(&loop_name) { "paused" } else { "running" };
+					let tip = state
+						.tips
+						.get(&loop_name)
+						.map(|tip| format!(", tip {tip}"))
+						.unwrap_or_default();
+					format!(
+						"{loop_name}: {run_state}, no progress during last {}s{tip}\n",
+						stalled_for.as_secs(),
+					)
+				})
+				.collect();
+			return ("200 OK", body)
+		},
+		("POST", Some("loops"), Some(loop_name)) => (loop_name, path.next(), path.next()),
+		_ => return ("404 Not Found", "unknown endpoint\n".into()),
+	};
+	if path.next().is_some() {
+		return ("404 Not Found", "unknown endpoint\n".into())
+	}
+	if !loops.iter().any(|(name, _)| name == loop_name) {
+		return ("404 Not Found", format!("unknown relay loop: {loop_name}\n"))
+	}
+
+	match (action, argument) {
+		(Some("pause"), None) => {
+			state.paused.insert(loop_name.into());
+		},
+		(Some("resume"), None) => {
+			state.paused.remove(loop_name);
+		},
+		(Some("trigger"), None) => {
+			state.triggered.insert(loop_name.into());
+		},
+		(Some("tip"), Some(tip)) => match tip.parse::<u128>() {
+			Ok(0) => {
+				state.tips.remove(loop_name);
+			},
+			Ok(tip) => {
+				state.tips.insert(loop_name.into(), tip);
+			},
+			Err(_) => return ("400 Bad Request", format!("invalid tip: {tip}\n")),
+		},
+		_ => return ("404 Not Found", "unknown endpoint\n".into()),
+	}
+	log::info!(
+		target: "bridge",
+		"Relay loop {} has been asked to {}{}",
+		loop_name,
+		action.unwrap_or_default(),
+		argument.map(|argument| format!(" {argument}")).unwrap_or_default(),
+	);
+
+	("200 OK", "ok\n".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn loops() -> Vec<(String, Duration)> {
+		vec![("A-to-B".into(), Duration::from_secs(5)), ("B-to-A".into(), Duration::from_secs(7))]
+	}
+
+	#[test]
+	fn loopback_hosts_are_detected() {
+		assert!(is_loopback_host("localhost"));
+		assert!(is_loopback_host("127.0.0.1"));
+		assert!(is_loopback_host("::1"));
+		assert!(is_loopback_host("[::1]"));
+		assert!(!is_loopback_host("0.0.0.0"));
+		assert!(!is_loopback_host("::"));
+		assert!(!is_loopback_host("10.0.0.1"));
+		assert!(!is_loopback_host("relay.example.com"));
+	}
+
+	#[test]
+	fn loops_are_paused_and_resumed() {
+		let mut state = ControlState::default();
+
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/A-to-B/pause"),
+			("200 OK", "ok\n".into()),
+		);
+		assert_eq!(state.paused, vec!["A-to-B".to_string()].into_iter().collect());
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "GET", "/loops"),
+			(
+				"200 OK",
+				"A-to-B: paused, no progress during last 5s\n\
+				B-to-A: running, no progress during last 7s\n"
+					.into()
+			),
+		);
+
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/A-to-B/resume"),
+			("200 OK", "ok\n".into()),
+		);
+		assert_eq!(state, ControlState::default());
+	}
+
+	#[test]
+	fn loops_are_triggered_and_tipped() {
+		let mut state = ControlState::default();
+
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/A-to-B/trigger"),
+			("200 OK", "ok\n".into()),
+		);
+		assert_eq!(state.triggered, vec!["A-to-B".to_string()].into_iter().collect());
+
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/B-to-A/tip/1000"),
+			("200 OK", "ok\n".into()),
+		);
+		assert_eq!(state.tips, vec![("B-to-A".to_string(), 1000)].into_iter().collect());
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "GET", "/loops"),
+			(
+				"200 OK",
+				"A-to-B: running, no progress during last 5s\n\
+				B-to-A: running, no progress during last 7s, tip 1000\n"
+					.into()
+			),
+		);
+
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/B-to-A/tip/0"),
+			("200 OK", "ok\n".into()),
+		);
+		assert!(state.tips.is_empty());
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/B-to-A/tip/many").0,
+			"400 Bad Request",
+		);
+	}
+
+	#[test]
+	fn unknown_loops_and_endpoints_are_rejected() {
+		let mut state = ControlState::default();
+
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/C-to-D/pause").0,
+			"404 Not Found",
+		);
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "GET", "/loops/A-to-B/pause").0,
+			"404 Not Found",
+		);
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/A-to-B/tip").0,
+			"404 Not Found",
+		);
+		assert_eq!(
+			handle_loops_request(&mut state, loops(), "POST", "/loops/A-to-B/pause/now").0,
+			"404 Not Found",
+		);
+		assert_eq!(state, ControlState::default());
+	}
+}
@@ -34,12 +34,21 @@ pub enum Error {
 		"Failed to parse float value {0:?} from response. It is assumed to be positive and normal"
 	)]
 	ParseFloat(f64),
+	/// Invalid token price source specification.
+	#[error("Invalid token price source: {0}")]
+	InvalidTokenPriceSource(String),
 	/// Couldn't found value in the JSON response.
 	#[error("Missing required value from response: {0:?}")]
 	MissingResponseValue(String),
 	/// Invalid host address was used for exposing Prometheus metrics.
 	#[error("Invalid host {0} is used to expose Prometheus metrics: {1}")]
 	ExposingMetricsInvalidHost(String, AddrParseError),
+	/// Failed to load TLS configuration of the Prometheus endpoint.
+	#[error("Failed to load TLS configuration of the Prometheus endpoint: {0}")]
+	ExposingMetricsTls(String),
+	/// Failed to read or write the relay state database.
+	#[error("Relay state database error: {0}")]
+	StateStore(String),
 	/// Prometheus error.
 	#[error("{0}")]
 	Prometheus(#[from] substrate_prometheus_endpoint::prometheus::Error),
@@ -0,0 +1,311 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Health and readiness of relay loops, running in this process.
+//!
+//! The state is collected from all relay loops and is served at the relay (Prometheus) endpoint:
+//!
+//! - `/healthz` returns `200 OK` unless some relay loop has not made any progress during the
+//!   configured period. The loop makes progress when the target chain gets new headers or messages
+//!   from the loop, or when there's nothing to relay. It is supposed to be used as a liveness
+//!   probe, because restarting the stalled relay may help;
+//!
+//! - `/readyz` returns `200 OK` if all relay loops are connected to both nodes, have recently made
+//!   some progress and all additional checks (like relayer account balance check) are passing.
+//!   Otherwise, `503 Service Unavailable` is returned along with the list of reasons.
+
+use crate::{initialize::loop_name, FailedClient};
+
+use std::{
+	collections::BTreeMap,
+	sync::{Mutex, OnceLock},
+	time::{Duration, Instant},
+};
+
+/// Default period, during which every relay loop must make some progress to be considered
+/// healthy.
+pub const DEFAULT_MAX_STALL: Duration = Duration::from_secs(10 * 60);
+
+/// Global health state of all relay loops.
+static HEALTH: OnceLock<Mutex<HealthState>> = OnceLock::new();
+
+fn health() -> &'static Mutex<HealthState> {
+	HEALTH.get_or_init(|| Mutex::new(HealthState::default()))
+}
+
+fn with_health<R>(f: impl FnOnce(&mut HealthState) -> R) -> R {
+	// the lock is never held across panics, so poisoning is not expected
+	let mut state = health().lock().unwrap_or_else(|e| e.into_inner());
+	f(&mut state)
+}
+
+/// Health of the single relay loop.
+#[derive(Debug)]
+struct LoopHealth {
+	/// Client that the loop has lost connection to, if any.
+	disconnected: Option<FailedClient>,
+	/// The moment when loop has been started.
+	started: Instant,
+	/// Progress of every race of the loop (e.g. messages delivery and confirmation races).
+	races: BTreeMap<String, RaceProgress>,
+}
+
+/// Progress of the single race of the relay loop.
+#[derive(Debug)]
+struct RaceProgress {
+	/// The best item (header number or message nonce), known to the target chain.
+	best_at_target: u64,
+	/// The last moment when race has made some progress.
+	last_progress: Instant,
+}
+
+impl LoopHealth {
+	/// Create health of the loop that has been started at given moment.
+	fn new(started: Instant) -> Self {
+		LoopHealth { disconnected: None, started, races: BTreeMap::new() }
+	}
+
+	/// Returns the last moment when all races of the loop have made some progress.
+	fn last_progress(&self) -> Instant {
+		self.races.values().map(|race| race.last_progress).min().unwrap_or(self.started)
+	}
+
+	/// Note the best item at the source and target chains of given race.
+	fn note_race_state(
+		&mut self,
+		race: &str,
+		best_at_source: Option<u64>,
+		best_at_target: u64,
+		now: Instant,
+	) {
+		let started = self.started;
+		let race = self
+			.races
+			.entry(race.into())
+			.or_insert(RaceProgress { best_at_target, last_progress: started });
+		let is_target_advanced = best_at_target > race.best_at_target;
+		let is_nothing_to_relay = best_at_source
+			.map(|best_at_source| best_at_target >= best_at_source)
+			.unwrap_or(false);
+		if is_target_advanced || is_nothing_to_relay {
+			race.last_progress = now;
+		}
+		race.best_at_target = best_at_target;
+	}
+}
+
+/// Health state of all relay loops.
+#[derive(Debug, Default)]
+struct HealthState {
+	/// Max period of time without loop progress.
+	max_stall: Option<Duration>,
+	/// Relay loops by their names.
+	loops: BTreeMap<String, LoopHealth>,
+	/// Additional checks and the failure reason of every failed check.
+	checks: BTreeMap<String, Option<String>>,
+}
+
+impl HealthState {
+	/// Returns reasons why relay is not healthy.
+	fn liveness_failures(&self, now: Instant) -> Vec<String> {
+		let max_stall = self.max_stall.unwrap_or(DEFAULT_MAX_STALL);
+		self.loops
+			.iter()
+			.filter_map(|(loop_name, loop_health)| {
+				let stalled_for = now.saturating_duration_since(loop_health.last_progress());
+				// paused loop doesn't relay anything, so it is not stalled
+				if stalled_for > max_stall && !crate::control::is_loop_paused(loop_name) {
+					Some(format!("{loop_name}: no progress during last {}s", stalled_for.as_secs()))
+				} else {
+					None
+				}
+			})
+			.collect()
+	}
+
+	/// Returns reasons why relay is not ready.
+	fn readiness_failures(&self, now: Instant) -> Vec<String> {
+		let disconnected = self.loops.iter().filter_map(|(loop_name, loop_health)| {
+			loop_health.disconnected.map(|failed_client| {
+				let failed_client = match failed_client {
+					FailedClient::Source => "source node",
+					FailedClient::Target => "target node",
+					FailedClient::Both => "both nodes",
+				};
+				format!("{loop_name}: disconnected from {failed_client}")
+			})
+		});
+		let failed_checks = self.checks.iter().filter_map(|(check, reason)| {
+			reason.as_ref().map(|reason| format!("{check}: {reason}"))
+		});
+		disconnected.chain(failed_checks).chain(self.liveness_failures(now)).collect()
+	}
+}
+
+/// Start tracking health of the current relay loop.
+pub(crate) fn note_loop_started(loop_name: &str) {
+	with_health(|state| {
+		state.loops.insert(loop_name.into(), LoopHealth::new(Instant::now()));
+	})
+}
+
+/// Note that the relay loop has lost connection to some of its clients.
+pub(crate) fn note_loop_disconnected(loop_name: &str, failed_client: FailedClient) {
+	with_health(|state| {
+		if let Some(loop_health) = state.loops.get_mut(loop_name) {
+			loop_health.disconnected = Some(failed_client);
+		}
+	})
+}
+
+/// Note that the relay loop has restored connection to its clients.
+pub(crate) fn note_loop_reconnected(loop_name: &str) {
+	with_health(|state| {
+		if let Some(loop_health) = state.loops.get_mut(loop_name) {
+			loop_health.disconnected = None;
+		}
+	})
+}
+
+/// Note the state of given race of the current relay loop.
+///
+/// The `best_at_target` is the best item (header number or message nonce), known to the target
+/// chain, and the `best_at_source` is the best item that may be relayed, if it is known. The race
+/// makes progress when the `best_at_target` grows or when there's nothing to relay. Reading
+/// the state alone is not a progress, so the race that fails to submit its transactions is
+/// considered stalled.
+pub fn note_race_state(race: &str, best_at_source: Option<u64>, best_at_target: u64) {
+	if let Some(loop_name) = loop_name() {
+		with_health(|state| {
+			if let Some(loop_health) = state.loops.get_mut(&loop_name) {
+				loop_health.note_race_state(race, best_at_source, best_at_target, Instant::now());
+			}
+		})
+	}
+}
+
+/// Returns time since the last progress of every relay loop.
+pub(crate) fn loops_stalled_for(now: Instant) -> Vec<(String, Duration)> {
+	with_health(|state| {
+		state
+			.loops
+			.iter()
+			.map(|(loop_name, loop_health)| {
+				(loop_name.clone(), now.saturating_duration_since(loop_health.last_progress()))
+			})
+			.collect()
+	})
+}
+
+/// Update result of additional readiness check. `None` means that the check is passing and
+/// `Some(reason)` means that it is failing.
+pub fn set_readiness_check(check: String, failure: Option<String>) {
+	with_health(|state| {
+		state.checks.insert(check, failure);
+	})
+}
+
+/// Set period, during which every relay loop must make some progress to be considered healthy.
+pub fn set_max_stall(max_stall: Duration) {
+	with_health(|state| state.max_stall = Some(max_stall))
+}
+
+/// Handle single health request with given path (`/healthz` or `/readyz`).
+pub(crate) fn handle_request(path: &str) -> (&'static str, String) {
+	let now = Instant::now();
+	let failures = if path == "/healthz" {
+		with_health(|state| state.liveness_failures(now))
+	} else {
+		with_health(|state| state.readiness_failures(now))
+	};
+
+	if failures.is_empty() {
+		("200 OK", "ok\n".into())
+	} else {
+		("503 Service Unavailable", failures.join("\n") + "\n")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn state(now: Instant) -> HealthState {
+		let mut state =
+			HealthState { max_stall: Some(Duration::from_secs(60)), ..Default::default() };
+		state.loops.insert("A-to-B".into(), LoopHealth::new(now));
+		state
+	}
+
+	#[test]
+	fn stalled_loop_is_not_healthy() {
+		let now = Instant::now();
+		let state = state(now);
+
+		assert!(state.liveness_failures(now + Duration::from_secs(60)).is_empty());
+		assert!(state.readiness_failures(now + Duration::from_secs(60)).is_empty());
+		assert_eq!(state.liveness_failures(now + Duration::from_secs(61)).len(), 1);
+		assert_eq!(state.readiness_failures(now + Duration::from_secs(61)).len(), 1);
+	}
+
+	#[test]
+	fn only_target_advance_or_nothing_to_relay_is_progress() {
+		let now = Instant::now();
+		let mut state = state(now);
+		let loop_health = state.loops.get_mut("A-to-B").unwrap();
+		let at = |secs| now + Duration::from_secs(secs);
+
+		// target is behind and doesn't advance => no progress
+		loop_health.note_race_state("delivery", Some(10), 5, at(30));
+		loop_health.note_race_state("delivery", Some(10), 5, at(61));
+		assert_eq!(state.liveness_failures(at(61)).len(), 1);
+
+		// target advances => progress
+		let loop_health = state.loops.get_mut("A-to-B").unwrap();
+		loop_health.note_race_state("delivery", Some(10), 7, at(90));
+		assert!(state.liveness_failures(at(120)).is_empty());
+		assert_eq!(state.liveness_failures(at(151)).len(), 1);
+
+		// nothing to relay => progress
+		let loop_health = state.loops.get_mut("A-to-B").unwrap();
+		loop_health.note_race_state("delivery", Some(10), 10, at(200));
+		assert!(state.liveness_failures(at(260)).is_empty());
+
+		// the loop is stalled if any of its races is stalled
+		let loop_health = state.loops.get_mut("A-to-B").unwrap();
+		loop_health.note_race_state("delivery", Some(10), 10, at(300));
+		loop_health.note_race_state("confirmation", None, 3, at(300));
+		assert_eq!(state.liveness_failures(at(300)).len(), 1);
+	}
+
+	#[test]
+	fn disconnected_loop_and_failed_check_are_not_ready() {
+		let now = Instant::now();
+		let mut state = state(now);
+
+		state.loops.get_mut("A-to-B").unwrap().disconnected = Some(FailedClient::Target);
+		state.checks.insert("balance".into(), Some("too low".into()));
+		assert!(state.liveness_failures(now).is_empty());
+		assert_eq!(
+			state.readiness_failures(now),
+			vec!["A-to-B: disconnected from target node".to_string(), "balance: too low".into()],
+		);
+
+		state.loops.get_mut("A-to-B").unwrap().disconnected = None;
+		state.checks.insert("balance".into(), None);
+		assert!(state.readiness_failures(now).is_empty());
+	}
+}
@@ -16,19 +16,49 @@
 
 //! Relayer initialization functions.
 
-use std::{cell::RefCell, fmt::Display, io::Write};
+use crate::log_file::{LogFileParams, RotatingLogFile};
+
+use std::{cell::RefCell, fmt::Display, io::Write, str::FromStr};
 
 async_std::task_local! {
 	pub(crate) static LOOP_NAME: RefCell<String> = RefCell::new(String::default());
 }
 
+/// Format of log records.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+	/// Human-readable text records.
+	#[default]
+	Text,
+	/// Structured JSON records, one record per line.
+	Json,
+}
+
+impl FromStr for LogFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(LogFormat::Text),
+			"json" => Ok(LogFormat::Json),
+			_ => Err(format!("Unknown log format: {s}. Supported formats are: text, json")),
+		}
+	}
+}
+
 /// Initialize relay environment.
-pub fn initialize_relay() {
-	initialize_logger(true);
+pub fn initialize_relay(log_format: LogFormat, log_file: Option<LogFileParams>) {
+	initialize_logger(true, log_format, log_file);
 }
 
 /// Initialize Relay logger instance.
-pub fn initialize_logger(with_timestamp: bool) {
+///
+/// If log file is specified, records are written to this file instead of stderr.
+pub fn initialize_logger(
+	with_timestamp: bool,
+	log_format: LogFormat,
+	log_file: Option<LogFileParams>,
+) {
 	let format = time::format_description::parse(
 		"[year]-[month]-[day] \
 		[hour repr:24]:[minute]:[second] [offset_hour sign:mandatory]",
@@ -39,15 +69,29 @@ pub fn initialize_logger(with_timestamp: bool) {
 	builder.filter_level(log::LevelFilter::Warn);
 	builder.filter_module("bridge", log::LevelFilter::Info);
 	builder.parse_default_env();
-	if with_timestamp {
+	// there's no point in writing escape sequences to the file
+	let colored = !cfg!(windows) && log_file.is_none();
+	if let Some(log_file) = log_file {
+		match RotatingLogFile::open(log_file.clone()) {
+			Ok(file) => {
+				builder.target(env_logger::Target::Pipe(Box::new(file)));
+			},
+			Err(e) => {
+				eprintln!("Failed to open log file {:?}: {}. Logging to stderr", log_file.path, e);
+			},
+		}
+	}
+	if log_format == LogFormat::Json {
+		builder.format(move |buf, record| writeln!(buf, "{}", json_record(record)));
+	} else if with_timestamp {
 		builder.format(move |buf, record| {
 			let timestamp = time::OffsetDateTime::now_local()
 				.unwrap_or_else(|_| time::OffsetDateTime::now_utc());
 			let timestamp = timestamp.format(&format).unwrap_or_else(|_| timestamp.to_string());
 
-			let log_level = color_level(record.level());
-			let log_target = color_target(record.target());
-			let timestamp = if cfg!(windows) {
+			let log_level = color_level(record.level(), colored);
+			let log_target = color_target(record.target(), colored);
+			let timestamp = if !colored {
 				Either::Left(timestamp)
 			} else {
 				Either::Right(ansi_term::Colour::Fixed(8).bold().paint(timestamp))
@@ -65,8 +109,8 @@ pub fn initialize_logger(with_timestamp: bool) {
 		});
 	} else {
 		builder.format(move |buf, record| {
-			let log_level = color_level(record.level());
-			let log_target = color_target(record.target());
+			let log_level = color_level(record.level(), colored);
+			let log_target = color_target(record.target(), colored);
 
 			writeln!(buf, "{}{log_level} {log_target} {}", loop_name_prefix(), record.args(),)
 		});
@@ -75,6 +119,53 @@ pub fn initialize_logger(with_timestamp: bool) {
 	builder.init();
 }
 
+/// Convert log record into JSON object.
+///
+/// Apart from the message itself, the object contains the record timestamp, level, target and
+/// the name of relay loop (`bridge`) that has emitted the record. All structured key-value pairs
+/// of the record (e.g. `lane`, `chain`, `nonces_begin`, `tx_hash`) are added as separate fields.
+fn json_record(record: &log::Record) -> serde_json::Value {
+	let timestamp = time::OffsetDateTime::now_utc();
+	let timestamp = timestamp
+		.format(&time::format_description::well_known::Rfc3339)
+		.unwrap_or_else(|_| timestamp.to_string());
+
+	let mut fields = serde_json::Map::new();
+	fields.insert("timestamp".into(), timestamp.into());
+	fields.insert("level".into(), record.level().as_str().into());
+	fields.insert("target".into(), record.target().into());
+	if let Some(loop_name) = loop_name() {
+		fields.insert("bridge".into(), loop_name.into());
+	}
+	let _ = record.key_values().visit(&mut JsonFieldsVisitor(&mut fields));
+	fields.insert("message".into(), record.args().to_string().into());
+
+	serde_json::Value::Object(fields)
+}
+
+/// Key-values visitor that inserts all visited pairs into JSON object.
+struct JsonFieldsVisitor<'a>(&'a mut serde_json::Map<String, serde_json::Value>);
+
+impl<'kvs, 'a> log::kv::Visitor<'kvs> for JsonFieldsVisitor<'a> {
+	fn visit_pair(
+		&mut self,
+		key: log::kv::Key<'kvs>,
+		value: log::kv::Value<'kvs>,
+	) -> Result<(), log::kv::Error> {
+		let value = if let Some(value) = value.to_u64() {
+			value.into()
+		} else if let Some(value) = value.to_i64() {
+			value.into()
+		} else if let Some(value) = value.to_bool() {
+			value.into()
+		} else {
+			value.to_string().into()
+		};
+		self.0.insert(key.as_str().into(), value);
+		Ok(())
+	}
+}
+
 /// Initialize relay loop. Must only be called once per every loop task.
 pub(crate) fn initialize_loop(loop_name: String) {
 	LOOP_NAME.with(|g_loop_name| *g_loop_name.borrow_mut() = loop_name);
@@ -83,6 +174,12 @@ pub(crate) fn initialize_loop(loop_name: String) {
 /// Returns loop name prefix to use in logs. The prefix is initialized with the `initialize_loop`
 /// call.
 fn loop_name_prefix() -> String {
+	loop_name().map(|loop_name| format!("[{loop_name}] ")).unwrap_or_default()
+}
+
+/// Returns name of the current relay loop. The name is initialized with the `initialize_loop`
+/// call.
+pub(crate) fn loop_name() -> Option<String> {
 	// try_with to avoid panic outside of async-std task context
 	LOOP_NAME
 		.try_with(|loop_name| {
@@ -90,12 +187,12 @@ fn loop_name_prefix() -> String {
 			// only be called once)
 			let loop_name = loop_name.borrow();
 			if loop_name.is_empty() {
-				String::new()
+				None
 			} else {
-				format!("[{loop_name}] ")
+				Some(loop_name.clone())
 			}
 		})
-		.unwrap_or_else(|_| String::new())
+		.unwrap_or(None)
 }
 
 enum Either<A, B> {
@@ -111,16 +208,16 @@ impl<A: Display, B: Display> Display for Either<A, B> {
 	}
 }
 
-fn color_target(target: &str) -> impl Display + '_ {
-	if cfg!(windows) {
+fn color_target(target: &str, colored: bool) -> impl Display + '_ {
+	if !colored {
 		Either::Left(target)
 	} else {
 		Either::Right(ansi_term::Colour::Fixed(8).paint(target))
 	}
 }
 
-fn color_level(level: log::Level) -> impl Display {
-	if cfg!(windows) {
+fn color_level(level: log::Level, colored: bool) -> impl Display {
+	if !colored {
 		Either::Left(level)
 	} else {
 		let s = level.to_string();
@@ -134,3 +231,39 @@ fn color_level(level: log::Level) -> impl Display {
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn log_format_is_parsed() {
+		assert_eq!(LogFormat::from_str("text"), Ok(LogFormat::Text));
+		assert_eq!(LogFormat::from_str("json"), Ok(LogFormat::Json));
+		assert!(LogFormat::from_str("xml").is_err());
+	}
+
+	#[test]
+	fn json_record_contains_structured_fields() {
+		let kvs: &[(&str, log::kv::Value)] = &[
+			("chain", "Millau".into()),
+			("nonces_begin", 1u64.into()),
+			("nonces_end", 10u64.into()),
+		];
+		let record = log::Record::builder()
+			.level(log::Level::Info)
+			.target("bridge")
+			.args(format_args!("Submitted proof"))
+			.key_values(&kvs)
+			.build();
+		let json = json_record(&record);
+
+		assert_eq!(json["level"], "INFO");
+		assert_eq!(json["target"], "bridge");
+		assert_eq!(json["message"], "Submitted proof");
+		assert_eq!(json["chain"], "Millau");
+		assert_eq!(json["nonces_begin"], 1);
+		assert_eq!(json["nonces_end"], 10);
+		assert!(json["timestamp"].is_string());
+	}
+}
@@ -24,7 +24,7 @@ pub use sp_runtime::traits::{UniqueSaturatedFrom, UniqueSaturatedInto};
 use async_trait::async_trait;
 use backoff::{backoff::Backoff, ExponentialBackoff};
 use futures::future::FutureExt;
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 
 /// Default relay loop stall timeout. If transactions generated by relay are immortal, then
@@ -43,10 +43,21 @@ pub const MAX_BACKOFF_INTERVAL: Duration = Duration::from_secs(60);
 /// reconnection again.
 pub const CONNECTION_ERROR_DELAY: Duration = Duration::from_secs(10);
 
+pub mod alerts;
+pub mod clock;
+pub mod control;
 pub mod error;
+pub mod health;
 pub mod initialize;
+pub mod log_file;
 pub mod metrics;
+pub mod otlp;
+pub mod reconnect_backoff;
 pub mod relay_loop;
+pub mod shutdown;
+pub mod simulation;
+pub mod state_store;
+pub mod supervisor;
 
 /// Block number traits shared by all chains that relay is able to serve.
 pub trait BlockNumberBase:
@@ -119,6 +130,15 @@ macro_rules! bail_on_arg_error {
 pub trait MaybeConnectionError {
 	/// Returns true if error (maybe) represents connection error.
 	fn is_connection_error(&self) -> bool;
+
+	/// Returns stable short code of the error, used to group failures in metrics.
+	fn error_code(&self) -> &'static str {
+		if self.is_connection_error() {
+			"connection"
+		} else {
+			"other"
+		}
+	}
 }
 
 /// Final status of the tracked transaction.
@@ -202,9 +222,17 @@ pub fn format_ids<Id: std::fmt::Debug>(mut ids: impl ExactSizeIterator<Item = Id
 
 /// Stream that emits item every `timeout_ms` milliseconds.
 pub fn interval(timeout: Duration) -> impl futures::Stream<Item = ()> {
-	futures::stream::unfold((), move |_| async move {
-		async_std::task::sleep(timeout).await;
-		Some(((), ()))
+	interval_with_clock(Arc::new(clock::SystemClock), timeout)
+}
+
+/// Stream that emits item every `timeout` of given clock.
+pub fn interval_with_clock(
+	clock: Arc<dyn clock::Clock>,
+	timeout: Duration,
+) -> impl futures::Stream<Item = ()> {
+	futures::stream::unfold(clock, move |clock| async move {
+		clock.sleep(timeout).await;
+		Some(((), clock))
 	})
 }
 
@@ -285,6 +313,7 @@ where
 			ProcessFutureResult::Success
 		},
 		Err(error) if error.is_connection_error() => {
+			metrics::note_iteration_failure(&error);
 			log::error!(
 				target: "bridge",
 				"{}: {:?}. Going to restart",
@@ -297,6 +326,7 @@ where
 			ProcessFutureResult::ConnectionFailed
 		},
 		Err(error) => {
+			metrics::note_iteration_failure(&error);
 			let retry_delay = retry_backoff.next_backoff().unwrap_or(CONNECTION_ERROR_DELAY);
 			log::error!(
 				target: "bridge",
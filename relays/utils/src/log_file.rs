@@ -0,0 +1,182 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Log file with rotation.
+//!
+//! The current log file is rotated when it grows above the configured size or when it has been
+//! written for longer than the configured period. Rotated files are renamed to `<path>.1`,
+//! `<path>.2`, ... (`<path>.1` is the most recent one) and only the configured number of
+//! rotated files is kept.
+
+use std::{
+	fs::{File, OpenOptions},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	time::{Duration, Instant},
+};
+
+/// Log file parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogFileParams {
+	/// Path to the current log file.
+	pub path: PathBuf,
+	/// Rotate the file when its size exceeds this number of bytes.
+	pub max_size: Option<u64>,
+	/// Rotate the file when it has been written for this period.
+	pub rotation_period: Option<Duration>,
+	/// Number of rotated files to keep. Older files are removed.
+	pub max_rotated_files: usize,
+}
+
+impl LogFileParams {
+	/// Create parameters of the log file that is never rotated.
+	pub fn new(path: PathBuf) -> Self {
+		LogFileParams { path, max_size: None, rotation_period: None, max_rotated_files: 0 }
+	}
+}
+
+/// Log file that is rotated according to its parameters.
+pub struct RotatingLogFile {
+	/// Log file parameters.
+	params: LogFileParams,
+	/// Current log file.
+	file: File,
+	/// Size of the current log file.
+	size: u64,
+	/// Time when the current log file has been opened.
+	opened_at: Instant,
+}
+
+impl RotatingLogFile {
+	/// Open (or create) the log file. If the file exists, new records are appended to it.
+	pub fn open(params: LogFileParams) -> io::Result<Self> {
+		let file = open_file(&params.path)?;
+		let size = file.metadata()?.len();
+		Ok(RotatingLogFile { params, file, size, opened_at: Instant::now() })
+	}
+
+	/// Returns true if the file needs to be rotated before writing `len` more bytes.
+	fn needs_rotation(&self, len: usize) -> bool {
+		let size_exceeded = self
+			.params
+			.max_size
+			.map(|max_size| self.size != 0 && self.size.saturating_add(len as u64) > max_size)
+			.unwrap_or(false);
+		let period_elapsed = self
+			.params
+			.rotation_period
+			.map(|rotation_period| self.opened_at.elapsed() >= rotation_period)
+			.unwrap_or(false);
+		size_exceeded || period_elapsed
+	}
+
+	/// Rotate log files and open the new current file.
+	fn rotate(&mut self) -> io::Result<()> {
+		self.file.flush()?;
+
+		let path = &self.params.path;
+		let max_rotated_files = self.params.max_rotated_files;
+		if max_rotated_files == 0 {
+			std::fs::remove_file(path)?;
+		} else {
+			remove_file_if_exists(&rotated_path(path, max_rotated_files))?;
+			for index in (1..max_rotated_files).rev() {
+				rename_file_if_exists(&rotated_path(path, index), &rotated_path(path, index + 1))?;
+			}
+			std::fs::rename(path, rotated_path(path, 1))?;
+		}
+
+		self.file = open_file(path)?;
+		self.size = 0;
+		self.opened_at = Instant::now();
+		Ok(())
+	}
+}
+
+impl Write for RotatingLogFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if self.needs_rotation(buf.len()) {
+			self.rotate()?;
+		}
+
+		let written = self.file.write(buf)?;
+		self.size = self.size.saturating_add(written as u64);
+		Ok(written)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+/// Open log file for appending.
+fn open_file(path: &Path) -> io::Result<File> {
+	OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Returns path to the rotated log file with given index.
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+	let mut rotated_path = path.as_os_str().to_owned();
+	rotated_path.push(format!(".{index}"));
+	rotated_path.into()
+}
+
+/// Remove file, ignoring error if it doesn't exist.
+fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+	match std::fs::remove_file(path) {
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+		result => result,
+	}
+}
+
+/// Rename file, ignoring error if it doesn't exist.
+fn rename_file_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+	match std::fs::rename(from, to) {
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+		result => result,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn log_file_is_rotated_by_size() {
+		let dir = std::env::temp_dir().join(format!("relay-log-file-test-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("relay.log");
+
+		let mut file = RotatingLogFile::open(LogFileParams {
+			max_size: Some(10),
+			max_rotated_files: 2,
+			..LogFileParams::new(path.clone())
+		})
+		.unwrap();
+		for record in ["record-1\n", "record-2\n", "record-3\n", "record-4\n"] {
+			file.write_all(record.as_bytes()).unwrap();
+		}
+		file.flush().unwrap();
+
+		let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+		assert_eq!(read(path.clone()), "record-4\n");
+		assert_eq!(read(rotated_path(&path, 1)), "record-3\n");
+		assert_eq!(read(rotated_path(&path, 2)), "record-2\n");
+		assert!(!rotated_path(&path, 3).exists());
+
+		std::fs::remove_dir_all(dir).unwrap();
+	}
+}
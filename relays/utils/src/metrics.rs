@@ -14,19 +14,32 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
-pub use float_json_value::FloatJsonValueMetric;
+pub use balance_threshold::BalanceThresholdMetric;
+pub use failures::{
+	note_iteration_failure, note_iteration_failure_code, register_iteration_failures,
+};
 pub use global::GlobalMetrics;
+pub use push_gateway::PushGatewayParams;
 pub use substrate_prometheus_endpoint::{
 	prometheus::core::{Atomic, Collector},
-	register, Counter, CounterVec, Gauge, GaugeVec, Opts, PrometheusError, Registry, F64, I64, U64,
+	register, Counter, CounterVec, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, Opts,
+	PrometheusError, Registry, F64, I64, U64,
+};
+pub use token_price::{
+	AnyPriceSource, CoinGeckoPriceSource, HttpJsonPriceSource, StaticPriceSource, TokenPriceMetric,
+	TokenPriceSource,
 };
 
 use async_std::sync::{Arc, RwLock};
 use async_trait::async_trait;
-use std::{fmt::Debug, time::Duration};
+use std::{collections::HashMap, fmt::Debug, path::PathBuf, time::Duration};
 
-mod float_json_value;
+mod balance_threshold;
+pub(crate) mod endpoint;
+mod failures;
 mod global;
+pub(crate) mod push_gateway;
+mod token_price;
 
 /// Shared reference to `f64` value that is updated by the metric.
 pub type F64SharedRef = Arc<RwLock<Option<f64>>>;
@@ -40,6 +53,45 @@ pub struct MetricsAddress {
 	pub host: String,
 	/// Serve HTTP requests at given port.
 	pub port: u16,
+	/// Authentication, required to access metrics.
+	pub auth: Option<MetricsAuth>,
+	/// Serve HTTPS requests instead of HTTP requests.
+	pub tls: Option<MetricsTls>,
+}
+
+/// Authentication, required to access Prometheus metrics.
+#[derive(Clone)]
+pub enum MetricsAuth {
+	/// Requests must have `Authorization: Bearer <token>` header.
+	Bearer(String),
+	/// Requests must use HTTP basic authentication.
+	Basic {
+		/// Expected user name.
+		username: String,
+		/// Expected password.
+		password: String,
+	},
+}
+
+impl Debug for MetricsAuth {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		// never print secrets
+		match self {
+			MetricsAuth::Bearer(_) => write!(fmt, "Bearer(<hidden>)"),
+			MetricsAuth::Basic { username, .. } => {
+				write!(fmt, "Basic {{ username: {username:?}, password: <hidden> }}")
+			},
+		}
+	}
+}
+
+/// TLS certificate and private key of the Prometheus endpoint.
+#[derive(Debug, Clone)]
+pub struct MetricsTls {
+	/// Path to the PEM file with the certificate chain.
+	pub cert_path: PathBuf,
+	/// Path to the PEM file with the private key.
+	pub key_path: PathBuf,
 }
 
 /// Prometheus endpoint MetricsParams.
@@ -47,6 +99,8 @@ pub struct MetricsAddress {
 pub struct MetricsParams {
 	/// Interface and TCP port to be used when exposing Prometheus metrics.
 	pub address: Option<MetricsAddress>,
+	/// Push metrics to the Prometheus Pushgateway.
+	pub push: Option<PushGatewayParams>,
 	/// Metrics registry. May be `Some(_)` if several components share the same endpoint.
 	pub registry: Registry,
 }
@@ -94,20 +148,27 @@ pub trait StandaloneMetric: Metric {
 
 impl Default for MetricsAddress {
 	fn default() -> Self {
-		MetricsAddress { host: "127.0.0.1".into(), port: 9616 }
+		MetricsAddress { host: "127.0.0.1".into(), port: 9616, auth: None, tls: None }
 	}
 }
 
 impl MetricsParams {
 	/// Creates metrics params from metrics address.
+	///
+	/// Given static `labels` are attached to every metric, registered in the created registry.
 	pub fn new(
 		address: Option<MetricsAddress>,
 		relay_version: String,
 		relay_commit: String,
+		labels: HashMap<String, String>,
 	) -> Result<Self, PrometheusError> {
 		const BUILD_INFO_METRIC: &str = "substrate_relay_build_info";
 
-		let registry = Registry::new();
+		let registry = if labels.is_empty() {
+			Registry::new()
+		} else {
+			Registry::new_custom(None, Some(labels))?
+		};
 		register(
 			Gauge::<U64>::with_opts(
 				Opts::new(
@@ -129,18 +190,26 @@ impl MetricsParams {
 			relay_commit,
 		);
 
-		Ok(MetricsParams { address, registry })
+		Ok(MetricsParams { address, push: None, registry })
 	}
 
 	/// Creates metrics params so that metrics are not exposed.
 	pub fn disabled() -> Self {
-		MetricsParams { address: None, registry: Registry::new() }
+		MetricsParams { address: None, push: None, registry: Registry::new() }
 	}
 
 	/// Do not expose metrics.
 	#[must_use]
 	pub fn disable(mut self) -> Self {
 		self.address = None;
+		self.push = None;
+		self
+	}
+
+	/// Push metrics to the Prometheus Pushgateway.
+	#[must_use]
+	pub fn with_push_gateway(mut self, push: PushGatewayParams) -> Self {
+		self.push = Some(push);
 		self
 	}
 }
@@ -190,3 +259,29 @@ pub fn set_gauge_value<T: Default + Debug, V: Atomic<T = T>, E: Debug>(
 		},
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn labels_are_attached_to_all_metrics() {
+		let params = MetricsParams::new(
+			None,
+			"1.0.0".into(),
+			"commit".into(),
+			vec![("environment".to_string(), "test".to_string())].into_iter().collect(),
+		)
+		.unwrap();
+
+		let metric_families = params.registry.gather();
+		let build_info = metric_families
+			.iter()
+			.find(|family| family.get_name() == "substrate_relay_build_info")
+			.unwrap();
+		assert!(build_info.get_metric()[0]
+			.get_label()
+			.iter()
+			.any(|label| label.get_name() == "environment" && label.get_value() == "test"));
+	}
+}
@@ -0,0 +1,129 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	alerts::{fire_alert, resolve_alert, AlertKind},
+	metrics::{
+		metric_name, register, F64SharedRef, IntGauge, Metric, PrometheusError, Registry,
+		StandaloneMetric,
+	},
+};
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Metric that has `1` value when the relay account balance is below the configured threshold
+/// and `0` otherwise.
+///
+/// The balance itself (in display units) is read by another metric and is shared using the
+/// `F64SharedRef`. When the balance drops below the threshold, the low balance alert is fired.
+#[derive(Debug, Clone)]
+pub struct BalanceThresholdMetric {
+	/// Name of the account, used in alerts.
+	account_name: String,
+	/// Shared reference to the account balance.
+	balance: F64SharedRef,
+	/// Balance threshold.
+	threshold: f64,
+	/// Balance update interval.
+	update_interval: Duration,
+	/// The metric.
+	metric: IntGauge,
+}
+
+impl BalanceThresholdMetric {
+	/// Create new metric instance with given name and help.
+	pub fn new(
+		account_name: String,
+		balance: F64SharedRef,
+		threshold: f64,
+		update_interval: Duration,
+		name: String,
+		help: String,
+	) -> Result<Self, PrometheusError> {
+		Ok(BalanceThresholdMetric {
+			account_name,
+			balance,
+			threshold,
+			update_interval,
+			metric: IntGauge::new(metric_name(None, &name), help)?,
+		})
+	}
+}
+
+impl Metric for BalanceThresholdMetric {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		register(self.metric.clone(), registry).map(drop)
+	}
+}
+
+#[async_trait]
+impl StandaloneMetric for BalanceThresholdMetric {
+	fn update_interval(&self) -> Duration {
+		self.update_interval
+	}
+
+	async fn update(&self) {
+		// leave the previous value if balance is unknown
+		let balance = match *self.balance.read().await {
+			Some(balance) => balance,
+			None => return,
+		};
+
+		let is_below_threshold = balance < self.threshold;
+		self.metric.set(is_below_threshold.into());
+		if is_below_threshold {
+			fire_alert(
+				AlertKind::LowBalance,
+				self.account_name.clone(),
+				format!("balance {balance} is below {}", self.threshold),
+			);
+		} else {
+			resolve_alert(AlertKind::LowBalance, self.account_name.clone());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use async_std::sync::{Arc, RwLock};
+
+	#[test]
+	fn metric_is_updated() {
+		let balance = Arc::new(RwLock::new(None));
+		let metric = BalanceThresholdMetric::new(
+			"Millau relay account".into(),
+			balance.clone(),
+			10.0,
+			Duration::from_secs(60),
+			"at_Millau_relay_balance_below_threshold".into(),
+			"help".into(),
+		)
+		.unwrap();
+
+		async_std::task::block_on(metric.update());
+		assert_eq!(metric.metric.get(), 0);
+
+		*async_std::task::block_on(balance.write()) = Some(9.5);
+		async_std::task::block_on(metric.update());
+		assert_eq!(metric.metric.get(), 1);
+
+		*async_std::task::block_on(balance.write()) = Some(10.0);
+		async_std::task::block_on(metric.update());
+		assert_eq!(metric.metric.get(), 0);
+	}
+}
@@ -0,0 +1,106 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	metrics::{CounterVec, Opts, PrometheusError, Registry, U64},
+	MaybeConnectionError,
+};
+
+use std::sync::OnceLock;
+
+/// Name of the relay iteration failures metric.
+const FAILURES_METRIC_NAME: &str = "relay_iteration_failures_total";
+/// Loop name that is used when failure happens outside of any named relay loop.
+const UNKNOWN_LOOP_NAME: &str = "unknown";
+
+/// Counter of relay iteration failures, labelled by the loop name and the error code.
+static ITERATION_FAILURES: OnceLock<CounterVec<U64>> = OnceLock::new();
+
+/// Returns counter of relay iteration failures.
+fn iteration_failures() -> &'static CounterVec<U64> {
+	ITERATION_FAILURES.get_or_init(|| {
+		CounterVec::new(
+			Opts::new(
+				FAILURES_METRIC_NAME,
+				"Number of failed relay loop iterations, grouped by the error code",
+			),
+			&["loop", "code"],
+		)
+		.expect("metric name, help and labels are static and valid; qed")
+	})
+}
+
+/// Register relay iteration failures counter in the registry.
+///
+/// The counter is shared by all relay loops of the process, so it is fine to call this
+/// function multiple times with the same registry.
+pub fn register_iteration_failures(registry: &Registry) -> Result<(), PrometheusError> {
+	match registry.register(Box::new(iteration_failures().clone())) {
+		Ok(()) | Err(PrometheusError::AlreadyReg) => Ok(()),
+		Err(e) => Err(e),
+	}
+}
+
+/// Note relay iteration failure, caused by given error.
+pub fn note_iteration_failure(error: &impl MaybeConnectionError) {
+	note_iteration_failure_code(error.error_code())
+}
+
+/// Note relay iteration failure with given error code.
+pub fn note_iteration_failure_code(code: &str) {
+	let loop_name = crate::initialize::loop_name();
+	iteration_failures()
+		.with_label_values(&[loop_name.as_deref().unwrap_or(UNKNOWN_LOOP_NAME), code])
+		.inc();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct TestError(bool);
+
+	impl MaybeConnectionError for TestError {
+		fn is_connection_error(&self) -> bool {
+			self.0
+		}
+	}
+
+	#[test]
+	fn failures_are_counted_by_error_code() {
+		let registry = Registry::new();
+		register_iteration_failures(&registry).unwrap();
+		register_iteration_failures(&registry).unwrap();
+
+		let connection_failures =
+			iteration_failures().with_label_values(&[UNKNOWN_LOOP_NAME, "connection"]).get();
+		let other_failures =
+			iteration_failures().with_label_values(&[UNKNOWN_LOOP_NAME, "other"]).get();
+
+		note_iteration_failure(&TestError(true));
+		note_iteration_failure(&TestError(true));
+		note_iteration_failure(&TestError(false));
+
+		assert_eq!(
+			iteration_failures().with_label_values(&[UNKNOWN_LOOP_NAME, "connection"]).get(),
+			connection_failures + 2,
+		);
+		assert_eq!(
+			iteration_failures().with_label_values(&[UNKNOWN_LOOP_NAME, "other"]).get(),
+			other_failures + 1,
+		);
+	}
+}
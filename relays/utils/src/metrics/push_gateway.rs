@@ -0,0 +1,114 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pushing metrics to the Prometheus Pushgateway.
+//!
+//! Relays that are running behind NAT or as short-living jobs can't be scraped by the
+//! Prometheus server. Instead, they may periodically push all their metrics to the Pushgateway.
+//! Every push replaces all metrics of the same grouping key (job and instance labels).
+
+use crate::metrics::Registry;
+
+use std::time::Duration;
+use substrate_prometheus_endpoint::prometheus::{Encoder, TextEncoder};
+
+/// Prometheus Pushgateway parameters.
+#[derive(Debug, Clone)]
+pub struct PushGatewayParams {
+	/// Pushgateway URL (e.g. `http://pushgateway:9091`).
+	pub url: String,
+	/// Value of the `job` label of pushed metrics.
+	pub job: String,
+	/// Value of the `instance` label of pushed metrics.
+	pub instance: Option<String>,
+	/// Interval between pushes.
+	pub interval: Duration,
+}
+
+impl PushGatewayParams {
+	/// Returns URL of the metrics group at the Pushgateway.
+	fn group_url(&self) -> String {
+		let mut url = format!("{}/metrics/job/{}", self.url.trim_end_matches('/'), self.job);
+		if let Some(ref instance) = self.instance {
+			url.push_str("/instance/");
+			url.push_str(instance);
+		}
+		url
+	}
+}
+
+/// Start pushing metrics from the registry to the Pushgateway.
+pub(crate) fn spawn(params: PushGatewayParams, registry: Registry) {
+	async_std::task::spawn(async move {
+		log::trace!(
+			target: "bridge-metrics",
+			"Starting pushing metrics to: {}",
+			params.group_url(),
+		);
+		loop {
+			if let Err(err) = push(&params, &registry).await {
+				log::debug!(
+					target: "bridge-metrics",
+					"Failed to push metrics to {}: {:?}",
+					params.group_url(),
+					err,
+				);
+			}
+			async_std::task::sleep(params.interval).await;
+		}
+	});
+}
+
+/// Push all metrics from the registry to the Pushgateway.
+async fn push(params: &PushGatewayParams, registry: &Registry) -> anyhow::Result<()> {
+	use isahc::{HttpClient, Request};
+
+	let encoder = TextEncoder::new();
+	let mut body = Vec::new();
+	encoder.encode(&registry.gather(), &mut body)?;
+
+	let request = Request::put(params.group_url())
+		.header("Content-Type", encoder.format_type())
+		.body(body)?;
+	let response = HttpClient::new()?.send_async(request).await?;
+	if !response.status().is_success() {
+		return Err(anyhow::format_err!("Pushgateway has responded with {}", response.status()))
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn group_url_is_built() {
+		let mut params = PushGatewayParams {
+			url: "http://pushgateway:9091/".into(),
+			job: "relay".into(),
+			instance: None,
+			interval: Duration::from_secs(15),
+		};
+		assert_eq!(params.group_url(), "http://pushgateway:9091/metrics/job/relay");
+
+		params.instance = Some("millau-rialto".into());
+		assert_eq!(
+			params.group_url(),
+			"http://pushgateway:9091/metrics/job/relay/instance/millau-rialto"
+		);
+	}
+}
@@ -0,0 +1,285 @@
+// Copyright 2019-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	error::{self, Error},
+	metrics::{
+		metric_name, register, F64SharedRef, Gauge, Metric, PrometheusError, Registry,
+		StandaloneMetric, F64,
+	},
+};
+
+use async_std::sync::{Arc, RwLock};
+use async_trait::async_trait;
+use std::{fmt::Debug, str::FromStr, time::Duration};
+
+/// Value update interval.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(300);
+/// CoinGecko simple price API endpoint.
+const COINGECKO_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+/// Source of the token price.
+#[async_trait]
+pub trait TokenPriceSource: Debug + Clone + Send + Sync + 'static {
+	/// Read the current token price.
+	///
+	/// The price is assumed to be normal (`f64::is_normal` should return `true`) and strictly
+	/// positive.
+	async fn read_price(&self) -> error::Result<f64>;
+}
+
+/// Token price that is read from the CoinGecko API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinGeckoPriceSource {
+	/// CoinGecko token identifier (e.g. `polkadot`).
+	pub token_id: String,
+	/// Currency of the price (e.g. `usd` or `btc`).
+	pub currency: String,
+}
+
+#[async_trait]
+impl TokenPriceSource for CoinGeckoPriceSource {
+	async fn read_price(&self) -> error::Result<f64> {
+		HttpJsonPriceSource {
+			url: format!(
+				"{COINGECKO_PRICE_URL}?ids={}&vs_currencies={}",
+				self.token_id, self.currency
+			),
+			json_path: format!("$.{}.{}", self.token_id, self.currency),
+		}
+		.read_price()
+		.await
+	}
+}
+
+/// Token price that is selected from the JSON response of some HTTP service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpJsonPriceSource {
+	/// HTTP service URL.
+	pub url: String,
+	/// JSONPath of the price in the service response.
+	pub json_path: String,
+}
+
+impl HttpJsonPriceSource {
+	/// Request value from HTTP service.
+	async fn request_value(&self) -> anyhow::Result<String> {
+		use isahc::{AsyncReadResponseExt, HttpClient, Request};
+
+		let request = Request::get(&self.url).header("Accept", "application/json").body(())?;
+		let raw_response = HttpClient::new()?.send_async(request).await?.text().await?;
+		Ok(raw_response)
+	}
+}
+
+#[async_trait]
+impl TokenPriceSource for HttpJsonPriceSource {
+	async fn read_price(&self) -> error::Result<f64> {
+		let raw_response = self.request_value().await.map_err(Error::FetchTokenPrice)?;
+		parse_service_response(&self.json_path, &raw_response)
+	}
+}
+
+/// Static token price. Useful for test deployments and tokens that are not listed anywhere.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticPriceSource(pub f64);
+
+#[async_trait]
+impl TokenPriceSource for StaticPriceSource {
+	async fn read_price(&self) -> error::Result<f64> {
+		Ok(self.0)
+	}
+}
+
+/// Any of supported token price sources.
+///
+/// May be parsed from string:
+///
+/// - `coingecko:<token-id>/<currency>` for the [`CoinGeckoPriceSource`];
+///
+/// - `json:<url>#<json-path>` for the [`HttpJsonPriceSource`];
+///
+/// - `static:<price>` for the [`StaticPriceSource`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyPriceSource {
+	/// CoinGecko API.
+	CoinGecko(CoinGeckoPriceSource),
+	/// Custom HTTP JSON service.
+	HttpJson(HttpJsonPriceSource),
+	/// Static price.
+	Static(StaticPriceSource),
+}
+
+#[async_trait]
+impl TokenPriceSource for AnyPriceSource {
+	async fn read_price(&self) -> error::Result<f64> {
+		match self {
+			AnyPriceSource::CoinGecko(source) => source.read_price().await,
+			AnyPriceSource::HttpJson(source) => source.read_price().await,
+			AnyPriceSource::Static(source) => source.read_price().await,
+		}
+	}
+}
+
+impl FromStr for AnyPriceSource {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || Error::InvalidTokenPriceSource(s.into());
+		let (kind, params) = s.split_once(':').ok_or_else(invalid)?;
+		match kind {
+			"coingecko" => {
+				let (token_id, currency) = params.split_once('/').ok_or_else(invalid)?;
+				Ok(AnyPriceSource::CoinGecko(CoinGeckoPriceSource {
+					token_id: token_id.into(),
+					currency: currency.into(),
+				}))
+			},
+			"json" => {
+				let (url, json_path) = params.rsplit_once('#').ok_or_else(invalid)?;
+				Ok(AnyPriceSource::HttpJson(HttpJsonPriceSource {
+					url: url.into(),
+					json_path: json_path.into(),
+				}))
+			},
+			"static" => {
+				let price = params.parse::<f64>().map_err(|_| invalid())?;
+				if !price.is_normal() || price < 0.0 {
+					return Err(Error::ParseFloat(price))
+				}
+				Ok(AnyPriceSource::Static(StaticPriceSource(price)))
+			},
+			_ => Err(invalid()),
+		}
+	}
+}
+
+/// Metric that represents token price, received from some source, as float gauge.
+#[derive(Debug, Clone)]
+pub struct TokenPriceMetric<S> {
+	source: S,
+	metric: Gauge<F64>,
+	shared_value_ref: F64SharedRef,
+}
+
+impl<S: TokenPriceSource> TokenPriceMetric<S> {
+	/// Create new metric instance with given name and help.
+	pub fn new(source: S, name: String, help: String) -> Result<Self, PrometheusError> {
+		let shared_value_ref = Arc::new(RwLock::new(None));
+		Ok(TokenPriceMetric {
+			source,
+			metric: Gauge::new(metric_name(None, &name), help)?,
+			shared_value_ref,
+		})
+	}
+
+	/// Get shared reference to metric value.
+	pub fn shared_value_ref(&self) -> F64SharedRef {
+		self.shared_value_ref.clone()
+	}
+}
+
+impl<S: TokenPriceSource> Metric for TokenPriceMetric<S> {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		register(self.metric.clone(), registry).map(drop)
+	}
+}
+
+#[async_trait]
+impl<S: TokenPriceSource> StandaloneMetric for TokenPriceMetric<S> {
+	fn update_interval(&self) -> Duration {
+		UPDATE_INTERVAL
+	}
+
+	async fn update(&self) {
+		let value = self.source.read_price().await;
+		let maybe_ok = value.as_ref().ok().copied();
+		crate::metrics::set_gauge_value(&self.metric, value.map(Some));
+		*self.shared_value_ref.write().await = maybe_ok;
+	}
+}
+
+/// Parse HTTP service response.
+fn parse_service_response(json_path: &str, response: &str) -> error::Result<f64> {
+	let json =
+		serde_json::from_str(response).map_err(|err| Error::ParseHttp(err, response.to_owned()))?;
+
+	let mut selector = jsonpath_lib::selector(&json);
+	let maybe_selected_value =
+		selector(json_path).map_err(|err| Error::SelectResponseValue(err, response.to_owned()))?;
+	let selected_value = maybe_selected_value
+		.first()
+		.and_then(|v| v.as_f64())
+		.ok_or_else(|| Error::MissingResponseValue(response.to_owned()))?;
+	if !selected_value.is_normal() || selected_value < 0.0 {
+		return Err(Error::ParseFloat(selected_value))
+	}
+
+	Ok(selected_value)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_service_response_works() {
+		assert_eq!(
+			parse_service_response("$.kusama.usd", r#"{"kusama":{"usd":433.05}}"#).map_err(drop),
+			Ok(433.05),
+		);
+	}
+
+	#[test]
+	fn parse_service_response_rejects_negative_numbers() {
+		assert!(parse_service_response("$.kusama.usd", r#"{"kusama":{"usd":-433.05}}"#).is_err());
+	}
+
+	#[test]
+	fn parse_service_response_rejects_zero_numbers() {
+		assert!(parse_service_response("$.kusama.usd", r#"{"kusama":{"usd":0.0}}"#).is_err());
+	}
+
+	#[test]
+	fn parse_service_response_rejects_nan() {
+		assert!(parse_service_response("$.kusama.usd", r#"{"kusama":{"usd":NaN}}"#).is_err());
+	}
+
+	#[test]
+	fn price_source_is_parsed() {
+		assert_eq!(
+			"coingecko:polkadot/usd".parse::<AnyPriceSource>().map_err(drop),
+			Ok(AnyPriceSource::CoinGecko(CoinGeckoPriceSource {
+				token_id: "polkadot".into(),
+				currency: "usd".into(),
+			})),
+		);
+		assert_eq!(
+			"json:https://prices.example/api?token=wnd#$.price".parse::<AnyPriceSource>().map_err(drop),
+			Ok(AnyPriceSource::HttpJson(HttpJsonPriceSource {
+				url: "https://prices.example/api?token=wnd".into(),
+				json_path: "$.price".into(),
+			})),
+		);
+		assert_eq!(
+			"static:1.5".parse::<AnyPriceSource>().map_err(drop),
+			Ok(AnyPriceSource::Static(StaticPriceSource(1.5))),
+		);
+		assert!("static:0".parse::<AnyPriceSource>().is_err());
+		assert!("coingecko:polkadot".parse::<AnyPriceSource>().is_err());
+		assert!("unknown:1".parse::<AnyPriceSource>().is_err());
+	}
+}
@@ -0,0 +1,400 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Exporting `tracing` spans to the OpenTelemetry collector.
+//!
+//! Relay loops, races and RPC calls are instrumented with `tracing` spans, which target is
+//! [`SPANS_TARGET`]. When the exporter is started, those spans are collected and periodically
+//! sent to the collector using OTLP/HTTP protocol with JSON encoding. Spans of other crates
+//! are ignored.
+//!
+//! Every span gets the `bridge.loop` attribute with the name of the relay loop that has
+//! started it, so spans of different loops may be correlated by their attributes (e.g.
+//! `nonces_begin` and `nonces_end`).
+
+use std::{
+	cell::RefCell,
+	collections::{hash_map::RandomState, HashMap},
+	hash::{BuildHasher, Hasher},
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{
+	field::{Field, Visit},
+	span::{Attributes, Id, Record},
+	Event, Metadata, Subscriber,
+};
+
+/// Target of all spans, exported by the relay.
+pub const SPANS_TARGET: &str = "bridge";
+/// Interval between exports.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+/// Maximal number of finished spans that are waiting for export. When the collector is
+/// unavailable, excess spans are dropped.
+const MAX_PENDING_SPANS: usize = 16 * 1024;
+
+/// OpenTelemetry exporter parameters.
+#[derive(Debug, Clone)]
+pub struct OtlpParams {
+	/// OTLP/HTTP endpoint of the collector (e.g. `http://otel-collector:4318`).
+	pub endpoint: String,
+	/// Value of the `service.name` resource attribute.
+	pub service_name: String,
+}
+
+/// Start collecting relay spans and exporting them to the collector.
+pub fn init_otlp_exporter(params: OtlpParams) -> Result<(), String> {
+	let finished_spans = Arc::new(Mutex::new(Vec::new()));
+	let subscriber = SpansCollector {
+		random: RandomState::new(),
+		next_id: AtomicU64::new(1),
+		spans: Mutex::new(HashMap::new()),
+		finished_spans: finished_spans.clone(),
+	};
+	tracing::subscriber::set_global_default(subscriber).map_err(|e| e.to_string())?;
+
+	async_std::task::spawn(async move {
+		loop {
+			async_std::task::sleep(EXPORT_INTERVAL).await;
+
+			let spans = std::mem::take(&mut *lock(&finished_spans));
+			if spans.is_empty() {
+				continue
+			}
+			if let Err(err) = export(&params, &spans).await {
+				log::debug!(
+					target: "bridge",
+					"Failed to export {} spans to {}: {:?}",
+					spans.len(),
+					params.endpoint,
+					err,
+				);
+			}
+		}
+	});
+
+	Ok(())
+}
+
+/// Attribute value.
+#[derive(Debug, Clone, PartialEq)]
+enum AttributeValue {
+	String(String),
+	Int(i64),
+	Bool(bool),
+}
+
+/// Span that has been finished and is waiting for export.
+#[derive(Debug, Clone)]
+struct FinishedSpan {
+	trace_id: u128,
+	span_id: u64,
+	parent_span_id: Option<u64>,
+	name: &'static str,
+	start: SystemTime,
+	end: SystemTime,
+	attributes: Vec<(String, AttributeValue)>,
+}
+
+/// Span that is still active.
+struct ActiveSpan {
+	/// Number of `Id` handles of the span.
+	ref_count: usize,
+	/// Span data.
+	span: FinishedSpan,
+}
+
+thread_local! {
+	/// Stack of spans, entered at the current thread.
+	static CURRENT_SPANS: RefCell<Vec<Id>> = RefCell::new(Vec::new());
+}
+
+/// Subscriber that collects relay spans.
+struct SpansCollector {
+	random: RandomState,
+	next_id: AtomicU64,
+	spans: Mutex<HashMap<u64, ActiveSpan>>,
+	finished_spans: Arc<Mutex<Vec<FinishedSpan>>>,
+}
+
+impl SpansCollector {
+	/// Returns random number.
+	fn random_u64(&self, seed: u64) -> u64 {
+		let mut hasher = self.random.build_hasher();
+		hasher.write_u64(seed);
+		hasher.finish()
+	}
+}
+
+impl Subscriber for SpansCollector {
+	fn enabled(&self, metadata: &Metadata) -> bool {
+		metadata.is_span() && metadata.target() == SPANS_TARGET
+	}
+
+	fn new_span(&self, attrs: &Attributes) -> Id {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let parent = if attrs.is_root() {
+			None
+		} else if let Some(parent) = attrs.parent() {
+			Some(parent.clone())
+		} else if attrs.is_contextual() {
+			CURRENT_SPANS.with(|spans| spans.borrow().last().cloned())
+		} else {
+			None
+		};
+
+		let mut spans = lock(&self.spans);
+		let parent = parent.and_then(|parent| spans.get(&parent.into_u64()));
+		let mut span = FinishedSpan {
+			trace_id: parent.map(|parent| parent.span.trace_id).unwrap_or_else(|| {
+				((self.random_u64(id) as u128) << 64) | self.random_u64(!id) as u128
+			}),
+			span_id: self.random_u64(id.rotate_left(32)),
+			parent_span_id: parent.map(|parent| parent.span.span_id),
+			name: attrs.metadata().name(),
+			start: SystemTime::now(),
+			end: SystemTime::now(),
+			attributes: Vec::new(),
+		};
+		if let Some(loop_name) = crate::initialize::loop_name() {
+			span.attributes.push(("bridge.loop".into(), AttributeValue::String(loop_name)));
+		}
+		attrs.record(&mut AttributesVisitor(&mut span.attributes));
+		spans.insert(id, ActiveSpan { ref_count: 1, span });
+
+		Id::from_u64(id)
+	}
+
+	fn record(&self, span: &Id, values: &Record) {
+		if let Some(span) = lock(&self.spans).get_mut(&span.into_u64()) {
+			values.record(&mut AttributesVisitor(&mut span.span.attributes));
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+	fn event(&self, _event: &Event) {}
+
+	fn enter(&self, span: &Id) {
+		CURRENT_SPANS.with(|spans| spans.borrow_mut().push(span.clone()));
+	}
+
+	fn exit(&self, span: &Id) {
+		CURRENT_SPANS.with(|spans| {
+			let mut spans = spans.borrow_mut();
+			if let Some(position) = spans.iter().rposition(|entered| entered == span) {
+				spans.remove(position);
+			}
+		});
+	}
+
+	fn clone_span(&self, span: &Id) -> Id {
+		if let Some(span) = lock(&self.spans).get_mut(&span.into_u64()) {
+			span.ref_count += 1;
+		}
+		span.clone()
+	}
+
+	fn try_close(&self, span: Id) -> bool {
+		let mut spans = lock(&self.spans);
+		let is_closed = match spans.get_mut(&span.into_u64()) {
+			Some(active_span) => {
+				active_span.ref_count -= 1;
+				active_span.ref_count == 0
+			},
+			None => false,
+		};
+		if !is_closed {
+			return false
+		}
+
+		if let Some(mut active_span) = spans.remove(&span.into_u64()) {
+			active_span.span.end = SystemTime::now();
+			let mut finished_spans = lock(&self.finished_spans);
+			if finished_spans.len() < MAX_PENDING_SPANS {
+				finished_spans.push(active_span.span);
+			}
+		}
+		true
+	}
+}
+
+/// Visitor that collects span fields as attributes.
+struct AttributesVisitor<'a>(&'a mut Vec<(String, AttributeValue)>);
+
+impl<'a> AttributesVisitor<'a> {
+	fn insert(&mut self, field: &Field, value: AttributeValue) {
+		match self.0.iter_mut().find(|(name, _)| name == field.name()) {
+			Some((_, existing_value)) => *existing_value = value,
+			None => self.0.push((field.name().into(), value)),
+		}
+	}
+}
+
+impl<'a> Visit for AttributesVisitor<'a> {
+	fn record_i64(&mut self, field: &Field, value: i64) {
+		self.insert(field, AttributeValue::Int(value));
+	}
+
+	fn record_u64(&mut self, field: &Field, value: u64) {
+		match i64::try_from(value) {
+			Ok(value) => self.insert(field, AttributeValue::Int(value)),
+			Err(_) => self.insert(field, AttributeValue::String(value.to_string())),
+		}
+	}
+
+	fn record_bool(&mut self, field: &Field, value: bool) {
+		self.insert(field, AttributeValue::Bool(value));
+	}
+
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.insert(field, AttributeValue::String(value.into()));
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+		self.insert(field, AttributeValue::String(format!("{value:?}")));
+	}
+}
+
+/// Send spans to the collector.
+async fn export(params: &OtlpParams, spans: &[FinishedSpan]) -> anyhow::Result<()> {
+	use isahc::{HttpClient, Request};
+
+	let request = Request::post(format!("{}/v1/traces", params.endpoint.trim_end_matches('/')))
+		.header("Content-Type", "application/json")
+		.body(encode_spans(&params.service_name, spans).to_string())?;
+	let response = HttpClient::new()?.send_async(request).await?;
+	if !response.status().is_success() {
+		return Err(anyhow::format_err!("Collector has responded with {}", response.status()))
+	}
+
+	Ok(())
+}
+
+/// Encode spans as OTLP `ExportTraceServiceRequest` JSON.
+fn encode_spans(service_name: &str, spans: &[FinishedSpan]) -> serde_json::Value {
+	fn unix_nanos(time: SystemTime) -> String {
+		time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string()
+	}
+
+	fn attribute(key: &str, value: &AttributeValue) -> serde_json::Value {
+		let value = match value {
+			AttributeValue::String(value) => serde_json::json!({ "stringValue": value }),
+			AttributeValue::Int(value) => serde_json::json!({ "intValue": value.to_string() }),
+			AttributeValue::Bool(value) => serde_json::json!({ "boolValue": value }),
+		};
+		serde_json::json!({ "key": key, "value": value })
+	}
+
+	let spans = spans
+		.iter()
+		.map(|span| {
+			let mut encoded = serde_json::json!({
+				"traceId": format!("{:032x}", span.trace_id),
+				"spanId": format!("{:016x}", span.span_id),
+				"name": span.name,
+				// SPAN_KIND_INTERNAL
+				"kind": 1,
+				"startTimeUnixNano": unix_nanos(span.start),
+				"endTimeUnixNano": unix_nanos(span.end),
+				"attributes": span
+					.attributes
+					.iter()
+					.map(|(key, value)| attribute(key, value))
+					.collect::<Vec<_>>(),
+			});
+			if let Some(parent_span_id) = span.parent_span_id {
+				encoded["parentSpanId"] = format!("{parent_span_id:016x}").into();
+			}
+			encoded
+		})
+		.collect::<Vec<_>>();
+
+	serde_json::json!({
+		"resourceSpans": [{
+			"resource": {
+				"attributes": [
+					attribute("service.name", &AttributeValue::String(service_name.into())),
+				],
+			},
+			"scopeSpans": [{
+				"scope": { "name": "substrate-relay" },
+				"spans": spans,
+			}],
+		}],
+	})
+}
+
+/// Lock the mutex, ignoring poisoning.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<T> {
+	// the lock is never held across panics, so poisoning is not expected
+	mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn child_span_shares_trace_with_parent() {
+		let finished_spans = Arc::new(Mutex::new(Vec::new()));
+		let subscriber = SpansCollector {
+			random: RandomState::new(),
+			next_id: AtomicU64::new(1),
+			spans: Mutex::new(HashMap::new()),
+			finished_spans: finished_spans.clone(),
+		};
+
+		tracing::subscriber::with_default(subscriber, || {
+			let parent = tracing::info_span!(
+				target: "bridge",
+				"messages_race",
+				nonces_begin = 1u64,
+				status = tracing::field::Empty,
+			);
+			parent.in_scope(|| {
+				let _child = tracing::info_span!(target: "bridge", "generate_proof").entered();
+				let _ignored = tracing::info_span!(target: "other", "ignored").entered();
+			});
+			parent.record("status", "finalized");
+		});
+
+		let spans = lock(&finished_spans).clone();
+		assert_eq!(spans.len(), 2);
+		let (child, parent) = (&spans[0], &spans[1]);
+		assert_eq!(child.name, "generate_proof");
+		assert_eq!(parent.name, "messages_race");
+		assert_eq!(child.trace_id, parent.trace_id);
+		assert_eq!(child.parent_span_id, Some(parent.span_id));
+		assert_eq!(parent.parent_span_id, None);
+		assert_eq!(
+			parent.attributes,
+			vec![
+				("nonces_begin".into(), AttributeValue::Int(1)),
+				("status".into(), AttributeValue::String("finalized".into())),
+			],
+		);
+
+		let encoded = encode_spans("relay", &spans);
+		assert_eq!(
+			encoded["resourceSpans"][0]["scopeSpans"][0]["spans"][1]["name"],
+			"messages_race"
+		);
+	}
+}
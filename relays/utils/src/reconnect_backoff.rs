@@ -0,0 +1,242 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Policies of delays between relay loop reconnect attempts.
+//!
+//! Policy may be selected by the loop itself (see `Loop::reconnect_backoff`) or configured for
+//! all loops of the process (or for loops with given name prefix) using the
+//! `configure_reconnect_backoffs` call. Configured policy overrides the policy selected by the
+//! loop.
+
+use crate::relay_loop::RECONNECT_DELAY;
+
+use std::{
+	str::FromStr,
+	sync::{Mutex, OnceLock},
+	time::Duration,
+};
+
+/// Policy of delays between reconnect attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconnectBackoff {
+	/// The same delay is used before every attempt.
+	Constant(Duration),
+	/// The delay is doubled after every failed attempt.
+	Exponential {
+		/// Delay before the first attempt.
+		initial: Duration,
+		/// Maximal delay.
+		max: Duration,
+	},
+	/// Delays are growing as Fibonacci numbers (`initial`, `initial`, `2 * initial`,
+	/// `3 * initial`, `5 * initial`, ...).
+	Fibonacci {
+		/// Delay before the first attempt.
+		initial: Duration,
+		/// Maximal delay.
+		max: Duration,
+	},
+}
+
+impl Default for ReconnectBackoff {
+	fn default() -> Self {
+		ReconnectBackoff::Constant(RECONNECT_DELAY)
+	}
+}
+
+impl ReconnectBackoff {
+	/// Returns delay before reconnect attempt with given (zero-based) index.
+	pub fn delay(&self, attempt: u32) -> Duration {
+		match *self {
+			ReconnectBackoff::Constant(delay) => delay,
+			ReconnectBackoff::Exponential { initial, max } => initial
+				.checked_mul(2u32.checked_pow(attempt).unwrap_or(u32::MAX))
+				.unwrap_or(max)
+				.min(max),
+			ReconnectBackoff::Fibonacci { initial, max } => {
+				let (mut current, mut next) = (1u32, 1u32);
+				// all Fibonacci numbers after 47th are saturated to `u32::MAX` anyway
+				for _ in 0..attempt.min(64) {
+					(current, next) = (next, current.saturating_add(next));
+				}
+				initial.checked_mul(current).unwrap_or(max).min(max)
+			},
+		}
+	}
+}
+
+impl FromStr for ReconnectBackoff {
+	type Err = String;
+
+	/// Parses policy from one of following strings (all durations are in seconds):
+	/// `constant:<delay>`, `exponential:<initial>:<max>` or `fibonacci:<initial>:<max>`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let parse_secs = |secs: &str| {
+			secs.parse::<u64>()
+				.map(Duration::from_secs)
+				.map_err(|e| format!("Invalid reconnect backoff delay {secs:?}: {e}"))
+		};
+
+		let parts = s.split(':').collect::<Vec<_>>();
+		match parts.as_slice() {
+			["constant", delay] => Ok(ReconnectBackoff::Constant(parse_secs(delay)?)),
+			["exponential", initial, max] => Ok(ReconnectBackoff::Exponential {
+				initial: parse_secs(initial)?,
+				max: parse_secs(max)?,
+			}),
+			["fibonacci", initial, max] => Ok(ReconnectBackoff::Fibonacci {
+				initial: parse_secs(initial)?,
+				max: parse_secs(max)?,
+			}),
+			_ => Err(format!(
+				"Invalid reconnect backoff {s:?}. Expected `constant:<delay>`, \
+				`exponential:<initial>:<max>` or `fibonacci:<initial>:<max>`"
+			)),
+		}
+	}
+}
+
+/// Reconnect backoff policy, configured for all loops or for loops with given name prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopReconnectBackoff {
+	/// Prefix of the loop name. If `None`, the policy is used by all loops.
+	pub loop_name_prefix: Option<String>,
+	/// Reconnect backoff policy.
+	pub backoff: ReconnectBackoff,
+}
+
+impl FromStr for LoopReconnectBackoff {
+	type Err = String;
+
+	/// Parses policy from the `[<loop-name-prefix>=]<policy>` string.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.split_once('=') {
+			Some((loop_name_prefix, backoff)) => Ok(LoopReconnectBackoff {
+				loop_name_prefix: Some(loop_name_prefix.into()),
+				backoff: backoff.parse()?,
+			}),
+			None => Ok(LoopReconnectBackoff { loop_name_prefix: None, backoff: s.parse()? }),
+		}
+	}
+}
+
+/// Reconnect backoff policies, configured for the process.
+static CONFIGURED_BACKOFFS: OnceLock<Mutex<Vec<LoopReconnectBackoff>>> = OnceLock::new();
+
+/// Configure reconnect backoff policies for relay loops of the process.
+pub fn configure_reconnect_backoffs(backoffs: Vec<LoopReconnectBackoff>) {
+	*CONFIGURED_BACKOFFS
+		.get_or_init(Default::default)
+		.lock()
+		.unwrap_or_else(|e| e.into_inner()) = backoffs;
+}
+
+/// Returns reconnect backoff policy, configured for the loop with given name.
+///
+/// If there are several policies configured for the loop, the policy with the longest name
+/// prefix is selected.
+pub fn configured_reconnect_backoff(loop_name: &str) -> Option<ReconnectBackoff> {
+	let backoffs = CONFIGURED_BACKOFFS.get()?.lock().unwrap_or_else(|e| e.into_inner());
+	select_backoff(&backoffs, loop_name)
+}
+
+/// Select the most specific policy for the loop with given name.
+fn select_backoff(backoffs: &[LoopReconnectBackoff], loop_name: &str) -> Option<ReconnectBackoff> {
+	backoffs
+		.iter()
+		.filter(|backoff| match backoff.loop_name_prefix {
+			Some(ref prefix) => loop_name.starts_with(prefix.as_str()),
+			None => true,
+		})
+		.max_by_key(|backoff| backoff.loop_name_prefix.as_ref().map(|prefix| prefix.len() + 1))
+		.map(|backoff| backoff.backoff)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn delays_are_computed() {
+		let secs = Duration::from_secs;
+
+		let constant = ReconnectBackoff::Constant(secs(10));
+		assert_eq!((0..4).map(|a| constant.delay(a)).collect::<Vec<_>>(), vec![secs(10); 4]);
+
+		let exponential = ReconnectBackoff::Exponential { initial: secs(1), max: secs(10) };
+		assert_eq!(
+			(0..6).map(|a| exponential.delay(a)).collect::<Vec<_>>(),
+			vec![secs(1), secs(2), secs(4), secs(8), secs(10), secs(10)],
+		);
+		assert_eq!(exponential.delay(u32::MAX), secs(10));
+
+		let fibonacci = ReconnectBackoff::Fibonacci { initial: secs(2), max: secs(15) };
+		assert_eq!(
+			(0..7).map(|a| fibonacci.delay(a)).collect::<Vec<_>>(),
+			vec![secs(2), secs(2), secs(4), secs(6), secs(10), secs(15), secs(15)],
+		);
+		assert_eq!(fibonacci.delay(u32::MAX), secs(15));
+	}
+
+	#[test]
+	fn backoffs_are_parsed() {
+		let secs = Duration::from_secs;
+
+		assert_eq!("constant:5".parse(), Ok(ReconnectBackoff::Constant(secs(5))));
+		assert_eq!(
+			"exponential:1:300".parse(),
+			Ok(ReconnectBackoff::Exponential { initial: secs(1), max: secs(300) }),
+		);
+		assert_eq!(
+			"fibonacci:2:60".parse(),
+			Ok(ReconnectBackoff::Fibonacci { initial: secs(2), max: secs(60) }),
+		);
+		assert!("exponential:1".parse::<ReconnectBackoff>().is_err());
+		assert!("linear:1:2".parse::<ReconnectBackoff>().is_err());
+
+		assert_eq!(
+			"Millau_to_Rialto_Sync=constant:1".parse(),
+			Ok(LoopReconnectBackoff {
+				loop_name_prefix: Some("Millau_to_Rialto_Sync".into()),
+				backoff: ReconnectBackoff::Constant(secs(1)),
+			}),
+		);
+	}
+
+	#[test]
+	fn most_specific_backoff_is_selected() {
+		let backoffs = vec![
+			"Millau_to_Rialto=constant:2".parse().unwrap(),
+			"constant:1".parse().unwrap(),
+			"Millau_to_Rialto_Sync=constant:3".parse().unwrap(),
+		];
+		let secs = Duration::from_secs;
+
+		assert_eq!(
+			select_backoff(&backoffs, "Rialto_to_Millau_Sync"),
+			Some(ReconnectBackoff::Constant(secs(1))),
+		);
+		assert_eq!(
+			select_backoff(&backoffs, "Millau_to_Rialto_MessageLane_00000000"),
+			Some(ReconnectBackoff::Constant(secs(2))),
+		);
+		assert_eq!(
+			select_backoff(&backoffs, "Millau_to_Rialto_Sync"),
+			Some(ReconnectBackoff::Constant(secs(3))),
+		);
+		assert_eq!(select_backoff(&backoffs[..1], "Rialto_to_Millau_Sync"), None);
+	}
+}
@@ -15,14 +15,16 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
+	clock::{Clock, SystemClock},
 	error::Error,
-	metrics::{Metric, MetricsAddress, MetricsParams},
+	metrics::{endpoint, Metric, MetricsAddress, MetricsParams, PushGatewayParams},
+	reconnect_backoff::{configured_reconnect_backoff, ReconnectBackoff},
 	FailedClient, MaybeConnectionError,
 };
 
 use async_trait::async_trait;
-use std::{fmt::Debug, future::Future, net::SocketAddr, time::Duration};
-use substrate_prometheus_endpoint::{init_prometheus, Registry};
+use std::{fmt::Debug, future::Future, net::SocketAddr, sync::Arc, time::Duration};
+use substrate_prometheus_endpoint::Registry;
 
 /// Default pause between reconnect attempts.
 pub const RECONNECT_DELAY: Duration = Duration::from_secs(10);
@@ -48,7 +50,13 @@ impl Client for () {
 
 /// Returns generic loop that may be customized and started.
 pub fn relay_loop<SC, TC>(source_client: SC, target_client: TC) -> Loop<SC, TC, ()> {
-	Loop { reconnect_delay: RECONNECT_DELAY, source_client, target_client, loop_metric: None }
+	Loop {
+		reconnect_backoff: ReconnectBackoff::default(),
+		clock: Arc::new(SystemClock),
+		source_client,
+		target_client,
+		loop_metric: None,
+	}
 }
 
 /// Returns generic relay loop metrics that may be customized and used in one or several relay
@@ -56,12 +64,14 @@ pub fn relay_loop<SC, TC>(source_client: SC, target_client: TC) -> Loop<SC, TC,
 pub fn relay_metrics(params: MetricsParams) -> LoopMetrics<(), (), ()> {
 	LoopMetrics {
 		relay_loop: Loop {
-			reconnect_delay: RECONNECT_DELAY,
+			reconnect_backoff: ReconnectBackoff::default(),
+			clock: Arc::new(SystemClock),
 			source_client: (),
 			target_client: (),
 			loop_metric: None,
 		},
 		address: params.address,
+		push: params.push,
 		registry: params.registry,
 		loop_metric: None,
 	}
@@ -69,7 +79,8 @@ pub fn relay_metrics(params: MetricsParams) -> LoopMetrics<(), (), ()> {
 
 /// Generic relay loop.
 pub struct Loop<SC, TC, LM> {
-	reconnect_delay: Duration,
+	reconnect_backoff: ReconnectBackoff,
+	clock: Arc<dyn Clock>,
 	source_client: SC,
 	target_client: TC,
 	loop_metric: Option<LM>,
@@ -79,6 +90,7 @@ pub struct Loop<SC, TC, LM> {
 pub struct LoopMetrics<SC, TC, LM> {
 	relay_loop: Loop<SC, TC, ()>,
 	address: Option<MetricsAddress>,
+	push: Option<PushGatewayParams>,
 	registry: Registry,
 	loop_metric: Option<LM>,
 }
@@ -86,8 +98,24 @@ pub struct LoopMetrics<SC, TC, LM> {
 impl<SC, TC, LM> Loop<SC, TC, LM> {
 	/// Customize delay between reconnect attempts.
 	#[must_use]
-	pub fn reconnect_delay(mut self, reconnect_delay: Duration) -> Self {
-		self.reconnect_delay = reconnect_delay;
+	pub fn reconnect_delay(self, reconnect_delay: Duration) -> Self {
+		self.reconnect_backoff(ReconnectBackoff::Constant(reconnect_delay))
+	}
+
+	/// Customize policy of delays between reconnect attempts.
+	///
+	/// The policy, configured for the loop using `configure_reconnect_backoffs` overrides this
+	/// policy.
+	#[must_use]
+	pub fn reconnect_backoff(mut self, reconnect_backoff: ReconnectBackoff) -> Self {
+		self.reconnect_backoff = reconnect_backoff;
+		self
+	}
+
+	/// Customize clock that is used by the loop. By default, the system clock is used.
+	#[must_use]
+	pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+		self.clock = clock;
 		self
 	}
 
@@ -95,12 +123,14 @@ impl<SC, TC, LM> Loop<SC, TC, LM> {
 	pub fn with_metrics(self, params: MetricsParams) -> LoopMetrics<SC, TC, ()> {
 		LoopMetrics {
 			relay_loop: Loop {
-				reconnect_delay: self.reconnect_delay,
+				reconnect_backoff: self.reconnect_backoff,
+				clock: self.clock,
 				source_client: self.source_client,
 				target_client: self.target_client,
 				loop_metric: None,
 			},
 			address: params.address,
+			push: params.push,
 			registry: params.registry,
 			loop_metric: None,
 		}
@@ -119,8 +149,11 @@ impl<SC, TC, LM> Loop<SC, TC, LM> {
 		TC: 'static + Client,
 		LM: 'static + Send + Clone,
 	{
+		let reconnect_backoff =
+			configured_reconnect_backoff(&loop_name).unwrap_or(self.reconnect_backoff);
 		let run_loop_task = async move {
-			crate::initialize::initialize_loop(loop_name);
+			crate::health::note_loop_started(&loop_name);
+			crate::initialize::initialize_loop(loop_name.clone());
 
 			loop {
 				let loop_metric = self.loop_metric.clone();
@@ -133,13 +166,16 @@ impl<SC, TC, LM> Loop<SC, TC, LM> {
 					Err(failed_client) => {
 						log::debug!(target: "bridge", "Restarting relay loop");
 
-						reconnect_failed_client(
+						crate::health::note_loop_disconnected(&loop_name, failed_client);
+						reconnect_failed_client_with_clock(
+							&*self.clock,
 							failed_client,
-							self.reconnect_delay,
+							&reconnect_backoff,
 							&mut self.source_client,
 							&mut self.target_client,
 						)
-						.await
+						.await;
+						crate::health::note_loop_reconnected(&loop_name);
 					},
 				}
 			}
@@ -164,6 +200,7 @@ impl<SC, TC, LM> LoopMetrics<SC, TC, LM> {
 		Ok(LoopMetrics {
 			relay_loop: self.relay_loop,
 			address: self.address,
+			push: self.push,
 			registry: self.registry,
 			loop_metric: Some(metric),
 		})
@@ -171,13 +208,19 @@ impl<SC, TC, LM> LoopMetrics<SC, TC, LM> {
 
 	/// Convert into `MetricsParams` structure so that metrics registry may be extended later.
 	pub fn into_params(self) -> MetricsParams {
-		MetricsParams { address: self.address, registry: self.registry }
+		MetricsParams { address: self.address, push: self.push, registry: self.registry }
 	}
 
-	/// Expose metrics using address passed at creation.
+	/// Expose metrics using address passed at creation. Health and control requests are served
+	/// at the same address.
 	///
-	/// If passed `address` is `None`, metrics are not exposed.
+	/// If passed `address` is `None`, metrics are not exposed. If Pushgateway parameters are
+	/// passed, metrics are also periodically pushed to the Pushgateway.
 	pub async fn expose(self) -> Result<Loop<SC, TC, LM>, Error> {
+		crate::metrics::register_iteration_failures(&self.registry)?;
+		if let Some(push) = self.push {
+			crate::metrics::push_gateway::spawn(push, self.registry.clone());
+		}
 		if let Some(address) = self.address {
 			let socket_addr = SocketAddr::new(
 				address
@@ -186,40 +229,28 @@ impl<SC, TC, LM> LoopMetrics<SC, TC, LM> {
 					.map_err(|err| Error::ExposingMetricsInvalidHost(address.host.clone(), err))?,
 				address.port,
 			);
+			let tls = address.tls.as_ref().map(endpoint::load_tls_acceptor).transpose()?;
+			let auth = address.auth;
 
 			let registry = self.registry;
 			async_std::task::spawn(async move {
-				let runtime =
-					match tokio::runtime::Builder::new_current_thread().enable_all().build() {
-						Ok(runtime) => runtime,
-						Err(err) => {
-							log::trace!(
-								target: "bridge-metrics",
-								"Failed to create tokio runtime. Prometheus metrics are not available: {:?}",
-								err,
-							);
-							return
-						},
-					};
-
-				runtime.block_on(async move {
-					log::trace!(
-						target: "bridge-metrics",
-						"Starting prometheus endpoint at: {:?}",
-						socket_addr,
-					);
-					let result = init_prometheus(socket_addr, registry).await;
-					log::trace!(
-						target: "bridge-metrics",
-						"Prometheus endpoint has exited with result: {:?}",
-						result,
-					);
-				});
+				log::trace!(
+					target: "bridge-metrics",
+					"Starting relay endpoint at: {:?}",
+					socket_addr,
+				);
+				let result = endpoint::serve(socket_addr, registry, auth, tls).await;
+				log::trace!(
+					target: "bridge-metrics",
+					"Relay endpoint has exited with result: {:?}",
+					result,
+				);
 			});
 		}
 
 		Ok(Loop {
-			reconnect_delay: self.relay_loop.reconnect_delay,
+			reconnect_backoff: self.relay_loop.reconnect_backoff,
+			clock: self.relay_loop.clock,
 			source_client: self.relay_loop.source_client,
 			target_client: self.relay_loop.target_client,
 			loop_metric: self.loop_metric,
@@ -234,8 +265,29 @@ pub async fn reconnect_failed_client(
 	source_client: &mut impl Client,
 	target_client: &mut impl Client,
 ) {
+	reconnect_failed_client_with_clock(
+		&SystemClock,
+		failed_client,
+		&ReconnectBackoff::Constant(reconnect_delay),
+		source_client,
+		target_client,
+	)
+	.await
+}
+
+/// Deal with the client who has returned connection error, using given clock and backoff policy
+/// to wait between reconnect attempts.
+pub async fn reconnect_failed_client_with_clock(
+	clock: &dyn Clock,
+	failed_client: FailedClient,
+	reconnect_backoff: &ReconnectBackoff,
+	source_client: &mut impl Client,
+	target_client: &mut impl Client,
+) {
+	let mut attempt = 0;
 	loop {
-		async_std::task::sleep(reconnect_delay).await;
+		clock.sleep(reconnect_backoff.delay(attempt)).await;
+		attempt = attempt.saturating_add(1);
 		if failed_client == FailedClient::Both || failed_client == FailedClient::Source {
 			match source_client.reconnect().await {
 				Ok(()) => (),
@@ -243,7 +295,7 @@ pub async fn reconnect_failed_client(
 					log::warn!(
 						target: "bridge",
 						"Failed to reconnect to source client. Going to retry in {}s: {:?}",
-						reconnect_delay.as_secs(),
+						reconnect_backoff.delay(attempt).as_secs(),
 						error,
 					);
 					continue
@@ -257,7 +309,7 @@ pub async fn reconnect_failed_client(
 					log::warn!(
 						target: "bridge",
 						"Failed to reconnect to target client. Going to retry in {}s: {:?}",
-						reconnect_delay.as_secs(),
+						reconnect_backoff.delay(attempt).as_secs(),
 						error,
 					);
 					continue
@@ -268,3 +320,66 @@ pub async fn reconnect_failed_client(
 		break
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{clock::TestClock, StringifiedMaybeConnectionError};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	#[derive(Clone, Default)]
+	struct TestClient {
+		reconnects: Arc<AtomicUsize>,
+	}
+
+	#[async_trait]
+	impl Client for TestClient {
+		type Error = StringifiedMaybeConnectionError;
+
+		async fn reconnect(&mut self) -> Result<(), Self::Error> {
+			self.reconnects.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn failed_client_is_reconnected_after_reconnect_delay() {
+		let clock = TestClock::new();
+		let source_client = TestClient::default();
+		let source_reconnects = source_client.reconnects.clone();
+		let runs = Arc::new(AtomicUsize::new(0));
+
+		let relay_loop = {
+			let runs = runs.clone();
+			relay_loop(source_client, ()).clock(Arc::new(clock.clone())).run(
+				"test".into(),
+				move |_, _, _| {
+					let run = runs.fetch_add(1, Ordering::SeqCst);
+					async move {
+						if run == 0 {
+							Err(FailedClient::Source)
+						} else {
+							Ok(())
+						}
+					}
+				},
+			)
+		};
+		let relay_loop = async_std::task::spawn(relay_loop);
+
+		// the loop has failed and waits before reconnecting
+		async_std::task::block_on(clock.wait_for_sleepers(1));
+		assert_eq!(runs.load(Ordering::SeqCst), 1);
+		assert_eq!(source_reconnects.load(Ordering::SeqCst), 0);
+
+		// the loop is not woken up before the reconnect delay has passed
+		clock.advance(RECONNECT_DELAY - Duration::from_secs(1));
+		assert_eq!(clock.sleepers(), 1);
+
+		// when the delay has passed, client is reconnected and the loop is restarted
+		clock.advance(Duration::from_secs(1));
+		async_std::task::block_on(relay_loop).unwrap();
+		assert_eq!(runs.load(Ordering::SeqCst), 2);
+		assert_eq!(source_reconnects.load(Ordering::SeqCst), 1);
+	}
+}
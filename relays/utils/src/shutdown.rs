@@ -0,0 +1,122 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Graceful shutdown of relay loops.
+//!
+//! Once shutdown is requested, relay loops stop starting new work and wait until transactions
+//! that they have already submitted are finalized (or lost). Relay loops are restoring their
+//! state from the state of both chains on restart, so once in-flight transactions are drained,
+//! there's nothing else that needs to be persisted.
+
+use async_std::channel::{Receiver, Sender};
+use futures::Future;
+use std::{sync::OnceLock, time::Duration};
+
+/// Maximal time that every relay loop waits for its in-flight transactions after shutdown has
+/// been requested.
+pub const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Shutdown channel. No messages are ever sent over it - it is closed when shutdown is requested.
+static SHUTDOWN_CHANNEL: OnceLock<(Sender<()>, Receiver<()>)> = OnceLock::new();
+
+fn shutdown_channel() -> &'static (Sender<()>, Receiver<()>) {
+	SHUTDOWN_CHANNEL.get_or_init(|| async_std::channel::bounded(1))
+}
+
+/// Request graceful shutdown of all relay loops, running in this process.
+pub fn request_shutdown() {
+	shutdown_channel().0.close();
+}
+
+/// Returns true if graceful shutdown has been requested.
+pub fn is_shutdown_requested() -> bool {
+	shutdown_channel().0.is_closed()
+}
+
+/// Returns future that resolves when graceful shutdown is requested.
+pub async fn shutdown_requested() {
+	// `recv` only returns error when channel is closed
+	let _ = shutdown_channel().1.recv().await;
+}
+
+/// Wait (with `SHUTDOWN_DRAIN_TIMEOUT` timeout) until in-flight transaction is finalized or lost.
+///
+/// Returns `None` if we have not managed to track transaction status in time.
+pub async fn drain_in_flight_transaction<T>(
+	loop_name: &str,
+	in_flight_transaction: impl Future<Output = T>,
+) -> Option<T> {
+	drain_in_flight_transaction_with_timeout(
+		loop_name,
+		in_flight_transaction,
+		SHUTDOWN_DRAIN_TIMEOUT,
+	)
+	.await
+}
+
+async fn drain_in_flight_transaction_with_timeout<T>(
+	loop_name: &str,
+	in_flight_transaction: impl Future<Output = T>,
+	timeout: Duration,
+) -> Option<T> {
+	log::info!(
+		target: "bridge",
+		"{} is shutting down. Waiting for in-flight transaction status",
+		loop_name,
+	);
+
+	match async_std::future::timeout(timeout, in_flight_transaction).await {
+		Ok(result) => Some(result),
+		Err(_) => {
+			log::warn!(
+				target: "bridge",
+				"{} is shutting down. Failed to track in-flight transaction status in {}s",
+				loop_name,
+				timeout.as_secs(),
+			);
+			None
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn drain_returns_transaction_status() {
+		assert_eq!(
+			async_std::task::block_on(drain_in_flight_transaction_with_timeout(
+				"Test",
+				async { 42 },
+				Duration::from_secs(1),
+			)),
+			Some(42),
+		);
+	}
+
+	#[test]
+	fn drain_stops_waiting_after_timeout() {
+		assert_eq!(
+			async_std::task::block_on(drain_in_flight_transaction_with_timeout(
+				"Test",
+				futures::future::pending::<()>(),
+				Duration::from_millis(10),
+			)),
+			None,
+		);
+	}
+}
@@ -0,0 +1,56 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Utilities for deterministic simulation tests of relay loops.
+//!
+//! Loops are driven through scripted scenarios by mocked clients. All randomness comes from
+//! the seeded `TestRng` and time is measured by the `crate::clock::TestClock`, so every
+//! scenario is reproducible given its seed.
+
+/// Deterministic pseudo-random generator (xorshift), that drives scripted loop scenarios.
+#[derive(Clone, Debug)]
+pub struct TestRng(u64);
+
+impl TestRng {
+	/// Create generator with given seed.
+	pub fn new(seed: u64) -> Self {
+		TestRng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+	}
+
+	/// Returns true with `1/n` probability.
+	pub fn one_in(&mut self, n: u64) -> bool {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0 % n == 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_rng_is_deterministic() {
+		let sample = |seed| {
+			let mut rng = TestRng::new(seed);
+			(0..64).map(|_| rng.one_in(2)).collect::<Vec<_>>()
+		};
+
+		assert_eq!(sample(42), sample(42));
+		assert_ne!(sample(42), sample(43));
+	}
+}
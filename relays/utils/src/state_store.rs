@@ -0,0 +1,271 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent state of relay loops, running in this process.
+//!
+//! If the state database is opened, relay loops are saving their latest state there: finality
+//! loops are saving the best submitted header and message races are saving the range of nonces
+//! that they have submitted. After restart, the loop resumes from the saved state. It doesn't
+//! submit the same headers or messages again until the in-flight transaction is either included
+//! into the target chain or the transaction stall timeout expires. Everything else is still
+//! read from the chains.
+//!
+//! The state of every loop is saved under its own key, prefixed with the loop name. So multiple
+//! loops may share the same database. The whole database may be exported to the JSON object
+//! (keyed by the same keys) and imported back, e.g. to move the relay to another host.
+
+use crate::{error::Error, initialize::loop_name};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+	collections::BTreeMap,
+	path::Path,
+	sync::{Mutex, OnceLock},
+};
+
+/// Storage of the relay loops state.
+pub trait StateStore: Send + Sync {
+	/// Read value with given key.
+	fn read(&self, key: &str) -> Result<Option<Vec<u8>>, Error>;
+	/// Write value with given key.
+	fn write(&self, key: &str, value: &[u8]) -> Result<(), Error>;
+	/// Read all stored keys and values.
+	fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, Error>;
+}
+
+/// State store, backed by the embedded `sled` database.
+pub struct SledStateStore(sled::Db);
+
+impl SledStateStore {
+	/// Open the database at given path, creating it if it doesn't exist.
+	pub fn open(path: &Path) -> Result<Self, Error> {
+		sled::open(path)
+			.map(SledStateStore)
+			.map_err(|e| Error::StateStore(e.to_string()))
+	}
+}
+
+impl StateStore for SledStateStore {
+	fn read(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+		self.0
+			.get(key)
+			.map(|value| value.map(|value| value.to_vec()))
+			.map_err(|e| Error::StateStore(e.to_string()))
+	}
+
+	fn write(&self, key: &str, value: &[u8]) -> Result<(), Error> {
+		self.0.insert(key, value).map_err(|e| Error::StateStore(e.to_string()))?;
+		// the state is written rarely (once per submitted transaction), so let's not lose it if
+		// the relay is killed
+		self.0.flush().map(drop).map_err(|e| Error::StateStore(e.to_string()))
+	}
+
+	fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, Error> {
+		self.0
+			.iter()
+			.map(|entry| {
+				let (key, value) = entry.map_err(|e| Error::StateStore(e.to_string()))?;
+				Ok((String::from_utf8_lossy(&key).into_owned(), value.to_vec()))
+			})
+			.collect()
+	}
+}
+
+/// In-memory state store, that is used in tests.
+#[derive(Default)]
+pub struct InMemoryStateStore(Mutex<BTreeMap<String, Vec<u8>>>);
+
+impl StateStore for InMemoryStateStore {
+	fn read(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+		Ok(self.0.lock().unwrap_or_else(|e| e.into_inner()).get(key).cloned())
+	}
+
+	fn write(&self, key: &str, value: &[u8]) -> Result<(), Error> {
+		self.0
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.insert(key.into(), value.to_vec());
+		Ok(())
+	}
+
+	fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, Error> {
+		Ok(self
+			.0
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.iter()
+			.map(|(key, value)| (key.clone(), value.clone()))
+			.collect())
+	}
+}
+
+/// State store, used by all relay loops of this process.
+static STATE_STORE: OnceLock<Box<dyn StateStore>> = OnceLock::new();
+
+/// Open the state database at given path. Relay loops, started after this call, are going to
+/// save their state there and resume from the saved state.
+pub fn open(path: &Path) -> Result<(), Error> {
+	set_state_store(Box::new(SledStateStore::open(path)?))
+}
+
+/// Use given state store for all relay loops of this process.
+pub fn set_state_store(store: Box<dyn StateStore>) -> Result<(), Error> {
+	STATE_STORE
+		.set(store)
+		.map_err(|_| Error::StateStore("state store is already opened".into()))
+}
+
+/// Returns state store, used by all relay loops of this process.
+pub fn state_store() -> Option<&'static dyn StateStore> {
+	STATE_STORE.get().map(|store| &**store)
+}
+
+/// Load state of the current relay loop with given name (e.g. race name). Returns `None` if the
+/// state database is not opened or the state has never been saved.
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+	state_store().and_then(|store| load_from(store, &state_key(name)))
+}
+
+/// Save state of the current relay loop with given name (e.g. race name). Does nothing if the
+/// state database is not opened.
+pub fn save<T: Serialize>(name: &str, state: &T) {
+	if let Some(store) = state_store() {
+		save_to(store, &state_key(name), state)
+	}
+}
+
+/// Export all states from given store as JSON object.
+pub fn export(store: &dyn StateStore) -> Result<serde_json::Value, Error> {
+	store
+		.entries()?
+		.into_iter()
+		.map(|(key, value)| {
+			serde_json::from_slice(&value)
+				.map(|value| (key.clone(), value))
+				.map_err(|e| Error::StateStore(format!("malformed state {key}: {e}")))
+		})
+		.collect::<Result<serde_json::Map<_, _>, _>>()
+		.map(serde_json::Value::Object)
+}
+
+/// Import all states from JSON object (produced by `export`) to given store. Returns number of
+/// imported states.
+pub fn import(store: &dyn StateStore, states: serde_json::Value) -> Result<usize, Error> {
+	let states = match states {
+		serde_json::Value::Object(states) => states,
+		_ => return Err(Error::StateStore("relay state must be a JSON object".into())),
+	};
+	for (key, value) in &states {
+		let value = serde_json::to_vec(value).map_err(|e| Error::StateStore(e.to_string()))?;
+		store.write(key, &value)?;
+	}
+	Ok(states.len())
+}
+
+/// Returns key of the state with given name of the current relay loop.
+fn state_key(name: &str) -> String {
+	match loop_name() {
+		Some(loop_name) => format!("{loop_name}/{name}"),
+		None => name.into(),
+	}
+}
+
+/// Load state with given key from the store.
+fn load_from<T: DeserializeOwned>(store: &dyn StateStore, key: &str) -> Option<T> {
+	let state = store
+		.read(key)
+		.and_then(|value| {
+			value
+				.map(|value| {
+					serde_json::from_slice(&value).map_err(|e| Error::StateStore(e.to_string()))
+				})
+				.transpose()
+		})
+		.map_err(|e| {
+			log::warn!(target: "bridge", "Failed to load relay state {}: {}. Ignoring", key, e);
+		})
+		.ok()
+		.flatten();
+	if state.is_some() {
+		log::info!(target: "bridge", "Resuming from the saved relay state {}", key);
+	}
+	state
+}
+
+/// Save state with given key to the store.
+fn save_to<T: Serialize>(store: &dyn StateStore, key: &str, state: &T) {
+	let result = serde_json::to_vec(state)
+		.map_err(|e| Error::StateStore(e.to_string()))
+		.and_then(|value| store.write(key, &value));
+	if let Err(e) = result {
+		log::warn!(target: "bridge", "Failed to save relay state {}: {}", key, e);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn state_is_saved_and_loaded() {
+		let store = InMemoryStateStore::default();
+		assert_eq!(load_from::<(u64, u64)>(&store, "A-to-B/race"), None);
+
+		save_to(&store, "A-to-B/race", &(10u64, 20u64));
+		save_to(&store, "B-to-A/race", &(30u64, 40u64));
+		assert_eq!(load_from::<(u64, u64)>(&store, "A-to-B/race"), Some((10, 20)));
+		assert_eq!(load_from::<(u64, u64)>(&store, "B-to-A/race"), Some((30, 40)));
+		assert_eq!(store.entries().unwrap().len(), 2);
+	}
+
+	#[test]
+	fn malformed_state_is_ignored() {
+		let store = InMemoryStateStore::default();
+		store.write("A-to-B/race", b"not a json").unwrap();
+		assert_eq!(load_from::<(u64, u64)>(&store, "A-to-B/race"), None);
+	}
+
+	#[test]
+	fn state_is_exported_and_imported() {
+		let store = InMemoryStateStore::default();
+		save_to(&store, "A-to-B/finality", &Some(42u64));
+		save_to(&store, "A-to-B/race", &(10u64, 20u64));
+
+		let exported = export(&store).unwrap();
+		assert_eq!(exported, serde_json::json!({ "A-to-B/finality": 42, "A-to-B/race": [10, 20] }));
+
+		let new_store = InMemoryStateStore::default();
+		assert_eq!(import(&new_store, exported).unwrap(), 2);
+		assert_eq!(new_store.entries().unwrap(), store.entries().unwrap());
+	}
+
+	#[test]
+	fn malformed_state_is_not_exported_or_imported() {
+		let store = InMemoryStateStore::default();
+		store.write("A-to-B/race", b"not a json").unwrap();
+		assert!(export(&store).is_err());
+		assert!(import(&store, serde_json::json!([1, 2])).is_err());
+	}
+
+	#[test]
+	fn state_key_is_prefixed_with_loop_name() {
+		assert_eq!(state_key("race"), "race");
+		async_std::task::block_on(async {
+			crate::initialize::initialize_loop("A-to-B".into());
+			assert_eq!(state_key("race"), "A-to-B/race");
+		});
+	}
+}
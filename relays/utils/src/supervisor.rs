@@ -0,0 +1,240 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Supervisor of relay loops.
+//!
+//! Supervised loop is restarted every time it fails (returns an error or panics). Delay
+//! between restarts grows exponentially with the number of restarts during the recent period.
+//! When the loop is restarted too often, it is considered crash-looping: the alert is fired
+//! and, if configured, the supervisor stops restarting the loop and returns an error.
+
+use crate::{
+	alerts::{fire_alert, resolve_alert, AlertKind},
+	clock::{Clock, SystemClock},
+};
+
+use futures::{Future, FutureExt};
+use std::{
+	any::Any,
+	collections::VecDeque,
+	panic::AssertUnwindSafe,
+	time::{Duration, Instant},
+};
+
+/// Delay before the first restart of the failed loop.
+const INITIAL_RESTART_DELAY: Duration = Duration::from_secs(5);
+/// Maximal delay between restarts of the failed loop.
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Supervisor parameters.
+#[derive(Debug, Clone)]
+pub struct SupervisorParams {
+	/// Loop that is restarted more than this number of times during `crash_loop_window` is
+	/// considered crash-looping.
+	pub max_restarts: u32,
+	/// Period during which loop restarts are counted.
+	pub crash_loop_window: Duration,
+	/// If true, supervisor returns error when the loop is crash-looping. Otherwise, it keeps
+	/// restarting the loop with the maximal delay.
+	pub exit_on_crash_loop: bool,
+}
+
+impl Default for SupervisorParams {
+	fn default() -> Self {
+		SupervisorParams {
+			max_restarts: 5,
+			crash_loop_window: Duration::from_secs(10 * 60),
+			exit_on_crash_loop: false,
+		}
+	}
+}
+
+/// Run the loop, restarting it every time it fails. Returns `Ok(())` when the loop exits
+/// normally or error if the loop is crash-looping and `exit_on_crash_loop` is set.
+pub async fn supervise<F, Fut>(
+	loop_name: String,
+	params: SupervisorParams,
+	start_loop: F,
+) -> anyhow::Result<()>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = anyhow::Result<()>>,
+{
+	supervise_with_clock(&SystemClock, loop_name, params, start_loop).await
+}
+
+/// Run the loop, restarting it every time it fails, using given clock to measure delays
+/// between restarts and the crash loop window.
+pub async fn supervise_with_clock<F, Fut>(
+	clock: &dyn Clock,
+	loop_name: String,
+	params: SupervisorParams,
+	mut start_loop: F,
+) -> anyhow::Result<()>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = anyhow::Result<()>>,
+{
+	let mut restarts = VecDeque::new();
+	loop {
+		let error = match AssertUnwindSafe(start_loop()).catch_unwind().await {
+			Ok(Ok(())) => return Ok(()),
+			Ok(Err(e)) => e.to_string(),
+			Err(panic) => format!("panicked: {}", panic_message(&*panic)),
+		};
+
+		let restarts_in_window = note_restart(&mut restarts, clock.now(), params.crash_loop_window);
+		let restart_delay = restart_delay(restarts_in_window);
+		if restarts_in_window > params.max_restarts as usize {
+			log::error!(
+				target: "bridge",
+				"Relay loop {} is crash-looping: it has been restarted {} times during last {}s. \
+				Last error: {}",
+				loop_name,
+				restarts_in_window,
+				params.crash_loop_window.as_secs(),
+				error,
+			);
+			fire_alert(
+				AlertKind::CrashLoop,
+				loop_name.clone(),
+				format!("restarted {restarts_in_window} times. Last error: {error}"),
+			);
+			if params.exit_on_crash_loop {
+				return Err(anyhow::format_err!(
+					"Relay loop {} is crash-looping: {}",
+					loop_name,
+					error
+				))
+			}
+		} else {
+			log::warn!(
+				target: "bridge",
+				"Relay loop {} has failed: {}. Restarting in {}s",
+				loop_name,
+				error,
+				restart_delay.as_secs(),
+			);
+			resolve_alert(AlertKind::CrashLoop, loop_name.clone());
+		}
+
+		clock.sleep(restart_delay).await;
+	}
+}
+
+/// Note loop restart. Returns number of restarts during the `window`.
+fn note_restart(restarts: &mut VecDeque<Instant>, now: Instant, window: Duration) -> usize {
+	while restarts
+		.front()
+		.map(|restarted_at| now.saturating_duration_since(*restarted_at) > window)
+		.unwrap_or(false)
+	{
+		restarts.pop_front();
+	}
+	restarts.push_back(now);
+	restarts.len()
+}
+
+/// Returns delay before restart, given number of recent restarts.
+fn restart_delay(restarts_in_window: usize) -> Duration {
+	let exponent = restarts_in_window.saturating_sub(1).min(16) as u32;
+	INITIAL_RESTART_DELAY.saturating_mul(2u32.pow(exponent)).min(MAX_RESTART_DELAY)
+}
+
+/// Returns panic message.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+	panic
+		.downcast_ref::<&str>()
+		.copied()
+		.or_else(|| panic.downcast_ref::<String>().map(|s| s.as_str()))
+		.unwrap_or("unknown panic")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::clock::TestClock;
+
+	#[test]
+	fn restarts_are_counted_within_window() {
+		let now = Instant::now();
+		let window = Duration::from_secs(60);
+		let mut restarts = VecDeque::new();
+
+		assert_eq!(note_restart(&mut restarts, now, window), 1);
+		assert_eq!(note_restart(&mut restarts, now + Duration::from_secs(30), window), 2);
+		assert_eq!(note_restart(&mut restarts, now + Duration::from_secs(61), window), 2);
+		assert_eq!(note_restart(&mut restarts, now + Duration::from_secs(200), window), 1);
+	}
+
+	#[test]
+	fn restart_delay_grows_exponentially() {
+		assert_eq!(restart_delay(1), INITIAL_RESTART_DELAY);
+		assert_eq!(restart_delay(2), INITIAL_RESTART_DELAY * 2);
+		assert_eq!(restart_delay(3), INITIAL_RESTART_DELAY * 4);
+		assert_eq!(restart_delay(100), MAX_RESTART_DELAY);
+	}
+
+	async fn failing_loop() -> anyhow::Result<()> {
+		panic!("failed")
+	}
+
+	#[test]
+	fn crash_looping_loop_is_stopped() {
+		let params = SupervisorParams {
+			max_restarts: 0,
+			crash_loop_window: Duration::from_secs(60),
+			exit_on_crash_loop: true,
+		};
+		let mut starts = 0;
+		let result = async_std::task::block_on(supervise("A-to-B".into(), params, || {
+			starts += 1;
+			failing_loop()
+		}));
+
+		assert_eq!(starts, 1);
+		assert_eq!(
+			result.unwrap_err().to_string(),
+			"Relay loop A-to-B is crash-looping: panicked: failed"
+		);
+	}
+
+	#[test]
+	fn failed_loop_is_restarted_after_delay() {
+		let clock = TestClock::new();
+		let started_at = clock.now();
+		let mut starts = 0;
+		let result = async_std::task::block_on(clock.drive(
+			supervise_with_clock(&clock, "A-to-B".into(), SupervisorParams::default(), || {
+				starts += 1;
+				let is_failing = starts < 3;
+				async move {
+					if is_failing {
+						Err(anyhow::format_err!("failed"))
+					} else {
+						Ok(())
+					}
+				}
+			}),
+			1,
+			Duration::from_secs(1),
+		));
+
+		assert!(result.is_ok());
+		assert_eq!(starts, 3);
+		assert_eq!(clock.now(), started_at + INITIAL_RESTART_DELAY * 3);
+	}
+}
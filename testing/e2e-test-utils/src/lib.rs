@@ -0,0 +1,113 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Framework for end-to-end bridge tests, that are not using real nodes.
+//!
+//! The [`TestNode`] is the in-process node of the test chain, running the mock [`runtime`]
+//! with GRANDPA and messages pallets. Two nodes, bridged with each other, are created by
+//! the [`bridged_nodes`] call. The [`relay`] module has in-memory clients of the messages
+//! relay, so the real messages relay loop may be used to deliver messages between nodes,
+//! dispatch them and confirm their delivery - all within `cargo test`:
+//!
+//! ```ignore
+//! let (chain_a, chain_b) = bridged_nodes();
+//! chain_a.send_message(vec![42]);
+//! relay_messages(&chain_a, &chain_b, Duration::from_secs(60)).await?;
+//! ```
+
+#![warn(missing_docs)]
+
+pub use node::{Error, HeaderIdOf, TestNode};
+pub use relay::relay_messages;
+
+pub mod node;
+pub mod relay;
+pub mod runtime;
+
+/// Start two test nodes and bridge them with each other.
+pub fn bridged_nodes() -> (TestNode, TestNode) {
+	let chain_a = TestNode::new("ChainA");
+	let chain_b = TestNode::new("ChainB");
+	chain_a
+		.initialize_bridge(&chain_b)
+		.expect("GRANDPA pallet is not initialized yet; qed");
+	chain_b
+		.initialize_bridge(&chain_a)
+		.expect("GRANDPA pallet is not initialized yet; qed");
+	(chain_a, chain_b)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::runtime::{test_lane_id, RecordingMessageDispatch, TestRuntime};
+
+	use bp_messages::MessageNonce;
+	use std::time::Duration;
+
+	const TIMEOUT: Duration = Duration::from_secs(60);
+
+	fn latest_received_nonce(node: &TestNode) -> MessageNonce {
+		node.execute_with(|| {
+			pallet_bridge_messages::OutboundLanes::<TestRuntime>::get(test_lane_id())
+				.map(|data| data.latest_received_nonce)
+				.unwrap_or(0)
+		})
+	}
+
+	#[test]
+	fn messages_are_delivered_dispatched_and_confirmed() {
+		let (chain_a, chain_b) = bridged_nodes();
+		assert_eq!(chain_a.send_message(vec![1]), 1);
+		assert_eq!(chain_a.send_message(vec![2, 2]), 2);
+
+		async_std::task::block_on(relay_messages(&chain_a, &chain_b, TIMEOUT)).unwrap();
+
+		assert_eq!(
+			chain_b.execute_with(RecordingMessageDispatch::dispatched_messages),
+			vec![(test_lane_id(), 1, vec![1]), (test_lane_id(), 2, vec![2, 2])],
+		);
+		assert_eq!(latest_received_nonce(&chain_a), 2);
+		assert!(chain_a.execute_with(RecordingMessageDispatch::dispatched_messages).is_empty());
+	}
+
+	#[test]
+	fn messages_are_relayed_in_both_directions() {
+		let (chain_a, chain_b) = bridged_nodes();
+		chain_a.send_message(vec![1]);
+		chain_b.send_message(vec![2]);
+		chain_b.send_message(vec![3]);
+
+		async_std::task::block_on(async {
+			futures::try_join!(
+				relay_messages(&chain_a, &chain_b, TIMEOUT),
+				relay_messages(&chain_b, &chain_a, TIMEOUT),
+			)
+		})
+		.unwrap();
+
+		assert_eq!(
+			chain_a.execute_with(RecordingMessageDispatch::dispatched_messages),
+			vec![(test_lane_id(), 1, vec![2]), (test_lane_id(), 2, vec![3])],
+		);
+		assert_eq!(
+			chain_b.execute_with(RecordingMessageDispatch::dispatched_messages),
+			vec![(test_lane_id(), 1, vec![1])],
+		);
+		assert_eq!(latest_received_nonce(&chain_a), 1);
+		assert_eq!(latest_received_nonce(&chain_b), 2);
+	}
+}
@@ -0,0 +1,237 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! In-process node of the test chain.
+
+use crate::runtime::{
+	genesis_storage, test_lane_id, AccountId, BlockNumber, Hash, Header, RuntimeCall,
+	RuntimeOrigin, TestRuntime,
+};
+
+use bp_header_chain::InitializationData;
+use bp_messages::{source_chain::MessagesBridge, MessageNonce, MessagePayload};
+use bp_runtime::{BasicOperatingMode, HeaderId, HeaderIdProvider, UnverifiedStorageProof};
+use codec::Decode;
+use parking_lot::Mutex;
+use sp_core::storage::StorageKey;
+use sp_io::TestExternalities;
+use sp_runtime::traits::{BlakeTwo256, Dispatchable, Header as HeaderT};
+use sp_state_machine::{Backend, InMemoryBackend};
+use std::sync::Arc;
+
+/// Header id of the test chain.
+pub type HeaderIdOf = HeaderId<Hash, BlockNumber>;
+
+/// Test node error.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct Error(pub String);
+
+impl relay_utils::MaybeConnectionError for Error {
+	fn is_connection_error(&self) -> bool {
+		false
+	}
+}
+
+/// In-process node of the test chain.
+///
+/// The node has no transaction pool and no block authoring. Every transaction is executed
+/// immediately, in its own block, and this block is immediately finalized. State of all
+/// blocks is kept in memory, so it may be read and proved at any block.
+#[derive(Clone)]
+pub struct TestNode {
+	name: &'static str,
+	state: Arc<Mutex<NodeState>>,
+}
+
+struct NodeState {
+	/// State of the next block.
+	ext: TestExternalities,
+	/// All produced blocks, starting from genesis.
+	blocks: Vec<(Header, InMemoryBackend<BlakeTwo256>)>,
+}
+
+impl TestNode {
+	/// Start new node with the genesis block.
+	pub fn new(name: &'static str) -> Self {
+		let mut state =
+			NodeState { ext: TestExternalities::new(genesis_storage()), blocks: Vec::new() };
+		state.seal_block();
+		TestNode { name, state: Arc::new(Mutex::new(state)) }
+	}
+
+	/// Returns name of the node.
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Execute closure against the state of the next block. All changes are included into
+	/// the next block.
+	pub fn execute_with<R>(&self, f: impl FnOnce() -> R) -> R {
+		self.state.lock().ext.execute_with(f)
+	}
+
+	/// Seal the next block and return its id.
+	pub fn seal_block(&self) -> HeaderIdOf {
+		self.state.lock().seal_block()
+	}
+
+	/// Returns best (and best finalized) header of the chain.
+	pub fn best_header(&self) -> Header {
+		self.state
+			.lock()
+			.blocks
+			.last()
+			.expect("there's always genesis block; qed")
+			.0
+			.clone()
+	}
+
+	/// Returns header with given number.
+	pub fn header_by_number(&self, number: BlockNumber) -> Result<Header, Error> {
+		self.state
+			.lock()
+			.blocks
+			.get(number as usize)
+			.map(|(header, _)| header.clone())
+			.ok_or_else(|| Error(format!("{}: unknown header #{}", self.name, number)))
+	}
+
+	/// Returns header with given id.
+	pub fn header(&self, id: HeaderIdOf) -> Result<Header, Error> {
+		self.header_by_number(id.0).and_then(|header| {
+			if header.hash() == id.1 {
+				Ok(header)
+			} else {
+				Err(Error(format!("{}: unknown header {:?}", self.name, id)))
+			}
+		})
+	}
+
+	/// Read and decode value from the storage of given block.
+	pub fn storage_value<T: Decode>(
+		&self,
+		at: HeaderIdOf,
+		key: StorageKey,
+	) -> Result<Option<T>, Error> {
+		self.with_block_state(at, |state| {
+			state
+				.storage(&key.0)
+				.map_err(|e| Error(format!("failed to read {key:?}: {e}")))?
+				.map(|value| {
+					T::decode(&mut &value[..])
+						.map_err(|e| Error(format!("failed to decode {key:?}: {e}")))
+				})
+				.transpose()
+		})
+	}
+
+	/// Returns storage proof of given keys at given block.
+	pub fn prove_storage(
+		&self,
+		at: HeaderIdOf,
+		keys: Vec<StorageKey>,
+	) -> Result<UnverifiedStorageProof, Error> {
+		self.with_block_state(at, |state| {
+			UnverifiedStorageProof::try_from_db::<BlakeTwo256, _>(
+				state.backend_storage(),
+				*state.root(),
+				keys,
+			)
+			.map_err(|e| Error(format!("failed to prove storage: {e:?}")))
+		})
+	}
+
+	/// Execute signed transaction in the new block and return id of this block.
+	pub fn submit(&self, signer: AccountId, call: RuntimeCall) -> Result<HeaderIdOf, Error> {
+		self.dispatch(RuntimeOrigin::signed(signer), call)
+	}
+
+	/// Execute root call in the new block and return id of this block.
+	pub fn submit_root(&self, call: RuntimeCall) -> Result<HeaderIdOf, Error> {
+		self.dispatch(RuntimeOrigin::root(), call)
+	}
+
+	/// Send message over the test lane and return its nonce.
+	pub fn send_message(&self, payload: MessagePayload) -> MessageNonce {
+		let nonce = self.execute_with(|| {
+			pallet_bridge_messages::Pallet::<TestRuntime>::send_message(test_lane_id(), payload)
+				.expect("test lane is opened at genesis; qed")
+				.nonce
+		});
+		self.seal_block();
+		nonce
+	}
+
+	/// Initialize GRANDPA pallet of this chain with the best header of the bridged chain.
+	pub fn initialize_bridge(&self, bridged: &TestNode) -> Result<HeaderIdOf, Error> {
+		self.submit_root(RuntimeCall::BridgeGrandpa(pallet_bridge_grandpa::Call::initialize {
+			init_data: InitializationData {
+				header: Box::new(bridged.best_header()),
+				authority_list: bp_test_utils::authority_list(),
+				set_id: bp_test_utils::TEST_GRANDPA_SET_ID,
+				operating_mode: BasicOperatingMode::Normal,
+			},
+		}))
+	}
+
+	fn dispatch(&self, origin: RuntimeOrigin, call: RuntimeCall) -> Result<HeaderIdOf, Error> {
+		let mut state = self.state.lock();
+		state
+			.ext
+			.execute_with(|| call.dispatch(origin))
+			.map_err(|e| Error(format!("{}: transaction has failed: {:?}", self.name, e.error)))?;
+		Ok(state.seal_block())
+	}
+
+	fn with_block_state<R>(
+		&self,
+		at: HeaderIdOf,
+		f: impl FnOnce(&InMemoryBackend<BlakeTwo256>) -> Result<R, Error>,
+	) -> Result<R, Error> {
+		let state = self.state.lock();
+		match state.blocks.get(at.0 as usize) {
+			Some((header, block_state)) if header.hash() == at.1 => f(block_state),
+			_ => Err(Error(format!("{}: unknown header {:?}", self.name, at))),
+		}
+	}
+}
+
+impl NodeState {
+	fn seal_block(&mut self) -> HeaderIdOf {
+		self.ext.commit_all().expect("in-memory backend never fails; qed");
+		let block_state = self.ext.as_backend();
+		let number = self.blocks.len() as BlockNumber;
+		let parent_hash = self.blocks.last().map(|(header, _)| header.hash()).unwrap_or_default();
+		let header = Header::new(
+			number,
+			Default::default(),
+			*block_state.root(),
+			parent_hash,
+			Default::default(),
+		);
+		let id = header.id();
+		self.blocks.push((header, block_state));
+
+		// events are only deposited if block number is non-zero
+		self.ext.execute_with(|| {
+			frame_system::Pallet::<TestRuntime>::reset_events();
+			frame_system::Pallet::<TestRuntime>::set_block_number(number + 1);
+		});
+
+		id
+	}
+}
@@ -0,0 +1,542 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! In-memory clients of the messages relay, connected to the test nodes.
+//!
+//! Clients are reading the state of test nodes directly and submitting transactions to them.
+//! When the messages relay needs some header of the bridged chain, clients are immediately
+//! importing it into the GRANDPA pallet, so there's no need to run the finality relay.
+
+use crate::{
+	node::{Error, HeaderIdOf, TestNode},
+	runtime::{
+		test_lane_id, AccountId, Balance, BlockNumber, Hash, RuntimeCall, TestChain, TestRuntime,
+		RELAYER, WITH_TEST_CHAIN_GRANDPA_PALLET_NAME, WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+	},
+};
+
+use async_trait::async_trait;
+use bp_messages::{
+	source_chain::FromBridgedChainMessagesDeliveryProof,
+	target_chain::FromBridgedChainMessagesProof, ChainWithMessages, InboundLaneData, LaneId,
+	MessageNonce, MessagePayload, OutboundLaneData, UnrewardedRelayersState,
+};
+use bp_runtime::{HeaderIdProvider, RangeInclusiveExt};
+use frame_support::weights::Weight;
+use messages_relay::{
+	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
+	message_lane_loop::{
+		BatchTransaction, ClientState, MessageDeliveryParams, MessageDetails, MessageDetailsMap,
+		MessageProofParameters, NoncesSubmitArtifacts, Params, SourceClient, SourceClientState,
+		TargetClient, TargetClientState,
+	},
+};
+use relay_utils::{
+	metrics::MetricsParams, relay_loop::Client as RelayClient, TrackedTransactionStatus,
+	TransactionTracker,
+};
+use sp_runtime::Perbill;
+use std::{ops::RangeInclusive, time::Duration};
+
+/// Interval at which the relay is polling test nodes.
+const TICK: Duration = Duration::from_millis(10);
+
+/// Message lane between two test chains.
+#[derive(Clone, Debug)]
+pub struct TestMessageLane;
+
+impl MessageLane for TestMessageLane {
+	const SOURCE_NAME: &'static str = "TestSource";
+	const TARGET_NAME: &'static str = "TestTarget";
+
+	type MessagesProof = FromBridgedChainMessagesProof<Hash>;
+	type MessagesReceivingProof =
+		(UnrewardedRelayersState, FromBridgedChainMessagesDeliveryProof<Hash>);
+
+	type SourceChainBalance = Balance;
+	type SourceHeaderNumber = BlockNumber;
+	type SourceHeaderHash = Hash;
+
+	type TargetHeaderNumber = BlockNumber;
+	type TargetHeaderHash = Hash;
+}
+
+/// Batch transaction that is never created - headers are imported immediately.
+#[derive(Clone, Debug)]
+pub enum NoBatchTransaction {}
+
+impl BatchTransaction<HeaderIdOf> for NoBatchTransaction {
+	fn required_header_id(&self) -> HeaderIdOf {
+		match *self {}
+	}
+}
+
+/// Tracker of the transaction, submitted to the test node.
+///
+/// Every transaction is finalized in the same block, so the tracker only holds its id.
+pub struct InMemoryTransactionTracker(HeaderIdOf);
+
+#[async_trait]
+impl TransactionTracker for InMemoryTransactionTracker {
+	type HeaderId = HeaderIdOf;
+
+	async fn wait(self) -> TrackedTransactionStatus<HeaderIdOf> {
+		TrackedTransactionStatus::Finalized(self.0)
+	}
+}
+
+/// Source client of the messages relay, connected to the test nodes.
+#[derive(Clone)]
+pub struct InMemorySourceClient {
+	source: TestNode,
+	target: TestNode,
+	lane: LaneId,
+}
+
+impl InMemorySourceClient {
+	/// Create new source client.
+	pub fn new(source: TestNode, target: TestNode, lane: LaneId) -> Self {
+		InMemorySourceClient { source, target, lane }
+	}
+}
+
+#[async_trait]
+impl RelayClient for InMemorySourceClient {
+	type Error = Error;
+
+	async fn reconnect(&mut self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl SourceClient<TestMessageLane> for InMemorySourceClient {
+	type BatchTransaction = NoBatchTransaction;
+	type TransactionTracker = InMemoryTransactionTracker;
+
+	async fn state(&self) -> Result<SourceClientState<TestMessageLane>, Error> {
+		client_state(&self.source, &self.target)
+	}
+
+	async fn latest_generated_nonce(
+		&self,
+		id: SourceHeaderIdOf<TestMessageLane>,
+	) -> Result<(SourceHeaderIdOf<TestMessageLane>, MessageNonce), Error> {
+		let latest_generated_nonce = outbound_lane_data(&self.source, id, self.lane)?
+			.map(|data| data.latest_generated_nonce)
+			.unwrap_or(0);
+		Ok((id, latest_generated_nonce))
+	}
+
+	async fn latest_confirmed_received_nonce(
+		&self,
+		id: SourceHeaderIdOf<TestMessageLane>,
+	) -> Result<(SourceHeaderIdOf<TestMessageLane>, MessageNonce), Error> {
+		let latest_received_nonce = outbound_lane_data(&self.source, id, self.lane)?
+			.map(|data| data.latest_received_nonce)
+			.unwrap_or(0);
+		Ok((id, latest_received_nonce))
+	}
+
+	async fn generated_message_details(
+		&self,
+		id: SourceHeaderIdOf<TestMessageLane>,
+		nonces: RangeInclusive<MessageNonce>,
+	) -> Result<MessageDetailsMap<Balance>, Error> {
+		let mut messages = MessageDetailsMap::new();
+		for nonce in nonces {
+			let payload: Option<MessagePayload> = self.source.storage_value(
+				id,
+				bp_messages::storage_keys::message_key(
+					WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+					&self.lane,
+					nonce,
+				),
+			)?;
+			// pruned messages are missing from the map
+			if let Some(payload) = payload {
+				messages.insert(
+					nonce,
+					MessageDetails {
+						dispatch_weight: Weight::zero(),
+						size: payload.len() as u32,
+						reward: 0,
+					},
+				);
+			}
+		}
+		Ok(messages)
+	}
+
+	async fn prove_messages(
+		&self,
+		id: SourceHeaderIdOf<TestMessageLane>,
+		nonces: RangeInclusive<MessageNonce>,
+		proof_parameters: MessageProofParameters,
+	) -> Result<
+		(
+			SourceHeaderIdOf<TestMessageLane>,
+			RangeInclusive<MessageNonce>,
+			FromBridgedChainMessagesProof<Hash>,
+		),
+		Error,
+	> {
+		let mut storage_keys = nonces
+			.clone()
+			.map(|nonce| {
+				bp_messages::storage_keys::message_key(
+					WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+					&self.lane,
+					nonce,
+				)
+			})
+			.collect::<Vec<_>>();
+		if proof_parameters.outbound_state_proof_required {
+			storage_keys.push(bp_messages::storage_keys::outbound_lane_data_key(
+				WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+				&self.lane,
+			));
+		}
+
+		let proof = FromBridgedChainMessagesProof {
+			bridged_header_hash: id.1,
+			storage: self.source.prove_storage(id, storage_keys)?,
+			lane: self.lane,
+			nonces_start: *nonces.start(),
+			nonces_end: *nonces.end(),
+		};
+		Ok((id, nonces, proof))
+	}
+
+	async fn submit_messages_receiving_proof(
+		&self,
+		_maybe_batch_tx: Option<NoBatchTransaction>,
+		_generated_at_block: TargetHeaderIdOf<TestMessageLane>,
+		proof: (UnrewardedRelayersState, FromBridgedChainMessagesDeliveryProof<Hash>),
+	) -> Result<InMemoryTransactionTracker, Error> {
+		let (relayers_state, proof) = proof;
+		self.source
+			.submit(
+				RELAYER,
+				RuntimeCall::BridgeMessages(
+					pallet_bridge_messages::Call::receive_messages_delivery_proof {
+						proof,
+						relayers_state,
+					},
+				),
+			)
+			.map(InMemoryTransactionTracker)
+	}
+
+	async fn require_target_header_on_source(
+		&self,
+		id: TargetHeaderIdOf<TestMessageLane>,
+	) -> Result<Option<NoBatchTransaction>, Error> {
+		import_finalized_header(&self.target, &self.source, id).map(|_| None)
+	}
+}
+
+/// Target client of the messages relay, connected to the test nodes.
+#[derive(Clone)]
+pub struct InMemoryTargetClient {
+	source: TestNode,
+	target: TestNode,
+	lane: LaneId,
+}
+
+impl InMemoryTargetClient {
+	/// Create new target client.
+	pub fn new(source: TestNode, target: TestNode, lane: LaneId) -> Self {
+		InMemoryTargetClient { source, target, lane }
+	}
+
+	fn inbound_lane_data(
+		&self,
+		id: HeaderIdOf,
+	) -> Result<Option<InboundLaneData<AccountId>>, Error> {
+		self.target.storage_value(
+			id,
+			bp_messages::storage_keys::inbound_lane_data_key(
+				WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+				&self.lane,
+			),
+		)
+	}
+}
+
+#[async_trait]
+impl RelayClient for InMemoryTargetClient {
+	type Error = Error;
+
+	async fn reconnect(&mut self) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl TargetClient<TestMessageLane> for InMemoryTargetClient {
+	type BatchTransaction = NoBatchTransaction;
+	type TransactionTracker = InMemoryTransactionTracker;
+
+	async fn state(&self) -> Result<TargetClientState<TestMessageLane>, Error> {
+		client_state(&self.target, &self.source)
+	}
+
+	async fn latest_received_nonce(
+		&self,
+		id: TargetHeaderIdOf<TestMessageLane>,
+	) -> Result<(TargetHeaderIdOf<TestMessageLane>, MessageNonce), Error> {
+		let latest_received_nonce =
+			self.inbound_lane_data(id)?.map(|data| data.last_delivered_nonce()).unwrap_or(0);
+		Ok((id, latest_received_nonce))
+	}
+
+	async fn latest_confirmed_received_nonce(
+		&self,
+		id: TargetHeaderIdOf<TestMessageLane>,
+	) -> Result<(TargetHeaderIdOf<TestMessageLane>, MessageNonce), Error> {
+		let last_confirmed_nonce =
+			self.inbound_lane_data(id)?.map(|data| data.last_confirmed_nonce).unwrap_or(0);
+		Ok((id, last_confirmed_nonce))
+	}
+
+	async fn unrewarded_relayers_state(
+		&self,
+		id: TargetHeaderIdOf<TestMessageLane>,
+	) -> Result<(TargetHeaderIdOf<TestMessageLane>, UnrewardedRelayersState), Error> {
+		let relayers_state =
+			self.inbound_lane_data(id)?.map(|data| (&data).into()).unwrap_or_default();
+		Ok((id, relayers_state))
+	}
+
+	async fn block_fullness(
+		&self,
+		id: TargetHeaderIdOf<TestMessageLane>,
+	) -> Result<(TargetHeaderIdOf<TestMessageLane>, Perbill), Error> {
+		// every block of the test chain has exactly one transaction
+		Ok((id, Perbill::zero()))
+	}
+
+	async fn prove_messages_receiving(
+		&self,
+		id: TargetHeaderIdOf<TestMessageLane>,
+	) -> Result<
+		(
+			TargetHeaderIdOf<TestMessageLane>,
+			(UnrewardedRelayersState, FromBridgedChainMessagesDeliveryProof<Hash>),
+		),
+		Error,
+	> {
+		let (_, relayers_state) = self.unrewarded_relayers_state(id).await?;
+		let storage_proof = self.target.prove_storage(
+			id,
+			vec![bp_messages::storage_keys::inbound_lane_data_key(
+				WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+				&self.lane,
+			)],
+		)?;
+		let proof = FromBridgedChainMessagesDeliveryProof {
+			bridged_header_hash: id.1,
+			storage_proof,
+			lane: self.lane,
+		};
+		Ok((id, (relayers_state, proof)))
+	}
+
+	async fn submit_messages_proof(
+		&self,
+		_maybe_batch_tx: Option<NoBatchTransaction>,
+		_generated_at_header: SourceHeaderIdOf<TestMessageLane>,
+		nonces: RangeInclusive<MessageNonce>,
+		proof: FromBridgedChainMessagesProof<Hash>,
+	) -> Result<NoncesSubmitArtifacts<InMemoryTransactionTracker>, Error> {
+		let tx_tracker = self
+			.target
+			.submit(
+				RELAYER,
+				RuntimeCall::BridgeMessages(pallet_bridge_messages::Call::receive_messages_proof {
+					relayer_id_at_bridged_chain: RELAYER,
+					proof: Box::new(proof),
+					messages_count: nonces.saturating_len() as u32,
+					dispatch_weight: Weight::zero(),
+				}),
+			)
+			.map(InMemoryTransactionTracker)?;
+		Ok(NoncesSubmitArtifacts { nonces, tx_tracker })
+	}
+
+	async fn require_source_header_on_target(
+		&self,
+		id: SourceHeaderIdOf<TestMessageLane>,
+	) -> Result<Option<NoBatchTransaction>, Error> {
+		import_finalized_header(&self.source, &self.target, id).map(|_| None)
+	}
+}
+
+/// Run messages relay loop between given nodes until all messages, sent over the test lane
+/// before the call, are delivered to the `target` and their delivery is confirmed at the
+/// `source`.
+///
+/// Returns error if the relay hasn't finished its work within given timeout.
+pub async fn relay_messages(
+	source: &TestNode,
+	target: &TestNode,
+	timeout: Duration,
+) -> Result<(), Error> {
+	let lane = test_lane_id();
+	let latest_generated_nonce = source.execute_with(|| {
+		pallet_bridge_messages::OutboundLanes::<TestRuntime>::get(lane)
+			.map(|data| data.latest_generated_nonce)
+			.unwrap_or(0)
+	});
+
+	let exit_source = source.clone();
+	let exit_signal = async move {
+		loop {
+			let latest_received_nonce = exit_source.execute_with(|| {
+				pallet_bridge_messages::OutboundLanes::<TestRuntime>::get(lane)
+					.map(|data| data.latest_received_nonce)
+					.unwrap_or(0)
+			});
+			if latest_received_nonce >= latest_generated_nonce {
+				break
+			}
+			async_std::task::sleep(TICK).await;
+		}
+	};
+
+	let relay = messages_relay::message_lane_loop::run(
+		Params {
+			lane,
+			source_tick: TICK,
+			target_tick: TICK,
+			reconnect_delay: TICK,
+			stall_timeout: timeout,
+			delivery_params: MessageDeliveryParams {
+				max_unrewarded_relayer_entries_at_target:
+					TestChain::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+				max_unconfirmed_nonces_at_target:
+					TestChain::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+				max_messages_in_single_batch:
+					TestChain::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+				max_messages_weight_in_single_batch:
+					TestChain::maximal_incoming_message_dispatch_weight(),
+				max_messages_size_in_single_batch: TestChain::maximal_incoming_message_size(),
+				congested_target_block_fullness: None,
+			},
+		},
+		None,
+		InMemorySourceClient::new(source.clone(), target.clone(), lane),
+		InMemoryTargetClient::new(source.clone(), target.clone(), lane),
+		MetricsParams::disabled(),
+		exit_signal,
+	);
+
+	async_std::future::timeout(timeout, relay)
+		.await
+		.map_err(|_| {
+			Error(format!(
+				"{} -> {}: messages are not delivered and confirmed within {:?}",
+				source.name(),
+				target.name(),
+				timeout,
+			))
+		})?
+		.map_err(|e| {
+			Error(format!("{} -> {}: relay has failed: {e:?}", source.name(), target.name()))
+		})
+}
+
+/// Read state of the node, connected to the `self_node`.
+fn client_state(
+	self_node: &TestNode,
+	peer_node: &TestNode,
+) -> Result<ClientState<HeaderIdOf, HeaderIdOf>, Error> {
+	// every block of the test chain is finalized immediately
+	let best_self = self_node.best_header().id();
+	let best_finalized_peer_at_best_self = best_finalized_peer_header(self_node, best_self)?;
+	let actual_best_finalized_peer_at_best_self = best_finalized_peer_at_best_self
+		.map(|id| peer_node.header_by_number(id.0).map(|header| header.id()))
+		.transpose()?;
+	Ok(ClientState {
+		best_self,
+		best_finalized_self: best_self,
+		best_finalized_peer_at_best_self,
+		actual_best_finalized_peer_at_best_self,
+	})
+}
+
+/// Read id of the best finalized header of the peer chain, known to the node.
+fn best_finalized_peer_header(
+	node: &TestNode,
+	at: HeaderIdOf,
+) -> Result<Option<HeaderIdOf>, Error> {
+	node.storage_value(
+		at,
+		bp_header_chain::storage_keys::best_finalized_key(WITH_TEST_CHAIN_GRANDPA_PALLET_NAME),
+	)
+}
+
+/// Read outbound lane data at given block.
+fn outbound_lane_data(
+	node: &TestNode,
+	at: HeaderIdOf,
+	lane: LaneId,
+) -> Result<Option<OutboundLaneData>, Error> {
+	node.storage_value(
+		at,
+		bp_messages::storage_keys::outbound_lane_data_key(
+			WITH_TEST_CHAIN_MESSAGES_PALLET_NAME,
+			&lane,
+		),
+	)
+}
+
+/// Import finalized header of the `source` chain into GRANDPA pallet at the `target` chain,
+/// unless it already knows this (or better) header.
+fn import_finalized_header(
+	source: &TestNode,
+	target: &TestNode,
+	id: HeaderIdOf,
+) -> Result<(), Error> {
+	if is_header_known(target, id)? {
+		return Ok(())
+	}
+
+	let header = source.header(id)?;
+	let justification = bp_test_utils::make_default_justification(&header);
+	let result = target.submit(
+		RELAYER,
+		RuntimeCall::BridgeGrandpa(pallet_bridge_grandpa::Call::submit_finality_proof {
+			finality_target: Box::new(header),
+			justification,
+		}),
+	);
+	match result {
+		Ok(_) => Ok(()),
+		// clients of both messages relays (in both directions) may be importing headers
+		// concurrently, so the header may have been imported since we've checked it
+		Err(_) if is_header_known(target, id)? => Ok(()),
+		Err(e) => Err(e),
+	}
+}
+
+/// Returns true if the `target` node knows given (or better) finalized header of the peer chain.
+fn is_header_known(target: &TestNode, id: HeaderIdOf) -> Result<bool, Error> {
+	Ok(best_finalized_peer_header(target, target.best_header().id())?
+		.map(|best_finalized| best_finalized.0 >= id.0)
+		.unwrap_or(false))
+}
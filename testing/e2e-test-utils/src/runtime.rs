@@ -0,0 +1,235 @@
+// Copyright 2019-2023 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mock runtime of the bridged chains.
+//!
+//! Both bridged chains are running the same runtime, so every chain is bridged with the chain
+//! of the same type. The runtime has the GRANDPA pallet to track finalized headers of the
+//! bridged chain and the messages pallet to exchange messages with it.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use bp_header_chain::ChainWithGrandpa;
+use bp_messages::{
+	target_chain::{DispatchMessage, MessageDispatch},
+	ChainWithMessages, LaneId, MessageNonce, MessagePayload,
+};
+use bp_runtime::{messages::MessageDispatchResult, Chain, ChainId};
+use frame_support::{
+	traits::{ConstU32, ConstU64},
+	weights::{constants::RocksDbWeight, Weight},
+	StateVersion,
+};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+/// Account identifier at the test chain.
+pub type AccountId = u64;
+/// Balance at the test chain.
+pub type Balance = u64;
+/// Block number at the test chain.
+pub type BlockNumber = u64;
+/// Hash at the test chain.
+pub type Hash = H256;
+/// Header of the test chain.
+pub type Header = SubstrateHeader;
+
+/// Name of the GRANDPA pallet at the test chain.
+pub const WITH_TEST_CHAIN_GRANDPA_PALLET_NAME: &str = "BridgeGrandpa";
+/// Name of the messages pallet at the test chain.
+pub const WITH_TEST_CHAIN_MESSAGES_PALLET_NAME: &str = "BridgeMessages";
+
+/// Account of the relayer, that is endowed at genesis of every test chain.
+pub const RELAYER: AccountId = 100;
+
+/// Lane that is opened at genesis of every test chain.
+pub fn test_lane_id() -> LaneId {
+	LaneId::new(1, 2)
+}
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime
+	{
+		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
+		BridgeGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
+		BridgeMessages: pallet_bridge_messages::{Pallet, Call, Config<T>, Storage, Event<T>},
+	}
+}
+
+impl frame_system::Config for TestRuntime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Nonce = u64;
+	type RuntimeCall = RuntimeCall;
+	type Hash = Hash;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = RocksDbWeight;
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type DustRemoval = ();
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type FreezeIdentifier = ();
+	type MaxHolds = ConstU32<0>;
+	type MaxFreezes = ConstU32<0>;
+}
+
+impl pallet_bridge_grandpa::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type BridgedChain = TestChain;
+	type MaxFreeMandatoryHeadersPerBlock = ConstU32<4>;
+	type MaxFreeHeadersPerEpoch = ConstU32<32>;
+	type FreeHeadersEpochLength = ConstU32<8>;
+	type FreeHeadersInterval = ();
+	type HeadersToKeep = ConstU32<1024>;
+	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<TestRuntime>;
+}
+
+impl pallet_bridge_messages::Config for TestRuntime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_bridge_messages::weights::BridgeWeight<TestRuntime>;
+
+	type ThisChain = TestChain;
+	type BridgedChain = TestChain;
+	type BridgedHeaderChain = BridgeGrandpa;
+
+	type OutboundPayload = MessagePayload;
+
+	type InboundPayload = MessagePayload;
+	type DeliveryPayments = ();
+
+	type DeliveryConfirmationPayments = ();
+
+	type MessageDispatch = RecordingMessageDispatch;
+	type OnMessagesExpired = ();
+	type OnLaneCongestion = ();
+	type MaxAllowedRelayersPerLane = ConstU32<16>;
+}
+
+/// The test chain.
+pub struct TestChain;
+
+impl Chain for TestChain {
+	const ID: ChainId = *b"test";
+
+	type BlockNumber = BlockNumber;
+	type Hash = Hash;
+	type Hasher = BlakeTwo256;
+	type Header = Header;
+	type AccountId = AccountId;
+	type Balance = Balance;
+	type Nonce = u64;
+	type Signature = sp_runtime::MultiSignature;
+	const STATE_VERSION: StateVersion = StateVersion::V1;
+
+	fn max_extrinsic_size() -> u32 {
+		1024 * 1024
+	}
+
+	fn max_extrinsic_weight() -> Weight {
+		Weight::MAX
+	}
+}
+
+impl ChainWithGrandpa for TestChain {
+	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = WITH_TEST_CHAIN_GRANDPA_PALLET_NAME;
+	const MAX_AUTHORITIES_COUNT: u32 = 16;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+	const MAX_HEADER_SIZE: u32 = 4096;
+	const AVERAGE_HEADER_SIZE_IN_JUSTIFICATION: u32 = 256;
+}
+
+impl ChainWithMessages for TestChain {
+	const WITH_CHAIN_MESSAGES_PALLET_NAME: &'static str = WITH_TEST_CHAIN_MESSAGES_PALLET_NAME;
+	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce = 16;
+	const MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX: MessageNonce = 128;
+}
+
+/// Message dispatcher that remembers all dispatched messages in the runtime storage.
+pub struct RecordingMessageDispatch;
+
+impl RecordingMessageDispatch {
+	/// Returns all messages, dispatched by this chain.
+	pub fn dispatched_messages() -> Vec<(LaneId, MessageNonce, MessagePayload)> {
+		frame_support::storage::unhashed::get_or_default(b":dispatched-messages:")
+	}
+}
+
+impl MessageDispatch for RecordingMessageDispatch {
+	type DispatchPayload = MessagePayload;
+	type DispatchLevelResult = ();
+
+	fn dispatch_weight(_message: &mut DispatchMessage<MessagePayload>) -> Weight {
+		Weight::zero()
+	}
+
+	fn dispatch(message: DispatchMessage<MessagePayload>) -> MessageDispatchResult<()> {
+		let mut dispatched_messages = Self::dispatched_messages();
+		dispatched_messages.push((
+			message.key.lane_id,
+			message.key.nonce,
+			message.data.payload.unwrap_or_default(),
+		));
+		frame_support::storage::unhashed::put(b":dispatched-messages:", &dispatched_messages);
+		MessageDispatchResult { unspent_weight: Weight::zero(), dispatch_level_result: () }
+	}
+}
+
+/// Returns genesis storage of the test chain.
+pub fn genesis_storage() -> sp_core::storage::Storage {
+	RuntimeGenesisConfig {
+		balances: pallet_balances::GenesisConfig { balances: vec![(RELAYER, 1_000_000)] },
+		bridge_messages: pallet_bridge_messages::GenesisConfig {
+			opened_lanes: vec![test_lane_id()],
+			..Default::default()
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.expect("genesis config is valid; qed")
+}